@@ -0,0 +1,319 @@
+//! Procedural macros for the SurrealDB client library
+//!
+//! These macros are re-exported by the `surrealdb` crate, and should be used from there.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::parse_macro_input;
+use syn::token;
+use syn::Attribute;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Error;
+use syn::Fields;
+use syn::LitStr;
+use syn::Token;
+
+/// Derives `surrealdb::model::SurrealModel` for a struct
+///
+/// The table of the struct defaults to its name in snake case, and can be set with the
+/// `#[surreal(table = "...")]` attribute. The record ID is read from the `id` field of the
+/// struct, which must be a `Thing` or an `Option<Thing>`, if there is one.
+///
+/// Fields which hold other models can be marked with the `#[surreal(link)]` attribute, so that
+/// only the record IDs of the models are stored, and the models are created along with the
+/// struct if they don't have a record ID yet. Links are stored under the name which serde
+/// serializes the field as, so the `rename` and `rename_all` serde attributes are honoured.
+#[proc_macro_derive(SurrealModel, attributes(surreal))]
+pub fn surreal_model(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	expand(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+	let name = &input.ident;
+	let fields = match &input.data {
+		Data::Struct(data) => &data.fields,
+		_ => {
+			return Err(Error::new(
+				Span::call_site(),
+				"SurrealModel can only be derived for structs",
+			))
+		}
+	};
+	let mut table = None;
+	for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("surreal")) {
+		attr.parse_nested_meta(|meta| {
+			if meta.path.is_ident("table") {
+				let value: LitStr = meta.value()?.parse()?;
+				if value.value().is_empty() {
+					return Err(meta.error("the table name can not be empty"));
+				}
+				table = Some(value.value());
+				Ok(())
+			} else {
+				Err(meta.error("unsupported surreal attribute, expected `table`"))
+			}
+		})?;
+	}
+	let table = table.unwrap_or_else(|| snake_case(&name.to_string()));
+	let rename_all = match serde_rename(&input.attrs, "rename_all")? {
+		Some(rule) => Some(RenameRule::parse(&rule).ok_or_else(|| {
+			Error::new(Span::call_site(), format!("unsupported serde rename_all rule `{rule}`"))
+		})?),
+		None => None,
+	};
+	let named = match fields {
+		Fields::Named(fields) => fields.named.iter().collect(),
+		_ => Vec::new(),
+	};
+	let has_id = named.iter().any(|field| match &field.ident {
+		Some(ident) => ident == "id",
+		None => false,
+	});
+	let record_id = match has_id {
+		true => quote! {
+			::surrealdb::model::__private::RecordId::record_id(&self.id)
+		},
+		false => quote! {
+			::core::option::Option::None
+		},
+	};
+	let mut links = Vec::new();
+	for field in named.iter() {
+		let mut link = false;
+		for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("surreal")) {
+			attr.parse_nested_meta(|meta| {
+				if meta.path.is_ident("link") {
+					link = true;
+					Ok(())
+				} else {
+					Err(meta.error("unsupported surreal field attribute, expected `link`"))
+				}
+			})?;
+		}
+		if let (true, Some(ident)) = (link, &field.ident) {
+			let name = match serde_rename(&field.attrs, "rename")? {
+				Some(name) => name,
+				None => match rename_all {
+					Some(rule) => rule.apply(&ident.unraw().to_string()),
+					None => ident.unraw().to_string(),
+				},
+			};
+			links.push((ident, name));
+		}
+	}
+	let links = match links.is_empty() {
+		true => quote! {},
+		false => {
+			let names = links.iter().map(|(_, name)| name);
+			let stages = links.iter().map(|(ident, name)| {
+				quote! {
+					(#name, ::surrealdb::model::__private::Link::stage(&self.#ident, work)?)
+				}
+			});
+			quote! {
+				fn links() -> &'static [&'static str] {
+					&[#(#names),*]
+				}
+
+				fn stage_links(
+					&self,
+					work: &mut ::surrealdb::unit_of_work::UnitOfWork,
+				) -> ::surrealdb::Result<::std::vec::Vec<(&'static str, ::surrealdb::sql::Value)>> {
+					::core::result::Result::Ok(::std::vec![#(#stages),*])
+				}
+			}
+		}
+	};
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	let mut generics = input.generics.clone();
+	generics.params.push(syn::parse_quote!(__R));
+	let (resource_generics, _, _) = generics.split_for_impl();
+	Ok(quote! {
+		impl #impl_generics ::surrealdb::model::SurrealModel for #name #ty_generics #where_clause {
+			fn table_name() -> &'static str {
+				#table
+			}
+
+			fn record_id(&self) -> ::core::option::Option<::surrealdb::sql::Thing> {
+				#record_id
+			}
+
+			#links
+		}
+
+		impl #resource_generics ::surrealdb::opt::IntoResource<::core::option::Option<__R>> for &#name #ty_generics #where_clause {
+			fn into_resource(self) -> ::surrealdb::Result<::surrealdb::opt::Resource> {
+				::surrealdb::model::SurrealModel::resource(self)
+			}
+		}
+	})
+}
+
+/// Reads the name which serde serializes a container or a field as, from its `rename` or
+/// `rename_all` attribute, skipping over the other serde attributes
+fn serde_rename(attrs: &[Attribute], key: &str) -> syn::Result<Option<String>> {
+	let mut name = None;
+	for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+		attr.parse_nested_meta(|meta| {
+			if !meta.path.is_ident(key) {
+				return skip(&meta);
+			}
+			if meta.input.peek(Token![=]) {
+				let value: LitStr = meta.value()?.parse()?;
+				name = Some(value.value());
+				return Ok(());
+			}
+			// Only the name which the field is serialized as is stored
+			meta.parse_nested_meta(|meta| {
+				if meta.path.is_ident("serialize") {
+					let value: LitStr = meta.value()?.parse()?;
+					name = Some(value.value());
+					Ok(())
+				} else {
+					skip(&meta)
+				}
+			})
+		})?;
+	}
+	Ok(name)
+}
+
+/// Skips over the value of a serde attribute which doesn't affect the stored names
+fn skip(meta: &ParseNestedMeta) -> syn::Result<()> {
+	if meta.input.peek(Token![=]) {
+		meta.value()?.parse::<syn::Lit>()?;
+	} else if meta.input.peek(token::Paren) {
+		meta.parse_nested_meta(|meta| skip(&meta))?;
+	}
+	Ok(())
+}
+
+/// The case conversions of the serde `rename_all` attribute
+#[derive(Clone, Copy)]
+enum RenameRule {
+	Lower,
+	Upper,
+	Pascal,
+	Camel,
+	Snake,
+	ScreamingSnake,
+	Kebab,
+	ScreamingKebab,
+}
+
+impl RenameRule {
+	fn parse(rule: &str) -> Option<Self> {
+		Some(match rule {
+			"lowercase" => Self::Lower,
+			"UPPERCASE" => Self::Upper,
+			"PascalCase" => Self::Pascal,
+			"camelCase" => Self::Camel,
+			"snake_case" => Self::Snake,
+			"SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+			"kebab-case" => Self::Kebab,
+			"SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+			_ => return None,
+		})
+	}
+
+	/// Renames a field, which is named in snake case, in the same way as serde
+	fn apply(self, field: &str) -> String {
+		match self {
+			Self::Lower | Self::Snake => field.to_owned(),
+			Self::Upper | Self::ScreamingSnake => field.to_ascii_uppercase(),
+			Self::Pascal => {
+				let mut out = String::with_capacity(field.len());
+				let mut upper = true;
+				for c in field.chars() {
+					if c == '_' {
+						upper = true;
+					} else if upper {
+						out.push(c.to_ascii_uppercase());
+						upper = false;
+					} else {
+						out.push(c);
+					}
+				}
+				out
+			}
+			Self::Camel => {
+				let pascal = Self::Pascal.apply(field);
+				let mut chars = pascal.chars();
+				match chars.next() {
+					Some(first) => first.to_lowercase().chain(chars).collect(),
+					None => pascal,
+				}
+			}
+			Self::Kebab => field.replace('_', "-"),
+			Self::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+		}
+	}
+}
+
+/// Converts a type name to snake case, keeping acronyms together
+fn snake_case(name: &str) -> String {
+	let chars: Vec<char> = name.chars().collect();
+	let mut out = String::with_capacity(name.len() + 4);
+	for (i, c) in chars.iter().enumerate() {
+		if c.is_uppercase() && i > 0 {
+			let prev = chars[i - 1];
+			let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+			if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+				out.push('_');
+			}
+		}
+		out.extend(c.to_lowercase());
+	}
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn snake_case_names() {
+		assert_eq!(snake_case("Person"), "person");
+		assert_eq!(snake_case("BlogPost"), "blog_post");
+		assert_eq!(snake_case("HTTPRequest"), "http_request");
+		assert_eq!(snake_case("Version2Log"), "version2_log");
+		assert_eq!(snake_case("user_account"), "user_account");
+	}
+
+	#[test]
+	fn serde_renamed_fields() {
+		let rule = |rule| RenameRule::parse(rule).unwrap();
+		assert_eq!(rule("camelCase").apply("main_author"), "mainAuthor");
+		assert_eq!(rule("PascalCase").apply("main_author"), "MainAuthor");
+		assert_eq!(rule("SCREAMING_SNAKE_CASE").apply("main_author"), "MAIN_AUTHOR");
+		assert_eq!(rule("kebab-case").apply("main_author"), "main-author");
+		assert_eq!(rule("SCREAMING-KEBAB-CASE").apply("main_author"), "MAIN-AUTHOR");
+		assert_eq!(rule("lowercase").apply("main_author"), "main_author");
+		assert!(RenameRule::parse("Title Case").is_none());
+	}
+
+	#[test]
+	fn serde_rename_attributes() {
+		let input: DeriveInput = syn::parse_quote! {
+			#[serde(rename_all = "camelCase", deny_unknown_fields)]
+			struct Book {
+				#[serde(default, rename(serialize = "writer", deserialize = "author"))]
+				author: Author,
+				#[serde(skip_serializing_if = "Vec::is_empty", with = "links")]
+				reviewers: Vec<Author>,
+			}
+		};
+		let field = |i: usize| match &input.data {
+			Data::Struct(data) => data.fields.iter().nth(i).unwrap().attrs.clone(),
+			_ => unreachable!(),
+		};
+		assert_eq!(serde_rename(&input.attrs, "rename_all").unwrap(), Some("camelCase".into()));
+		assert_eq!(serde_rename(&field(0), "rename").unwrap(), Some("writer".into()));
+		assert_eq!(serde_rename(&field(1), "rename").unwrap(), None);
+	}
+}
@@ -0,0 +1,171 @@
+//! A typed builder for SurrealQL queries
+//!
+//! The builder produces the statements directly, so queries can be composed programmatically,
+//! without concatenating strings and without running them through the parser. Conditions are
+//! built with the [`expr`](crate::expr) module.
+//!
+//! ```no_run
+//! use surrealdb::builder::select_from;
+//! use surrealdb::expr::field;
+//!
+//! # #[derive(serde::Deserialize)]
+//! # struct Person;
+//! #
+//! # #[tokio::main]
+//! # async fn main() -> surrealdb::Result<()> {
+//! # let db = surrealdb::engine::any::connect("mem://").await?;
+//! #
+//! let query = select_from("person")
+//!     .fields(["name", "age"])
+//!     .filter(field("age").gt(18))
+//!     .order_by("name")
+//!     .limit(10);
+//!
+//! let people: Vec<Person> = db.query(query).await?.take(0)?;
+//! #
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::expr::idiom;
+use crate::api::opt::IntoQuery;
+use crate::api::Result;
+use crate::sql::statements::SelectStatement;
+use crate::sql::Cond;
+use crate::sql::Field;
+use crate::sql::Fields;
+use crate::sql::Limit;
+use crate::sql::Order;
+use crate::sql::Orders;
+use crate::sql::Start;
+use crate::sql::Statement;
+use crate::sql::Table;
+use crate::sql::Value;
+use crate::sql::Values;
+
+#[doc(no_inline)]
+pub use crate::api::expr::field;
+#[doc(no_inline)]
+pub use crate::api::expr::Expr;
+
+/// A `SELECT` query, which is built up from [`select_from`]
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub struct SelectQuery(SelectStatement);
+
+/// Starts a query which selects the records of a table
+pub fn select_from(table: impl Into<String>) -> SelectQuery {
+	SelectQuery(SelectStatement {
+		expr: Fields(vec![Field::All], false),
+		what: Values(vec![Value::Table(Table(table.into()))]),
+		..Default::default()
+	})
+}
+
+impl SelectQuery {
+	/// Selects only the specified fields, with nested fields separated by dots
+	pub fn fields(mut self, fields: impl IntoIterator<Item = impl Into<String>>) -> Self {
+		let fields = fields.into_iter().map(|path| Field::Single {
+			expr: Value::Idiom(idiom(&path.into())),
+			alias: None,
+		});
+		self.0.expr = Fields(fields.collect(), false);
+		self
+	}
+
+	/// Selects only the records which match the condition
+	///
+	/// Calling this more than once selects the records which match all the conditions.
+	pub fn filter(mut self, condition: Expr) -> Self {
+		let condition = match self.0.cond.take() {
+			Some(cond) => Expr(cond.0).and(condition),
+			None => condition,
+		};
+		self.0.cond = Some(Cond(condition.into()));
+		self
+	}
+
+	/// Orders the records by a field, in ascending order
+	pub fn order_by(self, path: &str) -> Self {
+		self.order(path, true)
+	}
+
+	/// Orders the records by a field, in descending order
+	pub fn order_by_desc(self, path: &str) -> Self {
+		self.order(path, false)
+	}
+
+	fn order(mut self, path: &str, direction: bool) -> Self {
+		let order = Order {
+			order: idiom(path),
+			random: false,
+			collate: false,
+			numeric: false,
+			direction,
+		};
+		self.0.order.get_or_insert_with(|| Orders(Vec::new())).0.push(order);
+		self
+	}
+
+	/// Returns at most the specified number of records
+	pub fn limit(mut self, limit: u64) -> Self {
+		self.0.limit = Some(Limit(limit.into()));
+		self
+	}
+
+	/// Skips the specified number of records
+	pub fn start(mut self, start: u64) -> Self {
+		self.0.start = Some(Start(start.into()));
+		self
+	}
+}
+
+impl From<SelectQuery> for SelectStatement {
+	fn from(query: SelectQuery) -> Self {
+		query.0
+	}
+}
+
+impl IntoQuery for SelectQuery {
+	fn into_query(self) -> Result<Vec<Statement>> {
+		Ok(vec![Statement::Select(self.0)])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql;
+
+	fn parse(query: &str) -> Statement {
+		sql::parse(query).unwrap().0 .0.remove(0)
+	}
+
+	#[test]
+	fn matches_the_parser() {
+		let query = select_from("person")
+			.fields(["name", "age"])
+			.filter(field("age").gt(18))
+			.order_by("name")
+			.limit(10);
+		assert_eq!(
+			query.into_query().unwrap(),
+			vec![parse("SELECT name, age FROM person WHERE age > 18 ORDER BY name LIMIT 10")]
+		);
+	}
+
+	#[test]
+	fn combined_conditions() {
+		let query = select_from("person")
+			.filter(field("age").gte(18))
+			.filter(field("tags").contains("admin").or(field("name.first").eq("Tobie")))
+			.order_by_desc("age")
+			.start(20);
+		let statement = SelectStatement::from(query);
+		assert_eq!(Statement::Select(statement.clone()), parse(&statement.to_string()));
+		assert_eq!(
+			statement.to_string(),
+			"SELECT * FROM person WHERE (age >= 18) AND ((tags CONTAINS 'admin') OR (name.first = 'Tobie')) ORDER BY age DESC START 20"
+		);
+	}
+}
@@ -1,7 +1,16 @@
 use crate::api;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::api::engine::runtime::sleep;
 use crate::api::err::Error;
 use crate::api::method::query::Response;
+use crate::api::method::Cache;
+use crate::api::method::Emitter;
+use crate::api::method::InFlight;
+use crate::api::method::Schemas;
+use crate::api::method::Subscriptions;
+use crate::api::opt::Breaker;
 use crate::api::opt::Endpoint;
+use crate::api::opt::RetryPolicy;
 use crate::api::ExtraFeatures;
 use crate::api::Result;
 use crate::api::Surreal;
@@ -20,6 +29,8 @@ use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::atomic::AtomicI64;
 use std::sync::atomic::Ordering;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio::sleep;
 
 #[derive(Debug)]
 #[allow(dead_code)] // used by the embedded and remote connections
@@ -35,6 +46,13 @@ pub struct Router<C: api::Connection> {
 	pub(crate) sender: Sender<Option<Route>>,
 	pub(crate) last_id: AtomicI64,
 	pub(crate) features: HashSet<ExtraFeatures>,
+	pub(crate) retry: RetryPolicy,
+	pub(crate) circuit_breaker: Breaker,
+	pub(crate) events: Emitter,
+	pub(crate) schemas: Schemas,
+	pub(crate) cache: Cache,
+	pub(crate) subscriptions: Subscriptions,
+	pub(crate) in_flight: InFlight,
 }
 
 impl<C> Router<C>
@@ -69,6 +87,8 @@ pub enum Method {
 	Delete,
 	/// Exports a database
 	Export,
+	/// Reads the binary blob of a record
+	GetBlob,
 	/// Checks the health of the server
 	Health,
 	/// Imports a database
@@ -83,6 +103,8 @@ pub enum Method {
 	Live,
 	/// Perfoms a patch update operation
 	Patch,
+	/// Stores a binary blob for a record
+	PutBlob,
 	/// Sends a raw query to the database
 	Query,
 	/// Selects a record or records from a table
@@ -113,13 +135,17 @@ pub enum DbResponse {
 }
 
 /// Holds the parameters given to the caller
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[allow(dead_code)] // used by the embedded and remote connections
 pub struct Param {
 	pub(crate) query: Option<(Query, BTreeMap<String, Value>)>,
 	pub(crate) other: Vec<Value>,
 	pub(crate) file: Option<PathBuf>,
 	pub(crate) sender: Option<channel::Sender<Result<Vec<u8>>>>,
+	pub(crate) receiver: Option<channel::Receiver<Result<Vec<u8>>>>,
+	// Only requests which read data and queries marked with `Query::idempotent` are retried,
+	// as the other methods can't tell whether a write is safe to send again
+	pub(crate) idempotent: bool,
 }
 
 impl Param {
@@ -129,6 +155,8 @@ impl Param {
 			other,
 			file: None,
 			sender: None,
+			receiver: None,
+			idempotent: false,
 		}
 	}
 
@@ -138,6 +166,8 @@ impl Param {
 			other: Vec::new(),
 			file: None,
 			sender: None,
+			receiver: None,
+			idempotent: false,
 		}
 	}
 
@@ -147,6 +177,8 @@ impl Param {
 			other: Vec::new(),
 			file: Some(file),
 			sender: None,
+			receiver: None,
+			idempotent: false,
 		}
 	}
 
@@ -156,8 +188,28 @@ impl Param {
 			other: Vec::new(),
 			file: None,
 			sender: Some(send),
+			receiver: None,
+			idempotent: false,
+		}
+	}
+
+	#[cfg(not(target_arch = "wasm32"))]
+	pub(crate) fn receiver(recv: channel::Receiver<Result<Vec<u8>>>) -> Self {
+		Self {
+			query: None,
+			other: Vec::new(),
+			file: None,
+			sender: None,
+			receiver: Some(recv),
+			idempotent: false,
 		}
 	}
+
+	/// Marks the request as safe to send more than once
+	pub(crate) fn idempotent(mut self) -> Self {
+		self.idempotent = true;
+		self
+	}
 }
 
 /// Connection trait implemented by supported protocols
@@ -211,6 +263,74 @@ pub trait Connection: Sized + Send + Sync + 'static {
 		})
 	}
 
+	/// Send a single request through the circuit breaker and wait for its response
+	fn attempt<'r>(
+		&'r mut self,
+		router: &'r Router<Self>,
+		param: Param,
+	) -> Pin<Box<dyn Future<Output = Result<DbResponse>> + Send + Sync + 'r>>
+	where
+		Self: api::Connection,
+	{
+		Box::pin(async move {
+			router.circuit_breaker.acquire()?;
+			let result = match self.send(router, param).await {
+				Ok(receiver) => receiver.into_recv_async().await?,
+				Err(error) => Err(error),
+			};
+			router.circuit_breaker.record(&result);
+			result
+		})
+	}
+
+	/// Send a request and wait for its response, retrying idempotent requests
+	/// according to the retry policy of the router
+	fn request<'r>(
+		&'r mut self,
+		router: &'r Router<Self>,
+		param: Param,
+	) -> Pin<Box<dyn Future<Output = Result<DbResponse>> + Send + Sync + 'r>>
+	where
+		Self: api::Connection,
+	{
+		Box::pin(async move {
+			if !param.idempotent || router.retry.max_attempts <= 1 {
+				return self.attempt(router, param).await;
+			}
+			let mut attempt = 1;
+			loop {
+				match self.attempt(router, param.clone()).await {
+					Err(error) if router.retry.should_retry(attempt, &error) => {
+						let delay = router.retry.delay(attempt);
+						trace!(
+							"Retrying request in {delay:?} after attempt {attempt} failed; {error}"
+						);
+						sleep(delay).await;
+						attempt += 1;
+					}
+					result => return result,
+				}
+			}
+		})
+	}
+
+	/// Send a request and wait for a response for all methods except `query`
+	fn recv_value<'r>(
+		&'r mut self,
+		router: &'r Router<Self>,
+		param: Param,
+	) -> Pin<Box<dyn Future<Output = Result<Value>> + Send + Sync + 'r>>
+	where
+		Self: api::Connection,
+	{
+		Box::pin(async move {
+			match self.request(router, param).await? {
+				DbResponse::Other(value) => Ok(value),
+				DbResponse::Query(..) => unreachable!(),
+			}
+		})
+	}
+
 	/// Execute all methods except `query`
 	#[tracing::instrument(skip(self, router), ret, err)]
 	fn execute<'r, R>(
@@ -223,8 +343,7 @@ pub trait Connection: Sized + Send + Sync + 'static {
 		Self: api::Connection,
 	{
 		Box::pin(async move {
-			let rx = self.send(router, param).await?;
-			let value = self.recv(rx).await?;
+			let value = self.recv_value(router, param).await?;
 			from_value(value).map_err(Into::into)
 		})
 	}
@@ -241,8 +360,7 @@ pub trait Connection: Sized + Send + Sync + 'static {
 		Self: api::Connection,
 	{
 		Box::pin(async move {
-			let rx = self.send(router, param).await?;
-			match self.recv(rx).await? {
+			match self.recv_value(router, param).await? {
 				Value::None | Value::Null => Ok(None),
 				value => from_value(value).map_err(Into::into),
 			}
@@ -261,8 +379,7 @@ pub trait Connection: Sized + Send + Sync + 'static {
 		Self: api::Connection,
 	{
 		Box::pin(async move {
-			let rx = self.send(router, param).await?;
-			let value = match self.recv(rx).await? {
+			let value = match self.recv_value(router, param).await? {
 				Value::None | Value::Null => Value::Array(Default::default()),
 				Value::Array(array) => Value::Array(array),
 				value => vec![value].into(),
@@ -282,8 +399,7 @@ pub trait Connection: Sized + Send + Sync + 'static {
 		Self: api::Connection,
 	{
 		Box::pin(async move {
-			let rx = self.send(router, param).await?;
-			match self.recv(rx).await? {
+			match self.recv_value(router, param).await? {
 				Value::None | Value::Null => Ok(()),
 				Value::Array(array) if array.is_empty() => Ok(()),
 				value => Err(Error::FromValue {
@@ -305,10 +421,7 @@ pub trait Connection: Sized + Send + Sync + 'static {
 	where
 		Self: api::Connection,
 	{
-		Box::pin(async move {
-			let rx = self.send(router, param).await?;
-			self.recv(rx).await
-		})
+		Box::pin(async move { self.recv_value(router, param).await })
 	}
 
 	/// Execute the `query` method
@@ -322,8 +435,10 @@ pub trait Connection: Sized + Send + Sync + 'static {
 		Self: api::Connection,
 	{
 		Box::pin(async move {
-			let rx = self.send(router, param).await?;
-			self.recv_query(rx).await
+			match self.request(router, param).await? {
+				DbResponse::Query(results) => Ok(results),
+				DbResponse::Other(..) => unreachable!(),
+			}
 		})
 	}
 }
@@ -187,6 +187,8 @@ impl Surreal<Any> {
 			router: self.router.clone(),
 			address: address.into_endpoint(),
 			capacity: 0,
+			retry: Default::default(),
+			circuit_breaker: Default::default(),
 			client: PhantomData,
 			response_type: PhantomData,
 		}
@@ -223,6 +225,9 @@ impl Surreal<Any> {
 /// // Instantiate an IndxDB-backed instance
 /// let db = connect("indxdb://MyDatabase").await?;
 ///
+/// // Instantiate an OPFS-backed instance
+/// let db = connect("opfs://MyDatabase").await?;
+///
 /// // Instantiate a TiKV-backed instance
 /// let db = connect("tikv://localhost:2379").await?;
 ///
@@ -236,6 +241,8 @@ pub fn connect(address: impl IntoEndpoint) -> Connect<Any, Surreal<Any>> {
 		router: Arc::new(OnceLock::new()),
 		address: address.into_endpoint(),
 		capacity: 0,
+		retry: Default::default(),
+		circuit_breaker: Default::default(),
 		client: PhantomData,
 		response_type: PhantomData,
 	}
@@ -9,6 +9,10 @@ use crate::api::engine::any::Any;
 #[cfg(feature = "protocol-http")]
 use crate::api::engine::remote::http;
 use crate::api::err::Error;
+use crate::api::method::Cache;
+use crate::api::method::Emitter;
+use crate::api::method::InFlight;
+use crate::api::method::Subscriptions;
 use crate::api::opt::Endpoint;
 #[cfg(any(feature = "native-tls", feature = "rustls"))]
 #[cfg(feature = "protocol-http")]
@@ -32,8 +36,6 @@ use std::sync::atomic::AtomicI64;
 use std::sync::Arc;
 use std::sync::OnceLock;
 #[cfg(feature = "protocol-ws")]
-use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
-#[cfg(feature = "protocol-ws")]
 #[cfg(any(feature = "native-tls", feature = "rustls"))]
 use tokio_tungstenite::Connector;
 
@@ -58,6 +60,12 @@ impl Connection for Any {
 				capacity => flume::bounded(capacity),
 			};
 
+			let events = Emitter::default();
+			let mut cache = Cache::new(address.config.cache);
+			let subscriptions = Subscriptions::default();
+			// Only the WebSocket engine limits the requests which are in flight
+			let mut in_flight = InFlight::default();
+
 			let (conn_tx, conn_rx) = flume::bounded::<Result<()>>(1);
 			let mut features = HashSet::new();
 
@@ -66,7 +74,14 @@ impl Connection for Any {
 					#[cfg(feature = "kv-fdb")]
 					{
 						features.insert(ExtraFeatures::Backup);
-						engine::local::native::router(address, conn_tx, route_rx);
+						features.insert(ExtraFeatures::Blobs);
+						engine::local::native::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
 						conn_rx.into_recv_async().await??
 					}
 
@@ -80,7 +95,14 @@ impl Connection for Any {
 					#[cfg(feature = "kv-mem")]
 					{
 						features.insert(ExtraFeatures::Backup);
-						engine::local::native::router(address, conn_tx, route_rx);
+						features.insert(ExtraFeatures::Blobs);
+						engine::local::native::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
 						conn_rx.into_recv_async().await??
 					}
 
@@ -94,7 +116,14 @@ impl Connection for Any {
 					#[cfg(feature = "kv-rocksdb")]
 					{
 						features.insert(ExtraFeatures::Backup);
-						engine::local::native::router(address, conn_tx, route_rx);
+						features.insert(ExtraFeatures::Blobs);
+						engine::local::native::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
 						conn_rx.into_recv_async().await??
 					}
 
@@ -109,7 +138,14 @@ impl Connection for Any {
 					#[cfg(feature = "kv-speedb")]
 					{
 						features.insert(ExtraFeatures::Backup);
-						engine::local::native::router(address, conn_tx, route_rx);
+						features.insert(ExtraFeatures::Blobs);
+						engine::local::native::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
 						conn_rx.into_recv_async().await??
 					}
 
@@ -120,11 +156,62 @@ impl Connection for Any {
 					.into());
 				}
 
+				"sled" => {
+					#[cfg(feature = "kv-sled")]
+					{
+						features.insert(ExtraFeatures::Backup);
+						features.insert(ExtraFeatures::Blobs);
+						engine::local::native::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
+						conn_rx.into_recv_async().await??
+					}
+
+					#[cfg(not(feature = "kv-sled"))]
+					return Err(DbError::Ds(
+						"Cannot connect to the `sled` storage engine as it is not enabled in this build of SurrealDB".to_owned(),
+					)
+					.into());
+				}
+
+				"redb" => {
+					#[cfg(feature = "kv-redb")]
+					{
+						features.insert(ExtraFeatures::Backup);
+						features.insert(ExtraFeatures::Blobs);
+						engine::local::native::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
+						conn_rx.into_recv_async().await??
+					}
+
+					#[cfg(not(feature = "kv-redb"))]
+					return Err(DbError::Ds(
+						"Cannot connect to the `redb` storage engine as it is not enabled in this build of SurrealDB".to_owned(),
+					)
+					.into());
+				}
+
 				"tikv" => {
 					#[cfg(feature = "kv-tikv")]
 					{
 						features.insert(ExtraFeatures::Backup);
-						engine::local::native::router(address, conn_tx, route_rx);
+						features.insert(ExtraFeatures::Blobs);
+						engine::local::native::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
 						conn_rx.into_recv_async().await??
 					}
 
@@ -137,7 +224,11 @@ impl Connection for Any {
 				"http" | "https" => {
 					#[cfg(feature = "protocol-http")]
 					{
+						// Live queries are not supported over HTTP, so nothing could be cached
+						cache = Cache::default();
 						features.insert(ExtraFeatures::Backup);
+						features.insert(ExtraFeatures::Blobs);
+						features.insert(ExtraFeatures::StreamedParams);
 						let headers = http::default_headers();
 						#[allow(unused_mut)]
 						let mut builder = ClientBuilder::new().default_headers(headers);
@@ -170,33 +261,22 @@ impl Connection for Any {
 					#[cfg(feature = "protocol-ws")]
 					{
 						let url = address.url.join(engine::remote::ws::PATH)?;
+						in_flight = InFlight::new(address.config.max_concurrent_requests);
 						#[cfg(any(feature = "native-tls", feature = "rustls"))]
 						let maybe_connector = address.config.tls_config.map(Connector::from);
 						#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
 						let maybe_connector = None;
-						let config = WebSocketConfig {
-							max_send_queue: match capacity {
-								0 => None,
-								capacity => Some(capacity),
-							},
-							max_message_size: Some(engine::remote::ws::native::MAX_MESSAGE_SIZE),
-							max_frame_size: Some(engine::remote::ws::native::MAX_FRAME_SIZE),
-							accept_unmasked_frames: false,
-						};
-						let socket = engine::remote::ws::native::connect(
-							&url,
-							Some(config),
-							maybe_connector.clone(),
-						)
-						.await?;
-						engine::remote::ws::native::router(
+						engine::remote::ws::native::serve(
 							url,
 							maybe_connector,
 							capacity,
-							config,
-							socket,
 							route_rx,
-						);
+							events.clone(),
+							cache.clone(),
+							subscriptions.clone(),
+							in_flight.clone(),
+						)
+						.await?;
 					}
 
 					#[cfg(not(feature = "protocol-ws"))]
@@ -217,6 +297,13 @@ impl Connection for Any {
 					conn: PhantomData,
 					sender: route_tx,
 					last_id: AtomicI64::new(0),
+					retry: Default::default(),
+					circuit_breaker: Default::default(),
+					events,
+					schemas: Default::default(),
+					cache,
+					subscriptions,
+					in_flight,
 				})),
 			})
 		})
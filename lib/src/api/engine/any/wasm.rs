@@ -7,6 +7,10 @@ use crate::api::conn::Router;
 use crate::api::engine;
 use crate::api::engine::any::Any;
 use crate::api::err::Error;
+use crate::api::method::Cache;
+use crate::api::method::Emitter;
+use crate::api::method::InFlight;
+use crate::api::method::Subscriptions;
 use crate::api::opt::Endpoint;
 use crate::api::DbResponse;
 use crate::api::OnceLockExt;
@@ -43,6 +47,12 @@ impl Connection for Any {
 				capacity => flume::bounded(capacity),
 			};
 
+			let events = Emitter::default();
+			let mut cache = Cache::new(address.config.cache);
+			let subscriptions = Subscriptions::default();
+			// Only the WebSocket engine limits the requests which are in flight
+			let mut in_flight = InFlight::default();
+
 			let (conn_tx, conn_rx) = flume::bounded::<Result<()>>(1);
 			let mut features = HashSet::new();
 
@@ -50,7 +60,13 @@ impl Connection for Any {
 				"fdb" => {
 					#[cfg(feature = "kv-fdb")]
 					{
-						engine::local::wasm::router(address, conn_tx, route_rx);
+						engine::local::wasm::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
 						conn_rx.into_recv_async().await??;
 					}
 
@@ -63,7 +79,13 @@ impl Connection for Any {
 				"indxdb" => {
 					#[cfg(feature = "kv-indxdb")]
 					{
-						engine::local::wasm::router(address, conn_tx, route_rx);
+						engine::local::wasm::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
 						conn_rx.into_recv_async().await??;
 					}
 
@@ -76,7 +98,13 @@ impl Connection for Any {
 				"mem" => {
 					#[cfg(feature = "kv-mem")]
 					{
-						engine::local::wasm::router(address, conn_tx, route_rx);
+						engine::local::wasm::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
 						conn_rx.into_recv_async().await??;
 					}
 
@@ -86,10 +114,35 @@ impl Connection for Any {
 					);
 				}
 
+				"opfs" => {
+					#[cfg(feature = "kv-opfs")]
+					{
+						engine::local::wasm::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
+						conn_rx.into_recv_async().await??;
+					}
+
+					#[cfg(not(feature = "kv-opfs"))]
+					return Err(
+						DbError::Ds("Cannot connect to the `opfs` storage engine as it is not enabled in this build of SurrealDB".to_owned()).into()
+					);
+				}
+
 				"file" | "rocksdb" => {
 					#[cfg(feature = "kv-rocksdb")]
 					{
-						engine::local::wasm::router(address, conn_tx, route_rx);
+						engine::local::wasm::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
 						conn_rx.into_recv_async().await??;
 					}
 
@@ -103,7 +156,13 @@ impl Connection for Any {
 				"speedb" => {
 					#[cfg(feature = "kv-speedb")]
 					{
-						engine::local::wasm::router(address, conn_tx, route_rx);
+						engine::local::wasm::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
 						conn_rx.into_recv_async().await??;
 					}
 
@@ -114,10 +173,56 @@ impl Connection for Any {
 					.into());
 				}
 
+				"sled" => {
+					#[cfg(feature = "kv-sled")]
+					{
+						engine::local::wasm::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
+						conn_rx.into_recv_async().await??;
+					}
+
+					#[cfg(not(feature = "kv-sled"))]
+					return Err(DbError::Ds(
+						"Cannot connect to the `sled` storage engine as it is not enabled in this build of SurrealDB".to_owned(),
+					)
+					.into());
+				}
+
+				"redb" => {
+					#[cfg(feature = "kv-redb")]
+					{
+						engine::local::wasm::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
+						conn_rx.into_recv_async().await??;
+					}
+
+					#[cfg(not(feature = "kv-redb"))]
+					return Err(DbError::Ds(
+						"Cannot connect to the `redb` storage engine as it is not enabled in this build of SurrealDB".to_owned(),
+					)
+					.into());
+				}
+
 				"tikv" => {
 					#[cfg(feature = "kv-tikv")]
 					{
-						engine::local::wasm::router(address, conn_tx, route_rx);
+						engine::local::wasm::router(
+							address,
+							conn_tx,
+							route_rx,
+							cache.clone(),
+							subscriptions.clone(),
+						);
 						conn_rx.into_recv_async().await??;
 					}
 
@@ -130,6 +235,8 @@ impl Connection for Any {
 				"http" | "https" => {
 					#[cfg(feature = "protocol-http")]
 					{
+						// Live queries are not supported over HTTP, so nothing could be cached
+						cache = Cache::default();
 						engine::remote::http::wasm::router(address, conn_tx, route_rx);
 					}
 
@@ -144,8 +251,18 @@ impl Connection for Any {
 					#[cfg(feature = "protocol-ws")]
 					{
 						let mut address = address;
+						in_flight = InFlight::new(address.config.max_concurrent_requests);
 						address.url = address.url.join(engine::remote::ws::PATH)?;
-						engine::remote::ws::wasm::router(address, capacity, conn_tx, route_rx);
+						engine::remote::ws::wasm::router(
+							address,
+							capacity,
+							conn_tx,
+							route_rx,
+							events.clone(),
+							cache.clone(),
+							subscriptions.clone(),
+							in_flight.clone(),
+						);
 						conn_rx.into_recv_async().await??;
 					}
 
@@ -167,6 +284,13 @@ impl Connection for Any {
 					conn: PhantomData,
 					sender: route_tx,
 					last_id: AtomicI64::new(0),
+					retry: Default::default(),
+					circuit_breaker: Default::default(),
+					events,
+					schemas: Default::default(),
+					cache,
+					subscriptions,
+					in_flight,
 				})),
 			})
 		})
@@ -53,6 +53,8 @@ use crate::sql::Statement;
 use crate::sql::Statements;
 use crate::sql::Strand;
 use crate::sql::Value;
+#[cfg(not(target_arch = "wasm32"))]
+use futures::TryStreamExt;
 use indexmap::IndexMap;
 use std::collections::BTreeMap;
 use std::marker::PhantomData;
@@ -234,6 +236,78 @@ pub struct RocksDb;
 #[derive(Debug)]
 pub struct SpeeDb;
 
+/// Sled database
+///
+/// # Examples
+///
+/// Instantiating a sled-backed instance
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::Sled;
+///
+/// let db = Surreal::new::<Sled>("temp.db").await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Instantiating a sled-backed strict instance
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::opt::Config;
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::Sled;
+///
+/// let config = Config::default().strict();
+/// let db = Surreal::new::<Sled>(("temp.db", config)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "kv-sled")]
+#[cfg_attr(docsrs, doc(cfg(feature = "kv-sled")))]
+#[derive(Debug)]
+pub struct Sled;
+
+/// Redb database
+///
+/// # Examples
+///
+/// Instantiating a redb-backed instance
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::RedbFile;
+///
+/// let db = Surreal::new::<RedbFile>("temp.db").await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Instantiating a redb-backed strict instance
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::opt::Config;
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::RedbFile;
+///
+/// let config = Config::default().strict();
+/// let db = Surreal::new::<RedbFile>(("temp.db", config)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "kv-redb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "kv-redb")))]
+#[derive(Debug)]
+pub struct RedbFile;
+
 /// IndxDB database
 ///
 /// # Examples
@@ -270,6 +344,51 @@ pub struct SpeeDb;
 #[derive(Debug)]
 pub struct IndxDb;
 
+/// OPFS database
+///
+/// Stores the data in the browser's Origin Private File System, which has a much higher write
+/// throughput than IndexedDB. The data is kept in memory and every committed transaction is
+/// appended to a log file, which is replayed when the database is opened.
+///
+/// Synchronous access to OPFS files is only available inside a web worker, so the database
+/// must be opened from one. Only one worker can open a given database at a time. The bindings
+/// used by this engine are still unstable in `web-sys`, so it must be built with
+/// `RUSTFLAGS="--cfg web_sys_unstable_apis"`.
+///
+/// # Examples
+///
+/// Instantiating an OPFS-backed instance
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::Opfs;
+///
+/// let db = Surreal::new::<Opfs>("MyDatabase").await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Instantiating an OPFS-backed strict instance
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// use surrealdb::opt::Config;
+/// use surrealdb::Surreal;
+/// use surrealdb::engine::local::Opfs;
+///
+/// let config = Config::default().strict();
+/// let db = Surreal::new::<Opfs>(("MyDatabase", config)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "kv-opfs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "kv-opfs")))]
+#[derive(Debug)]
+pub struct Opfs;
+
 /// TiKV database
 ///
 /// # Examples
@@ -355,6 +474,8 @@ impl Surreal<Db> {
 			router: self.router.clone(),
 			address: address.into_endpoint(),
 			capacity: 0,
+			retry: Default::default(),
+			circuit_breaker: Default::default(),
 			client: PhantomData,
 			response_type: PhantomData,
 		}
@@ -547,7 +668,7 @@ async fn router(
 			Ok(DbResponse::Query(response))
 		}
 		#[cfg(target_arch = "wasm32")]
-		Method::Export | Method::Import => unreachable!(),
+		Method::Export | Method::Import | Method::PutBlob | Method::GetBlob => unreachable!(),
 		#[cfg(not(target_arch = "wasm32"))]
 		Method::Export => {
 			let ns = session.ns.clone().unwrap_or_default();
@@ -648,6 +769,45 @@ async fn router(
 			}
 			Ok(DbResponse::Other(Value::None))
 		}
+		#[cfg(not(target_arch = "wasm32"))]
+		Method::PutBlob => {
+			let Some(Value::Thing(rid)) = params.pop() else {
+				unreachable!();
+			};
+			let rx = param.receiver.expect("channel to receive the blob from");
+			// Read the chunks of the blob as they are received
+			let mut reader =
+				rx.map_err(|error| io::Error::new(io::ErrorKind::Other, error)).into_async_read();
+			let size = kvs.put_blob(session, &rid, &mut reader).await?;
+			Ok(DbResponse::Other(size.into()))
+		}
+		#[cfg(not(target_arch = "wasm32"))]
+		Method::GetBlob => {
+			let Some(Value::Thing(rid)) = params.pop() else {
+				unreachable!();
+			};
+			let blob = param.sender.expect("channel to send the blob to");
+			let (tx, rx) = channel::new(1);
+			let job = kvs.get_blob(session, &rid, tx).await?;
+			tokio::spawn(async move {
+				let read = async {
+					if let Err(error) = job.await {
+						let _ = blob.send(Err(error.into())).await;
+					}
+				};
+
+				let bridge = async {
+					while let Ok(bytes) = rx.recv().await {
+						if blob.send(Ok(bytes)).await.is_err() {
+							break;
+						}
+					}
+				};
+
+				tokio::join!(read, bridge);
+			});
+			Ok(DbResponse::Other(Value::None))
+		}
 		Method::Health => Ok(DbResponse::Other(Value::None)),
 		Method::Version => Ok(DbResponse::Other(crate::env::VERSION.into())),
 		Method::Set => {
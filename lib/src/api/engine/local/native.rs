@@ -6,6 +6,8 @@ use crate::api::conn::Route;
 use crate::api::conn::Router;
 use crate::api::engine::local::Db;
 use crate::api::engine::local::DEFAULT_TICK_INTERVAL;
+use crate::api::method::Cache;
+use crate::api::method::Subscriptions;
 use crate::api::opt::Endpoint;
 use crate::api::ExtraFeatures;
 use crate::api::OnceLockExt;
@@ -53,12 +55,16 @@ impl Connection for Db {
 
 			let (conn_tx, conn_rx) = flume::bounded(1);
 
-			router(address, conn_tx, route_rx);
+			let cache = Cache::new(address.config.cache);
+			let subscriptions = Subscriptions::default();
+
+			router(address, conn_tx, route_rx, cache.clone(), subscriptions.clone());
 
 			conn_rx.into_recv_async().await??;
 
 			let mut features = HashSet::new();
 			features.insert(ExtraFeatures::Backup);
+			features.insert(ExtraFeatures::Blobs);
 
 			Ok(Surreal {
 				router: Arc::new(OnceLock::with_value(Router {
@@ -66,6 +72,13 @@ impl Connection for Db {
 					conn: PhantomData,
 					sender: route_tx,
 					last_id: AtomicI64::new(0),
+					retry: Default::default(),
+					circuit_breaker: Default::default(),
+					events: Default::default(),
+					schemas: Default::default(),
+					cache,
+					subscriptions,
+					in_flight: Default::default(),
 				})),
 			})
 		})
@@ -92,6 +105,8 @@ pub(crate) fn router(
 	address: Endpoint,
 	conn_tx: Sender<Result<()>>,
 	route_rx: Receiver<Option<Route>>,
+	cache: Cache,
+	subscriptions: Subscriptions,
 ) {
 	tokio::spawn(async move {
 		let configured_root = match address.config.auth {
@@ -126,21 +141,56 @@ pub(crate) fn router(
 			.with_transaction_timeout(address.config.transaction_timeout)
 			.with_capabilities(address.config.capabilities);
 
-		let kvs = match address.config.notifications {
+		// The cache is invalidated by the notifications of its live queries
+		let kvs = match address.config.notifications || cache.is_enabled() {
 			true => kvs.with_notifications(),
 			false => kvs,
 		};
 
+		#[cfg(feature = "cron-schedules")]
+		let kvs = match address.config.backup_schedule {
+			Some(schedule) => kvs.with_backup_schedule(schedule),
+			None => kvs,
+		};
+
+		let kvs = match address.config.http_quota {
+			Some(quota) => kvs.with_http_quota(quota),
+			None => kvs,
+		};
+
+		let kvs = address
+			.config
+			.write_limits
+			.iter()
+			.fold(kvs, |kvs, (table, limit)| kvs.with_write_limit(table, *limit));
+
+		// Live queries can only be run when their notifications are delivered
+		let realtime = match kvs.notifications() {
+			Some(notifications) => {
+				let cache = cache.clone();
+				tokio::spawn(async move {
+					while let Ok(notification) = notifications.recv().await {
+						cache.notify(notification.id.0);
+						subscriptions.notify(notification);
+					}
+				});
+				true
+			}
+			None => false,
+		};
+
 		let kvs = Arc::new(kvs);
 		let mut vars = BTreeMap::new();
 		let mut stream = route_rx.into_stream();
-		let mut session = Session::default();
+		let mut session = Session::default().with_rt(realtime);
 
 		let (maintenance_tx, maintenance_rx) = flume::bounded::<()>(1);
 		let tick_interval = address.config.tick_interval.unwrap_or(DEFAULT_TICK_INTERVAL);
 		run_maintenance(kvs.clone(), tick_interval, maintenance_rx);
 
 		while let Some(Some(route)) = stream.next().await {
+			let (_, method, param) = &route.request;
+			cache.sent(*method, param);
 			match super::router(route.request, &kvs, &mut session, &mut vars).await {
 				Ok(value) => {
 					let _ = route.response.into_send_async(Ok(value)).await;
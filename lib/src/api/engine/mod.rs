@@ -6,12 +6,17 @@ pub mod any;
 	feature = "kv-tikv",
 	feature = "kv-rocksdb",
 	feature = "kv-speedb",
+	feature = "kv-sled",
+	feature = "kv-redb",
 	feature = "kv-fdb",
 	feature = "kv-indxdb",
+	feature = "kv-opfs",
 ))]
 pub mod local;
 #[cfg(any(feature = "protocol-http", feature = "protocol-ws"))]
 pub mod remote;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod runtime;
 
 use crate::sql::statements::CreateStatement;
 use crate::sql::statements::DeleteStatement;
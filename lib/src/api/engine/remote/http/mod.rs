@@ -26,6 +26,8 @@ use crate::opt::IntoEndpoint;
 use crate::sql::serde::deserialize;
 use crate::sql::Array;
 use crate::sql::Strand;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::sql::Thing;
 use crate::sql::Value;
 #[cfg(not(target_arch = "wasm32"))]
 use futures::TryStreamExt;
@@ -51,6 +53,11 @@ use tokio_util::compat::FuturesAsyncReadCompatExt;
 use url::Url;
 
 const SQL_PATH: &str = "sql";
+#[cfg(not(target_arch = "wasm32"))]
+const BLOB_PATH: &str = "blob";
+/// The header which holds the query, when the body holds the value of a streamed parameter
+#[cfg(not(target_arch = "wasm32"))]
+const QUERY_HEADER: &str = "surreal-query";
 
 /// The HTTP scheme used to connect to `http://` endpoints
 #[derive(Debug)]
@@ -93,6 +100,8 @@ impl Surreal<Client> {
 			router: self.router.clone(),
 			address: address.into_endpoint(),
 			capacity: 0,
+			retry: Default::default(),
+			circuit_breaker: Default::default(),
 			client: PhantomData,
 			response_type: PhantomData,
 		}
@@ -309,6 +318,31 @@ async fn import(request: RequestBuilder, path: PathBuf) -> Result<Value> {
 	Ok(Value::None)
 }
 
+/// The URL of the blob of a record
+#[cfg(not(target_arch = "wasm32"))]
+fn blob_url(base_url: &Url, rid: &Thing) -> Result<Url> {
+	let mut url = base_url.join(BLOB_PATH)?;
+	url.path_segments_mut()
+		.map_err(|_| Error::InvalidUrl(base_url.to_string()))?
+		.push(&rid.to_string());
+	Ok(url)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn put_blob(
+	request: RequestBuilder,
+	rx: channel::Receiver<Result<Vec<u8>>>,
+) -> Result<Value> {
+	let response = request.body(reqwest::Body::wrap_stream(rx)).send().await?;
+	let response = response.error_for_status()?;
+	let bytes = response.bytes().await?;
+	let size: u64 = deserialize(&bytes).map_err(|error| Error::ResponseFromBinary {
+		binary: bytes.to_vec(),
+		error,
+	})?;
+	Ok(size.into())
+}
+
 async fn version(request: RequestBuilder) -> Result<Value> {
 	let response = request.send().await?.error_for_status()?;
 	let version = response.text().await?;
@@ -479,13 +513,32 @@ async fn router(
 			Ok(DbResponse::Other(value))
 		}
 		Method::Query => {
-			let path = base_url.join(SQL_PATH)?;
+			#[allow(unused_mut)]
+			let mut path = base_url.join(SQL_PATH)?;
+			#[cfg(not(target_arch = "wasm32"))]
+			if let Some(Value::Strand(Strand(key))) = params.pop() {
+				// The value of the streamed parameter is sent as the body, so the query is
+				// sent in a header
+				path.path_segments_mut()
+					.map_err(|_| Error::InvalidUrl(base_url.to_string()))?
+					.push(&key);
+			}
 			let mut request = client.post(path).headers(headers.clone()).query(&vars).auth(auth);
 			match param.query {
 				Some((query, bindings)) => {
 					let bindings: Vec<_> =
 						bindings.iter().map(|(key, value)| (key, value.to_string())).collect();
-					request = request.query(&bindings).body(query.to_string());
+					request = request.query(&bindings);
+					request = match param.receiver {
+						#[cfg(not(target_arch = "wasm32"))]
+						Some(rx) => {
+							let query: String =
+								url::form_urlencoded::byte_serialize(query.to_string().as_bytes())
+									.collect();
+							request.header(QUERY_HEADER, query).body(reqwest::Body::wrap_stream(rx))
+						}
+						_ => request.body(query.to_string()),
+					};
 				}
 				None => unreachable!(),
 			}
@@ -493,7 +546,7 @@ async fn router(
 			Ok(DbResponse::Query(values))
 		}
 		#[cfg(target_arch = "wasm32")]
-		Method::Export | Method::Import => unreachable!(),
+		Method::Export | Method::Import | Method::PutBlob | Method::GetBlob => unreachable!(),
 		#[cfg(not(target_arch = "wasm32"))]
 		Method::Export => {
 			let path = base_url.join(Method::Export.as_str())?;
@@ -517,6 +570,36 @@ async fn router(
 			let value = import(request, file).await?;
 			Ok(DbResponse::Other(value))
 		}
+		#[cfg(not(target_arch = "wasm32"))]
+		Method::PutBlob => {
+			let Some(Value::Thing(rid)) = params.pop() else {
+				unreachable!();
+			};
+			let path = blob_url(base_url, &rid)?;
+			let rx = param.receiver.expect("channel to receive the blob from");
+			let request = client
+				.put(path)
+				.headers(headers.clone())
+				.auth(auth)
+				.header(CONTENT_TYPE, "application/octet-stream");
+			let value = put_blob(request, rx).await?;
+			Ok(DbResponse::Other(value))
+		}
+		#[cfg(not(target_arch = "wasm32"))]
+		Method::GetBlob => {
+			let Some(Value::Thing(rid)) = params.pop() else {
+				unreachable!();
+			};
+			let path = blob_url(base_url, &rid)?;
+			let request = client
+				.get(path)
+				.headers(headers.clone())
+				.auth(auth)
+				.header(ACCEPT, "application/octet-stream");
+			// Blobs are streamed to the caller in the same way as exports
+			let value = export(request, (None, param.sender)).await?;
+			Ok(DbResponse::Other(value))
+		}
 		Method::Health => {
 			let path = base_url.join(Method::Health.as_str())?;
 			let request = client.get(path);
@@ -70,6 +70,8 @@ impl Connection for Client {
 
 			let mut features = HashSet::new();
 			features.insert(ExtraFeatures::Backup);
+			features.insert(ExtraFeatures::Blobs);
+			features.insert(ExtraFeatures::StreamedParams);
 
 			Ok(Surreal {
 				router: Arc::new(OnceLock::with_value(Router {
@@ -77,6 +79,13 @@ impl Connection for Client {
 					conn: PhantomData,
 					sender: route_tx,
 					last_id: AtomicI64::new(0),
+					retry: Default::default(),
+					circuit_breaker: Default::default(),
+					events: Default::default(),
+					schemas: Default::default(),
+					cache: Default::default(),
+					subscriptions: Default::default(),
+					in_flight: Default::default(),
 				})),
 			})
 		})
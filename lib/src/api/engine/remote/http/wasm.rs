@@ -56,6 +56,13 @@ impl Connection for Client {
 					conn: PhantomData,
 					sender: route_tx,
 					last_id: AtomicI64::new(0),
+					retry: Default::default(),
+					circuit_breaker: Default::default(),
+					events: Default::default(),
+					schemas: Default::default(),
+					cache: Default::default(),
+					subscriptions: Default::default(),
+					in_flight: Default::default(),
 				})),
 			})
 		})
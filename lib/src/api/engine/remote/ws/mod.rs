@@ -12,10 +12,14 @@ use crate::api::err::Error;
 use crate::api::Connect;
 use crate::api::Result;
 use crate::api::Surreal;
+use crate::dbs::Action;
+use crate::dbs::Notification;
 use crate::dbs::Status;
 use crate::opt::IntoEndpoint;
 use crate::sql::Array;
+use crate::sql::Part;
 use crate::sql::Strand;
+use crate::sql::Uuid;
 use crate::sql::Value;
 use serde::Deserialize;
 use std::marker::PhantomData;
@@ -67,6 +71,8 @@ impl Surreal<Client> {
 			router: self.router.clone(),
 			address: address.into_endpoint(),
 			capacity: 0,
+			retry: Default::default(),
+			circuit_breaker: Default::default(),
 			client: PhantomData,
 			response_type: PhantomData,
 		}
@@ -137,3 +143,32 @@ pub(crate) struct Response {
 	id: Option<Value>,
 	pub(crate) result: ServerResult,
 }
+
+impl Response {
+	/// The live query notification which this response holds, if it is one
+	pub(crate) fn notification(&self) -> Option<Notification> {
+		// Notifications are the only responses which are not sent for a request
+		if self.id.is_some() {
+			return None;
+		}
+		let Ok(Data::Other(notification)) = &self.result else {
+			return None;
+		};
+		let id = match notification.pick(&[Part::from("id")]) {
+			Value::Uuid(id) => id,
+			Value::Strand(id) => Uuid::try_from(id.as_str()).ok()?,
+			_ => return None,
+		};
+		let action = match notification.pick(&[Part::from("action")]).as_raw_string().as_str() {
+			"CREATE" => Action::Create,
+			"UPDATE" => Action::Update,
+			"DELETE" => Action::Delete,
+			_ => return None,
+		};
+		Some(Notification {
+			id,
+			action,
+			result: notification.pick(&[Part::from("result")]),
+		})
+	}
+}
@@ -9,19 +9,31 @@ use crate::api::engine::remote::ws::Client;
 use crate::api::engine::remote::ws::Response;
 use crate::api::engine::remote::ws::PING_INTERVAL;
 use crate::api::engine::remote::ws::PING_METHOD;
+#[cfg(feature = "runtime-async-std")]
+use crate::api::engine::runtime;
+#[cfg(feature = "runtime-async-std")]
+use crate::api::engine::runtime::AsyncStd;
+use crate::api::engine::runtime::Runtime;
+use crate::api::engine::runtime::Tokio;
 use crate::api::err::Error;
+use crate::api::method::Cache;
+use crate::api::method::Emitter;
+use crate::api::method::Event;
+use crate::api::method::InFlight;
+use crate::api::method::Permit;
+use crate::api::method::Subscriptions;
 use crate::api::opt::Endpoint;
 #[cfg(any(feature = "native-tls", feature = "rustls"))]
 use crate::api::opt::Tls;
 use crate::api::OnceLockExt;
 use crate::api::Result;
 use crate::api::Surreal;
-use crate::engine::IntervalStream;
 use crate::sql::serde::{deserialize, serialize};
 use crate::sql::Strand;
 use crate::sql::Value;
 use flume::Receiver;
 use futures::stream::SplitSink;
+use futures::Sink;
 use futures::SinkExt;
 use futures::StreamExt;
 use futures_concurrency::stream::Merge as _;
@@ -39,25 +51,21 @@ use std::pin::Pin;
 use std::sync::atomic::AtomicI64;
 use std::sync::Arc;
 use std::sync::OnceLock;
-use tokio::net::TcpStream;
-use tokio::time;
-use tokio::time::MissedTickBehavior;
+use std::time::Duration;
 use tokio_tungstenite::tungstenite::error::Error as WsError;
 use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::Connector;
-use tokio_tungstenite::MaybeTlsStream;
-use tokio_tungstenite::WebSocketStream;
 use trice::Instant;
 use url::Url;
 
 type WsResult<T> = std::result::Result<T, WsError>;
 
-pub(crate) const MAX_MESSAGE_SIZE: usize = 64 << 20; // 64 MiB
-pub(crate) const MAX_FRAME_SIZE: usize = 16 << 20; // 16 MiB
+const MAX_MESSAGE_SIZE: usize = 64 << 20; // 64 MiB
+const MAX_FRAME_SIZE: usize = 16 << 20; // 16 MiB
 
 pub(crate) enum Either {
-	Request(Option<Route>),
+	Request(Option<Route>, Permit),
 	Response(WsResult<Message>),
 	Ping,
 }
@@ -74,19 +82,87 @@ impl From<Tls> for Connector {
 	}
 }
 
-pub(crate) async fn connect(
-	url: &Url,
-	config: Option<WebSocketConfig>,
-	#[allow(unused_variables)] maybe_connector: Option<Connector>,
-) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
-	#[cfg(any(feature = "native-tls", feature = "rustls"))]
-	let (socket, _) =
-		tokio_tungstenite::connect_async_tls_with_config(url, config, maybe_connector).await?;
+/// The configuration of a WebSocket connection
+fn config(capacity: usize) -> WebSocketConfig {
+	WebSocketConfig {
+		max_send_queue: match capacity {
+			0 => None,
+			capacity => Some(capacity),
+		},
+		max_message_size: Some(MAX_MESSAGE_SIZE),
+		max_frame_size: Some(MAX_FRAME_SIZE),
+		accept_unmasked_frames: false,
+	}
+}
 
-	#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
-	let (socket, _) = tokio_tungstenite::connect_async_with_config(url, config).await?;
+/// Connects to the server, then spawns the router on the runtime the client is used from
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn serve(
+	url: Url,
+	maybe_connector: Option<Connector>,
+	capacity: usize,
+	route_rx: Receiver<Option<Route>>,
+	emitter: Emitter,
+	cache: Cache,
+	subscriptions: Subscriptions,
+	in_flight: InFlight,
+) -> Result<()> {
+	#[cfg(feature = "runtime-async-std")]
+	if runtime::outside_tokio() {
+		return serve_on::<AsyncStd>(
+			url,
+			maybe_connector,
+			capacity,
+			route_rx,
+			emitter,
+			cache,
+			subscriptions,
+			in_flight,
+		)
+		.await;
+	}
+	serve_on::<Tokio>(
+		url,
+		maybe_connector,
+		capacity,
+		route_rx,
+		emitter,
+		cache,
+		subscriptions,
+		in_flight,
+	)
+	.await
+}
 
-	Ok(socket)
+#[allow(clippy::too_many_arguments)]
+async fn serve_on<R>(
+	url: Url,
+	maybe_connector: Option<Connector>,
+	capacity: usize,
+	route_rx: Receiver<Option<Route>>,
+	emitter: Emitter,
+	cache: Cache,
+	subscriptions: Subscriptions,
+	in_flight: InFlight,
+) -> Result<()>
+where
+	R: Runtime,
+{
+	let config = config(capacity);
+	let socket = R::connect(url.clone(), Some(config), maybe_connector.clone()).await?;
+	router::<R>(
+		url,
+		maybe_connector,
+		capacity,
+		config,
+		socket,
+		route_rx,
+		emitter,
+		cache,
+		subscriptions,
+		in_flight,
+	);
+	Ok(())
 }
 
 impl crate::api::Connection for Client {}
@@ -110,24 +186,27 @@ impl Connection for Client {
 			#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
 			let maybe_connector = None;
 
-			let config = WebSocketConfig {
-				max_send_queue: match capacity {
-					0 => None,
-					capacity => Some(capacity),
-				},
-				max_message_size: Some(MAX_MESSAGE_SIZE),
-				max_frame_size: Some(MAX_FRAME_SIZE),
-				accept_unmasked_frames: false,
-			};
-
-			let socket = connect(&url, Some(config), maybe_connector.clone()).await?;
-
 			let (route_tx, route_rx) = match capacity {
 				0 => flume::unbounded(),
 				capacity => flume::bounded(capacity),
 			};
 
-			router(url, maybe_connector, capacity, config, socket, route_rx);
+			let events = Emitter::default();
+			let cache = Cache::new(address.config.cache);
+			let subscriptions = Subscriptions::default();
+			let in_flight = InFlight::new(address.config.max_concurrent_requests);
+
+			serve(
+				url,
+				maybe_connector,
+				capacity,
+				route_rx,
+				events.clone(),
+				cache.clone(),
+				subscriptions.clone(),
+				in_flight.clone(),
+			)
+			.await?;
 
 			Ok(Surreal {
 				router: Arc::new(OnceLock::with_value(Router {
@@ -135,6 +214,13 @@ impl Connection for Client {
 					conn: PhantomData,
 					sender: route_tx,
 					last_id: AtomicI64::new(0),
+					retry: Default::default(),
+					circuit_breaker: Default::default(),
+					events,
+					schemas: Default::default(),
+					cache,
+					subscriptions,
+					in_flight,
 				})),
 			})
 		})
@@ -158,16 +244,22 @@ impl Connection for Client {
 	}
 }
 
-#[allow(clippy::too_many_lines)]
-pub(crate) fn router(
+#[allow(clippy::too_many_lines, clippy::too_many_arguments)]
+fn router<R>(
 	url: Url,
 	maybe_connector: Option<Connector>,
 	capacity: usize,
 	config: WebSocketConfig,
-	mut socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+	mut socket: R::WebSocket,
 	route_rx: Receiver<Option<Route>>,
-) {
-	tokio::spawn(async move {
+	emitter: Emitter,
+	cache: Cache,
+	subscriptions: Subscriptions,
+	in_flight: InFlight,
+) where
+	R: Runtime,
+{
+	R::spawn(async move {
 		let ping = {
 			let mut request = BTreeMap::new();
 			request.insert("method".to_owned(), PING_METHOD.into());
@@ -183,23 +275,23 @@ pub(crate) fn router(
 			let (socket_sink, socket_stream) = socket.split();
 			let mut socket_sink = Socket(Some(socket_sink));
 
+			let mut reason = String::from("the connection was closed");
+
 			if let Socket(Some(socket_sink)) = &mut socket_sink {
 				let mut routes = match capacity {
 					0 => HashMap::new(),
 					capacity => HashMap::with_capacity(capacity),
 				};
 
-				let mut interval = time::interval(PING_INTERVAL);
-				// don't bombard the server with pings if we miss some ticks
-				interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
-				// Delay sending the first ping
-				interval.tick().await;
-
-				let pinger = IntervalStream::new(interval);
+				// Delay sending the first ping, and don't bombard the server
+				// with pings if we miss some ticks
+				let pinger = R::interval(PING_INTERVAL);
 
 				let streams = (
 					socket_stream.map(Either::Response),
-					route_rx.stream().map(Either::Request),
+					in_flight
+						.requests(route_rx.clone())
+						.map(|(route, permit)| Either::Request(route, permit)),
 					pinger.map(|_| Either::Ping),
 				);
 
@@ -208,11 +300,15 @@ pub(crate) fn router(
 
 				while let Some(either) = merged.next().await {
 					match either {
-						Either::Request(Some(Route {
-							request,
-							response,
-						})) => {
+						Either::Request(
+							Some(Route {
+								request,
+								response,
+							}),
+							permit,
+						) => {
 							let (id, method, param) = request;
+							cache.sent(method, &param);
 							let params = match param.query {
 								Some((query, bindings)) => {
 									vec![query.into(), bindings.into()]
@@ -261,7 +357,7 @@ pub(crate) fn router(
 									last_activity = Instant::now();
 									match routes.entry(id) {
 										Entry::Vacant(entry) => {
-											entry.insert((method, response));
+											entry.insert((method, response, permit));
 										}
 										Entry::Occupied(..) => {
 											let error = Error::DuplicateRequestId(id);
@@ -277,6 +373,7 @@ pub(crate) fn router(
 								}
 								Err(error) => {
 									let error = Error::Ws(error.to_string());
+									reason = error.to_string();
 									if response.into_send_async(Err(error.into())).await.is_err() {
 										trace!("Receiver dropped");
 									}
@@ -291,10 +388,15 @@ pub(crate) fn router(
 									Ok(option) => {
 										if let Some(response) = option {
 											trace!("{response:?}");
+											if let Some(notification) = response.notification() {
+												cache.notify(notification.id.0);
+												subscriptions.notify(notification);
+											}
 											if let Some(Ok(id)) =
 												response.id.map(Value::coerce_to_i64)
 											{
-												if let Some((_method, sender)) = routes.remove(&id)
+												if let Some((_method, sender, _permit)) =
+													routes.remove(&id)
 												{
 													let _res = sender
 														.into_send_async(DbResponse::from(
@@ -319,7 +421,7 @@ pub(crate) fn router(
 											{
 												// Return an error if an ID was returned
 												if let Some(Ok(id)) = id.map(Value::coerce_to_i64) {
-													if let Some((_method, sender)) =
+													if let Some((_method, sender, _permit)) =
 														routes.remove(&id)
 													{
 														let _res = sender
@@ -341,6 +443,7 @@ pub(crate) fn router(
 										}
 										error => {
 											trace!("{error}");
+											reason = error.to_string();
 										}
 									}
 									break;
@@ -353,26 +456,34 @@ pub(crate) fn router(
 								trace!("Pinging the server");
 								if let Err(error) = socket_sink.send(ping.clone()).await {
 									trace!("failed to ping the server; {error:?}");
+									reason = format!("failed to ping the server; {error}");
 									break;
 								}
 							}
 						}
-						Either::Request(None) => {
+						Either::Request(None, _) => {
 							break 'router;
 						}
 					}
 				}
 			}
 
+			// Live queries don't survive the connection, so their results can't be trusted anymore
+			cache.reset();
+			subscriptions.reset();
+			emitter.emit(Event::Disconnected {
+				reason,
+			});
+
 			'reconnect: loop {
 				trace!("Reconnecting...");
-				match connect(&url, Some(config), maybe_connector.clone()).await {
+				match R::connect(url.clone(), Some(config), maybe_connector.clone()).await {
 					Ok(s) => {
 						socket = s;
 						for (_, message) in &replay {
 							if let Err(error) = socket.send(message.clone()).await {
 								trace!("{error}");
-								time::sleep(time::Duration::from_secs(1)).await;
+								R::sleep(Duration::from_secs(1)).await;
 								continue 'reconnect;
 							}
 						}
@@ -388,16 +499,22 @@ pub(crate) fn router(
 							trace!("Request {payload}");
 							if let Err(error) = socket.send(Message::Binary(payload.into())).await {
 								trace!("{error}");
-								time::sleep(time::Duration::from_secs(1)).await;
+								R::sleep(Duration::from_secs(1)).await;
 								continue 'reconnect;
 							}
 						}
 						trace!("Reconnected successfully");
+						emitter.emit(Event::Connected);
+						if replay.keys().any(|method| {
+							matches!(method, Method::Authenticate | Method::Signin | Method::Signup)
+						}) {
+							emitter.emit(Event::Reauthenticated);
+						}
 						break;
 					}
 					Err(error) => {
 						trace!("Failed to reconnect; {error}");
-						time::sleep(time::Duration::from_secs(1)).await;
+						R::sleep(Duration::from_secs(1)).await;
 					}
 				}
 			}
@@ -439,9 +556,14 @@ impl Response {
 	}
 }
 
-pub struct Socket(Option<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>>);
+pub struct Socket<S>(Option<SplitSink<S, Message>>)
+where
+	S: Sink<Message, Error = WsError>;
 
-impl Drop for Socket {
+impl<S> Drop for Socket<S>
+where
+	S: Sink<Message, Error = WsError>,
+{
 	fn drop(&mut self) {
 		if let Some(mut conn) = mem::take(&mut self.0) {
 			futures::executor::block_on(async move {
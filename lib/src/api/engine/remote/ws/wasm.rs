@@ -10,6 +10,12 @@ use crate::api::engine::remote::ws::Response;
 use crate::api::engine::remote::ws::PING_INTERVAL;
 use crate::api::engine::remote::ws::PING_METHOD;
 use crate::api::err::Error;
+use crate::api::method::Cache;
+use crate::api::method::Emitter;
+use crate::api::method::Event;
+use crate::api::method::InFlight;
+use crate::api::method::Permit;
+use crate::api::method::Subscriptions;
 use crate::api::opt::Endpoint;
 use crate::api::OnceLockExt;
 use crate::api::Result;
@@ -48,7 +54,7 @@ use ws_stream_wasm::WsMessage as Message;
 use ws_stream_wasm::WsMeta;
 
 pub(crate) enum Either {
-	Request(Option<Route>),
+	Request(Option<Route>, Permit),
 	Response(Message),
 	Event(WsEvent),
 	Ping,
@@ -78,7 +84,21 @@ impl Connection for Client {
 
 			let (conn_tx, conn_rx) = flume::bounded(1);
 
-			router(address, capacity, conn_tx, route_rx);
+			let events = Emitter::default();
+			let cache = Cache::new(address.config.cache);
+			let subscriptions = Subscriptions::default();
+			let in_flight = InFlight::new(address.config.max_concurrent_requests);
+
+			router(
+				address,
+				capacity,
+				conn_tx,
+				route_rx,
+				events.clone(),
+				cache.clone(),
+				subscriptions.clone(),
+				in_flight.clone(),
+			);
 
 			conn_rx.into_recv_async().await??;
 
@@ -88,6 +108,13 @@ impl Connection for Client {
 					conn: PhantomData,
 					sender: route_tx,
 					last_id: AtomicI64::new(0),
+					retry: Default::default(),
+					circuit_breaker: Default::default(),
+					events,
+					schemas: Default::default(),
+					cache,
+					subscriptions,
+					in_flight,
 				})),
 			})
 		})
@@ -111,11 +138,16 @@ impl Connection for Client {
 	}
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn router(
 	address: Endpoint,
 	capacity: usize,
 	conn_tx: Sender<Result<()>>,
 	route_rx: Receiver<Option<Route>>,
+	emitter: Emitter,
+	cache: Cache,
+	subscriptions: Subscriptions,
+	in_flight: InFlight,
 ) {
 	spawn_local(async move {
 		let (mut ws, mut socket) = match WsMeta::connect(&address.url, None).await {
@@ -156,6 +188,8 @@ pub(crate) fn router(
 		'router: loop {
 			let (mut socket_sink, socket_stream) = socket.split();
 
+			let mut reason = String::from("the connection was closed");
+
 			let mut routes = match capacity {
 				0 => HashMap::new(),
 				capacity => HashMap::with_capacity(capacity),
@@ -171,7 +205,9 @@ pub(crate) fn router(
 
 			let streams = (
 				socket_stream.map(Either::Response),
-				route_rx.stream().map(Either::Request),
+				in_flight
+					.requests(route_rx.clone())
+					.map(|(route, permit)| Either::Request(route, permit)),
 				pinger.map(|_| Either::Ping),
 				events.map(Either::Event),
 			);
@@ -181,11 +217,15 @@ pub(crate) fn router(
 
 			while let Some(either) = merged.next().await {
 				match either {
-					Either::Request(Some(Route {
-						request,
-						response,
-					})) => {
+					Either::Request(
+						Some(Route {
+							request,
+							response,
+						}),
+						permit,
+					) => {
 						let (id, method, param) = request;
+						cache.sent(method, &param);
 						let params = match param.query {
 							Some((query, bindings)) => {
 								vec![query.into(), bindings.into()]
@@ -234,7 +274,7 @@ pub(crate) fn router(
 								last_activity = Instant::now();
 								match routes.entry(id) {
 									Entry::Vacant(entry) => {
-										entry.insert((method, response));
+										entry.insert((method, response, permit));
 									}
 									Entry::Occupied(..) => {
 										let error = Error::DuplicateRequestId(id);
@@ -250,6 +290,7 @@ pub(crate) fn router(
 							}
 							Err(error) => {
 								let error = Error::Ws(error.to_string());
+								reason = error.to_string();
 								if response.into_send_async(Err(error.into())).await.is_err() {
 									trace!("Receiver dropped");
 								}
@@ -263,8 +304,13 @@ pub(crate) fn router(
 							Ok(option) => {
 								if let Some(response) = option {
 									trace!("{response:?}");
+									if let Some(notification) = response.notification() {
+										cache.notify(notification.id.0);
+										subscriptions.notify(notification);
+									}
 									if let Some(Ok(id)) = response.id.map(Value::coerce_to_i64) {
-										if let Some((_method, sender)) = routes.remove(&id) {
+										if let Some((_method, sender, _permit)) = routes.remove(&id)
+										{
 											let _res = sender
 												.into_send_async(DbResponse::from(response.result))
 												.await;
@@ -286,7 +332,9 @@ pub(crate) fn router(
 									{
 										// Return an error if an ID was returned
 										if let Some(Ok(id)) = id.map(Value::coerce_to_i64) {
-											if let Some((_method, sender)) = routes.remove(&id) {
+											if let Some((_method, sender, _permit)) =
+												routes.remove(&id)
+											{
 												let _res = sender.into_send_async(Err(error)).await;
 											}
 										}
@@ -301,6 +349,7 @@ pub(crate) fn router(
 					Either::Event(event) => match event {
 						WsEvent::Error => {
 							trace!("connection errored");
+							reason = String::from("the connection errored");
 							break;
 						}
 						WsEvent::WsErr(error) => {
@@ -318,16 +367,24 @@ pub(crate) fn router(
 							trace!("Pinging the server");
 							if let Err(error) = socket_sink.send(ping.clone()).await {
 								trace!("failed to ping the server; {error:?}");
+								reason = format!("failed to ping the server; {error}");
 								break;
 							}
 						}
 					}
-					Either::Request(None) => {
+					Either::Request(None, _) => {
 						break 'router;
 					}
 				}
 			}
 
+			// Live queries don't survive the connection, so their results can't be trusted anymore
+			cache.reset();
+			subscriptions.reset();
+			emitter.emit(Event::Disconnected {
+				reason,
+			});
+
 			'reconnect: loop {
 				trace!("Reconnecting...");
 				match WsMeta::connect(&address.url, None).await {
@@ -370,6 +427,12 @@ pub(crate) fn router(
 							}
 						}
 						trace!("Reconnected successfully");
+						emitter.emit(Event::Connected);
+						if replay.keys().any(|method| {
+							matches!(method, Method::Authenticate | Method::Signin | Method::Signup)
+						}) {
+							emitter.emit(Event::Reauthenticated);
+						}
 						break;
 					}
 					Err(error) => {
@@ -0,0 +1,191 @@
+//! The async runtimes which the client can run on
+//!
+//! Tokio is always available. With the `runtime-async-std` feature, the WebSocket engine
+//! also runs on async-std, and is picked when the client isn't used from a Tokio runtime.
+//! Secure WebSocket connections on async-std need `runtime-async-std-rustls` or
+//! `runtime-async-std-native-tls`, so the `rustls` and `native-tls` features don't pull in a
+//! TLS stack for async-std unless it is used.
+
+#[cfg(feature = "protocol-ws")]
+use crate::api::Result;
+use crate::engine::IntervalStream;
+#[cfg(feature = "protocol-ws")]
+use futures::Sink;
+use futures::Stream;
+use futures::StreamExt;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+#[cfg(feature = "protocol-ws")]
+use tokio::net::TcpStream;
+use tokio::time;
+use tokio::time::Instant;
+use tokio::time::MissedTickBehavior;
+#[cfg(feature = "protocol-ws")]
+use tokio_tungstenite::tungstenite::error::Error as WsError;
+#[cfg(feature = "protocol-ws")]
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+#[cfg(feature = "protocol-ws")]
+use tokio_tungstenite::tungstenite::Message;
+#[cfg(feature = "protocol-ws")]
+use tokio_tungstenite::Connector;
+#[cfg(feature = "protocol-ws")]
+use tokio_tungstenite::MaybeTlsStream;
+#[cfg(feature = "protocol-ws")]
+use tokio_tungstenite::WebSocketStream;
+#[cfg(feature = "protocol-ws")]
+use url::Url;
+
+/// The primitives which the client needs from an async runtime
+#[allow(dead_code)] // used by the WebSocket engine
+pub(crate) trait Runtime: 'static {
+	/// A WebSocket connection to the server
+	#[cfg(feature = "protocol-ws")]
+	type WebSocket: Stream<Item = std::result::Result<Message, WsError>>
+		+ Sink<Message, Error = WsError>
+		+ Unpin
+		+ Send
+		+ 'static;
+
+	/// Runs a future in the background
+	fn spawn(future: impl Future<Output = ()> + Send + 'static);
+
+	/// Waits until the duration has elapsed
+	fn sleep(duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + Sync>>;
+
+	/// Ticks once every period, starting one period from now
+	///
+	/// Ticks which are missed are delayed, rather than fired in a burst to catch up.
+	fn interval(period: Duration) -> Pin<Box<dyn Stream<Item = ()> + Send>> {
+		Box::pin(futures::stream::unfold((), move |()| async move {
+			Self::sleep(period).await;
+			Some(((), ()))
+		}))
+	}
+
+	/// Opens a WebSocket connection to the server
+	#[cfg(feature = "protocol-ws")]
+	fn connect(
+		url: Url,
+		config: Option<WebSocketConfig>,
+		maybe_connector: Option<Connector>,
+	) -> Pin<Box<dyn Future<Output = Result<Self::WebSocket>> + Send + Sync>>;
+}
+
+/// The Tokio runtime
+#[derive(Debug)]
+pub(crate) struct Tokio;
+
+impl Runtime for Tokio {
+	#[cfg(feature = "protocol-ws")]
+	type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+	fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+		tokio::spawn(future);
+	}
+
+	fn sleep(duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + Sync>> {
+		Box::pin(time::sleep(duration))
+	}
+
+	fn interval(period: Duration) -> Pin<Box<dyn Stream<Item = ()> + Send>> {
+		let mut interval = time::interval_at(Instant::now() + period, period);
+		interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+		Box::pin(IntervalStream::new(interval).map(|_| ()))
+	}
+
+	#[cfg(feature = "protocol-ws")]
+	fn connect(
+		url: Url,
+		config: Option<WebSocketConfig>,
+		#[allow(unused_variables)] maybe_connector: Option<Connector>,
+	) -> Pin<Box<dyn Future<Output = Result<Self::WebSocket>> + Send + Sync>> {
+		Box::pin(async move {
+			#[cfg(any(feature = "native-tls", feature = "rustls"))]
+			let (socket, _) =
+				tokio_tungstenite::connect_async_tls_with_config(&url, config, maybe_connector)
+					.await?;
+
+			#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+			let (socket, _) = tokio_tungstenite::connect_async_with_config(&url, config).await?;
+
+			Ok(socket)
+		})
+	}
+}
+
+/// The async-std runtime
+#[cfg(feature = "runtime-async-std")]
+#[derive(Debug)]
+pub(crate) struct AsyncStd;
+
+#[cfg(feature = "runtime-async-std")]
+impl Runtime for AsyncStd {
+	type WebSocket =
+		async_tungstenite::WebSocketStream<async_tungstenite::async_std::ConnectStream>;
+
+	fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+		async_std::task::spawn(future);
+	}
+
+	fn sleep(duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + Sync>> {
+		Box::pin(async_std::task::sleep(duration))
+	}
+
+	fn connect(
+		url: Url,
+		config: Option<WebSocketConfig>,
+		maybe_connector: Option<Connector>,
+	) -> Pin<Box<dyn Future<Output = Result<Self::WebSocket>> + Send + Sync>> {
+		Box::pin(async move {
+			if maybe_connector.is_some() {
+				return Err(crate::api::err::Error::Ws(
+					"Custom TLS configurations are only supported on the Tokio runtime".to_owned(),
+				)
+				.into());
+			}
+			// The connection is made on a task, as the connect future isn't Sync
+			let (socket, _) = async_std::task::spawn(
+				async_tungstenite::async_std::connect_async_with_config(url, config),
+			)
+			.await?;
+			Ok(socket)
+		})
+	}
+}
+
+/// Whether the client is used from outside of a Tokio runtime
+#[cfg(feature = "runtime-async-std")]
+pub(crate) fn outside_tokio() -> bool {
+	tokio::runtime::Handle::try_current().is_err()
+}
+
+/// Waits until the duration has elapsed, on the runtime which the client is used from
+pub(crate) fn sleep(duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + Sync>> {
+	#[cfg(feature = "runtime-async-std")]
+	if outside_tokio() {
+		return AsyncStd::sleep(duration);
+	}
+	Tokio::sleep(duration)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn interval_starts_after_one_period() {
+		let period = Duration::from_millis(50);
+		let start = Instant::now();
+		Tokio::interval(period).next().await;
+		assert!(start.elapsed() >= period);
+	}
+
+	#[tokio::test]
+	async fn sleep_runs_on_tokio() {
+		let duration = Duration::from_millis(10);
+		let start = Instant::now();
+		sleep(duration).await;
+		assert!(start.elapsed() >= duration);
+	}
+}
@@ -9,6 +9,10 @@ use std::io;
 use std::path::PathBuf;
 use thiserror::Error;
 
+mod status;
+
+pub use status::ErrorBody;
+
 /// An error originating from a remote SurrealDB database
 #[derive(Error, Debug)]
 #[non_exhaustive]
@@ -33,6 +37,10 @@ pub enum Error {
 	#[error("Connection uninitialised")]
 	ConnectionUninitialised,
 
+	/// The circuit breaker is open because the server has been failing
+	#[error("The circuit breaker is open; not sending requests to a server which is unavailable")]
+	CircuitOpen,
+
 	/// Tried to call `connect` on an instance already connected
 	#[error("Already connected")]
 	AlreadyConnected,
@@ -64,6 +72,10 @@ pub enum Error {
 		id: String,
 	},
 
+	/// Tried to use an input that is not a record ID where one was expected
+	#[error("Invalid record ID: {0}")]
+	InvalidRecordId(String),
+
 	/// Duplicate request ID
 	#[error("Duplicate request ID: {0}")]
 	DuplicateRequestId(i64),
@@ -120,6 +132,25 @@ pub enum Error {
 		error: String,
 	},
 
+	/// Failed to serialize `sql::Value` to CBOR
+	#[error("Failed to serialize sql::Value to CBOR: {error}")]
+	ToCbor {
+		error: String,
+	},
+
+	/// Failed to deserialize from CBOR to `sql::Value`
+	#[error("Failed to deserialize CBOR to sql::Value: {error}")]
+	FromCbor {
+		error: String,
+	},
+
+	/// The content of a record doesn't match the schema of its table
+	#[error("The content does not match the schema of table `{table}`: {}", .problems.join(", "))]
+	SchemaMismatch {
+		table: String,
+		problems: Vec<String>,
+	},
+
 	/// Invalid namespace name
 	#[error("Invalid namespace name: {0:?}")]
 	InvalidNsName(String),
@@ -142,6 +173,25 @@ pub enum Error {
 		error: io::Error,
 	},
 
+	/// Failed to write exported data to the destination
+	#[error("Failed to write the export: {0}")]
+	ExportWrite(io::Error),
+
+	/// Failed to read the data to import from the source
+	#[error("Failed to read the import: {0}")]
+	ImportRead(io::Error),
+
+	/// Failed to read the blob to store from the source
+	#[error("Failed to read the blob: {0}")]
+	BlobRead(io::Error),
+
+	/// Failed to read the value of a parameter from its source
+	#[error("Failed to read the parameter `{key}`: {error}")]
+	ParamRead {
+		key: String,
+		error: io::Error,
+	},
+
 	/// Tried to take only a single result when the query returned multiple records
 	#[error("Tried to take only a single result from a query that contains multiple")]
 	LossyTake(Response),
@@ -151,6 +201,11 @@ pub enum Error {
 	#[error("The protocol or storage engine does not support backups on this architecture")]
 	BackupsNotSupported,
 
+	/// The protocol or storage engine being used does not support binary blobs on the architecture
+	/// it's running on
+	#[error("The protocol or storage engine does not support binary blobs on this architecture")]
+	BlobsNotSupported,
+
 	/// The version of the server is not compatible with the versions supported by this SDK
 	#[error("server version `{server_version}` does not match the range supported by the client `{supported_versions}`")]
 	VersionMismatch {
@@ -164,6 +219,69 @@ pub enum Error {
 		server_metadata: semver::BuildMetadata,
 		supported_metadata: semver::BuildMetadata,
 	},
+
+	/// More than one migration was registered with the same version
+	#[error("Migration version `{0}` was registered more than once")]
+	DuplicateMigration(u64),
+
+	/// A migration was applied to the database, but it is not registered, so it can't be reverted
+	#[error("Migration version `{0}` was applied, but it is not registered")]
+	MigrationNotFound(u64),
+
+	/// A migration can't be reverted, because it doesn't have a down script
+	#[error("Migration version `{0}` can not be reverted, as it does not have a down script")]
+	IrreversibleMigration(u64),
+
+	/// Another process is migrating the database
+	#[error("The database is being migrated by another process. Remove the `_migrations_lock` record if that process is no longer running")]
+	MigrationLocked,
+
+	/// A Rust migration failed part way, so the state of the database is unknown
+	#[error("Migration version `{0}` did not complete. Resolve it, and remove its `_migrations` record, before migrating again")]
+	IncompleteMigration(u64),
+
+	/// A live query subscription fell too far behind, so its notifications were cut off
+	#[error("The live query subscription fell behind by more than {0} notifications, and was closed")]
+	SubscriptionLagged(usize),
+
+	/// The seed data could not be parsed
+	#[error("Invalid seed data: {0}")]
+	InvalidSeed(String),
+
+	/// A table of the seed data depends on a table which is not in the seed data
+	#[error("The `{table}` table of the seed data depends on `{dependency}`, which is not in the seed data")]
+	SeedDependencyNotFound {
+		table: String,
+		dependency: String,
+	},
+
+	/// The tables of the seed data depend on each other in a cycle
+	#[error("The tables of the seed data depend on each other in a cycle")]
+	CyclicSeedDependencies,
+
+	/// Tried to use a model which does not have a record ID as a record
+	#[error("A record of the `{0}` table does not have a record ID")]
+	MissingRecordId(String),
+
+	/// A unit of work referenced a record which it never creates
+	#[error("The unit of work references `{0}`, which it does not create")]
+	UndefinedRef(String),
+
+	/// The writes of a unit of work depend on each other in a cycle
+	#[error("The writes of the unit of work reference each other in a cycle")]
+	CyclicRefs,
+
+	/// Tried to derive the ID of a record from its content, when the record was not created in a table
+	#[error("The record ID can only be derived from the content when creating a record in a table, not in `{0}`")]
+	HashIdOnRecord(String),
+
+	/// A path of a diff does not exist in the value which the diff is applied to
+	#[error("The path `{0}` of the diff does not exist in the value")]
+	DiffPathNotFound(String),
+
+	/// A change of a diff can't be reverted, because the value which it replaced is not known
+	#[error("The change at `{0}` can not be reverted, as the previous value is not known")]
+	IrreversibleDiff(String),
 }
 
 #[cfg(feature = "protocol-http")]
@@ -0,0 +1,334 @@
+//! The mapping of errors to HTTP status codes
+//!
+//! Errors which are caused by the request are mapped to `4xx` codes, and errors which are
+//! caused by the database or the connection to it are mapped to `5xx` codes. Any error
+//! which is not listed here is treated as a bad request.
+
+use crate::api::err::Error as ApiError;
+use crate::err::Error as DbError;
+use crate::iam::Error as IamError;
+use serde::Deserialize;
+use serde::Serialize;
+
+const BAD_REQUEST: u16 = 400;
+const UNAUTHORIZED: u16 = 401;
+const FORBIDDEN: u16 = 403;
+const NOT_FOUND: u16 = 404;
+const CONFLICT: u16 = 409;
+const PAYLOAD_TOO_LARGE: u16 = 413;
+const TOO_MANY_REQUESTS: u16 = 429;
+const INTERNAL_SERVER_ERROR: u16 = 500;
+const NOT_IMPLEMENTED: u16 = 501;
+const BAD_GATEWAY: u16 = 502;
+const SERVICE_UNAVAILABLE: u16 = 503;
+const GATEWAY_TIMEOUT: u16 = 504;
+
+/// The body of an HTTP response for an error
+///
+/// # Examples
+///
+/// ```
+/// use surrealdb::error::Db;
+/// use surrealdb::error::ErrorBody;
+///
+/// let error = surrealdb::Error::from(Db::QueryTimedout);
+/// let body = ErrorBody::from(&error);
+/// assert_eq!(body.code, 504);
+/// assert_eq!(body.status, "Gateway Timeout");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ErrorBody {
+	/// The HTTP status code of the error
+	pub code: u16,
+	/// The reason phrase of the status code
+	pub status: String,
+	/// The message of the error
+	pub message: String,
+}
+
+impl From<&crate::Error> for ErrorBody {
+	fn from(error: &crate::Error) -> Self {
+		let code = error.status_code();
+		Self {
+			code,
+			status: reason(code).to_owned(),
+			message: error.to_string(),
+		}
+	}
+}
+
+impl From<crate::Error> for ErrorBody {
+	fn from(error: crate::Error) -> Self {
+		Self::from(&error)
+	}
+}
+
+/// The reason phrase of a status code which errors are mapped to
+fn reason(code: u16) -> &'static str {
+	match code {
+		UNAUTHORIZED => "Unauthorized",
+		FORBIDDEN => "Forbidden",
+		NOT_FOUND => "Not Found",
+		CONFLICT => "Conflict",
+		PAYLOAD_TOO_LARGE => "Payload Too Large",
+		TOO_MANY_REQUESTS => "Too Many Requests",
+		INTERNAL_SERVER_ERROR => "Internal Server Error",
+		NOT_IMPLEMENTED => "Not Implemented",
+		BAD_GATEWAY => "Bad Gateway",
+		SERVICE_UNAVAILABLE => "Service Unavailable",
+		GATEWAY_TIMEOUT => "Gateway Timeout",
+		_ => "Bad Request",
+	}
+}
+
+impl crate::Error {
+	/// The HTTP status code which best describes the error
+	///
+	/// Authentication errors map to `401`, permission errors to `403`, missing resources
+	/// to `404`, conflicting writes to `409` and timeouts to `504`. Errors with the
+	/// connection to a remote database map to `502` or `503`, and internal errors to `500`.
+	pub fn status_code(&self) -> u16 {
+		match self {
+			Self::Db(error) => error.status_code(),
+			Self::Api(error) => error.status_code(),
+		}
+	}
+}
+
+impl DbError {
+	/// The HTTP status code which best describes the error
+	pub fn status_code(&self) -> u16 {
+		match self {
+			DbError::InvalidAuth
+			| DbError::InvalidPass
+			| DbError::InvalidSignup
+			| DbError::UnknownAuth
+			| DbError::MissingUserOrPass
+			| DbError::NoSigninTarget
+			| DbError::SigninQueryFailed
+			| DbError::SignupQueryFailed
+			| DbError::ScopeNoSignin
+			| DbError::ScopeNoSignup
+			| DbError::NoScopeFound => UNAUTHORIZED,
+			DbError::IamError(IamError::NotAllowed {
+				..
+			})
+			| DbError::NsNotAllowed {
+				..
+			}
+			| DbError::DbNotAllowed {
+				..
+			}
+			| DbError::TablePermissions {
+				..
+			}
+			| DbError::ParamPermissions {
+				..
+			}
+			| DbError::FunctionPermissions {
+				..
+			}
+			| DbError::ScriptingNotAllowed
+			| DbError::FunctionNotAllowed(..)
+			| DbError::NetTargetNotAllowed(..)
+			| DbError::HttpDisabled
+			| DbError::FunctionDisabled {
+				..
+			}
+			| DbError::RealtimeDisabled => FORBIDDEN,
+			DbError::NsNotFound {
+				..
+			}
+			| DbError::NtNotFound {
+				..
+			}
+			| DbError::NlNotFound {
+				..
+			}
+			| DbError::DbNotFound {
+				..
+			}
+			| DbError::DtNotFound {
+				..
+			}
+			| DbError::DlNotFound {
+				..
+			}
+			| DbError::FcNotFound {
+				..
+			}
+			| DbError::ScNotFound {
+				..
+			}
+			| DbError::PaNotFound {
+				..
+			}
+			| DbError::TbNotFound {
+				..
+			}
+			| DbError::LvNotFound {
+				..
+			}
+			| DbError::LqNotFound {
+				..
+			}
+			| DbError::AzNotFound {
+				..
+			}
+			| DbError::IxNotFound {
+				..
+			}
+			| DbError::UserRootNotFound {
+				..
+			}
+			| DbError::UserNsNotFound {
+				..
+			}
+			| DbError::UserDbNotFound {
+				..
+			}
+			| DbError::BlobNotFound {
+				..
+			}
+			| DbError::NoRecordFound => NOT_FOUND,
+			DbError::RecordExists {
+				..
+			}
+			| DbError::IndexExists {
+				..
+			}
+			| DbError::ClAlreadyExists {
+				..
+			}
+			| DbError::FdAlreadyExists {
+				..
+			}
+			| DbError::TxKeyAlreadyExists
+			| DbError::TxConditionNotMet
+			| DbError::TxFailure => CONFLICT,
+			DbError::TxKeyTooLarge | DbError::TxValueTooLarge | DbError::TxTooLarge => {
+				PAYLOAD_TOO_LARGE
+			}
+			DbError::HttpQuotaExceeded {
+				..
+			}
+			| DbError::TableWriteLimitExceeded {
+				..
+			}
+			| DbError::TableWriteConcurrencyExceeded {
+				..
+			} => TOO_MANY_REQUESTS,
+			DbError::QueryTimedout => GATEWAY_TIMEOUT,
+			DbError::FeatureNotYetImplemented {
+				..
+			}
+			| DbError::Unimplemented(..)
+			| DbError::TxPointInTimeUnsupported(..)
+			| DbError::ReplicationUnsupported(..) => NOT_IMPLEMENTED,
+			DbError::Unreachable
+			| DbError::Ds(..)
+			| DbError::Tx(..)
+			| DbError::Io(..)
+			| DbError::Channel(..)
+			| DbError::Encode(..)
+			| DbError::Decode(..)
+			| DbError::Revision(..)
+			| DbError::Bincode(..)
+			| DbError::FstError(..)
+			| DbError::CorruptedIndex
+			| DbError::IndexInconsistent {
+				..
+			}
+			| DbError::CorruptedVersionstampInKey(..)
+			| DbError::SharedValueNotFound {
+				..
+			}
+			| DbError::TokenMakingFailed
+			| DbError::Internal(..) => INTERNAL_SERVER_ERROR,
+			_ => BAD_REQUEST,
+		}
+	}
+}
+
+impl ApiError {
+	/// The HTTP status code which best describes the error
+	pub fn status_code(&self) -> u16 {
+		match self {
+			ApiError::MigrationNotFound(..) => NOT_FOUND,
+			ApiError::DuplicateMigration(..)
+			| ApiError::MigrationLocked
+			| ApiError::IncompleteMigration(..)
+			| ApiError::DuplicateRequestId(..) => CONFLICT,
+			ApiError::BackupsNotSupported | ApiError::BlobsNotSupported => NOT_IMPLEMENTED,
+			ApiError::Http(..)
+			| ApiError::Ws(..)
+			| ApiError::ResponseFromBinary {
+				..
+			}
+			| ApiError::VersionMismatch {
+				..
+			}
+			| ApiError::BuildMetadataMismatch {
+				..
+			} => BAD_GATEWAY,
+			ApiError::CircuitOpen
+			| ApiError::ConnectionUninitialised
+			| ApiError::SubscriptionLagged(..) => SERVICE_UNAVAILABLE,
+			ApiError::InternalError(..)
+			| ApiError::FileOpen {
+				..
+			}
+			| ApiError::FileRead {
+				..
+			}
+			| ApiError::ExportWrite(..) => INTERNAL_SERVER_ERROR,
+			_ => BAD_REQUEST,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn maps_errors_to_status_codes() {
+		let error = crate::Error::from(DbError::InvalidAuth);
+		assert_eq!(error.status_code(), UNAUTHORIZED);
+		let error = crate::Error::from(DbError::IamError(IamError::NotAllowed {
+			actor: "user".to_owned(),
+			action: "edit".to_owned(),
+			resource: "db".to_owned(),
+		}));
+		assert_eq!(error.status_code(), FORBIDDEN);
+		let error = crate::Error::from(DbError::TbNotFound {
+			value: "person".to_owned(),
+		});
+		assert_eq!(error.status_code(), NOT_FOUND);
+		let error = crate::Error::from(DbError::TxKeyAlreadyExists);
+		assert_eq!(error.status_code(), CONFLICT);
+		let error = crate::Error::from(DbError::QueryTimedout);
+		assert_eq!(error.status_code(), GATEWAY_TIMEOUT);
+		let error = crate::Error::from(ApiError::Ws("connection reset".to_owned()));
+		assert_eq!(error.status_code(), BAD_GATEWAY);
+		let error = crate::Error::from(ApiError::Query("parse error".to_owned()));
+		assert_eq!(error.status_code(), BAD_REQUEST);
+	}
+
+	#[test]
+	fn serializes_the_error_body() {
+		let error = crate::Error::from(DbError::BlobNotFound {
+			value: "person:tobie".to_owned(),
+		});
+		let body = serde_json::to_value(ErrorBody::from(error)).unwrap();
+		assert_eq!(
+			body,
+			serde_json::json!({
+				"code": 404,
+				"status": "Not Found",
+				"message": "The record 'person:tobie' has no blob",
+			})
+		);
+	}
+}
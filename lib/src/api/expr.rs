@@ -0,0 +1,248 @@
+//! Typed expressions, which are composed into the conditions of queries
+//!
+//! Expressions are built from fields, parameters and values, and are combined with the
+//! comparison and logical operators of SurrealQL. They produce the expression directly, without
+//! going through the parser, and can be used wherever a condition is accepted, such as in
+//! [`select_from`](crate::builder::select_from), [`Delete::filter`](crate::method::Delete::filter),
+//! [`Update::filter`](crate::method::Update::filter) and live queries.
+//!
+//! ```no_run
+//! use surrealdb::builder::select_from;
+//! use surrealdb::expr::field;
+//! use surrealdb::expr::param;
+//! use surrealdb::sql::Value;
+//!
+//! # #[derive(serde::Deserialize)]
+//! # struct Person;
+//! #
+//! # #[tokio::main]
+//! # async fn main() -> surrealdb::Result<()> {
+//! # let db = surrealdb::engine::any::connect("mem://").await?;
+//! #
+//! let adults = field("age").gte(18).and(field("name").contains("a"));
+//!
+//! let people: Vec<Person> = db.query(select_from("person").filter(adults.clone())).await?.take(0)?;
+//!
+//! let _: Vec<Value> = db.update("person").filter(adults.not()).merge(("minor", true)).await?;
+//!
+//! let deleted = db.delete("person").filter(field("banned").eq(param("flag"))).bind(("flag", true)).await?;
+//! #
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::opt::IntoCondition;
+use crate::api::Result;
+use crate::sql::Expression;
+use crate::sql::Geometry;
+use crate::sql::Idiom;
+use crate::sql::Operator;
+use crate::sql::Part;
+use crate::sql::Subquery;
+use crate::sql::Value;
+use std::fmt;
+
+/// Converts a field path, with nested fields separated by dots, into an idiom
+pub(crate) fn idiom(path: &str) -> Idiom {
+	Idiom(path.split('.').map(Part::from).collect())
+}
+
+/// Wraps a nested expression in parentheses
+///
+/// Remote engines send the statements as text, so the parentheses keep the
+/// order in which the expressions are evaluated the same as in the builder.
+fn group(value: Value) -> Value {
+	match value {
+		Value::Expression(_) => Value::Subquery(Box::new(Subquery::Value(value))),
+		value => value,
+	}
+}
+
+/// An expression, such as the condition of a query
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr(pub(crate) Value);
+
+/// Refers to a field of the records, with nested fields separated by dots, such as `name.first`
+pub fn field(path: &str) -> Expr {
+	Expr(Value::Idiom(idiom(path)))
+}
+
+/// Refers to a parameter, such as one which is bound to the query, without the leading `$`
+pub fn param(name: &str) -> Expr {
+	Expr(Value::Param(name.trim_start_matches('$').into()))
+}
+
+/// A literal value
+pub fn value(value: impl Into<Value>) -> Expr {
+	Expr(value.into())
+}
+
+impl Expr {
+	fn binary(self, o: Operator, r: impl Into<Value>) -> Self {
+		Self(
+			Expression::Binary {
+				l: group(self.0),
+				o,
+				r: group(r.into()),
+			}
+			.into(),
+		)
+	}
+
+	/// Checks whether the expression is equal to a value
+	pub fn eq(self, value: impl Into<Value>) -> Self {
+		self.binary(Operator::Equal, value)
+	}
+
+	/// Checks whether the expression is not equal to a value
+	pub fn ne(self, value: impl Into<Value>) -> Self {
+		self.binary(Operator::NotEqual, value)
+	}
+
+	/// Checks whether the expression is greater than a value
+	pub fn gt(self, value: impl Into<Value>) -> Self {
+		self.binary(Operator::MoreThan, value)
+	}
+
+	/// Checks whether the expression is greater than or equal to a value
+	pub fn gte(self, value: impl Into<Value>) -> Self {
+		self.binary(Operator::MoreThanOrEqual, value)
+	}
+
+	/// Checks whether the expression is less than a value
+	pub fn lt(self, value: impl Into<Value>) -> Self {
+		self.binary(Operator::LessThan, value)
+	}
+
+	/// Checks whether the expression is less than or equal to a value
+	pub fn lte(self, value: impl Into<Value>) -> Self {
+		self.binary(Operator::LessThanOrEqual, value)
+	}
+
+	/// Checks whether the expression fuzzy matches a value
+	pub fn like(self, value: impl Into<Value>) -> Self {
+		self.binary(Operator::Like, value)
+	}
+
+	/// Checks whether the expression, such as an array or a string, contains a value
+	pub fn contains(self, value: impl Into<Value>) -> Self {
+		self.binary(Operator::Contain, value)
+	}
+
+	/// Checks whether the expression, such as an array or a string, doesn't contain a value
+	pub fn not_contains(self, value: impl Into<Value>) -> Self {
+		self.binary(Operator::NotContain, value)
+	}
+
+	/// Checks whether the expression contains any of the values
+	pub fn contains_any(self, values: impl Into<Value>) -> Self {
+		self.binary(Operator::ContainAny, values)
+	}
+
+	/// Checks whether the expression contains all of the values
+	pub fn contains_all(self, values: impl Into<Value>) -> Self {
+		self.binary(Operator::ContainAll, values)
+	}
+
+	/// Checks whether the expression is one of the values
+	pub fn inside(self, values: impl Into<Value>) -> Self {
+		self.binary(Operator::Inside, values)
+	}
+
+	/// Checks whether the expression is none of the values
+	pub fn not_inside(self, values: impl Into<Value>) -> Self {
+		self.binary(Operator::NotInside, values)
+	}
+
+	/// Checks whether the expression, such as a point, is inside a geometry, such as a polygon
+	pub fn within(self, geometry: impl Into<Geometry>) -> Self {
+		self.binary(Operator::Inside, geometry.into())
+	}
+
+	/// Checks whether the expression is a geometry which intersects another geometry
+	pub fn intersects(self, geometry: impl Into<Geometry>) -> Self {
+		self.binary(Operator::Intersects, geometry.into())
+	}
+
+	/// Checks whether the expression is `NONE` or `NULL`, such as a field which isn't set
+	pub fn is_none(self) -> Self {
+		self.clone().eq(Value::None).or(self.eq(Value::Null))
+	}
+
+	/// Checks whether both expressions are true
+	pub fn and(self, other: Expr) -> Self {
+		self.binary(Operator::And, other)
+	}
+
+	/// Checks whether either expression is true
+	pub fn or(self, other: Expr) -> Self {
+		self.binary(Operator::Or, other)
+	}
+
+	/// Negates the expression
+	#[allow(clippy::should_implement_trait)]
+	pub fn not(self) -> Self {
+		Self(
+			Expression::Unary {
+				o: Operator::Not,
+				v: group(self.0),
+			}
+			.into(),
+		)
+	}
+}
+
+impl From<Expr> for Value {
+	fn from(expr: Expr) -> Self {
+		expr.0
+	}
+}
+
+impl IntoCondition for Expr {
+	fn into_condition(self) -> Result<Value> {
+		Ok(self.0)
+	}
+}
+
+impl fmt::Display for Expr {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql;
+
+	#[test]
+	fn matches_the_parser() {
+		let expr = field("age").gt(18).and(field("name").contains("a"));
+		assert_eq!(expr.to_string(), "(age > 18) AND (name CONTAINS 'a')");
+		assert_eq!(Value::from(expr.clone()), sql::value(&expr.to_string()).unwrap());
+	}
+
+	#[test]
+	fn nested_expressions() {
+		let expr = field("tags")
+			.inside(vec!["admin", "staff"])
+			.or(field("name.first").like("tob").not())
+			.and(param("$limit").gte(field("age")));
+		assert_eq!(
+			expr.to_string(),
+			"((tags INSIDE ['admin', 'staff']) OR (!(name.first ~ 'tob'))) AND ($limit >= age)"
+		);
+		assert_eq!(Value::from(expr.clone()), sql::value(&expr.to_string()).unwrap());
+	}
+
+	#[test]
+	fn spatial_expressions() {
+		let area = geo::Rect::new((0.0, 0.0), (1.0, 1.0));
+		let expr = field("location").within(area).and(field("route").intersects((0.5, 0.5)));
+		assert_eq!(
+			expr.to_string(),
+			"(location INSIDE { type: 'Polygon', coordinates: [[[0, 0], [0, 1], [1, 1], [1, 0], [0, 0]]] }) AND (route INTERSECTS (0.5, 0.5))"
+		);
+		assert_eq!(Value::from(expr.clone()), sql::value(&expr.to_string()).unwrap());
+	}
+}
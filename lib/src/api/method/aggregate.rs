@@ -0,0 +1,89 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::opt::from_value;
+use crate::sql::statements::SelectStatement;
+use crate::sql::Field;
+use crate::sql::Fields;
+use crate::sql::Function;
+use crate::sql::Groups;
+use crate::sql::Idiom;
+use crate::sql::Part;
+use crate::sql::Query;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Table;
+use crate::sql::Value;
+use crate::sql::Values;
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+/// The aggregate function which is computed over the records of a table
+#[derive(Debug)]
+pub(super) enum Aggregation {
+	Count,
+	Sum(String),
+	Avg(String),
+}
+
+/// An aggregate future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Aggregate<'r, C: Connection, R> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) table: String,
+	pub(super) aggregation: Aggregation,
+	pub(super) response_type: PhantomData<R>,
+}
+
+impl<'r, Client, R> IntoFuture for Aggregate<'r, Client, R>
+where
+	Client: Connection,
+	R: DeserializeOwned + std::fmt::Debug,
+{
+	type Output = Result<R>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let router = self.router?;
+			let field =
+				|path: String| Value::Idiom(Idiom(path.split('.').map(Part::from).collect()));
+			// An empty table has no group, so the count and the sum are zero, and there is no average
+			let (function, default) = match self.aggregation {
+				Aggregation::Count => {
+					(Function::Normal("count".to_owned(), vec![]), Value::from(0))
+				}
+				Aggregation::Sum(path) => {
+					(Function::Normal("math::sum".to_owned(), vec![field(path)]), Value::from(0))
+				}
+				Aggregation::Avg(path) => {
+					(Function::Normal("math::mean".to_owned(), vec![field(path)]), Value::None)
+				}
+			};
+			let statement = SelectStatement {
+				expr: Fields(
+					vec![Field::Single {
+						expr: function.into(),
+						alias: Some(Idiom(vec![Part::from("value")])),
+					}],
+					false,
+				),
+				what: Values(vec![Value::Table(Table(self.table))]),
+				group: Some(Groups(Vec::new())),
+				..Default::default()
+			};
+			let query = Query(Statements(vec![Statement::Select(statement)]));
+			let mut conn = Client::new(Method::Query);
+			let param = Param::query(query, Default::default()).idempotent();
+			let value: Option<Value> =
+				conn.execute_query(router, param).await?.take((0, "value"))?;
+			Ok(from_value(value.unwrap_or(default))?)
+		})
+	}
+}
@@ -0,0 +1,143 @@
+use crate::api::conn::Route;
+use flume::Receiver;
+use futures::stream;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::sync::Semaphore;
+
+/// The depth of the request queue of a connection
+///
+/// See [`Surreal::request_metrics`](crate::Surreal::request_metrics).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RequestMetrics {
+	/// The requests which are waiting to be sent to the server
+	pub queued: usize,
+	/// The requests which were sent to the server, and are waiting for a response
+	pub in_flight: usize,
+	/// The maximum number of requests which are in flight at once, if there is one
+	pub max_in_flight: Option<usize>,
+}
+
+/// Limits the number of requests which are waiting for a response from the server
+#[derive(Debug, Clone, Default)]
+pub(crate) struct InFlight {
+	semaphore: Option<Arc<Semaphore>>,
+	max: Option<usize>,
+	count: Arc<AtomicUsize>,
+}
+
+/// A request which is in flight until it's dropped, such as when its response arrives
+#[derive(Debug)]
+pub(crate) struct Permit {
+	_permit: Option<OwnedSemaphorePermit>,
+	count: Arc<AtomicUsize>,
+}
+
+impl Drop for Permit {
+	fn drop(&mut self) {
+		self.count.fetch_sub(1, Ordering::SeqCst);
+	}
+}
+
+impl InFlight {
+	pub(crate) fn new(max: Option<usize>) -> Self {
+		Self {
+			semaphore: max.map(|max| Arc::new(Semaphore::new(max))),
+			max,
+			count: Default::default(),
+		}
+	}
+
+	/// The number of requests which are in flight
+	pub(crate) fn count(&self) -> usize {
+		self.count.load(Ordering::SeqCst)
+	}
+
+	/// Takes the next request off the queue, once fewer than the maximum are in flight
+	async fn next(&self, rx: &Receiver<Option<Route>>) -> Option<(Option<Route>, Permit)> {
+		let permit = match &self.semaphore {
+			Some(semaphore) => Some(semaphore.clone().acquire_owned().await.ok()?),
+			None => None,
+		};
+		let route = rx.recv_async().await.ok()?;
+		self.count.fetch_add(1, Ordering::SeqCst);
+		Some((
+			route,
+			Permit {
+				_permit: permit,
+				count: self.count.clone(),
+			},
+		))
+	}
+
+	/// The requests in a queue, each along with the permit which keeps it in flight
+	///
+	/// Requests are left in the queue while the maximum number of requests are in flight, so
+	/// that the queue fills up and the senders wait for room in it.
+	#[allow(dead_code)] // used by the WebSocket connections
+	pub(crate) fn requests(
+		&self,
+		rx: Receiver<Option<Route>>,
+	) -> BoxStream<'static, (Option<Route>, Permit)> {
+		stream::unfold((self.clone(), rx), |(in_flight, rx)| async move {
+			let next = in_flight.next(&rx).await?;
+			Some((next, (in_flight, rx)))
+		})
+		.boxed()
+	}
+
+	pub(crate) fn metrics(&self, queued: usize) -> RequestMetrics {
+		RequestMetrics {
+			queued,
+			in_flight: self.count(),
+			max_in_flight: self.max,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::api::conn::Method;
+	use crate::api::conn::Param;
+	use std::time::Duration;
+
+	fn route(id: i64) -> Option<Route> {
+		let (response, _) = flume::bounded(1);
+		Some(Route {
+			request: (id, Method::Health, Param::new(Vec::new())),
+			response,
+		})
+	}
+
+	#[tokio::test]
+	async fn holds_requests_back_at_the_limit() {
+		let in_flight = InFlight::new(Some(2));
+		let (tx, rx) = flume::unbounded();
+		for id in 0..3 {
+			tx.send(route(id)).unwrap();
+		}
+		let mut requests = in_flight.requests(rx);
+		let (_, first) = requests.next().await.unwrap();
+		let (_, _second) = requests.next().await.unwrap();
+		assert_eq!(
+			in_flight.metrics(tx.len()),
+			RequestMetrics {
+				queued: 1,
+				in_flight: 2,
+				max_in_flight: Some(2),
+			}
+		);
+		// The third request stays queued until a response arrives
+		let third = tokio::time::timeout(Duration::from_millis(50), requests.next()).await;
+		assert!(third.is_err());
+		drop(first);
+		assert!(requests.next().await.is_some());
+		assert_eq!(in_flight.metrics(tx.len()).queued, 0);
+	}
+}
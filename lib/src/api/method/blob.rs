@@ -0,0 +1,205 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::Connection;
+use crate::api::Error;
+use crate::api::ExtraFeatures;
+use crate::api::Result;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::cnf::BLOB_CHUNK_SIZE;
+use crate::sql::Thing;
+use channel::Receiver;
+#[cfg(not(target_arch = "wasm32"))]
+use channel::Sender;
+use futures::Stream;
+use futures::StreamExt;
+use std::future::Future;
+use std::future::IntoFuture;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io;
+use std::pin::Pin;
+#[cfg(not(target_arch = "wasm32"))]
+use std::task::ready;
+use std::task::Context;
+use std::task::Poll;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::AsyncRead;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::AsyncReadExt;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::ReadBuf;
+
+/// A future which stores the binary blob of a record
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct PutBlob<'r, C: Connection, R> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) resource: Result<Thing>,
+	pub(super) reader: R,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<'r, Client, R> IntoFuture for PutBlob<'r, Client, R>
+where
+	Client: Connection,
+	R: AsyncRead + Unpin + Send + Sync + 'r,
+{
+	type Output = Result<u64>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let router = self.router?;
+			if !router.features.contains(&ExtraFeatures::Blobs) {
+				return Err(Error::BlobsNotSupported.into());
+			}
+			let (tx, rx) = crate::channel::new(1);
+			let mut param = Param::receiver(rx);
+			param.other = vec![self.resource?.into()];
+			// The blob is read while it is being stored, so it is never held in memory as a whole
+			let upload = upload(self.reader, tx, |error| Error::BlobRead(error).into());
+			let mut conn = Client::new(Method::PutBlob);
+			let (size, ()) = futures::join!(conn.execute(router, param), upload);
+			size
+		})
+	}
+}
+
+/// Sends what is read from a reader to a channel in chunks, until the end of the reader
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) async fn upload<R>(
+	mut reader: R,
+	tx: Sender<Result<Vec<u8>>>,
+	error: impl Fn(io::Error) -> crate::Error,
+) where
+	R: AsyncRead + Unpin,
+{
+	let mut buf = vec![0; BLOB_CHUNK_SIZE];
+	loop {
+		let chunk = match reader.read(&mut buf).await {
+			Ok(0) => break,
+			Ok(n) => Ok(buf[..n].to_vec()),
+			Err(e) => Err(error(e)),
+		};
+		let failed = chunk.is_err();
+		// The request has already failed if the chunks are no longer received
+		if tx.send(chunk).await.is_err() || failed {
+			break;
+		}
+	}
+}
+
+/// A future which reads the binary blob of a record
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct GetBlob<'r, C: Connection> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) resource: Result<Thing>,
+}
+
+impl<'r, Client> IntoFuture for GetBlob<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<Blob>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let router = self.router?;
+			if !router.features.contains(&ExtraFeatures::Blobs) {
+				return Err(Error::BlobsNotSupported.into());
+			}
+			let (tx, rx) = crate::channel::new(1);
+			let mut param = Param::sender(tx);
+			param.other = vec![self.resource?.into()];
+			let mut conn = Client::new(Method::GetBlob);
+			conn.execute_unit(router, param).await?;
+			Ok(Blob {
+				rx,
+				chunk: Vec::new(),
+				pos: 0,
+			})
+		})
+	}
+}
+
+/// The binary blob of a record, as it is read from the database
+///
+/// A blob can be consumed either as a stream of chunks, or through `AsyncRead`.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless you poll them"]
+pub struct Blob {
+	rx: Receiver<Result<Vec<u8>>>,
+	/// The chunk which is partially read through `AsyncRead`
+	chunk: Vec<u8>,
+	pos: usize,
+}
+
+impl Stream for Blob {
+	type Item = Result<Vec<u8>>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		// Hand out what is left of a chunk which was partially read first
+		if self.pos < self.chunk.len() {
+			let pos = std::mem::take(&mut self.pos);
+			let mut chunk = std::mem::take(&mut self.chunk);
+			chunk.drain(..pos);
+			return Poll::Ready(Some(Ok(chunk)));
+		}
+		self.as_mut().rx.poll_next_unpin(cx)
+	}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl AsyncRead for Blob {
+	fn poll_read(
+		mut self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut ReadBuf<'_>,
+	) -> Poll<io::Result<()>> {
+		while self.pos == self.chunk.len() {
+			match ready!(self.rx.poll_next_unpin(cx)) {
+				Some(Ok(chunk)) => {
+					self.chunk = chunk;
+					self.pos = 0;
+				}
+				Some(Err(error)) => {
+					return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, error)));
+				}
+				None => return Poll::Ready(Ok(())),
+			}
+		}
+		let len = buf.remaining().min(self.chunk.len() - self.pos);
+		buf.put_slice(&self.chunk[self.pos..self.pos + len]);
+		self.pos += len;
+		Poll::Ready(Ok(()))
+	}
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn reads_across_chunks() {
+		let (tx, rx) = crate::channel::new(3);
+		tx.send(Ok(b"hello ".to_vec())).await.unwrap();
+		tx.send(Ok(Vec::new())).await.unwrap();
+		tx.send(Ok(b"world".to_vec())).await.unwrap();
+		drop(tx);
+		let mut blob = Blob {
+			rx,
+			chunk: Vec::new(),
+			pos: 0,
+		};
+		let mut head = [0; 4];
+		blob.read_exact(&mut head).await.unwrap();
+		assert_eq!(&head, b"hell");
+		// What is left of the chunk which was partially read comes first
+		assert_eq!(blob.next().await.unwrap().unwrap(), b"o ");
+		let mut rest = Vec::new();
+		blob.read_to_end(&mut rest).await.unwrap();
+		assert_eq!(rest, b"world");
+	}
+}
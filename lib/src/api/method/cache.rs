@@ -0,0 +1,300 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::opt::CacheConfig;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::opt::from_value;
+use crate::sql::Statement;
+use crate::sql::Table;
+use crate::sql::Value;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use trice::Instant;
+use uuid::Uuid;
+
+/// A cached `select` result
+#[derive(Debug)]
+struct Entry {
+	table: String,
+	value: Value,
+	cached_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct State {
+	config: Option<CacheConfig>,
+	/// The cached results, by the resource which was selected
+	entries: HashMap<String, Entry>,
+	/// The resources in the order in which they were cached, so the oldest are evicted first
+	order: VecDeque<String>,
+	/// The tables which are watched by a live query, by the ID of the live query
+	live: HashMap<Uuid, String>,
+	/// Incremented on every invalidation, so results which were fetched before an invalidation
+	/// are not cached after it
+	generation: u64,
+}
+
+impl State {
+	fn invalidate(&mut self, table: &str) {
+		self.entries.retain(|_, entry| entry.table != table);
+		self.order.retain(|key| self.entries.contains_key(key));
+		self.generation += 1;
+	}
+
+	fn clear(&mut self) {
+		self.entries.clear();
+		self.order.clear();
+		self.generation += 1;
+	}
+}
+
+/// The client-side cache of `select` results
+///
+/// Each cached table is watched by a live query, and its results are invalidated when the
+/// live query is notified of a change, or when the table is written through this client.
+/// The cache is disabled unless it was configured with [`Config::with_cache`](crate::opt::Config::with_cache).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Cache {
+	state: Arc<Mutex<State>>,
+}
+
+/// The table of a resource whose `select` results can be cached
+fn table(resource: &Value) -> Option<&str> {
+	match resource {
+		Value::Table(table) => Some(&table.0),
+		Value::Thing(thing) => Some(&thing.tb),
+		Value::Range(range) => Some(&range.tb),
+		_ => None,
+	}
+}
+
+impl Cache {
+	pub(crate) fn new(config: Option<CacheConfig>) -> Self {
+		Self {
+			state: Arc::new(Mutex::new(State {
+				config,
+				..Default::default()
+			})),
+		}
+	}
+
+	fn state(&self) -> MutexGuard<'_, State> {
+		self.state.lock().unwrap_or_else(|error| error.into_inner())
+	}
+
+	pub(crate) fn is_enabled(&self) -> bool {
+		self.state().config.is_some()
+	}
+
+	/// Selects a resource, from the cache if it holds a fresh result
+	pub(super) async fn select<C>(&self, router: &Router<C>, resource: Value) -> Result<Value>
+	where
+		C: Connection,
+	{
+		let key = resource.to_string();
+		let Some(table) = table(&resource).map(ToOwned::to_owned) else {
+			let mut conn = C::new(Method::Select);
+			return conn.execute_value(router, Param::new(vec![resource]).idempotent()).await;
+		};
+		let (watched, generation) = {
+			let mut state = self.state();
+			let ttl = state.config.and_then(|config| config.ttl);
+			match state.entries.get(&key) {
+				Some(entry) if ttl.map_or(true, |ttl| entry.cached_at.elapsed() < ttl) => {
+					return Ok(entry.value.clone());
+				}
+				Some(..) => {
+					state.entries.remove(&key);
+				}
+				None => {}
+			}
+			(state.live.values().any(|watched| *watched == table), state.generation)
+		};
+		// The table is watched before it is read, so no change is missed in between
+		let watched = watched || self.watch(router, &table).await;
+		let mut conn = C::new(Method::Select);
+		let value = conn.execute_value(router, Param::new(vec![resource]).idempotent()).await?;
+		let mut state = self.state();
+		if let (true, true, Some(config)) = (watched, state.generation == generation, state.config)
+		{
+			state.entries.insert(
+				key.clone(),
+				Entry {
+					table,
+					value: value.clone(),
+					cached_at: Instant::now(),
+				},
+			);
+			state.order.push_back(key);
+			while state.entries.len() > config.capacity {
+				match state.order.pop_front() {
+					Some(key) => state.entries.remove(&key),
+					None => break,
+				};
+			}
+		}
+		Ok(value)
+	}
+
+	/// Starts a live query on a table, returning whether its changes are now watched
+	async fn watch<C>(&self, router: &Router<C>, table: &str) -> bool
+	where
+		C: Connection,
+	{
+		let mut conn = C::new(Method::Live);
+		let param = Param::new(vec![Value::Table(Table(table.to_owned()))]);
+		let result: Result<crate::sql::Uuid> = conn.execute(router, param).await;
+		match result {
+			Ok(id) => {
+				self.state().live.insert(id.0, table.to_owned());
+				true
+			}
+			Err(error) => {
+				trace!("Not caching the `{table}` table, as it can't be watched; {error}");
+				false
+			}
+		}
+	}
+
+	/// Invalidates the results of the table which a live query watches
+	pub(crate) fn notify(&self, live: Uuid) {
+		let mut state = self.state();
+		match state.live.get(&live).cloned() {
+			Some(table) => state.invalidate(&table),
+			// The notification may be for a live query which is still being registered
+			None => state.generation += 1,
+		}
+	}
+
+	/// Forgets every result and live query, such as after the connection was re-established
+	/// or the session has changed
+	pub(crate) fn reset(&self) {
+		let mut state = self.state();
+		state.live.clear();
+		state.clear();
+	}
+
+	/// Invalidates the results which a request may change, before it is sent
+	pub(crate) fn sent(&self, method: Method, param: &Param) {
+		if !self.is_enabled() {
+			return;
+		}
+		match method {
+			Method::Select
+			| Method::Live
+			| Method::Kill
+			| Method::Health
+			| Method::Version
+			| Method::Export
+			| Method::GetBlob
+			| Method::PutBlob
+			| Method::Set
+			| Method::Unset => {}
+			Method::Use
+			| Method::Signin
+			| Method::Signup
+			| Method::Authenticate
+			| Method::Invalidate => self.reset(),
+			Method::Create | Method::Update | Method::Merge | Method::Patch | Method::Delete => {
+				let mut state = self.state();
+				match param.other.first().and_then(table) {
+					Some(table) => state.invalidate(table),
+					None => state.clear(),
+				}
+			}
+			Method::Query => {
+				let Some((query, _)) = &param.query else {
+					return;
+				};
+				if query.iter().any(|statement| matches!(statement, Statement::Use(..))) {
+					self.reset();
+				} else if query.iter().any(Statement::writeable) {
+					self.state().clear();
+				}
+			}
+			Method::Import => self.state().clear(),
+		}
+	}
+}
+
+/// Converts a cached value like [`Connection::execute_value`](crate::api::conn::Connection::execute_value)
+pub(super) fn execute_value(value: Value) -> Result<Value> {
+	Ok(value)
+}
+
+/// Converts a cached value like [`Connection::execute_opt`](crate::api::conn::Connection::execute_opt)
+pub(super) fn execute_opt<R>(value: Value) -> Result<Option<R>>
+where
+	R: DeserializeOwned + std::fmt::Debug,
+{
+	match value {
+		Value::None | Value::Null => Ok(None),
+		value => from_value(value).map_err(Into::into),
+	}
+}
+
+/// Converts a cached value like [`Connection::execute_vec`](crate::api::conn::Connection::execute_vec)
+pub(super) fn execute_vec<R>(value: Value) -> Result<Vec<R>>
+where
+	R: DeserializeOwned + std::fmt::Debug,
+{
+	let value = match value {
+		Value::None | Value::Null => Value::Array(Default::default()),
+		Value::Array(array) => Value::Array(array),
+		value => vec![value].into(),
+	};
+	from_value(value).map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::thing;
+
+	fn cached(cache: &Cache, resource: &str, table: &str) {
+		let mut state = cache.state();
+		state.entries.insert(
+			resource.to_owned(),
+			Entry {
+				table: table.to_owned(),
+				value: Value::None,
+				cached_at: Instant::now(),
+			},
+		);
+		state.order.push_back(resource.to_owned());
+	}
+
+	#[test]
+	fn writes_invalidate_their_table() {
+		let cache = Cache::new(Some(CacheConfig::new()));
+		cached(&cache, "person", "person");
+		cached(&cache, "person:tobie", "person");
+		cached(&cache, "user", "user");
+		let param = Param::new(vec![thing("person:jaime").unwrap().into()]);
+		cache.sent(Method::Update, &param);
+		assert_eq!(cache.state().entries.keys().collect::<Vec<_>>(), vec!["user"]);
+		cache.sent(Method::Select, &Param::new(vec![]));
+		assert_eq!(cache.state().entries.len(), 1);
+		let query = crate::sql::parse("CREATE post").unwrap();
+		cache.sent(Method::Query, &Param::query(query, Default::default()));
+		assert!(cache.state().entries.is_empty());
+	}
+
+	#[test]
+	fn notifications_invalidate_the_watched_table() {
+		let cache = Cache::new(Some(CacheConfig::new()));
+		let live = Uuid::new_v4();
+		cache.state().live.insert(live, "person".to_owned());
+		cached(&cache, "person:tobie", "person");
+		cached(&cache, "user", "user");
+		let generation = cache.state().generation;
+		cache.notify(live);
+		assert_eq!(cache.state().entries.keys().collect::<Vec<_>>(), vec!["user"]);
+		assert!(cache.state().generation > generation);
+	}
+}
@@ -0,0 +1,276 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::api::engine::runtime::sleep;
+use crate::api::err::Error;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::opt::from_value;
+use crate::sql::statements::show::ShowSince;
+use crate::sql::statements::show::ShowStatement;
+use crate::sql::Query;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Table;
+use crate::sql::Thing;
+use crate::sql::Value;
+use crate::sql::Versionstamp;
+use futures::stream;
+use futures::Stream;
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio::sleep;
+
+/// How long a change stream waits before checking for new changes
+pub(super) const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A single change made to a table
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Change<R> {
+	/// A record was created or updated
+	Update(R),
+	/// A record was deleted
+	Delete(Thing),
+	/// The table was defined
+	DefineTable {
+		/// The name of the table
+		name: String,
+	},
+}
+
+/// A set of changes committed at the same versionstamp
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeSet<R> {
+	/// The versionstamp the changes were committed at
+	///
+	/// Pass the [next](Versionstamp::next) versionstamp to [`Changes::since`] to only
+	/// read the changes made afterwards.
+	pub versionstamp: Versionstamp,
+	/// The changes which were made
+	pub changes: Vec<Change<R>>,
+}
+
+/// A change feed future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Changes<'r, C: Connection, R> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) table: String,
+	pub(super) since: Versionstamp,
+	pub(super) limit: Option<u32>,
+	pub(super) interval: Duration,
+	pub(super) response_type: PhantomData<R>,
+}
+
+impl<'r, C, R> Changes<'r, C, R>
+where
+	C: Connection,
+{
+	/// Only returns the changes made at or after this versionstamp
+	pub fn since(mut self, versionstamp: impl Into<Versionstamp>) -> Self {
+		self.since = versionstamp.into();
+		self
+	}
+
+	/// Sets the maximum number of changes read in a single request
+	pub fn limit(mut self, limit: u32) -> Self {
+		self.limit = Some(limit);
+		self
+	}
+
+	/// Sets how long a stream waits before checking for new changes
+	pub fn poll_interval(mut self, interval: Duration) -> Self {
+		self.interval = interval;
+		self
+	}
+}
+
+impl<'r, C, R> Changes<'r, C, R>
+where
+	C: Connection,
+	R: DeserializeOwned + Send + Sync + 'r,
+{
+	/// Follows the changes made to the table, waiting for new ones as they are committed
+	///
+	/// The stream never ends by itself. Errors are returned in the stream, after which
+	/// it continues to poll for changes from where it left off.
+	pub fn stream(self) -> ChangeStream<'r, R> {
+		let Changes {
+			router,
+			table,
+			since,
+			limit,
+			interval,
+			..
+		} = self;
+		let router = match router {
+			Ok(router) => router,
+			Err(error) => {
+				return ChangeStream {
+					inner: Box::pin(stream::once(async { Err(error) })),
+				};
+			}
+		};
+		let state = (since, VecDeque::new());
+		let inner = stream::unfold(state, move |(mut since, mut buffer)| {
+			let table = table.clone();
+			async move {
+				loop {
+					if let Some(set) = buffer.pop_front() {
+						return Some((Ok(set), (since, buffer)));
+					}
+					match read::<C, R>(router, table.clone(), since, limit).await {
+						Ok(sets) if sets.is_empty() => sleep(interval).await,
+						Ok(sets) => {
+							if let Some(last) = sets.last() {
+								since = last.versionstamp.next();
+							}
+							buffer.extend(sets);
+						}
+						Err(error) => {
+							sleep(interval).await;
+							return Some((Err(error), (since, buffer)));
+						}
+					}
+				}
+			}
+		});
+		ChangeStream {
+			inner: Box::pin(inner),
+		}
+	}
+}
+
+impl<'r, Client, R> IntoFuture for Changes<'r, Client, R>
+where
+	Client: Connection,
+	R: DeserializeOwned,
+{
+	type Output = Result<Vec<ChangeSet<R>>>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			read::<Client, R>(self.router?, self.table, self.since, self.limit).await
+		})
+	}
+}
+
+/// A stream of changes made to a table
+#[must_use = "streams do nothing unless you poll them"]
+pub struct ChangeStream<'r, R> {
+	inner: Pin<Box<dyn Stream<Item = Result<ChangeSet<R>>> + Send + Sync + 'r>>,
+}
+
+impl<R> std::fmt::Debug for ChangeStream<'_, R> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("ChangeStream").finish_non_exhaustive()
+	}
+}
+
+impl<R> Stream for ChangeStream<'_, R> {
+	type Item = Result<ChangeSet<R>>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.inner.poll_next_unpin(cx)
+	}
+}
+
+async fn read<C, R>(
+	router: &Router<C>,
+	table: String,
+	since: Versionstamp,
+	limit: Option<u32>,
+) -> Result<Vec<ChangeSet<R>>>
+where
+	C: Connection,
+	R: DeserializeOwned,
+{
+	let statement = ShowStatement {
+		table: Some(Table(table)),
+		since: ShowSince::Versionstamp(since.into()),
+		limit,
+	};
+	let query = Query(Statements(vec![Statement::Show(statement)]));
+	let mut conn = C::new(Method::Query);
+	let param = Param::query(query, Default::default()).idempotent();
+	let value: Value = conn.execute_query(router, param).await?.take(0)?;
+	match value {
+		Value::None | Value::Null => Ok(Vec::new()),
+		Value::Array(array) => array.into_iter().map(change_set).collect(),
+		value => Err(invalid(value).into()),
+	}
+}
+
+fn change_set<R>(value: Value) -> Result<ChangeSet<R>>
+where
+	R: DeserializeOwned,
+{
+	let Value::Object(mut object) = value else {
+		return Err(invalid(value).into());
+	};
+	let Some(versionstamp) = versionstamp(object.remove("versionstamp")) else {
+		return Err(invalid(Value::Object(object)).into());
+	};
+	let changes = match object.remove("changes") {
+		Some(Value::Array(changes)) => changes.into_iter().map(change).collect::<Result<_>>()?,
+		_ => return Err(invalid(Value::Object(object)).into()),
+	};
+	Ok(ChangeSet {
+		versionstamp,
+		changes,
+	})
+}
+
+/// Reads the versionstamp of a change feed entry
+pub(super) fn versionstamp(value: Option<Value>) -> Option<Versionstamp> {
+	match value {
+		// The versionstamp is returned with the two bytes used for ordering
+		// within a transaction appended to it
+		Some(Value::Number(number)) => Some(Versionstamp((number.to_int() as u64) >> 16)),
+		_ => None,
+	}
+}
+
+fn change<R>(value: Value) -> Result<Change<R>>
+where
+	R: DeserializeOwned,
+{
+	let Value::Object(mut object) = value else {
+		return Err(invalid(value).into());
+	};
+	if let Some(record) = object.remove("update") {
+		return Ok(Change::Update(from_value(record)?));
+	}
+	if let Some(Value::Object(mut record)) = object.remove("delete") {
+		if let Some(Value::Thing(id)) = record.remove("id") {
+			return Ok(Change::Delete(id));
+		}
+	}
+	if let Some(Value::Object(mut table)) = object.remove("define_table") {
+		if let Some(Value::Strand(name)) = table.remove("name") {
+			return Ok(Change::DefineTable {
+				name: name.0,
+			});
+		}
+	}
+	Err(invalid(Value::Object(object)).into())
+}
+
+pub(super) fn invalid(value: Value) -> Error {
+	Error::FromValue {
+		value,
+		error: "expected a change feed entry".to_owned(),
+	}
+}
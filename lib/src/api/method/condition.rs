@@ -0,0 +1,258 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::err::Error;
+use crate::api::method::query::Response;
+use crate::api::opt::write_diff;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::err::Error as DbError;
+use crate::sql;
+use crate::sql::Array;
+use crate::sql::Field;
+use crate::sql::Fields;
+use crate::sql::Idiom;
+use crate::sql::Output;
+use crate::sql::Part;
+use crate::sql::Value;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::mem;
+
+/// A `RETURN` clause which returns the specified fields
+///
+/// Nested fields are separated by dots, such as `name.first`.
+pub(super) fn return_fields(fields: impl IntoIterator<Item = impl Into<String>>) -> Output {
+	let fields = fields.into_iter().map(|field| {
+		let field = field.into();
+		Field::Single {
+			expr: Value::Idiom(Idiom(field.split('.').map(Part::from).collect())),
+			alias: None,
+		}
+	});
+	Output::Fields(Fields(fields.collect(), false))
+}
+
+/// A `RETURN` clause which returns the records before and after they were written
+fn return_records() -> Output {
+	let fields = ["before", "after"].into_iter().map(|name| Field::Single {
+		expr: Value::Param(name.into()),
+		alias: Some(Idiom(vec![Part::from(name)])),
+	});
+	Output::Fields(Fields(fields.collect(), false))
+}
+
+/// A condition which a record must meet before it is written
+#[derive(Debug, Clone)]
+pub(super) enum Condition {
+	/// The record must not exist yet
+	NotExists,
+	/// The checksum of the record must match
+	Matches(String),
+	/// The records must match the expression
+	Where(Value),
+	/// The records are always written, with the specified statement
+	///
+	/// This is used to write records with a custom `RETURN` clause.
+	Always(&'static str),
+	/// The write is run with its condition, but is never committed
+	DryRun(Box<Condition>),
+}
+
+impl Condition {
+	/// The unconditional write of a method, when it is run as a query
+	pub(super) fn always(method: Method) -> Self {
+		match method {
+			Method::Create => Condition::Always("CREATE"),
+			Method::Delete => Condition::Always("DELETE"),
+			_ => Condition::Always("UPDATE"),
+		}
+	}
+
+	/// Turns the write of a method into a dry run, keeping its condition
+	pub(super) fn dry_run(condition: Option<Condition>, method: Method) -> Self {
+		match condition {
+			Some(Condition::DryRun(condition)) => Condition::DryRun(condition),
+			Some(condition) => Condition::DryRun(Box::new(condition)),
+			None => Condition::DryRun(Box::new(Condition::always(method))),
+		}
+	}
+
+	/// Replaces the condition of a write, which stays a dry run if it is one
+	pub(super) fn replace(current: &mut Option<Condition>, condition: Condition) {
+		match current {
+			Some(Condition::DryRun(current)) => **current = condition,
+			current => *current = Some(condition),
+		}
+	}
+
+	/// Whether the records are only written if they weren't changed since they were checked
+	fn checked(&self) -> bool {
+		match self {
+			Condition::NotExists | Condition::Matches(_) => true,
+			Condition::DryRun(condition) => condition.checked(),
+			Condition::Where(_) | Condition::Always(_) => false,
+		}
+	}
+}
+
+/// Whether the error is returned for a record which was changed while it was written
+fn is_conflict(error: &crate::Error) -> bool {
+	match error {
+		crate::Error::Db(DbError::TxConditionNotMet | DbError::TxKeyAlreadyExists) => true,
+		// Remote engines only return the message of the error
+		crate::Error::Api(Error::Query(message)) => {
+			*message == DbError::TxConditionNotMet.to_string()
+				|| *message == DbError::TxKeyAlreadyExists.to_string()
+		}
+		_ => false,
+	}
+}
+
+impl Condition {
+	/// Writes the records, only when they meet the condition
+	///
+	/// The data, if any, is written with the specified clause, such as `CONTENT` or `MERGE`.
+	/// Records which must not exist, or must match a checksum, are written with a
+	/// compare-and-swap against the record which was checked, so a record which is changed
+	/// between the check and the write is treated as not meeting the condition. The written
+	/// records are returned, unless another `RETURN` clause is specified.
+	async fn execute<Client>(
+		self,
+		router: &Router<Client>,
+		what: Value,
+		data: Option<(&str, Value)>,
+		output: Option<Output>,
+	) -> Result<Response>
+	where
+		Client: Connection,
+	{
+		let mut bindings = BTreeMap::new();
+		let clause = match data {
+			Some((clause, data)) => {
+				bindings.insert("data".to_owned(), data);
+				format!(" {clause} $data")
+			}
+			None => String::new(),
+		};
+		// Diffs are computed from the records before and after the write, so they can be reverted
+		let diff = matches!(output, Some(Output::Diff));
+		let output = match output {
+			Some(Output::Diff) => return_records(),
+			output => output.unwrap_or(Output::After),
+		};
+		let checked = self.checked();
+		let query = self.statement(&what, &clause, &output, &mut bindings)?;
+		bindings.insert("what".to_owned(), what);
+		let mut conn = Client::new(Method::Query);
+		let mut response =
+			conn.execute_query(router, Param::query(sql::parse(&query)?, bindings)).await?;
+		if let (true, Some(result)) = (checked, response.0.get_mut(&0)) {
+			if matches!(result, Err(error) if is_conflict(error)) {
+				*result = Ok(Vec::new());
+			}
+		}
+		if let (true, Some(Ok(records))) = (diff, response.0.get_mut(&0)) {
+			for record in records.iter_mut() {
+				*record = write_diff(mem::take(record))?;
+			}
+		}
+		Ok(response)
+	}
+
+	/// The statement which writes the records in `$what`, when they meet the condition
+	fn statement(
+		self,
+		what: &Value,
+		clause: &str,
+		output: &Output,
+		bindings: &mut BTreeMap<String, Value>,
+	) -> Result<String> {
+		let statement = match self {
+			Condition::NotExists => {
+				if !what.is_thing() {
+					return Err(Error::InvalidRecordId(what.to_string()).into());
+				}
+				format!("OPTION CAS; IF (SELECT VALUE id FROM $what) THEN [] ELSE (CREATE $what{clause} {output}) END")
+			}
+			Condition::Matches(checksum) => {
+				bindings.insert("checksum".to_owned(), checksum.into());
+				format!("OPTION CAS; UPDATE $what{clause} WHERE $this != NONE AND meta::checksum($this) = $checksum {output}")
+			}
+			Condition::Where(cond) => format!("UPDATE $what{clause} WHERE {cond} {output}"),
+			Condition::Always(statement) => format!("{statement} $what{clause} {output}"),
+			Condition::DryRun(condition) => {
+				let statement = condition.statement(what, clause, output, bindings)?;
+				format!("OPTION DRY_RUN; {statement}")
+			}
+		};
+		Ok(statement)
+	}
+
+	/// Writes the records, returning the result as a value
+	pub(super) async fn execute_value<Client>(
+		self,
+		router: &Router<Client>,
+		what: Value,
+		data: Option<(&str, Value)>,
+		output: Option<Output>,
+	) -> Result<Value>
+	where
+		Client: Connection,
+	{
+		let one = what.is_thing();
+		let value: Value = self.execute(router, what, data, output).await?.take(0)?;
+		match value {
+			Value::Array(Array(mut vec)) if one && vec.len() <= 1 => {
+				Ok(vec.pop().unwrap_or_default())
+			}
+			value => Ok(value),
+		}
+	}
+
+	/// Writes a record, returning the result if the record was written
+	pub(super) async fn execute_opt<Client, R>(
+		self,
+		router: &Router<Client>,
+		what: Value,
+		data: Option<(&str, Value)>,
+		output: Option<Output>,
+	) -> Result<Option<R>>
+	where
+		Client: Connection,
+		R: DeserializeOwned + std::fmt::Debug,
+	{
+		self.execute(router, what, data, output).await?.take(0)
+	}
+
+	/// Writes the records, returning the records which were written
+	pub(super) async fn execute_vec<Client, R>(
+		self,
+		router: &Router<Client>,
+		what: Value,
+		data: Option<(&str, Value)>,
+		output: Option<Output>,
+	) -> Result<Vec<R>>
+	where
+		Client: Connection,
+		R: DeserializeOwned + std::fmt::Debug,
+	{
+		self.execute(router, what, data, output).await?.take(0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn nested_return_fields() {
+		let output = return_fields(["id", "name.first"]);
+		assert_eq!(output.to_string(), "RETURN id, name.first");
+	}
+
+	#[test]
+	fn return_records_for_diffs() {
+		assert_eq!(return_records().to_string(), "RETURN $before AS before, $after AS after");
+	}
+}
@@ -1,12 +1,15 @@
 use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
+use crate::api::method::condition::Condition;
+use crate::api::method::create::HashId;
 use crate::api::opt::Range;
 use crate::api::opt::Resource;
 use crate::api::Connection;
 use crate::api::Result;
 use crate::sql::to_value;
 use crate::sql::Id;
+use crate::sql::Output;
 use crate::sql::Value;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -25,10 +28,38 @@ pub struct Content<'r, C: Connection, D, R> {
 	pub(super) method: Method,
 	pub(super) resource: Result<Resource>,
 	pub(super) range: Option<Range<Id>>,
+	pub(super) condition: Option<Condition>,
+	pub(super) output: Option<Output>,
 	pub(super) content: D,
+	pub(super) validate: bool,
+	pub(super) hash_id: Option<HashId>,
 	pub(super) response_type: PhantomData<R>,
 }
 
+impl<'r, C, D, R> Content<'r, C, D, R>
+where
+	C: Connection,
+{
+	/// Checks the content against the schema of the table before it is sent
+	///
+	/// The field definitions of the table are fetched once and cached for the connection.
+	/// Fields which are not defined on a `SCHEMAFULL` table, or which don't match the type
+	/// of their definition, are listed in the error which is returned.
+	pub fn validate(mut self) -> Self {
+		self.validate = true;
+		self
+	}
+
+	/// Runs the write without applying it, returning the changes it would have made
+	///
+	/// See [`Update::dry_run`](crate::method::Update::dry_run).
+	pub fn dry_run(mut self) -> Self {
+		self.condition = Some(Condition::dry_run(self.condition.take(), self.method));
+		self.output = Some(Output::Diff);
+		self
+	}
+}
+
 macro_rules! into_future {
 	($method:ident) => {
 		fn into_future(self) -> Self::IntoFuture {
@@ -37,19 +68,39 @@ macro_rules! into_future {
 				method,
 				resource,
 				range,
+				condition,
+				output,
 				content,
+				validate,
+				hash_id,
 				..
 			} = self;
 			let content = to_value(content);
 			Box::pin(async move {
+				let content = match content? {
+					Value::None | Value::Null => None,
+					content => Some(content),
+				};
+				let resource = match hash_id {
+					Some(hash_id) => hash_id.apply(resource?, content.as_ref()),
+					None => resource,
+				};
 				let param = match range {
 					Some(range) => resource?.with_range(range)?,
 					None => resource?.into(),
 				};
+				if let (true, Some(content), Ok(router)) = (validate, &content, &router) {
+					router.schemas.check(*router, &param, content).await?;
+				}
+				if condition.is_some() || output.is_some() {
+					let condition = condition.unwrap_or(Condition::always(method));
+					let data = content.map(|content| ("CONTENT", content));
+					return condition.$method(router?, param, data, output).await;
+				}
 				let mut conn = Client::new(method);
-				let params = match content? {
-					Value::None | Value::Null => vec![param],
-					content => vec![param, content],
+				let params = match content {
+					None => vec![param],
+					Some(content) => vec![param, content],
 				};
 				conn.$method(router?, Param::new(params)).await
 			})
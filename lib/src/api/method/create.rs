@@ -1,13 +1,25 @@
 use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
+use crate::api::err::Error;
+use crate::api::method::condition;
+use crate::api::method::condition::Condition;
 use crate::api::method::Content;
 use crate::api::opt::Resource;
 use crate::api::Connection;
 use crate::api::Result;
+use crate::sql::Id;
+use crate::sql::Idiom;
+use crate::sql::Object;
+use crate::sql::Output;
+use crate::sql::Part;
+use crate::sql::Table;
+use crate::sql::Thing;
 use crate::sql::Value;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
@@ -19,18 +31,70 @@ use std::pin::Pin;
 pub struct Create<'r, C: Connection, R> {
 	pub(super) router: Result<&'r Router<C>>,
 	pub(super) resource: Result<Resource>,
+	pub(super) condition: Option<Condition>,
+	pub(super) output: Option<Output>,
+	pub(super) hash_id: Option<HashId>,
 	pub(super) response_type: PhantomData<R>,
 }
 
+/// The content from which the ID of a created record is derived
+#[derive(Debug, Clone)]
+pub(super) enum HashId {
+	/// The whole content of the record, except for its `id` field
+	Content,
+	/// Only the selected fields of the record
+	Fields(Vec<Idiom>),
+}
+
+impl HashId {
+	/// Replaces a table with a record in it, whose ID is a hash of the content
+	///
+	/// Objects are hashed in the order of their keys, so the ID doesn't depend on the order
+	/// in which the fields of the content were serialized.
+	pub(super) fn apply(&self, resource: Resource, content: Option<&Value>) -> Result<Resource> {
+		let Resource::Table(Table(table)) = resource else {
+			return Err(Error::HashIdOnRecord(Value::from(resource).to_string()).into());
+		};
+		let content = content.cloned().unwrap_or_default();
+		let hashed = match self {
+			Self::Content => match content {
+				Value::Object(mut object) => {
+					object.remove("id");
+					Value::Object(object)
+				}
+				content => content,
+			},
+			Self::Fields(fields) => Value::Object(Object(
+				fields.iter().map(|field| (field.to_string(), content.pick(field))).collect(),
+			)),
+		};
+		let mut hasher = Sha256::new();
+		hasher.update(hashed.to_string());
+		let id = Id::String(format!("{:x}", hasher.finalize()));
+		Ok(Resource::RecordId(Thing::from((table, id))))
+	}
+}
+
 macro_rules! into_future {
 	($method:ident) => {
 		fn into_future(self) -> Self::IntoFuture {
 			let Create {
 				router,
 				resource,
+				condition,
+				output,
+				hash_id,
 				..
 			} = self;
 			Box::pin(async {
+				let resource = match hash_id {
+					Some(hash_id) => hash_id.apply(resource?, None),
+					None => resource,
+				};
+				if condition.is_some() || output.is_some() {
+					let condition = condition.unwrap_or(Condition::always(Method::Create));
+					return condition.$method(router?, resource?.into(), None, output).await;
+				}
 				let mut conn = Client::new(Method::Create);
 				conn.$method(router?, Param::new(vec![resource?.into()])).await
 			})
@@ -74,6 +138,148 @@ impl<'r, C, R> Create<'r, C, R>
 where
 	C: Connection,
 {
+	/// Only creates the record if it doesn't exist yet
+	///
+	/// If the record already exists, it is left untouched and nothing is returned. The record
+	/// is written with a compare-and-swap which only succeeds while the key is empty, so a
+	/// record which is created by someone else after the check is never overwritten, and
+	/// nothing is returned in that case either.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use serde::Serialize;
+	///
+	/// #[derive(Serialize)]
+	/// struct Lock {
+	///     owner: String,
+	/// }
+	///
+	/// # #[derive(serde::Deserialize)]
+	/// # struct Record;
+	/// #
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// let lock: Option<Record> = db
+	///     .create(("lock", "jobs"))
+	///     .if_not_exists()
+	///     .content(Lock {
+	///         owner: "worker-1".to_owned(),
+	///     })
+	///     .await?;
+	///
+	/// if lock.is_none() {
+	///     // Another worker already holds the lock
+	/// }
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn if_not_exists(mut self) -> Self {
+		Condition::replace(&mut self.condition, Condition::NotExists);
+		self
+	}
+
+	/// Derives the ID of the record from a hash of its content
+	///
+	/// The record is created in the table which was passed to [`Surreal::create`], with an ID
+	/// which is the SHA-256 hash of its content, apart from any `id` field. Creating the same
+	/// content again resolves to the same record, so together with [`Create::if_not_exists`],
+	/// inserts can safely be retried.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use serde::Serialize;
+	///
+	/// #[derive(Serialize)]
+	/// struct Event {
+	///     source: String,
+	///     sequence: u64,
+	/// }
+	///
+	/// # #[derive(serde::Deserialize)]
+	/// # struct Record;
+	/// #
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// let event: Vec<Record> = db
+	///     .create("event")
+	///     .id_from_hash()
+	///     .if_not_exists()
+	///     .content(Event {
+	///         source: "sensor-1".to_owned(),
+	///         sequence: 42,
+	///     })
+	///     .await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	///
+	/// [`Surreal::create`]: crate::Surreal::create
+	pub fn id_from_hash(mut self) -> Self {
+		self.hash_id = Some(HashId::Content);
+		self
+	}
+
+	/// Derives the ID of the record from a hash of the specified fields of its content
+	///
+	/// This works like [`Create::id_from_hash`], but only the specified fields are hashed, so
+	/// records with the same values for these fields resolve to the same record. Nested fields
+	/// are separated by dots, such as `name.first`.
+	pub fn id_from_fields(mut self, fields: impl IntoIterator<Item = impl Into<String>>) -> Self {
+		let fields = fields.into_iter().map(|field| {
+			let field = field.into();
+			Idiom(field.split('.').map(Part::from).collect())
+		});
+		self.hash_id = Some(HashId::Fields(fields.collect()));
+		self
+	}
+
+	/// Returns the changes which were made to the records, as JSON Patch operations
+	///
+	/// See [`Update::return_diff`](crate::method::Update::return_diff).
+	pub fn return_diff(mut self) -> Self {
+		self.output = Some(Output::Diff);
+		self
+	}
+
+	/// Runs the write without applying it, returning the changes it would have made
+	///
+	/// See [`Update::dry_run`](crate::method::Update::dry_run).
+	pub fn dry_run(mut self) -> Self {
+		self.condition = Some(Condition::dry_run(self.condition.take(), Method::Create));
+		self.output = Some(Output::Diff);
+		self
+	}
+
+	/// Returns nothing, so the created records aren't sent back
+	pub fn return_none(mut self) -> Self {
+		self.output = Some(Output::None);
+		self
+	}
+
+	/// Returns the records as they were before they were created, which is nothing
+	///
+	/// This is mostly useful together with [`Create::if_not_exists`].
+	pub fn return_before(mut self) -> Self {
+		self.output = Some(Output::Before);
+		self
+	}
+
+	/// Returns only the specified fields of the created records
+	///
+	/// Nested fields are separated by dots, such as `name.first`.
+	pub fn return_fields(mut self, fields: impl IntoIterator<Item = impl Into<String>>) -> Self {
+		self.output = Some(condition::return_fields(fields));
+		self
+	}
+
 	/// Sets content of a record
 	pub fn content<D>(self, data: D) -> Content<'r, C, D, R>
 	where
@@ -84,7 +290,11 @@ where
 			method: Method::Create,
 			resource: self.resource,
 			range: None,
+			condition: self.condition,
+			output: self.output,
 			content: data,
+			validate: false,
+			hash_id: self.hash_id,
 			response_type: PhantomData,
 		}
 	}
@@ -0,0 +1,321 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::err::Error;
+use crate::api::expr::idiom;
+use crate::api::opt::IntoQuery;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::sql::index::SearchParams;
+use crate::sql::statements::DefineAnalyzerStatement;
+use crate::sql::statements::DefineEventStatement;
+use crate::sql::statements::DefineFieldStatement;
+use crate::sql::statements::DefineIndexStatement;
+use crate::sql::statements::DefineStatement;
+use crate::sql::Filter;
+use crate::sql::Ident;
+use crate::sql::Idioms;
+use crate::sql::Index;
+use crate::sql::Kind;
+use crate::sql::Query;
+use crate::sql::Scoring;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Strand;
+use crate::sql::Subquery;
+use crate::sql::Tokenizer;
+use crate::sql::Value;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::pin::Pin;
+
+/// An index definition future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct DefineIndex<'r, C: Connection> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) statement: DefineIndexStatement,
+}
+
+impl<'r, C> DefineIndex<'r, C>
+where
+	C: Connection,
+{
+	/// Sets the name of the index
+	pub fn name(mut self, name: impl Into<String>) -> Self {
+		self.statement.name = Ident(name.into());
+		self
+	}
+
+	/// Sets the fields which are indexed, with nested fields separated by dots
+	///
+	/// An index on more than one field is a composite index, which indexes the values
+	/// of the fields together. To index a computed value, define a field which computes
+	/// it with [`DefineField::value`], and index that field.
+	pub fn columns(mut self, columns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+		self.statement.cols = Idioms(columns.into_iter().map(|v| idiom(&v.into())).collect());
+		self
+	}
+
+	/// Only allows a single record for each of the values which are indexed
+	pub fn unique(mut self) -> Self {
+		self.statement.index = Index::Uniq;
+		self
+	}
+
+	/// Indexes the text of the fields for full-text search, with the BM25 scoring
+	pub fn search(mut self, analyzer: impl Into<String>) -> Self {
+		self.statement.index = Index::Search(SearchParams {
+			az: Ident(analyzer.into()),
+			hl: false,
+			sc: Scoring::bm25(),
+			doc_ids_order: 100,
+			doc_lengths_order: 100,
+			postings_order: 100,
+			terms_order: 100,
+		});
+		self
+	}
+
+	/// Sets a comment describing the index
+	pub fn comment(mut self, comment: impl Into<String>) -> Self {
+		self.statement.comment = Some(Strand(comment.into()));
+		self
+	}
+}
+
+impl<'r, Client> IntoFuture for DefineIndex<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<()>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			if self.statement.name.is_empty() {
+				return Err(Error::InvalidParams("an index needs a name".to_owned()).into());
+			}
+			define(self.router?, DefineStatement::Index(self.statement)).await
+		})
+	}
+}
+
+/// A field definition future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct DefineField<'r, C: Connection> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) statement: DefineFieldStatement,
+}
+
+impl<'r, C> DefineField<'r, C>
+where
+	C: Connection,
+{
+	/// Sets the path of the field, with nested fields separated by dots
+	pub fn name(mut self, path: impl Into<String>) -> Self {
+		self.statement.name = idiom(&path.into());
+		self
+	}
+
+	/// Sets the kind of value which the field accepts
+	pub fn kind(mut self, kind: Kind) -> Self {
+		self.statement.kind = Some(kind);
+		self
+	}
+
+	/// Allows the fields of an object in a schemafull table not to be defined
+	pub fn flexible(mut self) -> Self {
+		self.statement.flex = true;
+		self
+	}
+
+	/// Computes the value of the field whenever a record is written
+	pub fn value(mut self, value: impl Into<Value>) -> Self {
+		self.statement.value = Some(value.into());
+		self
+	}
+
+	/// Sets the value of the field when a record is written without it
+	pub fn default(mut self, value: impl Into<Value>) -> Self {
+		self.statement.default = Some(value.into());
+		self
+	}
+
+	/// Only allows values for which the condition is true
+	pub fn assert(mut self, condition: impl Into<Value>) -> Self {
+		self.statement.assert = Some(condition.into());
+		self
+	}
+
+	/// Sets a comment describing the field
+	pub fn comment(mut self, comment: impl Into<String>) -> Self {
+		self.statement.comment = Some(Strand(comment.into()));
+		self
+	}
+}
+
+impl<'r, Client> IntoFuture for DefineField<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<()>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			if self.statement.name.is_empty() {
+				return Err(Error::InvalidParams("a field needs a name".to_owned()).into());
+			}
+			define(self.router?, DefineStatement::Field(self.statement)).await
+		})
+	}
+}
+
+/// An event definition future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct DefineEvent<'r, C: Connection> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) statement: Result<DefineEventStatement>,
+}
+
+impl<'r, C> DefineEvent<'r, C>
+where
+	C: Connection,
+{
+	/// Sets the name of the event
+	pub fn name(mut self, name: impl Into<String>) -> Self {
+		if let Ok(statement) = &mut self.statement {
+			statement.name = Ident(name.into());
+		}
+		self
+	}
+
+	/// Only runs the event when the condition is true
+	///
+	/// By default, the event runs whenever a record is created, updated or deleted.
+	pub fn when(mut self, condition: impl Into<Value>) -> Self {
+		if let Ok(statement) = &mut self.statement {
+			statement.when = condition.into();
+		}
+		self
+	}
+
+	/// Adds statements which run when the event is triggered
+	pub fn then(mut self, query: impl IntoQuery) -> Self {
+		self.statement = self.statement.and_then(|mut statement| {
+			for v in query.into_query()? {
+				statement.then.0.push(subquery(v)?);
+			}
+			Ok(statement)
+		});
+		self
+	}
+
+	/// Sets a comment describing the event
+	pub fn comment(mut self, comment: impl Into<String>) -> Self {
+		if let Ok(statement) = &mut self.statement {
+			statement.comment = Some(Strand(comment.into()));
+		}
+		self
+	}
+}
+
+impl<'r, Client> IntoFuture for DefineEvent<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<()>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let statement = self.statement?;
+			if statement.name.is_empty() {
+				return Err(Error::InvalidParams("an event needs a name".to_owned()).into());
+			}
+			if statement.then.is_empty() {
+				return Err(Error::InvalidParams("an event needs a statement".to_owned()).into());
+			}
+			define(self.router?, DefineStatement::Event(statement)).await
+		})
+	}
+}
+
+/// An analyzer definition future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct DefineAnalyzer<'r, C: Connection> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) statement: DefineAnalyzerStatement,
+}
+
+impl<'r, C> DefineAnalyzer<'r, C>
+where
+	C: Connection,
+{
+	/// Sets how text is split into tokens
+	pub fn tokenizers(mut self, tokenizers: impl IntoIterator<Item = Tokenizer>) -> Self {
+		self.statement.tokenizers = Some(tokenizers.into_iter().collect());
+		self
+	}
+
+	/// Sets how the tokens are transformed, in order
+	pub fn filters(mut self, filters: impl IntoIterator<Item = Filter>) -> Self {
+		self.statement.filters = Some(filters.into_iter().collect());
+		self
+	}
+
+	/// Sets a comment describing the analyzer
+	pub fn comment(mut self, comment: impl Into<String>) -> Self {
+		self.statement.comment = Some(Strand(comment.into()));
+		self
+	}
+}
+
+impl<'r, Client> IntoFuture for DefineAnalyzer<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<()>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let statement = DefineStatement::Analyzer(self.statement);
+			define(self.router?, statement).await
+		})
+	}
+}
+
+async fn define<C>(router: &Router<C>, statement: DefineStatement) -> Result<()>
+where
+	C: Connection,
+{
+	let query = Query(Statements(vec![Statement::Define(statement)]));
+	let mut conn = C::new(Method::Query);
+	let param = Param::query(query, Default::default());
+	conn.execute_query(router, param).await?.check()?;
+	Ok(())
+}
+
+/// Converts a statement into a value which can be run by an event
+fn subquery(statement: Statement) -> Result<Value> {
+	let subquery = match statement {
+		Statement::Value(v) => return Ok(v),
+		Statement::Ifelse(v) => Subquery::Ifelse(v),
+		Statement::Output(v) => Subquery::Output(v),
+		Statement::Select(v) => Subquery::Select(v),
+		Statement::Create(v) => Subquery::Create(v),
+		Statement::Update(v) => Subquery::Update(v),
+		Statement::Delete(v) => Subquery::Delete(v),
+		Statement::Relate(v) => Subquery::Relate(v),
+		Statement::Insert(v) => Subquery::Insert(v),
+		Statement::Define(v) => Subquery::Define(v),
+		Statement::Remove(v) => Subquery::Remove(v),
+		v => return Err(Error::InvalidParams(format!("`{v}` can't be run by an event")).into()),
+	};
+	Ok(Value::Subquery(Box::new(subquery)))
+}
@@ -1,13 +1,33 @@
 use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
+use crate::api::method::condition;
+use crate::api::method::condition::Condition;
+use crate::api::method::query;
+use crate::api::opt::IntoCondition;
 use crate::api::opt::Range;
 use crate::api::opt::Resource;
 use crate::api::Connection;
 use crate::api::Result;
+use crate::sql::statements::DeleteStatement;
+use crate::sql::statements::OutputStatement;
+use crate::sql::Cond;
+use crate::sql::Field;
+use crate::sql::Fields;
+use crate::sql::Function;
 use crate::sql::Id;
+use crate::sql::Idiom;
+use crate::sql::Output;
+use crate::sql::Part;
+use crate::sql::Query;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Subquery;
 use crate::sql::Value;
+use crate::sql::Values;
 use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::marker::PhantomData;
@@ -20,6 +40,8 @@ pub struct Delete<'r, C: Connection, R> {
 	pub(super) router: Result<&'r Router<C>>,
 	pub(super) resource: Result<Resource>,
 	pub(super) range: Option<Range<Id>>,
+	pub(super) condition: Option<Condition>,
+	pub(super) output: Option<Output>,
 	pub(super) response_type: PhantomData<R>,
 }
 
@@ -30,13 +52,19 @@ macro_rules! into_future {
 				router,
 				resource,
 				range,
+				condition,
+				output,
 				..
 			} = self;
-			Box::pin(async {
+			Box::pin(async move {
 				let param = match range {
 					Some(range) => resource?.with_range(range)?,
 					None => resource?.into(),
 				};
+				if condition.is_some() || output.is_some() {
+					let condition = condition.unwrap_or(Condition::always(Method::Delete));
+					return condition.$method(router?, param, None, output).await;
+				}
 				let mut conn = Client::new(Method::Delete);
 				conn.$method(router?, Param::new(vec![param])).await
 			})
@@ -97,3 +125,169 @@ where
 		self
 	}
 }
+
+impl<C, R> Delete<'_, C, R>
+where
+	C: Connection,
+{
+	/// Returns the changes which were made to the records, as JSON Patch operations
+	///
+	/// See [`Update::return_diff`](crate::method::Update::return_diff).
+	pub fn return_diff(mut self) -> Self {
+		self.output = Some(Output::Diff);
+		self
+	}
+
+	/// Runs the write without applying it, returning the changes it would have made
+	///
+	/// See [`Update::dry_run`](crate::method::Update::dry_run).
+	pub fn dry_run(mut self) -> Self {
+		self.condition = Some(Condition::dry_run(self.condition.take(), Method::Delete));
+		self.output = Some(Output::Diff);
+		self
+	}
+
+	/// Returns nothing, so the deleted records aren't sent back
+	pub fn return_none(mut self) -> Self {
+		self.output = Some(Output::None);
+		self
+	}
+
+	/// Returns the records as they were before they were deleted
+	pub fn return_before(mut self) -> Self {
+		self.output = Some(Output::Before);
+		self
+	}
+
+	/// Returns only the specified fields of the deleted records
+	///
+	/// Nested fields are separated by dots, such as `name.first`.
+	pub fn return_fields(mut self, fields: impl IntoIterator<Item = impl Into<String>>) -> Self {
+		self.output = Some(condition::return_fields(fields));
+		self
+	}
+}
+
+impl<'r, C> Delete<'r, C, Value>
+where
+	C: Connection,
+{
+	/// Only deletes the records which match the condition, returning how many were deleted
+	pub fn filter(self, condition: impl IntoCondition) -> DeleteWhere<'r, C> {
+		DeleteWhere {
+			router: self.router,
+			resource: self.resource,
+			range: self.range,
+			condition: condition.into_condition(),
+			bindings: Ok(BTreeMap::new()),
+		}
+	}
+}
+
+impl<'r, C> Delete<'r, C, Vec<Value>>
+where
+	C: Connection,
+{
+	/// Only deletes the records which match the condition, returning how many were deleted
+	///
+	/// The condition can be a SurrealQL expression, or an expression from the
+	/// [`builder`](crate::builder) module. Parameters in the condition are bound with
+	/// [`DeleteWhere::bind`].
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::expr::field;
+	/// use surrealdb::sql::Datetime;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// # let cutoff = Datetime::default();
+	/// #
+	/// let deleted = db.delete("logs").filter("created < $cutoff").bind(("cutoff", cutoff)).await?;
+	///
+	/// let deleted = db.delete("logs").filter(field("level").eq("debug")).await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn filter(self, condition: impl IntoCondition) -> DeleteWhere<'r, C> {
+		DeleteWhere {
+			router: self.router,
+			resource: self.resource,
+			range: self.range,
+			condition: condition.into_condition(),
+			bindings: Ok(BTreeMap::new()),
+		}
+	}
+}
+
+/// A future which deletes the records matching a condition
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct DeleteWhere<'r, C: Connection> {
+	router: Result<&'r Router<C>>,
+	resource: Result<Resource>,
+	range: Option<Range<Id>>,
+	condition: Result<Value>,
+	bindings: Result<BTreeMap<String, Value>>,
+}
+
+impl<C> DeleteWhere<'_, C>
+where
+	C: Connection,
+{
+	/// Binds a parameter or parameters to the condition
+	///
+	/// The bindings are either a `(key, value)` tuple, or a struct or map of values, as with
+	/// [`Query::bind`](crate::method::Query::bind).
+	pub fn bind(mut self, bindings: impl Serialize) -> Self {
+		query::bind(&mut self.bindings, bindings);
+		self
+	}
+}
+
+impl<'r, Client> IntoFuture for DeleteWhere<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<u64>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let router = self.router?;
+			let what = match self.range {
+				Some(range) => self.resource?.with_range(range)?,
+				None => self.resource?.into(),
+			};
+			// Only the IDs of the deleted records are counted, so the records aren't sent back
+			let delete = DeleteStatement {
+				what: Values(vec![what]),
+				cond: Some(Cond(self.condition?)),
+				output: Some(Output::Fields(Fields(
+					vec![Field::Single {
+						expr: Value::Idiom(Idiom(vec![Part::from("id")])),
+						alias: None,
+					}],
+					true,
+				))),
+				..Default::default()
+			};
+			let count = Function::Normal(
+				"array::len".to_owned(),
+				vec![Value::Subquery(Box::new(Subquery::Delete(delete)))],
+			);
+			let statement = Statement::Output(OutputStatement {
+				what: count.into(),
+				fetch: None,
+			});
+			let query = Query(Statements(vec![statement]));
+			let mut conn = Client::new(Method::Query);
+			let param = Param::query(query, self.bindings?);
+			let count: Option<u64> = conn.execute_query(router, param).await?.take(0)?;
+			Ok(count.unwrap_or_default())
+		})
+	}
+}
@@ -0,0 +1,188 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::expr::idiom;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::sql::statements::SelectStatement;
+use crate::sql::Explain;
+use crate::sql::Field;
+use crate::sql::Fields;
+use crate::sql::Function;
+use crate::sql::Groups;
+use crate::sql::Idiom;
+use crate::sql::Part;
+use crate::sql::Query;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Table;
+use crate::sql::Value;
+use crate::sql::Values;
+use serde::Deserialize;
+use serde::Serialize;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::pin::Pin;
+
+/// The estimated cost of a query
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Cost {
+	/// The approximate number of records which the query reads
+	pub rows: u64,
+	/// The indexes which the query planner uses
+	pub indexes: Vec<String>,
+	/// The tables which are scanned in full, because no index matches the condition
+	pub table_scans: Vec<String>,
+}
+
+impl Cost {
+	/// Checks whether any table is scanned in full
+	pub fn requires_table_scan(&self) -> bool {
+		!self.table_scans.is_empty()
+	}
+}
+
+/// A query cost estimation future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Estimate<'r, C: Connection> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) query: Result<Vec<Statement>>,
+}
+
+/// A `SELECT` statement which reads the same records as a statement, if it reads any
+fn probe(statement: Statement) -> Option<SelectStatement> {
+	let probe = match statement {
+		Statement::Select(stm) => stm,
+		Statement::Update(stm) => SelectStatement {
+			expr: Fields(vec![Field::All], false),
+			what: stm.what,
+			cond: stm.cond,
+			..Default::default()
+		},
+		Statement::Delete(stm) => SelectStatement {
+			expr: Fields(vec![Field::All], false),
+			what: stm.what,
+			cond: stm.cond,
+			..Default::default()
+		},
+		_ => return None,
+	};
+	Some(probe)
+}
+
+/// A statement which counts the records of a probe, without fetching them
+fn count(mut probe: SelectStatement) -> Statement {
+	probe.expr = Fields(
+		vec![Field::Single {
+			expr: Function::Normal("count".to_owned(), vec![]).into(),
+			alias: Some(Idiom(vec![Part::from("value")])),
+		}],
+		false,
+	);
+	probe.omit = None;
+	probe.only = false;
+	probe.group = Some(Groups(Vec::new()));
+	probe.order = None;
+	probe.split = None;
+	probe.start = None;
+	probe.limit = None;
+	probe.fetch = None;
+	probe.explain = None;
+	Statement::Select(probe)
+}
+
+impl<'r, Client> IntoFuture for Estimate<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<Cost>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let router = self.router?;
+			let probes: Vec<_> = self.query?.into_iter().filter_map(probe).collect();
+			let mut cost = Cost::default();
+			if probes.is_empty() {
+				return Ok(cost);
+			}
+			// Ask the query planner how each statement iterates, without running it
+			let statements = probes
+				.iter()
+				.map(|probe| {
+					Statement::Select(SelectStatement {
+						explain: Some(Explain(false)),
+						..probe.clone()
+					})
+				})
+				.collect();
+			let query = Query(Statements(statements));
+			let mut conn = Client::new(Method::Query);
+			let param = Param::query(query, Default::default()).idempotent();
+			let mut response = conn.execute_query(router, param).await?;
+			// Scanned tables are counted once, and indexed statements are counted by their condition
+			let mut tables = Vec::new();
+			let mut counts = Vec::new();
+			for (index, probe) in probes.into_iter().enumerate() {
+				let items: Vec<Value> = response.take(index)?;
+				let mut indexed = false;
+				let mut scanned = false;
+				for item in items {
+					let table = item.pick(&idiom("detail.table")).as_raw_string();
+					match item.pick(&idiom("operation")).as_raw_string().as_str() {
+						"Iterate Table" => {
+							scanned = true;
+							if !cost.table_scans.contains(&table) {
+								cost.table_scans.push(table.clone());
+							}
+							if !tables.contains(&table) {
+								tables.push(table);
+							}
+						}
+						// A range of records is estimated as the whole table
+						"Iterate Range" => {
+							scanned = true;
+							if !tables.contains(&table) {
+								tables.push(table);
+							}
+						}
+						"Iterate Index" => {
+							indexed = true;
+							let name = item.pick(&idiom("detail.plan.index")).as_raw_string();
+							if !cost.indexes.contains(&name) {
+								cost.indexes.push(name);
+							}
+						}
+						"Fetch" | "Fallback" => {}
+						// Records and values are read directly
+						_ => cost.rows += 1,
+					}
+				}
+				if indexed && !scanned {
+					counts.push(count(probe));
+				}
+			}
+			counts.extend(tables.into_iter().map(|table| {
+				count(SelectStatement {
+					what: Values(vec![Value::Table(Table(table))]),
+					..Default::default()
+				})
+			}));
+			if counts.is_empty() {
+				return Ok(cost);
+			}
+			let total = counts.len();
+			let query = Query(Statements(counts));
+			let mut conn = Client::new(Method::Query);
+			let param = Param::query(query, Default::default()).idempotent();
+			let mut response = conn.execute_query(router, param).await?;
+			for index in 0..total {
+				let rows: Option<u64> = response.take((index, "value"))?;
+				cost.rows += rows.unwrap_or_default();
+			}
+			Ok(cost)
+		})
+	}
+}
@@ -0,0 +1,102 @@
+use channel::Receiver;
+use channel::Sender;
+use channel::TrySendError;
+use futures::Stream;
+use futures::StreamExt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+
+/// The maximum number of events buffered for a subscriber which isn't keeping up
+const EVENT_BUFFER: usize = 64;
+
+/// A connection lifecycle event
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Event {
+	/// The connection to the server was re-established
+	Connected,
+	/// The connection to the server was lost
+	Disconnected {
+		/// Why the connection was lost
+		reason: String,
+	},
+	/// The authentication of the session was restored after reconnecting
+	Reauthenticated,
+}
+
+/// A stream of connection lifecycle events
+#[derive(Debug, Clone)]
+#[must_use = "streams do nothing unless you poll them"]
+pub struct Events {
+	rx: Receiver<Event>,
+}
+
+impl Stream for Events {
+	type Item = Event;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.as_mut().rx.poll_next_unpin(cx)
+	}
+}
+
+/// Sends connection lifecycle events to all subscribers
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Emitter {
+	subscribers: Arc<Mutex<Vec<Sender<Event>>>>,
+}
+
+impl Emitter {
+	pub(crate) fn subscribe(&self) -> Events {
+		let (tx, rx) = channel::bounded(EVENT_BUFFER);
+		self.subscribers.lock().unwrap_or_else(|error| error.into_inner()).push(tx);
+		Events {
+			rx,
+		}
+	}
+
+	#[allow(dead_code)] // used by the remote connections
+	pub(crate) fn emit(&self, event: Event) {
+		trace!("Connection event {event:?}");
+		let mut subscribers = self.subscribers.lock().unwrap_or_else(|error| error.into_inner());
+		subscribers.retain(|tx| match tx.try_send(event.clone()) {
+			Ok(()) => true,
+			Err(TrySendError::Full(..)) => {
+				trace!("Event subscriber is lagging behind; dropping event");
+				true
+			}
+			// The subscriber has gone away
+			Err(TrySendError::Closed(..)) => false,
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn emits_to_all_subscribers() {
+		let emitter = Emitter::default();
+		let mut first = emitter.subscribe();
+		let mut second = emitter.subscribe();
+		emitter.emit(Event::Disconnected {
+			reason: "connection reset".to_owned(),
+		});
+		emitter.emit(Event::Connected);
+		for events in [&mut first, &mut second] {
+			assert!(matches!(events.next().await, Some(Event::Disconnected { .. })));
+			assert_eq!(events.next().await, Some(Event::Connected));
+		}
+	}
+
+	#[tokio::test]
+	async fn forgets_dropped_subscribers() {
+		let emitter = Emitter::default();
+		drop(emitter.subscribe());
+		emitter.emit(Event::Connected);
+		assert!(emitter.subscribers.lock().unwrap().is_empty());
+	}
+}
@@ -11,19 +11,21 @@ use futures::Stream;
 use futures::StreamExt;
 use std::future::Future;
 use std::future::IntoFuture;
-use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::task::Context;
 use std::task::Poll;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::AsyncWrite;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::AsyncWriteExt;
 
 /// A database export future
 #[derive(Debug)]
 #[must_use = "futures do nothing unless you `.await` or poll them"]
 pub struct Export<'r, C: Connection, R> {
 	pub(super) router: Result<&'r Router<C>>,
-	pub(super) target: ExportDestination,
-	pub(super) response: PhantomData<R>,
+	pub(super) target: ExportDestination<R>,
 }
 
 impl<'r, Client> IntoFuture for Export<'r, Client, PathBuf>
@@ -42,7 +44,7 @@ where
 			let mut conn = Client::new(Method::Export);
 			match self.target {
 				ExportDestination::File(path) => conn.execute_unit(router, Param::file(path)).await,
-				ExportDestination::Memory => unreachable!(),
+				_ => unreachable!(),
 			}
 		})
 	}
@@ -74,6 +76,34 @@ where
 	}
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl<'r, 'w: 'r, Client, W> IntoFuture for Export<'r, Client, &'w mut W>
+where
+	Client: Connection,
+	W: AsyncWrite + Unpin + Send + Sync + ?Sized,
+{
+	type Output = Result<()>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let ExportDestination::Writer(writer) = self.target else {
+				unreachable!();
+			};
+			let mut backup = Export {
+				router: self.router,
+				target: ExportDestination::Memory,
+			}
+			.await?;
+			while let Some(bytes) = backup.next().await {
+				writer.write_all(&bytes?).await.map_err(Error::ExportWrite)?;
+			}
+			writer.flush().await.map_err(Error::ExportWrite)?;
+			Ok(())
+		})
+	}
+}
+
 /// A stream of exported data
 #[derive(Debug, Clone)]
 #[must_use = "streams do nothing unless you poll them"]
@@ -24,7 +24,7 @@ where
 	fn into_future(self) -> Self::IntoFuture {
 		Box::pin(async {
 			let mut conn = Client::new(Method::Health);
-			conn.execute_unit(self.router?, Param::new(Vec::new())).await
+			conn.execute_unit(self.router?, Param::new(Vec::new()).idempotent()).await
 		})
 	}
 }
@@ -5,20 +5,33 @@ use crate::api::Connection;
 use crate::api::Error;
 use crate::api::ExtraFeatures;
 use crate::api::Result;
+use crate::opt::ImportSource;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::sql::{Query, Statement, Statements};
 use std::future::Future;
 use std::future::IntoFuture;
 use std::path::PathBuf;
 use std::pin::Pin;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::AsyncBufReadExt;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::AsyncRead;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::BufReader;
+
+/// The number of statements which are imported from a reader in each transaction
+#[cfg(not(target_arch = "wasm32"))]
+const IMPORT_BATCH_SIZE: usize = 1000;
 
 /// An database import future
 #[derive(Debug)]
 #[must_use = "futures do nothing unless you `.await` or poll them"]
-pub struct Import<'r, C: Connection> {
+pub struct Import<'r, C: Connection, R = PathBuf> {
 	pub(super) router: Result<&'r Router<C>>,
-	pub(super) file: PathBuf,
+	pub(super) source: ImportSource<R>,
 }
 
-impl<'r, Client> IntoFuture for Import<'r, Client>
+impl<'r, Client> IntoFuture for Import<'r, Client, PathBuf>
 where
 	Client: Connection,
 {
@@ -31,8 +44,75 @@ where
 			if !router.features.contains(&ExtraFeatures::Backup) {
 				return Err(Error::BackupsNotSupported.into());
 			}
+			let ImportSource::File(file) = self.source else {
+				unreachable!();
+			};
 			let mut conn = Client::new(Method::Import);
-			conn.execute_unit(router, Param::file(self.file)).await
+			conn.execute_unit(router, Param::file(file)).await
+		})
+	}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<'r, 'w: 'r, Client, R> IntoFuture for Import<'r, Client, &'w mut R>
+where
+	Client: Connection,
+	R: AsyncRead + Unpin + Send + Sync + ?Sized,
+{
+	type Output = Result<()>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let router = self.router?;
+			let ImportSource::Reader(reader) = self.source else {
+				unreachable!();
+			};
+			let mut lines = BufReader::new(reader);
+			let mut line = String::new();
+			let mut buffer = String::new();
+			let mut batch = Vec::new();
+			loop {
+				line.clear();
+				let read = lines.read_line(&mut line).await.map_err(Error::ImportRead)?;
+				buffer.push_str(&line);
+				// A statement can only end at the end of a line which ends with a semicolon, but
+				// such a line can also be part of a string, so the statement is only complete once
+				// it parses
+				let end = read == 0;
+				if !end && !buffer.trim_end().ends_with(';') {
+					continue;
+				}
+				match crate::sql::parse(&buffer) {
+					Ok(query) => {
+						buffer.clear();
+						// The batches are the transactions of the import, so the transactions
+						// of the dump itself are left out
+						batch.extend(query.0 .0.into_iter().filter(|statement| {
+							!matches!(statement, Statement::Begin(_) | Statement::Commit(_))
+						}));
+					}
+					Err(crate::err::Error::QueryEmpty) => buffer.clear(),
+					Err(error) if end => return Err(error.into()),
+					Err(_) => continue,
+				}
+				if batch.len() >= IMPORT_BATCH_SIZE || (end && !batch.is_empty()) {
+					let mut statements = Vec::with_capacity(batch.len() + 2);
+					statements.push(Statement::Begin(Default::default()));
+					statements.append(&mut batch);
+					statements.push(Statement::Commit(Default::default()));
+					let query = Query(Statements(statements));
+					// The statements are sent as a regular query so this works with every engine
+					let mut conn = Client::new(Method::Query);
+					conn.execute_query(router, Param::query(query, Default::default()))
+						.await?
+						.check()?;
+				}
+				if end {
+					break;
+				}
+			}
+			Ok(())
 		})
 	}
 }
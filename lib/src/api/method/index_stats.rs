@@ -0,0 +1,77 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::opt::from_value;
+use crate::sql::statements::analyze::AnalyzeStatement;
+use crate::sql::Ident;
+use crate::sql::Query;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Value;
+use serde::Deserialize;
+use serde::Serialize;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::pin::Pin;
+
+/// The statistics of a full-text index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct IndexStatistics {
+	/// The number of documents in the index
+	pub doc_count: u64,
+	/// The total number of terms in the indexed documents
+	pub total_docs_lengths: u64,
+	/// The tree mapping record ids to document ids
+	pub doc_ids: TreeStatistics,
+	/// The tree of the indexed terms
+	pub terms: TreeStatistics,
+	/// The tree of the number of terms in each document
+	pub doc_lengths: TreeStatistics,
+	/// The tree of the documents each term is found in
+	pub postings: TreeStatistics,
+}
+
+/// The statistics of one of the trees backing an index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct TreeStatistics {
+	/// The number of keys in the tree
+	pub keys_count: u64,
+	/// The depth of the deepest leaf
+	pub max_depth: u32,
+	/// The number of nodes in the tree
+	pub nodes_count: u32,
+	/// The total size of the nodes, in bytes
+	pub total_size: u64,
+}
+
+/// An index statistics future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct IndexStats<'r, C: Connection> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) table: String,
+	pub(super) index: String,
+}
+
+impl<'r, Client> IntoFuture for IndexStats<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<IndexStatistics>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let statement = AnalyzeStatement::Idx(Ident(self.table), Ident(self.index));
+			let query = Query(Statements(vec![Statement::Analyze(statement)]));
+			let mut conn = Client::new(Method::Query);
+			let param = Param::query(query, Default::default()).idempotent();
+			let stats: Value = conn.execute_query(self.router?, param).await?.take(0)?;
+			Ok(from_value(stats)?)
+		})
+	}
+}
@@ -0,0 +1,53 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::sql::infer::infer_fields;
+use crate::sql::statements::DefineFieldStatement;
+use crate::sql::statements::SelectStatement;
+use crate::sql::Field;
+use crate::sql::Fields;
+use crate::sql::Limit;
+use crate::sql::Query;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Table;
+use crate::sql::Value;
+use crate::sql::Values;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::pin::Pin;
+
+/// A schema inference future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct InferSchema<'r, C: Connection> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) table: String,
+	pub(super) sample_size: usize,
+}
+
+impl<'r, Client> IntoFuture for InferSchema<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<Vec<DefineFieldStatement>>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let statement = SelectStatement {
+				expr: Fields(vec![Field::All], false),
+				what: Values(vec![Value::Table(Table(self.table.clone()))]),
+				limit: Some(Limit(Value::from(self.sample_size))),
+				..Default::default()
+			};
+			let query = Query(Statements(vec![Statement::Select(statement)]));
+			let mut conn = Client::new(Method::Query);
+			let param = Param::query(query, Default::default()).idempotent();
+			let records: Vec<Value> = conn.execute_query(self.router?, param).await?.take(0)?;
+			Ok(infer_fields(&self.table, &records))
+		})
+	}
+}
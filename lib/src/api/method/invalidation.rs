@@ -0,0 +1,248 @@
+use super::changes::invalid;
+use super::changes::versionstamp;
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::api::engine::runtime::sleep;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::dbs::Action;
+use crate::sql::statements::show::ShowSince;
+use crate::sql::statements::show::ShowStatement;
+use crate::sql::Query;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Table;
+use crate::sql::Thing;
+use crate::sql::Value;
+use crate::sql::Versionstamp;
+use futures::stream;
+use futures::Stream;
+use futures::StreamExt;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio::sleep;
+
+/// A record which was changed, and whose cached copies are no longer valid
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Invalidation {
+	/// The table of the record
+	pub table: String,
+	/// The ID of the record
+	pub id: Thing,
+	/// How the record was changed
+	///
+	/// Change feeds don't tell records which were created apart from records which were
+	/// updated, so both are reported as [`Action::Update`].
+	pub action: Action,
+	/// The versionstamp the change was committed at
+	pub versionstamp: Versionstamp,
+}
+
+/// A future which reads the records which were changed in a set of tables
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct InvalidationEvents<'r, C: Connection> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) tables: Vec<String>,
+	pub(super) since: Versionstamp,
+	pub(super) limit: Option<u32>,
+	pub(super) interval: Duration,
+}
+
+impl<'r, C> InvalidationEvents<'r, C>
+where
+	C: Connection,
+{
+	/// Only returns the changes made at or after this versionstamp
+	pub fn since(mut self, versionstamp: impl Into<Versionstamp>) -> Self {
+		self.since = versionstamp.into();
+		self
+	}
+
+	/// Sets the maximum number of change sets read from each table in a single request
+	pub fn limit(mut self, limit: u32) -> Self {
+		self.limit = Some(limit);
+		self
+	}
+
+	/// Sets how long a stream waits before checking for new changes
+	pub fn poll_interval(mut self, interval: Duration) -> Self {
+		self.interval = interval;
+		self
+	}
+
+	/// Follows the changes made to the tables, waiting for new ones as they are committed
+	///
+	/// The stream never ends by itself. Errors are returned in the stream, after which
+	/// it continues to poll for changes from where it left off.
+	pub fn stream(self) -> InvalidationStream<'r> {
+		let InvalidationEvents {
+			router,
+			tables,
+			since,
+			limit,
+			interval,
+		} = self;
+		let router = match router {
+			Ok(router) => router,
+			Err(error) => {
+				return InvalidationStream {
+					inner: Box::pin(stream::once(async { Err(error) })),
+				};
+			}
+		};
+		let cursors: Vec<_> = tables.into_iter().map(|table| (table, since)).collect();
+		let inner = stream::unfold(
+			(cursors, VecDeque::new()),
+			move |(mut cursors, mut buffer)| async move {
+				loop {
+					if let Some(event) = buffer.pop_front() {
+						return Some((Ok(event), (cursors, buffer)));
+					}
+					match read::<C>(router, &mut cursors, limit).await {
+						Ok(events) if events.is_empty() => sleep(interval).await,
+						Ok(events) => buffer.extend(events),
+						Err(error) => {
+							sleep(interval).await;
+							return Some((Err(error), (cursors, buffer)));
+						}
+					}
+				}
+			},
+		);
+		InvalidationStream {
+			inner: Box::pin(inner),
+		}
+	}
+}
+
+impl<'r, Client> IntoFuture for InvalidationEvents<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<Vec<Invalidation>>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let router = self.router?;
+			let mut cursors: Vec<_> =
+				self.tables.into_iter().map(|table| (table, self.since)).collect();
+			read::<Client>(router, &mut cursors, self.limit).await
+		})
+	}
+}
+
+/// A stream of the records which were changed in a set of tables
+#[must_use = "streams do nothing unless you poll them"]
+pub struct InvalidationStream<'r> {
+	inner: Pin<Box<dyn Stream<Item = Result<Invalidation>> + Send + Sync + 'r>>,
+}
+
+impl std::fmt::Debug for InvalidationStream<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("InvalidationStream").finish_non_exhaustive()
+	}
+}
+
+impl Stream for InvalidationStream<'_> {
+	type Item = Result<Invalidation>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.inner.poll_next_unpin(cx)
+	}
+}
+
+/// Reads the changes made to all the tables in a single request, moving their cursors past them
+async fn read<C>(
+	router: &Router<C>,
+	cursors: &mut [(String, Versionstamp)],
+	limit: Option<u32>,
+) -> Result<Vec<Invalidation>>
+where
+	C: Connection,
+{
+	if cursors.is_empty() {
+		return Ok(Vec::new());
+	}
+	let statements = cursors
+		.iter()
+		.map(|(table, since)| {
+			Statement::Show(ShowStatement {
+				table: Some(Table(table.clone())),
+				since: ShowSince::Versionstamp((*since).into()),
+				limit,
+			})
+		})
+		.collect();
+	let query = Query(Statements(statements));
+	let mut conn = C::new(Method::Query);
+	let param = Param::query(query, Default::default()).idempotent();
+	let mut response = conn.execute_query(router, param).await?;
+	let mut events = Vec::new();
+	let mut next = Vec::with_capacity(cursors.len());
+	for (index, (table, since)) in cursors.iter().enumerate() {
+		let value: Value = response.take(index)?;
+		let sets = match value {
+			Value::None | Value::Null => Vec::new(),
+			Value::Array(array) => array.0,
+			value => return Err(invalid(value).into()),
+		};
+		let mut since = *since;
+		for set in sets {
+			since = change_set(table, set, &mut events)?.next();
+		}
+		next.push(since);
+	}
+	// The cursors are only moved once all the changes were read
+	for ((_, since), next) in cursors.iter_mut().zip(next) {
+		*since = next;
+	}
+	// Changes to different tables are returned in the order they were committed
+	events.sort_by_key(|event| event.versionstamp);
+	Ok(events)
+}
+
+/// Reads the records which were changed in a set of changes, returning its versionstamp
+///
+/// Only the IDs of the records are read, so the records themselves are never deserialized.
+fn change_set(table: &str, value: Value, events: &mut Vec<Invalidation>) -> Result<Versionstamp> {
+	let Value::Object(mut object) = value else {
+		return Err(invalid(value).into());
+	};
+	let Some(versionstamp) = versionstamp(object.remove("versionstamp")) else {
+		return Err(invalid(Value::Object(object)).into());
+	};
+	let Some(Value::Array(changes)) = object.remove("changes") else {
+		return Err(invalid(Value::Object(object)).into());
+	};
+	for change in changes {
+		let Value::Object(mut change) = change else {
+			return Err(invalid(change).into());
+		};
+		let (action, record) = match (change.remove("update"), change.remove("delete")) {
+			(Some(record), _) => (Action::Update, record),
+			(_, Some(record)) => (Action::Delete, record),
+			// Other changes, such as the table being defined, don't change any records
+			_ => continue,
+		};
+		if let Value::Thing(id) = record.rid() {
+			events.push(Invalidation {
+				table: table.to_owned(),
+				id,
+				action,
+				versionstamp,
+			});
+		}
+	}
+	Ok(versionstamp)
+}
@@ -25,8 +25,11 @@ where
 
 	fn into_future(self) -> Self::IntoFuture {
 		Box::pin(async move {
+			let router = self.router?;
+			// A shared live query is killed for all of its subscribers
+			router.subscriptions.kill(&self.query_id);
 			let mut conn = Client::new(Method::Kill);
-			conn.execute_unit(self.router?, Param::new(vec![self.query_id.into()])).await
+			conn.execute_unit(router, Param::new(vec![self.query_id.into()])).await
 		})
 	}
 }
@@ -1,34 +1,430 @@
 use crate::api::conn::Method;
 use crate::api::conn::Param;
-use crate::api::conn::Router;
+use crate::api::conn::Route;
+use crate::api::err::Error;
+use crate::api::expr::Expr;
 use crate::api::Connection;
+use crate::api::OnceLockExt;
 use crate::api::Result;
+use crate::api::Surreal;
+use crate::dbs::Notification;
+use crate::opt::from_value;
+use crate::sql;
 use crate::sql::Table;
 use crate::sql::Uuid;
 use crate::sql::Value;
+use channel::Receiver;
+use channel::Sender;
+use channel::TrySendError;
+use futures::Stream;
+use futures::StreamExt;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::task::ready;
+use std::task::Context;
+use std::task::Poll;
+
+/// The maximum number of notifications buffered for a subscriber which isn't keeping up
+const NOTIFICATION_BUFFER: usize = 64;
+
+/// The maximum number of notifications kept for live queries which are still being started
+const PENDING_NOTIFICATIONS: usize = 64;
 
 /// A live query future
 #[derive(Debug)]
 #[must_use = "futures do nothing unless you `.await` or poll them"]
-pub struct Live<'r, C: Connection> {
-	pub(super) router: Result<&'r Router<C>>,
+pub struct Live<C: Connection> {
+	pub(super) client: Surreal<C>,
 	pub(super) table_name: String,
+	pub(super) condition: Option<Value>,
 }
 
-impl<'r, Client> IntoFuture for Live<'r, Client>
+impl<C> Live<C>
+where
+	C: Connection,
+{
+	/// Only notifies about the records which match the condition
+	pub fn filter(mut self, condition: Expr) -> Self {
+		self.condition = Some(condition.into());
+		self
+	}
+}
+
+impl<Client> IntoFuture for Live<Client>
 where
 	Client: Connection,
 {
-	type Output = Result<Uuid>;
-	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+	type Output = Result<Subscription<Client>>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'static>>;
 
 	fn into_future(self) -> Self::IntoFuture {
 		Box::pin(async move {
-			let mut conn = Client::new(Method::Live);
-			conn.execute(self.router?, Param::new(vec![Value::Table(Table(self.table_name))])).await
+			let router = self.client.router.extract()?;
+			// Live queries are only shared within the same namespace, database and authentication
+			let mut conn = Client::new(Method::Query);
+			let query = sql::parse("RETURN [session::ns(), session::db(), $token]")?;
+			let param = Param::query(query, BTreeMap::new());
+			let session: Value = conn.execute_query(router, param).await?.take(0)?;
+			// Live queries with the same session, table and condition are shared
+			let key = match &self.condition {
+				Some(cond) => format!("{session} {} WHERE {cond}", self.table_name),
+				None => format!("{session} {}", self.table_name),
+			};
+			let joined = router.subscriptions.join(&key);
+			let (id, token, rx, lagged) = match joined {
+				Some(joined) => joined,
+				None => {
+					let table = Value::Table(Table(self.table_name));
+					let id: Uuid = match self.condition {
+						Some(cond) => {
+							let query =
+								format!("LIVE SELECT * FROM type::table($table) WHERE {cond}");
+							let mut bindings = BTreeMap::new();
+							bindings.insert("table".to_owned(), table);
+							let mut conn = Client::new(Method::Query);
+							let param = Param::query(sql::parse(&query)?, bindings);
+							let id: Value = conn.execute_query(router, param).await?.take(0)?;
+							from_value(id)?
+						}
+						None => {
+							let mut conn = Client::new(Method::Live);
+							conn.execute(router, Param::new(vec![table])).await?
+						}
+					};
+					let (shared, token, rx, lagged) =
+						router.subscriptions.register(key.clone(), id.clone());
+					// Another caller started the same live query in the meantime
+					if shared != id {
+						let mut conn = Client::new(Method::Kill);
+						conn.execute_unit(router, Param::new(vec![id.into()])).await?;
+					}
+					(shared, token, rx, lagged)
+				}
+			};
+			Ok(Subscription {
+				client: self.client,
+				key,
+				token,
+				id,
+				rx,
+				lagged,
+			})
 		})
 	}
 }
+
+/// A stream of the notifications of a live query
+///
+/// Subscriptions to the same table and condition, in the same namespace and database and with
+/// the same authentication, share a single live query on the server, which is killed once all
+/// of them have been dropped. The stream ends when the live query is killed, or when the
+/// connection to the server is lost. A subscription which falls too far behind is closed with
+/// an error after its buffered notifications, rather than silently missing notifications.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless you poll them"]
+pub struct Subscription<C: Connection> {
+	client: Surreal<C>,
+	key: String,
+	token: u64,
+	id: Uuid,
+	rx: Receiver<Notification>,
+	lagged: Arc<AtomicBool>,
+}
+
+impl<C> Subscription<C>
+where
+	C: Connection,
+{
+	/// The ID of the live query, which is the same for all of its subscriptions
+	pub fn id(&self) -> Uuid {
+		self.id.clone()
+	}
+}
+
+impl<C> Stream for Subscription<C>
+where
+	C: Connection,
+{
+	type Item = Result<Notification>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		match ready!(self.as_mut().rx.poll_next_unpin(cx)) {
+			Some(notification) => Poll::Ready(Some(Ok(notification))),
+			// The buffered notifications were delivered, but the ones after them were cut off
+			None if self.lagged.swap(false, Ordering::Relaxed) => {
+				Poll::Ready(Some(Err(Error::SubscriptionLagged(NOTIFICATION_BUFFER).into())))
+			}
+			None => Poll::Ready(None),
+		}
+	}
+}
+
+impl<C> Drop for Subscription<C>
+where
+	C: Connection,
+{
+	fn drop(&mut self) {
+		let Ok(router) = self.client.router.extract() else {
+			return;
+		};
+		let Some(id) = router.subscriptions.unsubscribe(&self.key, self.token) else {
+			return;
+		};
+		// The last subscriber is gone, so the live query is killed without waiting for it
+		let (response, _) = flume::bounded(1);
+		let route = Route {
+			request: (router.next_id(), Method::Kill, Param::new(vec![id.into()])),
+			response,
+		};
+		if router.sender.try_send(Some(route)).is_err() {
+			trace!("Failed to kill the live query {id}");
+		}
+	}
+}
+
+/// A subscriber to a shared live query
+#[derive(Debug)]
+struct Subscriber {
+	token: u64,
+	tx: Sender<Notification>,
+	lagged: Arc<AtomicBool>,
+}
+
+/// The channel of a new subscriber
+type Subscribed = (Uuid, u64, Receiver<Notification>, Arc<AtomicBool>);
+
+/// A live query which is shared by its subscribers
+#[derive(Debug)]
+struct Shared {
+	id: Uuid,
+	subscribers: Vec<Subscriber>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+	/// The running live queries, by their session, table and condition
+	queries: HashMap<String, Shared>,
+	/// The session, table and condition of each running live query, by its ID
+	keys: HashMap<Uuid, String>,
+	/// The notifications which arrived before their live query was registered
+	pending: VecDeque<Notification>,
+	/// The token of the next subscriber
+	next: u64,
+}
+
+/// Fans the notifications of shared live queries out to their subscribers
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Subscriptions {
+	state: Arc<Mutex<State>>,
+}
+
+impl Subscriptions {
+	fn state(&self) -> MutexGuard<'_, State> {
+		self.state.lock().unwrap_or_else(|error| error.into_inner())
+	}
+
+	fn subscribe(state: &mut State, key: &str) -> Option<Subscribed> {
+		let token = state.next;
+		let shared = state.queries.get_mut(key)?;
+		let (tx, rx) = channel::bounded(NOTIFICATION_BUFFER);
+		let lagged = Arc::new(AtomicBool::new(false));
+		shared.subscribers.push(Subscriber {
+			token,
+			tx,
+			lagged: lagged.clone(),
+		});
+		let id = shared.id.clone();
+		state.next += 1;
+		Some((id, token, rx, lagged))
+	}
+
+	/// Subscribes to a live query which is already running, if there is one
+	fn join(&self, key: &str) -> Option<Subscribed> {
+		Self::subscribe(&mut self.state(), key)
+	}
+
+	/// Registers a live query which was started, and subscribes to it
+	///
+	/// If the same live query was registered while this one was being started, that one is
+	/// subscribed to instead, and its ID is returned.
+	fn register(&self, key: String, id: Uuid) -> Subscribed {
+		let mut state = self.state();
+		if let Some(joined) = Self::subscribe(&mut state, &key) {
+			return joined;
+		}
+		state.keys.insert(id.clone(), key.clone());
+		state.queries.insert(
+			key.clone(),
+			Shared {
+				id,
+				subscribers: Vec::new(),
+			},
+		);
+		let (id, token, rx, lagged) =
+			Self::subscribe(&mut state, &key).expect("the live query was just registered");
+		// Deliver what was notified before the live query was registered
+		let pending = std::mem::take(&mut state.pending);
+		for notification in pending {
+			match notification.id == id {
+				true => Self::send(&mut state, notification),
+				false => state.pending.push_back(notification),
+			}
+		}
+		(id, token, rx, lagged)
+	}
+
+	/// Removes a subscriber, returning the ID of its live query if it has no subscribers left
+	///
+	/// The subscriber may already have been removed, if it fell too far behind.
+	fn unsubscribe(&self, key: &str, token: u64) -> Option<Uuid> {
+		let mut state = self.state();
+		let shared = state.queries.get_mut(key)?;
+		shared.subscribers.retain(|subscriber| subscriber.token != token);
+		if !shared.subscribers.is_empty() {
+			return None;
+		}
+		let shared = state.queries.remove(key)?;
+		state.keys.remove(&shared.id);
+		Some(shared.id)
+	}
+
+	fn send(state: &mut State, notification: Notification) {
+		let Some(shared) =
+			state.keys.get(&notification.id).and_then(|key| state.queries.get_mut(key))
+		else {
+			return;
+		};
+		shared.subscribers.retain(|subscriber| {
+			match subscriber.tx.try_send(notification.clone()) {
+				Ok(()) => true,
+				// The subscriber is closed rather than silently missing notifications
+				Err(TrySendError::Full(..)) => {
+					warn!("Live query subscriber fell too far behind; closing the subscription");
+					subscriber.lagged.store(true, Ordering::Relaxed);
+					false
+				}
+				// The subscriber has gone away
+				Err(TrySendError::Closed(..)) => false,
+			}
+		});
+	}
+
+	/// Sends a notification to the subscribers of its live query
+	#[allow(dead_code)] // used by the embedded and WebSocket connections
+	pub(crate) fn notify(&self, notification: Notification) {
+		let mut state = self.state();
+		if state.keys.contains_key(&notification.id) {
+			return Self::send(&mut state, notification);
+		}
+		// The live query may still be being registered
+		if state.pending.len() == PENDING_NOTIFICATIONS {
+			state.pending.pop_front();
+		}
+		state.pending.push_back(notification);
+	}
+
+	/// Forgets a live query which was killed, ending its subscriptions
+	pub(crate) fn kill(&self, id: &Uuid) {
+		let mut state = self.state();
+		if let Some(key) = state.keys.remove(id) {
+			state.queries.remove(&key);
+		}
+	}
+
+	/// Forgets all live queries, such as after the connection was lost, ending their subscriptions
+	#[allow(dead_code)] // used by the WebSocket connections
+	pub(crate) fn reset(&self) {
+		let mut state = self.state();
+		state.queries.clear();
+		state.keys.clear();
+		state.pending.clear();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::dbs::Action;
+
+	fn notification(id: Uuid) -> Notification {
+		Notification {
+			id,
+			action: Action::Create,
+			result: Value::None,
+		}
+	}
+
+	#[tokio::test]
+	async fn notifies_all_subscribers() {
+		let subscriptions = Subscriptions::default();
+		assert!(subscriptions.join("person").is_none());
+		let id = Uuid::new_v4();
+		let (first, _, mut first_rx, _) = subscriptions.register("person".to_owned(), id.clone());
+		let (second, _, mut second_rx, _) = subscriptions.join("person").unwrap();
+		assert_eq!(first, id);
+		assert_eq!(second, id);
+		subscriptions.notify(notification(id.clone()));
+		subscriptions.notify(notification(Uuid::new_v4()));
+		assert_eq!(first_rx.next().await, Some(notification(id.clone())));
+		assert_eq!(second_rx.next().await, Some(notification(id)));
+		assert!(first_rx.try_recv().is_err());
+	}
+
+	#[tokio::test]
+	async fn shares_live_queries_which_are_started_concurrently() {
+		let subscriptions = Subscriptions::default();
+		let first = Uuid::new_v4();
+		// A notification arrives before the live query is registered
+		subscriptions.notify(notification(first.clone()));
+		let (id, _, mut rx, _) = subscriptions.register("person".to_owned(), first.clone());
+		assert_eq!(id, first);
+		assert_eq!(rx.next().await, Some(notification(first.clone())));
+		// The same live query was started by another caller in the meantime
+		let (id, ..) = subscriptions.register("person".to_owned(), Uuid::new_v4());
+		assert_eq!(id, first);
+	}
+
+	#[test]
+	fn kills_once_the_last_subscriber_is_gone() {
+		let subscriptions = Subscriptions::default();
+		let id = Uuid::new_v4();
+		let (_, first, _rx, _) = subscriptions.register("person".to_owned(), id.clone());
+		let (_, second, _rx, _) = subscriptions.join("person").unwrap();
+		assert_eq!(subscriptions.unsubscribe("person", first), None);
+		assert_eq!(subscriptions.unsubscribe("person", first), None);
+		assert_eq!(subscriptions.unsubscribe("person", second), Some(id));
+		assert!(subscriptions.join("person").is_none());
+	}
+
+	#[tokio::test]
+	async fn closes_subscribers_which_fall_behind() {
+		let subscriptions = Subscriptions::default();
+		let id = Uuid::new_v4();
+		let (_, slow, mut slow_rx, lagged) =
+			subscriptions.register("person".to_owned(), id.clone());
+		for _ in 0..NOTIFICATION_BUFFER {
+			subscriptions.notify(notification(id.clone()));
+		}
+		assert!(!lagged.load(Ordering::Relaxed));
+		// A new subscriber is not held back by the one which fell behind
+		let (_, _, mut fast_rx, _) = subscriptions.join("person").unwrap();
+		subscriptions.notify(notification(id.clone()));
+		assert!(lagged.load(Ordering::Relaxed));
+		assert_eq!(fast_rx.next().await, Some(notification(id.clone())));
+		// The buffered notifications are still delivered before the channel ends
+		for _ in 0..NOTIFICATION_BUFFER {
+			assert_eq!(slow_rx.next().await, Some(notification(id.clone())));
+		}
+		assert_eq!(slow_rx.next().await, None);
+		assert_eq!(subscriptions.unsubscribe("person", slow), None);
+	}
+}
@@ -1,12 +1,15 @@
 use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
+use crate::api::method::condition::Condition;
 use crate::api::opt::Range;
 use crate::api::opt::Resource;
 use crate::api::Connection;
 use crate::api::Result;
 use crate::sql::to_value;
 use crate::sql::Id;
+use crate::sql::Object;
+use crate::sql::Output;
 use crate::sql::Value;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -22,10 +25,44 @@ pub struct Merge<'r, C: Connection, D, R> {
 	pub(super) router: Result<&'r Router<C>>,
 	pub(super) resource: Result<Resource>,
 	pub(super) range: Option<Range<Id>>,
+	pub(super) condition: Option<Condition>,
+	pub(super) output: Option<Output>,
 	pub(super) content: D,
+	pub(super) skip_none: bool,
 	pub(super) response_type: PhantomData<R>,
 }
 
+impl<'r, C, D, R> Merge<'r, C, D, R>
+where
+	C: Connection,
+{
+	/// Leaves out the fields of the data which are `None`
+	///
+	/// Optional fields which are not set don't overwrite the data in the record,
+	/// as if they were annotated with `#[serde(skip_serializing_if = "Option::is_none")]`.
+	pub fn skip_none(mut self) -> Self {
+		self.skip_none = true;
+		self
+	}
+
+	/// Runs the write without applying it, returning the changes it would have made
+	///
+	/// See [`Update::dry_run`](crate::method::Update::dry_run).
+	pub fn dry_run(mut self) -> Self {
+		self.condition = Some(Condition::dry_run(self.condition.take(), Method::Merge));
+		self.output = Some(Output::Diff);
+		self
+	}
+}
+
+/// Removes the fields which are `NONE` from the objects in the value
+fn strip_none(value: &mut Value) {
+	if let Value::Object(Object(map)) = value {
+		map.retain(|_, value| !value.is_none());
+		map.values_mut().for_each(strip_none);
+	}
+}
+
 macro_rules! into_future {
 	($method:ident) => {
 		fn into_future(self) -> Self::IntoFuture {
@@ -33,15 +70,29 @@ macro_rules! into_future {
 				router,
 				resource,
 				range,
+				condition,
+				output,
 				content,
+				skip_none,
 				..
 			} = self;
-			let content = to_value(content);
+			let content = to_value(content).map(|mut content| {
+				if skip_none {
+					strip_none(&mut content);
+				}
+				content
+			});
 			Box::pin(async move {
 				let param = match range {
 					Some(range) => resource?.with_range(range)?,
 					None => resource?.into(),
 				};
+				if condition.is_some() || output.is_some() {
+					let condition = condition.unwrap_or(Condition::always(Method::Merge));
+					return condition
+						.$method(router?, param, Some(("MERGE", content?)), output)
+						.await;
+				}
 				let mut conn = Client::new(Method::Merge);
 				conn.$method(router?, Param::new(vec![param, content?])).await
 			})
@@ -83,3 +134,28 @@ where
 
 	into_future! {execute_vec}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn strip_nested_none() {
+		let mut value = Value::from(map! {
+			"name".to_owned() => Value::from("Tobie"),
+			"email".to_owned() => Value::None,
+			"settings".to_owned() => Value::from(map! {
+				"theme".to_owned() => Value::None,
+				"active".to_owned() => Value::Bool(true),
+			}),
+		});
+		strip_none(&mut value);
+		let expected = Value::from(map! {
+			"name".to_owned() => Value::from("Tobie"),
+			"settings".to_owned() => Value::from(map! {
+				"active".to_owned() => Value::Bool(true),
+			}),
+		});
+		assert_eq!(value, expected);
+	}
+}
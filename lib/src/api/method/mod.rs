@@ -2,25 +2,42 @@
 
 pub(crate) mod query;
 
+mod aggregate;
 mod authenticate;
+mod backpressure;
 mod begin;
+mod blob;
+mod cache;
 mod cancel;
+mod changes;
 mod commit;
+mod condition;
 mod content;
 mod create;
+mod define;
 mod delete;
+mod estimate;
+mod events;
 mod export;
 mod health;
 mod import;
+mod index_stats;
+mod infer_schema;
 mod invalidate;
+mod invalidation;
 mod kill;
 mod live;
 mod merge;
 mod patch;
+mod rebuild_index;
+mod schema;
+mod search;
 mod select;
+mod select_many;
 mod set;
 mod signin;
 mod signup;
+mod traverse;
 mod unset;
 mod update;
 mod use_db;
@@ -30,57 +47,116 @@ mod version;
 #[cfg(test)]
 mod tests;
 
+pub use aggregate::Aggregate;
 pub use authenticate::Authenticate;
+pub use backpressure::RequestMetrics;
 #[doc(hidden)] // Not supported yet
 pub use begin::Begin;
 #[doc(hidden)] // Not supported yet
 pub use begin::Transaction;
 #[doc(hidden)] // Not supported yet
+pub use blob::Blob;
+pub use blob::GetBlob;
+pub use blob::PutBlob;
 pub use cancel::Cancel;
+pub use changes::Change;
+pub use changes::ChangeSet;
+pub use changes::ChangeStream;
+pub use changes::Changes;
 #[doc(hidden)] // Not supported yet
 pub use commit::Commit;
 pub use content::Content;
 pub use create::Create;
+pub use define::DefineAnalyzer;
+pub use define::DefineEvent;
+pub use define::DefineField;
+pub use define::DefineIndex;
 pub use delete::Delete;
+pub use delete::DeleteWhere;
+pub use estimate::Cost;
+pub use estimate::Estimate;
+pub use events::Event;
+pub use events::Events;
 pub use export::Backup;
 pub use export::Export;
 pub use health::Health;
 pub use import::Import;
+pub use index_stats::IndexStatistics;
+pub use index_stats::IndexStats;
+pub use index_stats::TreeStatistics;
+pub use infer_schema::InferSchema;
 pub use invalidate::Invalidate;
+pub use invalidation::Invalidation;
+pub use invalidation::InvalidationEvents;
+pub use invalidation::InvalidationStream;
 #[doc(hidden)] // Not supported yet
 pub use kill::Kill;
-#[doc(hidden)] // Not supported yet
 pub use live::Live;
+pub use live::Subscription;
 pub use merge::Merge;
 pub use patch::Patch;
 pub use query::Query;
+pub use rebuild_index::RebuildIndex;
+pub use rebuild_index::RebuildProgress;
+pub use rebuild_index::RebuildStream;
+pub use search::Distance;
+pub use search::Search;
+pub use search::SearchResult;
+pub use search::TextSearch;
+pub use search::TextSearchResult;
+pub use search::VectorSearch;
 pub use select::Select;
+pub use select_many::SelectMany;
 pub use set::Set;
 pub use signin::Signin;
 pub use signup::Signup;
+pub use traverse::Traverse;
 pub use unset::Unset;
 pub use update::Update;
 pub use use_db::UseDb;
 pub use use_ns::UseNs;
 pub use version::Version;
 
+pub(crate) use backpressure::InFlight;
+pub(crate) use backpressure::Permit;
+pub(crate) use cache::Cache;
+pub(crate) use events::Emitter;
+pub(crate) use live::Subscriptions;
+pub(crate) use schema::Schemas;
+
 use crate::api::conn::Method;
+use crate::api::migrations::Migrate;
+use crate::api::migrations::Migrations;
 use crate::api::opt;
 use crate::api::opt::auth;
 use crate::api::opt::auth::Credentials;
 use crate::api::opt::auth::Jwt;
 use crate::api::opt::IntoEndpoint;
+use crate::api::opt::IntoRecordId;
+use crate::api::seed::Seed;
+use crate::api::seed::Seeding;
+use crate::api::unit_of_work::Submit;
+use crate::api::unit_of_work::UnitOfWork;
 use crate::api::Connect;
 use crate::api::Connection;
 use crate::api::OnceLockExt;
+use crate::api::Result;
 use crate::api::Surreal;
 use crate::opt::IntoExportDestination;
+use crate::opt::IntoImportSource;
+use crate::sql;
+use crate::sql::statements::DefineAnalyzerStatement;
+use crate::sql::statements::DefineEventStatement;
+use crate::sql::statements::DefineFieldStatement;
+use crate::sql::statements::DefineIndexStatement;
 use crate::sql::to_value;
+use crate::sql::Ident;
 use crate::sql::Uuid;
 use crate::sql::Value;
+use aggregate::Aggregation;
 use serde::Serialize;
 use std::marker::PhantomData;
-use std::path::Path;
+use std::ops::Bound;
 use std::sync::Arc;
 use std::sync::OnceLock;
 
@@ -92,6 +168,7 @@ impl Method {
 			Method::Create => "create",
 			Method::Delete => "delete",
 			Method::Export => "export",
+			Method::GetBlob => "blob",
 			Method::Health => "health",
 			Method::Import => "import",
 			Method::Invalidate => "invalidate",
@@ -99,6 +176,7 @@ impl Method {
 			Method::Live => "live",
 			Method::Merge => "merge",
 			Method::Patch => "patch",
+			Method::PutBlob => "blob",
 			Method::Query => "query",
 			Method::Select => "select",
 			Method::Set => "set",
@@ -239,6 +317,8 @@ where
 			router: Arc::new(OnceLock::new()),
 			address: address.into_endpoint(),
 			capacity: 0,
+			retry: Default::default(),
+			circuit_breaker: Default::default(),
 			client: PhantomData,
 			response_type: PhantomData,
 		}
@@ -292,6 +372,10 @@ where
 
 	/// Assigns a value as a parameter for this connection
 	///
+	/// The parameter is stored on the session and is available as `$key` in every
+	/// subsequent query, without having to bind it each time. Protected parameters,
+	/// such as `$auth` or `$session`, can't be assigned.
+	///
 	/// # Examples
 	///
 	/// ```no_run
@@ -568,6 +652,9 @@ where
 
 	/// Authenticates the current connection with a JWT token
 	///
+	/// Tokens issued by external identity providers, such as Auth0 or Keycloak, can be
+	/// verified against the keys they publish by defining a token with the `JWKS` type.
+	///
 	/// # Examples
 	///
 	/// ```no_run
@@ -579,6 +666,28 @@ where
 	/// # Ok(())
 	/// # }
 	/// ```
+	///
+	/// Authenticating with a token issued by an external provider
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// # let token = String::new();
+	/// // Trust the keys published by the identity provider
+	/// let sql = r#"
+	///     DEFINE TOKEN auth0 ON SCOPE user_scope TYPE JWKS
+	///     VALUE "https://example.auth0.com/.well-known/jwks.json"
+	///     ISSUER "https://example.auth0.com/"
+	///     AUDIENCE "surrealdb"
+	/// "#;
+	/// db.query(sql).await?.check()?;
+	///
+	/// // Authenticate with the token issued by the provider
+	/// db.authenticate(token).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
 	pub fn authenticate(&self, token: impl Into<Jwt>) -> Authenticate<C> {
 		Authenticate {
 			router: self.router.extract(),
@@ -624,6 +733,54 @@ where
 			router: self.router.extract(),
 			query: vec![query.into_query()],
 			bindings: Ok(Default::default()),
+			idempotent: false,
+			at: None,
+			dry_run: false,
+			chunked: None,
+			#[cfg(not(target_arch = "wasm32"))]
+			streamed: None,
+		}
+	}
+
+	/// Runs a query which was already parsed, or built with the [`builder`](crate::builder)
+	///
+	/// The embedded engines run the statements as they are, without turning them back into
+	/// text and parsing them again. Remote engines send the statements to the server as text.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::sql;
+	///
+	/// # #[derive(serde::Deserialize)]
+	/// # struct Person;
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Parse the query once
+	/// let query = sql::parse("SELECT * FROM person WHERE age > $age")?;
+	///
+	/// // Run it as many times as needed
+	/// for age in [18, 21] {
+	///     let people: Vec<Person> = db.process(query.clone()).bind(("age", age)).await?.take(0)?;
+	/// }
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[tracing::instrument(skip(self))]
+	pub fn process(&self, query: sql::Query) -> Query<C> {
+		Query {
+			router: self.router.extract(),
+			query: vec![Ok(query.0 .0)],
+			bindings: Ok(Default::default()),
+			idempotent: false,
+			at: None,
+			dry_run: false,
+			chunked: None,
+			#[cfg(not(target_arch = "wasm32"))]
+			streamed: None,
 		}
 	}
 
@@ -661,6 +818,85 @@ where
 		}
 	}
 
+	/// Selects multiple records by their IDs in a single request
+	///
+	/// The records are returned in the same order as the IDs requested, with `None` in
+	/// place of any record that doesn't exist.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[derive(serde::Deserialize, Debug)]
+	/// # struct Person;
+	/// #
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Select specific records from a table
+	/// let people: Vec<Option<Person>> = db.select_many(["person:tobie", "person:jaime"]).await?;
+	///
+	/// // Select records using tuples
+	/// let people: Vec<Option<Person>> = db.select_many([("person", "tobie"), ("person", "jaime")]).await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn select_many<R, I>(&self, ids: I) -> SelectMany<C, R>
+	where
+		I: IntoIterator,
+		I::Item: IntoRecordId,
+	{
+		SelectMany {
+			router: self.router.extract(),
+			ids: ids.into_iter().map(IntoRecordId::into_record_id).collect(),
+			response_type: PhantomData,
+		}
+	}
+
+	/// Traverses the graph from a record, following the edges between records
+	///
+	/// Each step of the traversal follows the edges in an edge table to the records at
+	/// their other end. The records which are reached are returned once each.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[derive(serde::Deserialize)]
+	/// # struct Person;
+	/// # #[derive(serde::Deserialize)]
+	/// # struct Company;
+	/// #
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Select the companies which the people Tobie knows work at
+	/// let companies: Vec<Company> =
+	///     db.traverse("person:tobie").out("knows").out("works_at").await?;
+	///
+	/// // Follow up to three hops of friendships
+	/// let people: Vec<Person> = db.traverse("person:tobie").out("knows").depth(1..=3).await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn traverse<R>(&self, start: impl IntoRecordId) -> Traverse<C, R> {
+		Traverse {
+			router: self.router.extract(),
+			start: start.into_record_id(),
+			steps: Vec::new(),
+			depth: (Bound::Included(1), Bound::Included(1)),
+			response_type: PhantomData,
+		}
+	}
+
 	/// Creates a record in the database
 	///
 	/// # Examples
@@ -711,6 +947,9 @@ where
 		Create {
 			router: self.router.extract(),
 			resource: resource.into_resource(),
+			condition: None,
+			output: None,
+			hash_id: None,
 			response_type: PhantomData,
 		}
 	}
@@ -822,6 +1061,7 @@ where
 	/// ```no_run
 	/// use serde::Serialize;
 	/// use surrealdb::opt::PatchOp;
+	/// use surrealdb::path;
 	/// use time::OffsetDateTime;
 	///
 	/// # #[derive(serde::Deserialize)]
@@ -861,6 +1101,15 @@ where
 	///     .patch(PatchOp::add("/tags", ["developer", "engineer"]))
 	///     .patch(PatchOp::remove("/temp"))
 	///     .await?;
+	///
+	/// // Apply a list of changes, only if the record is still active
+	/// let person: Option<Person> = db.update(("person", "tobie"))
+	///     .patch([
+	///         PatchOp::test(path!["settings", "active"], true),
+	///         PatchOp::r#move(path!["tags"], path!["skills"]),
+	///         PatchOp::copy(path!["skills", 0], path!["main_skill"]),
+	///     ])
+	///     .await?;
 	/// #
 	/// # Ok(())
 	/// # }
@@ -870,6 +1119,8 @@ where
 			router: self.router.extract(),
 			resource: resource.into_resource(),
 			range: None,
+			condition: None,
+			output: None,
 			response_type: PhantomData,
 		}
 	}
@@ -903,11 +1154,13 @@ where
 			router: self.router.extract(),
 			resource: resource.into_resource(),
 			range: None,
+			condition: None,
+			output: None,
 			response_type: PhantomData,
 		}
 	}
 
-	/// Returns the version of the server
+	/// Counts the records in a table
 	///
 	/// # Examples
 	///
@@ -915,17 +1168,28 @@ where
 	/// # #[tokio::main]
 	/// # async fn main() -> surrealdb::Result<()> {
 	/// # let db = surrealdb::engine::any::connect("mem://").await?;
-	/// let version = db.version().await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Count the people
+	/// let people: u64 = db.count("person").await?;
+	/// #
 	/// # Ok(())
 	/// # }
 	/// ```
-	pub fn version(&self) -> Version<C> {
-		Version {
+	pub fn count(&self, table: impl Into<String>) -> Aggregate<C, u64> {
+		Aggregate {
 			router: self.router.extract(),
+			table: table.into(),
+			aggregation: Aggregation::Count,
+			response_type: PhantomData,
 		}
 	}
 
-	/// Checks whether the server is healthy or not
+	/// Sums a numeric field, with nested fields separated by dots, over the records in a table
+	///
+	/// The sum of a table without any records is zero.
 	///
 	/// # Examples
 	///
@@ -933,100 +1197,871 @@ where
 	/// # #[tokio::main]
 	/// # async fn main() -> surrealdb::Result<()> {
 	/// # let db = surrealdb::engine::any::connect("mem://").await?;
-	/// db.health().await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Sum the totals of the orders
+	/// let revenue: f64 = db.sum("order", "total").await?;
+	/// #
 	/// # Ok(())
 	/// # }
 	/// ```
-	pub fn health(&self) -> Health<C> {
-		Health {
+	pub fn sum(&self, table: impl Into<String>, field: impl Into<String>) -> Aggregate<C, f64> {
+		Aggregate {
 			router: self.router.extract(),
+			table: table.into(),
+			aggregation: Aggregation::Sum(field.into()),
+			response_type: PhantomData,
 		}
 	}
 
-	#[doc(hidden)] // Not supported yet
-	pub fn kill(&self, query_id: Uuid) -> Kill<C> {
-		Kill {
+	/// Averages a numeric field, with nested fields separated by dots, over the records in a table
+	///
+	/// A table without any records has no average, so `None` is returned.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Average the ages of the people
+	/// let age: Option<f64> = db.avg("person", "age").await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn avg(
+		&self,
+		table: impl Into<String>,
+		field: impl Into<String>,
+	) -> Aggregate<C, Option<f64>> {
+		Aggregate {
 			router: self.router.extract(),
-			query_id,
+			table: table.into(),
+			aggregation: Aggregation::Avg(field.into()),
+			response_type: PhantomData,
 		}
 	}
 
-	#[doc(hidden)] // Not supported yet
-	pub fn live(&self, table_name: impl Into<String>) -> Live<C> {
-		Live {
+	/// Searches a table for the records whose vector field is nearest to a query vector,
+	/// or whose text field matches a full-text query
+	///
+	/// A vector search is answered by the `MTREE` index on the field. The records are
+	/// returned nearest first, along with their distance from the query vector.
+	///
+	/// A full-text search is answered by the `SEARCH` index on the field. The records are
+	/// returned most relevant first, along with their score and the highlighted field.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[derive(serde::Deserialize, Debug)]
+	/// # struct Document;
+	/// #
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Find the 10 published documents nearest to the query vector
+	/// let results: Vec<surrealdb::method::SearchResult<Document>> = db
+	///     .search("document")
+	///     .vector("embedding", vec![0.1, 0.2, 0.3])
+	///     .knn(10)
+	///     .filter("published = true")
+	///     .await?;
+	///
+	/// // Find the articles whose title matches the query
+	/// let results: Vec<surrealdb::method::TextSearchResult<Document>> = db
+	///     .search("article")
+	///     .matches("title", "rust async")
+	///     .highlight("<em>", "</em>")
+	///     .await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn search(&self, table: impl Into<String>) -> Search<C> {
+		Search {
 			router: self.router.extract(),
-			table_name: table_name.into(),
+			table: table.into(),
 		}
 	}
 
-	/// Dumps the database contents to a file
+	/// Returns the statistics of a full-text index
 	///
-	/// # Support
+	/// The statistics include the number of indexed documents, and the sizes of the
+	/// trees which store the terms and postings of the index.
 	///
-	/// Currently only supported by HTTP and the local engines. *Not* supported on WebAssembly.
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// let stats = db.index_stats("article", "article_title").await?;
+	/// println!("{} documents, {} terms", stats.doc_count, stats.terms.keys_count);
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn index_stats(&self, table: impl Into<String>, index: impl Into<String>) -> IndexStats<C> {
+		IndexStats {
+			router: self.router.extract(),
+			table: table.into(),
+			index: index.into(),
+		}
+	}
+
+	/// Estimates the cost of a query, without running it
+	///
+	/// The query planner is asked how each statement which reads or writes existing records
+	/// iterates over them. The records in the tables which would be scanned, and the records
+	/// which match the conditions of indexed statements, are counted to estimate how many
+	/// records the query reads. This makes it possible to refuse expensive ad-hoc queries
+	/// before they run.
 	///
 	/// # Examples
 	///
 	/// ```no_run
-	/// # use futures::StreamExt;
 	/// # #[tokio::main]
 	/// # async fn main() -> surrealdb::Result<()> {
 	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
 	/// // Select the namespace/database to use
 	/// db.use_ns("namespace").use_db("database").await?;
 	///
-	/// // Export to a file
-	/// db.export("backup.sql").await?;
+	/// let query = "SELECT * FROM person WHERE email = 'tobie@surrealdb.com'";
+	/// let cost = db.estimate(query).await?;
+	/// if cost.requires_table_scan() || cost.rows > 10_000 {
+	///     // Refuse to run the query
+	/// }
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn estimate(&self, query: impl opt::IntoQuery) -> Estimate<C> {
+		Estimate {
+			router: self.router.extract(),
+			query: query.into_query(),
+		}
+	}
+
+	/// Proposes a schema for a schemaless table, from a sample of its records
 	///
-	/// // Export to a stream of bytes
-	/// let mut backup = db.export(()).await?;
-	/// while let Some(result) = backup.next().await {
-	///     match result {
-	///         Ok(bytes) => {
-	///             // Do something with the bytes received...
-	///         }
-	///         Err(error) => {
-	///             // Handle the export error
-	///         }
-	///     }
+	/// Up to `sample_size` records are read from the table, and a `DEFINE FIELD`
+	/// statement is proposed for each of the fields found in them, with the kind
+	/// inferred from the values. The statements can be reviewed, and then run along
+	/// with `DEFINE TABLE ... SCHEMAFULL`, to migrate the table to a schema.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Propose field definitions from the first 100 records
+	/// for statement in db.infer_schema("person", 100).await? {
+	///     println!("{statement};");
 	/// }
+	/// #
 	/// # Ok(())
 	/// # }
 	/// ```
-	pub fn export<R>(&self, target: impl IntoExportDestination<R>) -> Export<C, R> {
-		Export {
+	pub fn infer_schema(&self, table: impl Into<String>, sample_size: usize) -> InferSchema<C> {
+		InferSchema {
 			router: self.router.extract(),
-			target: target.into_export_destination(),
-			response: PhantomData,
+			table: table.into(),
+			sample_size,
 		}
 	}
 
-	/// Restores the database from a file
+	/// Defines an index on a table
 	///
-	/// # Support
+	/// The definition is built up with typed methods, so schema migrations don't need
+	/// to concatenate `DEFINE INDEX` statements. Defining an index which already exists
+	/// replaces it, and rebuilds it from the records in the table.
 	///
-	/// Currently only supported by HTTP and the local engines. *Not* supported on WebAssembly.
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // A composite index, which allows each name only once per country
+	/// db.define_index("person")
+	///     .name("person_name")
+	///     .columns(["name", "address.country"])
+	///     .unique()
+	///     .await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn define_index(&self, table: impl Into<String>) -> DefineIndex<C> {
+		DefineIndex {
+			router: self.router.extract(),
+			statement: DefineIndexStatement {
+				what: Ident(table.into()),
+				..Default::default()
+			},
+		}
+	}
+
+	/// Defines a field on a table
 	///
 	/// # Examples
 	///
 	/// ```no_run
+	/// use surrealdb::expr::field;
+	/// use surrealdb::sql::Kind;
+	///
 	/// # #[tokio::main]
 	/// # async fn main() -> surrealdb::Result<()> {
 	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
 	/// // Select the namespace/database to use
 	/// db.use_ns("namespace").use_db("database").await?;
 	///
-	/// db.import("backup.sql").await?;
+	/// db.define_field("person").name("age").kind(Kind::Int).default(0).await?;
+	///
+	/// // A computed field, which is set whenever a record is written
+	/// db.define_field("person").name("adult").value(field("age").gte(18)).await?;
+	/// #
 	/// # Ok(())
 	/// # }
 	/// ```
-	pub fn import<P>(&self, file: P) -> Import<C>
-	where
-		P: AsRef<Path>,
-	{
+	pub fn define_field(&self, table: impl Into<String>) -> DefineField<C> {
+		DefineField {
+			router: self.router.extract(),
+			statement: DefineFieldStatement {
+				what: Ident(table.into()),
+				..Default::default()
+			},
+		}
+	}
+
+	/// Defines an event on a table
+	///
+	/// The event runs its statements whenever a record of the table is created, updated
+	/// or deleted, unless it's limited with a [`when`](DefineEvent::when) condition.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// db.define_event("person")
+	///     .name("audit")
+	///     .then("CREATE audit SET person = $value.id, event = $event")
+	///     .await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn define_event(&self, table: impl Into<String>) -> DefineEvent<C> {
+		DefineEvent {
+			router: self.router.extract(),
+			statement: Ok(DefineEventStatement {
+				what: Ident(table.into()),
+				when: Value::Bool(true),
+				..Default::default()
+			}),
+		}
+	}
+
+	/// Defines an analyzer, which splits text into the terms of a full-text index
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::sql::Filter;
+	/// use surrealdb::sql::Tokenizer;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// db.define_analyzer("simple")
+	///     .tokenizers([Tokenizer::Blank, Tokenizer::Class])
+	///     .filters([Filter::Lowercase])
+	///     .await?;
+	/// db.define_index("article")
+	///     .name("article_title")
+	///     .columns(["title"])
+	///     .search("simple")
+	///     .await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn define_analyzer(&self, name: impl Into<String>) -> DefineAnalyzer<C> {
+		DefineAnalyzer {
+			router: self.router.extract(),
+			statement: DefineAnalyzerStatement {
+				name: Ident(name.into()),
+				..Default::default()
+			},
+		}
+	}
+
+	/// Applies the pending migrations to the selected database
+	///
+	/// The versions which have been applied are stored in the `_migrations` table. See the
+	/// [`migrations`](crate::migrations) module for more details.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::migrations::Migration;
+	/// use surrealdb::migrations::Migrations;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// let migrations = Migrations::new()
+	///     .add(Migration::sql(1, "create users", "DEFINE TABLE user").down_sql("REMOVE TABLE user"));
+	///
+	/// // Apply all pending migrations
+	/// db.migrate(&migrations).await?;
+	///
+	/// // Revert all migrations
+	/// db.migrate(&migrations).to(0).await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn migrate<'r>(&'r self, migrations: &'r Migrations<C>) -> Migrate<'r, C> {
+		Migrate {
+			client: self,
+			migrations,
+			target: None,
+		}
+	}
+
+	/// Writes seed data to the database
+	///
+	/// The namespace and the database of the seed data are defined, if they don't exist yet,
+	/// and the seed data is written to them without changing the namespace and the database
+	/// which the client uses. Its schema and its records are written in a single transaction.
+	/// Records are upserted by their ID, so the same seed data can be written more than once.
+	/// See the [`seed`](crate::seed) module for more details.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::seed::Seed;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// db.seed(Seed::from_json("seed.json")?).await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn seed(&self, seed: Seed) -> Seeding<C> {
+		Seeding {
+			client: self,
+			seed,
+		}
+	}
+
+	/// Submits a unit of work, running all of its writes in a single transaction
+	///
+	/// See the [`unit_of_work`](crate::unit_of_work) module for more details.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::unit_of_work::UnitOfWork;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// let mut work = UnitOfWork::new();
+	/// let tobie = work.create("person", ());
+	/// work.relate(tobie, "likes", ("person", "jaime"), ());
+	/// work.delete(("person", "john"));
+	///
+	/// let committed = db.submit(work).await?;
+	/// println!("Created {}", committed[tobie]);
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn submit(&self, work: UnitOfWork) -> Submit<C> {
+		Submit {
+			client: self,
+			work,
+		}
+	}
+
+	/// Rebuilds an index from the records in its table
+	///
+	/// The index is rebuilt in the background, in batches, so that writes to the table
+	/// are not blocked for the duration of the rebuild. Queries don't use the index until
+	/// it has been rebuilt. Awaiting the method only starts the rebuild, use
+	/// [`progress`](RebuildIndex::progress) to follow it until it completes.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use futures::StreamExt;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Rebuild the index, reporting its progress
+	/// let mut progress = db.rebuild_index("person", "person_email").progress();
+	/// while let Some(result) = progress.next().await {
+	///     let progress = result?;
+	///     println!("indexed {} of {} records", progress.processed, progress.total);
+	/// }
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn rebuild_index(
+		&self,
+		table: impl Into<String>,
+		index: impl Into<String>,
+	) -> RebuildIndex<C> {
+		RebuildIndex {
+			router: self.router.extract(),
+			table: table.into(),
+			index: index.into(),
+			interval: rebuild_index::POLL_INTERVAL,
+		}
+	}
+
+	/// Returns the version of the server
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// let version = db.version().await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn version(&self) -> Version<C> {
+		Version {
+			router: self.router.extract(),
+		}
+	}
+
+	/// Reads the changes made to a table with a change feed
+	///
+	/// The table must be defined with a `CHANGEFEED`. Awaiting the returned future
+	/// reads the changes made so far, while [`Changes::stream`] follows the table
+	/// and returns new changes as they are committed.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use futures::StreamExt;
+	/// use serde::Deserialize;
+	/// use surrealdb::method::Change;
+	///
+	/// #[derive(Debug, Deserialize)]
+	/// struct Person {
+	///     name: String,
+	/// }
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// // Read all the changes made since versionstamp 10
+	/// let changes = db.changes::<Person>("person").since(10).await?;
+	///
+	/// // Follow the changes made to the table
+	/// let mut stream = db.changes::<Person>("person").since(10).stream();
+	/// while let Some(result) = stream.next().await {
+	///     for change in result?.changes {
+	///         match change {
+	///             Change::Update(person) => println!("updated {person:?}"),
+	///             Change::Delete(id) => println!("deleted {id}"),
+	///             _ => {}
+	///         }
+	///     }
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn changes<R>(&self, table: impl Into<String>) -> Changes<C, R> {
+		Changes {
+			router: self.router.extract(),
+			table: table.into(),
+			since: Default::default(),
+			limit: None,
+			interval: changes::POLL_INTERVAL,
+			response_type: PhantomData,
+		}
+	}
+
+	/// Reads which records were changed in a set of tables, for invalidating external caches
+	///
+	/// Unlike [`changes`](Self::changes), only the table, ID and action of each changed
+	/// record is returned, along with the versionstamp it was changed at, and the changes to
+	/// all the tables are read in a single request. The tables must be defined with a
+	/// `CHANGEFEED`.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use futures::StreamExt;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// let mut events = db.invalidation_events(["person", "company"]).since(10).stream();
+	/// while let Some(event) = events.next().await {
+	///     let event = event?;
+	///     println!("evict {} ({:?} at {})", event.id, event.action, event.versionstamp);
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn invalidation_events<I>(&self, tables: I) -> InvalidationEvents<C>
+	where
+		I: IntoIterator,
+		I::Item: Into<String>,
+	{
+		InvalidationEvents {
+			router: self.router.extract(),
+			tables: tables.into_iter().map(Into::into).collect(),
+			since: Default::default(),
+			limit: None,
+			interval: changes::POLL_INTERVAL,
+		}
+	}
+
+	/// Subscribes to the lifecycle events of the connection
+	///
+	/// Events are emitted when the connection to the server is lost, when it's
+	/// re-established and when the session is authenticated again after reconnecting.
+	/// Only events which happen after subscribing are received.
+	///
+	/// # Support
+	///
+	/// Currently only the WebSocket engine emits events, as it's the only one which
+	/// keeps a persistent connection to the server.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use futures::StreamExt;
+	/// use surrealdb::method::Event;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("ws://localhost:8000").await?;
+	/// let mut events = db.on_event()?;
+	///
+	/// while let Some(event) = events.next().await {
+	///     match event {
+	///         Event::Disconnected { reason } => {
+	///             // Mark the database as unavailable...
+	///         }
+	///         Event::Connected => {
+	///             // Flush any caches...
+	///         }
+	///         _ => {}
+	///     }
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn on_event(&self) -> Result<Events> {
+		Ok(self.router.extract()?.events.subscribe())
+	}
+
+	/// Reports the depth of the request queue of the connection
+	///
+	/// Requests are queued until they are sent to the server, and are in flight until
+	/// their response arrives. See [`Config::max_concurrent_requests`](crate::opt::Config::max_concurrent_requests)
+	/// for limiting the requests which are in flight at once.
+	///
+	/// # Support
+	///
+	/// Currently only the WebSocket engine keeps track of the requests which are in flight,
+	/// so they are always reported as zero for the other engines.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::opt::Config;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// let config = Config::new().max_concurrent_requests(128);
+	/// let db = surrealdb::engine::any::connect(("ws://localhost:8000", config)).await?;
+	///
+	/// let metrics = db.request_metrics()?;
+	/// println!("{} queued, {} in flight", metrics.queued, metrics.in_flight);
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn request_metrics(&self) -> Result<RequestMetrics> {
+		let router = self.router.extract()?;
+		Ok(router.in_flight.metrics(router.sender.len()))
+	}
+
+	/// Checks whether the server is healthy or not
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// db.health().await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn health(&self) -> Health<C> {
+		Health {
+			router: self.router.extract(),
+		}
+	}
+
+	#[doc(hidden)] // Not supported yet
+	pub fn kill(&self, query_id: Uuid) -> Kill<C> {
+		Kill {
+			router: self.router.extract(),
+			query_id,
+		}
+	}
+
+	/// Subscribes to the changes of the records in a table
+	///
+	/// Callers which subscribe to the same table with the same condition, in the same namespace
+	/// and database and with the same authentication, share a single live query on the server,
+	/// and each of them receives all of its notifications. The live query is killed once all of
+	/// its subscriptions have been dropped. A subscription which falls too far behind ends with
+	/// an error.
+	///
+	/// # Support
+	///
+	/// Currently only supported by the WebSocket engine and the embedded engines, when they are
+	/// configured to send live query notifications.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use futures::StreamExt;
+	/// use surrealdb::expr::field;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("ws://localhost:8000").await?;
+	/// let mut people = db.live("person").await?;
+	/// let mut adults = db.live("person").filter(field("age").gte(18)).await?;
+	///
+	/// while let Some(notification) = people.next().await {
+	///     let notification = notification?;
+	///     println!("{:?} {}", notification.action, notification.result);
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn live(&self, table_name: impl Into<String>) -> Live<C> {
+		Live {
+			client: self.clone(),
+			table_name: table_name.into(),
+			condition: None,
+		}
+	}
+
+	/// Dumps the database contents to a file
+	///
+	/// # Support
+	///
+	/// Currently only supported by HTTP and the local engines. *Not* supported on WebAssembly.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # use futures::StreamExt;
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Export to a file
+	/// db.export("backup.sql").await?;
+	///
+	/// // Export to anything which implements `AsyncWrite`
+	/// let mut file = tokio::fs::File::create("backup.sql").await.unwrap();
+	/// db.export(&mut file).await?;
+	///
+	/// // Export to a stream of bytes
+	/// let mut backup = db.export(()).await?;
+	/// while let Some(result) = backup.next().await {
+	///     match result {
+	///         Ok(bytes) => {
+	///             // Do something with the bytes received...
+	///         }
+	///         Err(error) => {
+	///             // Handle the export error
+	///         }
+	///     }
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn export<R>(&self, target: impl IntoExportDestination<R>) -> Export<C, R> {
+		Export {
+			router: self.router.extract(),
+			target: target.into_export_destination(),
+		}
+	}
+
+	/// Restores the database from a file or a reader
+	///
+	/// # Support
+	///
+	/// Importing from a file is currently only supported by HTTP and the local engines.
+	/// Importing from anything which implements `AsyncRead` is supported by all engines.
+	/// *Not* supported on WebAssembly.
+	///
+	/// A reader is imported as it is read, in transactions of up to a thousand statements,
+	/// so an import which fails part of the way through leaves the earlier batches in place.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Import from a file
+	/// db.import("backup.sql").await?;
+	///
+	/// // Import from anything which implements `AsyncRead`
+	/// let mut file = tokio::fs::File::open("backup.sql").await.unwrap();
+	/// db.import(&mut file).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn import<R>(&self, source: impl IntoImportSource<R>) -> Import<C, R> {
 		Import {
 			router: self.router.extract(),
-			file: file.as_ref().to_owned(),
+			source: source.into_import_source(),
+		}
+	}
+
+	/// Stores a binary blob for a record, replacing any blob which it already has
+	///
+	/// The blob is streamed from the reader in chunks, which are stored under separate keys
+	/// rather than in a field of the record, so that large binaries are never held in memory
+	/// as a whole. The blob is removed along with its record. Resolves to the size of the
+	/// blob in bytes.
+	///
+	/// # Support
+	///
+	/// Currently only supported by HTTP and the local engines. *Not* supported on WebAssembly.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Store a file as the blob of a record
+	/// let mut file = tokio::fs::File::open("avatar.png").await.unwrap();
+	/// let size = db.put_blob(("person", "tobie"), &mut file).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn put_blob<R>(&self, resource: impl IntoRecordId, reader: R) -> PutBlob<C, R> {
+		PutBlob {
+			router: self.router.extract(),
+			resource: resource.into_record_id(),
+			reader,
+		}
+	}
+
+	/// Reads the binary blob of a record
+	///
+	/// The blob is streamed from the database in chunks, and can be consumed either as a
+	/// stream of chunks, or through `AsyncRead`.
+	///
+	/// # Support
+	///
+	/// Currently only supported by HTTP and the local engines.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// // Select the namespace/database to use
+	/// db.use_ns("namespace").use_db("database").await?;
+	///
+	/// // Copy the blob of a record to a file
+	/// let mut blob = db.get_blob(("person", "tobie")).await?;
+	/// let mut file = tokio::fs::File::create("avatar.png").await.unwrap();
+	/// tokio::io::copy(&mut blob, &mut file).await.unwrap();
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn get_blob(&self, resource: impl IntoRecordId) -> GetBlob<C> {
+		GetBlob {
+			router: self.router.extract(),
+			resource: resource.into_record_id(),
 		}
 	}
 }
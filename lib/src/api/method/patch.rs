@@ -1,6 +1,7 @@
 use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
+use crate::api::method::condition::Condition;
 use crate::api::opt::PatchOp;
 use crate::api::opt::Range;
 use crate::api::opt::Resource;
@@ -8,6 +9,7 @@ use crate::api::Connection;
 use crate::api::Result;
 use crate::sql::Array;
 use crate::sql::Id;
+use crate::sql::Output;
 use crate::sql::Value;
 use serde::de::DeserializeOwned;
 use std::future::Future;
@@ -23,6 +25,8 @@ pub struct Patch<'r, C: Connection, R> {
 	pub(super) router: Result<&'r Router<C>>,
 	pub(super) resource: Result<Resource>,
 	pub(super) range: Option<Range<Id>>,
+	pub(super) condition: Option<Condition>,
+	pub(super) output: Option<Output>,
 	pub(super) patches: Vec<StdResult<Value, crate::err::Error>>,
 	pub(super) response_type: PhantomData<R>,
 }
@@ -34,6 +38,8 @@ macro_rules! into_future {
 				router,
 				resource,
 				range,
+				condition,
+				output,
 				patches,
 				..
 			} = self;
@@ -47,6 +53,12 @@ macro_rules! into_future {
 					vec.push(result?);
 				}
 				let patches = Value::Array(Array(vec));
+				if condition.is_some() || output.is_some() {
+					let condition = condition.unwrap_or(Condition::always(Method::Patch));
+					return condition
+						.$method(router?, param, Some(("PATCH", patches)), output)
+						.await;
+				}
 				let mut conn = Client::new(Method::Patch);
 				conn.$method(router?, Param::new(vec![param, patches])).await
 			})
@@ -91,8 +103,19 @@ where
 	C: Connection,
 {
 	/// Applies JSON Patch changes to all records, or a specific record, in the database.
-	pub fn patch(mut self, PatchOp(patch): PatchOp) -> Patch<'r, C, R> {
-		self.patches.push(patch);
+	///
+	/// Accepts a single [`PatchOp`], or any iterator of them.
+	pub fn patch(mut self, patches: impl IntoIterator<Item = PatchOp>) -> Patch<'r, C, R> {
+		self.patches.extend(patches.into_iter().map(|PatchOp(patch)| patch));
+		self
+	}
+
+	/// Runs the write without applying it, returning the changes it would have made
+	///
+	/// See [`Update::dry_run`](crate::method::Update::dry_run).
+	pub fn dry_run(mut self) -> Self {
+		self.condition = Some(Condition::dry_run(self.condition.take(), Method::Patch));
+		self.output = Some(Output::Diff);
 		self
 	}
 }
@@ -2,26 +2,42 @@ use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
 use crate::api::err::Error;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::api::method::blob::upload;
 use crate::api::opt;
 use crate::api::Connection;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::api::ExtraFeatures;
 use crate::api::Result;
 use crate::sql;
+use crate::sql::statements::OptionStatement;
 use crate::sql::to_value;
 use crate::sql::Array;
+use crate::sql::Datetime;
+use crate::sql::Ident;
 use crate::sql::Object;
+use crate::sql::Output;
 use crate::sql::Statement;
 use crate::sql::Statements;
 use crate::sql::Strand;
 use crate::sql::Value;
+use crate::sql::Version;
+use indexmap::map::Entry;
 use indexmap::IndexMap;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fmt;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::mem;
 use std::pin::Pin;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::AsyncRead;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::AsyncReadExt;
 
 /// A query future
 #[derive(Debug)]
@@ -30,6 +46,76 @@ pub struct Query<'r, C: Connection> {
 	pub(super) router: Result<&'r Router<C>>,
 	pub(super) query: Vec<Result<Vec<Statement>>>,
 	pub(super) bindings: Result<BTreeMap<String, Value>>,
+	pub(super) idempotent: bool,
+	pub(super) at: Option<Datetime>,
+	pub(super) dry_run: bool,
+	pub(super) chunked: Option<Chunked>,
+	#[cfg(not(target_arch = "wasm32"))]
+	pub(super) streamed: Option<Streamed<'r>>,
+}
+
+/// Adds bindings, either a `(key, value)` tuple or a struct or map, to the bindings of a query
+pub(super) fn bind(current: &mut Result<BTreeMap<String, Value>>, bindings: impl Serialize) {
+	if let Ok(map) = current {
+		match to_value(bindings) {
+			Ok(mut bindings) => {
+				if let Value::Array(Array(array)) = &mut bindings {
+					if let [Value::Strand(Strand(key)), value] = &mut array[..] {
+						let mut map = BTreeMap::new();
+						map.insert(mem::take(key), mem::take(value));
+						bindings = map.into();
+					}
+				}
+				match &mut bindings {
+					Value::Object(Object(bindings)) => map.append(bindings),
+					_ => {
+						*current = Err(Error::InvalidBindings(bindings).into());
+					}
+				}
+			}
+			Err(error) => {
+				*current = Err(error.into());
+			}
+		}
+	}
+}
+
+/// A list which is bound in chunks, running the query once for each chunk
+#[derive(Debug)]
+pub(super) struct Chunked {
+	key: String,
+	values: Vec<Value>,
+	size: usize,
+}
+
+/// A parameter whose value is read from a reader when the query is sent
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) struct Streamed<'r> {
+	key: String,
+	reader: Pin<Box<dyn AsyncRead + Send + 'r>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl fmt::Debug for Streamed<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Streamed").field("key", &self.key).finish_non_exhaustive()
+	}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Streamed<'_> {
+	/// Reads the whole value, for connections which can't stream it
+	async fn read(mut self) -> Result<Value> {
+		let mut text = String::new();
+		if let Err(error) = self.reader.read_to_string(&mut text).await {
+			return Err(Error::ParamRead {
+				key: self.key,
+				error,
+			}
+			.into());
+		}
+		Ok(sql::value(&text)?)
+	}
 }
 
 impl<'r, Client> IntoFuture for Query<'r, Client>
@@ -45,10 +131,92 @@ where
 			for query in self.query {
 				statements.extend(query?);
 			}
+			if let Some(at) = self.at {
+				for statement in &mut statements {
+					if let Statement::Select(stm) = statement {
+						stm.version.get_or_insert_with(|| Version(at.clone()));
+					}
+				}
+			}
+			if self.dry_run {
+				for statement in &mut statements {
+					let output = match statement {
+						Statement::Create(stm) => &mut stm.output,
+						Statement::Update(stm) => &mut stm.output,
+						Statement::Delete(stm) => &mut stm.output,
+						Statement::Relate(stm) => &mut stm.output,
+						Statement::Insert(stm) => &mut stm.output,
+						_ => continue,
+					};
+					output.get_or_insert(Output::Diff);
+				}
+				let option = OptionStatement {
+					name: Ident("DRY_RUN".to_owned()),
+					what: true,
+				};
+				statements.insert(0, Statement::Option(option));
+			}
 			let query = sql::Query(Statements(statements));
-			let param = Param::query(query, self.bindings?);
-			let mut conn = Client::new(Method::Query);
-			conn.execute_query(self.router?, param).await
+			#[allow(unused_mut)]
+			let mut bindings = self.bindings?;
+			let router = self.router?;
+			#[cfg(not(target_arch = "wasm32"))]
+			if let Some(streamed) = self.streamed {
+				// The value is streamed to the server as it is read, unless the query is chunked
+				if self.chunked.is_none()
+					&& router.features.contains(&ExtraFeatures::StreamedParams)
+				{
+					let Streamed {
+						key,
+						reader,
+					} = streamed;
+					let (tx, rx) = crate::channel::new(1);
+					let mut param = Param::receiver(rx);
+					param.query = Some((query, bindings));
+					param.other = vec![key.clone().into()];
+					let upload = upload(reader, tx, |error| {
+						Error::ParamRead {
+							key: key.clone(),
+							error,
+						}
+						.into()
+					});
+					let mut conn = Client::new(Method::Query);
+					let (response, ()) = futures::join!(conn.execute_query(router, param), upload);
+					return response;
+				}
+				bindings.insert(streamed.key.clone(), streamed.read().await?);
+			}
+			let Some(Chunked {
+				key,
+				values,
+				size,
+			}) = self.chunked
+			else {
+				let mut param = Param::query(query, bindings);
+				if self.idempotent {
+					param = param.idempotent();
+				}
+				let mut conn = Client::new(Method::Query);
+				return conn.execute_query(router, param).await;
+			};
+			// An empty list is still bound, so that the query runs once
+			let chunks: Vec<&[Value]> = match values.is_empty() {
+				true => vec![values.as_slice()],
+				false => values.chunks(size).collect(),
+			};
+			let mut response = Response(IndexMap::new());
+			for chunk in chunks {
+				let mut bindings = bindings.clone();
+				bindings.insert(key.clone(), chunk.to_vec().into());
+				let mut param = Param::query(query.clone(), bindings);
+				if self.idempotent {
+					param = param.idempotent();
+				}
+				let mut conn = Client::new(Method::Query);
+				response.merge(conn.execute_query(router, param).await?);
+			}
+			Ok(response)
 		})
 	}
 }
@@ -63,6 +231,84 @@ where
 		self
 	}
 
+	/// Marks the query as safe to send more than once
+	///
+	/// Idempotent queries are retried according to the [`RetryPolicy`] of the
+	/// connection if they fail because of a connection error.
+	///
+	/// [`RetryPolicy`]: crate::opt::RetryPolicy
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// let response = db.query("UPDATE user:john SET name = $name")
+	///     .bind(("name", "John Doe"))
+	///     .idempotent()
+	///     .await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn idempotent(mut self) -> Self {
+		self.idempotent = true;
+		self
+	}
+
+	/// Reads the data as it was at a point in time
+	///
+	/// Every `SELECT` statement in the query, which doesn't have its own `VERSION` clause,
+	/// reads the data as it was at the specified time, so that reads across many tables are
+	/// consistent with each other while writes continue. This is only supported by storage
+	/// engines which keep historical versions of the data, such as TiKV and FoundationDB.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::sql::Datetime;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("tikv://localhost:2379").await?;
+	/// let at = Datetime::default();
+	/// let response = db.query("SELECT * FROM user; SELECT * FROM account")
+	///     .at(at)
+	///     .await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn at(mut self, at: impl Into<Datetime>) -> Self {
+		self.at = Some(at.into());
+		self
+	}
+
+	/// Runs the query without applying any of its writes
+	///
+	/// The query runs in transactions which are always cancelled, so nothing is changed in the
+	/// database and no live queries are notified. Write statements without their own `RETURN`
+	/// clause return the changes they would have made, as JSON Patch operations.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::sql::Value;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// let mut response = db.query("DELETE user WHERE active = false")
+	///     .dry_run()
+	///     .await?;
+	/// let changes: Value = response.take(0)?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn dry_run(mut self) -> Self {
+		self.dry_run = true;
+		self
+	}
+
 	/// Binds a parameter or parameters to a query
 	///
 	/// # Examples
@@ -105,26 +351,88 @@ where
 	/// # }
 	/// ```
 	pub fn bind(mut self, bindings: impl Serialize) -> Self {
-		if let Ok(current) = &mut self.bindings {
-			match to_value(bindings) {
-				Ok(mut bindings) => {
-					if let Value::Array(Array(array)) = &mut bindings {
-						if let [Value::Strand(Strand(key)), value] = &mut array[..] {
-							let mut map = BTreeMap::new();
-							map.insert(mem::take(key), mem::take(value));
-							bindings = map.into();
-						}
-					}
-					match &mut bindings {
-						Value::Object(Object(map)) => current.append(map),
-						_ => {
-							self.bindings = Err(Error::InvalidBindings(bindings).into());
-						}
-					}
-				}
-				Err(error) => {
-					self.bindings = Err(error.into());
-				}
+		bind(&mut self.bindings, bindings);
+		self
+	}
+
+	/// Binds a parameter to a value which is read from a reader, such as a file
+	///
+	/// The content of the reader is parsed as a SurrealQL value, of which JSON is a subset.
+	/// The HTTP engine streams the content to the server as it is read, so large payloads
+	/// are never held in memory as a whole on the client. Other engines read the whole
+	/// value before the query is sent. Queries which stream a parameter are never retried.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("http://localhost:8000").await?;
+	/// let file = tokio::fs::File::open("people.json").await.unwrap();
+	/// let response = db.query("INSERT INTO person $data")
+	///     .bind_reader("data", file)
+	///     .await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn bind_reader(
+		mut self,
+		key: impl Into<String>,
+		reader: impl AsyncRead + Send + 'r,
+	) -> Self {
+		self.streamed = Some(Streamed {
+			key: key.into(),
+			reader: Box::pin(reader),
+		});
+		self
+	}
+
+	/// Binds a list of values to a parameter, such as the `$ids` in `WHERE id IN $ids`
+	///
+	/// Lists which are longer than the chunk size are split into chunks, and the query is run
+	/// once for each chunk. The results of each statement are then merged in order, as if the
+	/// query had run once with the whole list. This keeps very long lists below the size limits
+	/// of the query parser and of the connection. As each chunk runs as a separate query, it is
+	/// meant for reading records, and the chunks are not run in a single transaction. Clauses
+	/// such as `ORDER BY` and `LIMIT` apply to each chunk on its own.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::sql::Thing;
+	///
+	/// # #[derive(serde::Deserialize)]
+	/// # struct User;
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// # let ids: Vec<Thing> = Vec::new();
+	/// let users: Vec<User> = db
+	///     .query("SELECT * FROM user WHERE id IN $ids")
+	///     .bind_chunked("ids", ids, 1000)
+	///     .await?
+	///     .take(0)?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn bind_chunked(
+		mut self,
+		key: impl Into<String>,
+		values: impl IntoIterator<Item = impl Serialize>,
+		size: usize,
+	) -> Self {
+		let values: std::result::Result<Vec<Value>, _> = values.into_iter().map(to_value).collect();
+		match values {
+			Ok(values) => {
+				self.chunked = Some(Chunked {
+					key: key.into(),
+					values,
+					size: size.max(1),
+				});
+			}
+			Err(error) => {
+				self.bindings = Err(error.into());
 			}
 		}
 		self
@@ -138,6 +446,34 @@ pub(crate) type QueryResult = Result<Vec<Value>>;
 pub struct Response(pub(crate) IndexMap<usize, QueryResult>);
 
 impl Response {
+	/// Appends the results of another run of the same query to the results of each statement
+	///
+	/// The first error of a statement is kept.
+	fn merge(&mut self, other: Response) {
+		for (index, result) in other.0 {
+			match self.0.entry(index) {
+				Entry::Vacant(entry) => {
+					entry.insert(result);
+				}
+				Entry::Occupied(mut entry) => {
+					let current = entry.get_mut();
+					match result {
+						Ok(more) => {
+							if let Ok(values) = current {
+								values.extend(more);
+							}
+						}
+						Err(error) => {
+							if current.is_ok() {
+								*current = Err(error);
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+
 	/// Takes and returns records returned from the database
 	///
 	/// A query that only returns one result can be deserialized into an
@@ -314,6 +650,19 @@ mod tests {
 		vec.into_iter().enumerate().collect()
 	}
 
+	#[test]
+	fn merge_chunked_responses() {
+		let mut response = Response(to_map(vec![Ok(vec![1.into()]), Ok(vec![])]));
+		response.merge(Response(to_map(vec![
+			Ok(vec![2.into(), 3.into()]),
+			Err(Error::ConnectionUninitialised.into()),
+		])));
+		response.merge(Response(to_map(vec![Ok(vec![4.into()]), Ok(vec![5.into()])])));
+		let values: Vec<i64> = response.take(0).unwrap();
+		assert_eq!(values, vec![1, 2, 3, 4]);
+		assert!(response.take::<Vec<i64>>(1).is_err());
+	}
+
 	#[test]
 	fn take_from_an_empty_response() {
 		let mut response = Response(Default::default());
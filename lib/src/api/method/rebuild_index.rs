@@ -0,0 +1,233 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::api::engine::runtime::sleep;
+use crate::api::err::Error;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::sql::statements::rebuild::RebuildIndexStatement;
+use crate::sql::statements::InfoStatement;
+use crate::sql::statements::RebuildStatement;
+use crate::sql::Ident;
+use crate::sql::Query;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Value;
+use futures::stream;
+use futures::Stream;
+use futures::StreamExt;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+#[cfg(target_arch = "wasm32")]
+use wasmtimer::tokio::sleep;
+
+/// How long a progress stream waits before checking the progress of a rebuild again
+pub(super) const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The progress of an index rebuild
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RebuildProgress {
+	/// The number of records which have been indexed
+	pub processed: u64,
+	/// The number of records in the table when the rebuild was started
+	pub total: u64,
+}
+
+/// The state of a progress stream
+enum State {
+	Start,
+	Running(RebuildProgress),
+	Done,
+}
+
+/// An index rebuild future
+///
+/// The index is rebuilt in the background, in batches, so that writes to the table are
+/// not blocked while it's rebuilt. Awaiting the future only starts the rebuild, use
+/// [`RebuildIndex::progress`] to follow it until it completes.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct RebuildIndex<'r, C: Connection> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) table: String,
+	pub(super) index: String,
+	pub(super) interval: Duration,
+}
+
+impl<'r, C> RebuildIndex<'r, C>
+where
+	C: Connection,
+{
+	/// Sets how long a progress stream waits before checking the progress again
+	pub fn poll_interval(mut self, interval: Duration) -> Self {
+		self.interval = interval;
+		self
+	}
+
+	/// Starts the rebuild, and follows its progress until it completes
+	///
+	/// The first event is returned once the rebuild has started, and another one whenever
+	/// more records have been indexed. The last event has all the records processed, after
+	/// which the stream ends. The stream also ends after returning an error.
+	pub fn progress(self) -> RebuildStream<'r> {
+		let RebuildIndex {
+			router,
+			table,
+			index,
+			interval,
+		} = self;
+		let router = match router {
+			Ok(router) => router,
+			Err(error) => {
+				return RebuildStream {
+					inner: Box::pin(stream::once(async { Err(error) })),
+				};
+			}
+		};
+		let inner = stream::unfold(State::Start, move |state| {
+			let table = table.clone();
+			let index = index.clone();
+			async move {
+				let last = match state {
+					State::Start => {
+						return match start(router, table, index).await {
+							Ok(Some(progress)) => Some((Ok(progress), State::Running(progress))),
+							Ok(None) => None,
+							Err(error) => Some((Err(error), State::Done)),
+						};
+					}
+					State::Running(last) => last,
+					State::Done => return None,
+				};
+				loop {
+					sleep(interval).await;
+					match read(router, table.clone(), &index).await {
+						// The rebuild is removed once all the records have been indexed
+						Ok(None) => {
+							let done = RebuildProgress {
+								processed: last.total,
+								total: last.total,
+							};
+							return Some((Ok(done), State::Done));
+						}
+						Ok(Some(progress)) if progress != last => {
+							return Some((Ok(progress), State::Running(progress)));
+						}
+						Ok(Some(_)) => {}
+						Err(error) => return Some((Err(error), State::Done)),
+					}
+				}
+			}
+		});
+		RebuildStream {
+			inner: Box::pin(inner),
+		}
+	}
+}
+
+impl<'r, Client> IntoFuture for RebuildIndex<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<()>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let query = Query(Statements(vec![rebuild_statement(self.table, self.index)]));
+			let mut conn = Client::new(Method::Query);
+			let param = Param::query(query, Default::default());
+			conn.execute_query(self.router?, param).await?.check()?;
+			Ok(())
+		})
+	}
+}
+
+/// A stream of the progress of an index rebuild
+#[must_use = "streams do nothing unless you poll them"]
+pub struct RebuildStream<'r> {
+	inner: Pin<Box<dyn Stream<Item = Result<RebuildProgress>> + Send + Sync + 'r>>,
+}
+
+impl std::fmt::Debug for RebuildStream<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("RebuildStream").finish_non_exhaustive()
+	}
+}
+
+impl Stream for RebuildStream<'_> {
+	type Item = Result<RebuildProgress>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		self.inner.poll_next_unpin(cx)
+	}
+}
+
+fn rebuild_statement(table: String, index: String) -> Statement {
+	Statement::Rebuild(RebuildStatement::Index(RebuildIndexStatement {
+		name: Ident(index),
+		what: Ident(table),
+	}))
+}
+
+fn info_statement(table: String) -> Statement {
+	Statement::Info(InfoStatement::Tb(Ident(table)))
+}
+
+/// Starts a rebuild, and reads its progress in the same request
+async fn start<C>(
+	router: &Router<C>,
+	table: String,
+	index: String,
+) -> Result<Option<RebuildProgress>>
+where
+	C: Connection,
+{
+	let statements = vec![rebuild_statement(table.clone(), index.clone()), info_statement(table)];
+	let query = Query(Statements(statements));
+	let mut conn = C::new(Method::Query);
+	let param = Param::query(query, Default::default());
+	let info: Value = conn.execute_query(router, param).await?.check()?.take(1)?;
+	progress(info, &index)
+}
+
+/// Reads the progress of a rebuild, which is `None` once it has completed
+async fn read<C>(router: &Router<C>, table: String, index: &str) -> Result<Option<RebuildProgress>>
+where
+	C: Connection,
+{
+	let query = Query(Statements(vec![info_statement(table)]));
+	let mut conn = C::new(Method::Query);
+	let param = Param::query(query, Default::default()).idempotent();
+	let info: Value = conn.execute_query(router, param).await?.take(0)?;
+	progress(info, index)
+}
+
+fn progress(info: Value, index: &str) -> Result<Option<RebuildProgress>> {
+	match info.pick(&["rebuilds".into(), index.into()]) {
+		Value::None => Ok(None),
+		Value::Object(mut object) => match (object.remove("processed"), object.remove("total")) {
+			(Some(Value::Number(processed)), Some(Value::Number(total))) => {
+				Ok(Some(RebuildProgress {
+					processed: processed.to_int() as u64,
+					total: total.to_int() as u64,
+				}))
+			}
+			_ => Err(invalid(Value::Object(object)).into()),
+		},
+		value => Err(invalid(value).into()),
+	}
+}
+
+fn invalid(value: Value) -> Error {
+	Error::FromValue {
+		value,
+		error: "expected the progress of an index rebuild".to_owned(),
+	}
+}
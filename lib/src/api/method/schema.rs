@@ -0,0 +1,240 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::err::Error;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::sql;
+use crate::sql::statements::DefineStatement;
+use crate::sql::statements::InfoStatement;
+use crate::sql::Ident;
+use crate::sql::Kind;
+use crate::sql::Object;
+use crate::sql::Part;
+use crate::sql::Query;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Strand;
+use crate::sql::Value;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// A field which is defined on a table
+#[derive(Debug)]
+struct Field {
+	kind: Option<Kind>,
+	flex: bool,
+}
+
+/// The field definitions of a table, as far as content is checked against them
+#[derive(Debug, Default)]
+struct Schema {
+	/// Whether the table only accepts the fields which are defined on it
+	full: bool,
+	/// The defined fields, keyed by their path
+	fields: BTreeMap<String, Field>,
+}
+
+impl Schema {
+	/// Fetches the schema of a table from the database
+	async fn fetch<C>(router: &Router<C>, table: &str) -> Result<Self>
+	where
+		C: Connection,
+	{
+		let query = Query(Statements(vec![
+			Statement::Info(InfoStatement::Db),
+			Statement::Info(InfoStatement::Tb(Ident(table.to_owned()))),
+		]));
+		let mut conn = C::new(Method::Query);
+		let param = Param::query(query, Default::default()).idempotent();
+		let mut response = conn.execute_query(router, param).await?;
+		let db: Value = response.take(0)?;
+		let tb: Value = response.take(1)?;
+		let mut schema = Schema::default();
+		let table = db.pick(&[Part::First, Part::from("tables"), Part::from(table)]);
+		if let Value::Strand(Strand(definition)) = table {
+			for statement in sql::parse(&definition)?.0 .0 {
+				if let Statement::Define(DefineStatement::Table(table)) = statement {
+					schema.full = table.full;
+				}
+			}
+		}
+		if let Value::Object(Object(fields)) = tb.pick(&[Part::First, Part::from("fields")]) {
+			for definition in fields.into_values() {
+				let Value::Strand(Strand(definition)) = definition else {
+					continue;
+				};
+				for statement in sql::parse(&definition)?.0 .0 {
+					if let Statement::Define(DefineStatement::Field(field)) = statement {
+						schema.fields.insert(
+							field.name.to_string(),
+							Field {
+								kind: field.kind,
+								flex: field.flex,
+							},
+						);
+					}
+				}
+			}
+		}
+		Ok(schema)
+	}
+
+	/// Lists the fields of the content which are unknown or of the wrong type
+	fn problems(&self, content: &Value) -> Vec<String> {
+		let mut problems = Vec::new();
+		if let Value::Object(object) = content {
+			self.check(None, object, &mut problems);
+		}
+		problems
+	}
+
+	fn check(&self, parent: Option<&str>, object: &Object, problems: &mut Vec<String>) {
+		for (key, value) in object.iter() {
+			// The record id is not a field, and unset fields are left out
+			if (parent.is_none() && key == "id") || value.is_none() {
+				continue;
+			}
+			let path = match parent {
+				Some(parent) => format!("{parent}.{key}"),
+				None => key.clone(),
+			};
+			match self.fields.get(&path) {
+				Some(field) => {
+					if let Some(kind) = &field.kind {
+						if value.clone().coerce_to(kind).is_err() {
+							problems.push(format!("field `{path}` is not of type `{kind}`"));
+							continue;
+						}
+					}
+					// Flexible objects accept any nested fields
+					if let (false, Value::Object(object)) = (field.flex, value) {
+						self.check(Some(&path), object, problems);
+					}
+				}
+				None if self.full => problems.push(format!("field `{path}` is not defined")),
+				None => {}
+			}
+		}
+	}
+}
+
+/// Caches the schemas of the tables which content is checked against
+#[derive(Debug, Default)]
+pub(crate) struct Schemas {
+	tables: Mutex<HashMap<String, Arc<Schema>>>,
+}
+
+impl Schemas {
+	/// Forgets the cached schemas, such as after switching to another database
+	pub(crate) fn clear(&self) {
+		self.tables.lock().unwrap_or_else(|error| error.into_inner()).clear();
+	}
+
+	/// Checks the content of a record against the schema of its table
+	///
+	/// A cached schema is fetched again before the content is rejected,
+	/// in case the table was redefined since it was cached.
+	pub(crate) async fn check<C>(
+		&self,
+		router: &Router<C>,
+		what: &Value,
+		content: &Value,
+	) -> Result<()>
+	where
+		C: Connection,
+	{
+		let table = match what {
+			Value::Table(table) => table.0.as_str(),
+			Value::Thing(thing) => thing.tb.as_str(),
+			Value::Range(range) => range.tb.as_str(),
+			_ => {
+				return Err(Error::InvalidParams(
+					"Content can only be checked against the schema of a table or a record"
+						.to_owned(),
+				)
+				.into());
+			}
+		};
+		let cached =
+			self.tables.lock().unwrap_or_else(|error| error.into_inner()).get(table).cloned();
+		if let Some(schema) = cached {
+			if schema.problems(content).is_empty() {
+				return Ok(());
+			}
+		}
+		let schema = Arc::new(Schema::fetch(router, table).await?);
+		self.tables
+			.lock()
+			.unwrap_or_else(|error| error.into_inner())
+			.insert(table.to_owned(), schema.clone());
+		let problems = schema.problems(content);
+		if !problems.is_empty() {
+			return Err(Error::SchemaMismatch {
+				table: table.to_owned(),
+				problems,
+			}
+			.into());
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn schema(full: bool) -> Schema {
+		let mut fields = BTreeMap::new();
+		fields.insert(
+			"name".to_owned(),
+			Field {
+				kind: Some(Kind::Object),
+				flex: false,
+			},
+		);
+		fields.insert(
+			"name.first".to_owned(),
+			Field {
+				kind: Some(Kind::String),
+				flex: false,
+			},
+		);
+		fields.insert(
+			"age".to_owned(),
+			Field {
+				kind: Some(Kind::Int),
+				flex: false,
+			},
+		);
+		Schema {
+			full,
+			fields,
+		}
+	}
+
+	#[test]
+	fn unknown_and_ill_typed_fields() {
+		let content =
+			sql::json(r#"{ "id": "tobie", "age": "old", "nmae": { "first": "Tobie" } }"#).unwrap();
+		assert_eq!(
+			schema(true).problems(&content),
+			vec!["field `age` is not of type `int`", "field `nmae` is not defined"]
+		);
+		assert_eq!(schema(false).problems(&content), vec!["field `age` is not of type `int`"]);
+	}
+
+	#[test]
+	fn nested_fields() {
+		let content =
+			sql::json(r#"{ "age": 37, "name": { "first": "Tobie", "last": "Morgan" } }"#).unwrap();
+		assert_eq!(schema(true).problems(&content), vec!["field `name.last` is not defined"]);
+		let content = sql::json(r#"{ "age": 37, "name": { "first": 1 } }"#).unwrap();
+		assert_eq!(
+			schema(true).problems(&content),
+			vec!["field `name.first` is not of type `string`"]
+		);
+	}
+}
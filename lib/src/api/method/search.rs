@@ -0,0 +1,347 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::fnc::util::math::vector::CosineSimilarity;
+use crate::fnc::util::math::vector::EuclideanDistance;
+use crate::fnc::util::math::vector::HammingDistance;
+use crate::fnc::util::math::vector::ManhattanDistance;
+use crate::fnc::util::math::vector::MinkowskiDistance;
+use crate::opt::from_value;
+use crate::sql::statements::SelectStatement;
+use crate::sql::Array;
+use crate::sql::Cond;
+use crate::sql::Expression;
+use crate::sql::Field;
+use crate::sql::Fields;
+use crate::sql::Function;
+use crate::sql::Idiom;
+use crate::sql::Number;
+use crate::sql::Operator;
+use crate::sql::Part;
+use crate::sql::Query;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Table;
+use crate::sql::Value;
+use crate::sql::Values;
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+/// The number of nearest neighbours returned, unless another number is requested
+const DEFAULT_KNN: u32 = 10;
+
+/// The reference which ties the score and highlights of a record to the full-text match
+const MATCH_REF: u8 = 1;
+
+/// The metric which the distance of a record from the query vector is measured with
+///
+/// This should match the distance of the `MTREE` index on the searched field.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[non_exhaustive]
+pub enum Distance {
+	/// The straight-line distance between two vectors
+	#[default]
+	Euclidean,
+	/// The sum of the absolute differences between two vectors
+	Manhattan,
+	/// One minus the cosine similarity of two vectors
+	Cosine,
+	/// The number of positions at which two vectors differ
+	Hamming,
+	/// The Minkowski distance of the specified order
+	Minkowski(f64),
+}
+
+impl Distance {
+	#[allow(clippy::ptr_arg)]
+	fn measure(&self, a: &Vec<Number>, b: &Vec<Number>) -> Option<f64> {
+		let distance = match self {
+			Distance::Euclidean => a.euclidean_distance(b),
+			Distance::Manhattan => a.manhattan_distance(b),
+			Distance::Cosine => a.cosine_similarity(b).map(|v| Number::from(1) - v),
+			Distance::Hamming => a.hamming_distance(b),
+			Distance::Minkowski(order) => a.minkowski_distance(b, &Number::from(*order)),
+		};
+		distance.ok().map(|v| v.to_float())
+	}
+}
+
+/// A record found by a vector search
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult<R> {
+	/// The record which was found
+	pub record: R,
+	/// The distance of the record from the query vector
+	pub distance: f64,
+}
+
+/// A record found by a full-text search
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSearchResult<R> {
+	/// The record which was found
+	pub record: R,
+	/// The relevance of the record to the query
+	pub score: f32,
+	/// The matched field, with the terms of the query wrapped in the highlight tags
+	pub highlights: Vec<String>,
+}
+
+/// A search builder
+#[derive(Debug)]
+#[must_use = "a search does nothing until a query vector is set and it is awaited"]
+pub struct Search<'r, C: Connection> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) table: String,
+}
+
+impl<'r, C> Search<'r, C>
+where
+	C: Connection,
+{
+	/// Searches for the records whose field is nearest to the query vector
+	///
+	/// Nested fields are separated by dots, such as `embedding.text`. The field
+	/// must have an `MTREE` index.
+	pub fn vector<R>(self, field: impl Into<String>, query: Vec<f64>) -> VectorSearch<'r, C, R> {
+		VectorSearch {
+			router: self.router,
+			table: self.table,
+			field: field.into(),
+			query,
+			k: DEFAULT_KNN,
+			distance: Distance::default(),
+			filter: None,
+			response_type: PhantomData,
+		}
+	}
+}
+
+/// A vector search future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct VectorSearch<'r, C: Connection, R> {
+	router: Result<&'r Router<C>>,
+	table: String,
+	field: String,
+	query: Vec<f64>,
+	k: u32,
+	distance: Distance,
+	filter: Option<String>,
+	response_type: PhantomData<R>,
+}
+
+impl<'r, C, R> VectorSearch<'r, C, R>
+where
+	C: Connection,
+{
+	/// Returns the `k` nearest records, instead of the default of 10
+	pub fn knn(mut self, k: u32) -> Self {
+		self.k = k;
+		self
+	}
+
+	/// Measures the distance of the records with this metric, instead of the Euclidean distance
+	pub fn distance(mut self, distance: Distance) -> Self {
+		self.distance = distance;
+		self
+	}
+
+	/// Only returns the records which match a condition, such as `published = true`
+	///
+	/// The condition is applied to the nearest records, so fewer than `k` records
+	/// may be returned.
+	pub fn filter(mut self, condition: impl Into<String>) -> Self {
+		self.filter = Some(condition.into());
+		self
+	}
+}
+
+impl<'r, Client, R> IntoFuture for VectorSearch<'r, Client, R>
+where
+	Client: Connection,
+	R: DeserializeOwned + std::fmt::Debug,
+{
+	type Output = Result<Vec<SearchResult<R>>>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let router = self.router?;
+			let field = Idiom(self.field.split('.').map(Part::from).collect());
+			let query: Vec<Number> = self.query.into_iter().map(Number::from).collect();
+			// The KNN operator is answered by the MTREE index on the field
+			let mut cond = Value::Expression(Box::new(Expression::Binary {
+				l: Value::Idiom(field.clone()),
+				o: Operator::Knn(self.k),
+				r: Value::Array(Array(query.iter().cloned().map(Value::from).collect())),
+			}));
+			if let Some(filter) = self.filter {
+				cond = Value::Expression(Box::new(Expression::Binary {
+					l: cond,
+					o: Operator::And,
+					r: crate::sql::value(&filter)?,
+				}));
+			}
+			let statement = SelectStatement {
+				expr: Fields(vec![Field::All], false),
+				what: Values(vec![Value::Table(Table(self.table))]),
+				cond: Some(Cond(cond)),
+				..Default::default()
+			};
+			let query_statement = Query(Statements(vec![Statement::Select(statement)]));
+			let mut conn = Client::new(Method::Query);
+			let param = Param::query(query_statement, Default::default()).idempotent();
+			let records: Vec<Value> = conn.execute_query(router, param).await?.take(0)?;
+			let mut results = Vec::with_capacity(records.len());
+			for record in records {
+				let vector = match record.pick(&field) {
+					Value::Array(Array(v)) => v
+						.into_iter()
+						.map(|v| match v {
+							Value::Number(v) => Some(v),
+							_ => None,
+						})
+						.collect::<Option<Vec<_>>>(),
+					_ => None,
+				};
+				let distance =
+					vector.and_then(|v| self.distance.measure(&v, &query)).unwrap_or(f64::INFINITY);
+				results.push(SearchResult {
+					record: from_value(record)?,
+					distance,
+				});
+			}
+			// The nearest records come first
+			results.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+			Ok(results)
+		})
+	}
+}
+
+/// A full-text search future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct TextSearch<'r, C: Connection, R> {
+	router: Result<&'r Router<C>>,
+	table: String,
+	field: String,
+	query: String,
+	tags: (String, String),
+	filter: Option<String>,
+	response_type: PhantomData<R>,
+}
+
+impl<'r, C, R> TextSearch<'r, C, R>
+where
+	C: Connection,
+{
+	/// Wraps the matched terms in these tags, instead of `<b>` and `</b>`
+	pub fn highlight(mut self, prefix: impl Into<String>, suffix: impl Into<String>) -> Self {
+		self.tags = (prefix.into(), suffix.into());
+		self
+	}
+
+	/// Only returns the records which match a condition, such as `published = true`
+	pub fn filter(mut self, condition: impl Into<String>) -> Self {
+		self.filter = Some(condition.into());
+		self
+	}
+}
+
+impl<'r, Client, R> IntoFuture for TextSearch<'r, Client, R>
+where
+	Client: Connection,
+	R: DeserializeOwned + std::fmt::Debug,
+{
+	type Output = Result<Vec<TextSearchResult<R>>>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let router = self.router?;
+			let field = Idiom(self.field.split('.').map(Part::from).collect());
+			// The MATCHES operator is answered by the SEARCH index on the field
+			let mut cond = Value::Expression(Box::new(Expression::Binary {
+				l: Value::Idiom(field),
+				o: Operator::Matches(Some(MATCH_REF)),
+				r: Value::from(self.query),
+			}));
+			if let Some(filter) = self.filter {
+				cond = Value::Expression(Box::new(Expression::Binary {
+					l: cond,
+					o: Operator::And,
+					r: crate::sql::value(&filter)?,
+				}));
+			}
+			let function = |name: &str, mut args: Vec<Value>| {
+				args.push(Value::from(MATCH_REF));
+				Value::Function(Box::new(Function::Normal(name.to_owned(), args)))
+			};
+			let (prefix, suffix) = self.tags;
+			let statement = SelectStatement {
+				expr: Fields(
+					vec![
+						Field::Single {
+							expr: Value::Param(crate::sql::Param::from("this")),
+							alias: Some(Idiom::from("record".to_owned())),
+						},
+						Field::Single {
+							expr: function("search::score", vec![]),
+							alias: Some(Idiom::from("score".to_owned())),
+						},
+						Field::Single {
+							expr: function(
+								"search::highlight",
+								vec![Value::from(prefix), Value::from(suffix)],
+							),
+							alias: Some(Idiom::from("highlights".to_owned())),
+						},
+					],
+					false,
+				),
+				what: Values(vec![Value::Table(Table(self.table))]),
+				cond: Some(Cond(cond)),
+				..Default::default()
+			};
+			let query_statement = Query(Statements(vec![Statement::Select(statement)]));
+			let mut conn = Client::new(Method::Query);
+			let param = Param::query(query_statement, Default::default()).idempotent();
+			let rows: Vec<Value> = conn.execute_query(router, param).await?.take(0)?;
+			let mut results = Vec::with_capacity(rows.len());
+			for row in rows {
+				let Value::Object(mut row) = row else {
+					continue;
+				};
+				let score = match row.remove("score") {
+					Some(Value::Number(v)) => v.to_float() as f32,
+					_ => 0.0,
+				};
+				// A field with several values is highlighted value by value
+				let highlights = match row.remove("highlights") {
+					Some(Value::Strand(v)) => vec![v.0],
+					Some(Value::Array(Array(v))) => v
+						.into_iter()
+						.filter_map(|v| match v {
+							Value::Strand(v) => Some(v.0),
+							_ => None,
+						})
+						.collect(),
+					_ => vec![],
+				};
+				results.push(TextSearchResult {
+					record: from_value(row.remove("record").unwrap_or_default())?,
+					score,
+					highlights,
+				});
+			}
+			// The most relevant records come first
+			results.sort_by(|a, b| b.score.total_cmp(&a.score));
+			Ok(results)
+		})
+	}
+}
@@ -1,6 +1,7 @@
 use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
+use crate::api::method::cache;
 use crate::api::opt::Range;
 use crate::api::opt::Resource;
 use crate::api::Connection;
@@ -37,8 +38,13 @@ macro_rules! into_future {
 					Some(range) => resource?.with_range(range)?,
 					None => resource?.into(),
 				};
+				let router = router?;
+				if router.cache.is_enabled() {
+					let value = router.cache.select(router, param).await?;
+					return cache::$method(value);
+				}
 				let mut conn = Client::new(Method::Select);
-				conn.$method(router?, Param::new(vec![param])).await
+				conn.$method(router, Param::new(vec![param]).idempotent()).await
 			})
 		}
 	};
@@ -0,0 +1,67 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::opt::from_value;
+use crate::sql::Array;
+use crate::sql::Thing;
+use crate::sql::Value;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+/// A multi-record select future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct SelectMany<'r, C: Connection, R> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) ids: Result<Vec<Thing>>,
+	pub(super) response_type: PhantomData<R>,
+}
+
+impl<'r, Client, R> IntoFuture for SelectMany<'r, Client, R>
+where
+	Client: Connection,
+	R: DeserializeOwned + std::fmt::Debug,
+{
+	type Output = Result<Vec<Option<R>>>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let router = self.router?;
+			let ids = self.ids?;
+			if ids.is_empty() {
+				return Ok(Vec::new());
+			}
+			let what = Value::Array(Array(ids.iter().cloned().map(Value::from).collect()));
+			let param = Param::new(vec![what]).idempotent();
+			let mut conn = Client::new(Method::Select);
+			let records = match conn.execute_value(router, param).await? {
+				Value::None | Value::Null => Vec::new(),
+				Value::Array(Array(records)) => records,
+				record => vec![record],
+			};
+			// Map each requested ID to the positions it was requested at
+			let mut results: Vec<Option<R>> = ids.iter().map(|_| None).collect();
+			let mut positions: HashMap<Thing, Vec<usize>> = HashMap::with_capacity(ids.len());
+			for (position, id) in ids.into_iter().enumerate() {
+				positions.entry(id).or_default().push(position);
+			}
+			// Return the records in the order they were requested
+			for record in records {
+				let Value::Thing(id) = record.rid() else {
+					continue;
+				};
+				for position in positions.remove(&id).unwrap_or_default() {
+					results[position] = Some(from_value(record.clone())?);
+				}
+			}
+			Ok(results)
+		})
+	}
+}
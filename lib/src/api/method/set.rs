@@ -3,6 +3,8 @@ use crate::api::conn::Param;
 use crate::api::conn::Router;
 use crate::api::Connection;
 use crate::api::Result;
+use crate::cnf::PROTECTED_PARAM_NAMES;
+use crate::err::Error;
 use crate::sql::Value;
 use std::future::Future;
 use std::future::IntoFuture;
@@ -26,8 +28,16 @@ where
 
 	fn into_future(self) -> Self::IntoFuture {
 		Box::pin(async move {
+			let router = self.router?;
+			// Protected parameters are set by the server and can't be overridden
+			if PROTECTED_PARAM_NAMES.contains(&self.key.as_str()) {
+				return Err(Error::InvalidParam {
+					name: self.key,
+				}
+				.into());
+			}
 			let mut conn = Client::new(Method::Set);
-			conn.execute_unit(self.router?, Param::new(vec![self.key.into(), self.value?])).await
+			conn.execute_unit(router, Param::new(vec![self.key.into(), self.value?])).await
 		})
 	}
 }
@@ -6,6 +6,11 @@ mod server;
 mod types;
 
 use crate::api::method::tests::types::AuthParams;
+use crate::api::method::Blob;
+use crate::api::method::ChangeSet;
+use crate::api::method::Distance;
+use crate::api::method::SearchResult;
+use crate::api::method::TextSearchResult;
 use crate::api::opt::auth::Database;
 use crate::api::opt::auth::Jwt;
 use crate::api::opt::auth::Namespace;
@@ -14,8 +19,13 @@ use crate::api::opt::auth::Scope;
 use crate::api::opt::PatchOp;
 use crate::api::Response as QueryResponse;
 use crate::api::Surreal;
+use crate::path;
+use crate::sql;
 use crate::sql::statements::BeginStatement;
 use crate::sql::statements::CommitStatement;
+use crate::sql::statements::DefineFieldStatement;
+use crate::sql::Kind;
+use crate::sql::Tokenizer;
 use once_cell::sync::Lazy;
 use protocol::Client;
 use protocol::Test;
@@ -34,6 +44,12 @@ async fn api() {
 	// health
 	let _: () = DB.health().await.unwrap();
 
+	// changes
+	let _: Vec<ChangeSet<User>> = DB.changes(USER).since(1).limit(10).await.unwrap();
+
+	// on_event
+	let _events = DB.on_event().unwrap();
+
 	// invalidate
 	let _: () = DB.invalidate().await.unwrap();
 
@@ -110,12 +126,22 @@ async fn api() {
 		.query(CommitStatement)
 		.await
 		.unwrap();
+	let _: QueryResponse = DB.process(sql::parse("SELECT * FROM user").unwrap()).await.unwrap();
+	let _: QueryResponse = DB
+		.query("SELECT * FROM user WHERE id IN $ids")
+		.bind_chunked("ids", ["user:john", "user:jane"], 1)
+		.await
+		.unwrap();
 
 	// create
 	let _: Vec<User> = DB.create(USER).await.unwrap();
 	let _: Option<User> = DB.create((USER, "john")).await.unwrap();
 	let _: Vec<User> = DB.create(USER).content(User::default()).await.unwrap();
 	let _: Option<User> = DB.create((USER, "john")).content(User::default()).await.unwrap();
+	let _: Option<User> =
+		DB.create((USER, "john")).content(User::default()).validate().await.unwrap();
+	let _: Option<User> =
+		DB.create((USER, "john")).return_fields(["id"]).content(User::default()).await.unwrap();
 
 	// select
 	let _: Vec<User> = DB.select(USER).await.unwrap();
@@ -130,6 +156,51 @@ async fn api() {
 	let _: Vec<User> =
 		DB.select(USER).range((Bound::Excluded("jane"), Bound::Included("john"))).await.unwrap();
 
+	// select_many
+	let _: Vec<Option<User>> = DB.select_many([(USER, "john"), (USER, "jane")]).await.unwrap();
+	let _: Vec<Option<User>> = DB.select_many(["user:john", "user:jane"]).await.unwrap();
+
+	// aggregates
+	let _: u64 = DB.count(USER).await.unwrap();
+	let _: f64 = DB.sum(USER, "age").await.unwrap();
+	let _: Option<f64> = DB.avg(USER, "age").await.unwrap();
+
+	// search
+	let _: Vec<SearchResult<User>> =
+		DB.search(USER).vector("embedding", vec![0.1, 0.2]).knn(5).await.unwrap();
+	let _: Vec<SearchResult<User>> = DB
+		.search(USER)
+		.vector("embedding", vec![0.1, 0.2])
+		.distance(Distance::Cosine)
+		.filter("age > 18")
+		.await
+		.unwrap();
+	let _: Vec<TextSearchResult<User>> =
+		DB.search(USER).matches("bio", "rust async").await.unwrap();
+	let _: Vec<TextSearchResult<User>> = DB
+		.search(USER)
+		.matches("bio", "rust async")
+		.highlight("<em>", "</em>")
+		.filter("age > 18")
+		.await
+		.unwrap();
+
+	// infer schema
+	let _: Vec<DefineFieldStatement> = DB.infer_schema(USER, 100).await.unwrap();
+
+	// define
+	DB.define_index(USER).name("email").columns(["email"]).unique().await.unwrap();
+	DB.define_field(USER).name("name.first").kind(Kind::String).await.unwrap();
+	DB.define_event(USER).name("audit").then("CREATE audit").await.unwrap();
+	DB.define_analyzer("simple").tokenizers([Tokenizer::Blank]).await.unwrap();
+
+	// rebuild index
+	DB.rebuild_index(USER, "email").await.unwrap();
+
+	// traverse
+	let _: Vec<User> = DB.traverse((USER, "john")).out("knows").await.unwrap();
+	let _: Vec<User> = DB.traverse("user:john").r#in("follows").depth(1..3).await.unwrap();
+
 	// update
 	let _: Vec<User> = DB.update(USER).await.unwrap();
 	let _: Option<User> = DB.update((USER, "john")).await.unwrap();
@@ -137,22 +208,31 @@ async fn api() {
 	let _: Vec<User> =
 		DB.update(USER).range("jane".."john").content(User::default()).await.unwrap();
 	let _: Option<User> = DB.update((USER, "john")).content(User::default()).await.unwrap();
+	let _: Vec<User> = DB.update(USER).return_none().content(User::default()).await.unwrap();
 
 	// merge
 	let _: Vec<User> = DB.update(USER).merge(User::default()).await.unwrap();
 	let _: Vec<User> = DB.update(USER).range("jane".."john").merge(User::default()).await.unwrap();
 	let _: Option<User> = DB.update((USER, "john")).merge(User::default()).await.unwrap();
+	let _: Option<User> =
+		DB.update((USER, "john")).merge(User::default()).skip_none().await.unwrap();
 
 	// patch
 	let _: Vec<User> = DB.update(USER).patch(PatchOp::remove("/name")).await.unwrap();
 	let _: Vec<User> =
 		DB.update(USER).range("jane".."john").patch(PatchOp::remove("/name")).await.unwrap();
 	let _: Option<User> = DB.update((USER, "john")).patch(PatchOp::remove("/name")).await.unwrap();
+	let _: Option<User> = DB
+		.update((USER, "john"))
+		.patch([PatchOp::test(path!["name"], "John"), PatchOp::r#move("/name", "/nickname")])
+		.await
+		.unwrap();
 
 	// delete
 	let _: Vec<User> = DB.delete(USER).await.unwrap();
 	let _: Option<User> = DB.delete((USER, "john")).await.unwrap();
 	let _: Vec<User> = DB.delete(USER).range("jane".."john").await.unwrap();
+	let _: Option<User> = DB.delete((USER, "john")).return_before().await.unwrap();
 
 	// export
 	let _: () = DB.export("backup.sql").await.unwrap();
@@ -160,6 +240,12 @@ async fn api() {
 	// import
 	let _: () = DB.import("backup.sql").await.unwrap();
 
+	// put_blob
+	let _: u64 = DB.put_blob((USER, "john"), &b"avatar"[..]).await.unwrap();
+
+	// get_blob
+	let _: Blob = DB.get_blob((USER, "john")).await.unwrap();
+
 	// version
 	let _: Version = DB.version().await.unwrap();
 }
@@ -51,6 +51,8 @@ impl Surreal<Client> {
 			router: self.router.clone(),
 			address: address.into_endpoint(),
 			capacity: 0,
+			retry: Default::default(),
+			circuit_breaker: Default::default(),
 			client: PhantomData,
 			response_type: PhantomData,
 		}
@@ -74,11 +76,19 @@ impl Connection for Client {
 			let (route_tx, route_rx) = flume::bounded(capacity);
 			let mut features = HashSet::new();
 			features.insert(ExtraFeatures::Backup);
+			features.insert(ExtraFeatures::Blobs);
 			let router = Router {
 				features,
 				conn: PhantomData,
 				sender: route_tx,
 				last_id: AtomicI64::new(0),
+				retry: Default::default(),
+				circuit_breaker: Default::default(),
+				events: Default::default(),
+				schemas: Default::default(),
+				cache: Default::default(),
+				subscriptions: Default::default(),
+				in_flight: Default::default(),
 			};
 			server::mock(route_rx);
 			Ok(Surreal {
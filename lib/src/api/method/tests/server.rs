@@ -82,6 +82,14 @@ pub(super) fn mock(route_rx: Receiver<Option<Route>>) {
 					Some(_) => Ok(DbResponse::Other(Value::None)),
 					_ => unreachable!(),
 				},
+				Method::PutBlob => match (&params[..], param.receiver) {
+					([Value::Thing(..)], Some(_)) => Ok(DbResponse::Other(Value::from(0u64))),
+					_ => unreachable!(),
+				},
+				Method::GetBlob => match (&params[..], param.sender) {
+					([Value::Thing(..)], Some(_)) => Ok(DbResponse::Other(Value::None)),
+					_ => unreachable!(),
+				},
 			};
 
 			if let Err(message) = response.into_send_async(result).await {
@@ -0,0 +1,137 @@
+use crate::api::conn::Method;
+use crate::api::conn::Param;
+use crate::api::conn::Router;
+use crate::api::err::Error;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::sql::statements::SelectStatement;
+use crate::sql::Array;
+use crate::sql::Dir;
+use crate::sql::Field;
+use crate::sql::Fields;
+use crate::sql::Function;
+use crate::sql::Graph;
+use crate::sql::Idiom;
+use crate::sql::Part;
+use crate::sql::Query;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Table;
+use crate::sql::Tables;
+use crate::sql::Thing;
+use crate::sql::Value;
+use crate::sql::Values;
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::marker::PhantomData;
+use std::ops::Bound;
+use std::ops::RangeBounds;
+use std::pin::Pin;
+
+/// A graph traversal future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Traverse<'r, C: Connection, R> {
+	pub(super) router: Result<&'r Router<C>>,
+	pub(super) start: Result<Thing>,
+	pub(super) steps: Vec<(Dir, String)>,
+	pub(super) depth: (Bound<usize>, Bound<usize>),
+	pub(super) response_type: PhantomData<R>,
+}
+
+impl<'r, C, R> Traverse<'r, C, R>
+where
+	C: Connection,
+{
+	/// Follows the outgoing edges in the edge table, to the records they point to
+	pub fn out(mut self, edge: impl Into<String>) -> Self {
+		self.steps.push((Dir::Out, edge.into()));
+		self
+	}
+
+	/// Follows the incoming edges in the edge table, to the records they point from
+	pub fn r#in(mut self, edge: impl Into<String>) -> Self {
+		self.steps.push((Dir::In, edge.into()));
+		self
+	}
+
+	/// Repeats the steps of the traversal a number of times, which defaults to once
+	///
+	/// The records reached at each depth in the range are returned together.
+	pub fn depth(mut self, depth: impl RangeBounds<usize>) -> Self {
+		self.depth = (depth.start_bound().cloned(), depth.end_bound().cloned());
+		self
+	}
+}
+
+impl<'r, Client, R> IntoFuture for Traverse<'r, Client, R>
+where
+	Client: Connection,
+	R: DeserializeOwned,
+{
+	type Output = Result<Vec<R>>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let router = self.router?;
+			let start = self.start?;
+			let min = match self.depth.0 {
+				Bound::Included(min) => min,
+				Bound::Excluded(min) => min + 1,
+				Bound::Unbounded => 0,
+			};
+			let max = match self.depth.1 {
+				Bound::Included(max) => max,
+				Bound::Excluded(0) => return Ok(Vec::new()),
+				Bound::Excluded(max) => max - 1,
+				Bound::Unbounded => {
+					return Err(Error::InvalidParams(
+						"The depth of a graph traversal needs an upper bound".to_owned(),
+					)
+					.into());
+				}
+			};
+			if min > max {
+				return Ok(Vec::new());
+			}
+			// Build the graph expression for each depth
+			let paths = (min..=max).map(|depth| {
+				let mut parts = vec![Part::Start(start.clone().into())];
+				for _ in 0..depth {
+					for (dir, edge) in &self.steps {
+						parts.push(Part::Graph(Graph {
+							dir: dir.clone(),
+							what: Tables(vec![Table(edge.clone())]),
+							..Default::default()
+						}));
+						parts.push(Part::Graph(Graph {
+							dir: dir.clone(),
+							..Default::default()
+						}));
+					}
+				}
+				Value::Idiom(Idiom(parts))
+			});
+			// Select each record which is reached once
+			let records = Function::Normal(
+				"array::distinct".to_owned(),
+				vec![Function::Normal(
+					"array::flatten".to_owned(),
+					vec![Value::Array(Array(paths.collect()))],
+				)
+				.into()],
+			);
+			let statement = SelectStatement {
+				expr: Fields(vec![Field::All], false),
+				what: Values(vec![records.into()]),
+				..Default::default()
+			};
+			let query = Query(Statements(vec![Statement::Select(statement)]));
+			let mut conn = Client::new(Method::Query);
+			let param = Param::query(query, Default::default()).idempotent();
+			conn.execute_query(router, param).await?.take(0)
+		})
+	}
+}
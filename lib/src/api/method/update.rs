@@ -1,6 +1,9 @@
 use crate::api::conn::Method;
 use crate::api::conn::Param;
 use crate::api::conn::Router;
+use crate::api::expr::Expr;
+use crate::api::method::condition;
+use crate::api::method::condition::Condition;
 use crate::api::method::Content;
 use crate::api::method::Merge;
 use crate::api::method::Patch;
@@ -10,6 +13,7 @@ use crate::api::opt::Resource;
 use crate::api::Connection;
 use crate::api::Result;
 use crate::sql::Id;
+use crate::sql::Output;
 use crate::sql::Value;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -25,6 +29,8 @@ pub struct Update<'r, C: Connection, R> {
 	pub(super) router: Result<&'r Router<C>>,
 	pub(super) resource: Result<Resource>,
 	pub(super) range: Option<Range<Id>>,
+	pub(super) condition: Option<Condition>,
+	pub(super) output: Option<Output>,
 	pub(super) response_type: PhantomData<R>,
 }
 
@@ -35,6 +41,8 @@ macro_rules! into_future {
 				router,
 				resource,
 				range,
+				condition,
+				output,
 				..
 			} = self;
 			Box::pin(async move {
@@ -42,6 +50,10 @@ macro_rules! into_future {
 					Some(range) => resource?.with_range(range)?,
 					None => resource?.into(),
 				};
+				if condition.is_some() || output.is_some() {
+					let condition = condition.unwrap_or(Condition::always(Method::Update));
+					return condition.$method(router?, param, None, output).await;
+				}
 				let mut conn = Client::new(Method::Update);
 				conn.$method(router?, Param::new(vec![param])).await
 			})
@@ -108,6 +120,133 @@ where
 	C: Connection,
 	R: DeserializeOwned + std::fmt::Debug,
 {
+	/// Only updates the records whose current data matches the specified checksum
+	///
+	/// The checksum of a record is computed with [`Value::checksum`]. Records which were
+	/// changed since the checksum was computed, or which don't exist, are left untouched and
+	/// are not returned. Each record is written with a compare-and-swap against the record
+	/// whose checksum was checked, so a record which is changed by someone else after the
+	/// check is left untouched, and is not returned either.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::sql::Value;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// let current: Value = db.select(("account", "john")).await?;
+	///
+	/// let updated: Value = db
+	///     .update(("account", "john"))
+	///     .if_matches(current.checksum())
+	///     .merge(serde_json::json!({ "balance": 100 }))
+	///     .await?;
+	///
+	/// if updated.is_none() {
+	///     // The record was changed by someone else in the meantime
+	/// }
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn if_matches(mut self, checksum: impl Into<String>) -> Self {
+		Condition::replace(&mut self.condition, Condition::Matches(checksum.into()));
+		self
+	}
+
+	/// Only updates the records which match the condition
+	///
+	/// This replaces the checksum set with [`Update::if_matches`], if any.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::expr::field;
+	/// use surrealdb::sql::Value;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// let adults: Vec<Value> = db
+	///     .update("person")
+	///     .filter(field("age").gte(18))
+	///     .merge(serde_json::json!({ "adult": true }))
+	///     .await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn filter(mut self, condition: Expr) -> Self {
+		Condition::replace(&mut self.condition, Condition::Where(condition.into()));
+		self
+	}
+
+	/// Returns the changes which were made to the records, as JSON Patch operations
+	///
+	/// The changes to each record can be read as a [`Diff`](crate::opt::Diff), which holds the
+	/// values that were removed or replaced, so it can be applied to a local copy of the record
+	/// and reverted again.
+	pub fn return_diff(mut self) -> Self {
+		self.output = Some(Output::Diff);
+		self
+	}
+
+	/// Runs the write without applying it, returning the changes it would have made
+	///
+	/// The write runs in a transaction which is always cancelled, so nothing is changed in the
+	/// database and no live queries are notified. The changes which would have been made to each
+	/// record are returned as JSON Patch operations, as with [`Update::return_diff`].
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use surrealdb::expr::field;
+	/// use surrealdb::sql::Value;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// #
+	/// let changes: Value = db
+	///     .update("account")
+	///     .filter(field("balance").lt(0))
+	///     .merge(serde_json::json!({ "frozen": true }))
+	///     .dry_run()
+	///     .await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn dry_run(mut self) -> Self {
+		self.condition = Some(Condition::dry_run(self.condition.take(), Method::Update));
+		self.output = Some(Output::Diff);
+		self
+	}
+
+	/// Returns nothing, so the updated records aren't sent back
+	pub fn return_none(mut self) -> Self {
+		self.output = Some(Output::None);
+		self
+	}
+
+	/// Returns the records as they were before they were updated
+	pub fn return_before(mut self) -> Self {
+		self.output = Some(Output::Before);
+		self
+	}
+
+	/// Returns only the specified fields of the updated records
+	///
+	/// Nested fields are separated by dots, such as `name.first`.
+	pub fn return_fields(mut self, fields: impl IntoIterator<Item = impl Into<String>>) -> Self {
+		self.output = Some(condition::return_fields(fields));
+		self
+	}
+
 	/// Replaces the current document / record data with the specified data
 	pub fn content<D>(self, data: D) -> Content<'r, C, D, R>
 	where
@@ -118,7 +257,11 @@ where
 			method: Method::Update,
 			resource: self.resource,
 			range: self.range,
+			condition: self.condition,
+			output: self.output,
 			content: data,
+			validate: false,
+			hash_id: None,
 			response_type: PhantomData,
 		}
 	}
@@ -132,18 +275,25 @@ where
 			router: self.router,
 			resource: self.resource,
 			range: self.range,
+			condition: self.condition,
+			output: self.output,
 			content: data,
+			skip_none: false,
 			response_type: PhantomData,
 		}
 	}
 
 	/// Patches the current document / record data with the specified JSON Patch data
-	pub fn patch(self, PatchOp(patch): PatchOp) -> Patch<'r, C, R> {
+	///
+	/// Accepts a single [`PatchOp`], or any iterator of them.
+	pub fn patch(self, patches: impl IntoIterator<Item = PatchOp>) -> Patch<'r, C, R> {
 		Patch {
 			router: self.router,
 			resource: self.resource,
 			range: self.range,
-			patches: vec![patch],
+			condition: self.condition,
+			output: self.output,
+			patches: patches.into_iter().map(|PatchOp(patch)| patch).collect(),
 			response_type: PhantomData,
 		}
 	}
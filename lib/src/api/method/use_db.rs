@@ -25,8 +25,12 @@ where
 
 	fn into_future(self) -> Self::IntoFuture {
 		Box::pin(async move {
+			let router = self.router?;
 			let mut conn = Client::new(Method::Use);
-			conn.execute_unit(self.router?, Param::new(vec![self.ns, self.db.into()])).await
+			conn.execute_unit(router, Param::new(vec![self.ns, self.db.into()])).await?;
+			// The cached table schemas belong to the previous database
+			router.schemas.clear();
+			Ok(())
 		})
 	}
 }
@@ -40,8 +40,12 @@ where
 
 	fn into_future(self) -> Self::IntoFuture {
 		Box::pin(async move {
+			let router = self.router?;
 			let mut conn = Client::new(Method::Use);
-			conn.execute_unit(self.router?, Param::new(vec![self.ns.into(), Value::None])).await
+			conn.execute_unit(router, Param::new(vec![self.ns.into(), Value::None])).await?;
+			// The cached table schemas belong to the previous database
+			router.schemas.clear();
+			Ok(())
 		})
 	}
 }
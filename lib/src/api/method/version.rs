@@ -26,7 +26,7 @@ where
 		Box::pin(async {
 			let mut conn = Client::new(Method::Version);
 			let version = conn
-				.execute_value(self.router?, Param::new(Vec::new()))
+				.execute_value(self.router?, Param::new(Vec::new()).idempotent())
 				.await?
 				.convert_to_string()?;
 			let semantic = version.trim_start_matches("surrealdb-");
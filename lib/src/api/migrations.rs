@@ -0,0 +1,379 @@
+//! Versioned migrations of the schema and data of a database
+//!
+//! Migrations are registered in order of their version, as SurrealQL scripts or Rust closures.
+//! The versions which have been applied are stored in the `_migrations` table of the selected
+//! database, so that migrating a database only applies the migrations which are still pending.
+//! A lock record in the `_migrations_lock` table stops two processes from migrating the same
+//! database at the same time.
+//!
+//! ```no_run
+//! use surrealdb::migrations::Migration;
+//! use surrealdb::migrations::Migrations;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> surrealdb::Result<()> {
+//! # let db = surrealdb::engine::any::connect("mem://").await?;
+//! #
+//! db.use_ns("namespace").use_db("database").await?;
+//!
+//! let migrations = Migrations::new()
+//!     .add(
+//!         Migration::sql(1, "create users", "DEFINE TABLE user SCHEMAFULL")
+//!             .down_sql("REMOVE TABLE user"),
+//!     )
+//!     .add(Migration::new(2, "create admin", |db| async move {
+//!         db.query("CREATE user:admin").await?.check()?;
+//!         Ok(())
+//!     }));
+//!
+//! // Apply all pending migrations
+//! db.migrate(&migrations).await?;
+//!
+//! // Revert the migrations after version 1
+//! db.migrate(&migrations).to(1).await?;
+//! #
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::err::Error;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::api::Surreal;
+use crate::sql;
+use crate::sql::Statement;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// The table in which the applied migrations are stored
+pub const TABLE: &str = "_migrations";
+
+/// The table in which the lock of a running migration is stored
+pub const LOCK: &str = "_migrations_lock";
+
+type Step<C> = Arc<
+	dyn Fn(Surreal<C>) -> Pin<Box<dyn Future<Output = Result<()>> + Send + Sync>> + Send + Sync,
+>;
+
+/// A migration script
+enum Script<C: Connection> {
+	/// A SurrealQL script, run in a transaction
+	Sql(String),
+	/// A Rust closure, run with a client for the selected database
+	Rust(Step<C>),
+}
+
+impl<C: Connection> fmt::Debug for Script<C> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Sql(sql) => f.debug_tuple("Sql").field(sql).finish(),
+			Self::Rust(_) => f.write_str("Rust"),
+		}
+	}
+}
+
+fn step<C, F, Fut>(f: F) -> Step<C>
+where
+	C: Connection,
+	F: Fn(Surreal<C>) -> Fut + Send + Sync + 'static,
+	Fut: Future<Output = Result<()>> + Send + Sync + 'static,
+{
+	Arc::new(move |db| Box::pin(f(db)))
+}
+
+/// A single versioned migration
+#[derive(Debug)]
+pub struct Migration<C: Connection> {
+	version: u64,
+	name: String,
+	up: Script<C>,
+	down: Option<Script<C>>,
+}
+
+impl<C> Migration<C>
+where
+	C: Connection,
+{
+	/// A migration which runs a Rust closure
+	///
+	/// Unlike SurrealQL scripts, closures are not run in a transaction. The version is stored
+	/// as incomplete before the closure runs, and only marked as applied once the closure has
+	/// completed successfully. If the closure fails, migrating again returns
+	/// [`Error::IncompleteMigration`] until the database has been resolved by hand.
+	pub fn new<F, Fut>(version: u64, name: impl Into<String>, up: F) -> Self
+	where
+		F: Fn(Surreal<C>) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = Result<()>> + Send + Sync + 'static,
+	{
+		Self {
+			version,
+			name: name.into(),
+			up: Script::Rust(step(up)),
+			down: None,
+		}
+	}
+
+	/// A migration which runs a SurrealQL script
+	///
+	/// The script is run in the same transaction which stores the version, so either both
+	/// are committed, or neither is.
+	pub fn sql(version: u64, name: impl Into<String>, up: impl Into<String>) -> Self {
+		Self {
+			version,
+			name: name.into(),
+			up: Script::Sql(up.into()),
+			down: None,
+		}
+	}
+
+	/// Sets the Rust closure which reverts this migration
+	pub fn down<F, Fut>(mut self, down: F) -> Self
+	where
+		F: Fn(Surreal<C>) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = Result<()>> + Send + Sync + 'static,
+	{
+		self.down = Some(Script::Rust(step(down)));
+		self
+	}
+
+	/// Sets the SurrealQL script which reverts this migration
+	pub fn down_sql(mut self, down: impl Into<String>) -> Self {
+		self.down = Some(Script::Sql(down.into()));
+		self
+	}
+
+	/// The version of this migration
+	pub fn version(&self) -> u64 {
+		self.version
+	}
+
+	/// The name of this migration
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+}
+
+/// An ordered set of migrations
+#[derive(Debug)]
+pub struct Migrations<C: Connection> {
+	list: Vec<Migration<C>>,
+}
+
+impl<C> Default for Migrations<C>
+where
+	C: Connection,
+{
+	fn default() -> Self {
+		Self {
+			list: Vec::new(),
+		}
+	}
+}
+
+impl<C> Migrations<C>
+where
+	C: Connection,
+{
+	/// Creates an empty set of migrations
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Registers a migration
+	pub fn add(mut self, migration: Migration<C>) -> Self {
+		self.list.push(migration);
+		self
+	}
+
+	/// The latest version of these migrations
+	pub fn latest(&self) -> u64 {
+		self.list.iter().map(|m| m.version).max().unwrap_or_default()
+	}
+
+	/// Finds the migration with the given version
+	fn get(&self, version: u64) -> Option<&Migration<C>> {
+		self.list.iter().find(|m| m.version == version)
+	}
+
+	/// Checks that each version is only registered once
+	fn check(&self) -> Result<()> {
+		let mut versions = BTreeSet::new();
+		for migration in &self.list {
+			if !versions.insert(migration.version) {
+				return Err(Error::DuplicateMigration(migration.version).into());
+			}
+		}
+		Ok(())
+	}
+}
+
+/// A migration future
+///
+/// By default, all pending migrations are applied. Use [`Migrate::to`] to migrate the
+/// database up, or down, to a specific version instead.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Migrate<'r, C: Connection> {
+	pub(crate) client: &'r Surreal<C>,
+	pub(crate) migrations: &'r Migrations<C>,
+	pub(crate) target: Option<u64>,
+}
+
+impl<C> Migrate<'_, C>
+where
+	C: Connection,
+{
+	/// Migrates the database to the given version
+	///
+	/// Pending migrations up to, and including, this version are applied. Applied migrations
+	/// after this version are reverted, newest first.
+	pub fn to(mut self, version: u64) -> Self {
+		self.target = Some(version);
+		self
+	}
+}
+
+impl<'r, Client> IntoFuture for Migrate<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<()>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			self.migrations.check()?;
+			self.lock().await?;
+			let result = self.migrate().await;
+			let unlocked = self.unlock().await;
+			result.and(unlocked)
+		})
+	}
+}
+
+impl<Client> Migrate<'_, Client>
+where
+	Client: Connection,
+{
+	/// Takes the lock which stops other processes from migrating the database at the same time
+	async fn lock(&self) -> Result<()> {
+		let create =
+			format!("CREATE type::thing('{LOCK}', 'lock') SET locked_at = time::now() RETURN NONE");
+		if let Err(error) = self.client.query(create).await?.check() {
+			// The lock record can only be created once
+			let locked: Vec<bool> =
+				self.client.query(format!("SELECT VALUE true FROM {LOCK}")).await?.take(0)?;
+			if !locked.is_empty() {
+				return Err(Error::MigrationLocked.into());
+			}
+			return Err(error);
+		}
+		Ok(())
+	}
+
+	/// Releases the lock taken by [`Migrate::lock`]
+	async fn unlock(&self) -> Result<()> {
+		self.client.query(format!("DELETE type::thing('{LOCK}', 'lock')")).await?.check()?;
+		Ok(())
+	}
+
+	/// Applies, or reverts, the migrations up to the target version
+	async fn migrate(&self) -> Result<()> {
+		let target = self.target.unwrap_or_else(|| self.migrations.latest());
+		let mut response = self
+			.client
+			.query(format!("SELECT VALUE version FROM {TABLE} WHERE dirty"))
+			.query(format!("SELECT VALUE version FROM {TABLE}"))
+			.await?;
+		// A Rust migration which failed part way has to be resolved first
+		let dirty: Vec<u64> = response.take(0)?;
+		if let Some(version) = dirty.into_iter().next() {
+			return Err(Error::IncompleteMigration(version).into());
+		}
+		let applied: BTreeSet<u64> = response.take::<Vec<u64>>(1)?.into_iter().collect();
+		// Revert the applied migrations after the target version, newest first
+		for &version in applied.iter().rev().take_while(|v| **v > target) {
+			let migration =
+				self.migrations.get(version).ok_or(Error::MigrationNotFound(version))?;
+			let down = migration.down.as_ref().ok_or(Error::IrreversibleMigration(version))?;
+			self.run(migration, down, false).await?;
+		}
+		// Apply the pending migrations up to the target version, oldest first
+		let mut pending: Vec<_> = self
+			.migrations
+			.list
+			.iter()
+			.filter(|m| m.version <= target && !applied.contains(&m.version))
+			.collect();
+		pending.sort_by_key(|m| m.version);
+		for migration in pending {
+			self.run(migration, &migration.up, true).await?;
+		}
+		Ok(())
+	}
+
+	/// Runs a migration script, and adds its version to the applied versions when `up` is set,
+	/// or removes it otherwise
+	///
+	/// A SurrealQL script updates the applied versions in the same transaction. The version
+	/// of a Rust closure is marked as dirty while it runs, so that a closure which fails part
+	/// way isn't taken as applied, or as pending.
+	async fn run(
+		&self,
+		migration: &Migration<Client>,
+		script: &Script<Client>,
+		up: bool,
+	) -> Result<()> {
+		let record = "type::thing($table, $version)";
+		match script {
+			Script::Sql(sql) => {
+				let mut statements = vec![Statement::Begin(Default::default())];
+				statements.extend(sql::parse(sql)?.0 .0);
+				let version = if up {
+					format!("CREATE {record} CONTENT {{ version: $version, name: $name, applied_at: time::now() }}")
+				} else {
+					format!("DELETE {record}")
+				};
+				statements.extend(sql::parse(&version)?.0 .0);
+				statements.push(Statement::Commit(Default::default()));
+				self.version(migration, statements).await
+			}
+			Script::Rust(step) => {
+				let dirty =
+					if up {
+						format!("CREATE {record} CONTENT {{ version: $version, name: $name, dirty: true }}")
+					} else {
+						format!("UPDATE {record} SET dirty = true")
+					};
+				self.version(migration, sql::parse(&dirty)?.0 .0).await?;
+				step(self.client.clone()).await?;
+				let done = if up {
+					format!("UPDATE {record} SET dirty = NONE, applied_at = time::now()")
+				} else {
+					format!("DELETE {record}")
+				};
+				self.version(migration, sql::parse(&done)?.0 .0).await
+			}
+		}
+	}
+
+	/// Runs statements which update the version of a migration
+	async fn version(
+		&self,
+		migration: &Migration<Client>,
+		statements: Vec<Statement>,
+	) -> Result<()> {
+		self.client
+			.query(statements)
+			.bind(("table", TABLE))
+			.bind(("version", migration.version))
+			.bind(("name", migration.name.as_str()))
+			.await?
+			.check()?;
+		Ok(())
+	}
+}
@@ -1,9 +1,17 @@
 //! Functionality for connecting to local and remote databases
 
+pub mod builder;
 pub mod engine;
 pub mod err;
+pub mod expr;
 pub mod method;
+pub mod migrations;
+#[cfg(feature = "macros")]
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+pub mod model;
 pub mod opt;
+pub mod seed;
+pub mod unit_of_work;
 
 mod conn;
 
@@ -12,7 +20,9 @@ pub use method::query::Response;
 use crate::api::conn::DbResponse;
 use crate::api::conn::Router;
 use crate::api::err::Error;
+use crate::api::opt::CircuitBreaker;
 use crate::api::opt::Endpoint;
+use crate::api::opt::RetryPolicy;
 use semver::BuildMetadata;
 use semver::VersionReq;
 use std::fmt::Debug;
@@ -38,6 +48,8 @@ pub struct Connect<C: Connection, Response> {
 	router: Arc<OnceLock<Router<C>>>,
 	address: Result<Endpoint>,
 	capacity: usize,
+	retry: RetryPolicy,
+	circuit_breaker: CircuitBreaker,
 	client: PhantomData<C>,
 	response_type: PhantomData<Response>,
 }
@@ -76,6 +88,55 @@ where
 		self.capacity = capacity;
 		self
 	}
+
+	/// Sets the policy used to retry failed requests
+	///
+	/// Only requests which are safe to send more than once are retried.
+	/// See [`RetryPolicy`] for more details.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// use surrealdb::engine::remote::ws::Ws;
+	/// use surrealdb::opt::RetryPolicy;
+	/// use surrealdb::Surreal;
+	///
+	/// let db = Surreal::new::<Ws>("localhost:8000")
+	///     .with_retry(RetryPolicy::new().max_attempts(5))
+	///     .await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+		self.retry = retry;
+		self
+	}
+
+	/// Sets the circuit breaker used to fail fast while the server is unavailable
+	///
+	/// See [`CircuitBreaker`] for more details.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// use surrealdb::engine::remote::ws::Ws;
+	/// use surrealdb::opt::CircuitBreaker;
+	/// use surrealdb::Surreal;
+	///
+	/// let db = Surreal::new::<Ws>("localhost:8000")
+	///     .with_circuit_breaker(CircuitBreaker::new().failure_threshold(3))
+	///     .await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn with_circuit_breaker(mut self, circuit_breaker: CircuitBreaker) -> Self {
+		self.circuit_breaker = circuit_breaker;
+		self
+	}
 }
 
 impl<Client> IntoFuture for Connect<Client, Surreal<Client>>
@@ -87,7 +148,11 @@ where
 
 	fn into_future(self) -> Self::IntoFuture {
 		Box::pin(async move {
-			let client = Client::connect(self.address?, self.capacity).await?;
+			let mut client = Client::connect(self.address?, self.capacity).await?;
+			if let Some(router) = Arc::get_mut(&mut client.router).and_then(OnceLock::get_mut) {
+				router.retry = self.retry;
+				router.circuit_breaker = self.circuit_breaker.into();
+			}
 			client.check_server_version().await?;
 			Ok(client)
 		})
@@ -109,7 +174,9 @@ where
 			}
 			let arc = Client::connect(self.address?, self.capacity).await?.router;
 			let cell = Arc::into_inner(arc).expect("new connection to have no references");
-			let router = cell.into_inner().expect("router to be set");
+			let mut router = cell.into_inner().expect("router to be set");
+			router.retry = self.retry;
+			router.circuit_breaker = self.circuit_breaker.into();
 			self.router.set(router).map_err(|_| Error::AlreadyConnected)?;
 			let client = Surreal {
 				router: self.router,
@@ -123,6 +190,8 @@ where
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub(crate) enum ExtraFeatures {
 	Backup,
+	Blobs,
+	StreamedParams,
 }
 
 /// A database client instance for embedded or remote databases
@@ -0,0 +1,472 @@
+//! Typed mapping of structs to tables
+//!
+//! Deriving [`SurrealModel`] for a struct ties it to a table, so that its records can be read
+//! and written without spelling out the name of the table. The table defaults to the name of
+//! the struct in snake case, and can be set with the `#[surreal(table = "...")]` attribute. The
+//! record ID is read from the `id` field of the struct, if there is one.
+//!
+//! References to models can also be passed to the methods of [`Surreal`] which take a
+//! resource, such as [`Surreal::select`] and [`Surreal::delete`].
+//!
+//! This module is only available with the `macros` feature.
+//!
+//! ```no_run
+//! use serde::Deserialize;
+//! use serde::Serialize;
+//! use surrealdb::model::SurrealModel;
+//! use surrealdb::sql::Thing;
+//!
+//! #[derive(Debug, Serialize, Deserialize, SurrealModel)]
+//! #[surreal(table = "person")]
+//! struct Person {
+//!     id: Option<Thing>,
+//!     name: String,
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> surrealdb::Result<()> {
+//! # let db = surrealdb::engine::any::connect("mem://").await?;
+//! # db.use_ns("namespace").use_db("database").await?;
+//! #
+//! let person = Person {
+//!     id: None,
+//!     name: "Tobie".to_owned(),
+//! };
+//!
+//! // Create a record with a random ID
+//! let mut person = person.create(&db).await?.unwrap();
+//!
+//! // Update the record
+//! person.name = "Jaime".to_owned();
+//! person.update(&db).await?;
+//!
+//! // Find the record again by its ID
+//! let id = person.id.clone().unwrap();
+//! let person: Option<Person> = Person::find(&db, id).await?;
+//!
+//! // Select all the records of the table
+//! let people: Vec<Person> = Person::all(&db).await?;
+//!
+//! // Delete the record
+//! let person: Option<Person> = db.delete(&person.unwrap()).await?;
+//! #
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Linked records
+//!
+//! Fields which hold other models, rather than their record IDs, can be marked with the
+//! `#[surreal(link)]` attribute. Such a field can hold a model, or an `Option` or a `Vec` of
+//! models. When a record is created or updated, the linked records which don't have a record
+//! ID yet are created first, and only their record IDs are stored in the record, all in a
+//! single transaction. Linked records which already have a record ID are linked as they are.
+//! When records are read through the model, the linked records are fetched in their place.
+//!
+//! Links are stored under the name which serde serializes the field as, so link fields can be
+//! renamed with the `rename` and `rename_all` serde attributes.
+//!
+//! ```no_run
+//! use serde::Deserialize;
+//! use serde::Serialize;
+//! use surrealdb::model::SurrealModel;
+//! use surrealdb::sql::Thing;
+//!
+//! #[derive(Debug, Serialize, Deserialize, SurrealModel)]
+//! struct Author {
+//!     id: Option<Thing>,
+//!     name: String,
+//! }
+//!
+//! #[derive(Debug, Serialize, Deserialize, SurrealModel)]
+//! struct Book {
+//!     id: Option<Thing>,
+//!     title: String,
+//!     #[surreal(link)]
+//!     author: Author,
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> surrealdb::Result<()> {
+//! # let db = surrealdb::engine::any::connect("mem://").await?;
+//! # db.use_ns("namespace").use_db("database").await?;
+//! #
+//! let book = Book {
+//!     id: None,
+//!     title: "The Hobbit".to_owned(),
+//!     author: Author {
+//!         id: None,
+//!         name: "Tolkien".to_owned(),
+//!     },
+//! };
+//!
+//! // Creates the author, and then the book, which links to the author
+//! let book = book.create(&db).await?.unwrap();
+//! assert!(book.author.id.is_some());
+//! #
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::err::Error;
+use crate::api::opt::IntoResource;
+use crate::api::opt::Resource;
+use crate::api::unit_of_work::Target;
+use crate::api::unit_of_work::UnitOfWork;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::api::Surreal;
+use crate::sql::statements::SelectStatement;
+use crate::sql::to_value;
+use crate::sql::Fetch;
+use crate::sql::Fetchs;
+use crate::sql::Fields;
+use crate::sql::Id;
+use crate::sql::Idiom;
+use crate::sql::Statement;
+use crate::sql::Table;
+use crate::sql::Thing;
+use crate::sql::Value;
+use crate::sql::Values;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde::Serializer;
+use std::fmt::Debug;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::marker::PhantomData;
+use std::pin::Pin;
+
+#[doc(inline)]
+pub use surrealdb_macros::SurrealModel;
+
+/// A struct which is mapped to a table
+///
+/// This trait should be derived, rather than implemented by hand.
+pub trait SurrealModel {
+	/// The name of the table of this model
+	fn table_name() -> &'static str;
+
+	/// The record ID of this record, if it has one
+	fn record_id(&self) -> Option<Thing>;
+
+	/// The names of the fields which link to other models
+	fn links() -> &'static [&'static str] {
+		&[]
+	}
+
+	/// Registers the writes which create the linked records of this record
+	///
+	/// Returns the values which are stored in the link fields of this record.
+	#[doc(hidden)]
+	fn stage_links(&self, _work: &mut UnitOfWork) -> Result<Vec<(&'static str, Value)>> {
+		Ok(Vec::new())
+	}
+
+	/// The table of this model
+	fn table() -> Table {
+		Table(Self::table_name().to_owned())
+	}
+
+	/// Converts this record into a database resource
+	///
+	/// Returns an error if this record does not have a record ID.
+	fn resource(&self) -> Result<Resource> {
+		match self.record_id() {
+			Some(id) => Ok(Resource::RecordId(id)),
+			None => Err(Error::MissingRecordId(Self::table_name().to_owned()).into()),
+		}
+	}
+
+	/// Selects the record of this table with the given ID
+	fn find<C>(db: &Surreal<C>, id: impl Into<Id>) -> Load<'_, C, Option<Self>>
+	where
+		Self: Sized,
+		C: Connection,
+	{
+		Load {
+			client: db,
+			what: Value::Thing(Thing::from((Self::table_name().to_owned(), id.into()))),
+			links: Self::links(),
+			response_type: PhantomData,
+		}
+	}
+
+	/// Selects all the records of this table
+	fn all<C>(db: &Surreal<C>) -> Load<'_, C, Vec<Self>>
+	where
+		Self: Sized,
+		C: Connection,
+	{
+		Load {
+			client: db,
+			what: Value::Table(Self::table()),
+			links: Self::links(),
+			response_type: PhantomData,
+		}
+	}
+
+	/// Creates this record, and the linked records which don't have a record ID yet
+	///
+	/// If this record does not have a record ID, it is created with a random one.
+	fn create<'r, C>(&'r self, db: &'r Surreal<C>) -> Save<'r, C, Self>
+	where
+		Self: Sized,
+		C: Connection,
+	{
+		Save {
+			client: db,
+			model: self,
+			update: false,
+		}
+	}
+
+	/// Replaces the contents of this record, and creates the linked records which don't have a
+	/// record ID yet
+	///
+	/// Returns an error if this record does not have a record ID.
+	fn update<'r, C>(&'r self, db: &'r Surreal<C>) -> Save<'r, C, Self>
+	where
+		Self: Sized,
+		C: Connection,
+	{
+		Save {
+			client: db,
+			model: self,
+			update: true,
+		}
+	}
+}
+
+/// The contents of a model, without its record ID
+///
+/// The record ID is passed to the database as the resource which is written, so it is left
+/// out of the contents, where it could be `NONE` for records which have not been created yet.
+struct Data<'a, T>(&'a T);
+
+impl<T> Serialize for Data<'_, T>
+where
+	T: Serialize,
+{
+	fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let mut value = to_value(self.0).map_err(serde::ser::Error::custom)?;
+		if let Value::Object(object) = &mut value {
+			object.remove("id");
+		}
+		value.serialize(serializer)
+	}
+}
+
+/// The contents of a model, with its links replaced by the records they link to
+fn staged<M>(model: &M, work: &mut UnitOfWork) -> Result<Value>
+where
+	M: SurrealModel + Serialize,
+{
+	let mut value = to_value(Data(model))?;
+	let links = model.stage_links(work)?;
+	if let Value::Object(object) = &mut value {
+		for (name, link) in links {
+			object.insert(name.to_owned(), link);
+		}
+	}
+	Ok(value)
+}
+
+/// A record resource, or the error of a model without a record ID
+#[derive(Debug)]
+struct Record(Result<Resource>);
+
+impl<R> IntoResource<Option<R>> for Record {
+	fn into_resource(self) -> Result<Resource> {
+		self.0
+	}
+}
+
+/// A future which reads records through a model, fetching their linked records
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Load<'r, C: Connection, R> {
+	client: &'r Surreal<C>,
+	what: Value,
+	links: &'static [&'static str],
+	response_type: PhantomData<R>,
+}
+
+impl<C, R> Load<'_, C, R>
+where
+	C: Connection,
+{
+	fn statement(&self) -> Statement {
+		let fetch = self.links.iter().map(|link| Fetch(Idiom::from(link.to_string()))).collect();
+		Statement::Select(SelectStatement {
+			expr: Fields::all(),
+			what: Values(vec![self.what.clone()]),
+			fetch: match self.links.is_empty() {
+				true => None,
+				false => Some(Fetchs(fetch)),
+			},
+			..Default::default()
+		})
+	}
+}
+
+impl<'r, Client, M> IntoFuture for Load<'r, Client, Option<M>>
+where
+	Client: Connection,
+	M: DeserializeOwned + Send + Sync + 'r,
+{
+	type Output = Result<Option<M>>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let records: Vec<M> = self.client.query(self.statement()).await?.take(0)?;
+			Ok(records.into_iter().next())
+		})
+	}
+}
+
+impl<'r, Client, M> IntoFuture for Load<'r, Client, Vec<M>>
+where
+	Client: Connection,
+	M: DeserializeOwned + Send + Sync + 'r,
+{
+	type Output = Result<Vec<M>>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move { self.client.query(self.statement()).await?.take(0) })
+	}
+}
+
+/// A future which creates or updates a record through a model
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Save<'r, C: Connection, M> {
+	client: &'r Surreal<C>,
+	model: &'r M,
+	update: bool,
+}
+
+impl<'r, Client, M> IntoFuture for Save<'r, Client, M>
+where
+	Client: Connection,
+	M: SurrealModel + Serialize + DeserializeOwned + Debug + Send + Sync + 'r,
+{
+	type Output = Result<Option<M>>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let Save {
+				client,
+				model,
+				update,
+			} = self;
+			// Records without links are written directly
+			if M::links().is_empty() {
+				return match update {
+					true => client.update(Record(model.resource())).content(Data(model)).await,
+					false => {
+						let id = model.record_id().unwrap_or_else(|| {
+							Thing::from((M::table_name().to_owned(), Id::rand()))
+						});
+						client.create(id).content(Data(model)).await
+					}
+				};
+			}
+			// Otherwise the linked records are written in the same transaction
+			let mut work = UnitOfWork::new();
+			let target = match update {
+				true => {
+					let id = model
+						.record_id()
+						.ok_or_else(|| Error::MissingRecordId(M::table_name().to_owned()))?;
+					let content = staged(model, &mut work)?;
+					work.update(id.clone(), content);
+					Target::Record(id)
+				}
+				false => {
+					let content = staged(model, &mut work)?;
+					match model.record_id() {
+						Some(id) => Target::Ref(work.create_with_id(id, content)),
+						None => Target::Ref(work.create(M::table_name(), content)),
+					}
+				}
+			};
+			let committed = client.submit(work).await?;
+			let id = match target {
+				Target::Record(id) => id,
+				Target::Ref(r) => committed[r].clone(),
+			};
+			M::find(client, id).await
+		})
+	}
+}
+
+#[doc(hidden)]
+pub mod __private {
+	use super::staged;
+	use super::SurrealModel;
+	use crate::api::unit_of_work::UnitOfWork;
+	use crate::api::Result;
+	use crate::sql::Array;
+	use crate::sql::Thing;
+	use crate::sql::Value;
+	use serde::Serialize;
+
+	/// Reads the record ID from the `id` field of a derived model
+	pub trait RecordId {
+		fn record_id(&self) -> Option<Thing>;
+	}
+
+	impl RecordId for Thing {
+		fn record_id(&self) -> Option<Thing> {
+			Some(self.clone())
+		}
+	}
+
+	impl RecordId for Option<Thing> {
+		fn record_id(&self) -> Option<Thing> {
+			self.clone()
+		}
+	}
+
+	/// Stages the linked records in a link field of a derived model
+	pub trait Link {
+		fn stage(&self, work: &mut UnitOfWork) -> Result<Value>;
+	}
+
+	/// Links to a model, creating it first if it does not have a record ID
+	impl<M> Link for M
+	where
+		M: SurrealModel + Serialize,
+	{
+		fn stage(&self, work: &mut UnitOfWork) -> Result<Value> {
+			if let Some(id) = self.record_id() {
+				return Ok(Value::Thing(id));
+			}
+			let content = staged(self, work)?;
+			Ok(work.create(M::table_name(), content).into())
+		}
+	}
+
+	impl<T: Link> Link for Option<T> {
+		fn stage(&self, work: &mut UnitOfWork) -> Result<Value> {
+			match self {
+				Some(link) => link.stage(work),
+				None => Ok(Value::None),
+			}
+		}
+	}
+
+	impl<T: Link> Link for Vec<T> {
+		fn stage(&self, work: &mut UnitOfWork) -> Result<Value> {
+			let links = self.iter().map(|link| link.stage(work)).collect::<Result<Vec<_>>>()?;
+			Ok(Value::Array(Array(links)))
+		}
+	}
+}
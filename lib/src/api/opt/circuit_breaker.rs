@@ -0,0 +1,261 @@
+use crate::api::err::Error;
+use crate::api::opt::retry::is_transient;
+use std::sync::Mutex;
+use std::time::Duration;
+use trice::Instant;
+
+const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+const DEFAULT_RESET_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A circuit breaker for requests sent to the server
+///
+/// After a number of consecutive connection failures the circuit opens and every
+/// request fails immediately with [`Error::CircuitOpen`] instead of waiting on a
+/// server which is known to be down. Once the reset timeout has elapsed, a single
+/// probe request is let through. If it succeeds the circuit closes again, otherwise
+/// it stays open for another reset timeout.
+///
+/// Only connection and transport errors count as failures. Errors returned by the
+/// database itself, such as a failed query, don't affect the circuit.
+///
+/// By default the circuit breaker is disabled.
+///
+/// [`Error::CircuitOpen`]: crate::error::Api::CircuitOpen
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use surrealdb::engine::remote::ws::Ws;
+/// use surrealdb::opt::CircuitBreaker;
+/// use surrealdb::Surreal;
+///
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// let breaker = CircuitBreaker::new()
+///     .failure_threshold(3)
+///     .reset_timeout(Duration::from_secs(10));
+///
+/// let db = Surreal::new::<Ws>("localhost:8000").with_circuit_breaker(breaker).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CircuitBreaker {
+	pub(crate) failure_threshold: Option<u32>,
+	pub(crate) reset_timeout: Duration,
+}
+
+impl CircuitBreaker {
+	/// Creates a circuit breaker which opens after 5 consecutive failures
+	/// and probes the server again after 30 seconds
+	pub fn new() -> Self {
+		Self {
+			failure_threshold: Some(DEFAULT_FAILURE_THRESHOLD),
+			reset_timeout: DEFAULT_RESET_TIMEOUT,
+		}
+	}
+
+	/// Sets the number of consecutive failures after which the circuit opens
+	pub fn failure_threshold(mut self, failures: u32) -> Self {
+		self.failure_threshold = Some(failures.max(1));
+		self
+	}
+
+	/// Sets how long the circuit stays open before a probe request is let through
+	pub fn reset_timeout(mut self, timeout: Duration) -> Self {
+		self.reset_timeout = timeout;
+		self
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+	/// Requests are sent as normal
+	Closed {
+		failures: u32,
+	},
+	/// Requests fail fast until the deadline
+	Open {
+		until: Instant,
+	},
+	/// A single probe request is in flight
+	HalfOpen {
+		since: Instant,
+	},
+}
+
+/// The runtime state of a circuit breaker, shared by all requests on a connection
+#[derive(Debug)]
+pub(crate) struct Breaker {
+	config: CircuitBreaker,
+	state: Mutex<State>,
+}
+
+impl Default for Breaker {
+	fn default() -> Self {
+		Self::from(CircuitBreaker::default())
+	}
+}
+
+impl From<CircuitBreaker> for Breaker {
+	fn from(config: CircuitBreaker) -> Self {
+		Self {
+			config,
+			state: Mutex::new(State::Closed {
+				failures: 0,
+			}),
+		}
+	}
+}
+
+impl Breaker {
+	/// Checks whether a request may be sent to the server
+	pub(crate) fn acquire(&self) -> Result<(), Error> {
+		if self.config.failure_threshold.is_none() {
+			return Ok(());
+		}
+		let mut state = self.state.lock().unwrap_or_else(|error| error.into_inner());
+		let now = Instant::now();
+		match *state {
+			State::Closed {
+				..
+			} => Ok(()),
+			// The reset timeout has elapsed so let this request through as a probe
+			State::Open {
+				until,
+			} if now >= until => {
+				*state = State::HalfOpen {
+					since: now,
+				};
+				Ok(())
+			}
+			// The previous probe never reported back so send another one
+			State::HalfOpen {
+				since,
+			} if now.duration_since(since) >= self.config.reset_timeout => {
+				*state = State::HalfOpen {
+					since: now,
+				};
+				Ok(())
+			}
+			State::Open {
+				..
+			}
+			| State::HalfOpen {
+				..
+			} => Err(Error::CircuitOpen),
+		}
+	}
+
+	/// Records the outcome of a request which was let through
+	pub(crate) fn record<T>(&self, result: &crate::Result<T>) {
+		let Some(threshold) = self.config.failure_threshold else {
+			return;
+		};
+		let mut state = self.state.lock().unwrap_or_else(|error| error.into_inner());
+		match result {
+			Err(error) if is_transient(error) => {
+				let failures = match *state {
+					State::Closed {
+						failures,
+					} => failures + 1,
+					// A failed probe reopens the circuit straight away
+					State::Open {
+						..
+					}
+					| State::HalfOpen {
+						..
+					} => threshold,
+				};
+				*state = match failures >= threshold {
+					true => {
+						warn!("Circuit breaker opened after {failures} consecutive failures");
+						State::Open {
+							until: Instant::now() + self.config.reset_timeout,
+						}
+					}
+					false => State::Closed {
+						failures,
+					},
+				};
+			}
+			_ => {
+				if !matches!(*state, State::Closed { .. }) {
+					debug!("Circuit breaker closed");
+				}
+				*state = State::Closed {
+					failures: 0,
+				};
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn failure() -> crate::Result<()> {
+		Err(Error::Ws("connection reset".to_owned()).into())
+	}
+
+	#[test]
+	fn disabled_by_default() {
+		let breaker = Breaker::default();
+		for _ in 0..100 {
+			breaker.record(&failure());
+		}
+		assert!(breaker.acquire().is_ok());
+	}
+
+	#[test]
+	fn opens_after_consecutive_failures() {
+		let breaker = Breaker::from(CircuitBreaker::new().failure_threshold(3));
+		breaker.record(&failure());
+		breaker.record(&failure());
+		breaker.record(&Ok(()));
+		breaker.record(&failure());
+		breaker.record(&failure());
+		assert!(breaker.acquire().is_ok());
+		breaker.record(&failure());
+		assert!(matches!(breaker.acquire(), Err(Error::CircuitOpen)));
+	}
+
+	#[test]
+	fn ignores_database_errors() {
+		let breaker = Breaker::from(CircuitBreaker::new().failure_threshold(1));
+		breaker.record::<()>(&Err(Error::Query("parse error".to_owned()).into()));
+		assert!(breaker.acquire().is_ok());
+	}
+
+	#[test]
+	fn half_opens_after_reset_timeout() {
+		let config = CircuitBreaker::new().failure_threshold(1).reset_timeout(Duration::ZERO);
+		let breaker = Breaker::from(config);
+		breaker.record(&failure());
+		// The first request after the timeout is a probe
+		assert!(breaker.acquire().is_ok());
+		// A failed probe opens the circuit again
+		breaker.record(&failure());
+		assert!(breaker.acquire().is_ok());
+		// A successful probe closes it
+		breaker.record(&Ok(()));
+		assert!(breaker.acquire().is_ok());
+		assert!(breaker.acquire().is_ok());
+	}
+
+	#[test]
+	fn fails_fast_while_probing() {
+		let config =
+			CircuitBreaker::new().failure_threshold(1).reset_timeout(Duration::from_secs(60));
+		let breaker = Breaker::from(config);
+		breaker.record(&failure());
+		assert!(matches!(breaker.acquire(), Err(Error::CircuitOpen)));
+		*breaker.state.lock().unwrap() = State::Open {
+			until: Instant::now(),
+		};
+		assert!(breaker.acquire().is_ok());
+		assert!(matches!(breaker.acquire(), Err(Error::CircuitOpen)));
+	}
+}
@@ -1,4 +1,9 @@
-use crate::{dbs::Capabilities, iam::Level};
+#[cfg(feature = "cron-schedules")]
+use crate::kvs::BackupSchedule;
+use crate::{
+	dbs::{Capabilities, HttpQuota, WriteLimit},
+	iam::Level,
+};
 use std::time::Duration;
 
 /// Configuration for server connection, including: strictness, notifications, query_timeout, transaction_timeout
@@ -17,6 +22,17 @@ pub struct Config {
 	pub(crate) password: String,
 	pub(crate) tick_interval: Option<Duration>,
 	pub(crate) capabilities: Capabilities,
+	// Only used by the local engines
+	#[cfg(feature = "cron-schedules")]
+	pub(crate) backup_schedule: Option<BackupSchedule>,
+	// Only used by the local engines
+	pub(crate) http_quota: Option<HttpQuota>,
+	// Only used by the local engines
+	pub(crate) write_limits: Vec<(String, WriteLimit)>,
+	// Only used by the local engines and the WebSocket engine
+	pub(crate) cache: Option<CacheConfig>,
+	// Only used by the WebSocket engine
+	pub(crate) max_concurrent_requests: Option<usize>,
 }
 
 impl Config {
@@ -96,4 +112,107 @@ impl Config {
 		self.capabilities = capabilities;
 		self
 	}
+
+	/// Set the schedule on which the embedded database takes backups of itself
+	#[cfg(feature = "cron-schedules")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "cron-schedules")))]
+	pub fn backup_schedule(mut self, schedule: BackupSchedule) -> Self {
+		self.backup_schedule = Some(schedule);
+		self
+	}
+
+	/// Limit the outbound calls which each database can make with the `http::` functions
+	pub fn http_quota(mut self, quota: HttpQuota) -> Self {
+		self.http_quota = Some(quota);
+		self
+	}
+
+	/// Limit the writes to a table, so that a busy table can't starve the other tables
+	pub fn write_limit(mut self, table: impl Into<String>, limit: WriteLimit) -> Self {
+		self.write_limits.push((table.into(), limit));
+		self
+	}
+
+	/// Cache the results of `select` on the client
+	///
+	/// Each table which is selected from is watched by a live query, and its cached results are
+	/// invalidated when the table changes, or when it is written through this client. Switching
+	/// the namespace, database or user forgets all cached results. The cache needs live query
+	/// notifications, so it is only used by the embedded engines and the WebSocket engine.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use std::time::Duration;
+	/// use surrealdb::opt::CacheConfig;
+	/// use surrealdb::opt::Config;
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// let cache = CacheConfig::new().capacity(10_000).ttl(Duration::from_secs(60));
+	/// let config = Config::new().with_cache(cache);
+	/// let db = surrealdb::engine::any::connect(("mem://", config)).await?;
+	/// #
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn with_cache(mut self, cache: CacheConfig) -> Self {
+		self.cache = Some(cache);
+		self
+	}
+
+	/// Set the maximum number of requests which wait for a response from the server at once
+	///
+	/// Further requests stay in the queue of the connection until a response arrives, and
+	/// the requests which are sent once the queue is full wait for room in it, so a burst of
+	/// queries can't buffer an unlimited number of pending requests. The queue is only bounded
+	/// if the connection was created with a capacity. A maximum of zero means no limit, which
+	/// is the default. This is only enforced by the WebSocket engine, which sends all requests
+	/// over a single connection.
+	///
+	/// The depth of the queue can be watched with [`Surreal::request_metrics`](crate::Surreal::request_metrics).
+	pub fn max_concurrent_requests(mut self, max: usize) -> Self {
+		self.max_concurrent_requests = match max {
+			0 => None,
+			max => Some(max),
+		};
+		self
+	}
+}
+
+/// The configuration of the client-side cache of `select` results
+///
+/// See [`Config::with_cache`].
+#[derive(Debug, Clone, Copy)]
+pub struct CacheConfig {
+	pub(crate) capacity: usize,
+	pub(crate) ttl: Option<Duration>,
+}
+
+impl Default for CacheConfig {
+	fn default() -> Self {
+		Self {
+			capacity: 1000,
+			ttl: None,
+		}
+	}
+}
+
+impl CacheConfig {
+	/// A cache of up to 1000 results, which are kept until they are invalidated
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set the number of results which are cached, after which the oldest are evicted
+	pub fn capacity(mut self, capacity: usize) -> Self {
+		self.capacity = capacity;
+		self
+	}
+
+	/// Set how long results are cached for, even if they are not invalidated
+	pub fn ttl(mut self, ttl: impl Into<Option<Duration>>) -> Self {
+		self.ttl = ttl.into();
+		self
+	}
 }
@@ -0,0 +1,372 @@
+use super::into_json;
+use crate::api::err::Error;
+use crate::api::Result;
+use crate::sql::to_value;
+use crate::sql::Part;
+use crate::sql::Value;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+/// A change which was made to a record, as a [JSON Patch] operation
+///
+/// The changes which are returned by [`return_diff`](crate::method::Update::return_diff) also
+/// hold the values which they removed or replaced, so that they can be reverted.
+///
+/// [JSON Patch]: https://jsonpatch.com/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum DiffOp {
+	/// A value was added to an object or an array
+	Add {
+		/// The [JSON Pointer](https://datatracker.ietf.org/doc/html/rfc6901) to the value
+		path: String,
+		/// The value which was added
+		value: JsonValue,
+	},
+	/// A value was removed from an object or an array
+	Remove {
+		/// The JSON Pointer to the value
+		path: String,
+		/// The value which was removed, if it is known
+		#[serde(default, deserialize_with = "known", skip_serializing_if = "Option::is_none")]
+		old: Option<JsonValue>,
+	},
+	/// A value was replaced
+	Replace {
+		/// The JSON Pointer to the value
+		path: String,
+		/// The new value
+		value: JsonValue,
+		/// The value which was replaced, if it is known
+		#[serde(default, deserialize_with = "known", skip_serializing_if = "Option::is_none")]
+		old: Option<JsonValue>,
+	},
+	/// A string was changed with a text patch
+	Change {
+		/// The JSON Pointer to the string
+		path: String,
+		/// The patch which was applied to the string
+		value: String,
+		/// The string before it was changed, if it is known
+		#[serde(default, deserialize_with = "known", skip_serializing_if = "Option::is_none")]
+		old: Option<JsonValue>,
+	},
+}
+
+/// Keeps a previous value which is set to `null`, which would otherwise be read as unknown
+fn known<'de, D>(deserializer: D) -> std::result::Result<Option<JsonValue>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	JsonValue::deserialize(deserializer).map(Some)
+}
+
+/// The changes which were made to a record
+///
+/// A diff can be applied to a copy of the record, such as one which is kept in a local cache,
+/// to bring it up to date with a write, and reverted to undo the write again.
+///
+/// # Examples
+///
+/// ```no_run
+/// use serde_json::json;
+/// use surrealdb::opt::Diff;
+///
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// # let db = surrealdb::engine::any::connect("mem://").await?;
+/// #
+/// let mut cached = json!({ "name": "Tobie", "age": 30 });
+///
+/// let diff: Option<Diff> = db
+///     .update(("person", "tobie"))
+///     .merge(json!({ "age": 31 }))
+///     .return_diff()
+///     .await?;
+///
+/// if let Some(diff) = diff {
+///     diff.apply(&mut cached)?;
+///     assert_eq!(cached["age"], 31);
+///     diff.revert(&mut cached)?;
+///     assert_eq!(cached["age"], 30);
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Diff {
+	ops: Vec<DiffOp>,
+}
+
+impl Diff {
+	/// Computes the changes which turn one value into another
+	pub fn between(before: &JsonValue, after: &JsonValue) -> Self {
+		let mut ops = Vec::new();
+		diff(before, after, String::new(), &mut ops);
+		Self {
+			ops,
+		}
+	}
+
+	/// The changes, in the order in which they are applied
+	pub fn ops(&self) -> &[DiffOp] {
+		&self.ops
+	}
+
+	/// Checks whether nothing was changed
+	pub fn is_empty(&self) -> bool {
+		self.ops.is_empty()
+	}
+
+	/// Applies the changes to a value
+	///
+	/// The value is left untouched if any of the changes can not be applied.
+	pub fn apply(&self, value: &mut JsonValue) -> Result<()> {
+		let mut tmp = value.clone();
+		for op in &self.ops {
+			match op {
+				DiffOp::Add {
+					path,
+					value,
+				} => add(&mut tmp, path, value.clone())?,
+				DiffOp::Remove {
+					path,
+					..
+				} => {
+					remove(&mut tmp, path)?;
+				}
+				DiffOp::Replace {
+					path,
+					value,
+					..
+				} => *lookup(&mut tmp, path)? = value.clone(),
+				DiffOp::Change {
+					path,
+					value,
+					..
+				} => {
+					let current = lookup(&mut tmp, path)?;
+					let text = current.as_str().unwrap_or_default();
+					*current = JsonValue::String(patch_text(text, value)?);
+				}
+			}
+		}
+		*value = tmp;
+		Ok(())
+	}
+
+	/// Reverts the changes to a value, which they were applied to
+	///
+	/// Changes can only be reverted if the values which they removed or replaced are known,
+	/// which is the case for the changes returned by the client. The value is left untouched
+	/// if any of the changes can not be reverted.
+	pub fn revert(&self, value: &mut JsonValue) -> Result<()> {
+		let mut tmp = value.clone();
+		for op in self.ops.iter().rev() {
+			match op {
+				DiffOp::Add {
+					path,
+					..
+				} => {
+					remove(&mut tmp, path)?;
+				}
+				DiffOp::Remove {
+					path,
+					old: Some(old),
+				} => add(&mut tmp, path, old.clone())?,
+				DiffOp::Replace {
+					path,
+					old: Some(old),
+					..
+				}
+				| DiffOp::Change {
+					path,
+					old: Some(old),
+					..
+				} => *lookup(&mut tmp, path)? = old.clone(),
+				DiffOp::Remove {
+					path,
+					..
+				}
+				| DiffOp::Replace {
+					path,
+					..
+				}
+				| DiffOp::Change {
+					path,
+					..
+				} => return Err(Error::IrreversibleDiff(path.clone()).into()),
+			}
+		}
+		*value = tmp;
+		Ok(())
+	}
+}
+
+/// Turns the records before and after a write into the changes which were made to them
+pub(crate) fn write_diff(value: Value) -> Result<Value> {
+	let before = into_json(value.pick(&[Part::from("before")]), false);
+	let after = into_json(value.pick(&[Part::from("after")]), false);
+	Ok(to_value(Diff::between(&before, &after))?)
+}
+
+/// Appends a segment to a JSON Pointer, escaping it
+fn push(path: &str, segment: impl ToString) -> String {
+	let segment = segment.to_string().replace('~', "~0").replace('/', "~1");
+	format!("{path}/{segment}")
+}
+
+/// Splits a JSON Pointer into the pointer to its parent, and its last unescaped segment
+fn split(path: &str) -> Result<(&str, String)> {
+	match path.rsplit_once('/') {
+		Some((parent, segment)) => Ok((parent, segment.replace("~1", "/").replace("~0", "~"))),
+		None => Err(Error::DiffPathNotFound(path.to_owned()).into()),
+	}
+}
+
+fn diff(before: &JsonValue, after: &JsonValue, path: String, ops: &mut Vec<DiffOp>) {
+	match (before, after) {
+		(JsonValue::Object(a), JsonValue::Object(b)) if a != b => {
+			for (key, old) in a {
+				if !b.contains_key(key) {
+					ops.push(DiffOp::Remove {
+						path: push(&path, key),
+						old: Some(old.clone()),
+					});
+				}
+			}
+			for (key, value) in b {
+				match a.get(key) {
+					Some(old) => diff(old, value, push(&path, key), ops),
+					None => ops.push(DiffOp::Add {
+						path: push(&path, key),
+						value: value.clone(),
+					}),
+				}
+			}
+		}
+		(JsonValue::Array(a), JsonValue::Array(b)) if a != b => {
+			for (n, (old, value)) in a.iter().zip(b).enumerate() {
+				diff(old, value, push(&path, n), ops);
+			}
+			for (n, value) in b.iter().enumerate().skip(a.len()) {
+				ops.push(DiffOp::Add {
+					path: push(&path, n),
+					value: value.clone(),
+				});
+			}
+			// Trailing items are removed from the end, so the earlier indexes stay valid
+			for (n, old) in a.iter().enumerate().skip(b.len()).rev() {
+				ops.push(DiffOp::Remove {
+					path: push(&path, n),
+					old: Some(old.clone()),
+				});
+			}
+		}
+		(a, b) if a != b => ops.push(DiffOp::Replace {
+			path,
+			value: b.clone(),
+			old: Some(a.clone()),
+		}),
+		_ => {}
+	}
+}
+
+fn lookup<'a>(value: &'a mut JsonValue, path: &str) -> Result<&'a mut JsonValue> {
+	value.pointer_mut(path).ok_or_else(|| Error::DiffPathNotFound(path.to_owned()).into())
+}
+
+fn add(value: &mut JsonValue, path: &str, item: JsonValue) -> Result<()> {
+	let (parent, segment) = split(path)?;
+	match lookup(value, parent)? {
+		JsonValue::Object(map) => {
+			map.insert(segment, item);
+		}
+		JsonValue::Array(vec) if segment == "-" => vec.push(item),
+		JsonValue::Array(vec) => match segment.parse::<usize>() {
+			Ok(n) if n <= vec.len() => vec.insert(n, item),
+			_ => return Err(Error::DiffPathNotFound(path.to_owned()).into()),
+		},
+		_ => return Err(Error::DiffPathNotFound(path.to_owned()).into()),
+	}
+	Ok(())
+}
+
+fn remove(value: &mut JsonValue, path: &str) -> Result<JsonValue> {
+	let (parent, segment) = split(path)?;
+	let removed = match lookup(value, parent)? {
+		JsonValue::Object(map) => map.remove(&segment),
+		JsonValue::Array(vec) => match segment.parse::<usize>() {
+			Ok(n) if n < vec.len() => Some(vec.remove(n)),
+			_ => None,
+		},
+		_ => None,
+	};
+	removed.ok_or_else(|| Error::DiffPathNotFound(path.to_owned()).into())
+}
+
+fn patch_text(text: &str, patch: &str) -> Result<String> {
+	let invalid = |error: dmp::Error| crate::err::Error::InvalidPatch {
+		message: format!("{error:?}"),
+	};
+	let dmp = dmp::new();
+	let patches = dmp.patch_from_text(patch.to_owned()).map_err(invalid)?;
+	let (text, _) = dmp.patch_apply(&patches, text).map_err(invalid)?;
+	Ok(text.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn apply_and_revert() {
+		let before = json!({ "name": "Tobie", "tags": ["a", "b", "c"], "age": 30, "old": null });
+		let after = json!({ "name": "Tobie", "tags": ["a"], "age": 31, "new": { "a/b": true } });
+		let diff = Diff::between(&before, &after);
+		let mut value = before.clone();
+		diff.apply(&mut value).unwrap();
+		assert_eq!(value, after);
+		diff.revert(&mut value).unwrap();
+		assert_eq!(value, before);
+	}
+
+	#[test]
+	fn records_the_previous_values() {
+		let diff: Diff = serde_json::from_value(json!([
+			{ "op": "remove", "path": "/old", "old": null },
+			{ "op": "replace", "path": "/age", "value": 31 },
+		]))
+		.unwrap();
+		assert_eq!(
+			diff.ops()[0],
+			DiffOp::Remove {
+				path: "/old".to_owned(),
+				old: Some(JsonValue::Null),
+			}
+		);
+		let mut value = json!({ "age": 30, "old": null });
+		diff.apply(&mut value).unwrap();
+		assert_eq!(value, json!({ "age": 31 }));
+		// The replaced age is not known, so the value is left as it was
+		diff.revert(&mut value).unwrap_err();
+		assert_eq!(value, json!({ "age": 31 }));
+	}
+
+	#[test]
+	fn whole_records() {
+		let record = json!({ "id": "person:tobie", "name": "Tobie" });
+		let diff = Diff::between(&JsonValue::Null, &record);
+		let mut value = JsonValue::Null;
+		diff.apply(&mut value).unwrap();
+		assert_eq!(value, record);
+		diff.revert(&mut value).unwrap();
+		assert_eq!(value, JsonValue::Null);
+	}
+}
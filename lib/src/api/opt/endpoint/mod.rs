@@ -9,8 +9,14 @@ mod fdb;
 mod indxdb;
 #[cfg(feature = "kv-mem")]
 mod mem;
+#[cfg(feature = "kv-opfs")]
+mod opfs;
+#[cfg(feature = "kv-redb")]
+mod redb;
 #[cfg(feature = "kv-rocksdb")]
 mod rocksdb;
+#[cfg(feature = "kv-sled")]
+mod sled;
 #[cfg(feature = "kv-speedb")]
 mod speedb;
 #[cfg(feature = "kv-tikv")]
@@ -0,0 +1,40 @@
+use crate::api::engine::local::Db;
+use crate::api::engine::local::Sled;
+use crate::api::opt::Config;
+use crate::api::opt::Endpoint;
+use crate::api::opt::IntoEndpoint;
+use crate::api::Result;
+use std::path::Path;
+use std::path::PathBuf;
+use url::Url;
+
+macro_rules! endpoints {
+	($($name:ty),*) => {
+		$(
+			impl IntoEndpoint<Sled> for $name {
+				type Client = Db;
+
+				fn into_endpoint(self) -> Result<Endpoint> {
+					let protocol = "sled://";
+					Ok(Endpoint {
+						url: Url::parse(protocol).unwrap(),
+						path: super::path_to_string(protocol, self),
+						config: Default::default(),
+					})
+				}
+			}
+
+			impl IntoEndpoint<Sled> for ($name, Config) {
+				type Client = Db;
+
+				fn into_endpoint(self) -> Result<Endpoint> {
+					let mut endpoint = IntoEndpoint::<Sled>::into_endpoint(self.0)?;
+					endpoint.config = self.1;
+					Ok(endpoint)
+				}
+			}
+		)*
+	}
+}
+
+endpoints!(&str, &String, String, &Path, PathBuf);
@@ -1,30 +1,77 @@
 use std::path::Path;
 use std::path::PathBuf;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::AsyncRead;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::io::AsyncWrite;
 
 #[derive(Debug)]
 #[non_exhaustive]
-pub enum ExportDestination {
+pub enum ExportDestination<W = ()> {
 	File(PathBuf),
 	Memory,
+	Writer(W),
 }
 
 /// A trait for converting inputs into database export locations
 pub trait IntoExportDestination<R> {
 	/// Converts an input into a database export location
-	fn into_export_destination(self) -> ExportDestination;
+	fn into_export_destination(self) -> ExportDestination<R>;
 }
 
 impl<T> IntoExportDestination<PathBuf> for T
 where
 	T: AsRef<Path>,
 {
-	fn into_export_destination(self) -> ExportDestination {
+	fn into_export_destination(self) -> ExportDestination<PathBuf> {
 		ExportDestination::File(self.as_ref().to_path_buf())
 	}
 }
 
 impl IntoExportDestination<()> for () {
-	fn into_export_destination(self) -> ExportDestination {
+	fn into_export_destination(self) -> ExportDestination<()> {
 		ExportDestination::Memory
 	}
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<'w, W> IntoExportDestination<&'w mut W> for &'w mut W
+where
+	W: AsyncWrite + Unpin + Send + Sync + ?Sized,
+{
+	fn into_export_destination(self) -> ExportDestination<&'w mut W> {
+		ExportDestination::Writer(self)
+	}
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ImportSource<R = ()> {
+	File(PathBuf),
+	Reader(R),
+}
+
+/// A trait for converting inputs into database import sources
+pub trait IntoImportSource<R> {
+	/// Converts an input into a database import source
+	fn into_import_source(self) -> ImportSource<R>;
+}
+
+impl<T> IntoImportSource<PathBuf> for T
+where
+	T: AsRef<Path>,
+{
+	fn into_import_source(self) -> ImportSource<PathBuf> {
+		ImportSource::File(self.as_ref().to_path_buf())
+	}
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<'r, R> IntoImportSource<&'r mut R> for &'r mut R
+where
+	R: AsyncRead + Unpin + Send + Sync + ?Sized,
+{
+	fn into_import_source(self) -> ImportSource<&'r mut R> {
+		ImportSource::Reader(self)
+	}
+}
@@ -0,0 +1,166 @@
+//! Serde helpers for storing `geo` types as geometries
+//!
+//! Fields of types such as [`geo::Point`] or [`geo::Polygon`] are otherwise stored as plain
+//! objects, which can't be used with geospatial functions, operators or indexes. This module
+//! is used with `#[serde(with = "...")]` on the fields of records, so that they are stored as
+//! geometries and read back from them. When serialized to formats other than SurrealDB values,
+//! such as JSON, the fields are written as GeoJSON.
+//!
+//! ```
+//! use geo::Point;
+//! use geo::Polygon;
+//! use serde::Deserialize;
+//! use serde::Serialize;
+//!
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct Store {
+//!     #[serde(with = "surrealdb::opt::geojson")]
+//!     location: Point,
+//!     #[serde(with = "surrealdb::opt::geojson")]
+//!     delivery_area: Polygon,
+//! }
+//! ```
+//!
+//! Values which are bound to queries as parameters are wrapped in [`Geo`] instead.
+
+use crate::sql::geometry::GeoJson;
+use crate::sql::geometry::GEOJSON_TOKEN;
+use crate::sql::Geometry;
+use serde::de;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use std::fmt;
+
+/// Serializes a value as a geometry
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+	T: Clone + Into<Geometry>,
+	S: Serializer,
+{
+	let geometry = value.clone().into();
+	serializer.serialize_newtype_struct(GEOJSON_TOKEN, &GeoJson::from(&geometry))
+}
+
+/// Deserializes a value from a geometry, either in GeoJSON or in its native representation
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+	T: TryFrom<Geometry>,
+	T::Error: fmt::Display,
+	D: Deserializer<'de>,
+{
+	let geometry = Geometry::deserialize(deserializer)?;
+	T::try_from(geometry).map_err(de::Error::custom)
+}
+
+/// A `geo` type which is serialized as a geometry
+///
+/// This is used to bind `geo` types to queries, so that they can be used with geospatial
+/// functions, operators and indexes.
+///
+/// ```no_run
+/// use geo::Rect;
+/// use surrealdb::opt::Geo;
+///
+/// # #[derive(serde::Deserialize)]
+/// # struct Store {}
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// # let db = surrealdb::engine::any::connect("mem://").await?;
+/// let viewport = Rect::new((-0.5, 51.3), (0.3, 51.7));
+/// let stores: Vec<Store> = db
+///     .query("SELECT * FROM store WHERE geo::intersects(location, $viewport)")
+///     .bind(("viewport", Geo(viewport)))
+///     .await?
+///     .take(0)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geo<T>(pub T);
+
+impl<T> Serialize for Geo<T>
+where
+	T: Clone + Into<Geometry>,
+{
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serialize(&self.0, serializer)
+	}
+}
+
+impl<'de, T> Deserialize<'de> for Geo<T>
+where
+	T: TryFrom<Geometry>,
+	T::Error: fmt::Display,
+{
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		deserialize(deserializer).map(Geo)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Geo;
+	use crate::opt::from_value;
+	use crate::sql::to_value;
+	use crate::sql::Geometry;
+	use crate::sql::Value;
+	use geo::LineString;
+	use geo::Point;
+	use geo::Polygon;
+	use serde::Deserialize;
+	use serde::Serialize;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Store {
+		#[serde(with = "super")]
+		location: Point,
+		#[serde(with = "super")]
+		area: Polygon,
+	}
+
+	#[test]
+	fn stores_geo_types_as_geometries() {
+		let store = Store {
+			location: Point::new(0.5, 0.5),
+			area: Polygon::new(
+				LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 0.)]),
+				vec![],
+			),
+		};
+		let value = to_value(&store).unwrap();
+		let Value::Object(object) = &value else {
+			panic!("{value:?}");
+		};
+		assert!(matches!(object["location"], Value::Geometry(Geometry::Point(..))));
+		assert!(matches!(object["area"], Value::Geometry(Geometry::Polygon(..))));
+		let response: Store = from_value(value).unwrap();
+		assert_eq!(response, store);
+		let json = serde_json::to_value(&store).unwrap();
+		assert_eq!(
+			json["location"],
+			serde_json::json!({ "type": "Point", "coordinates": [0.5, 0.5] })
+		);
+		let value = crate::sql::value("{ location: (0.5, 0.5), area: (1, 1) }").unwrap();
+		from_value::<Store>(value).unwrap_err();
+	}
+	#[test]
+	fn binds_geo_types_as_geometries() {
+		let rect = geo::Rect::new((0., 0.), (1., 1.));
+		let value = to_value(("viewport", Geo(rect))).unwrap();
+		let Value::Array(array) = &value else {
+			panic!("{value:?}");
+		};
+		assert_eq!(array[1], Value::Geometry(Geometry::from(rect)));
+		let Geo(point): Geo<Point> =
+			from_value(Value::from(Geometry::from(Point::new(1., 2.)))).unwrap();
+		assert_eq!(point, Point::new(1., 2.));
+	}
+}
@@ -0,0 +1,242 @@
+use crate::api::Connection;
+use crate::api::Result;
+use crate::api::Surreal;
+use crate::sql::Thing;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A link to a record, which may or may not have been fetched
+///
+/// Graph traversals such as `->wrote->post` return record IDs, unless they are fetched with
+/// `FETCH`, in which case they return the records themselves. A field of type `Link<T>`
+/// can hold either of them, so that the same struct works for both kinds of queries.
+///
+/// Links which were not fetched can be loaded lazily, when they are needed, with
+/// [`load`](Link::load), or in a single request for many links with
+/// [`load_many`](Link::load_many).
+///
+/// # Examples
+///
+/// ```no_run
+/// use serde::Deserialize;
+/// use surrealdb::opt::Link;
+/// use surrealdb::sql::Thing;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Post {
+///     title: String,
+/// }
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Author {
+///     id: Thing,
+///     #[serde(rename = "->wrote->post")]
+///     posts: Vec<Link<Post>>,
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// # let db = surrealdb::engine::any::connect("mem://").await?;
+/// let sql = "SELECT id, ->wrote->post FROM author FETCH ->wrote->post";
+/// let authors: Vec<Author> = db.query(sql).await?.take(0)?;
+/// for post in authors.iter().flat_map(|author| &author.posts) {
+///     if let Some(post) = post.record() {
+///         println!("{}", post.title);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Link<T> {
+	/// The ID of a record which was not fetched
+	Id(Thing),
+	/// A record which was fetched
+	Record(T),
+}
+
+impl<T> Link<T> {
+	/// The ID of the record, if it was not fetched
+	pub fn id(&self) -> Option<&Thing> {
+		match self {
+			Self::Id(id) => Some(id),
+			Self::Record(..) => None,
+		}
+	}
+
+	/// The record, if it was fetched
+	pub fn record(&self) -> Option<&T> {
+		match self {
+			Self::Id(..) => None,
+			Self::Record(record) => Some(record),
+		}
+	}
+
+	/// Converts the link into the record, if it was fetched
+	pub fn into_record(self) -> Option<T> {
+		match self {
+			Self::Id(..) => None,
+			Self::Record(record) => Some(record),
+		}
+	}
+
+	/// Checks whether the record was fetched
+	pub fn is_fetched(&self) -> bool {
+		matches!(self, Self::Record(..))
+	}
+}
+
+impl<T> Link<T>
+where
+	T: DeserializeOwned + std::fmt::Debug,
+{
+	/// Loads the record if it was not fetched yet, returning it
+	///
+	/// The loaded record replaces the ID of the link, so it is only loaded once. `None` is
+	/// returned if the record doesn't exist, in which case the link keeps its ID.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use serde::Deserialize;
+	/// use surrealdb::opt::Link;
+	///
+	/// #[derive(Debug, Deserialize)]
+	/// struct Person {
+	///     name: String,
+	///     manager: Option<Link<Person>>,
+	/// }
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// let person: Option<Person> = db.select(("person", "tobie")).await?;
+	/// if let Some(manager) = person.and_then(|person| person.manager).as_mut() {
+	///     if let Some(manager) = manager.load(&db).await? {
+	///         println!("{}", manager.name);
+	///     }
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub async fn load<C>(&mut self, db: &Surreal<C>) -> Result<Option<&T>>
+	where
+		C: Connection,
+	{
+		if let Self::Id(id) = self {
+			match db.select(id.clone()).await? {
+				Some(record) => *self = Self::Record(record),
+				None => return Ok(None),
+			}
+		}
+		Ok(self.record())
+	}
+
+	/// Loads the records of all the links which were not fetched yet, in a single request
+	///
+	/// The loaded records replace the IDs of their links. Links to records which don't
+	/// exist keep their IDs.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// use serde::Deserialize;
+	/// use surrealdb::opt::Link;
+	///
+	/// #[derive(Debug, Deserialize)]
+	/// struct Post {
+	///     title: String,
+	/// }
+	///
+	/// #[derive(Debug, Deserialize)]
+	/// struct Author {
+	///     posts: Vec<Link<Post>>,
+	/// }
+	///
+	/// # #[tokio::main]
+	/// # async fn main() -> surrealdb::Result<()> {
+	/// # let db = surrealdb::engine::any::connect("mem://").await?;
+	/// let mut authors: Vec<Author> = db.select("author").await?;
+	/// // Loads the posts of all the authors at once
+	/// let mut posts: Vec<_> = authors.iter_mut().flat_map(|author| &mut author.posts).collect();
+	/// Link::load_many(&mut posts, &db).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub async fn load_many<C, L>(links: &mut [L], db: &Surreal<C>) -> Result<()>
+	where
+		C: Connection,
+		L: AsMut<Self>,
+	{
+		let ids: Vec<Thing> =
+			links.iter_mut().filter_map(|link| link.as_mut().id().cloned()).collect();
+		if ids.is_empty() {
+			return Ok(());
+		}
+		let mut records = db.select_many::<T, _>(ids).await?.into_iter();
+		for link in links.iter_mut().map(AsMut::as_mut).filter(|link| !link.is_fetched()) {
+			if let Some(record) = records.next().flatten() {
+				*link = Self::Record(record);
+			}
+		}
+		Ok(())
+	}
+}
+
+impl<T> AsMut<Link<T>> for Link<T> {
+	fn as_mut(&mut self) -> &mut Link<T> {
+		self
+	}
+}
+
+impl<T> From<Thing> for Link<T> {
+	fn from(id: Thing) -> Self {
+		Self::Id(id)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::opt::from_value;
+	use crate::sql;
+
+	#[derive(Debug, PartialEq, Deserialize)]
+	struct Post {
+		id: Thing,
+		title: String,
+	}
+
+	#[derive(Debug, PartialEq, Deserialize)]
+	struct Author {
+		id: Thing,
+		editor: Option<Thing>,
+		posts: Vec<Link<Post>>,
+	}
+
+	#[test]
+	fn deserializes_mixed_links() {
+		let value = sql::value(
+			r#"{
+				id: author:tobie,
+				editor: NONE,
+				posts: [post:one, { id: post:two, title: "Two" }]
+			}"#,
+		)
+		.unwrap();
+		let author: Author = from_value(value).unwrap();
+		assert_eq!(author.id, sql::thing("author:tobie").unwrap());
+		assert_eq!(author.editor, None);
+		assert_eq!(author.posts[0], Link::Id(sql::thing("post:one").unwrap()));
+		assert_eq!(
+			author.posts[1].record(),
+			Some(&Post {
+				id: sql::thing("post:two").unwrap(),
+				title: "Two".to_owned(),
+			})
+		);
+		assert!(!author.posts[0].is_fetched());
+	}
+}
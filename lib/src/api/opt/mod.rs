@@ -2,12 +2,18 @@
 
 pub mod auth;
 pub mod capabilities;
+pub mod geojson;
+pub mod number;
 
+mod circuit_breaker;
 mod config;
+mod diff;
 mod endpoint;
 mod export;
+mod link;
 mod query;
 mod resource;
+mod retry;
 mod tls;
 
 use crate::api::err::Error;
@@ -16,23 +22,33 @@ use crate::sql::id::Gen;
 use crate::sql::to_value;
 use crate::sql::Thing;
 use crate::sql::Value;
-use dmp::Diff;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::json;
 use serde_json::Map;
 use serde_json::Value as JsonValue;
 
+pub use circuit_breaker::*;
 pub use config::*;
+pub use diff::*;
 pub use endpoint::*;
 pub use export::*;
+pub use geojson::Geo;
+pub use link::*;
 pub use query::*;
 pub use resource::*;
+pub use retry::*;
 pub use tls::*;
 
 /// Record ID
 pub type RecordId = Thing;
 
+/// The ID of a record within its table
+///
+/// Sortable IDs can be generated on the client with [`Id::ulid`] and [`Id::uuid_v7`], or
+/// as a complete record ID with [`Thing::ulid`] and [`Thing::uuid_v7`].
+pub type Id = crate::sql::Id;
+
 type UnitOp<'a> = InnerOp<'a, ()>;
 
 #[derive(Debug, Serialize)]
@@ -53,6 +69,56 @@ enum InnerOp<'a, T> {
 		path: &'a str,
 		value: String,
 	},
+	Move {
+		from: &'a str,
+		path: &'a str,
+	},
+	Copy {
+		from: &'a str,
+		path: &'a str,
+	},
+	Test {
+		path: &'a str,
+		value: T,
+	},
+}
+
+/// Builds a [JSON Pointer] path for a [`PatchOp`] from its segments
+///
+/// Each segment can be any value which implements `Display`, such as a field name or an
+/// array index. Any `~` or `/` characters in a segment are escaped, and a path which starts
+/// with a `/` is only split on slashes, so the path always points to the segments it was
+/// built from, even when they contain dots.
+///
+/// # Examples
+///
+/// ```
+/// use surrealdb::opt::PatchOp;
+/// use surrealdb::path;
+///
+/// assert_eq!(path!["biscuits", 0, "name"], "/biscuits/0/name");
+/// assert_eq!(path!["tags", "-"], "/tags/-");
+/// assert_eq!(path!["a/b", "m~n"], "/a~1b/m~0n");
+/// assert_eq!(path!["settings", "theme.dark"], "/settings/theme.dark");
+///
+/// PatchOp::replace(path!["biscuits", 0, "name"], "Chocolate Digestive")
+/// # ;
+/// ```
+///
+/// [JSON Pointer]: https://datatracker.ietf.org/doc/html/rfc6901
+#[macro_export]
+macro_rules! path {
+	($($segment:expr),* $(,)?) => {{
+		#[allow(unused_mut)]
+		let mut path = ::std::string::String::new();
+		$(
+			path.push('/');
+			path.push_str(
+				&::std::string::ToString::to_string(&$segment).replace('~', "~0").replace('/', "~1"),
+			);
+		)*
+		path
+	}};
 }
 
 /// A [JSON Patch] operation
@@ -62,6 +128,9 @@ enum InnerOp<'a, T> {
 /// > JSON Patch is a format for describing changes to a JSON document.
 /// > It can be used to avoid sending a whole document when only a part has changed.
 ///
+/// Paths can be written by hand, or built from their segments with the [`path!`] macro.
+/// Anything which accepts a list of operations also accepts a single one.
+///
 /// [JSON Patch]: https://jsonpatch.com/
 #[derive(Debug)]
 #[must_use]
@@ -81,12 +150,12 @@ impl PatchOp {
 	/// PatchOp::add("/biscuits/1", json!({ "name": "Ginger Nut" }))
 	/// # ;
 	/// ```
-	pub fn add<T>(path: &str, value: T) -> Self
+	pub fn add<T>(path: impl AsRef<str>, value: T) -> Self
 	where
 		T: Serialize,
 	{
 		Self(to_value(InnerOp::Add {
-			path,
+			path: path.as_ref(),
 			value,
 		}))
 	}
@@ -109,9 +178,9 @@ impl PatchOp {
 	/// PatchOp::remove("/biscuits/0")
 	/// # ;
 	/// ```
-	pub fn remove(path: &str) -> Self {
+	pub fn remove(path: impl AsRef<str>) -> Self {
 		Self(to_value(UnitOp::Remove {
-			path,
+			path: path.as_ref(),
 		}))
 	}
 
@@ -126,23 +195,85 @@ impl PatchOp {
 	/// PatchOp::replace("/biscuits/0/name", "Chocolate Digestive")
 	/// # ;
 	/// ```
-	pub fn replace<T>(path: &str, value: T) -> Self
+	pub fn replace<T>(path: impl AsRef<str>, value: T) -> Self
 	where
 		T: Serialize,
 	{
 		Self(to_value(InnerOp::Replace {
-			path,
+			path: path.as_ref(),
 			value,
 		}))
 	}
 
 	/// Changes a value
-	pub fn change(path: &str, diff: Diff) -> Self {
+	pub fn change(path: impl AsRef<str>, diff: dmp::Diff) -> Self {
 		Self(to_value(UnitOp::Change {
-			path,
+			path: path.as_ref(),
 			value: diff.text,
 		}))
 	}
+
+	/// Removes a value from one location and adds it to another.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use surrealdb::opt::PatchOp;
+	/// PatchOp::r#move("/biscuits", "/cookies")
+	/// # ;
+	/// ```
+	pub fn r#move(from: impl AsRef<str>, path: impl AsRef<str>) -> Self {
+		Self(to_value(UnitOp::Move {
+			from: from.as_ref(),
+			path: path.as_ref(),
+		}))
+	}
+
+	/// Copies a value from one location to another.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use surrealdb::opt::PatchOp;
+	/// PatchOp::copy("/biscuits/0", "/best_biscuit")
+	/// # ;
+	/// ```
+	pub fn copy(from: impl AsRef<str>, path: impl AsRef<str>) -> Self {
+		Self(to_value(UnitOp::Copy {
+			from: from.as_ref(),
+			path: path.as_ref(),
+		}))
+	}
+
+	/// Tests that a value is set to the given value.
+	///
+	/// If the test fails, none of the operations in the patch are applied.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use surrealdb::opt::PatchOp;
+	/// PatchOp::test("/best_biscuit/name", "Choco Leibniz")
+	/// # ;
+	/// ```
+	pub fn test<T>(path: impl AsRef<str>, value: T) -> Self
+	where
+		T: Serialize,
+	{
+		Self(to_value(InnerOp::Test {
+			path: path.as_ref(),
+			value,
+		}))
+	}
+}
+
+impl IntoIterator for PatchOp {
+	type Item = PatchOp;
+	type IntoIter = std::iter::Once<PatchOp>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		std::iter::once(self)
+	}
 }
 
 impl From<Value> for serde_json::Value {
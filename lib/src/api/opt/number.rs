@@ -0,0 +1,252 @@
+//! Serde helpers for numbers which must not lose precision
+//!
+//! Decimals, 128-bit integers and big integers don't fit in an `f64`, and would otherwise be
+//! stored as strings or rounded when they are read back. These modules are used with
+//! `#[serde(with = "...")]` on the fields of records, so that they are stored as numbers and
+//! read back exactly, whether they were stored as integers, floats or decimals.
+//!
+//! ```
+//! use num_bigint::BigInt;
+//! use rust_decimal::Decimal;
+//! use serde::Deserialize;
+//! use serde::Serialize;
+//!
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct Account {
+//!     #[serde(with = "surrealdb::opt::number::decimal")]
+//!     balance: Decimal,
+//!     #[serde(with = "surrealdb::opt::number::int128")]
+//!     transactions: i128,
+//!     #[serde(with = "surrealdb::opt::number::bigint")]
+//!     nonce: BigInt,
+//! }
+//! ```
+
+use crate::sql::Number;
+use num_bigint::BigInt;
+use rust_decimal::prelude::*;
+use serde::de;
+use serde::de::Visitor;
+use serde::ser::Error as _;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use std::fmt;
+
+/// A number as it was received, before it is converted to the type of the field
+enum Received {
+	Int(i128),
+	Float(f64),
+	Str(String),
+}
+
+impl Received {
+	/// Parses a decimal which has no fractional part
+	fn whole(value: &str) -> Option<Decimal> {
+		let decimal = value.parse::<Decimal>().ok()?;
+		decimal.fract().is_zero().then_some(decimal)
+	}
+}
+
+struct ReceivedVisitor;
+
+impl<'de> Visitor<'de> for ReceivedVisitor {
+	type Value = Received;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("a number")
+	}
+
+	fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
+		Ok(Received::Int(value.into()))
+	}
+
+	fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+		Ok(Received::Int(value.into()))
+	}
+
+	fn visit_i128<E>(self, value: i128) -> Result<Self::Value, E> {
+		Ok(Received::Int(value))
+	}
+
+	fn visit_u128<E>(self, value: u128) -> Result<Self::Value, E> {
+		Ok(match i128::try_from(value) {
+			Ok(value) => Received::Int(value),
+			Err(_) => Received::Str(value.to_string()),
+		})
+	}
+
+	fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E> {
+		Ok(Received::Float(value))
+	}
+
+	// Decimals are received as strings, so that they aren't rounded
+	fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+		Ok(Received::Str(value.to_owned()))
+	}
+}
+
+fn invalid<E: de::Error>(value: impl fmt::Display, expected: &str) -> E {
+	E::custom(format!("`{value}` can't be converted to {expected} without losing precision"))
+}
+
+/// Serializes a whole number as an integer if it fits, or as a decimal otherwise
+fn serialize_whole<S>(value: i128, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	match i64::try_from(value) {
+		Ok(value) => serializer.serialize_i64(value),
+		Err(_) => serializer.serialize_i128(value),
+	}
+}
+
+/// Stores a [`Decimal`] as a decimal number, rather than as a string
+///
+/// Values are read back from integers, floats and decimals. When serialized to formats other
+/// than SurrealDB values, such as JSON, the decimal is written as `{"Decimal":"1.50"}`.
+pub mod decimal {
+	use super::*;
+
+	/// Serializes a decimal as a decimal number
+	pub fn serialize<S>(value: &Decimal, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		Number::Decimal(*value).serialize(serializer)
+	}
+
+	/// Deserializes a decimal from any number
+	pub fn deserialize<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		match deserializer.deserialize_any(ReceivedVisitor)? {
+			Received::Int(v) => Decimal::from_i128(v).ok_or_else(|| invalid(v, "a decimal")),
+			Received::Float(v) => Decimal::try_from(v).map_err(|_| invalid(v, "a decimal")),
+			Received::Str(v) => v.parse().map_err(|_| invalid(v, "a decimal")),
+		}
+	}
+}
+
+/// Reads an [`i128`] back exactly, including when it was stored as a decimal
+///
+/// Values which don't fit in an `i64` are stored as decimals.
+pub mod int128 {
+	use super::*;
+
+	/// Serializes a 128-bit integer as an integer, or as a decimal if it is too large
+	pub fn serialize<S>(value: &i128, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serialize_whole(*value, serializer)
+	}
+
+	/// Deserializes a 128-bit integer from any whole number
+	pub fn deserialize<'de, D>(deserializer: D) -> Result<i128, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		match deserializer.deserialize_any(ReceivedVisitor)? {
+			Received::Int(v) => Ok(v),
+			Received::Float(v) if v.fract() == 0.0 => {
+				v.to_i128().ok_or_else(|| invalid(v, "an i128"))
+			}
+			Received::Float(v) => Err(invalid(v, "an i128")),
+			Received::Str(v) => match v.parse() {
+				Ok(v) => Ok(v),
+				Err(_) => Received::whole(&v)
+					.and_then(|decimal| decimal.to_i128())
+					.ok_or_else(|| invalid(v, "an i128")),
+			},
+		}
+	}
+}
+
+/// Reads a [`BigInt`] back exactly, including when it was stored as a decimal
+///
+/// Values which don't fit in an `i64` are stored as decimals, and values which don't fit
+/// in a decimal can't be stored.
+pub mod bigint {
+	use super::*;
+
+	/// Serializes a big integer as an integer, or as a decimal if it is too large
+	pub fn serialize<S>(value: &BigInt, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		match value.to_i128() {
+			Some(value) => serialize_whole(value, serializer),
+			None => Err(S::Error::custom(format!("`{value}` is too large to be stored"))),
+		}
+	}
+
+	/// Deserializes a big integer from any whole number
+	pub fn deserialize<'de, D>(deserializer: D) -> Result<BigInt, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		match deserializer.deserialize_any(ReceivedVisitor)? {
+			Received::Int(v) => Ok(BigInt::from(v)),
+			Received::Float(v) if v.fract() == 0.0 => {
+				BigInt::from_f64(v).ok_or_else(|| invalid(v, "a BigInt"))
+			}
+			Received::Float(v) => Err(invalid(v, "a BigInt")),
+			Received::Str(v) => match v.parse() {
+				Ok(v) => Ok(v),
+				Err(_) => Received::whole(&v)
+					.and_then(|decimal| decimal.to_i128())
+					.map(BigInt::from)
+					.ok_or_else(|| invalid(v, "a BigInt")),
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::opt::from_value;
+	use crate::sql::to_value;
+	use crate::sql::Value;
+	use serde::Deserialize;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Account {
+		#[serde(with = "decimal")]
+		balance: Decimal,
+		#[serde(with = "int128")]
+		transactions: i128,
+		#[serde(with = "bigint")]
+		nonce: BigInt,
+	}
+
+	#[test]
+	fn round_trips_without_losing_precision() {
+		let account = Account {
+			balance: "12345678901234567890.12".parse().unwrap(),
+			transactions: i64::MAX as i128 * 4,
+			nonce: BigInt::from(i64::MAX) * 1000,
+		};
+		let value = to_value(&account).unwrap();
+		let Value::Object(object) = &value else {
+			panic!("{value:?}");
+		};
+		assert!(matches!(object["balance"], Value::Number(Number::Decimal(..))));
+		assert!(matches!(object["transactions"], Value::Number(Number::Decimal(..))));
+		let response: Account = from_value(value).unwrap();
+		assert_eq!(response, account);
+	}
+
+	#[test]
+	fn reads_any_kind_of_number() {
+		let value = crate::sql::value("{ balance: 10, transactions: 2.0, nonce: 3dec }").unwrap();
+		let account: Account = from_value(value).unwrap();
+		assert_eq!(account.balance, Decimal::from(10));
+		assert_eq!(account.transactions, 2);
+		assert_eq!(account.nonce, BigInt::from(3));
+		let value = crate::sql::value("{ balance: 0.1, transactions: 2.5, nonce: 3 }").unwrap();
+		from_value::<Account>(value).unwrap_err();
+	}
+}
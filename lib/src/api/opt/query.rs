@@ -17,6 +17,36 @@ pub trait IntoQuery {
 	fn into_query(self) -> Result<Vec<Statement>>;
 }
 
+/// A trait for converting inputs into the condition of a statement
+pub trait IntoCondition {
+	/// Converts an input into a condition, such as the `WHERE` clause of a statement
+	fn into_condition(self) -> Result<Value>;
+}
+
+impl IntoCondition for Value {
+	fn into_condition(self) -> Result<Value> {
+		Ok(self)
+	}
+}
+
+impl IntoCondition for &str {
+	fn into_condition(self) -> Result<Value> {
+		Ok(sql::value(self)?)
+	}
+}
+
+impl IntoCondition for &String {
+	fn into_condition(self) -> Result<Value> {
+		self.as_str().into_condition()
+	}
+}
+
+impl IntoCondition for String {
+	fn into_condition(self) -> Result<Value> {
+		self.as_str().into_condition()
+	}
+}
+
 impl IntoQuery for sql::Query {
 	#[tracing::instrument(ret, err)]
 	fn into_query(self) -> Result<Vec<Statement>> {
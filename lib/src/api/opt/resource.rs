@@ -228,6 +228,53 @@ impl<R> IntoResource<Vec<R>> for String {
 	}
 }
 
+/// A trait for converting inputs into record IDs
+pub trait IntoRecordId: Sized {
+	/// Converts an input into a record ID
+	fn into_record_id(self) -> Result<Thing>;
+}
+
+impl IntoRecordId for Thing {
+	fn into_record_id(self) -> Result<Thing> {
+		Ok(self)
+	}
+}
+
+impl IntoRecordId for &Thing {
+	fn into_record_id(self) -> Result<Thing> {
+		Ok(self.clone())
+	}
+}
+
+impl IntoRecordId for &str {
+	fn into_record_id(self) -> Result<Thing> {
+		sql::thing(self).map_err(|_| Error::InvalidRecordId(self.to_owned()).into())
+	}
+}
+
+impl IntoRecordId for &String {
+	fn into_record_id(self) -> Result<Thing> {
+		self.as_str().into_record_id()
+	}
+}
+
+impl IntoRecordId for String {
+	fn into_record_id(self) -> Result<Thing> {
+		self.as_str().into_record_id()
+	}
+}
+
+impl<T, I> IntoRecordId for (T, I)
+where
+	T: Into<String>,
+	I: Into<Id>,
+{
+	fn into_record_id(self) -> Result<Thing> {
+		let (table, id) = self;
+		Ok((table.into(), id.into()).into())
+	}
+}
+
 /// Holds the `start` and `end` bounds of a range query
 #[derive(Debug)]
 pub struct Range<T> {
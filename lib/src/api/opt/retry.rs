@@ -0,0 +1,146 @@
+use crate::api::err::Error;
+use std::time::Duration;
+
+const DEFAULT_BACKOFF: Duration = Duration::from_millis(100);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// A policy for retrying failed requests
+///
+/// Retries are only ever applied to requests which are safe to send more than once. These
+/// are requests which only read data, like `select`, `health` and `version`, as well as
+/// queries which have been explicitly marked as idempotent using [`Query::idempotent`].
+///
+/// Other methods which write data, like `create`, `update` and `delete`, are never retried,
+/// even when a particular write is safe to send again. Such a write can be retried by sending
+/// it as a query which is marked as idempotent instead, for example an `UPDATE` of a single
+/// record which sets its fields to fixed values.
+///
+/// By default requests are not retried, and only connection and transport errors are retried.
+///
+/// [`Query::idempotent`]: crate::method::Query::idempotent
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use surrealdb::engine::remote::ws::Ws;
+/// use surrealdb::opt::RetryPolicy;
+/// use surrealdb::Surreal;
+///
+/// # #[tokio::main]
+/// # async fn main() -> surrealdb::Result<()> {
+/// let retry = RetryPolicy::new()
+///     .max_attempts(5)
+///     .backoff(Duration::from_millis(50))
+///     .max_backoff(Duration::from_secs(2));
+///
+/// let db = Surreal::new::<Ws>("localhost:8000").with_retry(retry).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+	pub(crate) max_attempts: u32,
+	pub(crate) backoff: Duration,
+	pub(crate) max_backoff: Duration,
+	pub(crate) retry_on: fn(&crate::Error) -> bool,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self {
+			max_attempts: 1,
+			backoff: DEFAULT_BACKOFF,
+			max_backoff: DEFAULT_MAX_BACKOFF,
+			retry_on: is_transient,
+		}
+	}
+}
+
+impl RetryPolicy {
+	/// Creates a policy which retries failed requests up to 3 times
+	pub fn new() -> Self {
+		Self {
+			max_attempts: 3,
+			..Default::default()
+		}
+	}
+
+	/// Sets the maximum number of times a request is sent, including the first attempt
+	pub fn max_attempts(mut self, attempts: u32) -> Self {
+		self.max_attempts = attempts.max(1);
+		self
+	}
+
+	/// Sets the delay before the first retry
+	///
+	/// The delay is doubled after each subsequent failure.
+	pub fn backoff(mut self, backoff: Duration) -> Self {
+		self.backoff = backoff;
+		self
+	}
+
+	/// Sets the upper limit on the delay between retries
+	pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+		self.max_backoff = max_backoff;
+		self
+	}
+
+	/// Sets the function used to decide whether a failed request should be retried
+	///
+	/// By default only connection and transport errors are retried.
+	pub fn retry_on(mut self, retry_on: fn(&crate::Error) -> bool) -> Self {
+		self.retry_on = retry_on;
+		self
+	}
+
+	pub(crate) fn should_retry(&self, attempt: u32, error: &crate::Error) -> bool {
+		attempt < self.max_attempts && (self.retry_on)(error)
+	}
+
+	pub(crate) fn delay(&self, attempt: u32) -> Duration {
+		let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+		self.backoff.saturating_mul(factor).min(self.max_backoff)
+	}
+}
+
+/// Checks whether an error was caused by the connection rather than the request itself
+pub(crate) fn is_transient(error: &crate::Error) -> bool {
+	matches!(error, crate::Error::Api(Error::Http(..) | Error::Ws(..)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn disabled_by_default() {
+		let retry = RetryPolicy::default();
+		let error = Error::Ws("connection reset".to_owned()).into();
+		assert!(!retry.should_retry(1, &error));
+	}
+
+	#[test]
+	fn only_transient_errors() {
+		let retry = RetryPolicy::new();
+		let error = Error::Ws("connection reset".to_owned()).into();
+		assert!(retry.should_retry(1, &error));
+		assert!(retry.should_retry(2, &error));
+		assert!(!retry.should_retry(3, &error));
+		let error = Error::Query("parse error".to_owned()).into();
+		assert!(!retry.should_retry(1, &error));
+		let error = Error::InternalError("invalid response".to_owned()).into();
+		assert!(!retry.should_retry(1, &error));
+	}
+
+	#[test]
+	fn exponential_backoff() {
+		let retry = RetryPolicy::new()
+			.backoff(Duration::from_millis(100))
+			.max_backoff(Duration::from_millis(300));
+		assert_eq!(retry.delay(1), Duration::from_millis(100));
+		assert_eq!(retry.delay(2), Duration::from_millis(200));
+		assert_eq!(retry.delay(3), Duration::from_millis(300));
+		assert_eq!(retry.delay(30), Duration::from_millis(300));
+	}
+}
@@ -0,0 +1,378 @@
+//! Seed data, which bootstraps a database with its schema and reference records
+//!
+//! Seed data is declared in a JSON file, or in a TOML file with the `seed-toml` feature. It
+//! can name a namespace and a database, which are defined if they don't exist yet, and which
+//! it is written to. Its schema is a SurrealQL script, which is run before any records are
+//! written, and its records are grouped by table. A table can depend on other tables, whose
+//! records are written before its own.
+//!
+//! ```toml
+//! # seed.toml
+//! namespace = "app"
+//! database = "app"
+//! schema = """
+//! DEFINE TABLE country SCHEMAFULL;
+//! DEFINE FIELD name ON country TYPE string;
+//! """
+//!
+//! [tables.country]
+//! records = [{ id = "uk", name = "United Kingdom" }]
+//!
+//! [tables.person]
+//! depends_on = ["country"]
+//! records = [{ id = "tobie", country = { "$thing" = "country:uk" } }]
+//! ```
+//!
+//! Every record needs an `id`, as records are upserted by their ID, so seeding a database
+//! more than once leaves it in the same state. Values which JSON and TOML have no place for,
+//! such as record IDs, are written as tagged objects, as described in
+//! [`json_to_value`](crate::sql::json_to_value).
+//!
+//! ```no_run
+//! use surrealdb::seed::Seed;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> surrealdb::Result<()> {
+//! # let db = surrealdb::engine::any::connect("mem://").await?;
+//! #
+//! db.seed(Seed::from_json("seed.json")?).await?;
+//! #
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::err::Error;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::api::Surreal;
+use crate::sql;
+use crate::sql::statements::DefineDatabaseStatement;
+use crate::sql::statements::DefineNamespaceStatement;
+use crate::sql::statements::DefineStatement;
+use crate::sql::statements::UseStatement;
+use crate::sql::Id;
+use crate::sql::Ident;
+use crate::sql::Statement;
+use crate::sql::Thing;
+use crate::sql::Value;
+use serde::Deserialize;
+use serde_json::Value as Json;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::path::Path;
+use std::pin::Pin;
+
+/// The seed data of a database
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Seed {
+	#[serde(default)]
+	namespace: Option<String>,
+	#[serde(default)]
+	database: Option<String>,
+	#[serde(default)]
+	schema: String,
+	#[serde(default)]
+	tables: BTreeMap<String, SeedTable>,
+}
+
+/// The records of a table in the seed data
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SeedTable {
+	#[serde(default)]
+	depends_on: Vec<String>,
+	#[serde(default)]
+	records: Vec<Json>,
+}
+
+impl Seed {
+	/// Reads seed data from a JSON file
+	pub fn from_json(path: impl AsRef<Path>) -> Result<Self> {
+		Self::parse_json(&read(path.as_ref())?)
+	}
+
+	/// Reads seed data from a TOML file
+	///
+	/// TOML datetimes are read as datetimes, without needing to be tagged.
+	#[cfg(feature = "seed-toml")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "seed-toml")))]
+	pub fn from_toml(path: impl AsRef<Path>) -> Result<Self> {
+		Self::parse_toml(&read(path.as_ref())?)
+	}
+
+	/// Parses seed data from JSON
+	pub fn parse_json(json: &str) -> Result<Self> {
+		Ok(serde_json::from_str(json).map_err(|error| Error::InvalidSeed(error.to_string()))?)
+	}
+
+	/// Parses seed data from TOML
+	#[cfg(feature = "seed-toml")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "seed-toml")))]
+	pub fn parse_toml(toml: &str) -> Result<Self> {
+		let toml: toml::Value =
+			toml::from_str(toml).map_err(|error| Error::InvalidSeed(error.to_string()))?;
+		Ok(serde_json::from_value(toml_to_json(toml))
+			.map_err(|error| Error::InvalidSeed(error.to_string()))?)
+	}
+
+	/// Orders the tables so that every table comes after the tables which it depends on
+	///
+	/// Tables which don't depend on each other are kept in order of their names.
+	fn sorted(&self) -> Result<Vec<(&str, &SeedTable)>> {
+		let names: Vec<&str> = self.tables.keys().map(String::as_str).collect();
+		let count = names.len();
+		let mut dependents = vec![Vec::new(); count];
+		let mut blocking = vec![0usize; count];
+		for (i, table) in self.tables.values().enumerate() {
+			for dependency in &table.depends_on {
+				let Ok(j) = names.binary_search(&dependency.as_str()) else {
+					return Err(Error::SeedDependencyNotFound {
+						table: names[i].to_owned(),
+						dependency: dependency.clone(),
+					}
+					.into());
+				};
+				dependents[j].push(i);
+				blocking[i] += 1;
+			}
+		}
+		let mut ready: BTreeSet<usize> = (0..count).filter(|i| blocking[*i] == 0).collect();
+		let mut order = Vec::with_capacity(count);
+		while let Some(i) = ready.pop_first() {
+			order.push((names[i], &self.tables[names[i]]));
+			for &k in dependents[i].iter() {
+				blocking[k] -= 1;
+				if blocking[k] == 0 {
+					ready.insert(k);
+				}
+			}
+		}
+		if order.len() < count {
+			return Err(Error::CyclicSeedDependencies.into());
+		}
+		Ok(order)
+	}
+}
+
+fn read(path: &Path) -> Result<String> {
+	std::fs::read_to_string(path).map_err(|error| {
+		Error::FileRead {
+			path: path.to_owned(),
+			error,
+		}
+		.into()
+	})
+}
+
+#[cfg(feature = "seed-toml")]
+fn toml_to_json(value: toml::Value) -> Json {
+	match value {
+		toml::Value::String(v) => Json::String(v),
+		toml::Value::Integer(v) => v.into(),
+		toml::Value::Float(v) => v.into(),
+		toml::Value::Boolean(v) => v.into(),
+		toml::Value::Datetime(v) => serde_json::json!({ "$datetime": v.to_string() }),
+		toml::Value::Array(v) => Json::Array(v.into_iter().map(toml_to_json).collect()),
+		toml::Value::Table(v) => {
+			Json::Object(v.into_iter().map(|(k, v)| (k, toml_to_json(v))).collect())
+		}
+	}
+}
+
+/// The statement which selects the namespace and the database of the seed data, if it names either
+fn using(using: &UseStatement) -> Vec<Statement> {
+	match using.ns.is_some() || using.db.is_some() {
+		true => vec![Statement::Use(using.clone())],
+		false => Vec::new(),
+	}
+}
+
+/// Converts a record of the seed data, with the ID of the record in its table
+fn record(tb: &str, json: &Json) -> Result<Value> {
+	let Value::Object(mut record) = sql::json_to_value(json.clone()) else {
+		return Err(
+			Error::InvalidSeed(format!("A record of the `{tb}` table is not an object")).into()
+		);
+	};
+	let id = match record.remove("id") {
+		Some(Value::Thing(v)) if v.tb == tb => v.id,
+		Some(Value::Thing(v)) => {
+			return Err(
+				Error::InvalidSeed(format!("The record `{v}` is not in the `{tb}` table")).into()
+			)
+		}
+		Some(Value::Strand(v)) => Id::from(v),
+		Some(Value::Number(v)) => Id::from(v),
+		Some(Value::Uuid(v)) => Id::from(v),
+		Some(Value::Array(v)) => Id::from(v),
+		Some(Value::Object(v)) => Id::from(v),
+		_ => return Err(Error::MissingRecordId(tb.to_owned()).into()),
+	};
+	record.insert(
+		"id".to_owned(),
+		Value::Thing(Thing {
+			tb: tb.to_owned(),
+			id,
+		}),
+	);
+	Ok(Value::Object(record))
+}
+
+/// A seed future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Seeding<'r, C: Connection> {
+	pub(crate) client: &'r Surreal<C>,
+	pub(crate) seed: Seed,
+}
+
+impl<'r, Client> IntoFuture for Seeding<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<()>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let tables = self.seed.sorted()?;
+			// The namespace and the database are selected within each query, so the client
+			// keeps the namespace and the database which it uses
+			let mut using = UseStatement::default();
+			if let Some(ns) = &self.seed.namespace {
+				if !self.defines(&using, "INFO FOR ROOT", "namespaces", ns).await? {
+					let define = DefineStatement::Namespace(DefineNamespaceStatement {
+						name: Ident(ns.clone()),
+						..Default::default()
+					});
+					self.client.query(Statement::Define(define)).await?.check()?;
+				}
+				using.ns = Some(ns.clone());
+			}
+			if let Some(db) = &self.seed.database {
+				if !self.defines(&using, "INFO FOR NAMESPACE", "databases", db).await? {
+					let define = DefineStatement::Database(DefineDatabaseStatement {
+						name: Ident(db.clone()),
+						..Default::default()
+					});
+					let mut statements = self::using(&using);
+					statements.push(Statement::Define(define));
+					self.client.query(statements).await?.check()?;
+				}
+				using.db = Some(db.clone());
+			}
+			// The schema and the records are written in a single transaction
+			let mut statements = self::using(&using);
+			statements.push(Statement::Begin(Default::default()));
+			if !self.seed.schema.trim().is_empty() {
+				statements.extend(sql::parse(&self.seed.schema)?.0 .0);
+			}
+			let mut bindings = BTreeMap::new();
+			for (i, (tb, table)) in tables.into_iter().enumerate() {
+				let records = table
+					.records
+					.iter()
+					.map(|record| self::record(tb, record))
+					.collect::<Result<Vec<_>>>()?;
+				let sql =
+					format!("FOR $record IN $records_{i} {{ UPDATE $record.id CONTENT $record; }}");
+				statements.extend(sql::parse(&sql)?.0 .0);
+				bindings.insert(format!("records_{i}"), Value::from(records));
+			}
+			statements.push(Statement::Commit(Default::default()));
+			self.client.query(statements).bind(bindings).await?.check()?;
+			Ok(())
+		})
+	}
+}
+
+impl<Client> Seeding<'_, Client>
+where
+	Client: Connection,
+{
+	/// Checks whether the result of an `INFO` statement lists a definition
+	async fn defines(
+		&self,
+		using: &UseStatement,
+		info: &str,
+		kind: &str,
+		name: &str,
+	) -> Result<bool> {
+		let mut statements = self::using(using);
+		let index = statements.len();
+		statements.extend(sql::parse(info)?.0 .0);
+		let info: Value = self.client.query(statements).await?.take(index)?;
+		Ok(match info {
+			Value::Object(mut info) => match info.remove(kind) {
+				Some(Value::Object(v)) => v.contains_key(name),
+				_ => false,
+			},
+			_ => false,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn orders_tables_by_dependency() {
+		let seed = Seed::parse_json(
+			r#"{
+				"tables": {
+					"a": { "depends_on": ["c"] },
+					"b": {},
+					"c": { "depends_on": ["b"] }
+				}
+			}"#,
+		)
+		.unwrap();
+		let order: Vec<_> = seed.sorted().unwrap().into_iter().map(|(tb, _)| tb).collect();
+		assert_eq!(order, vec!["b", "c", "a"]);
+	}
+
+	#[test]
+	fn rejects_unknown_and_cyclic_dependencies() {
+		let seed = Seed::parse_json(r#"{ "tables": { "a": { "depends_on": ["b"] } } }"#).unwrap();
+		assert!(matches!(
+			seed.sorted(),
+			Err(crate::Error::Api(Error::SeedDependencyNotFound { .. }))
+		));
+		let seed = Seed::parse_json(
+			r#"{ "tables": { "a": { "depends_on": ["b"] }, "b": { "depends_on": ["a"] } } }"#,
+		)
+		.unwrap();
+		assert!(matches!(seed.sorted(), Err(crate::Error::Api(Error::CyclicSeedDependencies))));
+	}
+
+	#[test]
+	#[cfg(feature = "seed-toml")]
+	fn reads_records_from_toml() {
+		let seed = Seed::parse_toml(
+			r#"
+			[tables.person]
+			records = [{ id = "tobie", country = { "$thing" = "country:uk" }, born = 1990-01-01T00:00:00Z }]
+			"#,
+		)
+		.unwrap();
+		let record = record("person", &seed.tables["person"].records[0]).unwrap();
+		assert_eq!(
+			record,
+			sql::value("{ id: person:tobie, country: country:uk, born: '1990-01-01T00:00:00Z' }")
+				.unwrap()
+		);
+	}
+
+	#[test]
+	fn records_need_an_id() {
+		let json = serde_json::json!({ "name": "Tobie" });
+		assert!(matches!(
+			record("person", &json),
+			Err(crate::Error::Api(Error::MissingRecordId(..)))
+		));
+	}
+}
@@ -0,0 +1,499 @@
+//! Typed bulk writes, submitted together in a single transaction
+//!
+//! A [`UnitOfWork`] collects creates, updates, merges, deletes and relations, which can refer
+//! to records which are only created as part of the same unit of work. Creating or relating a
+//! record returns a [`Ref`], which can be used as the target of other writes, or within their
+//! contents, in place of the record ID which the database assigns to the record.
+//!
+//! When the unit of work is submitted, the writes are ordered so that every record is created
+//! before it is referenced, and are run in a single transaction. Once the transaction has been
+//! committed, the references resolve to the IDs of the records which were created.
+//!
+//! ```no_run
+//! use serde::Serialize;
+//! use surrealdb::unit_of_work::Ref;
+//! use surrealdb::unit_of_work::UnitOfWork;
+//!
+//! #[derive(Serialize)]
+//! struct Person {
+//!     name: &'static str,
+//! }
+//!
+//! #[derive(Serialize)]
+//! struct Post {
+//!     title: &'static str,
+//!     author: Ref,
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> surrealdb::Result<()> {
+//! # let db = surrealdb::engine::any::connect("mem://").await?;
+//! # db.use_ns("namespace").use_db("database").await?;
+//! #
+//! let mut work = UnitOfWork::new();
+//! let author = work.create("person", Person { name: "Tobie" });
+//! let post = work.create("post", Post { title: "Hello", author });
+//! work.relate(author, "wrote", post, ());
+//!
+//! let committed = db.submit(work).await?;
+//! println!("{} wrote {}", committed[author], committed[post]);
+//! #
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::err::Error;
+use crate::api::Connection;
+use crate::api::Result;
+use crate::api::Surreal;
+use crate::sql::statements::CreateStatement;
+use crate::sql::statements::DeleteStatement;
+use crate::sql::statements::OutputStatement;
+use crate::sql::statements::RelateStatement;
+use crate::sql::statements::SetStatement;
+use crate::sql::statements::UpdateStatement;
+use crate::sql::to_value;
+use crate::sql::Data;
+use crate::sql::Field;
+use crate::sql::Fields;
+use crate::sql::Id;
+use crate::sql::Idiom;
+use crate::sql::Output;
+use crate::sql::Param;
+use crate::sql::Statement;
+use crate::sql::Subquery;
+use crate::sql::Table;
+use crate::sql::Thing;
+use crate::sql::Value;
+use crate::sql::Values;
+use serde::Serialize;
+use serde::Serializer;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::future::IntoFuture;
+use std::ops::Index;
+use std::pin::Pin;
+
+/// The prefix of the parameters which hold the IDs of the created records
+const PREFIX: &str = "__uow_";
+
+/// A reference to a record which is created by a unit of work
+///
+/// References serialize to a parameter which holds the ID of the record, so they can be
+/// used in the contents of other writes of the same unit of work.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Ref(usize);
+
+impl Ref {
+	fn param(&self) -> Param {
+		Param::from(format!("{PREFIX}{}", self.0))
+	}
+
+	/// Finds the reference which a parameter holds, if any
+	fn parse(param: &Param) -> Option<Self> {
+		param.0 .0.strip_prefix(PREFIX)?.parse().ok().map(Self)
+	}
+}
+
+impl fmt::Display for Ref {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.param(), f)
+	}
+}
+
+impl From<Ref> for Value {
+	fn from(r: Ref) -> Self {
+		Value::Param(r.param())
+	}
+}
+
+impl Serialize for Ref {
+	fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		self.param().serialize(serializer)
+	}
+}
+
+/// The record which a write applies to
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Target {
+	/// An existing record
+	Record(Thing),
+	/// A record which is created by the same unit of work
+	Ref(Ref),
+}
+
+impl Target {
+	fn value(&self) -> Value {
+		match self {
+			Self::Record(thing) => Value::Thing(thing.clone()),
+			Self::Ref(r) => Value::Param(r.param()),
+		}
+	}
+}
+
+impl From<Thing> for Target {
+	fn from(thing: Thing) -> Self {
+		Self::Record(thing)
+	}
+}
+
+impl From<&Thing> for Target {
+	fn from(thing: &Thing) -> Self {
+		Self::Record(thing.clone())
+	}
+}
+
+impl From<Ref> for Target {
+	fn from(r: Ref) -> Self {
+		Self::Ref(r)
+	}
+}
+
+impl<T, I> From<(T, I)> for Target
+where
+	T: Into<String>,
+	I: Into<Id>,
+{
+	fn from((table, id): (T, I)) -> Self {
+		Self::Record(Thing::from((table.into(), id.into())))
+	}
+}
+
+#[derive(Debug)]
+enum Kind {
+	Create(Value),
+	Relate(Target, String, Target),
+	Update(Target),
+	Merge(Target),
+	Delete(Target),
+}
+
+#[derive(Debug)]
+struct Op {
+	kind: Kind,
+	/// The reference which this write defines, if it creates a record
+	defines: Option<Ref>,
+	content: Result<Value>,
+}
+
+impl Op {
+	/// The references which this write depends on
+	fn deps(&self) -> Vec<Ref> {
+		let mut deps = Vec::new();
+		let targets = match &self.kind {
+			Kind::Create(_) => vec![],
+			Kind::Relate(from, _, with) => vec![from, with],
+			Kind::Update(what) | Kind::Merge(what) | Kind::Delete(what) => vec![what],
+		};
+		for target in targets {
+			if let Target::Ref(r) = target {
+				deps.push(*r);
+			}
+		}
+		if let Ok(content) = &self.content {
+			refs(content, &mut deps);
+		}
+		deps
+	}
+
+	/// The record which this write applies to
+	fn target(&self) -> Target {
+		match (&self.kind, self.defines) {
+			(Kind::Update(what) | Kind::Merge(what) | Kind::Delete(what), _) => what.clone(),
+			(_, Some(r)) => Target::Ref(r),
+			(_, None) => unreachable!("records which are created always define a reference"),
+		}
+	}
+
+	fn statement(self) -> Result<Statement> {
+		let data = match self.content? {
+			Value::None | Value::Null => None,
+			content => Some(content),
+		};
+		let id = Output::Fields(Fields(
+			vec![Field::Single {
+				expr: Value::Idiom(Idiom::from("id".to_owned())),
+				alias: None,
+			}],
+			true,
+		));
+		let subquery = match self.kind {
+			Kind::Create(what) => Subquery::Create(CreateStatement {
+				only: true,
+				what: Values(vec![what]),
+				data: data.map(Data::ContentExpression),
+				output: Some(id),
+				..Default::default()
+			}),
+			Kind::Relate(from, edge, with) => Subquery::Relate(RelateStatement {
+				only: true,
+				kind: Value::Table(Table(edge)),
+				from: from.value(),
+				with: with.value(),
+				data: data.map(Data::ContentExpression),
+				output: Some(id),
+				..Default::default()
+			}),
+			Kind::Update(what) => {
+				return Ok(Statement::Update(UpdateStatement {
+					what: Values(vec![what.value()]),
+					data: data.map(Data::ContentExpression),
+					output: Some(Output::None),
+					..Default::default()
+				}))
+			}
+			Kind::Merge(what) => {
+				return Ok(Statement::Update(UpdateStatement {
+					what: Values(vec![what.value()]),
+					data: data.map(Data::MergeExpression),
+					output: Some(Output::None),
+					..Default::default()
+				}))
+			}
+			Kind::Delete(what) => {
+				return Ok(Statement::Delete(DeleteStatement {
+					what: Values(vec![what.value()]),
+					output: Some(Output::None),
+					..Default::default()
+				}))
+			}
+		};
+		// Records which are created are stored in the parameter of their reference
+		let name = self.defines.map(|r| r.param().0 .0).unwrap_or_default();
+		Ok(Statement::Set(SetStatement {
+			name,
+			what: Value::Subquery(Box::new(subquery)),
+		}))
+	}
+}
+
+/// Collects the references within the contents of a write
+fn refs(value: &Value, deps: &mut Vec<Ref>) {
+	match value {
+		Value::Param(param) => deps.extend(Ref::parse(param)),
+		Value::Array(array) => array.iter().for_each(|v| refs(v, deps)),
+		Value::Object(object) => object.values().for_each(|v| refs(v, deps)),
+		_ => {}
+	}
+}
+
+/// A set of writes which are submitted together in a single transaction
+#[derive(Debug, Default)]
+pub struct UnitOfWork {
+	ops: Vec<Op>,
+	refs: usize,
+}
+
+impl UnitOfWork {
+	/// Creates an empty unit of work
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Reserves a reference to a record, which is created later with [`UnitOfWork::create_reserved`]
+	///
+	/// This makes it possible to refer to a record in writes which are registered before it.
+	pub fn reserve(&mut self) -> Ref {
+		let r = Ref(self.refs);
+		self.refs += 1;
+		r
+	}
+
+	/// Creates a record in a table, with a record ID which is assigned by the database
+	pub fn create(&mut self, table: impl Into<String>, data: impl Serialize) -> Ref {
+		let r = self.reserve();
+		self.create_reserved(r, table, data);
+		r
+	}
+
+	/// Creates the record of a reserved reference
+	pub fn create_reserved(
+		&mut self,
+		r: Ref,
+		table: impl Into<String>,
+		data: impl Serialize,
+	) -> &mut Self {
+		self.push(Kind::Create(Value::Table(Table(table.into()))), Some(r), data)
+	}
+
+	/// Creates a record with the given record ID
+	pub fn create_with_id(&mut self, id: Thing, data: impl Serialize) -> Ref {
+		let r = self.reserve();
+		self.push(Kind::Create(Value::Thing(id)), Some(r), data);
+		r
+	}
+
+	/// Relates two records with an edge, which is created in the given table
+	pub fn relate(
+		&mut self,
+		from: impl Into<Target>,
+		edge: impl Into<String>,
+		with: impl Into<Target>,
+		data: impl Serialize,
+	) -> Ref {
+		let r = self.reserve();
+		self.push(Kind::Relate(from.into(), edge.into(), with.into()), Some(r), data);
+		r
+	}
+
+	/// Replaces the contents of a record
+	pub fn update(&mut self, what: impl Into<Target>, data: impl Serialize) -> &mut Self {
+		self.push(Kind::Update(what.into()), None, data)
+	}
+
+	/// Merges data into a record
+	pub fn merge(&mut self, what: impl Into<Target>, data: impl Serialize) -> &mut Self {
+		self.push(Kind::Merge(what.into()), None, data)
+	}
+
+	/// Deletes a record
+	pub fn delete(&mut self, what: impl Into<Target>) -> &mut Self {
+		self.push(Kind::Delete(what.into()), None, ())
+	}
+
+	/// The number of writes in this unit of work
+	pub fn len(&self) -> usize {
+		self.ops.len()
+	}
+
+	/// Whether this unit of work has no writes
+	pub fn is_empty(&self) -> bool {
+		self.ops.is_empty()
+	}
+
+	fn push(&mut self, kind: Kind, defines: Option<Ref>, data: impl Serialize) -> &mut Self {
+		self.ops.push(Op {
+			kind,
+			defines,
+			content: to_value(data).map_err(Into::into),
+		});
+		self
+	}
+
+	/// Orders the writes so that every record is created before it is referenced
+	///
+	/// Writes to the same record keep the order in which they were registered, and writes
+	/// which don't depend on each other run as early as they can, in order of registration.
+	fn sort(self) -> Result<Vec<Op>> {
+		let count = self.ops.len();
+		// The write which creates each referenced record
+		let mut creates = HashMap::new();
+		for (i, op) in self.ops.iter().enumerate() {
+			if let Some(r) = op.defines {
+				creates.insert(r, i);
+			}
+		}
+		// The writes which each write depends on
+		let mut dependents = vec![Vec::new(); count];
+		let mut blocking = vec![0usize; count];
+		let mut last = HashMap::new();
+		for (i, op) in self.ops.iter().enumerate() {
+			let mut deps = Vec::new();
+			for r in op.deps() {
+				match creates.get(&r) {
+					Some(&j) => deps.push(j),
+					None => return Err(Error::UndefinedRef(r.to_string()).into()),
+				}
+			}
+			if let Some(j) = last.insert(op.target(), i) {
+				deps.push(j);
+			}
+			for j in deps {
+				dependents[j].push(i);
+				blocking[i] += 1;
+			}
+		}
+		// Run each write once all the writes it depends on have run
+		let mut ready: BTreeSet<usize> = (0..count).filter(|i| blocking[*i] == 0).collect();
+		let mut order = Vec::with_capacity(count);
+		while let Some(i) = ready.pop_first() {
+			order.push(i);
+			for &k in dependents[i].iter() {
+				blocking[k] -= 1;
+				if blocking[k] == 0 {
+					ready.insert(k);
+				}
+			}
+		}
+		if order.len() < count {
+			return Err(Error::CyclicRefs.into());
+		}
+		let mut ops: Vec<_> = self.ops.into_iter().map(Some).collect();
+		Ok(order.into_iter().filter_map(|i| ops[i].take()).collect())
+	}
+}
+
+/// The record IDs of the records which a unit of work created
+#[derive(Debug, Clone)]
+pub struct Committed {
+	ids: Vec<Option<Thing>>,
+}
+
+impl Committed {
+	/// The record ID which a reference resolved to
+	///
+	/// Returns `None` for references which were reserved, but never created.
+	pub fn get(&self, r: Ref) -> Option<&Thing> {
+		self.ids.get(r.0).and_then(Option::as_ref)
+	}
+}
+
+impl Index<Ref> for Committed {
+	type Output = Thing;
+
+	fn index(&self, r: Ref) -> &Self::Output {
+		match self.get(r) {
+			Some(thing) => thing,
+			None => panic!("reference {r} was not created by this unit of work"),
+		}
+	}
+}
+
+/// A unit of work future
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Submit<'r, C: Connection> {
+	pub(crate) client: &'r Surreal<C>,
+	pub(crate) work: UnitOfWork,
+}
+
+impl<'r, Client> IntoFuture for Submit<'r, Client>
+where
+	Client: Connection,
+{
+	type Output = Result<Committed>;
+	type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+	fn into_future(self) -> Self::IntoFuture {
+		Box::pin(async move {
+			let refs = self.work.refs;
+			let ops = self.work.sort()?;
+			if ops.is_empty() {
+				return Ok(Committed {
+					ids: Vec::new(),
+				});
+			}
+			let mut statements = vec![Statement::Begin(Default::default())];
+			for op in ops {
+				statements.push(op.statement()?);
+			}
+			let ids = (0..refs).map(|i| Value::Param(Ref(i).param())).collect::<Vec<_>>();
+			statements.push(Statement::Output(OutputStatement {
+				what: Value::from(ids),
+				fetch: None,
+			}));
+			statements.push(Statement::Commit(Default::default()));
+			let mut response = self.client.query(statements).await?.check()?;
+			let last = response.num_statements() - 1;
+			let ids: Vec<Option<Thing>> = response.take(last)?;
+			Ok(Committed {
+				ids,
+			})
+		})
+	}
+}
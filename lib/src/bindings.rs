@@ -0,0 +1,125 @@
+//! Serialization entry points for language bindings.
+//!
+//! Bindings built with tools such as `napi-rs` or `pyo3` exchange values with their host
+//! language as bytes. The functions in this module write CBOR directly into a buffer which
+//! is owned by the binding, and read values directly from a borrowed byte slice, so a value
+//! is never serialized to an intermediate format, such as a JSON string, on its way across.
+//!
+//! Values are encoded with their own serde implementation, so record IDs, datetimes,
+//! durations, decimals and UUIDs keep their types when they are decoded again.
+//!
+//! ```
+//! use surrealdb::bindings;
+//! use surrealdb::sql::Value;
+//!
+//! # fn main() -> surrealdb::Result<()> {
+//! let mut buf = Vec::new();
+//! bindings::encode_value(Value::from("Tobie"), &mut buf)?;
+//! assert_eq!(bindings::decode_value(&buf)?, Value::from("Tobie"));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::err::Error;
+use crate::api::Result;
+use crate::sql::Object;
+use crate::sql::Value;
+use crate::Response;
+use std::collections::BTreeMap;
+
+/// Encodes a value as CBOR, appending it to the buffer
+///
+/// The buffer can be reused between calls, and handed to the host language without copying.
+pub fn encode_value(value: Value, buf: &mut Vec<u8>) -> Result<()> {
+	ciborium::into_writer(&value, buf).map_err(|error| {
+		Error::ToCbor {
+			error: error.to_string(),
+		}
+		.into()
+	})
+}
+
+/// Takes the result of a statement from a response, appending it to the buffer as CBOR
+pub fn encode_response(response: &mut Response, index: usize, buf: &mut Vec<u8>) -> Result<()> {
+	let value: Value = response.take(index)?;
+	encode_value(value, buf)
+}
+
+/// Decodes a value from CBOR
+pub fn decode_value(bytes: &[u8]) -> Result<Value> {
+	Ok(ciborium::from_reader(bytes).map_err(|error| Error::FromCbor {
+		error: error.to_string(),
+	})?)
+}
+
+/// Decodes the parameters of a query from a CBOR map
+///
+/// The parameters can be passed to [`Query::bind`](crate::method::Query::bind).
+pub fn decode_params(bytes: &[u8]) -> Result<BTreeMap<String, Value>> {
+	match decode_value(bytes)? {
+		Value::Object(Object(map)) => Ok(map),
+		value => Err(Error::InvalidBindings(value).into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::Array;
+	use crate::sql::Datetime;
+	use crate::sql::Duration;
+	use crate::sql::Number;
+	use crate::sql::Thing;
+	use crate::sql::Uuid;
+	use indexmap::IndexMap;
+	use rust_decimal::Decimal;
+
+	#[test]
+	fn value_round_trip() {
+		let value = Value::from(map! {
+			"name".to_owned() => Value::from("Tobie"),
+			"tags".to_owned() => Value::from(vec![Value::from(1), Value::from(true)]),
+		});
+		let mut buf = Vec::new();
+		encode_value(value.clone(), &mut buf).unwrap();
+		assert_eq!(decode_value(&buf).unwrap(), value);
+	}
+
+	#[test]
+	fn typed_value_round_trip() {
+		let value = Value::from(map! {
+			"at".to_owned() => Value::Datetime(Datetime::try_from("2023-01-01T10:00:00Z").unwrap()),
+			"by".to_owned() => Value::Thing(Thing::from(("person", "tobie"))),
+			"for".to_owned() => Value::Duration(Duration::try_from("1h30m").unwrap()),
+			"id".to_owned() => Value::Uuid(Uuid::try_from("e72bee20-f49b-11ec-b939-0242ac120002").unwrap()),
+			"price".to_owned() => Value::Number(Number::Decimal(Decimal::new(999, 2))),
+		});
+		let mut buf = Vec::new();
+		encode_value(value.clone(), &mut buf).unwrap();
+		assert_eq!(decode_value(&buf).unwrap(), value);
+	}
+
+	#[test]
+	fn params_must_be_a_map() {
+		let mut buf = Vec::new();
+		encode_value(Value::from(1), &mut buf).unwrap();
+		assert!(decode_params(&buf).is_err());
+		buf.clear();
+		encode_value(Value::from(map! { "id".to_owned() => Value::from(1) }), &mut buf).unwrap();
+		assert_eq!(decode_params(&buf).unwrap().get("id"), Some(&Value::from(1)));
+	}
+
+	#[test]
+	fn response_is_appended() {
+		let mut response = Response(IndexMap::from([
+			(0, Ok(vec![Value::from("one")])),
+			(1, Ok(vec![Value::from("two")])),
+		]));
+		let mut buf = Vec::new();
+		encode_response(&mut response, 1, &mut buf).unwrap();
+		let len = buf.len();
+		encode_response(&mut response, 0, &mut buf).unwrap();
+		assert_eq!(decode_value(&buf[..len]).unwrap(), Value::Array(Array(vec!["two".into()])));
+		assert_eq!(decode_value(&buf[len..]).unwrap(), Value::Array(Array(vec!["one".into()])));
+	}
+}
@@ -0,0 +1,369 @@
+//! A C API for embedding SurrealDB in other language runtimes.
+//!
+//! The functions in this module are exported with a stable C ABI, so a `cdylib` or
+//! `staticlib` crate which depends on `surrealdb` with the `capi` feature can be loaded
+//! from any language with a foreign function interface, without the server binary.
+//!
+//! Functions which connect to or query a database return immediately. The work runs on
+//! a runtime owned by this module, and the callback is then called from one of the
+//! runtime threads, with the `context` pointer which was passed in by the caller. The
+//! callback receives either a result or an error message, and the other is null.
+//!
+//! Query results are returned as JSON. Strings returned by this API, including error
+//! messages, are owned by the caller and must be released with [`surreal_string_free`].
+//!
+//! ```c
+//! typedef struct SurrealDb SurrealDb;
+//! typedef struct SurrealResponse SurrealResponse;
+//!
+//! void surreal_connect(const char *endpoint,
+//!     void (*callback)(void *context, SurrealDb *db, char *error), void *context);
+//! void surreal_use(const SurrealDb *db, const char *ns, const char *database,
+//!     void (*callback)(void *context, char *error), void *context);
+//! void surreal_query(const SurrealDb *db, const char *sql,
+//!     void (*callback)(void *context, SurrealResponse *response, char *error), void *context);
+//! size_t surreal_response_len(const SurrealResponse *response);
+//! char *surreal_take(SurrealResponse *response, size_t index, char **error);
+//! void surreal_response_free(SurrealResponse *response);
+//! void surreal_free(SurrealDb *db);
+//! void surreal_string_free(char *string);
+//! ```
+
+use crate::engine::any;
+use crate::engine::any::Any;
+use crate::sql::Value;
+use crate::Surreal;
+use once_cell::sync::Lazy;
+use std::ffi::c_char;
+use std::ffi::c_void;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::future::Future;
+use std::ptr;
+use tokio::runtime::Runtime;
+
+/// The runtime which runs the asynchronous work of the C API
+static RUNTIME: Lazy<Runtime> = Lazy::new(|| {
+	tokio::runtime::Builder::new_multi_thread()
+		.enable_all()
+		.build()
+		.expect("failed to start the runtime for the C API")
+});
+
+/// A database connection, which is opened with [`surreal_connect`]
+pub struct SurrealDb(Surreal<Any>);
+
+/// The results of a query, which are returned by [`surreal_query`]
+pub struct SurrealResponse(crate::Response);
+
+/// Called with the database connection, or with an error message
+pub type SurrealConnectCallback =
+	extern "C" fn(context: *mut c_void, db: *mut SurrealDb, error: *mut c_char);
+
+/// Called with an error message, which is null if the call succeeded
+pub type SurrealUseCallback = extern "C" fn(context: *mut c_void, error: *mut c_char);
+
+/// Called with the query results, or with an error message
+pub type SurrealQueryCallback =
+	extern "C" fn(context: *mut c_void, response: *mut SurrealResponse, error: *mut c_char);
+
+/// The context pointer of the caller, which is passed back to a callback
+struct Context(*mut c_void);
+
+// The caller is responsible for the context being usable from the runtime threads
+unsafe impl Send for Context {}
+
+impl Context {
+	fn ptr(&self) -> *mut c_void {
+		self.0
+	}
+}
+
+/// Copies a C string which was passed in by the caller
+unsafe fn from_raw(ptr: *const c_char) -> Result<String, String> {
+	if ptr.is_null() {
+		return Err("Unexpected null pointer".to_owned());
+	}
+	CStr::from_ptr(ptr).to_str().map(str::to_owned).map_err(|e| e.to_string())
+}
+
+/// Converts a string into a C string which is owned by the caller
+fn into_raw(string: String) -> *mut c_char {
+	// A C string can not contain any nul bytes
+	let string = string.replace('\0', "");
+	CString::new(string).map(CString::into_raw).unwrap_or(ptr::null_mut())
+}
+
+/// Clones the connection behind a pointer which was passed in by the caller
+unsafe fn connection(db: *const SurrealDb) -> Result<Surreal<Any>, String> {
+	match db.as_ref() {
+		Some(db) => Ok(db.0.clone()),
+		None => Err("Unexpected null pointer".to_owned()),
+	}
+}
+
+/// Runs a future on the runtime, in the background
+fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+	RUNTIME.spawn(future);
+}
+
+/// Connects to a database at the endpoint, such as `mem://` or `ws://localhost:8000`
+///
+/// # Safety
+///
+/// `endpoint` must be a nul-terminated string. `context` must be safe to use from
+/// another thread.
+#[no_mangle]
+pub unsafe extern "C" fn surreal_connect(
+	endpoint: *const c_char,
+	callback: SurrealConnectCallback,
+	context: *mut c_void,
+) {
+	let endpoint = from_raw(endpoint);
+	let context = Context(context);
+	spawn(async move {
+		let res = match endpoint {
+			Ok(endpoint) => any::connect(endpoint).await.map_err(|e| e.to_string()),
+			Err(e) => Err(e),
+		};
+		match res {
+			Ok(db) => {
+				callback(context.ptr(), Box::into_raw(Box::new(SurrealDb(db))), ptr::null_mut())
+			}
+			Err(e) => callback(context.ptr(), ptr::null_mut(), into_raw(e)),
+		}
+	});
+}
+
+/// Switches the connection to a specific namespace and database
+///
+/// # Safety
+///
+/// `db` must be a connection returned by [`surreal_connect`], and `ns` and `database`
+/// must be nul-terminated strings. `context` must be safe to use from another thread.
+#[no_mangle]
+pub unsafe extern "C" fn surreal_use(
+	db: *const SurrealDb,
+	ns: *const c_char,
+	database: *const c_char,
+	callback: SurrealUseCallback,
+	context: *mut c_void,
+) {
+	let args = connection(db).and_then(|db| Ok((db, from_raw(ns)?, from_raw(database)?)));
+	let context = Context(context);
+	spawn(async move {
+		let res = match args {
+			Ok((db, ns, database)) => {
+				db.use_ns(ns).use_db(database).await.map_err(|e| e.to_string())
+			}
+			Err(e) => Err(e),
+		};
+		match res {
+			Ok(()) => callback(context.ptr(), ptr::null_mut()),
+			Err(e) => callback(context.ptr(), into_raw(e)),
+		}
+	});
+}
+
+/// Runs a set of SurrealQL statements against the database
+///
+/// # Safety
+///
+/// `db` must be a connection returned by [`surreal_connect`], and `sql` must be a
+/// nul-terminated string. `context` must be safe to use from another thread.
+#[no_mangle]
+pub unsafe extern "C" fn surreal_query(
+	db: *const SurrealDb,
+	sql: *const c_char,
+	callback: SurrealQueryCallback,
+	context: *mut c_void,
+) {
+	let args = connection(db).and_then(|db| Ok((db, from_raw(sql)?)));
+	let context = Context(context);
+	spawn(async move {
+		let res = match args {
+			Ok((db, sql)) => db.query(sql).await.map_err(|e| e.to_string()),
+			Err(e) => Err(e),
+		};
+		match res {
+			Ok(res) => callback(
+				context.ptr(),
+				Box::into_raw(Box::new(SurrealResponse(res))),
+				ptr::null_mut(),
+			),
+			Err(e) => callback(context.ptr(), ptr::null_mut(), into_raw(e)),
+		}
+	});
+}
+
+/// Returns the number of statement results in a query response
+///
+/// # Safety
+///
+/// `response` must be a response returned by [`surreal_query`].
+#[no_mangle]
+pub unsafe extern "C" fn surreal_response_len(response: *const SurrealResponse) -> usize {
+	match response.as_ref() {
+		Some(response) => response.0.num_statements(),
+		None => 0,
+	}
+}
+
+/// Takes the result of a statement from a query response, as JSON
+///
+/// Returns null and sets `error` if the statement failed. Each result can only be taken
+/// once, after which `null` is returned as its JSON.
+///
+/// # Safety
+///
+/// `response` must be a response returned by [`surreal_query`], and `error` must be
+/// null or point to a writable `char *`.
+#[no_mangle]
+pub unsafe extern "C" fn surreal_take(
+	response: *mut SurrealResponse,
+	index: usize,
+	error: *mut *mut c_char,
+) -> *mut c_char {
+	let res = match response.as_mut() {
+		Some(response) => response.0.take::<Value>(index).map_err(|e| e.to_string()),
+		None => Err("Unexpected null pointer".to_owned()),
+	};
+	match res {
+		Ok(value) => into_raw(value.into_json().to_string()),
+		Err(e) => {
+			if let Some(error) = error.as_mut() {
+				*error = into_raw(e);
+			}
+			ptr::null_mut()
+		}
+	}
+}
+
+/// Releases a query response
+///
+/// # Safety
+///
+/// `response` must be null or a response returned by [`surreal_query`], which has not
+/// already been released.
+#[no_mangle]
+pub unsafe extern "C" fn surreal_response_free(response: *mut SurrealResponse) {
+	if !response.is_null() {
+		drop(Box::from_raw(response));
+	}
+}
+
+/// Closes and releases a database connection
+///
+/// # Safety
+///
+/// `db` must be null or a connection returned by [`surreal_connect`], which has not
+/// already been released.
+#[no_mangle]
+pub unsafe extern "C" fn surreal_free(db: *mut SurrealDb) {
+	if !db.is_null() {
+		drop(Box::from_raw(db));
+	}
+}
+
+/// Releases a string which was returned by this API
+///
+/// # Safety
+///
+/// `string` must be null or a string returned by this API, which has not already been
+/// released.
+#[no_mangle]
+pub unsafe extern "C" fn surreal_string_free(string: *mut c_char) {
+	if !string.is_null() {
+		drop(CString::from_raw(string));
+	}
+}
+
+#[cfg(test)]
+#[cfg(feature = "kv-mem")]
+mod tests {
+
+	use super::*;
+	use std::sync::mpsc;
+
+	/// Sends the addresses of the pointers which a callback is called with
+	type Sender = mpsc::Sender<(usize, usize)>;
+
+	fn context(tx: &Sender) -> *mut c_void {
+		tx as *const Sender as *mut c_void
+	}
+
+	extern "C" fn on_connect(context: *mut c_void, db: *mut SurrealDb, error: *mut c_char) {
+		let tx = unsafe { &*(context as *const Sender) };
+		tx.send((db as usize, error as usize)).unwrap();
+	}
+
+	extern "C" fn on_use(context: *mut c_void, error: *mut c_char) {
+		let tx = unsafe { &*(context as *const Sender) };
+		tx.send((0, error as usize)).unwrap();
+	}
+
+	extern "C" fn on_query(
+		context: *mut c_void,
+		response: *mut SurrealResponse,
+		error: *mut c_char,
+	) {
+		let tx = unsafe { &*(context as *const Sender) };
+		tx.send((response as usize, error as usize)).unwrap();
+	}
+
+	#[test]
+	fn connect_query_take() {
+		unsafe {
+			// Connect to an in-memory database
+			let (tx, rx) = mpsc::channel();
+			let endpoint = CString::new("mem://").unwrap();
+			surreal_connect(endpoint.as_ptr(), on_connect, context(&tx));
+			let (db, error) = rx.recv().unwrap();
+			assert_eq!(error, 0);
+			let db = db as *mut SurrealDb;
+			// Select the namespace and database
+			let (tx, rx) = mpsc::channel();
+			let ns = CString::new("test").unwrap();
+			surreal_use(db, ns.as_ptr(), ns.as_ptr(), on_use, context(&tx));
+			assert_eq!(rx.recv().unwrap().1, 0);
+			// Run a query
+			let (tx, rx) = mpsc::channel();
+			let sql =
+				CString::new("CREATE person:tobie SET name = 'Tobie'; THROW 'oops';").unwrap();
+			surreal_query(db, sql.as_ptr(), on_query, context(&tx));
+			let (response, error) = rx.recv().unwrap();
+			assert_eq!(error, 0);
+			let response = response as *mut SurrealResponse;
+			assert_eq!(surreal_response_len(response), 2);
+			// Take the results
+			let mut error = ptr::null_mut();
+			let json = surreal_take(response, 0, &mut error);
+			assert!(error.is_null());
+			assert_eq!(
+				CStr::from_ptr(json).to_str().unwrap(),
+				r#"[{"id":"person:tobie","name":"Tobie"}]"#
+			);
+			surreal_string_free(json);
+			let json = surreal_take(response, 1, &mut error);
+			assert!(json.is_null());
+			assert!(CStr::from_ptr(error).to_str().unwrap().contains("oops"));
+			surreal_string_free(error);
+			// Release everything
+			surreal_response_free(response);
+			surreal_free(db);
+		}
+	}
+
+	#[test]
+	fn connect_error() {
+		let (tx, rx) = mpsc::channel();
+		let endpoint = CString::new("unknown://").unwrap();
+		unsafe {
+			surreal_connect(endpoint.as_ptr(), on_connect, context(&tx));
+			let (db, error) = rx.recv().unwrap();
+			assert_eq!(db, 0);
+			assert_ne!(error, 0);
+			surreal_string_free(error as *mut c_char);
+		}
+	}
+}
@@ -35,3 +35,42 @@ pub const SERVER_NAME: &str = "SurrealDB";
 
 /// Datastore processor batch size for scan operations
 pub const PROCESSOR_BATCH_SIZE: u32 = 50;
+
+/// The number of bytes which adaptive scan and write batches aim to hold
+pub const BATCH_BYTE_BUDGET: usize = 4 * 1024 * 1024;
+
+/// The largest number of entries in an adaptive scan or write batch, however small they are
+pub const MAX_BATCH_SIZE: u32 = 10_000;
+
+/// The number of bytes which are stored under each key of a binary blob
+pub const BLOB_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The number of records which the field backfills update in total on a datastore tick
+pub const BACKFILL_BATCH_SIZE: u32 = 1000;
+
+/// The number of records which each index rebuild indexes on a datastore tick
+pub const REBUILD_BATCH_SIZE: u32 = 1000;
+
+/// The number of records which each table retention policy deletes or archives on a datastore tick
+pub const RETENTION_BATCH_SIZE: u32 = 1000;
+
+/// The number of records which each table tiering policy checks on a datastore tick
+pub const TIERING_BATCH_SIZE: u32 = 1000;
+
+/// The number of change feed entries which each rollup table applies on a datastore tick
+pub const ROLLUP_BATCH_SIZE: u32 = 1000;
+
+/// The number of seconds for which a remote JSON Web Key Set is cached
+pub const JWKS_CACHE_EXPIRATION: i64 = 12 * 60 * 60;
+
+/// The minimum number of seconds between refetches of a JSON Web Key Set when a key is missing
+pub const JWKS_CACHE_COOLDOWN: i64 = 5 * 60;
+
+/// The minimum number of seconds between fetches of a JSON Web Key Set after a fetch failed
+pub const JWKS_FAILURE_COOLDOWN: i64 = 60;
+
+/// The number of seconds after which connecting to a remote JSON Web Key Set times out
+pub const JWKS_CONNECT_TIMEOUT: u64 = 5;
+
+/// The number of seconds after which fetching a remote JSON Web Key Set times out
+pub const JWKS_FETCH_TIMEOUT: u64 = 10;
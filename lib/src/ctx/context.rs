@@ -3,10 +3,15 @@ use crate::ctx::reason::Reason;
 use crate::dbs::capabilities::FuncTarget;
 #[cfg(feature = "http")]
 use crate::dbs::capabilities::NetTarget;
-use crate::dbs::{Capabilities, Notification};
+use crate::dbs::{
+	Capabilities, DatabaseUsage, Denials, HttpUsage, Notification, WriteGuard, WriteThrottle,
+};
 use crate::err::Error;
 use crate::idx::planner::QueryPlanner;
+use crate::sql::paths::{DB, NS};
+use crate::sql::permission::Permission;
 use crate::sql::value::Value;
+use crate::sql::Idiom;
 use channel::Sender;
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -45,6 +50,12 @@ pub struct Context<'a> {
 	query_planner: Option<&'a QueryPlanner<'a>>,
 	// Capabilities
 	capabilities: Arc<Capabilities>,
+	// The outbound HTTP usage of each database
+	http_usage: Arc<HttpUsage>,
+	// The write limits of the tables
+	write_throttle: Arc<WriteThrottle>,
+	// The permission clauses which filtered records, when they are being reported
+	denials: Option<Arc<Denials>>,
 }
 
 impl<'a> Default for Context<'a> {
@@ -75,6 +86,9 @@ impl<'a> Context<'a> {
 			notifications: None,
 			query_planner: None,
 			capabilities: Arc::new(Capabilities::default()),
+			http_usage: Arc::new(HttpUsage::default()),
+			write_throttle: Arc::new(WriteThrottle::default()),
+			denials: None,
 		}
 	}
 
@@ -88,6 +102,9 @@ impl<'a> Context<'a> {
 			notifications: parent.notifications.clone(),
 			query_planner: parent.query_planner,
 			capabilities: parent.capabilities.clone(),
+			http_usage: parent.http_usage.clone(),
+			write_throttle: parent.write_throttle.clone(),
+			denials: parent.denials.clone(),
 		}
 	}
 
@@ -254,4 +271,92 @@ impl<'a> Context<'a> {
 			_ => Err(Error::NetTargetNotAllowed(target.to_string())),
 		}
 	}
+
+	//
+	// HTTP usage
+	//
+
+	/// Set the tracker of the outbound HTTP usage for this context
+	pub(crate) fn add_http_usage(&mut self, usage: Arc<HttpUsage>) {
+		self.http_usage = usage;
+	}
+
+	/// Counts an outbound HTTP call from the database of this context
+	#[cfg(feature = "http")]
+	pub(crate) fn check_http_quota(&self) -> Result<(), Error> {
+		let (ns, db) = self.database();
+		self.http_usage.call(&ns, &db)
+	}
+
+	/// Counts the bytes which the database of this context sent or received over HTTP
+	#[cfg(feature = "http")]
+	pub(crate) fn add_http_bytes(&self, bytes: usize) {
+		let (ns, db) = self.database();
+		self.http_usage.bytes(&ns, &db, bytes)
+	}
+
+	/// Get the outbound HTTP usage of the database of this context
+	pub(crate) fn get_http_usage(&self) -> DatabaseUsage {
+		let (ns, db) = self.database();
+		self.http_usage.get(&ns, &db)
+	}
+
+	//
+	// Write limits
+	//
+
+	/// Set the write limits of the tables for this context
+	pub(crate) fn add_write_throttle(&mut self, throttle: Arc<WriteThrottle>) {
+		self.write_throttle = throttle;
+	}
+
+	/// Counts a record written to a table, if the write limit of the table allows it
+	pub(crate) fn check_write_limit(&self, ns: &str, db: &str, tb: &str) -> Result<(), Error> {
+		self.write_throttle.write(ns, db, tb)
+	}
+
+	/// Marks a statement which writes to a table, if the write limit of the table allows it
+	pub(crate) fn enter_write(
+		&self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+	) -> Result<Option<WriteGuard>, Error> {
+		self.write_throttle.enter(ns, db, tb)
+	}
+
+	//
+	// Permission diagnostics
+	//
+
+	/// Report the permission clauses which filter records in this context
+	pub(crate) fn add_denials(&mut self, denials: Arc<Denials>) {
+		self.denials = Some(denials);
+	}
+
+	/// Records that a permission clause filtered a record, or a field of a record
+	pub(crate) fn add_denial(
+		&self,
+		table: &str,
+		field: Option<&Idiom>,
+		action: &'static str,
+		permission: &Permission,
+	) {
+		if let Some(denials) = &self.denials {
+			denials.add(table, field, action, permission);
+		}
+	}
+
+	/// Outputs and clears the permission clauses which filtered records, if they are reported
+	pub(crate) fn take_denials(&self) -> Option<Value> {
+		self.denials.as_ref().and_then(|v| v.output())
+	}
+
+	/// Get the namespace and database of the session of this context
+	fn database(&self) -> (String, String) {
+		let session = self.value("session").unwrap_or(&Value::None);
+		let ns = session.pick(NS.as_ref()).as_raw_string();
+		let db = session.pick(DB.as_ref()).as_raw_string();
+		(ns, db)
+	}
 }
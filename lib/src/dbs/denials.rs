@@ -0,0 +1,91 @@
+use crate::sql::permission::Permission;
+use crate::sql::{Idiom, Value};
+use std::sync::Mutex;
+
+/// The records and fields which were filtered by a permission clause while running a statement
+#[derive(Debug, Default)]
+pub(crate) struct Denials(Mutex<Vec<Denial>>);
+
+#[derive(Debug)]
+struct Denial {
+	table: String,
+	field: Option<Idiom>,
+	action: &'static str,
+	permission: String,
+	records: u64,
+}
+
+impl Denials {
+	/// Records that a permission clause filtered a record, or a field of a record
+	pub(crate) fn add(
+		&self,
+		table: &str,
+		field: Option<&Idiom>,
+		action: &'static str,
+		permission: &Permission,
+	) {
+		let permission = permission.to_string();
+		let mut denials = self.0.lock().unwrap_or_else(|e| e.into_inner());
+		match denials.iter_mut().find(|v| {
+			v.table == table
+				&& v.field.as_ref() == field
+				&& v.action == action
+				&& v.permission == permission
+		}) {
+			Some(v) => v.records += 1,
+			None => denials.push(Denial {
+				table: table.to_owned(),
+				field: field.cloned(),
+				action,
+				permission,
+				records: 1,
+			}),
+		}
+	}
+
+	/// Outputs and clears the permission clauses which filtered any records, in the order they were hit
+	pub(crate) fn output(&self) -> Option<Value> {
+		let denials = std::mem::take(&mut *self.0.lock().unwrap_or_else(|e| e.into_inner()));
+		if denials.is_empty() {
+			return None;
+		}
+		let denials = denials.into_iter().map(|v| {
+			let mut out = map! {
+				"action".to_string() => Value::from(v.action),
+				"permission".to_string() => Value::from(v.permission),
+				"records".to_string() => Value::from(v.records),
+				"table".to_string() => Value::from(v.table),
+			};
+			if let Some(field) = v.field {
+				out.insert("field".to_string(), Value::from(field.to_string()));
+			}
+			Value::from(out)
+		});
+		Some(Value::from(denials.collect::<Vec<_>>()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::test::Parse;
+
+	#[test]
+	fn counts_denials_by_clause() {
+		let denials = Denials::default();
+		assert_eq!(denials.output(), None);
+		let perms = Permission::Specific(Value::parse("user = $auth.id"));
+		let field = Idiom::parse("email");
+		denials.add("post", None, "select", &perms);
+		denials.add("post", None, "select", &perms);
+		denials.add("user", Some(&field), "select", &Permission::None);
+		let val = Value::parse(
+			"[
+				{ action: 'select', permission: 'WHERE user = $auth.id', records: 2, table: 'post' },
+				{ action: 'select', field: 'email', permission: 'NONE', records: 1, table: 'user' },
+			]",
+		);
+		assert_eq!(denials.output(), Some(val));
+		assert_eq!(denials.output(), None);
+	}
+}
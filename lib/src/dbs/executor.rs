@@ -1,21 +1,29 @@
 use crate::ctx::Context;
+use crate::dbs::response::checksum;
 use crate::dbs::response::Response;
+use crate::dbs::response::ResultTruncated;
 use crate::dbs::Notification;
 use crate::dbs::Options;
 use crate::dbs::QueryType;
+use crate::dbs::TelemetryKind;
 use crate::dbs::Transaction;
 use crate::err::Error;
 use crate::iam::Action;
 use crate::iam::ResourceKind;
+use crate::kvs;
 use crate::kvs::Datastore;
+use crate::kvs::Temporary;
 use crate::sql::paths::DB;
 use crate::sql::paths::NS;
 use crate::sql::query::Query;
 use crate::sql::statement::Statement;
+use crate::sql::statements::SelectStatement;
 use crate::sql::value::Value;
 use crate::sql::Base;
+use crate::sql::Versionstamp;
 use channel::Receiver;
 use futures::lock::Mutex;
+use std::borrow::Cow;
 use std::sync::Arc;
 use tracing::instrument;
 use trice::Instant;
@@ -24,6 +32,11 @@ pub(crate) struct Executor<'a> {
 	err: bool,
 	kvs: &'a Datastore,
 	txn: Option<Transaction>,
+	at: Option<Versionstamp>,
+	tmp: Option<Temporary>,
+	checksum: bool,
+	dry_run: bool,
+	limit: Option<usize>,
 }
 
 impl<'a> Executor<'a> {
@@ -32,9 +45,32 @@ impl<'a> Executor<'a> {
 			kvs,
 			txn: None,
 			err: false,
+			at: None,
+			tmp: None,
+			checksum: false,
+			dry_run: false,
+			limit: None,
 		}
 	}
 
+	/// Run all statements in read-only transactions at a historical version
+	pub fn with_versionstamp(mut self, at: Option<Versionstamp>) -> Executor<'a> {
+		self.at = at;
+		self
+	}
+
+	/// Keep the data of temporary tables in the keyspace of the session
+	pub fn with_temporary(mut self, tmp: Temporary) -> Executor<'a> {
+		self.tmp = Some(tmp);
+		self
+	}
+
+	/// Cut the result of each statement down to a maximum number of values
+	pub fn with_result_limit(mut self, limit: Option<usize>) -> Executor<'a> {
+		self.limit = limit;
+		self
+	}
+
 	fn txn(&self) -> Transaction {
 		self.txn.clone().expect("unreachable: txn was None after successful begin")
 	}
@@ -47,7 +83,7 @@ impl<'a> Executor<'a> {
 	async fn begin(&mut self, write: bool) -> bool {
 		match self.txn.as_ref() {
 			Some(_) => false,
-			None => match self.kvs.transaction(write, false).await {
+			None => match self.transaction(write).await {
 				Ok(v) => {
 					self.txn = Some(Arc::new(Mutex::new(v)));
 					true
@@ -60,6 +96,52 @@ impl<'a> Executor<'a> {
 		}
 	}
 
+	async fn transaction(&self, write: bool) -> Result<kvs::Transaction, Error> {
+		match self.at {
+			Some(at) => self.kvs.transaction_at(at).await,
+			None => {
+				let txn = self.kvs.transaction(write, false).await?;
+				Ok(match &self.tmp {
+					Some(tmp) => txn.with_temporary(tmp.clone()),
+					None => txn,
+				})
+			}
+		}
+	}
+
+	/// Computes a SELECT statement with a VERSION clause
+	///
+	/// The statement is computed in its own read-only transaction, which reads the data
+	/// as it was at the specified time, so any transaction which is running is not used.
+	async fn compute_at_version(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		stm: &SelectStatement,
+	) -> Result<Value, Error> {
+		// Get the version of the data at the specified time
+		let at = match &stm.version {
+			Some(v) => self.kvs.versionstamp_at(&v.0).await?,
+			None => return Err(Error::Unreachable),
+		};
+		// Create a read-only transaction at the version
+		let txn: Transaction = Arc::new(Mutex::new(self.kvs.transaction_at(at).await?));
+		let mut ctx = Context::new(ctx);
+		// Set statement timeout
+		if let Some(timeout) = &stm.timeout {
+			ctx.add_timeout(*timeout.0);
+		}
+		// Process the statement
+		let res = stm.compute(&ctx, opt, &txn, None).await;
+		// Read-only transactions are never committed
+		let _ = txn.lock().await.cancel().await;
+		// Catch statement timeout
+		match ctx.is_timedout() {
+			true => Err(Error::QueryTimedout),
+			false => res,
+		}
+	}
+
 	/// Commits the transaction if it is local.
 	///
 	/// # Return
@@ -75,6 +157,12 @@ impl<'a> Executor<'a> {
 					// Cancel and ignore any error because the error flag was
 					// already set
 					let _ = txn.cancel().await;
+				} else if self.dry_run {
+					// Dry runs are never committed, but their results are kept
+					if let Err(e) = txn.cancel().await {
+						self.err = true;
+						return Err(e);
+					}
 				} else {
 					let r = match txn.complete_changes(false).await {
 						Ok(_) => txn.commit().await,
@@ -112,6 +200,9 @@ impl<'a> Executor<'a> {
 			time: v.time,
 			result: Err(Error::QueryCancelled),
 			query_type: QueryType::Other,
+			checksum: None,
+			denials: None,
+			truncated: None,
 		}
 	}
 
@@ -129,6 +220,9 @@ impl<'a> Executor<'a> {
 					Err(e) => Err(e),
 				},
 				query_type: QueryType::Other,
+				checksum: None,
+				denials: None,
+				truncated: None,
 			},
 			_ => v,
 		}
@@ -144,13 +238,26 @@ impl<'a> Executor<'a> {
 	/// Flush notifications from a buffer channel (live queries) to the committed notification channel.
 	/// This is because we don't want to broadcast notifications to the user for failed transactions.
 	async fn flush(&self, ctx: &Context<'_>, rcv: Receiver<Notification>) {
+		if self.dry_run {
+			// Nothing was committed by a dry run
+			return self.clear(ctx, rcv).await;
+		}
+		let telemetry = self.kvs.telemetry_bus();
 		if let Some(chn) = ctx.notifications() {
 			while let Ok(v) = rcv.try_recv() {
+				telemetry.emit(TelemetryKind::LiveQueryFired {
+					id: v.id.0,
+					action: v.action.clone(),
+				});
 				let _ = chn.send(v).await;
 			}
 		} else {
-			while rcv.try_recv().is_ok() {
-				// Ignore notification
+			while let Ok(v) = rcv.try_recv() {
+				// Only report the notification
+				telemetry.emit(TelemetryKind::LiveQueryFired {
+					id: v.id.0,
+					action: v.action,
+				});
 			}
 		}
 	}
@@ -200,14 +307,26 @@ impl<'a> Executor<'a> {
 			let is_stm_kill = matches!(stm, Statement::Kill(_));
 			// Check if this is a RETURN statement
 			let is_stm_output = matches!(stm, Statement::Output(_));
+			// Check if this is a SELECT statement without an ORDER BY clause
+			let is_stm_unordered = matches!(&stm, Statement::Select(s) if s.order.is_none());
 			// Process a single statement
 			let res = match stm {
 				// Specify runtime options
 				Statement::Option(mut stm) => {
-					// Allowed to run?
-					opt.is_allowed(Action::Edit, ResourceKind::Option, &Base::Db)?;
 					// Convert to uppercase
 					stm.name.0.make_ascii_uppercase();
+					// Dry runs only prevent writes, so they are allowed for everyone
+					if stm.name.0 == "DRY_RUN" {
+						self.dry_run = stm.what;
+						continue;
+					}
+					// Checked writes only prevent writes too
+					if stm.name.0 == "CAS" {
+						opt = opt.with_cas(stm.what);
+						continue;
+					}
+					// Allowed to run?
+					opt.is_allowed(Action::Edit, ResourceKind::Option, &Base::Db)?;
 					// Process the option
 					opt = match stm.name.0.as_str() {
 						"FIELDS" => opt.with_fields(stm.what),
@@ -215,6 +334,10 @@ impl<'a> Executor<'a> {
 						"TABLES" => opt.with_tables(stm.what),
 						"IMPORT" => opt.with_import(stm.what),
 						"FORCE" => opt.with_force(stm.what),
+						"CHECKSUM" => {
+							self.checksum = stm.what;
+							opt
+						}
 						_ => break,
 					};
 					// Continue
@@ -304,6 +427,17 @@ impl<'a> Executor<'a> {
 						}
 					}
 				}
+				// Process SELECT statements with a VERSION clause
+				Statement::Select(stm) if stm.version.is_some() => match self.err {
+					// This transaction has failed
+					true => Err(Error::QueryNotExecuted),
+					// Compute the statement at the specified version
+					false => {
+						// Stop once there are more results than the result size limit
+						let opt = opt.clone().with_limit(self.limit.map(|l| l + 1));
+						self.compute_at_version(&ctx, &opt, &stm).await
+					}
+				},
 				// Process all other normal statements
 				_ => match self.err {
 					// This transaction has failed
@@ -319,6 +453,13 @@ impl<'a> Executor<'a> {
 							// The transaction began successfully
 							false => {
 								let mut ctx = Context::new(&ctx);
+								// Stop SELECT statements once there are more results than the result size limit
+								let opt = match (&stm, self.limit) {
+									(Statement::Select(_), Some(l)) => {
+										Cow::Owned(opt.clone().with_limit(Some(l + 1)))
+									}
+									_ => Cow::Borrowed(&opt),
+								};
 								// Process the statement
 								let res = match stm.timeout() {
 									// There is a timeout clause
@@ -368,6 +509,19 @@ impl<'a> Executor<'a> {
 					}
 				},
 			};
+			// Cut the result down to the result size limit
+			let mut res = res;
+			let truncated = match (&mut res, self.limit) {
+				(Ok(v), Some(limit)) => ResultTruncated::apply(v, limit),
+				_ => None,
+			};
+			// Compute the checksum of the result
+			let checksum = match &res {
+				Ok(v) if self.checksum => Some(checksum(v, is_stm_unordered)),
+				_ => None,
+			};
+			// Output the permission clauses which filtered records
+			let denials = ctx.take_denials();
 			// Produce the response
 			let res = Response {
 				// Get the statement end time
@@ -383,6 +537,9 @@ impl<'a> Executor<'a> {
 					(_, true) => QueryType::Kill,
 					_ => QueryType::Other,
 				},
+				checksum,
+				denials,
+				truncated,
 			};
 			// Output the response
 			if self.txn.is_some() {
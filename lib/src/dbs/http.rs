@@ -0,0 +1,171 @@
+use crate::err::Error;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use trice::Instant;
+
+/// Limits on the outbound calls which each database can make with the `http::` functions
+///
+/// The calls and the bytes which are sent and received are counted separately for each
+/// database, over a window which starts with the first call. Once either limit is reached,
+/// further calls fail until the window has elapsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct HttpQuota {
+	/// The maximum number of calls in each window
+	pub calls: Option<u64>,
+	/// The maximum number of request and response body bytes in each window
+	pub bytes: Option<u64>,
+	/// How long the usage is counted for before it is reset
+	pub window: Duration,
+}
+
+impl HttpQuota {
+	/// Creates a quota which counts the usage over the given window, without any limits
+	pub fn new(window: Duration) -> Self {
+		Self {
+			calls: None,
+			bytes: None,
+			window,
+		}
+	}
+
+	/// Limits the number of calls in each window
+	pub fn with_calls(mut self, calls: u64) -> Self {
+		self.calls = Some(calls);
+		self
+	}
+
+	/// Limits the number of bytes which are sent and received in each window
+	pub fn with_bytes(mut self, bytes: u64) -> Self {
+		self.bytes = Some(bytes);
+		self
+	}
+}
+
+/// The outbound HTTP usage of a database, in the current window
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DatabaseUsage {
+	pub(crate) calls: u64,
+	pub(crate) bytes: u64,
+	since: Instant,
+}
+
+impl DatabaseUsage {
+	fn new() -> Self {
+		Self {
+			calls: 0,
+			bytes: 0,
+			since: Instant::now(),
+		}
+	}
+}
+
+/// Tracks the outbound HTTP usage of each database on a datastore
+///
+/// Without a quota, the usage is counted from when the datastore was started.
+#[derive(Debug, Default)]
+pub(crate) struct HttpUsage {
+	quota: Option<HttpQuota>,
+	databases: Mutex<HashMap<(String, String), DatabaseUsage>>,
+}
+
+impl HttpUsage {
+	pub(crate) fn new(quota: HttpQuota) -> Self {
+		Self {
+			quota: Some(quota),
+			databases: Mutex::default(),
+		}
+	}
+
+	/// Counts a call from a database, if its quota allows it
+	#[allow(dead_code)]
+	pub(crate) fn call(&self, ns: &str, db: &str) -> Result<(), Error> {
+		self.with(ns, db, |quota, usage| {
+			if let Some(quota) = quota {
+				let calls = quota.calls.is_some_and(|v| usage.calls >= v);
+				let bytes = quota.bytes.is_some_and(|v| usage.bytes >= v);
+				if calls || bytes {
+					return Err(Error::HttpQuotaExceeded {
+						ns: ns.to_owned(),
+						db: db.to_owned(),
+					});
+				}
+			}
+			usage.calls += 1;
+			Ok(())
+		})
+	}
+
+	/// Counts the body bytes which a database sent or received
+	#[allow(dead_code)]
+	pub(crate) fn bytes(&self, ns: &str, db: &str, bytes: usize) {
+		self.with(ns, db, |_, usage| usage.bytes += bytes as u64);
+	}
+
+	/// Gets the usage of a database in the current window
+	pub(crate) fn get(&self, ns: &str, db: &str) -> DatabaseUsage {
+		self.with(ns, db, |_, usage| *usage)
+	}
+
+	fn with<T>(
+		&self,
+		ns: &str,
+		db: &str,
+		f: impl FnOnce(Option<&HttpQuota>, &mut DatabaseUsage) -> T,
+	) -> T {
+		let mut databases = self.databases.lock().unwrap_or_else(|e| e.into_inner());
+		let usage =
+			databases.entry((ns.to_owned(), db.to_owned())).or_insert_with(DatabaseUsage::new);
+		// Start a new window once the current one has elapsed
+		if let Some(quota) = &self.quota {
+			if usage.since.elapsed() >= quota.window {
+				*usage = DatabaseUsage::new();
+			}
+		}
+		f(self.quota.as_ref(), usage)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn counts_usage_per_database() {
+		let usage = HttpUsage::default();
+		usage.call("test", "one").unwrap();
+		usage.call("test", "one").unwrap();
+		usage.bytes("test", "one", 10);
+		usage.call("test", "two").unwrap();
+		let one = usage.get("test", "one");
+		assert_eq!((one.calls, one.bytes), (2, 10));
+		let two = usage.get("test", "two");
+		assert_eq!((two.calls, two.bytes), (1, 0));
+	}
+
+	#[test]
+	fn limits_calls() {
+		let usage = HttpUsage::new(HttpQuota::new(Duration::from_secs(60)).with_calls(2));
+		usage.call("test", "test").unwrap();
+		usage.call("test", "test").unwrap();
+		assert!(matches!(usage.call("test", "test"), Err(Error::HttpQuotaExceeded { .. })));
+		// Other databases have their own quota
+		usage.call("test", "other").unwrap();
+	}
+
+	#[test]
+	fn limits_bytes() {
+		let usage = HttpUsage::new(HttpQuota::new(Duration::from_secs(60)).with_bytes(100));
+		usage.call("test", "test").unwrap();
+		usage.bytes("test", "test", 100);
+		assert!(matches!(usage.call("test", "test"), Err(Error::HttpQuotaExceeded { .. })));
+	}
+
+	#[test]
+	fn resets_after_window() {
+		let usage = HttpUsage::new(HttpQuota::new(Duration::ZERO).with_calls(1));
+		usage.call("test", "test").unwrap();
+		usage.call("test", "test").unwrap();
+	}
+}
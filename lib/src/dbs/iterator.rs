@@ -5,11 +5,13 @@ use crate::dbs::distinct::AsyncDistinct;
 use crate::dbs::distinct::SyncDistinct;
 use crate::dbs::explanation::Explanation;
 use crate::dbs::Statement;
+use crate::dbs::WriteGuard;
 use crate::dbs::{Options, Transaction};
 use crate::doc::Document;
 use crate::err::Error;
 use crate::idx::docids::DocId;
 use crate::idx::planner::executor::IteratorRef;
+use crate::kvs::record::Refs;
 use crate::sql::array::Array;
 use crate::sql::edges::Edges;
 use crate::sql::field::Field;
@@ -20,7 +22,7 @@ use crate::sql::value::Value;
 use async_recursion::async_recursion;
 use std::borrow::Cow;
 use std::cmp::Ordering;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::mem;
 
 #[derive(Debug)]
@@ -40,6 +42,8 @@ pub(crate) struct Processed {
 	pub(crate) rid: Option<Thing>,
 	pub(crate) doc_id: Option<DocId>,
 	pub(crate) val: Operable,
+	/// The shared values which the record referenced when it was read, if it was read
+	pub(crate) refs: Option<Refs>,
 }
 
 pub(crate) enum Operable {
@@ -77,6 +81,11 @@ impl Iterator {
 		Self::default()
 	}
 
+	/// Stops the iteration once it has produced this number of results
+	pub fn cap(&mut self, limit: Option<usize>) {
+		self.limit = limit;
+	}
+
 	/// Ingests an iterable for processing
 	pub fn ingest(&mut self, val: Iterable) {
 		self.entries.push(val)
@@ -294,6 +303,8 @@ impl Iterator {
 		let (do_iterate, mut explanation) = Explanation::new(ctx, stm.explain(), &self.entries);
 
 		if do_iterate {
+			// Check the write limits of the tables, until the statement is done
+			let _guards = self.enter_writes(ctx, opt, stm)?;
 			// Process prepared values
 			self.iterate(&cancel_ctx, opt, txn, stm).await?;
 			// Return any document errors
@@ -329,6 +340,41 @@ impl Iterator {
 		Ok(mem::take(&mut self.results).into())
 	}
 
+	/// Marks the statement as writing to each of the tables which it iterates over
+	fn enter_writes(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		stm: &Statement<'_>,
+	) -> Result<Vec<WriteGuard>, Error> {
+		if !stm.is_write() {
+			return Ok(vec![]);
+		}
+		let mut tables = BTreeSet::new();
+		for v in &self.entries {
+			match v {
+				Iterable::Table(v) | Iterable::Index(v, _) => {
+					tables.insert(v.0.as_str());
+				}
+				Iterable::Thing(v) | Iterable::Mergeable(v, _) | Iterable::Relatable(_, v, _) => {
+					tables.insert(v.tb.as_str());
+				}
+				Iterable::Range(v) => {
+					tables.insert(v.tb.as_str());
+				}
+				Iterable::Edges(v) => tables.extend(v.what.0.iter().map(|t| t.0.as_str())),
+				Iterable::Value(_) => {}
+			}
+		}
+		let mut guards = Vec::new();
+		for tb in tables {
+			if let Some(guard) = ctx.enter_write(opt.ns(), opt.db(), tb)? {
+				guards.push(guard);
+			}
+		}
+		Ok(guards)
+	}
+
 	#[inline]
 	async fn setup_limit(
 		&mut self,
@@ -338,7 +384,8 @@ impl Iterator {
 		stm: &Statement<'_>,
 	) -> Result<(), Error> {
 		if let Some(v) = stm.limit() {
-			self.limit = Some(v.process(ctx, opt, txn, None).await?);
+			let v = v.process(ctx, opt, txn, None).await?;
+			self.limit = Some(self.limit.map_or(v, |cap| cap.min(v)));
 		}
 		Ok(())
 	}
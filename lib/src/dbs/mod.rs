@@ -2,26 +2,39 @@
 //! In this module we essentially manage the entire lifecycle of a database request acting as the
 //! glue between the API and the response. In this module we use channels as a transport layer
 //! and executors to process the operations. This module also gives a `context` to the transaction.
+mod denials;
 mod distinct;
 mod executor;
 mod explanation;
+mod http;
 mod iterator;
 mod notification;
 mod options;
 mod response;
+pub(crate) mod rewrite;
 mod session;
 mod statement;
+mod telemetry;
+mod throttle;
 mod transaction;
 mod variables;
 
+pub use self::http::HttpQuota;
 pub use self::notification::*;
 pub use self::options::*;
 pub use self::response::*;
+pub use self::rewrite::{ForceLimit, RenameField, RewriteRule};
 pub use self::session::*;
+pub use self::telemetry::{TelemetryEvent, TelemetryKind};
+pub use self::throttle::WriteLimit;
 
+pub(crate) use self::denials::Denials;
 pub(crate) use self::executor::*;
+pub(crate) use self::http::{DatabaseUsage, HttpUsage};
 pub(crate) use self::iterator::*;
 pub(crate) use self::statement::*;
+pub(crate) use self::telemetry::Telemetry;
+pub(crate) use self::throttle::{WriteGuard, WriteThrottle};
 pub(crate) use self::transaction::*;
 pub(crate) use self::variables::*;
 
@@ -48,6 +48,10 @@ pub struct Options {
 	pub futures: bool,
 	/// Should we process variable field projections?
 	pub projections: bool,
+	/// The number of results after which a SELECT statement stops
+	pub limit: Option<usize>,
+	/// Should records only be written if they weren't changed since they were read?
+	pub cas: bool,
 	/// The channel over which we send notifications
 	pub sender: Option<Sender<Notification>>,
 	/// Datastore capabilities
@@ -78,6 +82,8 @@ impl Options {
 			indexes: true,
 			futures: false,
 			projections: false,
+			limit: None,
+			cas: false,
 			auth_enabled: true,
 			sender: None,
 			auth: Arc::new(Auth::default()),
@@ -208,6 +214,18 @@ impl Options {
 		self
 	}
 
+	/// Stop a SELECT statement once it has produced this number of results
+	pub fn with_limit(mut self, limit: Option<usize>) -> Self {
+		self.limit = limit;
+		self
+	}
+
+	/// Only write records which weren't changed since they were read
+	pub fn with_cas(mut self, cas: bool) -> Self {
+		self.cas = cas;
+		self
+	}
+
 	/// Create a new Options object for a subquery
 	pub fn with_import(mut self, import: bool) -> Self {
 		self.fields = !import;
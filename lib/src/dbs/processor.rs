@@ -7,6 +7,9 @@ use crate::dbs::{Iterable, Iterator, Operable, Options, Processed, Statement, Tr
 use crate::err::Error;
 use crate::idx::planner::executor::IteratorRef;
 use crate::key::{graph, thing};
+use crate::kvs;
+use crate::kvs::record;
+use crate::kvs::{Key, Scanner, Val};
 use crate::sql::dir::Dir;
 use crate::sql::{Edges, Range, Table, Thing, Value};
 #[cfg(not(target_arch = "wasm32"))]
@@ -122,6 +125,7 @@ impl<'a> Processor<'a> {
 			rid: None,
 			doc_id: None,
 			val: Operable::Value(v),
+			refs: None,
 		};
 		// Process the document record
 		self.process(ctx, opt, txn, stm, pro).await
@@ -138,19 +142,16 @@ impl<'a> Processor<'a> {
 		// Check that the table exists
 		txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
 		// Fetch the data from the store
-		let key = thing::new(opt.ns(), opt.db(), &v.tb, &v.id);
-		let val = txn.clone().lock().await.get(key).await?;
-		// Parse the data from the store
-		let val = Operable::Value(match val {
-			Some(v) => Value::from(v),
-			None => Value::None,
-		});
+		let (val, refs) =
+			record::get_with_refs(&mut *txn.lock().await, opt.ns(), opt.db(), &v.tb, &v.id).await?;
+		let val = Operable::Value(val);
 		// Process the document record
 		let pro = Processed {
 			ir: None,
 			rid: Some(v),
 			doc_id: None,
 			val,
+			refs: Some(refs),
 		};
 		self.process(ctx, opt, txn, stm, pro).await?;
 		// Everything ok
@@ -169,13 +170,8 @@ impl<'a> Processor<'a> {
 		// Check that the table exists
 		txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
 		// Fetch the data from the store
-		let key = thing::new(opt.ns(), opt.db(), &v.tb, &v.id);
-		let val = txn.clone().lock().await.get(key).await?;
-		// Parse the data from the store
-		let x = match val {
-			Some(v) => Value::from(v),
-			None => Value::None,
-		};
+		let (x, refs) =
+			record::get_with_refs(&mut *txn.lock().await, opt.ns(), opt.db(), &v.tb, &v.id).await?;
 		// Create a new operable value
 		let val = Operable::Mergeable(x, o);
 		// Process the document record
@@ -184,6 +180,7 @@ impl<'a> Processor<'a> {
 			rid: Some(v),
 			doc_id: None,
 			val,
+			refs: Some(refs),
 		};
 		self.process(ctx, opt, txn, stm, pro).await?;
 		// Everything ok
@@ -204,13 +201,8 @@ impl<'a> Processor<'a> {
 		// Check that the table exists
 		txn.lock().await.check_ns_db_tb(opt.ns(), opt.db(), &v.tb, opt.strict).await?;
 		// Fetch the data from the store
-		let key = thing::new(opt.ns(), opt.db(), &v.tb, &v.id);
-		let val = txn.clone().lock().await.get(key).await?;
-		// Parse the data from the store
-		let x = match val {
-			Some(v) => Value::from(v),
-			None => Value::None,
-		};
+		let (x, refs) =
+			record::get_with_refs(&mut *txn.lock().await, opt.ns(), opt.db(), &v.tb, &v.id).await?;
 		// Create a new operable value
 		let val = Operable::Relatable(f, x, w);
 		// Process the document record
@@ -219,6 +211,7 @@ impl<'a> Processor<'a> {
 			rid: Some(v),
 			doc_id: None,
 			val,
+			refs: Some(refs),
 		};
 		self.process(ctx, opt, txn, stm, pro).await?;
 		// Everything ok
@@ -238,63 +231,9 @@ impl<'a> Processor<'a> {
 		// Prepare the start and end keys
 		let beg = thing::prefix(opt.ns(), opt.db(), &v);
 		let end = thing::suffix(opt.ns(), opt.db(), &v);
-		// Prepare the next holder key
-		let mut nxt: Option<Vec<u8>> = None;
-		// Loop until no more keys
-		loop {
-			// Check if the context is finished
-			if ctx.is_done() {
-				break;
-			}
-			// Get the next batch of key-value entries
-			let res = match nxt {
-				None => {
-					let min = beg.clone();
-					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, PROCESSOR_BATCH_SIZE).await?
-				}
-				Some(ref mut beg) => {
-					beg.push(0x00);
-					let min = beg.clone();
-					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, PROCESSOR_BATCH_SIZE).await?
-				}
-			};
-			// If there are key-value entries then fetch them
-			if !res.is_empty() {
-				// Get total results
-				let n = res.len();
-				// Loop over results
-				for (i, (k, v)) in res.into_iter().enumerate() {
-					// Check the context
-					if ctx.is_done() {
-						break;
-					}
-					// Ready the next
-					if n == i + 1 {
-						nxt = Some(k.clone());
-					}
-					// Parse the data from the store
-					let key: thing::Thing = (&k).into();
-					let val: Value = (&v).into();
-					let rid = Thing::from((key.tb, key.id));
-					// Create a new operable value
-					let val = Operable::Value(val);
-					// Process the record
-					let pro = Processed {
-						ir: None,
-						rid: Some(rid),
-						doc_id: None,
-						val,
-					};
-					self.process(ctx, opt, txn, stm, pro).await?;
-				}
-				continue;
-			}
-			break;
-		}
-		// Everything ok
-		Ok(())
+		// Scan the key-value entries in batches
+		let scan = Scanner::new(txn, beg, end).await;
+		self.process_scan(ctx, opt, txn, stm, scan).await
 	}
 
 	async fn process_range(
@@ -327,60 +266,72 @@ impl<'a> Processor<'a> {
 				key
 			}
 		};
-		// Prepare the next holder key
-		let mut nxt: Option<Vec<u8>> = None;
+		// Scan the key-value entries in batches
+		let scan = Scanner::new(txn, beg, end).await;
+		self.process_scan(ctx, opt, txn, stm, scan).await
+	}
+
+	async fn process_scan(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		mut scan: Scanner,
+	) -> Result<(), Error> {
+		// Statements which write records have to see the writes of the records before them
+		let read_ahead = !stm.is_write();
 		// Loop until no more keys
-		loop {
+		while let Some(res) = scan.next_batch().await? {
 			// Check if the context is finished
 			if ctx.is_done() {
 				break;
 			}
-			// Get the next batch of key-value entries
-			let res = match nxt {
-				None => {
-					let min = beg.clone();
-					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, PROCESSOR_BATCH_SIZE).await?
-				}
-				Some(ref mut beg) => {
-					beg.push(0x00);
-					let min = beg.clone();
-					let max = end.clone();
-					txn.clone().lock().await.scan(min..max, PROCESSOR_BATCH_SIZE).await?
-				}
-			};
-			// If there are key-value entries then fetch them
-			if !res.is_empty() {
-				// Get total results
-				let n = res.len();
-				// Loop over results
-				for (i, (k, v)) in res.into_iter().enumerate() {
-					// Check the context
-					if ctx.is_done() {
-						break;
-					}
-					// Ready the next
-					if n == i + 1 {
-						nxt = Some(k.clone());
-					}
-					// Parse the data from the store
-					let key: thing::Thing = (&k).into();
-					let val: Value = (&v).into();
-					let rid = Thing::from((key.tb, key.id));
-					// Create a new operable value
-					let val = Operable::Value(val);
-					// Process the record
-					let pro = Processed {
-						ir: None,
-						rid: Some(rid),
-						doc_id: None,
-						val,
-					};
-					self.process(ctx, opt, txn, stm, pro).await?;
-				}
+			if !read_ahead {
+				self.process_batch(ctx, opt, txn, stm, res).await?;
 				continue;
 			}
-			break;
+			// Read the next batches while this one is processed
+			let (ahead, res) =
+				futures::join!(scan.read_ahead(), self.process_batch(ctx, opt, txn, stm, res));
+			res?;
+			ahead?;
+		}
+		// Everything ok
+		Ok(())
+	}
+
+	async fn process_batch(
+		&mut self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		stm: &Statement<'_>,
+		res: Vec<(Key, Val)>,
+	) -> Result<(), Error> {
+		// Loop over results
+		for (k, v) in res.into_iter() {
+			// Check the context
+			if ctx.is_done() {
+				break;
+			}
+			// Parse the data from the store
+			let key: thing::Thing = (&k).into();
+			let (val, refs) =
+				record::load_with_refs(&mut *txn.lock().await, opt.ns(), opt.db(), key.tb, &v)
+					.await?;
+			let rid = Thing::from((key.tb, key.id));
+			// Create a new operable value
+			let val = Operable::Value(val);
+			// Process the record
+			let pro = Processed {
+				ir: None,
+				rid: Some(rid),
+				doc_id: None,
+				val,
+				refs: Some(refs),
+			};
+			self.process(ctx, opt, txn, stm, pro).await?;
 		}
 		// Everything ok
 		Ok(())
@@ -507,20 +458,20 @@ impl<'a> Processor<'a> {
 						// Parse the data from the store
 						let gra: graph::Graph = (&k).into();
 						// Fetch the data from the store
-						let key = thing::new(opt.ns(), opt.db(), gra.ft, &gra.fk);
-						let val = txn.lock().await.get(key).await?;
+						let mut run = txn.lock().await;
+						let (val, refs) =
+							record::get_with_refs(&mut run, opt.ns(), opt.db(), gra.ft, &gra.fk)
+								.await?;
+						drop(run);
 						let rid = Thing::from((gra.ft, gra.fk));
-						// Parse the data from the store
-						let val = Operable::Value(match val {
-							Some(v) => Value::from(v),
-							None => Value::None,
-						});
+						let val = Operable::Value(val);
 						// Process the record
 						let pro = Processed {
 							ir: None,
 							rid: Some(rid),
 							doc_id: None,
 							val,
+							refs: Some(refs),
 						};
 						self.process(ctx, opt, txn, stm, pro).await?;
 					}
@@ -567,20 +518,25 @@ impl<'a> Processor<'a> {
 							}
 
 							// Fetch the data from the store
-							let key = thing::new(opt.ns(), opt.db(), &table.0, &thing.id);
-							let val = txn.lock().await.get(key.clone()).await?;
-							let rid = Thing::from((key.tb, key.id));
-							// Parse the data from the store
-							let val = Operable::Value(match val {
-								Some(v) => Value::from(v),
-								None => Value::None,
-							});
+							let mut run = txn.lock().await;
+							let (val, refs) = record::get_with_refs(
+								&mut run,
+								opt.ns(),
+								opt.db(),
+								&table.0,
+								&thing.id,
+							)
+							.await?;
+							drop(run);
+							let rid = Thing::from((table.0.as_str(), thing.id));
+							let val = Operable::Value(val);
 							// Process the document record
 							let pro = Processed {
 								ir: Some(ir),
 								rid: Some(rid),
 								doc_id: Some(doc_id),
 								val,
+								refs: Some(refs),
 							};
 							self.process(ctx, opt, txn, stm, pro).await?;
 						}
@@ -3,6 +3,8 @@ use crate::sql::value::Value;
 use serde::ser::SerializeStruct;
 use serde::Deserialize;
 use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
 use std::time::Duration;
 
 pub(crate) const TOKEN: &str = "$surrealdb::private::sql::Response";
@@ -24,6 +26,47 @@ pub struct Response {
 	pub result: Result<Value, Error>,
 	// Record the query type in case processing the response is necessary (such as tracking live queries).
 	pub query_type: QueryType,
+	// The checksum of the result, when enabled with `OPTION CHECKSUM`
+	pub checksum: Option<String>,
+	// The permission clauses which filtered records, when enabled with `Session::with_denials`
+	pub denials: Option<Value>,
+	// Whether the result was cut short by the result size limit of the datastore
+	pub truncated: Option<ResultTruncated>,
+}
+
+/// Notes that the result of a statement was cut short by the result size limit of the datastore
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ResultTruncated {
+	/// The number of values which were returned
+	pub returned: usize,
+	/// The maximum number of values which a statement can return
+	pub limit: usize,
+}
+
+impl ResultTruncated {
+	/// Cuts a result down to the limit, if it holds more values than that
+	pub(crate) fn apply(val: &mut Value, limit: usize) -> Option<Self> {
+		match val {
+			Value::Array(v) if v.len() > limit => {
+				v.truncate(limit);
+				Some(Self {
+					returned: v.len(),
+					limit,
+				})
+			}
+			_ => None,
+		}
+	}
+}
+
+impl From<ResultTruncated> for Value {
+	fn from(v: ResultTruncated) -> Self {
+		Value::from(map! {
+			"returned".to_string() => Value::from(v.returned),
+			"limit".to_string() => Value::from(v.limit),
+		})
+	}
 }
 
 impl Response {
@@ -37,6 +80,25 @@ impl Response {
 	}
 }
 
+/// Compute a deterministic checksum of a statement result
+///
+/// When `unordered` is true, and the result is an array, the checksum does not depend on the
+/// order of the elements, so that the results of SELECT statements without an ORDER BY clause
+/// can be compared across datastores which return records in a different order.
+pub(crate) fn checksum(val: &Value, unordered: bool) -> String {
+	match val {
+		Value::Array(v) if unordered => {
+			let mut hasher = Sha256::new();
+			let mut sums: Vec<_> = v.iter().map(|v| Sha256::digest(v.to_string())).collect();
+			sums.sort_unstable();
+			hasher.update(b"unordered");
+			sums.iter().for_each(|v| hasher.update(v));
+			format!("{:x}", hasher.finalize())
+		}
+		v => v.checksum(),
+	}
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub(crate) enum Status {
@@ -49,7 +111,11 @@ impl Serialize for Response {
 	where
 		S: serde::Serializer,
 	{
-		let mut val = serializer.serialize_struct(TOKEN, 3)?;
+		let len = 3
+			+ self.checksum.is_some() as usize
+			+ self.denials.is_some() as usize
+			+ self.truncated.is_some() as usize;
+		let mut val = serializer.serialize_struct(TOKEN, len)?;
 		val.serialize_field("time", self.speed().as_str())?;
 		match &self.result {
 			Ok(v) => {
@@ -61,6 +127,50 @@ impl Serialize for Response {
 				val.serialize_field("result", &Value::from(e.to_string()))?;
 			}
 		}
+		if let Some(v) = &self.checksum {
+			val.serialize_field("checksum", v)?;
+		}
+		if let Some(v) = &self.denials {
+			val.serialize_field("denials", v)?;
+		}
+		if let Some(v) = self.truncated {
+			val.serialize_field("truncated", &Value::from(v))?;
+		}
 		val.end()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn checksum_ordered() {
+		let a = Value::parse("[{ id: 1 }, { id: 2 }]");
+		let b = Value::parse("[{ id: 2 }, { id: 1 }]");
+		assert_eq!(checksum(&a, false), checksum(&a.clone(), false));
+		assert_ne!(checksum(&a, false), checksum(&b, false));
+	}
+
+	#[test]
+	fn truncates_to_the_limit() {
+		let mut val = Value::parse("[1, 2, 3]");
+		assert_eq!(ResultTruncated::apply(&mut val, 3), None);
+		let truncated = ResultTruncated::apply(&mut val, 2).unwrap();
+		assert_eq!(truncated.returned, 2);
+		assert_eq!(val, Value::parse("[1, 2]"));
+		let mut val = Value::parse("{ a: [1, 2, 3] }");
+		assert_eq!(ResultTruncated::apply(&mut val, 1), None);
+	}
+
+	#[test]
+	fn checksum_unordered() {
+		let a = Value::parse("[{ id: 1 }, { id: 2 }]");
+		let b = Value::parse("[{ id: 2 }, { id: 1 }]");
+		let c = Value::parse("[{ id: 1 }, { id: 3 }]");
+		assert_eq!(checksum(&a, true), checksum(&b, true));
+		assert_ne!(checksum(&a, true), checksum(&c, true));
+		assert_ne!(checksum(&a, true), checksum(&a, false));
+	}
+}
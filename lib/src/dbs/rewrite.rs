@@ -0,0 +1,310 @@
+//! Rewrite rules, which change the statements of a query before they are planned and run.
+//!
+//! Rules are added to a datastore with [`Datastore::with_rewrite_rule`], and are applied to
+//! every statement of every query, in the order in which they were added. Each rule which
+//! changes a statement is traced, along with the statement it produced.
+//!
+//! [`Datastore::with_rewrite_rule`]: crate::kvs::Datastore::with_rewrite_rule
+use crate::dbs::Session;
+use crate::sql::statements::SelectStatement;
+use crate::sql::Data;
+use crate::sql::Expression;
+use crate::sql::Field;
+use crate::sql::Function;
+use crate::sql::Ident;
+use crate::sql::Idiom;
+use crate::sql::Limit;
+use crate::sql::Object;
+use crate::sql::Part;
+use crate::sql::Query;
+use crate::sql::Statement;
+use crate::sql::Value;
+use crate::sql::Values;
+use std::sync::Arc;
+
+/// A rule which rewrites the statements of a query
+pub trait RewriteRule: Send + Sync + 'static {
+	/// The name of the rule, which is traced when the rule changes a statement
+	fn name(&self) -> &str;
+
+	/// Rewrites a statement which is run by the session, returning whether it was changed
+	fn rewrite(&self, sess: &Session, stm: &mut Statement) -> bool;
+}
+
+/// Applies the rules to each statement of the query
+pub(crate) fn apply(rules: &[Arc<dyn RewriteRule>], sess: &Session, ast: &mut Query) {
+	for stm in ast.0 .0.iter_mut() {
+		for rule in rules {
+			if rule.rewrite(sess, stm) {
+				trace!("Applied rewrite rule `{}`, which produced: {stm}", rule.name());
+			}
+		}
+	}
+}
+
+/// Renames a field of a table, such as a deprecated field which was given a new name
+///
+/// The field is renamed wherever it is read or written by `SELECT`, `CREATE`, `UPDATE`,
+/// `DELETE` and `INSERT` statements on the table.
+pub struct RenameField {
+	name: String,
+	table: String,
+	from: String,
+	to: String,
+}
+
+impl RenameField {
+	/// Creates a rule which renames the field of the table
+	pub fn new(table: impl Into<String>, from: impl Into<String>, to: impl Into<String>) -> Self {
+		let (table, from, to) = (table.into(), from.into(), to.into());
+		Self {
+			name: format!("rename {table}.{from} to {to}"),
+			table,
+			from,
+			to,
+		}
+	}
+
+	/// Checks whether the statement runs on the table
+	fn targets(&self, what: &Values) -> bool {
+		what.iter().any(|v| match v {
+			Value::Table(v) => v.0 == self.table,
+			Value::Thing(v) => v.tb == self.table,
+			Value::Range(v) => v.tb == self.table,
+			_ => false,
+		})
+	}
+
+	fn idiom(&self, idiom: &mut Idiom) -> bool {
+		match idiom.0.first_mut() {
+			Some(Part::Field(field)) if field.0 == self.from => {
+				*field = Ident(self.to.clone());
+				true
+			}
+			_ => false,
+		}
+	}
+
+	fn value(&self, value: &mut Value) -> bool {
+		match value {
+			Value::Idiom(v) => self.idiom(v),
+			Value::Array(v) => v.iter_mut().fold(false, |changed, v| self.value(v) | changed),
+			Value::Expression(v) => match v.as_mut() {
+				Expression::Unary {
+					v,
+					..
+				} => self.value(v),
+				Expression::Binary {
+					l,
+					r,
+					..
+				} => self.value(l) | self.value(r),
+			},
+			Value::Function(v) => match v.as_mut() {
+				Function::Normal(_, args)
+				| Function::Custom(_, args)
+				| Function::Script(_, args) => {
+					args.iter_mut().fold(false, |changed, v| self.value(v) | changed)
+				}
+			},
+			_ => false,
+		}
+	}
+
+	/// Renames the key of an object, such as the content of a record
+	fn object(&self, value: &mut Value) -> bool {
+		match value {
+			Value::Object(Object(v)) => match v.remove(&self.from) {
+				Some(field) => {
+					v.insert(self.to.clone(), field);
+					true
+				}
+				None => false,
+			},
+			_ => false,
+		}
+	}
+
+	fn data(&self, data: &mut Data) -> bool {
+		match data {
+			Data::SetExpression(v) | Data::UpdateExpression(v) => v
+				.iter_mut()
+				.fold(false, |changed, (i, _, v)| self.idiom(i) | self.value(v) | changed),
+			Data::UnsetExpression(v) => {
+				v.iter_mut().fold(false, |changed, i| self.idiom(i) | changed)
+			}
+			Data::MergeExpression(v)
+			| Data::ReplaceExpression(v)
+			| Data::ContentExpression(v)
+			| Data::SingleExpression(v) => match v {
+				Value::Array(v) => v.iter_mut().fold(false, |changed, v| self.object(v) | changed),
+				v => self.object(v),
+			},
+			Data::ValuesExpression(v) => v
+				.iter_mut()
+				.flatten()
+				.fold(false, |changed, (i, v)| self.idiom(i) | self.value(v) | changed),
+			_ => false,
+		}
+	}
+
+	fn select(&self, stm: &mut SelectStatement) -> bool {
+		let mut changed = false;
+		for field in stm.expr.0.iter_mut() {
+			if let Field::Single {
+				expr,
+				..
+			} = field
+			{
+				changed |= self.value(expr);
+			}
+		}
+		if let Some(v) = &mut stm.omit {
+			changed = v.0.iter_mut().fold(changed, |changed, i| self.idiom(i) | changed);
+		}
+		if let Some(v) = &mut stm.cond {
+			changed |= self.value(&mut v.0);
+		}
+		if let Some(v) = &mut stm.split {
+			changed = v.0.iter_mut().fold(changed, |changed, s| self.idiom(&mut s.0) | changed);
+		}
+		if let Some(v) = &mut stm.group {
+			changed = v.0.iter_mut().fold(changed, |changed, g| self.idiom(&mut g.0) | changed);
+		}
+		if let Some(v) = &mut stm.order {
+			changed = v.0.iter_mut().fold(changed, |changed, o| self.idiom(&mut o.order) | changed);
+		}
+		changed
+	}
+}
+
+impl RewriteRule for RenameField {
+	fn name(&self) -> &str {
+		&self.name
+	}
+
+	fn rewrite(&self, _: &Session, stm: &mut Statement) -> bool {
+		match stm {
+			Statement::Select(v) if self.targets(&v.what) => self.select(v),
+			Statement::Create(v) if self.targets(&v.what) => {
+				v.data.as_mut().map_or(false, |data| self.data(data))
+			}
+			Statement::Update(v) if self.targets(&v.what) => {
+				let changed = v.data.as_mut().map_or(false, |data| self.data(data));
+				v.cond.as_mut().map_or(false, |cond| self.value(&mut cond.0)) | changed
+			}
+			Statement::Delete(v) if self.targets(&v.what) => {
+				v.cond.as_mut().map_or(false, |cond| self.value(&mut cond.0))
+			}
+			Statement::Insert(v) if matches!(&v.into, Value::Table(t) if t.0 == self.table) => {
+				self.data(&mut v.data)
+			}
+			_ => false,
+		}
+	}
+}
+
+/// A condition on the session which runs a statement
+type SessionFilter = Box<dyn Fn(&Session) -> bool + Send + Sync>;
+
+/// Adds a `LIMIT` clause to `SELECT` statements which don't have one
+pub struct ForceLimit {
+	name: String,
+	limit: u64,
+	when: Option<SessionFilter>,
+}
+
+impl ForceLimit {
+	/// Creates a rule which limits the number of records which are selected
+	pub fn new(limit: u64) -> Self {
+		Self {
+			name: format!("force limit {limit}"),
+			limit,
+			when: None,
+		}
+	}
+
+	/// Only applies the rule to the statements run by the sessions which match the condition
+	pub fn when(mut self, condition: impl Fn(&Session) -> bool + Send + Sync + 'static) -> Self {
+		self.when = Some(Box::new(condition));
+		self
+	}
+}
+
+impl RewriteRule for ForceLimit {
+	fn name(&self) -> &str {
+		&self.name
+	}
+
+	fn rewrite(&self, sess: &Session, stm: &mut Statement) -> bool {
+		match stm {
+			Statement::Select(v) if v.limit.is_none() => {
+				if self.when.as_ref().map_or(true, |when| when(sess)) {
+					v.limit = Some(Limit(self.limit.into()));
+					return true;
+				}
+				false
+			}
+			_ => false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql;
+
+	fn rewrite(rule: impl RewriteRule, sess: &Session, query: &str) -> String {
+		let mut ast = sql::parse(query).unwrap();
+		apply(&[Arc::new(rule)], sess, &mut ast);
+		ast.to_string()
+	}
+
+	#[test]
+	fn rename_field() {
+		let sess = Session::owner();
+		let rule = || RenameField::new("person", "fullname", "name");
+		assert_eq!(
+			rewrite(
+				rule(),
+				&sess,
+				"SELECT fullname, age FROM person WHERE fullname = 'Tobie' ORDER BY fullname"
+			),
+			"SELECT name, age FROM person WHERE name = 'Tobie' ORDER BY name;"
+		);
+		assert_eq!(
+			rewrite(
+				rule(),
+				&sess,
+				"UPDATE person:tobie SET fullname = string::uppercase(fullname)"
+			),
+			"UPDATE person:tobie SET name = string::uppercase(name);"
+		);
+		assert_eq!(
+			rewrite(rule(), &sess, "CREATE person CONTENT { fullname: 'Tobie' }"),
+			"CREATE person CONTENT { name: 'Tobie' };"
+		);
+		// Other tables are left untouched
+		assert_eq!(
+			rewrite(rule(), &sess, "SELECT fullname FROM company"),
+			"SELECT fullname FROM company;"
+		);
+	}
+
+	#[test]
+	fn force_limit() {
+		let rule = || ForceLimit::new(100).when(|sess| sess.ip.is_some());
+		let mut sess = Session::owner();
+		assert_eq!(rewrite(rule(), &sess, "SELECT * FROM person"), "SELECT * FROM person;");
+		sess.ip = Some("127.0.0.1".to_owned());
+		assert_eq!(
+			rewrite(rule(), &sess, "SELECT * FROM person"),
+			"SELECT * FROM person LIMIT 100;"
+		);
+		assert_eq!(
+			rewrite(rule(), &sess, "SELECT * FROM person LIMIT 10"),
+			"SELECT * FROM person LIMIT 10;"
+		);
+	}
+}
@@ -1,6 +1,7 @@
 use crate::ctx::Context;
 use crate::iam::Auth;
 use crate::iam::{Level, Role};
+use crate::kvs::Temporary;
 use crate::sql::value::Value;
 use std::sync::Arc;
 
@@ -27,6 +28,12 @@ pub struct Session {
 	pub tk: Option<Value>,
 	/// The current scope authentication data
 	pub sd: Option<Value>,
+	/// The data of the temporary tables used in this session
+	pub tt: Temporary,
+	/// Whether the permission clauses which filtered records are reported
+	pub dn: bool,
+	/// Whether the result size limit of the datastore is lifted
+	pub ul: bool,
 }
 
 impl Session {
@@ -54,6 +61,25 @@ impl Session {
 		self
 	}
 
+	/// Report the permission clauses which filter records in each response
+	///
+	/// The permission clauses are only reported when the datastore allows it, with
+	/// [`Datastore::with_denials`](crate::kvs::Datastore::with_denials), so that a session
+	/// can't learn the permissions of the database by setting this itself.
+	pub fn with_denials(mut self, dn: bool) -> Session {
+		self.dn = dn;
+		self
+	}
+
+	/// Lift the result size limit of the datastore for this session
+	///
+	/// This should only be set for trusted sessions, such as those of administrative tools
+	/// which export large tables, and never from client input.
+	pub fn with_unlimited_results(mut self, ul: bool) -> Session {
+		self.ul = ul;
+		self
+	}
+
 	/// Retrieves the selected namespace
 	pub(crate) fn ns(&self) -> Option<Arc<str>> {
 		self.ns.as_deref().map(Into::into)
@@ -132,6 +158,9 @@ impl Session {
 			sc: Some(sc.to_owned()),
 			tk: None,
 			sd: Some(rid),
+			tt: Temporary::default(),
+			dn: false,
+			ul: false,
 		}
 	}
 
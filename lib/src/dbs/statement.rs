@@ -106,6 +106,18 @@ impl<'a> Statement<'a> {
 	pub fn is_delete(&self) -> bool {
 		matches!(self, Statement::Delete(_))
 	}
+	/// Check if the statement writes records
+	#[inline]
+	pub fn is_write(&self) -> bool {
+		matches!(
+			self,
+			Statement::Create(_)
+				| Statement::Update(_)
+				| Statement::Relate(_)
+				| Statement::Delete(_)
+				| Statement::Insert(_)
+		)
+	}
 	/// Returns any query fields if specified
 	#[inline]
 	pub fn expr(&self) -> Option<&Fields> {
@@ -0,0 +1,214 @@
+//! The internal events of a datastore, which embedders can ship to their own telemetry pipelines
+//!
+//! Events are received by subscribing with [`Datastore::telemetry`](crate::kvs::Datastore::telemetry),
+//! and serialize to JSON with a stable schema: every event is an object with a `time` and an
+//! `event` field, which names the kind of event, alongside the fields of that kind of event.
+//! Fields are never renamed or removed, but new kinds of events and new fields may be added.
+//!
+//! ```json
+//! {"time":"2024-01-01T00:00:00Z","event":"query_finished","query":"0189d8b1-...","duration_us":512,"statements":2,"errors":0}
+//! ```
+
+use crate::dbs::Action;
+use channel::Receiver;
+use channel::Sender;
+use channel::TrySendError;
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use uuid::Uuid;
+
+/// The maximum number of events buffered for a subscriber which isn't keeping up
+const TELEMETRY_BUFFER: usize = 1024;
+
+/// An internal event of a datastore
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct TelemetryEvent {
+	/// When the event happened
+	pub time: DateTime<Utc>,
+	/// What happened
+	#[serde(flatten)]
+	pub kind: TelemetryKind,
+}
+
+/// The kinds of internal events of a datastore
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum TelemetryKind {
+	/// A query was received, and is about to run
+	QueryStarted {
+		/// The ID which correlates the events of the query
+		query: Uuid,
+		/// The namespace of the session, if one was selected
+		ns: Option<String>,
+		/// The database of the session, if one was selected
+		db: Option<String>,
+		/// The number of statements in the query
+		statements: usize,
+	},
+	/// A query has run
+	QueryFinished {
+		/// The ID which correlates the events of the query
+		query: Uuid,
+		/// How long the query took to run, in microseconds
+		duration_us: u64,
+		/// The number of statements which were run
+		statements: usize,
+		/// The number of statements which failed
+		errors: usize,
+	},
+	/// A transaction was committed to the storage engine
+	TransactionCommitted,
+	/// The entries of an index were updated for a record
+	IndexUpdated {
+		/// The namespace of the index
+		ns: String,
+		/// The database of the index
+		db: String,
+		/// The table of the index
+		tb: String,
+		/// The name of the index
+		ix: String,
+	},
+	/// A live query was notified of a change which was committed
+	LiveQueryFired {
+		/// The ID of the live query
+		id: Uuid,
+		/// The change which was made
+		action: Action,
+	},
+}
+
+impl TelemetryEvent {
+	/// Serializes the event to its JSON representation
+	pub fn to_json(&self) -> String {
+		// Events only hold strings, numbers and IDs, so they always serialize
+		serde_json::to_string(self).unwrap_or_default()
+	}
+}
+
+/// Fans the internal events of a datastore out to its subscribers
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Telemetry {
+	subscribers: Arc<Mutex<Vec<Sender<TelemetryEvent>>>>,
+}
+
+impl Telemetry {
+	fn subscribers(&self) -> MutexGuard<'_, Vec<Sender<TelemetryEvent>>> {
+		self.subscribers.lock().unwrap_or_else(|error| error.into_inner())
+	}
+
+	/// Adds a subscriber, which receives the events from now on
+	pub(crate) fn subscribe(&self) -> Receiver<TelemetryEvent> {
+		let (tx, rx) = channel::bounded(TELEMETRY_BUFFER);
+		self.subscribers().push(tx);
+		rx
+	}
+
+	/// Checks whether anyone is subscribed, so that events are only built when they are received
+	pub(crate) fn is_active(&self) -> bool {
+		!self.subscribers().is_empty()
+	}
+
+	/// Sends an event to every subscriber, without waiting for any of them
+	pub(crate) fn emit(&self, kind: TelemetryKind) {
+		let mut subscribers = self.subscribers();
+		if subscribers.is_empty() {
+			return;
+		}
+		let event = TelemetryEvent {
+			time: Utc::now(),
+			kind,
+		};
+		subscribers.retain(|tx| match tx.try_send(event.clone()) {
+			Ok(()) => true,
+			Err(TrySendError::Full(..)) => {
+				trace!("Telemetry subscriber is lagging behind; dropping event");
+				true
+			}
+			// The subscriber has gone away
+			Err(TrySendError::Closed(..)) => false,
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::TimeZone;
+
+	#[test]
+	fn serializes_with_a_stable_schema() {
+		let event = TelemetryEvent {
+			time: Utc.timestamp_opt(0, 0).unwrap(),
+			kind: TelemetryKind::IndexUpdated {
+				ns: "test".to_owned(),
+				db: "test".to_owned(),
+				tb: "person".to_owned(),
+				ix: "email".to_owned(),
+			},
+		};
+		assert_eq!(
+			event.to_json(),
+			r#"{"time":"1970-01-01T00:00:00Z","event":"index_updated","ns":"test","db":"test","tb":"person","ix":"email"}"#
+		);
+		let event = TelemetryEvent {
+			time: Utc.timestamp_opt(0, 0).unwrap(),
+			kind: TelemetryKind::TransactionCommitted,
+		};
+		assert_eq!(
+			event.to_json(),
+			r#"{"time":"1970-01-01T00:00:00Z","event":"transaction_committed"}"#
+		);
+	}
+
+	#[tokio::test]
+	async fn drops_closed_subscribers() {
+		let telemetry = Telemetry::default();
+		assert!(!telemetry.is_active());
+		let rx = telemetry.subscribe();
+		let closed = telemetry.subscribe();
+		drop(closed);
+		telemetry.emit(TelemetryKind::TransactionCommitted);
+		assert_eq!(telemetry.subscribers().len(), 1);
+		assert_eq!(rx.recv().await.unwrap().kind, TelemetryKind::TransactionCommitted);
+	}
+
+	#[tokio::test]
+	async fn reports_the_events_of_a_query() {
+		let ds = crate::kvs::Datastore::new("memory").await.unwrap();
+		let ses = crate::dbs::Session::owner().with_ns("test").with_db("test");
+		let events = ds.telemetry();
+		let sql = "DEFINE INDEX email ON person FIELDS email; CREATE person SET email = 'a@b.c';";
+		ds.execute(sql, &ses, None).await.unwrap();
+		let kinds: Vec<_> = std::iter::from_fn(|| events.try_recv().ok()).map(|e| e.kind).collect();
+		assert!(matches!(
+			kinds.first(),
+			Some(TelemetryKind::QueryStarted {
+				statements: 2,
+				..
+			})
+		));
+		assert!(matches!(
+			kinds.last(),
+			Some(TelemetryKind::QueryFinished {
+				statements: 2,
+				errors: 0,
+				..
+			})
+		));
+		assert!(kinds.contains(&TelemetryKind::TransactionCommitted));
+		assert!(kinds.contains(&TelemetryKind::IndexUpdated {
+			ns: "test".to_owned(),
+			db: "test".to_owned(),
+			tb: "person".to_owned(),
+			ix: "email".to_owned(),
+		}));
+	}
+}
@@ -0,0 +1,196 @@
+use crate::err::Error;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use trice::Instant;
+
+/// Limits on the writes to a table, so that a busy table can't starve the other tables
+///
+/// The limits apply to the table of this name in every namespace and database, and are
+/// counted separately for each of them. Writes which go over a limit fail straight away,
+/// instead of waiting, so that they never hold a transaction open for longer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct WriteLimit {
+	/// The maximum number of records which are written in each window
+	pub writes: Option<u64>,
+	/// How long the writes are counted for before they are reset
+	pub window: Duration,
+	/// The maximum number of statements which write to the table at the same time
+	pub concurrency: Option<usize>,
+}
+
+impl WriteLimit {
+	/// Creates a limit which counts the writes over the given window, without any limits
+	pub fn new(window: Duration) -> Self {
+		Self {
+			writes: None,
+			window,
+			concurrency: None,
+		}
+	}
+
+	/// Limits the number of records which are created, updated or deleted in each window
+	pub fn with_writes(mut self, writes: u64) -> Self {
+		self.writes = Some(writes);
+		self
+	}
+
+	/// Limits the number of statements which write to the table at the same time
+	pub fn with_concurrency(mut self, statements: usize) -> Self {
+		self.concurrency = Some(statements);
+		self
+	}
+}
+
+/// The writes to a table, in the current window
+#[derive(Debug)]
+struct TableUsage {
+	writes: u64,
+	since: Instant,
+	active: Arc<AtomicUsize>,
+}
+
+impl TableUsage {
+	fn new() -> Self {
+		Self {
+			writes: 0,
+			since: Instant::now(),
+			active: Arc::default(),
+		}
+	}
+}
+
+/// Marks a statement which writes to a table, until it is dropped
+#[derive(Debug)]
+pub(crate) struct WriteGuard(Arc<AtomicUsize>);
+
+impl Drop for WriteGuard {
+	fn drop(&mut self) {
+		self.0.fetch_sub(1, Ordering::AcqRel);
+	}
+}
+
+/// Enforces the write limits of the tables on a datastore
+#[derive(Debug, Default)]
+pub(crate) struct WriteThrottle {
+	limits: HashMap<String, WriteLimit>,
+	tables: Mutex<HashMap<(String, String, String), TableUsage>>,
+}
+
+impl WriteThrottle {
+	/// Returns a throttle which also limits the writes to a table
+	pub(crate) fn with_limit(&self, tb: &str, limit: WriteLimit) -> Self {
+		let mut limits = self.limits.clone();
+		limits.insert(tb.to_owned(), limit);
+		Self {
+			limits,
+			tables: Mutex::default(),
+		}
+	}
+
+	/// Counts a record written to a table, if its limit allows it
+	pub(crate) fn write(&self, ns: &str, db: &str, tb: &str) -> Result<(), Error> {
+		let Some(limit) = self.limits.get(tb) else {
+			return Ok(());
+		};
+		self.with(ns, db, tb, limit, |usage| {
+			if limit.writes.is_some_and(|v| usage.writes >= v) {
+				return Err(Error::TableWriteLimitExceeded {
+					ns: ns.to_owned(),
+					db: db.to_owned(),
+					tb: tb.to_owned(),
+				});
+			}
+			usage.writes += 1;
+			Ok(())
+		})
+	}
+
+	/// Marks a statement which writes to a table, if its limit allows one more of them
+	pub(crate) fn enter(&self, ns: &str, db: &str, tb: &str) -> Result<Option<WriteGuard>, Error> {
+		let Some(limit) = self.limits.get(tb) else {
+			return Ok(None);
+		};
+		let Some(concurrency) = limit.concurrency else {
+			return Ok(None);
+		};
+		let active = self.with(ns, db, tb, limit, |usage| usage.active.clone());
+		let entered = active.fetch_update(Ordering::AcqRel, Ordering::Acquire, |v| {
+			(v < concurrency).then_some(v + 1)
+		});
+		match entered {
+			Ok(_) => Ok(Some(WriteGuard(active))),
+			Err(_) => Err(Error::TableWriteConcurrencyExceeded {
+				ns: ns.to_owned(),
+				db: db.to_owned(),
+				tb: tb.to_owned(),
+			}),
+		}
+	}
+
+	fn with<T>(
+		&self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		limit: &WriteLimit,
+		f: impl FnOnce(&mut TableUsage) -> T,
+	) -> T {
+		let mut tables = self.tables.lock().unwrap_or_else(|e| e.into_inner());
+		let usage = tables
+			.entry((ns.to_owned(), db.to_owned(), tb.to_owned()))
+			.or_insert_with(TableUsage::new);
+		// Start a new window once the current one has elapsed
+		if usage.since.elapsed() >= limit.window {
+			usage.writes = 0;
+			usage.since = Instant::now();
+		}
+		f(usage)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn limits_writes_per_table() {
+		let limit = WriteLimit::new(Duration::from_secs(60)).with_writes(2);
+		let throttle = WriteThrottle::default().with_limit("log", limit);
+		throttle.write("test", "test", "log").unwrap();
+		throttle.write("test", "test", "log").unwrap();
+		assert!(matches!(
+			throttle.write("test", "test", "log"),
+			Err(Error::TableWriteLimitExceeded { .. })
+		));
+		// Other tables and databases are not affected
+		throttle.write("test", "test", "user").unwrap();
+		throttle.write("test", "other", "log").unwrap();
+	}
+
+	#[test]
+	fn resets_after_window() {
+		let limit = WriteLimit::new(Duration::ZERO).with_writes(1);
+		let throttle = WriteThrottle::default().with_limit("log", limit);
+		throttle.write("test", "test", "log").unwrap();
+		throttle.write("test", "test", "log").unwrap();
+	}
+
+	#[test]
+	fn limits_concurrent_statements() {
+		let limit = WriteLimit::new(Duration::from_secs(60)).with_concurrency(1);
+		let throttle = WriteThrottle::default().with_limit("log", limit);
+		let guard = throttle.enter("test", "test", "log").unwrap();
+		assert!(guard.is_some());
+		assert!(matches!(
+			throttle.enter("test", "test", "log"),
+			Err(Error::TableWriteConcurrencyExceeded { .. })
+		));
+		assert!(throttle.enter("test", "test", "user").unwrap().is_none());
+		// The table can be written to again once the statement is done
+		drop(guard);
+		assert!(throttle.enter("test", "test", "log").unwrap().is_some());
+	}
+}
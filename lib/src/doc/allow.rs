@@ -20,24 +20,28 @@ impl<'a> Document<'a> {
 				// Get the table
 				let tb = self.tb(opt, txn).await?;
 				// Get the permission clause
-				let perms = if stm.is_delete() {
-					&tb.permissions.delete
+				let (action, perms) = if stm.is_delete() {
+					("delete", &tb.permissions.delete)
 				} else if stm.is_select() {
-					&tb.permissions.select
+					("select", &tb.permissions.select)
 				} else if self.is_new() {
-					&tb.permissions.create
+					("create", &tb.permissions.create)
 				} else {
-					&tb.permissions.update
+					("update", &tb.permissions.update)
 				};
 				// Process the table permissions
 				match perms {
-					Permission::None => return Err(Error::Ignore),
+					Permission::None => {
+						ctx.add_denial(&tb.name, None, action, perms);
+						return Err(Error::Ignore);
+					}
 					Permission::Full => return Ok(()),
 					Permission::Specific(e) => {
 						// Disable permissions
 						let opt = &opt.new_with_perms(false);
 						// Process the PERMISSION clause
 						if !e.compute(ctx, opt, txn, Some(&self.current)).await?.is_truthy() {
+							ctx.add_denial(&tb.name, None, action, perms);
 							return Err(Error::Ignore);
 						}
 					}
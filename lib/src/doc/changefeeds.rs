@@ -25,8 +25,8 @@ impl<'a> Document<'a> {
 		let mut run = run.lock().await;
 		// Get the database and the table for the record
 		let db = run.add_and_cache_db(opt.ns(), opt.db(), opt.strict).await?;
-		// Check if changefeeds are enabled
-		if db.changefeed.is_some() || tb.changefeed.is_some() {
+		// Check if changefeeds are enabled, which are never kept for temporary tables
+		if (db.changefeed.is_some() || tb.changefeed.is_some()) && !tb.temporary {
 			// Get the arguments
 			let tb = tb.name.as_str();
 			let id = self.id.as_ref().unwrap();
@@ -6,6 +6,7 @@ use crate::iam::Action;
 use crate::iam::ResourceKind;
 use crate::idx::docids::DocId;
 use crate::idx::planner::executor::IteratorRef;
+use crate::kvs::record::Refs;
 use crate::sql::statements::define::DefineEventStatement;
 use crate::sql::statements::define::DefineFieldStatement;
 use crate::sql::statements::define::DefineIndexStatement;
@@ -23,6 +24,8 @@ pub(crate) struct Document<'a> {
 	pub(super) extras: Workable,
 	pub(super) initial: CursorDoc<'a>,
 	pub(super) current: CursorDoc<'a>,
+	/// The shared values which the stored record referenced when it was read, if it was read
+	pub(super) refs: Option<Refs>,
 }
 
 #[derive(Debug)]
@@ -77,12 +80,6 @@ impl<'a> Debug for Document<'a> {
 	}
 }
 
-impl<'a> From<&Document<'a>> for Vec<u8> {
-	fn from(val: &Document<'a>) -> Vec<u8> {
-		val.current.doc.as_ref().into()
-	}
-}
-
 impl<'a> Document<'a> {
 	pub fn new(
 		ir: Option<IteratorRef>,
@@ -90,12 +87,14 @@ impl<'a> Document<'a> {
 		doc_id: Option<DocId>,
 		val: &'a Value,
 		extras: Workable,
+		refs: Option<Refs>,
 	) -> Self {
 		Document {
 			id,
 			extras,
 			current: CursorDoc::new(ir, id, doc_id, val),
 			initial: CursorDoc::new(ir, id, doc_id, val),
+			refs,
 		}
 	}
 }
@@ -0,0 +1,39 @@
+use crate::dbs::Options;
+use crate::doc::Document;
+use crate::err::Error;
+use crate::kvs;
+
+impl<'a> Document<'a> {
+	/// Sets, or clears, the time at which this document expires
+	///
+	/// The expiry time is stored in an index ordered by time, so that
+	/// expired records can be found without scanning the whole table.
+	pub async fn expire(
+		&self,
+		run: &mut kvs::Transaction,
+		opt: &Options,
+		ts: Option<u64>,
+	) -> Result<(), Error> {
+		// Get the record id
+		let rid = self.id.as_ref().unwrap();
+		// Remove the previous expiry time
+		let key = crate::key::table::et::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
+		if let Some(v) = run.get(key.clone()).await? {
+			let prev =
+				v.as_slice().try_into().map(u64::from_be_bytes).map_err(|_| Error::Unreachable)?;
+			let key = crate::key::table::ex::new(opt.ns(), opt.db(), &rid.tb, prev, &rid.id);
+			run.del(key).await?;
+		}
+		// Store the new expiry time
+		match ts {
+			Some(ts) => {
+				let ex = crate::key::table::ex::new(opt.ns(), opt.db(), &rid.tb, ts, &rid.id);
+				run.set(ex, vec![]).await?;
+				run.set(key, ts.to_be_bytes().to_vec()).await?;
+			}
+			None => run.del(key).await?,
+		}
+		// Carry on
+		Ok(())
+	}
+}
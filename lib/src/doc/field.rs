@@ -118,10 +118,10 @@ impl<'a> Document<'a> {
 				// Check for a PERMISSIONS clause
 				if opt.check_perms(Action::Edit) {
 					// Get the permission clause
-					let perms = if self.is_new() {
-						&fd.permissions.create
+					let (action, perms) = if self.is_new() {
+						("create", &fd.permissions.create)
 					} else {
-						&fd.permissions.update
+						("update", &fd.permissions.update)
 					};
 					// Match the permission clause
 					match perms {
@@ -132,7 +132,10 @@ impl<'a> Document<'a> {
 						// The field PERMISSIONS clause
 						// is NONE, meaning that this
 						// change will be reverted.
-						Permission::None => val = old,
+						Permission::None => {
+							ctx.add_denial(&fd.what, Some(&fd.name), action, perms);
+							val = old
+						}
 						// The field PERMISSIONS clause
 						// is a custom expression, so
 						// we check the expression and
@@ -148,6 +151,7 @@ impl<'a> Document<'a> {
 							ctx.add_value("before", &old);
 							// Process the PERMISSION clause
 							if !e.compute(&ctx, opt, txn, Some(&self.current)).await?.is_truthy() {
+								ctx.add_denial(&fd.what, Some(&fd.name), action, perms);
 								val = old
 							}
 						}
@@ -1,14 +1,20 @@
 use crate::ctx::Context;
 use crate::dbs::Statement;
-use crate::dbs::{Options, Transaction};
+use crate::dbs::{Options, TelemetryKind, Transaction};
 use crate::doc::{CursorDoc, Document};
 use crate::err::Error;
+use crate::idx::dedup::SimHashIndex;
+use crate::idx::ft::analyzer::Analyzer;
 use crate::idx::ft::FtIndex;
+use crate::idx::spatial::SpatialIndex;
+use crate::idx::trees::hnsw::HnswIndex;
 use crate::idx::trees::mtree::MTreeIndex;
 use crate::idx::trees::store::TreeStoreType;
 use crate::idx::IndexKeyBase;
 use crate::sql::array::Array;
-use crate::sql::index::{Index, MTreeParams, SearchParams};
+use crate::sql::index::{
+	HnswParams, Index, MTreeParams, SearchParams, SimHashParams, SpatialParams,
+};
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::{Part, Thing, Value};
 use crate::{key, kvs};
@@ -48,6 +54,10 @@ impl<'a> Document<'a> {
 				// Claim transaction
 				let mut run = txn.lock().await;
 
+				// Inject a failure before updating the index
+				#[cfg(feature = "failpoints")]
+				run.failpoint(kvs::failpoint::Failpoint::Index)?;
+
 				// Store all the variable and parameters required by the index operation
 				let mut ic = IndexOperation::new(opt, ix, o, n, rid);
 
@@ -57,7 +67,20 @@ impl<'a> Document<'a> {
 					Index::Idx => ic.index_non_unique(&mut run).await?,
 					Index::Search(p) => ic.index_full_text(&mut run, p).await?,
 					Index::MTree(p) => ic.index_mtree(&mut run, p).await?,
+					Index::SimHash(p) => ic.index_simhash(&mut run, p).await?,
+					Index::Hnsw(p) => ic.index_hnsw(&mut run, p).await?,
+					Index::Spatial(p) => ic.index_spatial(&mut run, p).await?,
 				};
+
+				// Report the updated index
+				if run.telemetry().is_active() {
+					run.telemetry().emit(TelemetryKind::IndexUpdated {
+						ns: opt.ns().to_owned(),
+						db: opt.db().to_owned(),
+						tb: ix.what.to_raw(),
+						ix: ix.name.to_raw(),
+					});
+				}
 			}
 		}
 		// Carry on
@@ -65,6 +88,22 @@ impl<'a> Document<'a> {
 	}
 }
 
+/// Checks whether the given document still produces an entry of a unique or non-unique index
+/// for the given values, so that stale entries can be pruned when the index is rebuilt.
+pub(crate) async fn has_index_entry(
+	ctx: &Context<'_>,
+	opt: &Options,
+	txn: &Transaction,
+	ix: &DefineIndexStatement,
+	doc: &CursorDoc<'_>,
+	fd: &Array,
+) -> Result<bool, Error> {
+	Ok(match build_opt_values(ctx, opt, txn, ix, doc).await? {
+		Some(v) => Indexable::new(v, ix).into_iter().any(|v| &v == fd),
+		None => false,
+	})
+}
+
 /// Extract from the given document, the values required by the index and put then in an array.
 /// Eg. IF the index is composed of the columns `name` and `instrument`
 /// Given this doc: { "id": 1, "instrument":"piano", "name":"Tobie" }
@@ -334,7 +373,7 @@ impl<'a> IndexOperation<'a> {
 		p: &SearchParams,
 	) -> Result<(), Error> {
 		let ikb = IndexKeyBase::new(self.opt, self.ix);
-		let az = run.get_db_analyzer(self.opt.ns(), self.opt.db(), p.az.as_str()).await?;
+		let az = Analyzer::load(run, self.opt.ns(), self.opt.db(), p.az.as_str()).await?;
 		let mut ft = FtIndex::new(run, az, ikb, p, TreeStoreType::Write).await?;
 		if let Some(n) = self.n.take() {
 			ft.index_document(run, self.rid, n).await?;
@@ -361,4 +400,54 @@ impl<'a> IndexOperation<'a> {
 		}
 		mt.finish(run).await
 	}
+
+	async fn index_hnsw(
+		&mut self,
+		run: &mut kvs::Transaction,
+		p: &HnswParams,
+	) -> Result<(), Error> {
+		let ikb = IndexKeyBase::new(self.opt, self.ix);
+		let mut hn = HnswIndex::new(run, ikb, p, TreeStoreType::Write).await?;
+		// The node of an existing record is replaced when it is indexed again
+		if let Some(n) = self.n.take() {
+			hn.index_document(run, self.rid, n).await?;
+		} else {
+			hn.remove_document(run, self.rid).await?;
+		}
+		hn.finish(run).await
+	}
+
+	async fn index_simhash(
+		&mut self,
+		run: &mut kvs::Transaction,
+		p: &SimHashParams,
+	) -> Result<(), Error> {
+		let sh = SimHashIndex::new(self.opt, self.ix, p);
+		// Delete the old index data
+		if let Some(o) = self.o.take() {
+			sh.remove_document(run, self.rid, o).await?;
+		}
+		// Create the new index data
+		if let Some(n) = self.n.take() {
+			sh.index_document(run, self.rid, n).await?;
+		}
+		Ok(())
+	}
+
+	async fn index_spatial(
+		&mut self,
+		run: &mut kvs::Transaction,
+		p: &SpatialParams,
+	) -> Result<(), Error> {
+		let sp = SpatialIndex::new(self.opt, self.ix, p);
+		// Delete the old index data
+		if let Some(o) = self.o.take() {
+			sp.remove_document(run, self.rid, o).await?;
+		}
+		// Create the new index data
+		if let Some(n) = self.n.take() {
+			sp.index_document(run, self.rid, n).await?;
+		}
+		Ok(())
+	}
 }
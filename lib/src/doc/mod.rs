@@ -5,6 +5,7 @@
 //! - `initial`: value before the transaction
 //! - `id`: traditionally an integer but can be an object or collection such as an array
 pub(crate) use self::document::*;
+pub(crate) use self::index::has_index_entry;
 
 mod document; // The entry point for a document to be processed
 
@@ -29,6 +30,7 @@ mod empty; // Checks whether the specified document actually exists
 mod erase; // Removes all content and field data for this document
 mod event; // Processes any table events relevant for this document
 mod exist; // Checks whether the specified document actually exists
+mod expire; // Stores the expiry time of this document, for tables with a TTL
 mod field; // Processes any schema-defined fields for this document
 mod index; // Attempts to store the index data for this document
 mod lives; // Processes any live queries relevant for this document
@@ -78,9 +78,13 @@ impl<'a> Document<'a> {
 					// Loop over each field in document
 					for k in out.each(&fd.name).iter() {
 						// Process the field permissions
-						match &fd.permissions.select {
+						let perms = &fd.permissions.select;
+						match perms {
 							Permission::Full => (),
-							Permission::None => out.del(ctx, opt, txn, k).await?,
+							Permission::None => {
+								ctx.add_denial(&fd.what, Some(&fd.name), "select", perms);
+								out.del(ctx, opt, txn, k).await?
+							}
 							Permission::Specific(e) => {
 								// Disable permissions
 								let opt = &opt.new_with_perms(false);
@@ -95,6 +99,7 @@ impl<'a> Document<'a> {
 									.await?
 									.is_truthy()
 								{
+									ctx.add_denial(&fd.what, Some(&fd.name), "select", perms);
 									out.del(&ctx, opt, txn, k).await?
 								}
 							}
@@ -5,6 +5,7 @@ use crate::dbs::{Operable, Transaction};
 use crate::dbs::{Options, Processed};
 use crate::doc::Document;
 use crate::err::Error;
+use crate::kvs::record;
 use crate::sql::value::Value;
 
 impl<'a> Document<'a> {
@@ -25,7 +26,8 @@ impl<'a> Document<'a> {
 				Operable::Relatable(f, v, w) => (v, Workable::Relate(f, w)),
 			};
 			// Setup a new document
-			let mut doc = Document::new(pro.ir, pro.rid.as_ref(), pro.doc_id, &ins.0, ins.1);
+			let mut doc =
+				Document::new(pro.ir, pro.rid.as_ref(), pro.doc_id, &ins.0, ins.1, pro.refs.take());
 			// Process the statement
 			let res = match stm {
 				Statement::Select(_) => doc.select(ctx, opt, txn, stm).await,
@@ -43,17 +45,15 @@ impl<'a> Document<'a> {
 				// we load the new record, and reprocess
 				Err(Error::RetryWithId(v)) => {
 					// Fetch the data from the store
-					let key = crate::key::thing::new(opt.ns(), opt.db(), &v.tb, &v.id);
-					let val = txn.clone().lock().await.get(key).await?;
-					// Parse the data from the store
-					let val = match val {
-						Some(v) => Value::from(v),
-						None => Value::None,
-					};
+					let mut run = txn.lock().await;
+					let (val, refs) =
+						record::get_with_refs(&mut run, opt.ns(), opt.db(), &v.tb, &v.id).await?;
+					drop(run);
 					pro = Processed {
 						ir: None,
 						doc_id: None,
 						rid: Some(v),
+						refs: Some(refs),
 						val: match doc.extras {
 							Workable::Normal => Operable::Value(val),
 							Workable::Insert(o) => Operable::Mergeable(val, o),
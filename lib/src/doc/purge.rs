@@ -3,6 +3,8 @@ use crate::dbs::Statement;
 use crate::dbs::{Options, Transaction};
 use crate::doc::Document;
 use crate::err::Error;
+use crate::kvs::blob;
+use crate::kvs::record;
 use crate::sql::dir::Dir;
 use crate::sql::edges::Edges;
 use crate::sql::paths::EDGE;
@@ -24,6 +26,12 @@ impl<'a> Document<'a> {
 		if !opt.force && !self.changed() {
 			return Ok(());
 		}
+		// Get the table definition
+		let tb = self.tb(opt, txn).await?;
+		// Check the write limit of the table
+		if let Some(rid) = self.id {
+			ctx.check_write_limit(opt.ns(), opt.db(), &rid.tb)?;
+		}
 		// Clone transaction
 		let run = txn.clone();
 		// Claim transaction
@@ -31,8 +39,15 @@ impl<'a> Document<'a> {
 		// Get the record id
 		if let Some(rid) = self.id {
 			// Purge the record data
-			let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
-			run.del(key).await?;
+			let refs = self.refs.as_ref();
+			let cas = opt.cas.then(|| self.initial.doc.as_ref());
+			record::delete(&mut run, opt.ns(), opt.db(), &rid.tb, &rid.id, refs, cas).await?;
+			// Purge the record expiry time
+			if tb.ttl.is_some() {
+				self.expire(&mut run, opt, None).await?;
+			}
+			// Purge the record blob
+			blob::delete(&mut run, opt.ns(), opt.db(), rid).await?;
 			// Purge the record edges
 			match (
 				self.initial.doc.pick(&*EDGE),
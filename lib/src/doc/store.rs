@@ -3,11 +3,13 @@ use crate::dbs::Statement;
 use crate::dbs::{Options, Transaction};
 use crate::doc::Document;
 use crate::err::Error;
+use crate::kvs::record;
+use chrono::Utc;
 
 impl<'a> Document<'a> {
 	pub async fn store(
 		&self,
-		_ctx: &Context<'_>,
+		ctx: &Context<'_>,
 		opt: &Options,
 		txn: &Transaction,
 		_stm: &Statement<'_>,
@@ -16,17 +18,29 @@ impl<'a> Document<'a> {
 		if !opt.force && !self.changed() {
 			return Ok(());
 		}
+		// Get the table definition
+		let tb = self.tb(opt, txn).await?;
 		// Check if the table is a view
-		if self.tb(opt, txn).await?.drop {
+		if tb.drop {
 			return Ok(());
 		}
-		// Claim transaction
-		let mut run = txn.lock().await;
 		// Get the record id
 		let rid = self.id.as_ref().unwrap();
+		// Check the write limit of the table
+		ctx.check_write_limit(opt.ns(), opt.db(), &rid.tb)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
 		// Store the record data
-		let key = crate::key::thing::new(opt.ns(), opt.db(), &rid.tb, &rid.id);
-		run.set(key, self).await?;
+		let doc = self.current.doc.as_ref();
+		let refs = self.refs.as_ref();
+		let cas = opt.cas.then(|| self.initial.doc.as_ref());
+		record::store(&mut run, opt.ns(), opt.db(), &rid.tb, &rid.id, doc, tb.dedup, refs, cas)
+			.await?;
+		// Store the record expiry time
+		if let Some(ttl) = &tb.ttl {
+			let ts = (Utc::now().timestamp() as u64).saturating_add(ttl.secs());
+			self.expire(&mut run, opt, Some(ts)).await?;
+		}
 		// Carry on
 		Ok(())
 	}
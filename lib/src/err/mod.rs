@@ -73,6 +73,18 @@ pub enum Error {
 	#[error("Couldn't write to a read only transaction")]
 	TxReadonly,
 
+	/// The datastore can not read a historical version of the data
+	#[error("The '{0}' storage engine does not support point-in-time transactions")]
+	TxPointInTimeUnsupported(String),
+
+	/// The datastore can not ship snapshots of its data to a standby directory
+	#[error("The '{0}' storage engine does not support replication to a standby directory")]
+	ReplicationUnsupported(String),
+
+	/// The datastore can not keep the records of a table in memory
+	#[error("The '{0}' storage engine does not support tables defined with CACHE FULL")]
+	TableCacheUnsupported(String),
+
 	/// The conditional value in the request was not equal
 	#[error("Value being checked was not correct")]
 	TxConditionNotMet,
@@ -142,6 +154,38 @@ pub enum Error {
 	#[error("Remote HTTP request functions are not enabled")]
 	HttpDisabled,
 
+	/// A database has used up its quota of outbound HTTP calls
+	#[error(
+		"The database '{db}' in namespace '{ns}' has exceeded its quota of outbound HTTP calls"
+	)]
+	HttpQuotaExceeded {
+		ns: String,
+		db: String,
+	},
+
+	/// A table has reached its limit of records written in the current window
+	#[error("The table '{tb}' in database '{db}' in namespace '{ns}' has exceeded its limit of writes, please try again later")]
+	TableWriteLimitExceeded {
+		ns: String,
+		db: String,
+		tb: String,
+	},
+
+	/// A table has reached its limit of statements writing to it at the same time
+	#[error("The table '{tb}' in database '{db}' in namespace '{ns}' is being written to by too many statements, please try again later")]
+	TableWriteConcurrencyExceeded {
+		ns: String,
+		db: String,
+		tb: String,
+	},
+
+	/// The function was left out of this build of SurrealDB
+	#[error("The function '{name}' is not enabled in this build of SurrealDB. Enable the `{feature}` feature to use it")]
+	FunctionDisabled {
+		name: String,
+		feature: &'static str,
+	},
+
 	/// it is not possible to set a variable with the specified name
 	#[error("Found '{name}' but it is not possible to set a variable with this name")]
 	InvalidParam {
@@ -208,6 +252,21 @@ pub enum Error {
 	#[error("The URL `{0}` is invalid")]
 	InvalidUrl(String),
 
+	/// The cron expression of a backup schedule is invalid
+	#[error("The backup schedule `{value}` is invalid: {message}")]
+	InvalidBackupSchedule {
+		value: String,
+		message: String,
+	},
+
+	/// The cron schedule of a task is invalid
+	#[error("The schedule `{value}` of the task '{name}' is invalid: {message}")]
+	InvalidTaskSchedule {
+		name: String,
+		value: String,
+		message: String,
+	},
+
 	/// The size of the vector is incorrect
 	#[error("Incorrect vector dimension ({current}). Expected a vector of {expected} dimension.")]
 	InvalidVectorDimension {
@@ -360,6 +419,12 @@ pub enum Error {
 		value: String,
 	},
 
+	/// The requested field already exists
+	#[error("The field '{value}' already exists")]
+	FdAlreadyExists {
+		value: String,
+	},
+
 	/// The requested root user does not exist
 	#[error("The root user '{value}' does not exist")]
 	UserRootNotFound {
@@ -479,6 +544,17 @@ pub enum Error {
 		value: String,
 	},
 
+	/// A failure was injected at a failpoint of the write path
+	#[error("An IO error was injected at the '{0}' failpoint")]
+	Failpoint(String),
+
+	/// The entries of an index do not match the records of its table
+	#[error("The entries of the index `{index}` do not match the records of the table `{table}`")]
+	IndexInconsistent {
+		table: String,
+		index: String,
+	},
+
 	/// The specified field did not conform to the field type check
 	#[error("Found {value} for field `{field}`, with record `{thing}`, but expected a {check}")]
 	FieldCheck {
@@ -571,6 +647,20 @@ pub enum Error {
 	#[error("I/O error: {0}")]
 	Io(#[from] IoError),
 
+	/// The data being restored is not a valid binary backup
+	#[error("Invalid backup: {0}")]
+	InvalidBackup(String),
+
+	/// A full backup can only be restored into an empty datastore
+	#[error("The datastore must be empty to restore a full backup into it")]
+	RestoreNotEmpty,
+
+	/// The record has no binary blob stored for it
+	#[error("The record '{value}' has no blob")]
+	BlobNotFound {
+		value: String,
+	},
+
 	/// Represents an error when encoding a key-value entry
 	#[error("Key encoding error: {0}")]
 	Encode(#[from] EncodeError),
@@ -583,6 +673,32 @@ pub enum Error {
 	#[error("Versioned error: {0}")]
 	Revision(#[from] RevisionError),
 
+	/// A record was stored with a newer encoding than this version of SurrealDB supports
+	#[error("A record was stored with version {version} of the record encoding, which is not supported by this version of SurrealDB")]
+	RecordVersion {
+		version: u8,
+	},
+
+	/// A record references a shared value which could not be found
+	#[error("The shared value '{hash}' referenced by a record could not be found")]
+	SharedValueNotFound {
+		hash: String,
+	},
+
+	/// A record which references shared values was decoded without loading them
+	#[error("A record which references shared values can not be decoded without them")]
+	SharedValuesNotLoaded,
+
+	/// A record in cold storage could not be found
+	#[error("The record '{id}' could not be found in cold storage")]
+	ColdRecordNotFound {
+		id: String,
+	},
+
+	/// A record in cold storage was decoded without loading it from cold storage
+	#[error("A record in cold storage can not be decoded without loading it from cold storage")]
+	ColdRecordNotLoaded,
+
 	/// The index has been found to be inconsistent
 	#[error("Index is corrupted")]
 	CorruptedIndex,
@@ -729,7 +845,7 @@ impl From<JWTError> for Error {
 	}
 }
 
-#[cfg(feature = "kv-mem")]
+#[cfg(any(feature = "kv-mem", feature = "kv-opfs"))]
 impl From<echodb::err::Error> for Error {
 	fn from(e: echodb::err::Error) -> Error {
 		match e {
@@ -777,6 +893,60 @@ impl From<rocksdb::Error> for Error {
 	}
 }
 
+#[cfg(feature = "kv-sled")]
+impl From<sled::Error> for Error {
+	fn from(e: sled::Error) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-sled")]
+impl From<sled::transaction::TransactionError<()>> for Error {
+	fn from(e: sled::transaction::TransactionError<()>) -> Error {
+		match e {
+			sled::transaction::TransactionError::Abort(()) => Error::Tx(
+				"Transaction conflict: a key was changed by another transaction".to_owned(),
+			),
+			sled::transaction::TransactionError::Storage(e) => e.into(),
+		}
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::DatabaseError> for Error {
+	fn from(e: redb::DatabaseError) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::TransactionError> for Error {
+	fn from(e: redb::TransactionError) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::TableError> for Error {
+	fn from(e: redb::TableError) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::StorageError> for Error {
+	fn from(e: redb::StorageError) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
+#[cfg(feature = "kv-redb")]
+impl From<redb::CommitError> for Error {
+	fn from(e: redb::CommitError) -> Error {
+		Error::Tx(e.to_string())
+	}
+}
+
 impl From<channel::RecvError> for Error {
 	fn from(e: channel::RecvError) -> Error {
 		Error::Channel(e.to_string())
@@ -1,6 +1,7 @@
 use crate::err::Error;
 use crate::sql::value::Value;
 use crate::sql::{Array, Bytes, Datetime, Duration, Kind, Number, Strand, Thing};
+use std::fmt;
 
 /// Implemented by types that are commonly used, in a certain way, as arguments.
 pub trait FromArg: Sized {
@@ -109,10 +110,53 @@ impl FromArg for Vec<Datetime> {
 	}
 }
 
+/// The number of arguments which a function accepts.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Arity {
+	pub min: usize,
+	pub max: Option<usize>,
+}
+
+impl Arity {
+	const fn new(min: usize, max: Option<usize>) -> Self {
+		Self {
+			min,
+			max,
+		}
+	}
+
+	/// Returns the arity of a function from the type of its arguments. The function is never called.
+	pub(crate) fn of<A: FromArgs, R>(_: impl FnOnce(A) -> R) -> Self {
+		A::arity()
+	}
+
+	/// Checks whether a function can be called with this number of arguments.
+	pub fn accepts(&self, len: usize) -> bool {
+		len >= self.min && self.max.map_or(true, |max| len <= max)
+	}
+}
+
+impl fmt::Display for Arity {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match (self.min, self.max) {
+			(0, Some(0)) => write!(f, "no arguments"),
+			(1, Some(1)) => write!(f, "1 argument"),
+			(min, Some(max)) if min == max => write!(f, "{min} arguments"),
+			(min, Some(max)) if min + 1 == max => write!(f, "{min} or {max} arguments"),
+			(min, Some(max)) => write!(f, "{min} to {max} arguments"),
+			(0, None) => write!(f, "any number of arguments"),
+			(min, None) => write!(f, "at least {min} arguments"),
+		}
+	}
+}
+
 pub trait FromArgs: Sized {
 	/// Convert a collection of argument values into a certain argument format, failing if there are
 	/// too many or too few arguments, or if one of the arguments could not be converted.
 	fn from_args(name: &str, args: Vec<Value>) -> Result<Self, Error>;
+
+	/// The number of arguments which this argument format accepts.
+	fn arity() -> Arity;
 }
 
 // Take ownership of the raw arguments collection, and assume responsibility of validating the
@@ -121,6 +165,10 @@ impl FromArgs for Vec<Value> {
 	fn from_args(_name: &str, args: Vec<Value>) -> Result<Self, Error> {
 		Ok(args)
 	}
+
+	fn arity() -> Arity {
+		Arity::new(0, None)
+	}
 }
 
 impl FromArgs for Vec<Array> {
@@ -135,6 +183,10 @@ impl FromArgs for Vec<Array> {
 			})
 			.collect()
 	}
+
+	fn arity() -> Arity {
+		Arity::new(0, None)
+	}
 }
 
 /// Some functions take a fixed number of arguments.
@@ -165,6 +217,10 @@ macro_rules! impl_tuple {
 					},)*
 				))
 			}
+
+			fn arity() -> Arity {
+				Arity::new($len, Some($len))
+			}
 		}
 	}
 }
@@ -199,6 +255,10 @@ impl<A: FromArg> FromArgs for (Option<A>,) {
 		}
 		Ok((a,))
 	}
+
+	fn arity() -> Arity {
+		Arity::new(0, Some(1))
+	}
 }
 
 // Some functions take 1 or 2 arguments, so the second argument is optional.
@@ -226,6 +286,10 @@ impl<A: FromArg, B: FromArg> FromArgs for (A, Option<B>) {
 		}
 		Ok((a, b))
 	}
+
+	fn arity() -> Arity {
+		Arity::new(1, Some(2))
+	}
 }
 
 // Some functions take 2 or 3 arguments, so the third argument is optional.
@@ -262,6 +326,10 @@ impl<A: FromArg, B: FromArg, C: FromArg> FromArgs for (A, B, Option<C>) {
 		}
 		Ok((a, b, c))
 	}
+
+	fn arity() -> Arity {
+		Arity::new(2, Some(3))
+	}
 }
 
 // Some functions take 0, 1, or 2 arguments, so both arguments are optional.
@@ -297,6 +365,10 @@ impl<A: FromArg, B: FromArg> FromArgs for (Option<A>, Option<B>) {
 		}
 		Ok((a, b))
 	}
+
+	fn arity() -> Arity {
+		Arity::new(0, Some(2))
+	}
 }
 
 // Some functions optionally take 2 arguments, or don't take any at all.
@@ -331,6 +403,10 @@ impl<A: FromArg, B: FromArg> FromArgs for (Option<(A, B)>,) {
 		}
 		Ok((a.zip(b),))
 	}
+
+	fn arity() -> Arity {
+		Arity::new(0, Some(2))
+	}
 }
 
 // Some functions take 1, 2, or 3 arguments. It is safe to assume that, if the second argument is
@@ -371,4 +447,8 @@ impl<A: FromArg, B: FromArg, C: FromArg> FromArgs for (A, Option<B>, Option<C>)
 		}
 		Ok((a, b, c))
 	}
+
+	fn arity() -> Arity {
+		Arity::new(1, Some(3))
+	}
 }
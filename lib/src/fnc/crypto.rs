@@ -111,6 +111,7 @@ pub mod argon2 {
 	}
 }
 
+#[cfg(feature = "password-functions")]
 pub mod bcrypt {
 
 	use crate::err::Error;
@@ -142,6 +143,7 @@ pub mod bcrypt {
 	}
 }
 
+#[cfg(feature = "password-functions")]
 pub mod pbkdf2 {
 
 	use super::COST_ALLOWANCE;
@@ -177,6 +179,7 @@ pub mod pbkdf2 {
 	}
 }
 
+#[cfg(feature = "password-functions")]
 pub mod scrypt {
 
 	use crate::err::Error;
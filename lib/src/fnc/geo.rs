@@ -5,6 +5,7 @@ use geo::algorithm::bearing::HaversineBearing;
 use geo::algorithm::centroid::Centroid;
 use geo::algorithm::chamberlain_duquette_area::ChamberlainDuquetteArea;
 use geo::algorithm::haversine_distance::HaversineDistance;
+use geo::BoundingRect;
 
 pub fn area((arg,): (Value,)) -> Result<Value, Error> {
 	match arg {
@@ -25,6 +26,16 @@ pub fn area((arg,): (Value,)) -> Result<Value, Error> {
 	}
 }
 
+pub fn bbox((arg,): (Value,)) -> Result<Value, Error> {
+	Ok(match arg {
+		Value::Geometry(v) => match geo::Geometry::from(v).bounding_rect() {
+			Some(v) => Geometry::from(v).into(),
+			None => Value::None,
+		},
+		_ => Value::None,
+	})
+}
+
 pub fn bearing(points: (Value, Value)) -> Result<Value, Error> {
 	Ok(match points {
 		(Value::Geometry(Geometry::Point(v)), Value::Geometry(Geometry::Point(w))) => {
@@ -50,6 +61,13 @@ pub fn centroid((arg,): (Value,)) -> Result<Value, Error> {
 	Ok(centroid.map(Into::into).unwrap_or(Value::None))
 }
 
+pub fn contains(geometries: (Value, Value)) -> Result<Value, Error> {
+	Ok(match geometries {
+		(Value::Geometry(v), Value::Geometry(w)) => v.contains(&w).into(),
+		_ => Value::None,
+	})
+}
+
 pub fn distance(points: (Value, Value)) -> Result<Value, Error> {
 	Ok(match points {
 		(Value::Geometry(Geometry::Point(v)), Value::Geometry(Geometry::Point(w))) => {
@@ -59,6 +77,13 @@ pub fn distance(points: (Value, Value)) -> Result<Value, Error> {
 	})
 }
 
+pub fn intersects(geometries: (Value, Value)) -> Result<Value, Error> {
+	Ok(match geometries {
+		(Value::Geometry(v), Value::Geometry(w)) => v.intersects(&w).into(),
+		_ => Value::None,
+	})
+}
+
 pub mod hash {
 
 	use crate::err::Error;
@@ -2,46 +2,6 @@ use crate::ctx::Context;
 use crate::err::Error;
 use crate::sql::value::Value;
 
-#[cfg(not(feature = "http"))]
-pub async fn head(_: &Context<'_>, (_, _): (Value, Option<Value>)) -> Result<Value, Error> {
-	Err(Error::HttpDisabled)
-}
-
-#[cfg(not(feature = "http"))]
-pub async fn get(_: &Context<'_>, (_, _): (Value, Option<Value>)) -> Result<Value, Error> {
-	Err(Error::HttpDisabled)
-}
-
-#[cfg(not(feature = "http"))]
-pub async fn put(
-	_: &Context<'_>,
-	(_, _, _): (Value, Option<Value>, Option<Value>),
-) -> Result<Value, Error> {
-	Err(Error::HttpDisabled)
-}
-
-#[cfg(not(feature = "http"))]
-pub async fn post(
-	_: &Context<'_>,
-	(_, _, _): (Value, Option<Value>, Option<Value>),
-) -> Result<Value, Error> {
-	Err(Error::HttpDisabled)
-}
-
-#[cfg(not(feature = "http"))]
-pub async fn patch(
-	_: &Context<'_>,
-	(_, _, _): (Value, Option<Value>, Option<Value>),
-) -> Result<Value, Error> {
-	Err(Error::HttpDisabled)
-}
-
-#[cfg(not(feature = "http"))]
-pub async fn delete(_: &Context<'_>, (_, _): (Value, Option<Value>)) -> Result<Value, Error> {
-	Err(Error::HttpDisabled)
-}
-
-#[cfg(feature = "http")]
 fn try_as_uri(fn_name: &str, value: Value) -> Result<crate::sql::Strand, Error> {
 	match value {
 		// Pre-check URI.
@@ -54,7 +14,6 @@ fn try_as_uri(fn_name: &str, value: Value) -> Result<crate::sql::Strand, Error>
 	}
 }
 
-#[cfg(feature = "http")]
 fn try_as_opts(
 	fn_name: &str,
 	error_message: &str,
@@ -70,21 +29,18 @@ fn try_as_opts(
 	}
 }
 
-#[cfg(feature = "http")]
 pub async fn head(ctx: &Context<'_>, (uri, opts): (Value, Option<Value>)) -> Result<Value, Error> {
 	let uri = try_as_uri("http::head", uri)?;
 	let opts = try_as_opts("http::head", "The second argument should be an object.", opts)?;
 	crate::fnc::util::http::head(ctx, uri, opts).await
 }
 
-#[cfg(feature = "http")]
 pub async fn get(ctx: &Context<'_>, (uri, opts): (Value, Option<Value>)) -> Result<Value, Error> {
 	let uri = try_as_uri("http::get", uri)?;
 	let opts = try_as_opts("http::get", "The second argument should be an object.", opts)?;
 	crate::fnc::util::http::get(ctx, uri, opts).await
 }
 
-#[cfg(feature = "http")]
 pub async fn put(
 	ctx: &Context<'_>,
 	(uri, body, opts): (Value, Option<Value>, Option<Value>),
@@ -94,7 +50,6 @@ pub async fn put(
 	crate::fnc::util::http::put(ctx, uri, body.unwrap_or(Value::Null), opts).await
 }
 
-#[cfg(feature = "http")]
 pub async fn post(
 	ctx: &Context<'_>,
 	(uri, body, opts): (Value, Option<Value>, Option<Value>),
@@ -104,7 +59,6 @@ pub async fn post(
 	crate::fnc::util::http::post(ctx, uri, body.unwrap_or(Value::Null), opts).await
 }
 
-#[cfg(feature = "http")]
 pub async fn patch(
 	ctx: &Context<'_>,
 	(uri, body, opts): (Value, Option<Value>, Option<Value>),
@@ -114,7 +68,6 @@ pub async fn patch(
 	crate::fnc::util::http::patch(ctx, uri, body.unwrap_or(Value::Null), opts).await
 }
 
-#[cfg(feature = "http")]
 pub async fn delete(
 	ctx: &Context<'_>,
 	(uri, opts): (Value, Option<Value>),
@@ -9,9 +9,11 @@ use crate::fnc::util::math::mode::Mode;
 use crate::fnc::util::math::nearestrank::Nearestrank;
 use crate::fnc::util::math::percentile::Percentile;
 use crate::fnc::util::math::spread::Spread;
+use crate::fnc::util::math::state::{Hll, State, TDigest};
 use crate::fnc::util::math::top::Top;
 use crate::fnc::util::math::trimean::Trimean;
 use crate::fnc::util::math::variance::Variance;
+use crate::sql::array::Array;
 use crate::sql::number::{Number, Sort};
 use crate::sql::value::Value;
 
@@ -34,6 +36,10 @@ pub fn ceil((arg,): (Number,)) -> Result<Value, Error> {
 	Ok(arg.ceil().into())
 }
 
+pub fn count_state((array,): (Array,)) -> Result<Value, Error> {
+	Ok(State::Count(array.len() as u64).into())
+}
+
 pub fn fixed((arg, p): (Number, i64)) -> Result<Value, Error> {
 	if p > 0 {
 		Ok(arg.fixed(p as usize).into())
@@ -49,6 +55,12 @@ pub fn floor((arg,): (Number,)) -> Result<Value, Error> {
 	Ok(arg.floor().into())
 }
 
+pub fn hll_state((array,): (Array,)) -> Result<Value, Error> {
+	let mut hll = Hll::default();
+	array.iter().for_each(|v| hll.add(v));
+	Ok(State::Hll(hll).into())
+}
+
 pub fn interquartile((mut array,): (Vec<Number>,)) -> Result<Value, Error> {
 	Ok(array.sorted().interquartile().into())
 }
@@ -71,6 +83,28 @@ pub fn median((mut array,): (Vec<Number>,)) -> Result<Value, Error> {
 	})
 }
 
+pub fn merge_state((array,): (Array,)) -> Result<Value, Error> {
+	let invalid = |message: &str| Error::InvalidArguments {
+		name: String::from("math::merge_state"),
+		message: String::from(message),
+	};
+	let mut merged: Option<State> = None;
+	for v in array.iter() {
+		let state = State::try_from(v)
+			.map_err(|_| invalid("The argument must be an array of aggregate states."))?;
+		merged = Some(match merged {
+			Some(merged) => merged
+				.merge(state)
+				.ok_or_else(|| invalid("The aggregate states must all be of the same kind."))?,
+			None => state,
+		});
+	}
+	Ok(match merged {
+		Some(v) => v.into(),
+		None => Value::None,
+	})
+}
+
 pub fn midhinge((mut array,): (Vec<Number>,)) -> Result<Value, Error> {
 	Ok(array.sorted().midhinge().into())
 }
@@ -117,6 +151,16 @@ pub fn sqrt((arg,): (Number,)) -> Result<Value, Error> {
 	})
 }
 
+pub fn state_value((state, perc): (Value, Option<Number>)) -> Result<Value, Error> {
+	match State::try_from(&state) {
+		Ok(v) => Ok(v.value(perc.map_or(50.0, |v| v.to_float()))),
+		Err(_) => Err(Error::InvalidArguments {
+			name: String::from("math::state_value"),
+			message: String::from("The first argument must be an aggregate state."),
+		}),
+	}
+}
+
 pub fn stddev((array,): (Vec<Number>,)) -> Result<Value, Error> {
 	Ok(array.deviation(true).into())
 }
@@ -125,6 +169,14 @@ pub fn sum((array,): (Vec<Number>,)) -> Result<Value, Error> {
 	Ok(array.into_iter().sum::<Number>().into())
 }
 
+pub fn sum_state((array,): (Vec<Number>,)) -> Result<Value, Error> {
+	Ok(State::Sum(array.into_iter().sum::<Number>()).into())
+}
+
+pub fn tdigest_state((array,): (Vec<Number>,)) -> Result<Value, Error> {
+	Ok(State::TDigest(TDigest::new(array.iter().map(Number::to_float))).into())
+}
+
 pub fn top((array, c): (Vec<Number>, i64)) -> Result<Value, Error> {
 	if c > 0 {
 		Ok(array.top(c).into())
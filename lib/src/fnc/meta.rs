@@ -2,6 +2,10 @@ use crate::err::Error;
 use crate::sql::thing::Thing;
 use crate::sql::value::Value;
 
+pub fn checksum((arg,): (Value,)) -> Result<Value, Error> {
+	Ok(arg.checksum().into())
+}
+
 pub fn id((arg,): (Thing,)) -> Result<Value, Error> {
 	Ok(arg.id.into())
 }
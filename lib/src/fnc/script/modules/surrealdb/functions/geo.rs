@@ -9,8 +9,11 @@ impl_module_def!(
 	Package,
 	"geo",
 	"area" => run,
+	"bbox" => run,
 	"bearing" => run,
 	"centroid" => run,
+	"contains" => run,
 	"distance" => run,
-	"hash" => (hash::Package)
+	"hash" => (hash::Package),
+	"intersects" => run
 );
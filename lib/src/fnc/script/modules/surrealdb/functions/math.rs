@@ -9,12 +9,15 @@ impl_module_def!(
 	"abs" => run,
 	"bottom" => run,
 	"ceil" => run,
+	"count_state" => run,
 	"fixed" => run,
 	"floor" => run,
+	"hll_state" => run,
 	"interquartile" => run,
 	"max" => run,
 	"mean" => run,
 	"median" => run,
+	"merge_state" => run,
 	"midhinge" => run,
 	"min" => run,
 	"mode" => run,
@@ -25,8 +28,11 @@ impl_module_def!(
 	"round" => run,
 	"spread" => run,
 	"sqrt" => run,
+	"state_value" => run,
 	"stddev" => run,
 	"sum" => run,
+	"sum_state" => run,
+	"tdigest_state" => run,
 	"top" => run,
 	"trimean" => run,
 	"variance" => run
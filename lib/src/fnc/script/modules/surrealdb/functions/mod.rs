@@ -21,6 +21,7 @@ mod rand;
 mod search;
 mod session;
 mod string;
+mod sys;
 mod time;
 mod r#type;
 mod vector;
@@ -48,6 +49,7 @@ impl_module_def!(
 	"session" => (session::Package),
 	"sleep" => fut Async,
 	"string" => (string::Package),
+	"sys" => (sys::Package),
 	"time" => (time::Package),
 	"type" => (r#type::Package),
 	"vector" => (vector::Package)
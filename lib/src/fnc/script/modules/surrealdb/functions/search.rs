@@ -1,4 +1,5 @@
 use super::fut;
+use super::run;
 use crate::fnc::script::modules::impl_module_def;
 use js::prelude::Async;
 
@@ -7,7 +8,10 @@ pub struct Package;
 impl_module_def!(
 	Package,
 	"search",
+	"duplicates" => fut Async,
 	"highlight" => fut Async,
+	"minhash" => run,
 	"offsets" => fut Async,
-	"score" => fut Async
+	"score" => fut Async,
+	"simhash" => run
 );
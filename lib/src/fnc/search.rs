@@ -1,9 +1,17 @@
 use crate::ctx::Context;
+use crate::dbs::Options;
 use crate::dbs::Transaction;
 use crate::doc::CursorDoc;
 use crate::err::Error;
+use crate::idx::dedup;
+use crate::idx::dedup::SimHashIndex;
 use crate::idx::planner::executor::QueryExecutor;
-use crate::sql::{Thing, Value};
+use crate::sql::index::Index;
+use crate::sql::statements::SelectStatement;
+use crate::sql::{Field, Fields, Thing, Value, Values};
+
+/// The largest number of hashes in a MinHash signature
+const MAX_MINHASH_SIZE: i64 = 1024;
 
 fn get_execution_context<'a>(
 	ctx: &'a Context<'_>,
@@ -56,3 +64,57 @@ pub async fn offsets(
 		Ok(Value::None)
 	}
 }
+
+pub fn minhash((text, size): (String, Option<i64>)) -> Result<Value, Error> {
+	let size = match size {
+		None => dedup::MINHASH_SIZE,
+		Some(size @ 1..=MAX_MINHASH_SIZE) => size as usize,
+		Some(_) => {
+			return Err(Error::InvalidArguments {
+				name: String::from("search::minhash"),
+				message: format!(
+					"The size of the signature must be between 1 and {MAX_MINHASH_SIZE}."
+				),
+			})
+		}
+	};
+	Ok(dedup::minhash(&text, size)
+		.into_iter()
+		.map(|h| Value::from(h as i64))
+		.collect::<Vec<_>>()
+		.into())
+}
+
+pub fn simhash((text,): (String,)) -> Result<Value, Error> {
+	Ok(Value::from(dedup::simhash(&text) as i64))
+}
+
+pub async fn duplicates(
+	(ctx, opt, txn): (&Context<'_>, Option<&Options>, Option<&Transaction>),
+	(tb, ix, text): (String, String, String),
+) -> Result<Value, Error> {
+	let (Some(opt), Some(txn)) = (opt, txn) else {
+		return Ok(Value::None);
+	};
+	let ids = {
+		let mut run = txn.lock().await;
+		let ix = run.get_and_cache_tb_index(opt.ns(), opt.db(), &tb, &ix).await?;
+		let Index::SimHash(p) = &ix.index else {
+			return Err(Error::InvalidArguments {
+				name: String::from("search::duplicates"),
+				message: format!("The index '{}' is not a SIMHASH index.", ix.name),
+			});
+		};
+		SimHashIndex::new(opt, &ix, p).lookup(&mut run, &text).await?
+	};
+	if ids.is_empty() {
+		return Ok(Value::Array(Default::default()));
+	}
+	// The records are selected, so that the permissions of the table apply to them
+	let stm = SelectStatement {
+		expr: Fields(vec![Field::All], false),
+		what: Values(ids.into_iter().map(Value::from).collect()),
+		..Default::default()
+	};
+	stm.compute(ctx, opt, txn, None).await
+}
@@ -0,0 +1,12 @@
+use crate::ctx::Context;
+use crate::err::Error;
+use crate::sql::value::Value;
+
+/// Returns the outbound HTTP calls and bytes of the current database, in the current window.
+pub fn http_usage(ctx: &Context, _: ()) -> Result<Value, Error> {
+	let usage = ctx.get_http_usage();
+	Ok(Value::from(map! {
+		"bytes".to_string() => Value::from(usage.bytes),
+		"calls".to_string() => Value::from(usage.calls),
+	}))
+}
@@ -12,29 +12,35 @@ pub(crate) fn uri_is_valid(uri: &str) -> bool {
 	reqwest::Url::parse(uri).is_ok()
 }
 
-fn encode_body(req: RequestBuilder, body: Value) -> RequestBuilder {
-	match body {
-		Value::Bytes(bytes) => req.header(CONTENT_TYPE, "application/octet-stream").body(bytes.0),
-		_ if body.is_some() => req.json(&body.into_json()),
-		_ => req,
-	}
+fn encode_body(ctx: &Context<'_>, req: RequestBuilder, body: Value) -> RequestBuilder {
+	let (mime, body) = match body {
+		Value::Bytes(bytes) => ("application/octet-stream", bytes.0),
+		_ if body.is_some() => ("application/json", body.into_json().to_string().into_bytes()),
+		_ => return req,
+	};
+	// Count the bytes which are sent
+	ctx.add_http_bytes(body.len());
+	req.header(CONTENT_TYPE, mime).body(body)
 }
 
-async fn decode_response(res: Response) -> Result<Value, Error> {
+async fn decode_response(ctx: &Context<'_>, res: Response) -> Result<Value, Error> {
 	match res.status() {
 		s if s.is_success() => match res.headers().get(CONTENT_TYPE) {
 			Some(mime) => match mime.to_str() {
 				Ok(v) if v.starts_with("application/json") => {
 					let txt = res.text().await?;
+					ctx.add_http_bytes(txt.len());
 					let val = json(&txt)?;
 					Ok(val)
 				}
 				Ok(v) if v.starts_with("application/octet-stream") => {
 					let bytes = res.bytes().await?;
+					ctx.add_http_bytes(bytes.len());
 					Ok(Value::Bytes(Bytes(bytes.into())))
 				}
 				Ok(v) if v.starts_with("text") => {
 					let txt = res.text().await?;
+					ctx.add_http_bytes(txt.len());
 					let val = txt.into();
 					Ok(val)
 				}
@@ -50,6 +56,8 @@ pub async fn head(ctx: &Context<'_>, uri: Strand, opts: impl Into<Object>) -> Re
 	// Check if the URI is valid and allowed
 	let url = Url::parse(&uri).map_err(|_| Error::InvalidUrl(uri.to_string()))?;
 	ctx.check_allowed_net(&url)?;
+	// Check that the database has not used up its quota
+	ctx.check_http_quota()?;
 	// Set a default client with no timeout
 	let cli = Client::builder().build()?;
 	// Start a new HEAD request
@@ -79,6 +87,8 @@ pub async fn get(ctx: &Context<'_>, uri: Strand, opts: impl Into<Object>) -> Res
 	// Check if the URI is valid and allowed
 	let url = Url::parse(&uri).map_err(|_| Error::InvalidUrl(uri.to_string()))?;
 	ctx.check_allowed_net(&url)?;
+	// Check that the database has not used up its quota
+	ctx.check_http_quota()?;
 	// Set a default client with no timeout
 	let cli = Client::builder().build()?;
 	// Start a new GET request
@@ -98,7 +108,7 @@ pub async fn get(ctx: &Context<'_>, uri: Strand, opts: impl Into<Object>) -> Res
 		_ => req.send().await?,
 	};
 	// Receive the response as a value
-	decode_response(res).await
+	decode_response(ctx, res).await
 }
 
 pub async fn put(
@@ -110,6 +120,8 @@ pub async fn put(
 	// Check if the URI is valid and allowed
 	let url = Url::parse(&uri).map_err(|_| Error::InvalidUrl(uri.to_string()))?;
 	ctx.check_allowed_net(&url)?;
+	// Check that the database has not used up its quota
+	ctx.check_http_quota()?;
 	// Set a default client with no timeout
 	let cli = Client::builder().build()?;
 	// Start a new GET request
@@ -123,7 +135,7 @@ pub async fn put(
 		req = req.header(k.as_str(), v.to_raw_string());
 	}
 	// Submit the request body
-	req = encode_body(req, body);
+	req = encode_body(ctx, req, body);
 	// Send the request and wait
 	let res = match ctx.timeout() {
 		#[cfg(not(target_arch = "wasm32"))]
@@ -131,7 +143,7 @@ pub async fn put(
 		_ => req.send().await?,
 	};
 	// Receive the response as a value
-	decode_response(res).await
+	decode_response(ctx, res).await
 }
 
 pub async fn post(
@@ -143,6 +155,8 @@ pub async fn post(
 	// Check if the URI is valid and allowed
 	let url = Url::parse(&uri).map_err(|_| Error::InvalidUrl(uri.to_string()))?;
 	ctx.check_allowed_net(&url)?;
+	// Check that the database has not used up its quota
+	ctx.check_http_quota()?;
 	// Set a default client with no timeout
 	let cli = Client::builder().build()?;
 	// Start a new GET request
@@ -156,7 +170,7 @@ pub async fn post(
 		req = req.header(k.as_str(), v.to_raw_string());
 	}
 	// Submit the request body
-	req = encode_body(req, body);
+	req = encode_body(ctx, req, body);
 	// Send the request and wait
 	let res = match ctx.timeout() {
 		#[cfg(not(target_arch = "wasm32"))]
@@ -164,7 +178,7 @@ pub async fn post(
 		_ => req.send().await?,
 	};
 	// Receive the response as a value
-	decode_response(res).await
+	decode_response(ctx, res).await
 }
 
 pub async fn patch(
@@ -176,6 +190,8 @@ pub async fn patch(
 	// Check if the URI is valid and allowed
 	let url = Url::parse(&uri).map_err(|_| Error::InvalidUrl(uri.to_string()))?;
 	ctx.check_allowed_net(&url)?;
+	// Check that the database has not used up its quota
+	ctx.check_http_quota()?;
 	// Set a default client with no timeout
 	let cli = Client::builder().build()?;
 	// Start a new GET request
@@ -189,7 +205,7 @@ pub async fn patch(
 		req = req.header(k.as_str(), v.to_raw_string());
 	}
 	// Submit the request body
-	req = encode_body(req, body);
+	req = encode_body(ctx, req, body);
 	// Send the request and wait
 	let res = match ctx.timeout() {
 		#[cfg(not(target_arch = "wasm32"))]
@@ -197,7 +213,7 @@ pub async fn patch(
 		_ => req.send().await?,
 	};
 	// Receive the response as a value
-	decode_response(res).await
+	decode_response(ctx, res).await
 }
 
 pub async fn delete(
@@ -208,6 +224,8 @@ pub async fn delete(
 	// Check if the URI is valid and allowed
 	let url = Url::parse(&uri).map_err(|_| Error::InvalidUrl(uri.to_string()))?;
 	ctx.check_allowed_net(&url)?;
+	// Check that the database has not used up its quota
+	ctx.check_http_quota()?;
 	// Set a default client with no timeout
 	let cli = Client::builder().build()?;
 	// Start a new GET request
@@ -227,5 +245,5 @@ pub async fn delete(
 		_ => req.send().await?,
 	};
 	// Receive the response as a value
-	decode_response(res).await
+	decode_response(ctx, res).await
 }
@@ -13,6 +13,7 @@ pub mod nearestrank;
 pub mod percentile;
 pub mod quartile;
 pub mod spread;
+pub mod state;
 pub mod top;
 pub mod trimean;
 pub mod variance;
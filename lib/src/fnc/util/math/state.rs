@@ -0,0 +1,362 @@
+//! Partial aggregates, which are computed separately and merged later
+//!
+//! Each state is a plain object with a `kind` field, so that it can be stored, or sent to
+//! other databases, before it is merged with the states of other shards or time windows.
+use crate::sql::{Array, Number, Object, Value};
+use base64_lib::engine::general_purpose::STANDARD_NO_PAD;
+use base64_lib::Engine;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+
+/// The number of bits of the hash of a value which pick its register in a HyperLogLog
+const HLL_PRECISION: u8 = 12;
+
+/// The compression of a t-digest, which bounds its number of centroids
+const TDIGEST_COMPRESSION: f64 = 100.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum State {
+	/// The number of values
+	Count(u64),
+	/// The sum of the values
+	Sum(Number),
+	/// A sketch of the distinct values
+	Hll(Hll),
+	/// A sketch of the distribution of the values
+	TDigest(TDigest),
+}
+
+impl State {
+	/// Merges two states of the same kind
+	pub fn merge(self, other: State) -> Option<State> {
+		match (self, other) {
+			(State::Count(a), State::Count(b)) => Some(State::Count(a + b)),
+			(State::Sum(a), State::Sum(b)) => Some(State::Sum(a + b)),
+			(State::Hll(mut a), State::Hll(b)) => a.merge(&b).then_some(State::Hll(a)),
+			(State::TDigest(a), State::TDigest(b)) => Some(State::TDigest(a.merge(b))),
+			_ => None,
+		}
+	}
+
+	/// The aggregate of the values, which is the given percentile of them for a t-digest
+	pub fn value(&self, perc: f64) -> Value {
+		match self {
+			State::Count(v) => (*v).into(),
+			State::Sum(v) => v.clone().into(),
+			State::Hll(v) => v.estimate().into(),
+			State::TDigest(v) => v.percentile(perc).into(),
+		}
+	}
+}
+
+impl From<State> for Value {
+	fn from(v: State) -> Self {
+		let mut o = BTreeMap::new();
+		match v {
+			State::Count(v) => {
+				o.insert("kind".to_owned(), "count".into());
+				o.insert("count".to_owned(), v.into());
+			}
+			State::Sum(v) => {
+				o.insert("kind".to_owned(), "sum".into());
+				o.insert("sum".to_owned(), v.into());
+			}
+			State::Hll(v) => {
+				o.insert("kind".to_owned(), "hll".into());
+				o.insert("precision".to_owned(), v.precision().into());
+				o.insert("registers".to_owned(), STANDARD_NO_PAD.encode(&v.registers).into());
+			}
+			State::TDigest(v) => {
+				let centroids: Vec<Value> = v
+					.centroids
+					.iter()
+					.map(|(mean, weight)| {
+						Value::from(vec![Value::from(*mean), Value::from(*weight)])
+					})
+					.collect();
+				let (min, max) = match v.centroids.is_empty() {
+					true => (Value::None, Value::None),
+					false => (v.min.into(), v.max.into()),
+				};
+				o.insert("kind".to_owned(), "tdigest".into());
+				o.insert("centroids".to_owned(), centroids.into());
+				o.insert("min".to_owned(), min);
+				o.insert("max".to_owned(), max);
+			}
+		}
+		Value::Object(Object(o))
+	}
+}
+
+impl TryFrom<&Value> for State {
+	type Error = ();
+	fn try_from(v: &Value) -> Result<Self, Self::Error> {
+		let Value::Object(o) = v else {
+			return Err(());
+		};
+		match (o.get("kind"), o.get("count"), o.get("sum")) {
+			(Some(Value::Strand(k)), Some(Value::Number(v)), _) if k.as_str() == "count" => {
+				Ok(State::Count(v.to_int().try_into().map_err(|_| ())?))
+			}
+			(Some(Value::Strand(k)), _, Some(Value::Number(v))) if k.as_str() == "sum" => {
+				Ok(State::Sum(v.clone()))
+			}
+			(Some(Value::Strand(k)), _, _) if k.as_str() == "hll" => {
+				Hll::try_from(o).map(State::Hll)
+			}
+			(Some(Value::Strand(k)), _, _) if k.as_str() == "tdigest" => {
+				TDigest::try_from(o).map(State::TDigest)
+			}
+			_ => Err(()),
+		}
+	}
+}
+
+/// A HyperLogLog, which estimates the number of distinct values
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hll {
+	registers: Vec<u8>,
+}
+
+impl Default for Hll {
+	fn default() -> Self {
+		Self {
+			registers: vec![0; 1 << HLL_PRECISION],
+		}
+	}
+}
+
+impl Hll {
+	fn precision(&self) -> u8 {
+		self.registers.len().trailing_zeros() as u8
+	}
+
+	pub fn add(&mut self, v: &Value) {
+		// The hash must be the same on every database, so that their states can be merged
+		let hash = Sha256::digest(v.to_string().as_bytes());
+		let hash = u64::from_be_bytes(hash[..8].try_into().unwrap());
+		let p = self.precision();
+		let index = (hash >> (64 - p)) as usize;
+		let rank = ((hash << p) | (1 << (p - 1))).leading_zeros() as u8 + 1;
+		self.registers[index] = self.registers[index].max(rank);
+	}
+
+	fn merge(&mut self, other: &Self) -> bool {
+		if self.registers.len() != other.registers.len() {
+			return false;
+		}
+		for (a, b) in self.registers.iter_mut().zip(&other.registers) {
+			*a = (*a).max(*b);
+		}
+		true
+	}
+
+	fn estimate(&self) -> u64 {
+		let m = self.registers.len() as f64;
+		let alpha = 0.7213 / (1.0 + 1.079 / m);
+		let sum: f64 = self.registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+		let zeros = self.registers.iter().filter(|r| **r == 0).count();
+		let estimate = alpha * m * m / sum;
+		// Small numbers of values are estimated from the registers which are still empty
+		let estimate = match estimate <= 2.5 * m && zeros > 0 {
+			true => m * (m / zeros as f64).ln(),
+			false => estimate,
+		};
+		estimate.round() as u64
+	}
+}
+
+impl TryFrom<&Object> for Hll {
+	type Error = ();
+	fn try_from(o: &Object) -> Result<Self, Self::Error> {
+		let (Some(Value::Number(p)), Some(Value::Strand(r))) =
+			(o.get("precision"), o.get("registers"))
+		else {
+			return Err(());
+		};
+		let registers = STANDARD_NO_PAD.decode(r.as_str()).map_err(|_| ())?;
+		match (4..=16).contains(&p.to_int()) && registers.len() == 1 << p.to_int() {
+			true => Ok(Self {
+				registers,
+			}),
+			false => Err(()),
+		}
+	}
+}
+
+/// A t-digest, which estimates the percentiles of the values
+///
+/// The values are clustered into centroids, which are kept small near the smallest and the
+/// largest values, so that extreme percentiles are estimated more accurately.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TDigest {
+	centroids: Vec<(f64, f64)>,
+	min: f64,
+	max: f64,
+}
+
+impl TDigest {
+	pub fn new(values: impl IntoIterator<Item = f64>) -> Self {
+		let centroids: Vec<_> = values.into_iter().map(|v| (v, 1.0)).collect();
+		Self::compress(centroids)
+	}
+
+	fn merge(mut self, other: Self) -> Self {
+		self.centroids.extend(other.centroids);
+		Self::compress(self.centroids)
+	}
+
+	fn compress(mut centroids: Vec<(f64, f64)>) -> Self {
+		centroids.sort_by(|a, b| a.0.total_cmp(&b.0));
+		let min = centroids.first().map_or(f64::INFINITY, |c| c.0);
+		let max = centroids.last().map_or(f64::NEG_INFINITY, |c| c.0);
+		let total: f64 = centroids.iter().map(|c| c.1).sum();
+		let mut out: Vec<(f64, f64)> = Vec::with_capacity(centroids.len());
+		let mut q = 0.0;
+		let mut limit = Self::limit(q);
+		for (mean, weight) in centroids {
+			match out.last_mut() {
+				Some(last) if q + (last.1 + weight) / total <= limit => {
+					last.1 += weight;
+					last.0 += (mean - last.0) * weight / last.1;
+				}
+				last => {
+					if let Some(last) = last {
+						q += last.1 / total;
+						limit = Self::limit(q);
+					}
+					out.push((mean, weight));
+				}
+			}
+		}
+		Self {
+			centroids: out,
+			min,
+			max,
+		}
+	}
+
+	/// The largest quantile which a centroid starting at a quantile can extend to
+	fn limit(q: f64) -> f64 {
+		let k = TDIGEST_COMPRESSION / (2.0 * PI) * (2.0 * q - 1.0).asin() + 1.0;
+		let x = k * 2.0 * PI / TDIGEST_COMPRESSION;
+		match x >= PI / 2.0 {
+			true => 1.0,
+			false => (x.sin() + 1.0) / 2.0,
+		}
+	}
+
+	fn percentile(&self, perc: f64) -> f64 {
+		if self.centroids.is_empty() || !(0.0..=100.0).contains(&perc) {
+			return f64::NAN;
+		}
+		let total: f64 = self.centroids.iter().map(|c| c.1).sum();
+		let target = perc / 100.0 * total;
+		// Each centroid sits in the middle of the values which it holds
+		let mut prev = (self.min, 0.0);
+		let mut seen = 0.0;
+		for &(mean, weight) in &self.centroids {
+			let centre = seen + weight / 2.0;
+			if target <= centre {
+				return Self::interpolate(prev, (mean, centre), target);
+			}
+			prev = (mean, centre);
+			seen += weight;
+		}
+		Self::interpolate(prev, (self.max, total), target)
+	}
+
+	fn interpolate((x0, p0): (f64, f64), (x1, p1): (f64, f64), target: f64) -> f64 {
+		match p1 > p0 {
+			true => x0 + (x1 - x0) * (target - p0) / (p1 - p0),
+			false => x1,
+		}
+	}
+}
+
+impl TryFrom<&Object> for TDigest {
+	type Error = ();
+	fn try_from(o: &Object) -> Result<Self, Self::Error> {
+		let Some(Value::Array(Array(centroids))) = o.get("centroids") else {
+			return Err(());
+		};
+		let centroids = centroids
+			.iter()
+			.map(|c| match c {
+				Value::Array(Array(c)) => match c.as_slice() {
+					[Value::Number(mean), Value::Number(weight)] => {
+						Ok((mean.to_float(), weight.to_float()))
+					}
+					_ => Err(()),
+				},
+				_ => Err(()),
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		let bound = |k: &str, default: f64| match o.get(k) {
+			Some(Value::Number(v)) => Ok(v.to_float()),
+			None | Some(Value::None) if centroids.is_empty() => Ok(default),
+			_ => Err(()),
+		};
+		Ok(Self {
+			min: bound("min", f64::INFINITY)?,
+			max: bound("max", f64::NEG_INFINITY)?,
+			centroids,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn merged_hll_counts_distinct_values() {
+		let mut a = Hll::default();
+		let mut b = Hll::default();
+		for i in 0..6000 {
+			a.add(&Value::from(i));
+		}
+		for i in 4000..10000 {
+			b.add(&Value::from(i));
+		}
+		let merged = State::Hll(a).merge(State::Hll(b)).unwrap();
+		let Value::Number(estimate) = merged.value(50.0) else {
+			panic!();
+		};
+		assert!((9700..=10300).contains(&estimate.to_int()), "{estimate}");
+		// The state survives being stored as a value
+		let value = Value::from(merged.clone());
+		assert_eq!(State::try_from(&value), Ok(merged));
+	}
+
+	#[test]
+	fn merged_tdigest_estimates_percentiles() {
+		let a = TDigest::new((0..5000).map(|v| v as f64));
+		let b = TDigest::new((5000..10000).map(|v| v as f64));
+		let merged = State::TDigest(a).merge(State::TDigest(b)).unwrap();
+		for (perc, expected) in [(0.0, 0.0), (50.0, 5000.0), (99.0, 9900.0), (100.0, 9999.0)] {
+			let Value::Number(v) = merged.value(perc) else {
+				panic!();
+			};
+			assert!((v.to_float() - expected).abs() <= 50.0, "{perc}: {v}");
+		}
+		let value = Value::from(merged.clone());
+		assert_eq!(State::try_from(&value), Ok(merged));
+	}
+
+	#[test]
+	fn small_tdigest_is_exact() {
+		let digest = State::TDigest(TDigest::new([1.0, 2.0, 3.0, 4.0, 5.0]));
+		assert_eq!(digest.value(0.0), Value::from(1.0));
+		assert_eq!(digest.value(50.0), Value::from(3.0));
+		assert_eq!(digest.value(100.0), Value::from(5.0));
+	}
+
+	#[test]
+	fn only_states_of_the_same_kind_are_merged() {
+		let merged = State::Count(2).merge(State::Count(3));
+		assert_eq!(merged, Some(State::Count(5)));
+		assert_eq!(State::Count(2).merge(State::Sum(3.into())), None);
+	}
+}
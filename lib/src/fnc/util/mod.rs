@@ -1,3 +1,4 @@
+#[cfg(feature = "geo-functions")]
 pub mod geo;
 pub mod math;
 pub mod string;
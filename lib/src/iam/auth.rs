@@ -1,9 +1,13 @@
+use crate::kvs::Transaction;
 use crate::sql::statements::{DefineTokenStatement, DefineUserStatement};
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 
 use super::{is_allowed, Action, Actor, Error, Level, Resource, Role};
 
+/// The id of the internal authentication, which is not a stored user
+const SYSTEM_AUTH: &str = "system_auth";
+
 /// Specifies the current authentication for the datastore execution context.
 #[derive(Clone, Default, Debug, Eq, PartialEq, PartialOrd, Hash, Serialize, Deserialize)]
 #[revisioned(revision = 1)]
@@ -57,21 +61,41 @@ impl Auth {
 	/// These are not stored in the database and are used for internal operations
 	/// Do not use for authentication
 	pub fn for_root(role: Role) -> Self {
-		Self::new(Actor::new("system_auth".into(), vec![role], Level::Root))
+		Self::new(Actor::new(SYSTEM_AUTH.into(), vec![role], Level::Root))
 	}
 
 	pub fn for_ns(role: Role, ns: &str) -> Self {
-		Self::new(Actor::new("system_auth".into(), vec![role], (ns,).into()))
+		Self::new(Actor::new(SYSTEM_AUTH.into(), vec![role], (ns,).into()))
 	}
 
 	pub fn for_db(role: Role, ns: &str, db: &str) -> Self {
-		Self::new(Actor::new("system_auth".into(), vec![role], (ns, db).into()))
+		Self::new(Actor::new(SYSTEM_AUTH.into(), vec![role], (ns, db).into()))
 	}
 
 	pub fn for_sc(rid: String, ns: &str, db: &str, sc: &str) -> Self {
 		Self::new(Actor::new(rid, vec![], (ns, db, sc).into()))
 	}
 
+	/// Resolves the current rights of the user who this authentication belongs to
+	///
+	/// Definitions which run with the rights of the user who defined them, such as tasks and
+	/// functions with definer rights, store the authentication of that user and resolve it
+	/// each time they run, so that they follow changes to the roles of the user, and fail once
+	/// the user is removed. The internal system authentication, and the authentication of
+	/// scope users, whose rights come from the permissions of the tables, are kept as they are.
+	pub(crate) async fn current(&self, tx: &mut Transaction) -> Result<Auth, crate::err::Error> {
+		if self.id() == SYSTEM_AUTH {
+			return Ok(self.clone());
+		}
+		let user = match self.level() {
+			Level::Root => tx.get_root_user(self.id()).await?,
+			Level::Namespace(ns) => tx.get_ns_user(ns, self.id()).await?,
+			Level::Database(ns, db) => tx.get_db_user(ns, db, self.id()).await?,
+			_ => return Ok(self.clone()),
+		};
+		Ok((&user, self.level().clone()).into())
+	}
+
 	//
 	// Permission checks
 	//
@@ -26,6 +26,7 @@ pub enum ResourceKind {
 	Event,
 	Field,
 	Index,
+	Task,
 
 	// IAM
 	Actor,
@@ -47,6 +48,7 @@ impl std::fmt::Display for ResourceKind {
 			ResourceKind::Event => write!(f, "Event"),
 			ResourceKind::Field => write!(f, "Field"),
 			ResourceKind::Index => write!(f, "Index"),
+			ResourceKind::Task => write!(f, "Task"),
 			ResourceKind::Actor => write!(f, "Actor"),
 		}
 	}
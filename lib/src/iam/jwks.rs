@@ -0,0 +1,215 @@
+use crate::cnf::{JWKS_CACHE_COOLDOWN, JWKS_CACHE_EXPIRATION, JWKS_FAILURE_COOLDOWN};
+use crate::err::Error;
+use chrono::Utc;
+use jsonwebtoken::jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet};
+use jsonwebtoken::{decode_header, Algorithm, DecodingKey, Validation};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+#[cfg(all(feature = "http", not(target_arch = "wasm32")))]
+use std::time::Duration;
+
+/// A JSON Web Key Set along with the time at which it was fetched
+#[derive(Clone)]
+struct CachedJwks {
+	jwks: JwkSet,
+	time: i64,
+}
+
+impl CachedJwks {
+	fn expired(&self) -> bool {
+		Utc::now().timestamp() - self.time > JWKS_CACHE_EXPIRATION
+	}
+
+	fn cooling(&self) -> bool {
+		Utc::now().timestamp() - self.time < JWKS_CACHE_COOLDOWN
+	}
+}
+
+static CACHE: Lazy<RwLock<HashMap<String, CachedJwks>>> = Lazy::new(Default::default);
+
+/// The time at which fetching each JSON Web Key Set last failed
+static FAILED: Lazy<RwLock<HashMap<String, i64>>> = Lazy::new(Default::default);
+
+/// Builds the decoding key and validation rules for a token signed by a key
+/// published at the specified JSON Web Key Set URL.
+pub(super) async fn config(url: &str, token: &str) -> Result<(DecodingKey, Validation), Error> {
+	// Decode the token header without verifying
+	let header = decode_header(token)?;
+	// Symmetric algorithms can not be used with public keys
+	if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+		trace!("The token algorithm `{:?}` is not supported with a JWKS", header.alg);
+		return Err(Error::InvalidAuth);
+	}
+	// Use the cached key set if it contains the key
+	let cached = CACHE.read().map_err(|_| Error::InvalidAuth)?.get(url).cloned();
+	let jwks = match cached {
+		Some(cached) if !cached.expired() && find(&cached.jwks, &header.kid).is_some() => {
+			cached.jwks
+		}
+		// Don't refetch a recently fetched key set for unknown keys
+		Some(cached) if !cached.expired() && cached.cooling() => cached.jwks,
+		_ => refetch(url).await?,
+	};
+	// Find the key which was used to sign the token
+	let jwk = find(&jwks, &header.kid).ok_or_else(|| {
+		trace!("No matching key was found in the JWKS at `{}`", url);
+		Error::InvalidAuth
+	})?;
+	// The token must be signed with an algorithm which the key is used with
+	if !compatible(header.alg, jwk) {
+		trace!("The token algorithm `{:?}` does not match the key in the JWKS", header.alg);
+		return Err(Error::InvalidAuth);
+	}
+	// Build the verification configuration
+	let key = DecodingKey::from_jwk(jwk)?;
+	Ok((key, Validation::new(header.alg)))
+}
+
+fn find<'a>(jwks: &'a JwkSet, kid: &Option<String>) -> Option<&'a Jwk> {
+	match kid {
+		Some(kid) => jwks.find(kid),
+		// Tokens without a key id can only match a single key
+		None if jwks.keys.len() == 1 => jwks.keys.first(),
+		None => None,
+	}
+}
+
+fn compatible(alg: Algorithm, jwk: &Jwk) -> bool {
+	// The key may be restricted to a single algorithm
+	if jwk.common.algorithm.is_some_and(|expected| expected != alg) {
+		return false;
+	}
+	match &jwk.algorithm {
+		AlgorithmParameters::RSA(_) => matches!(
+			alg,
+			Algorithm::RS256
+				| Algorithm::RS384
+				| Algorithm::RS512
+				| Algorithm::PS256
+				| Algorithm::PS384
+				| Algorithm::PS512
+		),
+		AlgorithmParameters::EllipticCurve(key) => matches!(
+			(alg, &key.curve),
+			(Algorithm::ES256, EllipticCurve::P256) | (Algorithm::ES384, EllipticCurve::P384)
+		),
+		AlgorithmParameters::OctetKeyPair(_) => alg == Algorithm::EdDSA,
+		AlgorithmParameters::OctetKey(_) => false,
+	}
+}
+
+/// Fetches a key set, unless fetching it failed recently
+async fn refetch(url: &str) -> Result<JwkSet, Error> {
+	let now = Utc::now().timestamp();
+	// Don't fetch a key set again soon after fetching it failed
+	let failed = FAILED.read().map_err(|_| Error::InvalidAuth)?.get(url).copied();
+	if failed.is_some_and(|time| now - time < JWKS_FAILURE_COOLDOWN) {
+		trace!("Not fetching the JWKS from `{}`, as fetching it recently failed", url);
+		return Err(Error::InvalidAuth);
+	}
+	match fetch(url).await {
+		Ok(jwks) => {
+			FAILED.write().map_err(|_| Error::InvalidAuth)?.remove(url);
+			Ok(jwks)
+		}
+		Err(e) => {
+			FAILED.write().map_err(|_| Error::InvalidAuth)?.insert(url.to_owned(), now);
+			Err(e)
+		}
+	}
+}
+
+#[cfg(feature = "http")]
+async fn fetch(url: &str) -> Result<JwkSet, Error> {
+	// Log the remote fetch
+	trace!("Fetching the JWKS from `{}`", url);
+	// A slow endpoint must not hold up every signin which uses it
+	let cli = reqwest::Client::builder();
+	#[cfg(not(target_arch = "wasm32"))]
+	let cli = cli
+		.connect_timeout(Duration::from_secs(crate::cnf::JWKS_CONNECT_TIMEOUT))
+		.timeout(Duration::from_secs(crate::cnf::JWKS_FETCH_TIMEOUT));
+	// Retrieve and parse the remote key set
+	let res = cli.build()?.get(url).send().await?;
+	let jwks = res.error_for_status()?.json::<JwkSet>().await?;
+	// Store the key set in the cache
+	CACHE.write().map_err(|_| Error::InvalidAuth)?.insert(
+		url.to_owned(),
+		CachedJwks {
+			jwks: jwks.clone(),
+			time: Utc::now().timestamp(),
+		},
+	);
+	Ok(jwks)
+}
+
+#[cfg(not(feature = "http"))]
+async fn fetch(url: &str) -> Result<JwkSet, Error> {
+	trace!("Unable to fetch the JWKS from `{}` without the `http` feature", url);
+	Err(Error::InvalidAuth)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn cache(url: &str, jwks: &str) {
+		let jwks = serde_json::from_str(jwks).unwrap();
+		CACHE.write().unwrap().insert(
+			url.to_owned(),
+			CachedJwks {
+				jwks,
+				time: Utc::now().timestamp(),
+			},
+		);
+	}
+
+	const JWKS: &str = r#"{"keys":[{"kty":"RSA","kid":"test","use":"sig","alg":"RS256","n":"u1SU1LfVLPHCozMxH2Mo4lgOEePzNm0tRgeLezV6ffAt0gunVTLw7onLRnrq0_IzW7yWR7QkrmBL7jTKEn5u-qKhbwKfBstIs-bMY2Zkp18gnTxKLxoS2tFczGkPLPgizskuemMghRniWaoLcyehkd3qqGElvW_VDL5AaWTg0nLVkjRo9z-40RQzuVaE8AkAFmxZzow3x-VJYKdjykkJ0iT9wCS0DRTXu269V264Vf_3jvredZiKRkgwlL9xNAwxXFg0x_XFw005UWVRIkdgcKWTjpBP2dPwVZ4WWC-9aGVd-Gyn1o0CLelf4rEjGoXbAAEgAqeGUxrcIlbjXfbcmw","e":"AQAB"}]}"#;
+
+	#[tokio::test]
+	async fn test_unknown_key() {
+		cache("https://example.com/unknown/jwks.json", JWKS);
+		// Header: {"alg":"RS256","kid":"other"}
+		let token = "eyJhbGciOiJSUzI1NiIsImtpZCI6Im90aGVyIn0.e30.c2ln";
+		let res = config("https://example.com/unknown/jwks.json", token).await;
+		assert!(matches!(res, Err(Error::InvalidAuth)));
+	}
+
+	#[tokio::test]
+	async fn test_symmetric_algorithm() {
+		cache("https://example.com/symmetric/jwks.json", JWKS);
+		// Header: {"alg":"HS256","kid":"test"}
+		let token = "eyJhbGciOiJIUzI1NiIsImtpZCI6InRlc3QifQ.e30.c2ln";
+		let res = config("https://example.com/symmetric/jwks.json", token).await;
+		assert!(matches!(res, Err(Error::InvalidAuth)));
+	}
+
+	#[tokio::test]
+	async fn test_cached_key() {
+		cache("https://example.com/cached/jwks.json", JWKS);
+		// Header: {"alg":"RS256","kid":"test"}
+		let token = "eyJhbGciOiJSUzI1NiIsImtpZCI6InRlc3QifQ.e30.c2ln";
+		let res = config("https://example.com/cached/jwks.json", token).await;
+		assert!(res.is_ok(), "Failed to find the cached key: {:?}", res.err());
+	}
+
+	#[tokio::test]
+	async fn test_mismatched_algorithm() {
+		cache("https://example.com/mismatched/jwks.json", JWKS);
+		// Header: {"alg":"ES256","kid":"test"}
+		let token = "eyJhbGciOiJFUzI1NiIsImtpZCI6InRlc3QifQ.e30.c2ln";
+		let res = config("https://example.com/mismatched/jwks.json", token).await;
+		assert!(matches!(res, Err(Error::InvalidAuth)));
+	}
+
+	#[tokio::test]
+	async fn test_failed_fetch() {
+		let url = "https://example.com/failed/jwks.json";
+		FAILED.write().unwrap().insert(url.to_owned(), Utc::now().timestamp());
+		// Header: {"alg":"RS256","kid":"test"}
+		let token = "eyJhbGciOiJSUzI1NiIsImtpZCI6InRlc3QifQ.e30.c2ln";
+		let res = config(url, token).await;
+		assert!(matches!(res, Err(Error::InvalidAuth)));
+	}
+}
@@ -6,6 +6,7 @@ pub mod auth;
 pub mod base;
 pub mod clear;
 pub mod entities;
+pub mod jwks;
 pub mod policies;
 pub mod signin;
 pub mod signup;
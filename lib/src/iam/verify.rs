@@ -1,11 +1,12 @@
 use crate::dbs::Session;
 use crate::err::Error;
+use crate::iam::jwks;
 use crate::iam::token::Claims;
 use crate::iam::Auth;
 use crate::iam::{Actor, Level, Role};
 use crate::kvs::Datastore;
 use crate::sql::json;
-use crate::sql::statements::DefineUserStatement;
+use crate::sql::statements::{DefineTokenStatement, DefineUserStatement};
 use crate::sql::Algorithm;
 use crate::sql::Value;
 use argon2::Argon2;
@@ -74,9 +75,31 @@ fn config(algo: Algorithm, code: String) -> Result<(DecodingKey, Validation), Er
 			DecodingKey::from_rsa_pem(code.as_ref())?,
 			Validation::new(jsonwebtoken::Algorithm::RS512),
 		)),
+		// Remote keys are resolved per token
+		Algorithm::Jwks => Err(Error::InvalidAuth),
 	}
 }
 
+async fn token_config(
+	de: &DefineTokenStatement,
+	token: &str,
+) -> Result<(DecodingKey, Validation), Error> {
+	// Resolve the verification key for the token
+	let (key, mut validation) = match de.kind {
+		Algorithm::Jwks => jwks::config(&de.code, token).await?,
+		_ => config(de.kind.clone(), de.code.clone())?,
+	};
+	// Check the token issuer if specified
+	if let Some(iss) = &de.issuer {
+		validation.set_issuer(&[iss.as_str()]);
+	}
+	// Check the token audience if specified
+	if let Some(aud) = &de.audience {
+		validation.set_audience(&[aud.as_str()]);
+	}
+	Ok((key, validation))
+}
+
 static KEY: Lazy<DecodingKey> = Lazy::new(|| DecodingKey::from_secret(&[]));
 
 static DUD: Lazy<Validation> = Lazy::new(|| {
@@ -160,7 +183,7 @@ pub async fn token(kvs: &Datastore, session: &mut Session, token: &str) -> Resul
 			};
 			// Get the scope token
 			let de = tx.get_sc_token(&ns, &db, &sc, &tk).await?;
-			let cf = config(de.kind, de.code)?;
+			let cf = token_config(&de, token).await?;
 			// Verify the token
 			decode::<Claims>(token, &cf.0, &cf.1)?;
 			// Log the success
@@ -225,7 +248,7 @@ pub async fn token(kvs: &Datastore, session: &mut Session, token: &str) -> Resul
 			let mut tx = kvs.transaction(false, false).await?;
 			// Get the database token
 			let de = tx.get_db_token(&ns, &db, &tk).await?;
-			let cf = config(de.kind, de.code)?;
+			let cf = token_config(&de, token).await?;
 			// Verify the token
 			decode::<Claims>(token, &cf.0, &cf.1)?;
 			// Parse the roles
@@ -294,7 +317,7 @@ pub async fn token(kvs: &Datastore, session: &mut Session, token: &str) -> Resul
 			let mut tx = kvs.transaction(false, false).await?;
 			// Get the namespace token
 			let de = tx.get_ns_token(&ns, &tk).await?;
-			let cf = config(de.kind, de.code)?;
+			let cf = token_config(&de, token).await?;
 			// Verify the token
 			decode::<Claims>(token, &cf.0, &cf.1)?;
 			// Parse the roles
@@ -922,6 +945,64 @@ mod tests {
 		}
 	}
 
+	#[tokio::test]
+	async fn test_token_issuer() {
+		let secret = "jwt_secret";
+		let key = EncodingKey::from_secret(secret.as_ref());
+		let claims = Claims {
+			iss: Some("surrealdb-test".to_string()),
+			iat: Some(Utc::now().timestamp()),
+			nbf: Some(Utc::now().timestamp()),
+			exp: Some((Utc::now() + Duration::hours(1)).timestamp()),
+			tk: Some("token".to_string()),
+			ns: Some("test".to_string()),
+			db: Some("test".to_string()),
+			..Claims::default()
+		};
+
+		let ds = Datastore::new("memory").await.unwrap();
+		let sess = Session::owner().with_ns("test").with_db("test");
+		ds.execute(
+			format!("DEFINE TOKEN token ON DB TYPE HS512 VALUE '{secret}' ISSUER 'surrealdb-test'")
+				.as_str(),
+			&sess,
+			None,
+		)
+		.await
+		.unwrap();
+
+		//
+		// Test with the expected issuer
+		//
+		{
+			// Create the token
+			let enc = encode(&HEADER, &claims, &key).unwrap();
+			// Signin with the token
+			let mut sess = Session::default();
+			let res = token(&ds, &mut sess, &enc).await;
+
+			assert!(res.is_ok(), "Failed to signin with token: {:?}", res);
+			assert_eq!(sess.au.id(), "token");
+			assert!(sess.au.is_db());
+		}
+
+		//
+		// Test with an unexpected issuer
+		//
+		{
+			// Prepare the claims object
+			let mut claims = claims.clone();
+			claims.iss = Some("invalid".to_string());
+			// Create the token
+			let enc = encode(&HEADER, &claims, &key).unwrap();
+			// Signin with the token
+			let mut sess = Session::default();
+			let res = token(&ds, &mut sess, &enc).await;
+
+			assert!(res.is_err(), "Unexpected success signing in with token: {:?}", res);
+		}
+	}
+
 	#[test]
 	fn test_verify_pass() {
 		let salt = SaltString::generate(&mut rand::thread_rng());
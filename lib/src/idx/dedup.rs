@@ -0,0 +1,218 @@
+//! Fingerprints of text for finding near-duplicates, and the SIMHASH index which looks them up
+//!
+//! The SimHash of a text is a 64-bit fingerprint, where texts which share most of their words
+//! differ in only a few bits. The index splits each fingerprint into one more band than the
+//! maximum distance of the index, so that any fingerprint within that distance shares at least
+//! one band exactly, and stores the record under each of its bands.
+use crate::dbs::Options;
+use crate::err::Error;
+use crate::kvs;
+use crate::sql::index::SimHashParams;
+use crate::sql::statements::DefineIndexStatement;
+use crate::sql::{Array, Thing, Value};
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+/// The number of hashes in a MinHash signature, unless another number is requested
+pub(crate) const MINHASH_SIZE: usize = 64;
+
+/// Splits a text into lowercase words
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+	text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).map(str::to_lowercase)
+}
+
+/// A stable 64-bit hash, so that fingerprints stored in an index remain valid across versions
+fn hash(bytes: &[u8], seed: u64) -> u64 {
+	// FNV-1a, followed by the SplitMix64 finalizer to spread the bits
+	let mut h = 0xcbf29ce484222325 ^ seed;
+	for b in bytes {
+		h ^= *b as u64;
+		h = h.wrapping_mul(0x100000001b3);
+	}
+	h = (h ^ (h >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+	h = (h ^ (h >> 27)).wrapping_mul(0x94d049bb133111eb);
+	h ^ (h >> 31)
+}
+
+/// Computes the SimHash fingerprint of a text, from the words in the text
+pub(crate) fn simhash(text: &str) -> u64 {
+	let mut weights = [0i64; 64];
+	for word in words(text) {
+		let h = hash(word.as_bytes(), 0);
+		for (bit, weight) in weights.iter_mut().enumerate() {
+			if h & (1 << bit) != 0 {
+				*weight += 1;
+			} else {
+				*weight -= 1;
+			}
+		}
+	}
+	weights.iter().enumerate().filter(|(_, w)| **w > 0).fold(0, |h, (bit, _)| h | (1 << bit))
+}
+
+/// Computes the MinHash signature of a text, from the three-word shingles in the text
+///
+/// The proportion of positions at which the signatures of two texts are equal estimates
+/// the Jaccard similarity of their shingles.
+pub(crate) fn minhash(text: &str, size: usize) -> Vec<u64> {
+	let words: Vec<String> = words(text).collect();
+	let shingles: BTreeSet<String> = match words.len() {
+		0..=3 => BTreeSet::from([words.join(" ")]),
+		_ => words.windows(3).map(|w| w.join(" ")).collect(),
+	};
+	(0..size as u64)
+		.map(|seed| shingles.iter().map(|s| hash(s.as_bytes(), seed)).min().unwrap_or(u64::MAX))
+		.collect()
+}
+
+/// Splits a fingerprint into the bands which are indexed, as pairs of the band and its bits
+fn bands(fingerprint: u64, distance: u8) -> Vec<(usize, u64)> {
+	let count = distance.min(63) as usize + 1;
+	let mut start = 0;
+	(0..count)
+		.map(|band| {
+			// The 64 bits are shared out as evenly as possible between the bands
+			let width = 64 / count + usize::from(band < 64 % count);
+			let bits = (fingerprint >> start) & (u64::MAX >> (64 - width));
+			start += width;
+			(band, bits)
+		})
+		.collect()
+}
+
+/// The text of a record which is fingerprinted, from the string values of the indexed fields
+fn text(values: &[Value]) -> Option<String> {
+	let text: Vec<&str> = values
+		.iter()
+		.filter_map(|v| match v {
+			Value::Strand(v) => Some(v.as_str()),
+			_ => None,
+		})
+		.collect();
+	(!text.is_empty()).then(|| text.join(" "))
+}
+
+pub(crate) struct SimHashIndex<'a> {
+	opt: &'a Options,
+	ix: &'a DefineIndexStatement,
+	distance: u8,
+}
+
+impl<'a> SimHashIndex<'a> {
+	pub(crate) fn new(opt: &'a Options, ix: &'a DefineIndexStatement, p: &SimHashParams) -> Self {
+		Self {
+			opt,
+			ix,
+			distance: p.distance,
+		}
+	}
+
+	fn key(&self, band: usize, bits: u64, rid: &Thing) -> kvs::Key {
+		let fd = Array(vec![Value::from(band), Value::from(bits as i64)]);
+		crate::key::index::Index::new(
+			self.opt.ns(),
+			self.opt.db(),
+			&self.ix.what,
+			&self.ix.name,
+			&fd,
+			Some(&rid.id),
+		)
+		.into()
+	}
+
+	pub(crate) async fn remove_document(
+		&self,
+		run: &mut kvs::Transaction,
+		rid: &Thing,
+		values: Vec<Value>,
+	) -> Result<(), Error> {
+		if let Some(text) = text(&values) {
+			for (band, bits) in bands(simhash(&text), self.distance) {
+				run.del(self.key(band, bits, rid)).await?;
+			}
+		}
+		Ok(())
+	}
+
+	pub(crate) async fn index_document(
+		&self,
+		run: &mut kvs::Transaction,
+		rid: &Thing,
+		values: Vec<Value>,
+	) -> Result<(), Error> {
+		if let Some(text) = text(&values) {
+			let fingerprint = simhash(&text);
+			for (band, bits) in bands(fingerprint, self.distance) {
+				run.set(self.key(band, bits, rid), fingerprint.to_be_bytes().to_vec()).await?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Finds the records whose fingerprint is within the distance of the index from the text
+	///
+	/// The records are returned in order of their distance, closest first.
+	pub(crate) async fn lookup(
+		&self,
+		run: &mut kvs::Transaction,
+		text: &str,
+	) -> Result<Vec<Thing>, Error> {
+		let fingerprint = simhash(text);
+		let mut found = HashMap::new();
+		for (band, bits) in bands(fingerprint, self.distance) {
+			let fd = Array(vec![Value::from(band), Value::from(bits as i64)]);
+			let (ns, db, tb, ix) = (self.opt.ns(), self.opt.db(), &self.ix.what, &self.ix.name);
+			let beg = crate::key::index::Index::prefix_ids_beg(ns, db, tb, ix, &fd);
+			let end = crate::key::index::Index::prefix_ids_end(ns, db, tb, ix, &fd);
+			for (k, v) in run.getr(beg..end, u32::MAX).await? {
+				let Ok(other) = <[u8; 8]>::try_from(v.as_slice()) else {
+					continue;
+				};
+				let distance = (fingerprint ^ u64::from_be_bytes(other)).count_ones();
+				if distance <= self.distance as u32 {
+					if let Some(id) = crate::key::index::Index::decode(&k)?.id {
+						found.insert(id.into_owned(), distance);
+					}
+				}
+			}
+		}
+		let mut found: Vec<_> = found.into_iter().collect();
+		found.sort_by(|(a, x), (b, y)| x.cmp(y).then_with(|| a.cmp(b)));
+		Ok(found.into_iter().map(|(id, _)| Thing::from((self.ix.what.0.as_str(), id))).collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn simhash_of_near_duplicates() {
+		let a = simhash("The quick brown fox jumps over the lazy dog, and runs into the forest");
+		let b = simhash("The quick brown fox jumps over the lazy cat, and runs into the forest");
+		let c = simhash("An entirely different sentence about databases and their indexes");
+		assert!((a ^ b).count_ones() < (a ^ c).count_ones());
+		assert_eq!(
+			a,
+			simhash("the QUICK brown fox jumps over the lazy dog and runs into the forest")
+		);
+	}
+
+	#[test]
+	fn minhash_estimates_similarity() {
+		let a = minhash("one two three four five six seven eight nine ten", MINHASH_SIZE);
+		let b = minhash("one two three four five six seven eight nine eleven", MINHASH_SIZE);
+		assert_eq!(a.len(), MINHASH_SIZE);
+		let equal = a.iter().zip(b.iter()).filter(|(a, b)| a == b).count();
+		assert!(equal > 0 && equal < MINHASH_SIZE);
+	}
+
+	#[test]
+	fn bands_cover_the_fingerprint() {
+		for distance in [0, 3, 5, 63] {
+			let bands = bands(u64::MAX, distance);
+			assert_eq!(bands.len(), distance as usize + 1);
+			assert_eq!(bands.iter().map(|(_, bits)| bits.count_ones()).sum::<u32>(), 64);
+		}
+	}
+}
@@ -0,0 +1,135 @@
+use crate::err::Error;
+use crate::idx::ft::analyzer::tokenizer::{Token, Tokens};
+use crate::idx::ft::offsets::Position;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::{Arc, RwLock};
+
+/// A full-text analyzer implemented in Rust
+///
+/// An analyzer which is registered with
+/// [`Datastore::register_analyzer`](crate::kvs::Datastore::register_analyzer) can be used by
+/// name in the `SEARCH ANALYZER` clause of a full-text index, for languages and tokenization
+/// schemes which the built-in tokenizers and filters don't cover. The same analyzer is used
+/// to index the documents and to analyze the search queries.
+pub trait Analyzer: Send + Sync + 'static {
+	/// Splits a text into the terms which are indexed or searched for
+	fn analyze(&self, text: &str) -> Vec<AnalyzedTerm>;
+}
+
+/// A term found by an [`Analyzer`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AnalyzedTerm {
+	/// The term, after any normalisation
+	pub term: String,
+	/// The byte range in the text which the term was taken from, used for highlighting
+	pub range: Range<usize>,
+}
+
+impl AnalyzedTerm {
+	pub fn new(term: impl Into<String>, range: Range<usize>) -> Self {
+		Self {
+			term: term.into(),
+			range,
+		}
+	}
+}
+
+/// The analyzers registered with a datastore, by name
+#[derive(Clone, Default)]
+pub(crate) struct Registry(Arc<RwLock<HashMap<String, Arc<dyn Analyzer>>>>);
+
+impl Registry {
+	pub(crate) fn register(&self, name: &str, az: Arc<dyn Analyzer>) {
+		self.0.write().unwrap_or_else(|e| e.into_inner()).insert(name.to_owned(), az);
+	}
+
+	pub(crate) fn get(&self, name: &str) -> Option<Arc<dyn Analyzer>> {
+		self.0.read().unwrap_or_else(|e| e.into_inner()).get(name).cloned()
+	}
+}
+
+/// Turns the terms found by a custom analyzer into tokens
+pub(super) fn tokenize(az: &dyn Analyzer, input: String) -> Result<Tokens, Error> {
+	let mut terms = az.analyze(&input);
+	terms.sort_by_key(|t| (t.range.start, t.range.end));
+	let mut t = Vec::with_capacity(terms.len());
+	// The characters are counted incrementally, as the terms are ordered
+	let (mut byte_pos, mut char_pos) = (0, 0);
+	for term in terms {
+		let Range {
+			start,
+			end,
+		} = term.range;
+		if start > end || end > input.len() {
+			return Err(Error::AnalyzerError(format!(
+				"The term '{}' has a range ({start},{end}) which is out of range ({}).",
+				term.term,
+				input.len()
+			)));
+		}
+		if !input.is_char_boundary(start) || !input.is_char_boundary(end) {
+			return Err(Error::AnalyzerError(format!(
+				"The term '{}' has a range ({start},{end}) which is not on a character boundary.",
+				term.term
+			)));
+		}
+		if start >= byte_pos {
+			char_pos += input[byte_pos..start].chars().count();
+		} else {
+			// Terms may overlap
+			char_pos -= input[start..byte_pos].chars().count();
+		}
+		byte_pos = start;
+		let chars = input[start..end].chars().count();
+		t.push(Token::String {
+			chars: (char_pos as Position, (char_pos + chars) as Position),
+			bytes: (start as Position, end as Position),
+			term: term.term,
+		});
+	}
+	Ok(Tokens::with_tokens(input, t))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct Bigrams;
+
+	impl Analyzer for Bigrams {
+		fn analyze(&self, text: &str) -> Vec<AnalyzedTerm> {
+			let chars: Vec<(usize, char)> = text.char_indices().collect();
+			chars
+				.windows(2)
+				.map(|w| {
+					let end = w[1].0 + w[1].1.len_utf8();
+					AnalyzedTerm::new(&text[w[0].0..end], w[0].0..end)
+				})
+				.collect()
+		}
+	}
+
+	#[test]
+	fn tokenize_custom() {
+		let tokens = tokenize(&Bigrams, "東京都".to_string()).unwrap();
+		let mut res = vec![];
+		for t in tokens.list() {
+			res.push(tokens.get_token_string(t).unwrap());
+			assert!(matches!(t, Token::String { .. }));
+		}
+		assert_eq!(res, vec!["東京", "京都"]);
+		assert_eq!(tokens.list()[1].new_offset(0), crate::idx::ft::offsets::Offset::new(0, 1, 3));
+	}
+
+	#[test]
+	fn tokenize_out_of_range() {
+		struct Broken;
+		impl Analyzer for Broken {
+			fn analyze(&self, _: &str) -> Vec<AnalyzedTerm> {
+				vec![AnalyzedTerm::new("a", 0..10)]
+			}
+		}
+		assert!(tokenize(&Broken, "abc".to_string()).is_err());
+	}
+}
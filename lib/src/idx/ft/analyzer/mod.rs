@@ -1,4 +1,5 @@
 use crate::err::Error;
+use crate::idx::ft::analyzer::custom::Analyzer as CustomAnalyzer;
 use crate::idx::ft::analyzer::tokenizer::{Tokenizer, Tokens};
 use crate::idx::ft::doclength::DocLength;
 use crate::idx::ft::offsets::{Offset, OffsetRecords};
@@ -11,7 +12,9 @@ use crate::sql::Value;
 use filter::Filter;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
+pub(crate) mod custom;
 mod filter;
 mod tokenizer;
 
@@ -21,9 +24,10 @@ impl Analyzers {
 	pub(crate) const LIKE: &'static str = "like";
 }
 
-pub(super) struct Analyzer {
+pub(crate) struct Analyzer {
 	t: Option<Vec<SqlTokenizer>>,
 	f: Option<Vec<Filter>>,
+	c: Option<Arc<dyn CustomAnalyzer>>,
 }
 
 impl From<DefineAnalyzerStatement> for Analyzer {
@@ -31,11 +35,30 @@ impl From<DefineAnalyzerStatement> for Analyzer {
 		Self {
 			t: az.tokenizers,
 			f: Filter::from(az.filters),
+			c: None,
 		}
 	}
 }
 
 impl Analyzer {
+	/// Loads an analyzer by name. An analyzer which is registered with the datastore takes
+	/// precedence over an analyzer which is defined in the database.
+	pub(crate) async fn load(
+		tx: &mut Transaction,
+		ns: &str,
+		db: &str,
+		az: &str,
+	) -> Result<Self, Error> {
+		if let Some(c) = tx.get_custom_analyzer(az) {
+			return Ok(Self {
+				t: None,
+				f: None,
+				c: Some(c),
+			});
+		}
+		Ok(tx.get_db_analyzer(ns, db, az).await?.into())
+	}
+
 	pub(super) async fn extract_terms(
 		&self,
 		t: &Terms,
@@ -163,6 +186,9 @@ impl Analyzer {
 	}
 
 	fn analyze(&self, input: String) -> Result<Tokens, Error> {
+		if let Some(c) = &self.c {
+			return custom::tokenize(c.as_ref(), input);
+		}
 		if let Some(t) = &self.t {
 			if !input.is_empty() {
 				let t = Tokenizer::tokenize(t, input);
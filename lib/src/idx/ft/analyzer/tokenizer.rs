@@ -18,6 +18,13 @@ impl Tokens {
 		}
 	}
 
+	pub(super) fn with_tokens(i: String, t: Vec<Token>) -> Self {
+		Self {
+			i,
+			t,
+		}
+	}
+
 	pub(super) fn get_token_string<'a>(&'a self, t: &'a Token) -> Result<&str, Error> {
 		t.get_str(&self.i)
 	}
@@ -23,7 +23,6 @@ use crate::idx::{IndexKeyBase, VersionedSerdeState};
 use crate::kvs::{Key, Transaction};
 use crate::sql::index::SearchParams;
 use crate::sql::scoring::Scoring;
-use crate::sql::statements::DefineAnalyzerStatement;
 use crate::sql::{Idiom, Object, Thing, Value};
 use revision::revisioned;
 use roaring::treemap::IntoIter;
@@ -66,6 +65,8 @@ impl Default for Bm25Params {
 }
 
 pub(crate) struct FtStatistics {
+	doc_count: u64,
+	total_docs_lengths: u128,
 	doc_ids: BStatistics,
 	terms: BStatistics,
 	doc_lengths: BStatistics,
@@ -75,6 +76,8 @@ pub(crate) struct FtStatistics {
 impl From<FtStatistics> for Value {
 	fn from(stats: FtStatistics) -> Self {
 		let mut res = Object::default();
+		res.insert("doc_count".to_owned(), Value::from(stats.doc_count));
+		res.insert("total_docs_lengths".to_owned(), Value::from(stats.total_docs_lengths));
 		res.insert("doc_ids".to_owned(), Value::from(stats.doc_ids));
 		res.insert("terms".to_owned(), Value::from(stats.terms));
 		res.insert("doc_lengths".to_owned(), Value::from(stats.doc_lengths));
@@ -95,7 +98,7 @@ impl VersionedSerdeState for State {}
 impl FtIndex {
 	pub(crate) async fn new(
 		tx: &mut Transaction,
-		az: DefineAnalyzerStatement,
+		az: Analyzer,
 		index_key_base: IndexKeyBase,
 		p: &SearchParams,
 		store_type: TreeStoreType,
@@ -137,7 +140,7 @@ impl FtIndex {
 			index_key_base,
 			bm25,
 			highlighting: p.hl,
-			analyzer: az.into(),
+			analyzer: az,
 			doc_ids,
 			doc_lengths,
 			postings,
@@ -408,6 +411,8 @@ impl FtIndex {
 	pub(crate) async fn statistics(&self, tx: &mut Transaction) -> Result<FtStatistics, Error> {
 		// TODO do parallel execution
 		Ok(FtStatistics {
+			doc_count: self.state.doc_count,
+			total_docs_lengths: self.state.total_docs_lengths,
 			doc_ids: self.doc_ids.read().await.statistics(tx).await?,
 			terms: self.terms.read().await.statistics(tx).await?,
 			doc_lengths: self.doc_lengths.read().await.statistics(tx).await?,
@@ -510,7 +515,7 @@ mod tests {
 		let mut tx = ds.transaction(write, false).await.unwrap();
 		let fti = FtIndex::new(
 			&mut tx,
-			az.clone(),
+			az.clone().into(),
 			IndexKeyBase::default(),
 			&SearchParams {
 				az: az.name.clone(),
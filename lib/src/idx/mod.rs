@@ -1,8 +1,12 @@
+pub(crate) mod dedup;
 pub(crate) mod docids;
 pub(crate) mod ft;
 pub(crate) mod planner;
+pub(crate) mod spatial;
 pub mod trees;
 
+pub use self::ft::analyzer::custom::{AnalyzedTerm, Analyzer};
+
 use crate::dbs::Options;
 use crate::err::Error;
 use crate::idx::docids::DocId;
@@ -19,6 +23,7 @@ use crate::key::index::bp::Bp;
 use crate::key::index::bs::Bs;
 use crate::key::index::bt::Bt;
 use crate::key::index::bu::Bu;
+use crate::key::index::hn::Hn;
 use crate::key::index::vm::Vm;
 use crate::kvs::{Key, Val};
 use crate::sql::statements::DefineIndexStatement;
@@ -174,6 +179,17 @@ impl IndexKeyBase {
 		.into()
 	}
 
+	fn new_hn_key(&self, doc_id: Option<DocId>) -> Key {
+		Hn::new(
+			self.inner.ns.as_str(),
+			self.inner.db.as_str(),
+			self.inner.tb.as_str(),
+			self.inner.ix.as_str(),
+			doc_id,
+		)
+		.into()
+	}
+
 	fn new_vm_key(&self, node_id: Option<NodeId>) -> Key {
 		Vm::new(
 			self.inner.ns.as_str(),
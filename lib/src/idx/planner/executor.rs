@@ -1,23 +1,26 @@
 use crate::dbs::{Options, Transaction};
 use crate::err::Error;
 use crate::idx::docids::{DocId, DocIds};
+use crate::idx::ft::analyzer::Analyzer;
 use crate::idx::ft::scorer::BM25Scorer;
 use crate::idx::ft::termdocs::TermsDocs;
 use crate::idx::ft::terms::TermId;
 use crate::idx::ft::{FtIndex, MatchRef};
 use crate::idx::planner::iterators::{
 	IndexEqualThingIterator, IndexRangeThingIterator, KnnThingIterator, MatchesThingIterator,
-	ThingIterator, UniqueEqualThingIterator, UniqueRangeThingIterator,
+	SpatialThingIterator, ThingIterator, UniqueEqualThingIterator, UniqueRangeThingIterator,
 };
 use crate::idx::planner::plan::IndexOperator::Matches;
 use crate::idx::planner::plan::{IndexOperator, IndexOption, RangeValue};
 use crate::idx::planner::tree::{IndexMap, IndexRef};
+use crate::idx::spatial::SpatialIndex;
+use crate::idx::trees::hnsw::HnswIndex;
 use crate::idx::trees::mtree::MTreeIndex;
 use crate::idx::trees::store::TreeStoreType;
 use crate::idx::IndexKeyBase;
 use crate::kvs;
 use crate::kvs::Key;
-use crate::sql::index::Index;
+use crate::sql::index::{Index, SpatialParams};
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::{Array, Expression, Object, Table, Thing, Value};
 use roaring::RoaringTreemap;
@@ -70,6 +73,7 @@ impl QueryExecutor {
 		let mut exp_entries = HashMap::default();
 		let mut ft_map = HashMap::default();
 		let mut mt_map: HashMap<IndexRef, MTreeIndex> = HashMap::default();
+		let mut hn_map: HashMap<IndexRef, HnswIndex> = HashMap::default();
 		let mut mt_exp = HashMap::default();
 
 		// Create all the instances of FtIndex
@@ -86,7 +90,8 @@ impl QueryExecutor {
 							}
 						} else {
 							let ikb = IndexKeyBase::new(opt, idx_def);
-							let az = run.get_db_analyzer(opt.ns(), opt.db(), p.az.as_str()).await?;
+							let az =
+								Analyzer::load(&mut run, opt.ns(), opt.db(), p.az.as_str()).await?;
 							let ft =
 								FtIndex::new(&mut run, az, ikb, p, TreeStoreType::Read).await?;
 							if ft_entry.is_none() {
@@ -120,6 +125,21 @@ impl QueryExecutor {
 							mt_exp.insert(exp, entry);
 						}
 					}
+					Index::Hnsw(p) => {
+						if let IndexOperator::Knn(a, k) = io.op() {
+							let entry = if let Some(hn) = hn_map.get(&ir) {
+								MtEntry::hnsw(&mut run, hn, a.clone(), *k).await?
+							} else {
+								let ikb = IndexKeyBase::new(opt, idx_def);
+								let hn =
+									HnswIndex::new(&mut run, ikb, p, TreeStoreType::Read).await?;
+								let entry = MtEntry::hnsw(&mut run, &hn, a.clone(), *k).await?;
+								hn_map.insert(ir, hn);
+								entry
+							};
+							mt_exp.insert(exp, entry);
+						}
+					}
 					_ => {}
 				}
 			}
@@ -204,7 +224,13 @@ impl QueryExecutor {
 							Index::Search {
 								..
 							} => self.new_search_index_iterator(ir, io.clone()).await,
-							Index::MTree(_) => Ok(self.new_mtree_index_knn_iterator(ir)),
+							Index::MTree(_) | Index::Hnsw(_) => {
+								Ok(self.new_mtree_index_knn_iterator(ir))
+							}
+							Index::SimHash(_) => Ok(None),
+							Index::Spatial(ref p) => {
+								Ok(Self::new_spatial_index_iterator(opt, ix, p, io.clone()))
+							}
 						}
 					} else {
 						Ok(None)
@@ -274,6 +300,19 @@ impl QueryExecutor {
 		}
 	}
 
+	fn new_spatial_index_iterator(
+		opt: &Options,
+		ix: &DefineIndexStatement,
+		p: &SpatialParams,
+		io: IndexOption,
+	) -> Option<ThingIterator> {
+		if let IndexOperator::Spatial(_, Value::Geometry(g)) = io.op() {
+			let ranges = SpatialIndex::new(opt, ix, p).key_ranges(g);
+			return Some(ThingIterator::Spatial(SpatialThingIterator::new(ranges)));
+		}
+		None
+	}
+
 	async fn new_search_index_iterator(
 		&self,
 		ir: IteratorRef,
@@ -470,4 +509,17 @@ impl MtEntry {
 			doc_ids: mt.doc_ids(),
 		})
 	}
+
+	async fn hnsw(
+		tx: &mut kvs::Transaction,
+		hn: &HnswIndex,
+		a: Array,
+		k: u32,
+	) -> Result<Self, Error> {
+		let res = hn.knn_search(tx, a, k as usize).await?;
+		Ok(Self {
+			res,
+			doc_ids: hn.doc_ids(),
+		})
+	}
 }
@@ -9,7 +9,7 @@ use crate::kvs::Key;
 use crate::sql::statements::DefineIndexStatement;
 use crate::sql::{Array, Thing, Value};
 use roaring::RoaringTreemap;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -20,6 +20,7 @@ pub(crate) enum ThingIterator {
 	UniqueRange(UniqueRangeThingIterator),
 	Matches(MatchesThingIterator),
 	Knn(KnnThingIterator),
+	Spatial(SpatialThingIterator),
 }
 
 impl ThingIterator {
@@ -35,6 +36,7 @@ impl ThingIterator {
 			ThingIterator::UniqueRange(i) => i.next_batch(tx, size).await,
 			ThingIterator::Matches(i) => i.next_batch(tx, size).await,
 			ThingIterator::Knn(i) => i.next_batch(tx, size).await,
+			ThingIterator::Spatial(i) => i.next_batch(tx, size).await,
 		}
 	}
 }
@@ -362,3 +364,47 @@ impl KnnThingIterator {
 		Ok(res)
 	}
 }
+
+pub(crate) struct SpatialThingIterator {
+	ranges: VecDeque<(Key, Key)>,
+	seen: HashSet<Thing>,
+}
+
+impl SpatialThingIterator {
+	pub(super) fn new(ranges: Vec<(Key, Key)>) -> Self {
+		Self {
+			ranges: ranges.into(),
+			seen: HashSet::new(),
+		}
+	}
+
+	async fn next_batch(
+		&mut self,
+		txn: &Transaction,
+		limit: u32,
+	) -> Result<Vec<(Thing, DocId)>, Error> {
+		let mut tx = txn.lock().await;
+		while let Some((beg, end)) = self.ranges.front_mut() {
+			let res = tx.scan(beg.clone()..end.clone(), limit).await?;
+			if let Some((key, _)) = res.last() {
+				*beg = key.clone();
+				beg.push(0x00);
+			}
+			if res.len() < limit as usize {
+				self.ranges.pop_front();
+			}
+			// A geometry may be stored under several of the scanned cells
+			let mut r = Vec::with_capacity(res.len());
+			for (_, val) in res {
+				let rid: Thing = val.into();
+				if self.seen.insert(rid.clone()) {
+					r.push((rid, NO_DOC_ID));
+				}
+			}
+			if !r.is_empty() {
+				return Ok(r);
+			}
+		}
+		Ok(vec![])
+	}
+}
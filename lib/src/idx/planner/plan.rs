@@ -150,6 +150,7 @@ pub(super) enum IndexOperator {
 	RangePart(Operator, Value),
 	Matches(String, Option<MatchRef>),
 	Knn(Array, u32),
+	Spatial(Operator, Value),
 }
 
 impl IndexOption {
@@ -196,6 +197,10 @@ impl IndexOption {
 				e.insert("operator", Value::from(format!("<{}>", k)));
 				e.insert("value", Value::Array(a.clone()));
 			}
+			IndexOperator::Spatial(op, v) => {
+				e.insert("operator", Value::from(op.to_string()));
+				e.insert("value", v.to_owned());
+			}
 		};
 	}
 }
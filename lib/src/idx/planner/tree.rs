@@ -4,7 +4,9 @@ use crate::err::Error;
 use crate::idx::planner::plan::{IndexOperator, IndexOption};
 use crate::sql::index::Index;
 use crate::sql::statements::DefineIndexStatement;
-use crate::sql::{Array, Cond, Expression, Idiom, Operator, Subquery, Table, Value, With};
+use crate::sql::{
+	Array, Cond, Expression, Function, Idiom, Operator, Subquery, Table, Value, With,
+};
 use async_recursion::async_recursion;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -63,13 +65,15 @@ impl<'a> TreeBuilder<'a> {
 			return Ok(irs.clone());
 		}
 		if self.indexes.is_none() {
-			let indexes = self
-				.txn
-				.clone()
-				.lock()
-				.await
-				.all_tb_indexes(self.opt.ns(), self.opt.db(), &self.table.0)
-				.await?;
+			let mut run = self.txn.lock().await;
+			let indexes = run.all_tb_indexes(self.opt.ns(), self.opt.db(), &self.table.0).await?;
+			let rebuilds = run.all_tb_rebuilds(self.opt.ns(), self.opt.db(), &self.table.0).await?;
+			// An index which is being rebuilt is incomplete, so it is not used until it is ready
+			let indexes = indexes
+				.iter()
+				.filter(|ix| !rebuilds.iter().any(|rb| rb.index == ix.name))
+				.cloned()
+				.collect();
 			self.indexes = Some(indexes);
 		}
 		let mut irs = Vec::new();
@@ -102,11 +106,14 @@ impl<'a> TreeBuilder<'a> {
 		match v {
 			Value::Expression(e) => self.eval_expression(e).await,
 			Value::Idiom(i) => self.eval_idiom(i).await,
-			Value::Strand(_) | Value::Number(_) | Value::Bool(_) | Value::Thing(_) => {
-				Ok(Node::Scalar(v.to_owned()))
-			}
+			Value::Strand(_)
+			| Value::Number(_)
+			| Value::Bool(_)
+			| Value::Thing(_)
+			| Value::Geometry(_) => Ok(Node::Scalar(v.to_owned())),
 			Value::Array(a) => Ok(self.eval_array(a)),
 			Value::Subquery(s) => self.eval_subquery(s).await,
+			Value::Function(f) => self.eval_function(f).await,
 			Value::Param(p) => {
 				let v = p.compute(self.ctx, self.opt, self.txn, None).await?;
 				self.eval_value(&v).await
@@ -196,7 +203,9 @@ impl<'a> TreeBuilder<'a> {
 							None
 						}
 					}
-					Index::MTree(_) => {
+					Index::SimHash(_) => None,
+					Index::Spatial(_) => Self::eval_spatial_operator(op, n),
+					Index::MTree(_) | Index::Hnsw(_) => {
 						if let Operator::Knn(k) = op {
 							if let Node::Vector(a) = n {
 								Some(IndexOperator::Knn(a.clone(), *k))
@@ -233,6 +242,53 @@ impl<'a> TreeBuilder<'a> {
 		}
 	}
 
+	fn eval_spatial_operator(op: &Operator, n: &Node) -> Option<IndexOperator> {
+		// Every one of these operators requires the geometries to intersect
+		if let Some(v @ Value::Geometry(_)) = n.is_scalar() {
+			match op {
+				Operator::Inside | Operator::Intersects | Operator::Contain => {
+					Some(IndexOperator::Spatial(op.clone(), v.clone()))
+				}
+				_ => None,
+			}
+		} else {
+			None
+		}
+	}
+
+	async fn eval_function(&mut self, f: &Function) -> Result<Node, Error> {
+		if let Function::Normal(name, args) = f {
+			match (name.as_str(), args.as_slice()) {
+				// The spatial functions are planned as the operators which they are equivalent to
+				("geo::intersects", [l, r]) => {
+					return self.eval_expression(&Self::binary(l, Operator::Intersects, r)).await;
+				}
+				("geo::contains", [l, r]) => {
+					return self.eval_expression(&Self::binary(l, Operator::Contain, r)).await;
+				}
+				// A bounding box of a constant geometry is a constant geometry
+				("geo::bbox", [Value::Geometry(_) | Value::Param(_)]) => {
+					let v = Value::Function(Box::new(f.clone()));
+					if let Ok(v @ Value::Geometry(_)) =
+						v.compute(self.ctx, self.opt, self.txn, None).await
+					{
+						return Ok(Node::Scalar(v));
+					}
+				}
+				_ => {}
+			}
+		}
+		Ok(Node::Unsupported(format!("Unsupported function: {}", f)))
+	}
+
+	fn binary(l: &Value, o: Operator, r: &Value) -> Expression {
+		Expression::Binary {
+			l: l.clone(),
+			o,
+			r: r.clone(),
+		}
+	}
+
 	async fn eval_subquery(&mut self, s: &Subquery) -> Result<Node, Error> {
 		match s {
 			Subquery::Value(v) => self.eval_value(v).await,
@@ -0,0 +1,263 @@
+//! The SPATIAL index, which finds the records whose geometries may intersect a geometry
+//!
+//! The plane of longitudes and latitudes is divided into a hierarchy of cells, where each
+//! cell is split into four cells on the next level, down to the finest level of the index.
+//! Each geometry is stored under the few cells of the finest level possible which cover its
+//! bounding box. The ids of the cells follow a Z-order curve, so that a cell and all the
+//! cells inside it form a single range of ids, which is scanned at once.
+use crate::dbs::Options;
+use crate::err::Error;
+use crate::kvs;
+use crate::kvs::Key;
+use crate::sql::index::SpatialParams;
+use crate::sql::statements::DefineIndexStatement;
+use crate::sql::{Array, Geometry, Thing, Value};
+use geo::BoundingRect;
+use std::collections::BTreeSet;
+
+/// The finest level which cells can be divided into
+pub(crate) const MAX_LEVEL: u8 = 30;
+
+/// The maximum number of cells which cover the bounding box of a query
+const MAX_COVERING: u64 = 4;
+
+/// The position of a coordinate on the grid of the cells of the finest level
+fn leaf(x: f64, y: f64) -> (u32, u32) {
+	let scale = (1u64 << MAX_LEVEL) as f64;
+	// Coordinates outside of the range of longitudes and latitudes are kept on its edges
+	let x = ((x + 180.0) / 360.0 * scale).clamp(0.0, scale - 1.0);
+	let y = ((y + 90.0) / 180.0 * scale).clamp(0.0, scale - 1.0);
+	(x as u32, y as u32)
+}
+
+/// Interleaves the bits of both positions, so that nearby positions have nearby values
+fn interleave(x: u32, y: u32) -> u64 {
+	fn spread(v: u32) -> u64 {
+		let mut v = v as u64;
+		v = (v | (v << 16)) & 0x0000_ffff_0000_ffff;
+		v = (v | (v << 8)) & 0x00ff_00ff_00ff_00ff;
+		v = (v | (v << 4)) & 0x0f0f_0f0f_0f0f_0f0f;
+		v = (v | (v << 2)) & 0x3333_3333_3333_3333;
+		v = (v | (v << 1)) & 0x5555_5555_5555_5555;
+		v
+	}
+	spread(x) | (spread(y) << 1)
+}
+
+/// The id of the cell of a level which contains a position
+///
+/// The id is made of the position of the cell, followed by a single set bit which marks its
+/// level, so that the ids of the cells inside it lie between [`first`] and [`last`].
+fn cell(x: u32, y: u32, level: u8) -> u64 {
+	let shift = 2 * (MAX_LEVEL - level) as u32;
+	let pos = interleave(x, y) >> shift;
+	((pos << 1) | 1) << shift
+}
+
+/// The smallest id of a cell inside a cell
+fn first(cell: u64) -> u64 {
+	cell - (cell & cell.wrapping_neg()) + 1
+}
+
+/// The largest id of a cell inside a cell
+fn last(cell: u64) -> u64 {
+	cell + (cell & cell.wrapping_neg()) - 1
+}
+
+/// The bounding box of a geometry, as the positions of its corners on the grid
+fn bounds(geometry: &Geometry) -> Option<((u32, u32), (u32, u32))> {
+	let rect = geo::Geometry::from(geometry.clone()).bounding_rect()?;
+	Some((leaf(rect.min().x, rect.min().y), leaf(rect.max().x, rect.max().y)))
+}
+
+/// The level and the positions of the few cells which cover the bounding box of a geometry
+///
+/// The cells are of the finest level, down to the level of the index, at which the bounding
+/// box is covered by no more than a few cells.
+fn covering(geometry: &Geometry, level: u8) -> Option<(u8, Vec<(u32, u32)>)> {
+	let ((x0, y0), (x1, y1)) = bounds(geometry)?;
+	let level = (0..=level.min(MAX_LEVEL))
+		.rev()
+		.find(|l| {
+			let shift = MAX_LEVEL - l;
+			let cols = ((x1 >> shift) - (x0 >> shift)) as u64 + 1;
+			let rows = ((y1 >> shift) - (y0 >> shift)) as u64 + 1;
+			cols * rows <= MAX_COVERING
+		})
+		.unwrap_or(0);
+	let shift = MAX_LEVEL - level;
+	let mut cells = Vec::new();
+	for cx in (x0 >> shift)..=(x1 >> shift) {
+		for cy in (y0 >> shift)..=(y1 >> shift) {
+			cells.push((cx << shift, cy << shift));
+		}
+	}
+	Some((level, cells))
+}
+
+/// The cells which a geometry is stored under
+fn cells(geometry: &Geometry, level: u8) -> Vec<u64> {
+	match covering(geometry, level) {
+		Some((level, cells)) => cells.into_iter().map(|(x, y)| cell(x, y, level)).collect(),
+		None => vec![],
+	}
+}
+
+/// The ranges of cells which hold the geometries that may intersect a geometry
+///
+/// Geometries whose bounding boxes intersect the one of the geometry share at least one
+/// position with it. They are either stored in cells inside the cells which cover the
+/// geometry, or in the larger cells which contain them.
+fn ranges(geometry: &Geometry, level: u8) -> Vec<(u64, u64)> {
+	let Some((level, cells)) = covering(geometry, level) else {
+		return vec![];
+	};
+	let mut ranges = Vec::new();
+	let mut parents = BTreeSet::new();
+	for (x, y) in cells {
+		let c = cell(x, y, level);
+		ranges.push((first(c), last(c)));
+		parents.extend((0..level).map(|l| cell(x, y, l)));
+	}
+	ranges.extend(parents.into_iter().map(|c| (c, c)));
+	ranges
+}
+
+/// The geometries of the indexed values, including the ones in arrays
+fn geometries(values: &[Value]) -> impl Iterator<Item = &Geometry> {
+	values.iter().flat_map(|v| match v {
+		Value::Geometry(g) => vec![g],
+		Value::Array(a) => a
+			.iter()
+			.filter_map(|v| match v {
+				Value::Geometry(g) => Some(g),
+				_ => None,
+			})
+			.collect(),
+		_ => vec![],
+	})
+}
+
+pub(crate) struct SpatialIndex<'a> {
+	opt: &'a Options,
+	ix: &'a DefineIndexStatement,
+	level: u8,
+}
+
+impl<'a> SpatialIndex<'a> {
+	pub(crate) fn new(opt: &'a Options, ix: &'a DefineIndexStatement, p: &SpatialParams) -> Self {
+		Self {
+			opt,
+			ix,
+			level: p.level,
+		}
+	}
+
+	fn key(&self, cell: u64, rid: &Thing) -> Key {
+		let fd = Array(vec![Value::from(cell as i64)]);
+		crate::key::index::Index::new(
+			self.opt.ns(),
+			self.opt.db(),
+			&self.ix.what,
+			&self.ix.name,
+			&fd,
+			Some(&rid.id),
+		)
+		.into()
+	}
+
+	pub(crate) async fn remove_document(
+		&self,
+		run: &mut kvs::Transaction,
+		rid: &Thing,
+		values: Vec<Value>,
+	) -> Result<(), Error> {
+		for g in geometries(&values) {
+			for cell in cells(g, self.level) {
+				run.del(self.key(cell, rid)).await?;
+			}
+		}
+		Ok(())
+	}
+
+	pub(crate) async fn index_document(
+		&self,
+		run: &mut kvs::Transaction,
+		rid: &Thing,
+		values: Vec<Value>,
+	) -> Result<(), Error> {
+		for g in geometries(&values) {
+			for cell in cells(g, self.level) {
+				run.set(self.key(cell, rid), rid).await?;
+			}
+		}
+		Ok(())
+	}
+
+	/// The key ranges to scan for the records whose geometries may intersect a geometry
+	pub(crate) fn key_ranges(&self, geometry: &Geometry) -> Vec<(Key, Key)> {
+		let (ns, db, tb, ix) = (self.opt.ns(), self.opt.db(), &self.ix.what, &self.ix.name);
+		ranges(geometry, self.level)
+			.into_iter()
+			.map(|(beg, end)| {
+				let beg = Array(vec![Value::from(beg as i64)]);
+				let end = Array(vec![Value::from(end as i64)]);
+				(
+					crate::key::index::Index::prefix_ids_beg(ns, db, tb, ix, &beg),
+					crate::key::index::Index::prefix_ids_end(ns, db, tb, ix, &end),
+				)
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use geo::{Point, Rect};
+
+	#[test]
+	fn cells_contain_their_children() {
+		let (x, y) = leaf(-0.118092, 51.509865);
+		let mut parent = cell(x, y, 0);
+		assert_eq!(parent, 1 << 60);
+		for level in 1..=MAX_LEVEL {
+			let child = cell(x, y, level);
+			assert!(first(parent) <= child && child <= last(parent));
+			assert!(first(parent) <= first(child) && last(child) <= last(parent));
+			parent = child;
+		}
+		assert_eq!(first(parent), parent);
+		assert_eq!(last(parent), parent);
+	}
+
+	#[test]
+	fn ranges_find_intersecting_geometries() {
+		let london = Geometry::from(Point::new(-0.118092, 51.509865));
+		let paris = Geometry::from(Point::new(2.352222, 48.856613));
+		let england = Geometry::from(Rect::new((-5.7, 49.9), (1.8, 55.8)));
+		let europe = Geometry::from(Rect::new((-25.0, 34.0), (45.0, 72.0)));
+		let stored =
+			[("london", &london), ("paris", &paris), ("england", &england), ("europe", &europe)];
+		let search = |query: &Geometry| {
+			let ranges = ranges(query, 20);
+			stored
+				.iter()
+				.filter(|(_, g)| {
+					cells(g, 20).into_iter().any(|c| ranges.iter().any(|(b, e)| *b <= c && c <= *e))
+				})
+				.map(|(name, _)| *name)
+				.collect::<Vec<_>>()
+		};
+		// Every geometry whose bounding box intersects the query is found
+		let found = search(&Geometry::from(Rect::new((-1.0, 51.0), (0.0, 52.0))));
+		assert_eq!(found, ["london", "england", "europe"]);
+		let found = search(&london);
+		assert_eq!(found, ["london", "england", "europe"]);
+		let found = search(&paris);
+		assert!(found.contains(&"paris") && found.contains(&"europe"), "{found:?}");
+		assert!(!found.contains(&"london"), "{found:?}");
+		// Geometries which are far from the query are skipped
+		assert!(search(&Geometry::from(Point::new(-120.0, -40.0))).is_empty());
+	}
+}
@@ -0,0 +1,514 @@
+use crate::err::Error;
+use crate::fnc::util::math::vector::{
+	CosineSimilarity, EuclideanDistance, HammingDistance, ManhattanDistance, MinkowskiDistance,
+};
+use crate::idx::docids::{DocId, DocIds};
+use crate::idx::trees::mtree::Vector;
+use crate::idx::trees::store::TreeStoreType;
+use crate::idx::{IndexKeyBase, VersionedSerdeState};
+use crate::key::index::hn::Hn;
+use crate::kvs::{Key, Transaction};
+use crate::sql::index::{Distance, HnswParams};
+use crate::sql::{Array, Thing, Value};
+use rand::Rng;
+use revision::revisioned;
+use roaring::RoaringTreemap;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// The highest layer a node can be inserted at
+const MAX_LEVEL: usize = 16;
+
+// https://arxiv.org/pdf/1603.09320.pdf
+pub(crate) struct HnswIndex {
+	state_key: Key,
+	ikb: IndexKeyBase,
+	dim: usize,
+	distance: Distance,
+	m: usize,
+	ef_construction: usize,
+	// The normalization factor of the level generation
+	ml: f64,
+	doc_ids: Arc<RwLock<DocIds>>,
+	state: HnswState,
+	updated: bool,
+}
+
+impl HnswIndex {
+	pub(crate) async fn new(
+		tx: &mut Transaction,
+		ikb: IndexKeyBase,
+		p: &HnswParams,
+		st: TreeStoreType,
+	) -> Result<Self, Error> {
+		let doc_ids =
+			Arc::new(RwLock::new(DocIds::new(tx, ikb.clone(), p.doc_ids_order, st).await?));
+		let state_key = ikb.new_hn_key(None);
+		let state: HnswState = if let Some(val) = tx.get(state_key.clone()).await? {
+			HnswState::try_from_val(val)?
+		} else {
+			HnswState::default()
+		};
+		let m = p.m.max(2) as usize;
+		Ok(Self {
+			state_key,
+			ikb,
+			dim: p.dimension as usize,
+			distance: p.distance.clone(),
+			m,
+			ef_construction: (p.ef_construction as usize).max(m),
+			ml: 1.0 / (m as f64).ln(),
+			doc_ids,
+			state,
+			updated: false,
+		})
+	}
+
+	pub(crate) async fn index_document(
+		&mut self,
+		tx: &mut Transaction,
+		rid: &Thing,
+		content: Vec<Value>,
+	) -> Result<(), Error> {
+		// Resolve the doc_id
+		let resolved = self.doc_ids.write().await.resolve_doc_id(tx, rid.into()).await?;
+		let doc_id = *resolved.doc_id();
+		if resolved.was_existing() {
+			self.remove(tx, doc_id).await?;
+		}
+		// A record is a single node of the graph, so only the first field is indexed
+		if let Some(v) = content.into_iter().next() {
+			let vector = self.check_vector_value(v)?;
+			self.insert(tx, doc_id, vector).await?;
+		}
+		Ok(())
+	}
+
+	pub(crate) async fn remove_document(
+		&mut self,
+		tx: &mut Transaction,
+		rid: &Thing,
+	) -> Result<(), Error> {
+		if let Some(doc_id) = self.doc_ids.write().await.remove_doc(tx, rid.into()).await? {
+			self.remove(tx, doc_id).await?;
+		}
+		Ok(())
+	}
+
+	pub(crate) async fn knn_search(
+		&self,
+		tx: &mut Transaction,
+		a: Array,
+		k: usize,
+	) -> Result<VecDeque<RoaringTreemap>, Error> {
+		// Extract the vector
+		let vector = self.check_vector_array(a)?;
+		let found = self.search(tx, &vector, k.max(self.ef_construction)).await?;
+		Ok(found
+			.into_iter()
+			.take(k)
+			.map(|c| {
+				let mut docs = RoaringTreemap::new();
+				docs.insert(c.1);
+				docs
+			})
+			.collect())
+	}
+
+	pub(in crate::idx) fn doc_ids(&self) -> Arc<RwLock<DocIds>> {
+		self.doc_ids.clone()
+	}
+
+	pub(crate) async fn finish(self, tx: &mut Transaction) -> Result<(), Error> {
+		self.doc_ids.write().await.finish(tx).await?;
+		if self.updated {
+			tx.set(self.state_key, self.state.try_to_val()?).await?;
+		}
+		Ok(())
+	}
+
+	fn check_vector_array(&self, a: Array) -> Result<Vector, Error> {
+		if a.0.len() != self.dim {
+			return Err(Error::InvalidVectorDimension {
+				current: a.0.len(),
+				expected: self.dim,
+			});
+		}
+		let mut vec = Vec::with_capacity(a.len());
+		for v in a.0 {
+			if let Value::Number(n) = v {
+				vec.push(n);
+			} else {
+				return Err(Error::InvalidVectorType {
+					current: v.clone().to_string(),
+					expected: "Number",
+				});
+			}
+		}
+		Ok(vec)
+	}
+
+	fn check_vector_value(&self, v: Value) -> Result<Vector, Error> {
+		if let Value::Array(a) = v {
+			self.check_vector_array(a)
+		} else {
+			Err(Error::InvalidVectorValue {
+				current: v.clone().to_raw_string(),
+			})
+		}
+	}
+
+	fn calculate_distance(&self, v1: &Vector, v2: &Vector) -> f64 {
+		match &self.distance {
+			Distance::Euclidean => v1.euclidean_distance(v2).unwrap().as_float(),
+			Distance::Manhattan => v1.manhattan_distance(v2).unwrap().as_float(),
+			// The graph is navigated towards the nearest nodes, so the similarity is inverted
+			Distance::Cosine => 1.0 - v1.cosine_similarity(v2).unwrap().as_float(),
+			Distance::Hamming => v1.hamming_distance(v2).unwrap().as_float(),
+			Distance::Mahalanobis => v1.manhattan_distance(v2).unwrap().as_float(),
+			Distance::Minkowski(order) => v1.minkowski_distance(v2, order).unwrap().as_float(),
+		}
+	}
+
+	/// The maximum number of neighbours of a node on a layer
+	fn max_links(&self, layer: usize) -> usize {
+		if layer == 0 {
+			self.m * 2
+		} else {
+			self.m
+		}
+	}
+
+	fn random_level(&self) -> usize {
+		let u: f64 = rand::thread_rng().gen_range(f64::MIN_POSITIVE..1.0);
+		((-u.ln() * self.ml) as usize).min(MAX_LEVEL)
+	}
+
+	async fn get_node(&self, tx: &mut Transaction, id: DocId) -> Result<Option<HnswNode>, Error> {
+		match tx.get(self.ikb.new_hn_key(Some(id))).await? {
+			Some(val) => Ok(Some(HnswNode::try_from_val(val)?)),
+			None => Ok(None),
+		}
+	}
+
+	async fn set_node(
+		&self,
+		tx: &mut Transaction,
+		id: DocId,
+		node: &HnswNode,
+	) -> Result<(), Error> {
+		tx.set(self.ikb.new_hn_key(Some(id)), node.try_to_val()?).await
+	}
+
+	/// Returns the `ef` nearest nodes of the bottom layer, nearest first
+	async fn search(
+		&self,
+		tx: &mut Transaction,
+		q: &Vector,
+		ef: usize,
+	) -> Result<Vec<Candidate>, Error> {
+		let Some(entry) = self.state.entry else {
+			return Ok(vec![]);
+		};
+		let Some(e) = self.get_node(tx, entry).await? else {
+			return Err(Error::CorruptedIndex);
+		};
+		let mut eps = vec![Candidate(self.calculate_distance(&e.vector, q), entry)];
+		// Greedily descend to the bottom layer
+		for l in (1..=self.state.level as usize).rev() {
+			eps = self.search_layer(tx, q, &eps, 1, l).await?;
+		}
+		self.search_layer(tx, q, &eps, ef, 0).await
+	}
+
+	/// Returns the `ef` nearest nodes of a layer reachable from the entry points, nearest first
+	async fn search_layer(
+		&self,
+		tx: &mut Transaction,
+		q: &Vector,
+		eps: &[Candidate],
+		ef: usize,
+		layer: usize,
+	) -> Result<Vec<Candidate>, Error> {
+		let mut visited: HashSet<DocId> = eps.iter().map(|c| c.1).collect();
+		let mut candidates: BinaryHeap<Reverse<Candidate>> =
+			eps.iter().copied().map(Reverse).collect();
+		let mut found: BinaryHeap<Candidate> = eps.iter().copied().collect();
+		while let Some(Reverse(c)) = candidates.pop() {
+			if found.len() >= ef && found.peek().map_or(false, |f| c.0 > f.0) {
+				break;
+			}
+			let Some(node) = self.get_node(tx, c.1).await? else {
+				continue;
+			};
+			let Some(links) = node.layers.get(layer) else {
+				continue;
+			};
+			for &id in links {
+				if !visited.insert(id) {
+					continue;
+				}
+				// A link may still point to a node which has been removed
+				let Some(n) = self.get_node(tx, id).await? else {
+					continue;
+				};
+				let d = self.calculate_distance(&n.vector, q);
+				if found.len() < ef || found.peek().map_or(true, |f| d < f.0) {
+					candidates.push(Reverse(Candidate(d, id)));
+					found.push(Candidate(d, id));
+					if found.len() > ef {
+						found.pop();
+					}
+				}
+			}
+		}
+		Ok(found.into_sorted_vec())
+	}
+
+	/// Keeps the `max` nodes nearest to a vector
+	async fn select(
+		&self,
+		tx: &mut Transaction,
+		q: &Vector,
+		ids: Vec<DocId>,
+		max: usize,
+	) -> Result<Vec<DocId>, Error> {
+		let mut res = Vec::with_capacity(ids.len());
+		for id in ids {
+			if let Some(n) = self.get_node(tx, id).await? {
+				res.push(Candidate(self.calculate_distance(&n.vector, q), id));
+			}
+		}
+		res.sort();
+		res.truncate(max);
+		Ok(res.into_iter().map(|c| c.1).collect())
+	}
+
+	async fn insert(
+		&mut self,
+		tx: &mut Transaction,
+		doc_id: DocId,
+		vector: Vector,
+	) -> Result<(), Error> {
+		let level = self.random_level();
+		let mut node = HnswNode {
+			vector,
+			layers: vec![vec![]; level + 1],
+		};
+		let top = self.state.level as usize;
+		if let Some(entry) = self.state.entry {
+			let Some(e) = self.get_node(tx, entry).await? else {
+				return Err(Error::CorruptedIndex);
+			};
+			let mut eps = vec![Candidate(self.calculate_distance(&e.vector, &node.vector), entry)];
+			// Greedily descend to the highest layer of the new node
+			for l in ((level + 1)..=top).rev() {
+				eps = self.search_layer(tx, &node.vector, &eps, 1, l).await?;
+			}
+			// Then pick the neighbours of the new node on each of its layers
+			for l in (0..=level.min(top)).rev() {
+				eps = self.search_layer(tx, &node.vector, &eps, self.ef_construction, l).await?;
+				node.layers[l] = eps.iter().take(self.m).map(|c| c.1).collect();
+			}
+		}
+		self.set_node(tx, doc_id, &node).await?;
+		// Link the neighbours back to the new node
+		for (l, links) in node.layers.iter().enumerate() {
+			let max = self.max_links(l);
+			for &id in links {
+				let Some(mut n) = self.get_node(tx, id).await? else {
+					continue;
+				};
+				if let Some(nl) = n.layers.get_mut(l) {
+					nl.push(doc_id);
+					if nl.len() > max {
+						let ids = std::mem::take(nl);
+						n.layers[l] = self.select(tx, &n.vector, ids, max).await?;
+					}
+					self.set_node(tx, id, &n).await?;
+				}
+			}
+		}
+		if self.state.entry.is_none() || level > top {
+			self.state.entry = Some(doc_id);
+			self.state.level = level as u16;
+			self.updated = true;
+		}
+		Ok(())
+	}
+
+	async fn remove(&mut self, tx: &mut Transaction, doc_id: DocId) -> Result<(), Error> {
+		let Some(node) = self.get_node(tx, doc_id).await? else {
+			return Ok(());
+		};
+		tx.del(self.ikb.new_hn_key(Some(doc_id))).await?;
+		// Reconnect each neighbour through the neighbours of the removed node
+		for (l, links) in node.layers.iter().enumerate() {
+			let max = self.max_links(l);
+			for &id in links {
+				let Some(mut n) = self.get_node(tx, id).await? else {
+					continue;
+				};
+				let Some(nl) = n.layers.get(l) else {
+					continue;
+				};
+				let mut ids: Vec<DocId> = nl.iter().copied().filter(|i| *i != doc_id).collect();
+				for &i in links {
+					if i != id && !ids.contains(&i) {
+						ids.push(i);
+					}
+				}
+				n.layers[l] = self.select(tx, &n.vector, ids, max).await?;
+				self.set_node(tx, id, &n).await?;
+			}
+		}
+		if self.state.entry == Some(doc_id) {
+			self.state.entry = None;
+			self.state.level = 0;
+			// The neighbour on the highest layer becomes the entry point
+			'layers: for links in node.layers.iter().rev() {
+				for &id in links {
+					if let Some(n) = self.get_node(tx, id).await? {
+						self.state.entry = Some(id);
+						self.state.level = (n.layers.len() - 1) as u16;
+						break 'layers;
+					}
+				}
+			}
+			// Otherwise any remaining node does
+			if self.state.entry.is_none() {
+				let beg = self.ikb.new_hn_key(Some(0));
+				let end = self.ikb.new_hn_key(Some(DocId::MAX));
+				if let Some((k, v)) = tx.scan(beg..end, 1).await?.pop() {
+					if let Some(id) = Hn::decode(&k)?.doc_id {
+						self.state.entry = Some(id);
+						self.state.level = (HnswNode::try_from_val(v)?.layers.len() - 1) as u16;
+					}
+				}
+			}
+			self.updated = true;
+		}
+		Ok(())
+	}
+}
+
+/// A node of the graph, ordered by its distance from a vector
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Candidate(f64, DocId);
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Candidate {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+	}
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+struct HnswState {
+	entry: Option<DocId>,
+	level: u16,
+}
+
+impl VersionedSerdeState for HnswState {}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[revisioned(revision = 1)]
+struct HnswNode {
+	vector: Vector,
+	// The neighbours of the node on each of its layers, starting from the bottom layer
+	layers: Vec<Vec<DocId>>,
+}
+
+impl VersionedSerdeState for HnswNode {}
+
+#[cfg(test)]
+mod tests {
+	use crate::idx::trees::hnsw::HnswIndex;
+	use crate::idx::trees::store::TreeStoreType;
+	use crate::idx::IndexKeyBase;
+	use crate::kvs::Datastore;
+	use crate::sql::index::{Distance, HnswParams};
+	use crate::sql::{Array, Thing, Value};
+	use roaring::RoaringTreemap;
+	use std::collections::VecDeque;
+	use test_log::test;
+
+	fn params() -> HnswParams {
+		HnswParams {
+			dimension: 2,
+			distance: Distance::Euclidean,
+			m: 4,
+			ef_construction: 20,
+			doc_ids_order: 100,
+		}
+	}
+
+	fn vector(x: i64, y: i64) -> Vec<Value> {
+		vec![Value::from(Array(vec![x.into(), y.into()]))]
+	}
+
+	async fn knn(ds: &Datastore, x: i64, y: i64, k: usize) -> VecDeque<RoaringTreemap> {
+		let mut tx = ds.transaction(false, false).await.unwrap();
+		let hnsw = HnswIndex::new(&mut tx, IndexKeyBase::default(), &params(), TreeStoreType::Read)
+			.await
+			.unwrap();
+		let a = Array(vec![x.into(), y.into()]);
+		hnsw.knn_search(&mut tx, a, k).await.unwrap()
+	}
+
+	fn check_knn(res: &VecDeque<RoaringTreemap>, expected: Vec<u64>) {
+		let res: Vec<u64> = res.iter().flat_map(|docs| docs.iter()).collect();
+		assert_eq!(res, expected);
+	}
+
+	#[test(tokio::test)]
+	async fn test_hnsw_insertions_and_deletions() {
+		let ds = Datastore::new("memory").await.unwrap();
+		// First the index is empty
+		check_knn(&knn(&ds, 0, 0, 3).await, vec![]);
+		// Index a grid of points
+		{
+			let mut tx = ds.transaction(true, false).await.unwrap();
+			let mut hnsw =
+				HnswIndex::new(&mut tx, IndexKeyBase::default(), &params(), TreeStoreType::Write)
+					.await
+					.unwrap();
+			for i in 0..100 {
+				let rid = Thing::from(("t", i.to_string().as_str()));
+				hnsw.index_document(&mut tx, &rid, vector(i % 10, i / 10)).await.unwrap();
+			}
+			hnsw.finish(&mut tx).await.unwrap();
+			tx.commit().await.unwrap();
+		}
+		// The nearest points come first
+		check_knn(&knn(&ds, 0, 0, 3).await, vec![0, 1, 10]);
+		check_knn(&knn(&ds, 9, 9, 1).await, vec![99]);
+		// Remove half of the points, including the nearest ones
+		{
+			let mut tx = ds.transaction(true, false).await.unwrap();
+			let mut hnsw =
+				HnswIndex::new(&mut tx, IndexKeyBase::default(), &params(), TreeStoreType::Write)
+					.await
+					.unwrap();
+			for i in (0..100).step_by(2) {
+				let rid = Thing::from(("t", i.to_string().as_str()));
+				hnsw.remove_document(&mut tx, &rid).await.unwrap();
+			}
+			hnsw.finish(&mut tx).await.unwrap();
+			tx.commit().await.unwrap();
+		}
+		check_knn(&knn(&ds, 0, 0, 3).await, vec![1, 11, 21]);
+		check_knn(&knn(&ds, 9, 9, 1).await, vec![99]);
+	}
+}
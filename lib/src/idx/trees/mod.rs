@@ -1,4 +1,5 @@
 pub mod bkeys;
 pub mod btree;
+pub mod hnsw;
 pub mod mtree;
 pub mod store;
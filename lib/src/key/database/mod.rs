@@ -4,9 +4,11 @@ pub mod fc;
 pub mod lg;
 pub mod pa;
 pub mod sc;
+pub mod ta;
 pub mod tb;
 pub mod ti;
 pub mod tk;
+pub mod tr;
 pub mod ts;
 pub mod us;
 pub mod vs;
@@ -0,0 +1,67 @@
+//! Stores a DEFINE TASK config definition
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ta<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	_d: u8,
+	_e: u8,
+	pub ta: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, ta: &'a str) -> Ta<'a> {
+	Ta::new(ns, db, ta)
+}
+
+pub fn prefix(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db).encode().unwrap();
+	k.extend_from_slice(&[b'!', b't', b'a', 0x00]);
+	k
+}
+
+pub fn suffix(ns: &str, db: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db).encode().unwrap();
+	k.extend_from_slice(&[b'!', b't', b'a', 0xff]);
+	k
+}
+
+impl<'a> Ta<'a> {
+	pub fn new(ns: &'a str, db: &'a str, ta: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'!',
+			_d: b't',
+			_e: b'a',
+			ta,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Ta::new(
+			"testns",
+			"testdb",
+			"testta",
+		);
+		let enc = Ta::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0!tatestta\0");
+
+		let dec = Ta::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
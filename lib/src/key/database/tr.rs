@@ -0,0 +1,55 @@
+//! Stores the time at which a task next runs
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Tr<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	_d: u8,
+	_e: u8,
+	pub tr: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tr: &'a str) -> Tr<'a> {
+	Tr::new(ns, db, tr)
+}
+
+impl<'a> Tr<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tr: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'!',
+			_d: b't',
+			_e: b'r',
+			tr,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Tr::new(
+			"testns",
+			"testdb",
+			"testtr",
+		);
+		let enc = Tr::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0!trtesttr\0");
+
+		let dec = Tr::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
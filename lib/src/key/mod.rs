@@ -21,9 +21,11 @@
 /// crate::key::database::lg             /*{ns}*{db}!lg{lg}
 /// crate::key::database::pa             /*{ns}*{db}!pa{pa}
 /// crate::key::database::sc             /*{ns}*{db}!sc{sc}
+/// crate::key::database::ta             /*{ns}*{db}!ta{ta}
 /// crate::key::database::tb             /*{ns}*{db}!tb{tb}
 /// crate::key::database::ti             /+{ns id}*{db id}!ti
 /// crate::key::database::tk             /*{ns}*{db}!tk{tk}
+/// crate::key::database::tr             /*{ns}*{db}!tr{tr}
 /// crate::key::database::ts             /*{ns}*{db}!ts{ts}
 /// crate::key::database::vs             /*{ns}*{db}!vs
 ///
@@ -31,11 +33,23 @@
 /// crate::key::scope::tk                /*{ns}*{db}±{sc}!tk{tk}
 ///
 /// crate::key::table::all               /*{ns}*{db}*{tb}
+/// crate::key::table::bb                /*{ns}*{db}*{tb}!bb{id}{v}{n}
+/// crate::key::table::bh                /*{ns}*{db}*{tb}!bh{id}
+/// crate::key::table::bf                /*{ns}*{db}*{tb}!bf{fd}
+/// crate::key::table::cd                /*{ns}*{db}*{tb}!cd{id}
+/// crate::key::table::cr                /*{ns}*{db}*{tb}!cr{hash}
+/// crate::key::table::cs                /*{ns}*{db}*{tb}!cs{hash}
+/// crate::key::table::et                /*{ns}*{db}*{tb}!et{id}
 /// crate::key::table::ev                /*{ns}*{db}*{tb}!ev{ev}
+/// crate::key::table::ex                /*{ns}*{db}*{tb}!ex{ts}{id}
 /// crate::key::table::fd                /*{ns}*{db}*{tb}!fd{fd}
 /// crate::key::table::ft                /*{ns}*{db}*{tb}!ft{ft}
 /// crate::key::table::ix                /*{ns}*{db}*{tb}!ix{ix}
 /// crate::key::table::lq                /*{ns}*{db}*{tb}!lq{lq}
+/// crate::key::table::rb                /*{ns}*{db}*{tb}!rb{ix}
+/// crate::key::table::rs                /*{ns}*{db}*{tb}!rs{id}
+/// crate::key::table::ru                /*{ns}*{db}*{tb}!ru
+/// crate::key::table::tc                /*{ns}*{db}*{tb}!tc
 ///
 /// crate::key::index::all               /*{ns}*{db}*{tb}+{ix}
 /// crate::key::index::bc                /*{ns}*{db}*{tb}+{ix}!bc{id}
@@ -49,6 +63,7 @@
 /// crate::key::index::bs                /*{ns}*{db}*{tb}+{ix}!bs
 /// crate::key::index::bt                /*{ns}*{db}*{tb}+{ix}!bt{id}
 /// crate::key::index::bu                /*{ns}*{db}*{tb}+{ix}!bu{id}
+/// crate::key::index::hn                /*{ns}*{db}*{tb}+{ix}!hn{id}
 /// crate::key::index                    /*{ns}*{db}*{tb}+{ix}*{fd}{id}
 ///
 /// crate::key::change                   /*{ns}*{db}#{ts}
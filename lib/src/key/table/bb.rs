@@ -0,0 +1,178 @@
+//! Stores the chunks of the binary blob of a record
+use crate::sql::id::Id;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+/// The value of the bb is one chunk of the blob, and the chunks of a
+/// blob are numbered in the order in which they are read back. Each
+/// write of a blob stores its chunks under a new version, which is
+/// only read once it is committed as the head of the blob.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Bb<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+	pub v: u64,
+	pub n: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct Prefix<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+struct PrefixVersion<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub id: Id,
+	pub v: u64,
+}
+
+impl<'a> PrefixVersion<'a> {
+	fn new(ns: &'a str, db: &'a str, tb: &'a str, id: &Id, v: u64) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'b',
+			_f: b'b',
+			id: id.to_owned(),
+			v,
+		}
+	}
+}
+
+impl<'a> Prefix<'a> {
+	fn new(ns: &'a str, db: &'a str, tb: &'a str, id: &Id) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'b',
+			_f: b'b',
+			id: id.to_owned(),
+		}
+	}
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, id: &Id, v: u64, n: u32) -> Bb<'a> {
+	Bb::new(ns, db, tb, id.to_owned(), v, n)
+}
+
+pub fn prefix(ns: &str, db: &str, tb: &str, id: &Id) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, id).encode().unwrap();
+	k.extend_from_slice(&[0x00]);
+	k
+}
+
+pub fn suffix(ns: &str, db: &str, tb: &str, id: &Id) -> Vec<u8> {
+	let mut k = Prefix::new(ns, db, tb, id).encode().unwrap();
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+pub fn vsprefix(ns: &str, db: &str, tb: &str, id: &Id, v: u64) -> Vec<u8> {
+	let mut k = PrefixVersion::new(ns, db, tb, id, v).encode().unwrap();
+	k.extend_from_slice(&[0x00]);
+	k
+}
+
+pub fn vssuffix(ns: &str, db: &str, tb: &str, id: &Id, v: u64) -> Vec<u8> {
+	let mut k = PrefixVersion::new(ns, db, tb, id, v).encode().unwrap();
+	k.extend_from_slice(&[0xff]);
+	k
+}
+
+impl<'a> Bb<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, id: Id, v: u64, n: u32) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'b',
+			_f: b'b',
+			id,
+			v,
+			n,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Bb::new(
+			"testns",
+			"testdb",
+			"testtb",
+			"testid".into(),
+			2,
+			3,
+		);
+		let enc = Bb::encode(&val).unwrap();
+		assert_eq!(
+			enc,
+			b"/*testns\0*testdb\0*testtb\0!bb\0\0\0\x01testid\0\0\0\0\0\0\0\0\x02\0\0\0\x03"
+		);
+
+		let dec = Bb::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn range() {
+		use super::*;
+		let id = Id::from("testid");
+		let val = Bb::new("testns", "testdb", "testtb", id.clone(), 7, 1_000_000).encode().unwrap();
+		assert!(prefix("testns", "testdb", "testtb", &id) <= val);
+		assert!(val < suffix("testns", "testdb", "testtb", &id));
+		assert!(vsprefix("testns", "testdb", "testtb", &id, 7) <= val);
+		assert!(val < vssuffix("testns", "testdb", "testtb", &id, 7));
+		assert!(val < vsprefix("testns", "testdb", "testtb", &id, 8));
+	}
+}
@@ -0,0 +1,60 @@
+//! Stores the number of references to a value which is shared by the records of a table
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Cr<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub hash: &'a str,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, hash: &'a str) -> Cr<'a> {
+	Cr::new(ns, db, tb, hash)
+}
+
+impl<'a> Cr<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, hash: &'a str) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'c',
+			_f: b'r',
+			hash,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Cr::new(
+			"testns",
+			"testdb",
+			"testtb",
+			"testhash",
+		);
+		let enc = Cr::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0*testtb\0!crtesthash\0");
+
+		let dec = Cr::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+}
@@ -0,0 +1,84 @@
+//! Stores the expiry time of a record, ordered by expiry time
+use crate::sql::id::Id;
+use derive::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Key)]
+pub struct Ex<'a> {
+	__: u8,
+	_a: u8,
+	pub ns: &'a str,
+	_b: u8,
+	pub db: &'a str,
+	_c: u8,
+	pub tb: &'a str,
+	_d: u8,
+	_e: u8,
+	_f: u8,
+	pub ts: u64,
+	pub id: Id,
+}
+
+pub fn new<'a>(ns: &'a str, db: &'a str, tb: &'a str, ts: u64, id: &Id) -> Ex<'a> {
+	Ex::new(ns, db, tb, ts, id.to_owned())
+}
+
+pub fn prefix(ns: &str, db: &str, tb: &str) -> Vec<u8> {
+	let mut k = super::all::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'e', b'x', 0x00]);
+	k
+}
+
+/// The end of the range of records which expire at or before the specified time
+pub fn suffix(ns: &str, db: &str, tb: &str, ts: u64) -> Vec<u8> {
+	let mut k = super::all::new(ns, db, tb).encode().unwrap();
+	k.extend_from_slice(&[b'!', b'e', b'x']);
+	k.extend_from_slice(&ts.saturating_add(1).to_be_bytes());
+	k
+}
+
+impl<'a> Ex<'a> {
+	pub fn new(ns: &'a str, db: &'a str, tb: &'a str, ts: u64, id: Id) -> Self {
+		Self {
+			__: b'/',
+			_a: b'*',
+			ns,
+			_b: b'*',
+			db,
+			_c: b'*',
+			tb,
+			_d: b'!',
+			_e: b'e',
+			_f: b'x',
+			ts,
+			id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn key() {
+		use super::*;
+		#[rustfmt::skip]
+		let val = Ex::new(
+			"testns",
+			"testdb",
+			"testtb",
+			123,
+			"testid".into(),
+		);
+		let enc = Ex::encode(&val).unwrap();
+		assert_eq!(enc, b"/*testns\0*testdb\0*testtb\0!ex\0\0\0\0\0\0\0\x7b\0\0\0\x01testid\0");
+
+		let dec = Ex::decode(&enc).unwrap();
+		assert_eq!(val, dec);
+	}
+
+	#[test]
+	fn suffix() {
+		let val = super::suffix("testns", "testdb", "testtb", 123);
+		assert_eq!(val, b"/*testns\0*testdb\0*testtb\0!ex\0\0\0\0\0\0\0\x7c")
+	}
+}
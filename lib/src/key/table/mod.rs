@@ -1,6 +1,18 @@
 pub mod all;
+pub mod bb;
+pub mod bh;
+pub mod bf;
+pub mod cd;
+pub mod cr;
+pub mod cs;
+pub mod et;
 pub mod ev;
+pub mod ex;
 pub mod fd;
 pub mod ft;
 pub mod ix;
 pub mod lq;
+pub mod rb;
+pub mod rs;
+pub mod ru;
+pub mod tc;
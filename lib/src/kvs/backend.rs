@@ -0,0 +1,254 @@
+//! Support for storage engines which are implemented outside of this crate.
+//!
+//! A storage engine implements the [`Backend`] and [`BackendTransaction`] traits, and is
+//! then registered for a URL scheme with [`register`]. Once registered, the storage engine
+//! is opened by [`Datastore::new`](super::Datastore::new) like any built-in storage engine.
+use super::Key;
+use super::Val;
+use crate::err::Error;
+use crate::vs::try_to_u64_be;
+use crate::vs::u64_to_versionstamp;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::future::Future;
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+/// The future returned by the methods of a custom storage engine
+pub type BackendFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>;
+
+/// Opens a custom storage engine at the path which follows the URL scheme
+type Factory = Arc<dyn Fn(String) -> BackendFuture<'static, Arc<dyn Backend>> + Send + Sync>;
+
+/// The custom storage engines which have been registered, keyed by URL scheme
+static BACKENDS: Lazy<RwLock<HashMap<String, Factory>>> = Lazy::new(Default::default);
+
+/// A custom key-value storage engine
+pub trait Backend: Send + Sync + 'static {
+	/// Start a new transaction
+	///
+	/// Transactions which are not `write` transactions are never committed. A `lock`
+	/// transaction should prevent concurrent transactions from modifying the keys it reads.
+	fn begin(&self, write: bool, lock: bool) -> BackendFuture<'_, Box<dyn BackendTransaction>>;
+}
+
+/// A transaction in a custom key-value storage engine
+///
+/// Only the methods without a default implementation are needed to run SurrealDB.
+/// The others can be overridden when the storage engine supports them natively.
+pub trait BackendTransaction: Send + Sync {
+	/// Check if the transaction has been cancelled or committed
+	fn closed(&self) -> bool;
+
+	/// Cancel the transaction, reverting all changes made within it
+	fn cancel(&mut self) -> BackendFuture<'_, ()>;
+
+	/// Commit all changes made within the transaction
+	fn commit(&mut self) -> BackendFuture<'_, ()>;
+
+	/// Fetch a key from the datastore
+	fn get(&mut self, key: Key) -> BackendFuture<'_, Option<Val>>;
+
+	/// Insert or update a key in the datastore
+	fn set(&mut self, key: Key, val: Val) -> BackendFuture<'_, ()>;
+
+	/// Delete a key from the datastore
+	fn del(&mut self, key: Key) -> BackendFuture<'_, ()>;
+
+	/// Retrieve a range of keys from the datastore, in ascending key order
+	fn scan(&mut self, rng: Range<Key>, limit: u32) -> BackendFuture<'_, Vec<(Key, Val)>>;
+
+	/// Check if a key exists in the datastore
+	fn exi(&mut self, key: Key) -> BackendFuture<'_, bool> {
+		Box::pin(async move { Ok(self.get(key).await?.is_some()) })
+	}
+
+	/// Insert a key if it doesn't exist in the datastore
+	fn put(&mut self, key: Key, val: Val) -> BackendFuture<'_, ()> {
+		Box::pin(async move {
+			match self.exi(key.clone()).await? {
+				false => self.set(key, val).await,
+				true => Err(Error::TxKeyAlreadyExists),
+			}
+		})
+	}
+
+	/// Update a key in the datastore if the current value matches a condition
+	fn putc(&mut self, key: Key, val: Val, chk: Option<Val>) -> BackendFuture<'_, ()> {
+		Box::pin(async move {
+			match (self.get(key.clone()).await?, chk) {
+				(Some(v), Some(w)) if v == w => self.set(key, val).await,
+				(None, None) => self.set(key, val).await,
+				_ => Err(Error::TxConditionNotMet),
+			}
+		})
+	}
+
+	/// Delete a key from the datastore if the current value matches a condition
+	fn delc(&mut self, key: Key, chk: Option<Val>) -> BackendFuture<'_, ()> {
+		Box::pin(async move {
+			match (self.get(key.clone()).await?, chk) {
+				(Some(v), Some(w)) if v == w => self.del(key).await,
+				(None, None) => self.del(key).await,
+				_ => Err(Error::TxConditionNotMet),
+			}
+		})
+	}
+
+	/// Obtain a new change timestamp, stored under the given key
+	///
+	/// The default implementation increments a counter stored at the key, so storage
+	/// engines with their own timestamp oracle should override it.
+	fn get_timestamp(&mut self, key: Key) -> BackendFuture<'_, [u8; 10]> {
+		Box::pin(async move {
+			let ver = match self.get(key.clone()).await? {
+				Some(prev) => {
+					let prev: [u8; 10] =
+						prev.as_slice().try_into().map_err(|e| Error::Ds(format!("{e}")))?;
+					try_to_u64_be(prev)? + 1
+				}
+				None => 1,
+			};
+			let verbytes = u64_to_versionstamp(ver);
+			self.set(key, verbytes.to_vec()).await?;
+			Ok(verbytes)
+		})
+	}
+}
+
+/// Register a custom storage engine for a URL scheme
+///
+/// Calling [`Datastore::new`](super::Datastore::new) with a path starting with `scheme:`
+/// or `scheme://` then calls `open` with the remainder of the path. Registering the same
+/// scheme again replaces the previous storage engine. The schemes of the built-in
+/// storage engines can not be overridden.
+///
+/// ```rust,ignore
+/// use surrealdb::kvs::{self, Datastore};
+///
+/// kvs::register("lmdb", |path| async move { LmdbBackend::open(&path) });
+/// let ds = Datastore::new("lmdb://path/to/database").await?;
+/// ```
+pub fn register<F, Fut, B>(scheme: &str, open: F)
+where
+	F: Fn(String) -> Fut + Send + Sync + 'static,
+	Fut: Future<Output = Result<B, Error>> + Send + 'static,
+	B: Backend,
+{
+	let factory: Factory = Arc::new(move |path| {
+		let fut = open(path);
+		Box::pin(async move { Ok(Arc::new(fut.await?) as Arc<dyn Backend>) })
+	});
+	BACKENDS.write().unwrap_or_else(|e| e.into_inner()).insert(scheme.to_owned(), factory);
+}
+
+/// Open the custom storage engine registered for the scheme of a path, if there is one
+pub(super) async fn open(path: &str) -> Option<Result<Arc<dyn Backend>, Error>> {
+	let (scheme, rest) = path.split_once(':')?;
+	let factory = BACKENDS.read().unwrap_or_else(|e| e.into_inner()).get(scheme).cloned()?;
+	let rest = rest.trim_start_matches("//");
+	Some(factory(rest.to_owned()).await)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+	use crate::dbs::Session;
+	use crate::kvs::Datastore;
+	use crate::sql::Value;
+	use std::collections::BTreeMap;
+	use std::sync::Mutex;
+
+	/// A storage engine which copies the whole dataset into each transaction
+	#[derive(Default)]
+	struct Memory {
+		data: Arc<Mutex<BTreeMap<Key, Val>>>,
+	}
+
+	struct MemoryTransaction {
+		done: bool,
+		write: bool,
+		data: Arc<Mutex<BTreeMap<Key, Val>>>,
+		copy: BTreeMap<Key, Val>,
+	}
+
+	impl Backend for Memory {
+		fn begin(&self, write: bool, _: bool) -> BackendFuture<'_, Box<dyn BackendTransaction>> {
+			Box::pin(async move {
+				Ok(Box::new(MemoryTransaction {
+					done: false,
+					write,
+					data: self.data.clone(),
+					copy: self.data.lock().unwrap().clone(),
+				}) as Box<dyn BackendTransaction>)
+			})
+		}
+	}
+
+	impl BackendTransaction for MemoryTransaction {
+		fn closed(&self) -> bool {
+			self.done
+		}
+		fn cancel(&mut self) -> BackendFuture<'_, ()> {
+			self.done = true;
+			Box::pin(async { Ok(()) })
+		}
+		fn commit(&mut self) -> BackendFuture<'_, ()> {
+			Box::pin(async move {
+				if !self.write {
+					return Err(Error::TxReadonly);
+				}
+				self.done = true;
+				*self.data.lock().unwrap() = std::mem::take(&mut self.copy);
+				Ok(())
+			})
+		}
+		fn get(&mut self, key: Key) -> BackendFuture<'_, Option<Val>> {
+			Box::pin(async move { Ok(self.copy.get(&key).cloned()) })
+		}
+		fn set(&mut self, key: Key, val: Val) -> BackendFuture<'_, ()> {
+			Box::pin(async move {
+				self.copy.insert(key, val);
+				Ok(())
+			})
+		}
+		fn del(&mut self, key: Key) -> BackendFuture<'_, ()> {
+			Box::pin(async move {
+				self.copy.remove(&key);
+				Ok(())
+			})
+		}
+		fn scan(&mut self, rng: Range<Key>, limit: u32) -> BackendFuture<'_, Vec<(Key, Val)>> {
+			Box::pin(async move {
+				Ok(self
+					.copy
+					.range(rng)
+					.take(limit as usize)
+					.map(|(k, v)| (k.clone(), v.clone()))
+					.collect())
+			})
+		}
+	}
+
+	#[tokio::test]
+	async fn custom_backend() {
+		register("custom-memory", |_| async { Ok(Memory::default()) });
+		let ds = Datastore::new("custom-memory://test").await.unwrap();
+		assert_eq!(ds.to_string(), "custom");
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let sql = "CREATE person:tobie SET name = 'Tobie'; SELECT VALUE name FROM person;";
+		let res = &mut ds.execute(sql, &ses, None).await.unwrap();
+		assert_eq!(res.len(), 2);
+		res.remove(0).result.unwrap();
+		let val = res.remove(0).result.unwrap();
+		assert_eq!(val, Value::from(vec!["Tobie"]));
+	}
+
+	#[tokio::test]
+	async fn unknown_backend() {
+		assert!(Datastore::new("unknown://test").await.is_err());
+	}
+}
@@ -0,0 +1,511 @@
+//! Binary backups of the underlying key-value store
+//!
+//! A backup starts with a header which identifies the format and the kind of
+//! backup, followed by a sequence of tagged frames. Byte strings are written with
+//! a big-endian `u64` length prefix.
+//!
+//! A full backup contains every key-value pair in the datastore, so restoring it
+//! doesn't need to parse or execute any SQL, and it can only be restored into an
+//! empty datastore. An incremental backup contains the change feed entries written
+//! since a previous backup, and is restored by applying those changes to the records
+//! they belong to. Tables which don't have a change feed are written in full to
+//! every incremental backup, and replace the table when they are restored.
+//!
+//! Both kinds of backup end with the versionstamp of every database, which is
+//! used as the starting point of the next incremental backup.
+
+use super::BatchSize;
+use super::Datastore;
+use super::Transaction;
+use crate::cf::TableMutation;
+use crate::cf::TableMutations;
+use crate::dbs::Session;
+use crate::err::Error;
+use crate::key::change;
+use crate::sql::Value;
+use crate::sql::Versionstamp;
+use crate::vs::try_to_u64_be;
+use futures::io::AsyncRead;
+use futures::io::AsyncReadExt;
+use futures::io::AsyncWrite;
+use futures::io::AsyncWriteExt;
+use std::collections::BTreeMap;
+
+/// Identifies a SurrealDB binary backup
+const MAGIC: &[u8; 4] = b"SDBK";
+/// The current version of the backup format
+const VERSION: u8 = 1;
+/// The number of keys read or written in the first batch, before their size is known
+const BATCH_SIZE: u32 = 1000;
+
+const KIND_FULL: u8 = 0;
+const KIND_INCREMENTAL: u8 = 1;
+
+const TAG_END: u8 = 0;
+const TAG_SET: u8 = 1;
+const TAG_MUTATIONS: u8 = 2;
+const TAG_CHECKPOINT: u8 = 3;
+const TAG_CLEAR: u8 = 4;
+
+/// Keys which belong to the nodes of a running cluster, and which are not backed up
+const NODE_PREFIXES: [&[u8]; 3] = [b"/!nd", b"/!hb", b"/$"];
+
+/// The versionstamp of every database at the time a backup was taken
+///
+/// Pass this to [`Datastore::backup_since`] to only back up the changes made
+/// after the backup it was returned from.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Checkpoint {
+	versionstamps: BTreeMap<(String, String), u64>,
+}
+
+impl Checkpoint {
+	/// Returns the versionstamp of a database at the time of the backup
+	pub fn versionstamp(&self, ns: &str, db: &str) -> Option<Versionstamp> {
+		self.versionstamps.get(&(ns.to_owned(), db.to_owned())).copied().map(Versionstamp)
+	}
+}
+
+/// Writes a full or incremental backup of the datastore
+pub(super) async fn backup<W>(
+	tx: &mut Transaction,
+	writer: &mut W,
+	since: Option<&Checkpoint>,
+) -> Result<Checkpoint, Error>
+where
+	W: AsyncWrite + Unpin + ?Sized,
+{
+	writer.write_all(MAGIC).await?;
+	writer.write_all(&[VERSION]).await?;
+	match since {
+		None => {
+			writer.write_all(&[KIND_FULL]).await?;
+			backup_keys(tx, writer, vec![0x00], vec![0xff]).await?;
+		}
+		Some(since) => {
+			writer.write_all(&[KIND_INCREMENTAL]).await?;
+			backup_changes(tx, writer, since).await?;
+		}
+	}
+	let checkpoint = checkpoint(tx).await?;
+	for ((ns, db), vs) in &checkpoint.versionstamps {
+		writer.write_all(&[TAG_CHECKPOINT]).await?;
+		write_bytes(writer, ns.as_bytes()).await?;
+		write_bytes(writer, db.as_bytes()).await?;
+		writer.write_all(&vs.to_be_bytes()).await?;
+	}
+	writer.write_all(&[TAG_END]).await?;
+	writer.flush().await?;
+	Ok(checkpoint)
+}
+
+/// Restores a full or incremental backup into the datastore
+pub(super) async fn restore<R>(
+	ds: &Datastore,
+	sess: &Session,
+	reader: &mut R,
+) -> Result<Checkpoint, Error>
+where
+	R: AsyncRead + Unpin + ?Sized,
+{
+	let mut header = [0; 6];
+	reader.read_exact(&mut header).await?;
+	if &header[..4] != MAGIC {
+		return Err(Error::InvalidBackup("not a SurrealDB backup".to_owned()));
+	}
+	if header[4] != VERSION {
+		return Err(Error::InvalidBackup(format!("unsupported format version {}", header[4])));
+	}
+	if header[5] != KIND_FULL && header[5] != KIND_INCREMENTAL {
+		return Err(Error::InvalidBackup(format!("unknown backup kind {}", header[5])));
+	}
+	// A full backup would be merged with any data which is already there
+	if header[5] == KIND_FULL && !is_empty(ds).await? {
+		return Err(Error::RestoreNotEmpty);
+	}
+	let mut checkpoint = Checkpoint::default();
+	// Key-value pairs are written in batches to keep transactions to a reasonable size
+	let mut batch = Vec::new();
+	let mut size = BatchSize::new(BATCH_SIZE);
+	loop {
+		let mut tag = [0];
+		reader.read_exact(&mut tag).await?;
+		match tag[0] {
+			TAG_SET => {
+				let key = read_bytes(reader).await?;
+				let val = read_bytes(reader).await?;
+				batch.push((key, val));
+				if batch.len() >= size.get() as usize {
+					size.observe(&batch);
+					write_batch(ds, &mut batch).await?;
+				}
+			}
+			TAG_MUTATIONS => {
+				let ns = read_string(reader).await?;
+				let db = read_string(reader).await?;
+				let TableMutations(_, mutations) = read_bytes(reader).await?.into();
+				write_batch(ds, &mut batch).await?;
+				apply(ds, sess, &ns, &db, mutations).await?;
+			}
+			TAG_CLEAR => {
+				let prefix = read_bytes(reader).await?;
+				write_batch(ds, &mut batch).await?;
+				clear(ds, prefix).await?;
+			}
+			TAG_CHECKPOINT => {
+				let ns = read_string(reader).await?;
+				let db = read_string(reader).await?;
+				let mut val = [0; 8];
+				reader.read_exact(&mut val).await?;
+				checkpoint.versionstamps.insert((ns, db), u64::from_be_bytes(val));
+			}
+			TAG_END => break,
+			tag => return Err(Error::InvalidBackup(format!("unknown frame {tag}"))),
+		}
+	}
+	write_batch(ds, &mut batch).await?;
+	Ok(checkpoint)
+}
+
+/// Writes a batch of restored key-value pairs in a single transaction
+async fn write_batch(ds: &Datastore, batch: &mut Vec<(Vec<u8>, Vec<u8>)>) -> Result<(), Error> {
+	if batch.is_empty() {
+		return Ok(());
+	}
+	let mut tx = ds.transaction(true, false).await?;
+	for (key, val) in batch.drain(..) {
+		if let Err(error) = tx.set(key, val).await {
+			tx.cancel().await?;
+			return Err(error);
+		}
+	}
+	tx.commit().await
+}
+
+/// Checks that the datastore has no keys, apart from the cluster nodes
+async fn is_empty(ds: &Datastore) -> Result<bool, Error> {
+	let mut tx = ds.transaction(false, false).await?;
+	let mut beg = vec![0x00];
+	let end = vec![0xff];
+	let res = loop {
+		let batch = match tx.scan(beg.clone()..end.clone(), BATCH_SIZE).await {
+			Ok(batch) => batch,
+			Err(error) => {
+				tx.cancel().await?;
+				return Err(error);
+			}
+		};
+		let Some((last, _)) = batch.last() else {
+			break true;
+		};
+		beg = last.clone();
+		beg.push(0x00);
+		if batch.iter().any(|(key, _)| !NODE_PREFIXES.iter().any(|prefix| key.starts_with(prefix)))
+		{
+			break false;
+		}
+	};
+	tx.cancel().await?;
+	Ok(res)
+}
+
+/// Deletes every key with a prefix, in transactions of a bounded size
+async fn clear(ds: &Datastore, prefix: Vec<u8>) -> Result<(), Error> {
+	let mut end = prefix.clone();
+	end.push(0xff);
+	loop {
+		let mut tx = ds.transaction(true, false).await?;
+		let batch = match tx.scan(prefix.clone()..end.clone(), BATCH_SIZE).await {
+			Ok(batch) => batch,
+			Err(error) => {
+				tx.cancel().await?;
+				return Err(error);
+			}
+		};
+		if batch.is_empty() {
+			tx.cancel().await?;
+			return Ok(());
+		}
+		for (key, _) in batch {
+			if let Err(error) = tx.del(key).await {
+				tx.cancel().await?;
+				return Err(error);
+			}
+		}
+		tx.commit().await?;
+	}
+}
+
+/// Writes every key-value pair in a range, apart from the cluster nodes
+async fn backup_keys<W>(
+	tx: &mut Transaction,
+	writer: &mut W,
+	mut beg: Vec<u8>,
+	end: Vec<u8>,
+) -> Result<(), Error>
+where
+	W: AsyncWrite + Unpin + ?Sized,
+{
+	let mut size = BatchSize::new(BATCH_SIZE);
+	loop {
+		let batch = tx.scan(beg.clone()..end.clone(), size.get()).await?;
+		size.observe(&batch);
+		let Some((last, _)) = batch.last() else {
+			break;
+		};
+		// Continue the next batch right after the last key
+		beg = last.clone();
+		beg.push(0x00);
+		for (key, val) in batch {
+			if NODE_PREFIXES.iter().any(|prefix| key.starts_with(prefix)) {
+				continue;
+			}
+			writer.write_all(&[TAG_SET]).await?;
+			write_bytes(writer, &key).await?;
+			write_bytes(writer, &val).await?;
+		}
+	}
+	Ok(())
+}
+
+/// Writes the change feed entries of every database since the checkpoint
+///
+/// Tables which have no change feed are written in full instead.
+async fn backup_changes<W>(
+	tx: &mut Transaction,
+	writer: &mut W,
+	since: &Checkpoint,
+) -> Result<(), Error>
+where
+	W: AsyncWrite + Unpin + ?Sized,
+{
+	for ns in tx.all_ns().await?.iter() {
+		let ns = ns.name.as_str();
+		for db in tx.all_db(ns).await?.iter() {
+			let changefeed = db.changefeed.is_some();
+			let db = db.name.as_str();
+			for tb in tx.all_tb(ns, db).await?.iter() {
+				if changefeed || tb.changefeed.is_some() || tb.temporary {
+					continue;
+				}
+				backup_table(tx, writer, ns, db, &tb.name).await?;
+			}
+			let start = since.versionstamp(ns, db).map_or(Versionstamp(0), Versionstamp::next);
+			let mut beg = change::prefix_ts(ns, db, start.to_bytes());
+			let end = change::suffix(ns, db);
+			let mut size = BatchSize::new(BATCH_SIZE);
+			loop {
+				let batch = tx.scan(beg.clone()..end.clone(), size.get()).await?;
+				size.observe(&batch);
+				let Some((last, _)) = batch.last() else {
+					break;
+				};
+				beg = last.clone();
+				beg.push(0x00);
+				for (_, val) in batch {
+					writer.write_all(&[TAG_MUTATIONS]).await?;
+					write_bytes(writer, ns.as_bytes()).await?;
+					write_bytes(writer, db.as_bytes()).await?;
+					write_bytes(writer, &val).await?;
+				}
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Writes a table in full, with a frame which replaces the table when it is restored
+async fn backup_table<W>(
+	tx: &mut Transaction,
+	writer: &mut W,
+	ns: &str,
+	db: &str,
+	tb: &str,
+) -> Result<(), Error>
+where
+	W: AsyncWrite + Unpin + ?Sized,
+{
+	let prefix: Vec<u8> = crate::key::table::all::new(ns, db, tb).into();
+	writer.write_all(&[TAG_CLEAR]).await?;
+	write_bytes(writer, &prefix).await?;
+	// The definition of the table is kept outside of the table prefix
+	let key: Vec<u8> = crate::key::database::tb::new(ns, db, tb).into();
+	if let Some(val) = tx.get(key.clone()).await? {
+		writer.write_all(&[TAG_SET]).await?;
+		write_bytes(writer, &key).await?;
+		write_bytes(writer, &val).await?;
+	}
+	let mut end = prefix.clone();
+	end.push(0xff);
+	backup_keys(tx, writer, prefix, end).await
+}
+
+/// Reads the latest versionstamp of every database
+async fn checkpoint(tx: &mut Transaction) -> Result<Checkpoint, Error> {
+	let mut checkpoint = Checkpoint::default();
+	for ns in tx.all_ns().await?.iter() {
+		for db in tx.all_db(&ns.name).await?.iter() {
+			let key = crate::key::database::vs::new(&ns.name, &db.name);
+			let vs = match tx.get(key).await? {
+				Some(val) => {
+					let val: [u8; 10] = val
+						.try_into()
+						.map_err(|_| Error::Internal("invalid versionstamp".to_owned()))?;
+					try_to_u64_be(val)?
+				}
+				None => 0,
+			};
+			checkpoint.versionstamps.insert((ns.name.to_raw(), db.name.to_raw()), vs);
+		}
+	}
+	Ok(checkpoint)
+}
+
+/// Applies the changes made to a table, so that indexes and views are kept up to date
+async fn apply(
+	ds: &Datastore,
+	sess: &Session,
+	ns: &str,
+	db: &str,
+	mutations: Vec<TableMutation>,
+) -> Result<(), Error> {
+	let sess = sess.clone().with_ns(ns).with_db(db);
+	for mutation in mutations {
+		let (sql, vars) = match mutation {
+			TableMutation::Set(id, value) => (
+				"UPDATE $id CONTENT $value RETURN NONE".to_owned(),
+				map! {
+					String::from("id") => Value::from(id),
+					String::from("value") => value,
+				},
+			),
+			TableMutation::Del(id) => (
+				"DELETE $id RETURN NONE".to_owned(),
+				map! {
+					String::from("id") => Value::from(id),
+				},
+			),
+			TableMutation::Def(stm) => (stm.to_string(), BTreeMap::new()),
+		};
+		for response in ds.execute(&sql, &sess, Some(vars)).await? {
+			response.result?;
+		}
+	}
+	Ok(())
+}
+
+async fn write_bytes<W>(writer: &mut W, bytes: &[u8]) -> Result<(), Error>
+where
+	W: AsyncWrite + Unpin + ?Sized,
+{
+	writer.write_all(&(bytes.len() as u64).to_be_bytes()).await?;
+	writer.write_all(bytes).await?;
+	Ok(())
+}
+
+async fn read_bytes<R>(reader: &mut R) -> Result<Vec<u8>, Error>
+where
+	R: AsyncRead + Unpin + ?Sized,
+{
+	let mut len = [0; 8];
+	reader.read_exact(&mut len).await?;
+	let len = usize::try_from(u64::from_be_bytes(len))
+		.map_err(|_| Error::InvalidBackup("frame is too large".to_owned()))?;
+	let mut bytes = Vec::new();
+	// Don't trust the length prefix to allocate the whole buffer up front
+	reader.take(len as u64).read_to_end(&mut bytes).await?;
+	if bytes.len() != len {
+		return Err(Error::InvalidBackup("unexpected end of backup".to_owned()));
+	}
+	Ok(bytes)
+}
+
+async fn read_string<R>(reader: &mut R) -> Result<String, Error>
+where
+	R: AsyncRead + Unpin + ?Sized,
+{
+	String::from_utf8(read_bytes(reader).await?)
+		.map_err(|_| Error::InvalidBackup("invalid name".to_owned()))
+}
+
+#[cfg(all(test, feature = "kv-mem"))]
+mod tests {
+	use super::*;
+
+	async fn records(ds: &Datastore, sess: &Session) -> Value {
+		let mut res = ds.execute("SELECT * FROM person", sess, None).await.unwrap();
+		res.remove(0).result.unwrap()
+	}
+
+	#[tokio::test]
+	async fn full_backup_and_restore() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let sess = Session::owner().with_ns("test").with_db("test");
+		let sql = "CREATE person:one SET name = 'Tobie'; CREATE person:two SET name = 'Jaime';";
+		ds.execute(sql, &sess, None).await.unwrap();
+		let mut backup = Vec::new();
+		ds.backup(&sess, &mut backup).await.unwrap();
+		// Restore into a new datastore
+		let restored = Datastore::new("memory").await.unwrap();
+		restored.restore(&sess, &mut backup.as_slice()).await.unwrap();
+		assert_eq!(records(&restored, &sess).await, records(&ds, &sess).await);
+	}
+
+	#[tokio::test]
+	async fn incremental_backup_and_restore() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let sess = Session::owner().with_ns("test").with_db("test");
+		let sql = "DEFINE TABLE person CHANGEFEED 1h; CREATE person:one SET name = 'Tobie';";
+		ds.execute(sql, &sess, None).await.unwrap();
+		let mut full = Vec::new();
+		let checkpoint = ds.backup(&sess, &mut full).await.unwrap();
+		// Make some changes after the full backup
+		let sql = "CREATE person:two SET name = 'Jaime'; DELETE person:one;";
+		ds.execute(sql, &sess, None).await.unwrap();
+		let mut incremental = Vec::new();
+		let next = ds.backup_since(&sess, &mut incremental, &checkpoint).await.unwrap();
+		assert!(next.versionstamp("test", "test") > checkpoint.versionstamp("test", "test"));
+		// Restore both backups into a new datastore
+		let restored = Datastore::new("memory").await.unwrap();
+		restored.restore(&sess, &mut full.as_slice()).await.unwrap();
+		restored.restore(&sess, &mut incremental.as_slice()).await.unwrap();
+		assert_eq!(records(&restored, &sess).await, records(&ds, &sess).await);
+	}
+
+	#[tokio::test]
+	async fn incremental_backup_includes_tables_without_changefeed() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let sess = Session::owner().with_ns("test").with_db("test");
+		let sql = "CREATE person:one SET name = 'Tobie'; CREATE person:two SET name = 'Jaime';";
+		ds.execute(sql, &sess, None).await.unwrap();
+		let mut full = Vec::new();
+		let checkpoint = ds.backup(&sess, &mut full).await.unwrap();
+		let sql = "CREATE person:three SET name = 'Lizzie'; DELETE person:one;";
+		ds.execute(sql, &sess, None).await.unwrap();
+		let mut incremental = Vec::new();
+		ds.backup_since(&sess, &mut incremental, &checkpoint).await.unwrap();
+		let restored = Datastore::new("memory").await.unwrap();
+		restored.restore(&sess, &mut full.as_slice()).await.unwrap();
+		restored.restore(&sess, &mut incremental.as_slice()).await.unwrap();
+		assert_eq!(records(&restored, &sess).await, records(&ds, &sess).await);
+	}
+
+	#[tokio::test]
+	async fn full_restore_needs_an_empty_datastore() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let sess = Session::owner().with_ns("test").with_db("test");
+		ds.execute("CREATE person:one", &sess, None).await.unwrap();
+		let mut backup = Vec::new();
+		ds.backup(&sess, &mut backup).await.unwrap();
+		let res = ds.restore(&sess, &mut backup.as_slice()).await;
+		assert!(matches!(res, Err(Error::RestoreNotEmpty)));
+	}
+
+	#[tokio::test]
+	async fn rejects_invalid_backups() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let sess = Session::owner();
+		let res = ds.restore(&sess, &mut &b"DEFINE TABLE person;"[..]).await;
+		assert!(matches!(res, Err(Error::InvalidBackup(_))));
+	}
+}
@@ -0,0 +1,75 @@
+//! Sizes the batches of scans and writes by the size of their entries
+//!
+//! A fixed number of entries per batch reads far too much at once from tables with wide
+//! records, and makes far too many round trips to the storage engine for tables with narrow
+//! ones. Batches instead aim for a number of bytes, using the average size of the entries
+//! which have been seen so far to choose how many entries the next batch holds.
+use super::Key;
+use super::Val;
+use crate::cnf::BATCH_BYTE_BUDGET;
+use crate::cnf::MAX_BATCH_SIZE;
+
+/// The number of entries in the next batch of a scan or a write
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BatchSize {
+	/// The size of the first batch, before any entries have been seen
+	initial: u32,
+	/// The moving average of the size of an entry, in bytes
+	average: Option<usize>,
+}
+
+impl BatchSize {
+	/// Starts with a fixed number of entries, until the size of the entries is known
+	pub(crate) fn new(initial: u32) -> Self {
+		Self {
+			initial,
+			average: None,
+		}
+	}
+
+	/// The number of entries which the next batch should hold
+	pub(crate) fn get(&self) -> u32 {
+		match self.average {
+			Some(average) => {
+				(BATCH_BYTE_BUDGET / average.max(1)).clamp(1, MAX_BATCH_SIZE as usize) as u32
+			}
+			None => self.initial,
+		}
+	}
+
+	/// Updates the average size of an entry with a batch which was read or written
+	pub(crate) fn observe(&mut self, batch: &[(Key, Val)]) {
+		if batch.is_empty() {
+			return;
+		}
+		let bytes: usize = batch.iter().map(|(k, v)| k.len() + v.len()).sum();
+		let size = bytes / batch.len();
+		// Recent batches weigh more, so the size follows a table whose records change in width
+		self.average = Some(match self.average {
+			Some(average) => (average + size) / 2,
+			None => size,
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn adapts_to_the_size_of_entries() {
+		let mut size = BatchSize::new(50);
+		assert_eq!(size.get(), 50);
+		// Narrow entries are read in large batches, up to the maximum
+		size.observe(&[(vec![0; 8], vec![0; 8])]);
+		assert_eq!(size.get(), MAX_BATCH_SIZE);
+		// Wide entries are read in small batches, but never empty ones
+		let mut size = BatchSize::new(50);
+		size.observe(&[(vec![0; 8], vec![0; BATCH_BYTE_BUDGET * 2])]);
+		assert_eq!(size.get(), 1);
+		// Entries which fit the budget a few times over
+		let mut size = BatchSize::new(50);
+		size.observe(&[(Vec::new(), vec![0; BATCH_BYTE_BUDGET / 4])]);
+		assert_eq!(size.get(), 4);
+	}
+}
@@ -0,0 +1,276 @@
+//! Binary blobs which are stored alongside records
+//!
+//! A blob is split into chunks of [`BLOB_CHUNK_SIZE`] bytes, which are stored under
+//! consecutive keys of the table of its record, so that neither writing nor reading a
+//! blob holds it in memory as a whole. An empty blob is stored as a single empty chunk.
+//!
+//! The chunks are written in a number of bounded transactions, under a new version of
+//! the blob. The version is committed as the head of the blob once all of its chunks are
+//! stored, so that a blob which is still being written, or which failed part way, is
+//! never read back in place of the blob it replaces.
+
+use super::Datastore;
+use super::Transaction;
+use crate::cnf::BLOB_CHUNK_SIZE;
+use crate::err::Error;
+use crate::key;
+use crate::sql::Thing;
+use channel::Sender;
+use futures::io::AsyncRead;
+use futures::io::AsyncReadExt;
+
+/// The number of chunks which are read from the datastore at once
+const BATCH_SIZE: u32 = 16;
+
+/// The number of chunks which are written to, or removed from, the datastore in each transaction
+const WRITE_BATCH_SIZE: usize = 16;
+
+/// Stores the blob of a record, replacing any blob it already has, and returns its size
+pub(super) async fn put<R>(
+	ds: &Datastore,
+	ns: &str,
+	db: &str,
+	rid: &Thing,
+	reader: &mut R,
+) -> Result<u64, Error>
+where
+	R: AsyncRead + Unpin + ?Sized,
+{
+	let v = rand::random();
+	let res = match write(ds, ns, db, rid, v, reader).await {
+		Ok(size) => commit(ds, ns, db, rid, v).await.map(|old| (size, old)),
+		Err(e) => Err(e),
+	};
+	match res {
+		Ok((size, old)) => {
+			// Remove the chunks of the blob which was replaced
+			if let Some(old) = old {
+				remove(ds, ns, db, rid, old).await?;
+			}
+			Ok(size)
+		}
+		Err(e) => {
+			// Remove the chunks of the blob which failed to be written
+			remove(ds, ns, db, rid, v).await?;
+			Err(e)
+		}
+	}
+}
+
+/// Writes the chunks of a version of a blob, in bounded transactions
+async fn write<R>(
+	ds: &Datastore,
+	ns: &str,
+	db: &str,
+	rid: &Thing,
+	v: u64,
+	reader: &mut R,
+) -> Result<u64, Error>
+where
+	R: AsyncRead + Unpin + ?Sized,
+{
+	let mut size = 0;
+	let mut n = 0;
+	let mut done = false;
+	while !done {
+		// Read a batch of chunks first, so that the transaction isn't held open while reading
+		let mut batch = Vec::with_capacity(WRITE_BATCH_SIZE);
+		while !done && batch.len() < WRITE_BATCH_SIZE {
+			let mut chunk = vec![0; BLOB_CHUNK_SIZE];
+			let mut len = 0;
+			// Fill the chunk, as readers may return fewer bytes than were asked for
+			while len < chunk.len() {
+				match reader.read(&mut chunk[len..]).await? {
+					0 => break,
+					read => len += read,
+				}
+			}
+			chunk.truncate(len);
+			done = len < BLOB_CHUNK_SIZE;
+			// An empty chunk is only stored for an empty blob
+			if len > 0 || (n == 0 && batch.is_empty()) {
+				batch.push(chunk);
+			}
+		}
+		if batch.is_empty() {
+			break;
+		}
+		let mut txn = ds.transaction(true, false).await?;
+		for chunk in batch {
+			size += chunk.len() as u64;
+			txn.set(key::table::bb::new(ns, db, &rid.tb, &rid.id, v, n), chunk).await?;
+			n += 1;
+		}
+		txn.commit().await?;
+	}
+	Ok(size)
+}
+
+/// Commits a version as the head of a blob, returning the version which it replaced
+async fn commit(
+	ds: &Datastore,
+	ns: &str,
+	db: &str,
+	rid: &Thing,
+	v: u64,
+) -> Result<Option<u64>, Error> {
+	let mut txn = ds.transaction(true, false).await?;
+	let old = head(&mut txn, ns, db, rid).await?;
+	txn.set(key::table::bh::new(ns, db, &rid.tb, &rid.id), v.to_be_bytes().to_vec()).await?;
+	txn.commit().await?;
+	Ok(old)
+}
+
+/// Removes the chunks of a version of a blob, in bounded transactions
+async fn remove(ds: &Datastore, ns: &str, db: &str, rid: &Thing, v: u64) -> Result<(), Error> {
+	let beg = key::table::bb::vsprefix(ns, db, &rid.tb, &rid.id, v);
+	let end = key::table::bb::vssuffix(ns, db, &rid.tb, &rid.id, v);
+	loop {
+		let mut txn = ds.transaction(true, false).await?;
+		let res = txn.scan(beg.clone()..end.clone(), WRITE_BATCH_SIZE as u32).await?;
+		if res.is_empty() {
+			txn.cancel().await?;
+			break;
+		}
+		for (k, _) in res {
+			txn.del(k).await?;
+		}
+		txn.commit().await?;
+	}
+	Ok(())
+}
+
+/// The committed version of the blob of a record, if it has one
+async fn head(
+	txn: &mut Transaction,
+	ns: &str,
+	db: &str,
+	rid: &Thing,
+) -> Result<Option<u64>, Error> {
+	let val = txn.get(key::table::bh::new(ns, db, &rid.tb, &rid.id)).await?;
+	Ok(val.and_then(|v| Some(u64::from_be_bytes(v.try_into().ok()?))))
+}
+
+/// Checks whether a record has a blob
+pub(super) async fn exists(
+	txn: &mut Transaction,
+	ns: &str,
+	db: &str,
+	rid: &Thing,
+) -> Result<bool, Error> {
+	txn.exi(key::table::bh::new(ns, db, &rid.tb, &rid.id)).await
+}
+
+/// Sends the chunks of the blob of a record, in order
+pub(super) async fn get(
+	txn: &mut Transaction,
+	ns: &str,
+	db: &str,
+	rid: &Thing,
+	chn: Sender<Vec<u8>>,
+) -> Result<(), Error> {
+	let Some(v) = head(txn, ns, db, rid).await? else {
+		return Ok(());
+	};
+	let mut beg = key::table::bb::vsprefix(ns, db, &rid.tb, &rid.id, v);
+	let end = key::table::bb::vssuffix(ns, db, &rid.tb, &rid.id, v);
+	loop {
+		let res = txn.scan(beg.clone()..end.clone(), BATCH_SIZE).await?;
+		let Some((last, _)) = res.last() else {
+			break;
+		};
+		// Continue after the last chunk of this batch
+		beg = last.clone();
+		beg.push(0x00);
+		for (_, chunk) in res {
+			chn.send(chunk).await?;
+		}
+	}
+	Ok(())
+}
+
+/// Removes the blob of a record, if it has one, along with any versions of it which are
+/// still being written
+pub(crate) async fn delete(
+	txn: &mut Transaction,
+	ns: &str,
+	db: &str,
+	rid: &Thing,
+) -> Result<(), Error> {
+	txn.del(key::table::bh::new(ns, db, &rid.tb, &rid.id)).await?;
+	let beg = key::table::bb::prefix(ns, db, &rid.tb, &rid.id);
+	let end = key::table::bb::suffix(ns, db, &rid.tb, &rid.id);
+	txn.delr(beg..end, u32::MAX).await
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::dbs::Session;
+	use crate::err::Error;
+	use crate::key;
+	use crate::kvs::Datastore;
+	use crate::sql::thing;
+	use futures::TryStreamExt;
+	use std::io;
+
+	async fn read(ds: &Datastore, ses: &Session, rid: &str) -> Result<Vec<u8>, Error> {
+		let (tx, rx) = channel::unbounded();
+		ds.get_blob(ses, &thing(rid).unwrap(), tx).await?.await?;
+		let mut out = Vec::new();
+		while let Ok(chunk) = rx.try_recv() {
+			out.extend(chunk);
+		}
+		Ok(out)
+	}
+
+	#[tokio::test]
+	async fn stores_blobs_in_chunks() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let rid = thing("file:one").unwrap();
+		// A blob which doesn't fill its last chunk
+		let data: Vec<u8> = (0..super::BLOB_CHUNK_SIZE * 2 + 10).map(|i| i as u8).collect();
+		let size = ds.put_blob(&ses, &rid, &mut data.as_slice()).await.unwrap();
+		assert_eq!(size, data.len() as u64);
+		assert_eq!(read(&ds, &ses, "file:one").await.unwrap(), data);
+		// A smaller blob replaces all of the chunks of the previous one
+		ds.put_blob(&ses, &rid, &mut &b"small"[..]).await.unwrap();
+		assert_eq!(read(&ds, &ses, "file:one").await.unwrap(), b"small");
+		// An empty blob is still a blob
+		ds.put_blob(&ses, &rid, &mut &b""[..]).await.unwrap();
+		assert_eq!(read(&ds, &ses, "file:one").await.unwrap(), b"");
+		assert!(matches!(read(&ds, &ses, "file:two").await, Err(Error::BlobNotFound { .. })));
+	}
+
+	#[tokio::test]
+	async fn keeps_the_previous_blob_when_a_write_fails() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let rid = thing("file:one").unwrap();
+		ds.put_blob(&ses, &rid, &mut &b"first"[..]).await.unwrap();
+		// A blob which fails after some of its chunks were committed
+		let chunks = vec![
+			Ok(vec![0; super::BLOB_CHUNK_SIZE * (super::WRITE_BATCH_SIZE + 1)]),
+			Err(io::Error::new(io::ErrorKind::Other, "connection lost")),
+		];
+		let mut reader = futures::stream::iter(chunks).into_async_read();
+		assert!(ds.put_blob(&ses, &rid, &mut reader).await.is_err());
+		assert_eq!(read(&ds, &ses, "file:one").await.unwrap(), b"first");
+		// None of the chunks of the failed blob are left behind
+		let mut txn = ds.transaction(false, false).await.unwrap();
+		let beg = key::table::bb::prefix("test", "test", &rid.tb, &rid.id);
+		let end = key::table::bb::suffix("test", "test", &rid.tb, &rid.id);
+		assert_eq!(txn.scan(beg..end, 1000).await.unwrap().len(), 1);
+		txn.cancel().await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn blobs_are_removed_with_their_record() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let ses = Session::owner().with_ns("test").with_db("test");
+		ds.execute("CREATE file:one", &ses, None).await.unwrap();
+		ds.put_blob(&ses, &thing("file:one").unwrap(), &mut &b"data"[..]).await.unwrap();
+		ds.execute("DELETE file:one", &ses, None).await.unwrap();
+		assert!(matches!(read(&ds, &ses, "file:one").await, Err(Error::BlobNotFound { .. })));
+	}
+}
@@ -1,5 +1,7 @@
 use crate::idg::u32::U32;
 use crate::kvs::kv::Key;
+use crate::sql::statements::alter::Backfill;
+use crate::sql::statements::rebuild::Rebuild;
 use crate::sql::statements::DefineAnalyzerStatement;
 use crate::sql::statements::DefineDatabaseStatement;
 use crate::sql::statements::DefineEventStatement;
@@ -10,6 +12,7 @@ use crate::sql::statements::DefineNamespaceStatement;
 use crate::sql::statements::DefineParamStatement;
 use crate::sql::statements::DefineScopeStatement;
 use crate::sql::statements::DefineTableStatement;
+use crate::sql::statements::DefineTaskStatement;
 use crate::sql::statements::DefineTokenStatement;
 use crate::sql::statements::DefineUserStatement;
 use crate::sql::statements::LiveStatement;
@@ -27,6 +30,7 @@ pub enum Entry {
 	Tb(Arc<DefineTableStatement>),
 	// Multi definitions
 	Azs(Arc<[DefineAnalyzerStatement]>),
+	Bfs(Arc<[Backfill]>),
 	Dbs(Arc<[DefineDatabaseStatement]>),
 	Dts(Arc<[DefineTokenStatement]>),
 	Dus(Arc<[DefineUserStatement]>),
@@ -40,7 +44,9 @@ pub enum Entry {
 	Nts(Arc<[DefineTokenStatement]>),
 	Nus(Arc<[DefineUserStatement]>),
 	Pas(Arc<[DefineParamStatement]>),
+	Rbs(Arc<[Rebuild]>),
 	Scs(Arc<[DefineScopeStatement]>),
+	Tas(Arc<[DefineTaskStatement]>),
 	Sts(Arc<[DefineTokenStatement]>),
 	Tbs(Arc<[DefineTableStatement]>),
 	// Sequences
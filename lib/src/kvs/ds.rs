@@ -1,37 +1,97 @@
+use super::backup;
+use super::backup::Checkpoint;
+use super::blob;
+#[cfg(feature = "failpoints")]
+use super::failpoint::Failpoints;
+#[cfg(feature = "kv-mem")]
+use super::mem::MemSnapshot;
+use super::pinned::Pinned;
+use super::pinned::TableCache;
+use super::record;
+use super::replica::Replica;
+use super::rollup;
+#[cfg(feature = "cron-schedules")]
+use super::schedule::BackupSchedule;
 use super::tx::Transaction;
 use crate::cf;
+use crate::cf::TableMutations;
+use crate::cnf::BACKFILL_BATCH_SIZE;
+use crate::cnf::REBUILD_BATCH_SIZE;
+use crate::cnf::RETENTION_BATCH_SIZE;
+use crate::cnf::ROLLUP_BATCH_SIZE;
+use crate::cnf::TIERING_BATCH_SIZE;
 use crate::ctx::Context;
 use crate::dbs::node::Timestamp;
+use crate::dbs::rewrite;
 use crate::dbs::Attach;
 use crate::dbs::Capabilities;
+use crate::dbs::Denials;
 use crate::dbs::Executor;
+use crate::dbs::HttpQuota;
+use crate::dbs::HttpUsage;
 use crate::dbs::Notification;
 use crate::dbs::Options;
 use crate::dbs::Response;
+use crate::dbs::RewriteRule;
 use crate::dbs::Session;
+use crate::dbs::Telemetry;
+use crate::dbs::TelemetryEvent;
+use crate::dbs::TelemetryKind;
 use crate::dbs::Variables;
+use crate::dbs::WriteLimit;
+use crate::dbs::WriteThrottle;
+use crate::doc::has_index_entry;
+use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::ResourceKind;
-use crate::iam::{Action, Auth, Error as IamError, Role};
+use crate::iam::{Action, Auth, Error as IamError, Level, Role};
+use crate::idx::ft::analyzer::custom::Registry;
+use crate::idx::Analyzer;
 use crate::key::root::hb::Hb;
 use crate::opt::auth::Root;
 use crate::sql;
+use crate::sql::statements::alter::Backfill;
+use crate::sql::statements::rebuild::Rebuild;
+use crate::sql::statements::DefineTaskStatement;
 use crate::sql::statements::DefineUserStatement;
+use crate::sql::statements::UpdateStatement;
 use crate::sql::Base;
+use crate::sql::Cond;
+use crate::sql::Data;
+use crate::sql::Datetime;
+use crate::sql::Expression;
+use crate::sql::Id;
+use crate::sql::Operator;
+use crate::sql::Output;
+use crate::sql::Param;
+use crate::sql::Rollup;
+use crate::sql::Statement;
+use crate::sql::Statements;
+use crate::sql::Thing;
+use crate::sql::Tiering;
 use crate::sql::Value;
+use crate::sql::Values;
+use crate::sql::Versionstamp;
 use crate::sql::{Query, Uuid};
 use crate::vs::Oracle;
 use channel::Receiver;
 use channel::Sender;
+use chrono::TimeZone;
+use chrono::Utc;
+use futures::io::AsyncRead;
+use futures::io::AsyncWrite;
 use futures::lock::Mutex;
 use futures::Future;
 use std::fmt;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::instrument;
 use tracing::trace;
+use trice::Instant;
 #[cfg(target_arch = "wasm32")]
 use wasmtimer::std::{SystemTime, UNIX_EPOCH};
 
@@ -61,13 +121,39 @@ pub struct Datastore {
 	query_timeout: Option<Duration>,
 	// The maximum duration timeout for running multiple statements in a transaction
 	transaction_timeout: Option<Duration>,
+	// The maximum number of values which the result of a statement holds
+	max_result_size: Option<usize>,
+	// Whether sessions may report the permission clauses which filtered records
+	denials: bool,
 	// Capabilities for this datastore
 	capabilities: Capabilities,
 	// The versionstamp oracle for this datastore.
 	// Used only in some datastores, such as tikv.
 	versionstamp_oracle: Arc<Mutex<Oracle>>,
+	// The records of the tables which are defined with CACHE FULL
+	table_cache: TableCache,
 	// Whether this datastore enables live query notifications to subscribers
 	notification_channel: Option<(Sender<Notification>, Receiver<Notification>)>,
+	// The schedule on which this datastore takes backups of itself
+	#[cfg(feature = "cron-schedules")]
+	backup_schedule: Option<BackupSchedule>,
+	// The standby directory to which this datastore ships snapshots of itself
+	replica: Option<Replica>,
+	// The rules which rewrite queries before they are run
+	rewrite_rules: Vec<Arc<dyn RewriteRule>>,
+	// The full-text analyzers which are implemented in Rust
+	analyzers: Registry,
+	// The number of batches which table and range scans request ahead
+	read_ahead: usize,
+	// The outbound HTTP usage of each database
+	http_usage: Arc<HttpUsage>,
+	// The write limits of the tables
+	write_throttle: Arc<WriteThrottle>,
+	// The subscribers to the internal events of this datastore
+	telemetry: Telemetry,
+	// The failures which are injected into the write paths
+	#[cfg(feature = "failpoints")]
+	failpoints: Failpoints,
 }
 
 /// We always want to be circulating the live query information
@@ -82,12 +168,19 @@ pub(super) enum Inner {
 	RocksDB(super::rocksdb::Datastore),
 	#[cfg(feature = "kv-speedb")]
 	SpeeDB(super::speedb::Datastore),
+	#[cfg(feature = "kv-sled")]
+	Sled(super::sled::Datastore),
+	#[cfg(feature = "kv-redb")]
+	Redb(super::redb::Datastore),
 	#[cfg(feature = "kv-indxdb")]
 	IndxDB(super::indxdb::Datastore),
+	#[cfg(feature = "kv-opfs")]
+	Opfs(super::opfs::Datastore),
 	#[cfg(feature = "kv-tikv")]
 	TiKV(super::tikv::Datastore),
 	#[cfg(feature = "kv-fdb")]
 	FoundationDB(super::fdb::Datastore),
+	Custom(Arc<dyn super::Backend>),
 }
 
 impl fmt::Display for Datastore {
@@ -100,12 +193,19 @@ impl fmt::Display for Datastore {
 			Inner::RocksDB(_) => write!(f, "rocksdb"),
 			#[cfg(feature = "kv-speedb")]
 			Inner::SpeeDB(_) => write!(f, "speedb"),
+			#[cfg(feature = "kv-sled")]
+			Inner::Sled(_) => write!(f, "sled"),
+			#[cfg(feature = "kv-redb")]
+			Inner::Redb(_) => write!(f, "redb"),
 			#[cfg(feature = "kv-indxdb")]
 			Inner::IndxDB(_) => write!(f, "indxdb"),
+			#[cfg(feature = "kv-opfs")]
+			Inner::Opfs(_) => write!(f, "opfs"),
 			#[cfg(feature = "kv-tikv")]
 			Inner::TiKV(_) => write!(f, "tikv"),
 			#[cfg(feature = "kv-fdb")]
 			Inner::FoundationDB(_) => write!(f, "fdb"),
+			Inner::Custom(_) => write!(f, "custom"),
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
 		}
@@ -206,6 +306,34 @@ impl Datastore {
 				#[cfg(not(feature = "kv-speedb"))]
 				return Err(Error::Ds("Cannot connect to the `speedb` storage engine as it is not enabled in this build of SurrealDB".to_owned()));
 			}
+			// Parse and initiate a sled database
+			s if s.starts_with("sled:") => {
+				#[cfg(feature = "kv-sled")]
+				{
+					info!("Starting kvs store at {}", path);
+					let s = s.trim_start_matches("sled://");
+					let s = s.trim_start_matches("sled:");
+					let v = super::sled::Datastore::new(s).await.map(Inner::Sled);
+					info!("Started kvs store at {}", path);
+					v
+				}
+				#[cfg(not(feature = "kv-sled"))]
+				return Err(Error::Ds("Cannot connect to the `sled` storage engine as it is not enabled in this build of SurrealDB".to_owned()));
+			}
+			// Parse and initiate a redb database
+			s if s.starts_with("redb:") => {
+				#[cfg(feature = "kv-redb")]
+				{
+					info!("Starting kvs store at {}", path);
+					let s = s.trim_start_matches("redb://");
+					let s = s.trim_start_matches("redb:");
+					let v = super::redb::Datastore::new(s).await.map(Inner::Redb);
+					info!("Started kvs store at {}", path);
+					v
+				}
+				#[cfg(not(feature = "kv-redb"))]
+				return Err(Error::Ds("Cannot connect to the `redb` storage engine as it is not enabled in this build of SurrealDB".to_owned()));
+			}
 			// Parse and initiate an IndxDB database
 			s if s.starts_with("indxdb:") => {
 				#[cfg(feature = "kv-indxdb")]
@@ -220,6 +348,20 @@ impl Datastore {
 				#[cfg(not(feature = "kv-indxdb"))]
 				return Err(Error::Ds("Cannot connect to the `indxdb` storage engine as it is not enabled in this build of SurrealDB".to_owned()));
 			}
+			// Parse and initiate an OPFS database
+			s if s.starts_with("opfs:") => {
+				#[cfg(feature = "kv-opfs")]
+				{
+					info!("Starting kvs store at {}", path);
+					let s = s.trim_start_matches("opfs://");
+					let s = s.trim_start_matches("opfs:");
+					let v = super::opfs::Datastore::new(s).await.map(Inner::Opfs);
+					info!("Started kvs store at {}", path);
+					v
+				}
+				#[cfg(not(feature = "kv-opfs"))]
+				return Err(Error::Ds("Cannot connect to the `opfs` storage engine as it is not enabled in this build of SurrealDB".to_owned()));
+			}
 			// Parse and initiate a TiKV database
 			s if s.starts_with("tikv:") => {
 				#[cfg(feature = "kv-tikv")]
@@ -248,11 +390,18 @@ impl Datastore {
 				#[cfg(not(feature = "kv-fdb"))]
 				return Err(Error::Ds("Cannot connect to the `foundationdb` storage engine as it is not enabled in this build of SurrealDB".to_owned()));
 			}
-			// The datastore path is not valid
-			_ => {
-				info!("Unable to load the specified datastore {}", path);
-				Err(Error::Ds("Unable to load the specified datastore".into()))
-			}
+			// Parse and initiate a custom datastore
+			s => match super::backend::open(s).await {
+				Some(v) => {
+					info!("Started custom kvs store at {}", path);
+					v.map(Inner::Custom)
+				}
+				// The datastore path is not valid
+				None => {
+					info!("Unable to load the specified datastore {}", path);
+					Err(Error::Ds("Unable to load the specified datastore".into()))
+				}
+			},
 		};
 		// Set the properties on the datastore
 		inner.map(|inner| Self {
@@ -262,9 +411,23 @@ impl Datastore {
 			auth_enabled: false,
 			query_timeout: None,
 			transaction_timeout: None,
+			max_result_size: None,
+			denials: false,
 			notification_channel: None,
+			#[cfg(feature = "cron-schedules")]
+			backup_schedule: None,
+			replica: None,
+			rewrite_rules: Vec::new(),
 			capabilities: Capabilities::default(),
 			versionstamp_oracle: Arc::new(Mutex::new(Oracle::systime_counter())),
+			table_cache: TableCache::default(),
+			analyzers: Registry::default(),
+			read_ahead: 0,
+			http_usage: Arc::new(HttpUsage::default()),
+			write_throttle: Arc::new(WriteThrottle::default()),
+			telemetry: Telemetry::default(),
+			#[cfg(feature = "failpoints")]
+			failpoints: Failpoints::default(),
 		})
 	}
 
@@ -298,6 +461,28 @@ impl Datastore {
 		self
 	}
 
+	/// Set the maximum number of values which the result of a statement holds
+	///
+	/// Results which hold more values are cut short, and their responses are marked with a
+	/// [`ResultTruncated`](crate::dbs::ResultTruncated) notice, so that a query which selects far
+	/// more records than expected doesn't send them all to the client. Sessions which are set
+	/// with [`Session::with_unlimited_results`] are exempt from the limit.
+	pub fn with_max_result_size(mut self, size: Option<usize>) -> Self {
+		self.max_result_size = size;
+		self
+	}
+
+	/// Allow sessions to report the permission clauses which filtered records
+	///
+	/// Sessions which are set with [`Session::with_denials`] only report the permission
+	/// clauses when this is enabled. This exposes the table and field permissions of the
+	/// database to the session, so it should only be enabled by the owner of the datastore
+	/// when debugging.
+	pub fn with_denials(mut self, enabled: bool) -> Self {
+		self.denials = enabled;
+		self
+	}
+
 	/// Set whether authentication is enabled for this Datastore
 	pub fn with_auth_enabled(mut self, enabled: bool) -> Self {
 		self.auth_enabled = enabled;
@@ -310,6 +495,119 @@ impl Datastore {
 		self
 	}
 
+	/// Limit the outbound calls which each database can make with the `http::` functions
+	pub fn with_http_quota(mut self, quota: HttpQuota) -> Self {
+		self.http_usage = Arc::new(HttpUsage::new(quota));
+		self
+	}
+
+	/// Limit the writes to a table, so that a busy table can't starve the other tables
+	///
+	/// The limit applies to the table of this name in every namespace and database. Writes
+	/// which go over the limit fail with an error, which can be retried later.
+	pub fn with_write_limit(mut self, table: &str, limit: WriteLimit) -> Self {
+		self.write_throttle = Arc::new(self.write_throttle.with_limit(table, limit));
+		self
+	}
+
+	/// Request the next batches of table and range scans while the current batch is processed
+	///
+	/// The window is the number of batches which are read ahead, and defaults to none. This
+	/// hides some of the latency of remote storage engines, such as TiKV, during large selects,
+	/// at the cost of reading batches which are never used when a query stops early. Batches
+	/// are only read ahead for statements which don't write records.
+	pub fn with_read_ahead(mut self, window: usize) -> Self {
+		self.read_ahead = window;
+		self
+	}
+
+	/// The failpoints at which failures are injected into the write paths of this datastore
+	#[cfg(feature = "failpoints")]
+	pub fn failpoints(&self) -> &Failpoints {
+		&self.failpoints
+	}
+
+	/// Take backups of the datastore on a schedule
+	///
+	/// The schedule is checked every time the datastore ticks.
+	#[cfg(feature = "cron-schedules")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "cron-schedules")))]
+	pub fn with_backup_schedule(mut self, schedule: BackupSchedule) -> Self {
+		self.backup_schedule = Some(schedule);
+		self
+	}
+
+	/// Ship snapshots of the datastore to a standby directory at an interval
+	///
+	/// Each snapshot replaces the contents of the standby directory, which can then be opened
+	/// with the same storage engine to fail over. The snapshots hard-link the data files of the
+	/// datastore, so the standby directory should be on the same filesystem. This is only
+	/// supported by the RocksDB and SpeeDB storage engines, and is checked every time the
+	/// datastore ticks.
+	pub fn replicate_to(
+		mut self,
+		path: impl Into<PathBuf>,
+		interval: Duration,
+	) -> Result<Self, Error> {
+		match &self.inner {
+			#[cfg(feature = "kv-rocksdb")]
+			Inner::RocksDB(_) => (),
+			#[cfg(feature = "kv-speedb")]
+			Inner::SpeeDB(_) => (),
+			#[allow(unreachable_patterns)]
+			_ => return Err(Error::ReplicationUnsupported(self.to_string())),
+		}
+		#[allow(unreachable_code)]
+		{
+			self.replica = Some(Replica::new(path.into(), interval));
+			Ok(self)
+		}
+	}
+
+	/// Add a rule which rewrites queries before they are run
+	///
+	/// Rules are applied in the order in which they were added.
+	pub fn with_rewrite_rule(mut self, rule: impl RewriteRule) -> Self {
+		self.rewrite_rules.push(Arc::new(rule));
+		self
+	}
+
+	/// Register a full-text analyzer which is implemented in Rust
+	///
+	/// The analyzer can then be used by name in the `SEARCH ANALYZER` clause of a full-text
+	/// index, and takes precedence over an analyzer of the same name which is defined with
+	/// `DEFINE ANALYZER`. Registering an analyzer under an existing name replaces it, so the
+	/// indexes which use it should then be rebuilt.
+	///
+	/// ```rust,no_run
+	/// use surrealdb::idx::{AnalyzedTerm, Analyzer};
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	///
+	/// struct Words;
+	///
+	/// impl Analyzer for Words {
+	///     fn analyze(&self, text: &str) -> Vec<AnalyzedTerm> {
+	///         text.split_whitespace()
+	///             .map(|w| {
+	///                 let start = w.as_ptr() as usize - text.as_ptr() as usize;
+	///                 AnalyzedTerm::new(w.to_lowercase(), start..start + w.len())
+	///             })
+	///             .collect()
+	///     }
+	/// }
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?;
+	///     ds.register_analyzer("words", Words);
+	///     Ok(())
+	/// }
+	/// ```
+	pub fn register_analyzer(&self, name: &str, analyzer: impl Analyzer) {
+		self.analyzers.register(name, Arc::new(analyzer));
+	}
+
 	/// Is authentication enabled for this Datastore?
 	pub fn is_auth_enabled(&self) -> bool {
 		self.auth_enabled
@@ -667,11 +965,73 @@ impl Datastore {
 	pub async fn tick_at(&self, ts: u64) -> Result<(), Error> {
 		self.save_timestamp_for_versionstamp(ts).await?;
 		self.garbage_collect_stale_change_feeds(ts).await?;
+		// The background jobs don't depend on each other, so a failing job is logged, and
+		// doesn't stop the jobs after it from running
+		if let Err(e) = self.expire_records_at(ts).await {
+			warn!("Error expiring records: {e}");
+		}
+		if let Err(e) = self.enforce_retention_at(ts).await {
+			warn!("Error enforcing retention policies: {e}");
+		}
+		if let Err(e) = self.tier_records_at(ts).await {
+			warn!("Error moving records to cold storage: {e}");
+		}
+		if let Err(e) = self.rollup_tables().await {
+			warn!("Error updating rollup tables: {e}");
+		}
+		if let Err(e) = self.run_tasks_at(ts).await {
+			warn!("Error running scheduled tasks: {e}");
+		}
+		if let Err(e) = self.backfill_fields().await {
+			warn!("Error backfilling fields: {e}");
+		}
+		if let Err(e) = self.rebuild_indexes().await {
+			warn!("Error rebuilding indexes: {e}");
+		}
+		#[cfg(feature = "cron-schedules")]
+		if let Err(e) = self.run_backups_at(ts).await {
+			warn!("Error running scheduled backups: {e}");
+		}
+		if let Err(e) = self.replicate_at(ts).await {
+			warn!("Error replicating the datastore: {e}");
+		}
 		// TODO Add LQ GC
 		// TODO Add Node GC?
 		Ok(())
 	}
 
+	// run_backups_at takes a scheduled backup of the datastore, if one is due at the timestamp.
+	// A failed backup doesn't fail the tick, and is reported to the failure callback of the schedule instead.
+	#[cfg(feature = "cron-schedules")]
+	pub async fn run_backups_at(&self, ts: u64) -> Result<(), Error> {
+		if let Some(schedule) = &self.backup_schedule {
+			schedule.run_at(self, ts).await;
+		}
+		Ok(())
+	}
+
+	// replicate_at ships a snapshot of the datastore to the standby directory, if one is due at the timestamp.
+	// A failed snapshot doesn't fail the tick, and is shipped again at the next interval.
+	pub async fn replicate_at(&self, ts: u64) -> Result<(), Error> {
+		if let Some(replica) = &self.replica {
+			replica.run_at(self, ts).await;
+		}
+		Ok(())
+	}
+
+	/// Write a consistent snapshot of the datastore to a new directory
+	pub(crate) fn checkpoint(&self, path: &Path) -> Result<(), Error> {
+		#![allow(unused_variables)]
+		match &self.inner {
+			#[cfg(feature = "kv-rocksdb")]
+			Inner::RocksDB(v) => v.checkpoint(path),
+			#[cfg(feature = "kv-speedb")]
+			Inner::SpeeDB(v) => v.checkpoint(path),
+			#[allow(unreachable_patterns)]
+			_ => Err(Error::ReplicationUnsupported(self.to_string())),
+		}
+	}
+
 	// save_timestamp_for_versionstamp saves the current timestamp for the each database's current versionstamp.
 	pub async fn save_timestamp_for_versionstamp(&self, ts: u64) -> Result<(), Error> {
 		let mut tx = self.transaction(true, false).await?;
@@ -699,6 +1059,713 @@ impl Datastore {
 		Ok(())
 	}
 
+	// expire_records_at deletes the records in tables with a TTL, which expired at or before the timestamp.
+	// The records are deleted with DELETE statements, so that events and live queries see the deletions.
+	pub async fn expire_records_at(&self, ts: u64) -> Result<(), Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let mut expired = Vec::new();
+		let nses = tx.all_ns().await?;
+		for ns in nses.iter() {
+			let ns = ns.name.as_str();
+			let dbs = tx.all_db(ns).await?;
+			for db in dbs.iter() {
+				let db = db.name.as_str();
+				let tbs = tx.all_tb(ns, db).await?;
+				for tb in tbs.iter().filter(|tb| tb.ttl.is_some()) {
+					let beg = crate::key::table::ex::prefix(ns, db, &tb.name);
+					let end = crate::key::table::ex::suffix(ns, db, &tb.name, ts);
+					// TODO Make expiry batch size/limit configurable?
+					let mut ids = Vec::new();
+					for (k, _) in tx.scan(beg..end, 1000).await? {
+						let ex = crate::key::table::ex::Ex::decode(&k)?;
+						ids.push(Value::from(Thing::from((tb.name.0.as_str(), ex.id))));
+					}
+					if !ids.is_empty() {
+						expired.push((ns.to_owned(), db.to_owned(), ids));
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		for (ns, db, ids) in expired {
+			let sess = Session::owner().with_ns(&ns).with_db(&db);
+			// Each record is deleted on its own, so a record which can't be deleted doesn't
+			// keep the other expired records around
+			for id in ids {
+				let vars = map! { String::from("expired") => id.clone() };
+				let res = match self.execute("DELETE $expired", &sess, Some(vars)).await {
+					Ok(res) => res,
+					Err(e) => {
+						warn!("Error deleting expired record {id} in {ns}/{db}: {e}");
+						continue;
+					}
+				};
+				for res in res {
+					if let Err(e) = res.result {
+						warn!("Error deleting expired record {id} in {ns}/{db}: {e}");
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	// enforce_retention_at deletes, or archives, the records in tables with a retention policy, which are
+	// older than the retention window at the timestamp. Each table is processed one batch per tick, so
+	// that a large backlog of old records is worked through gradually. The age of the records is compared
+	// in the WHERE clause, so that an index on the field is used to find them.
+	pub async fn enforce_retention_at(&self, ts: u64) -> Result<(), Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let mut policies = Vec::new();
+		let nses = tx.all_ns().await?;
+		for ns in nses.iter() {
+			let ns = ns.name.as_str();
+			let dbs = tx.all_db(ns).await?;
+			for db in dbs.iter() {
+				let db = db.name.as_str();
+				let tbs = tx.all_tb(ns, db).await?;
+				for tb in tbs.iter() {
+					if let Some(rt) = &tb.retention {
+						policies.push((ns.to_owned(), db.to_owned(), tb.name.clone(), rt.clone()));
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		for (ns, db, tb, rt) in policies {
+			let Some(cutoff) = ts
+				.checked_sub(rt.window.as_secs())
+				.and_then(|secs| Utc.timestamp_opt(secs as i64, 0).single())
+			else {
+				continue;
+			};
+			let field = &rt.field;
+			let mut sql = format!(
+				"BEGIN TRANSACTION; LET $expired = SELECT * FROM {tb} WHERE {field} < $cutoff LIMIT {RETENTION_BATCH_SIZE};"
+			);
+			if let Some(archive) = &rt.archive {
+				sql += &format!(
+					" INSERT INTO {archive} (SELECT *, meta::id(id) AS id FROM $expired);"
+				);
+			}
+			sql += " DELETE $expired.id; COMMIT TRANSACTION;";
+			let sess = Session::owner().with_ns(&ns).with_db(&db);
+			let vars = map! { String::from("cutoff") => Value::from(cutoff) };
+			let res = match self.execute(&sql, &sess, Some(vars)).await {
+				Ok(res) => res,
+				Err(e) => {
+					warn!("Error enforcing the retention policy of table {tb} in {ns}/{db}: {e}");
+					continue;
+				}
+			};
+			for res in res {
+				if let Err(e) = res.result {
+					warn!("Error enforcing the retention policy of table {tb} in {ns}/{db}: {e}");
+				}
+			}
+		}
+		Ok(())
+	}
+
+	// tier_records_at moves the records in tables with a tiering policy, which are older than the
+	// tiering window at the timestamp, to cold storage. Cold records are compressed under a cold storage
+	// key of their own, and are read through from there, so they remain transparently queryable, and are
+	// stored in place again when they are next written.
+	// Each table is swept one batch per tick, continuing from where the previous batch left off.
+	pub async fn tier_records_at(&self, ts: u64) -> Result<(), Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let mut policies = Vec::new();
+		let nses = tx.all_ns().await?;
+		for ns in nses.iter() {
+			let ns = ns.name.as_str();
+			let dbs = tx.all_db(ns).await?;
+			for db in dbs.iter() {
+				let db = db.name.as_str();
+				let tbs = tx.all_tb(ns, db).await?;
+				for tb in tbs.iter() {
+					if let Some(ti) = &tb.tiering {
+						policies.push((ns.to_owned(), db.to_owned(), tb.name.to_raw(), ti.clone()));
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		for (ns, db, tb, ti) in policies {
+			let Some(cutoff) = ts
+				.checked_sub(ti.after.as_secs())
+				.and_then(|secs| Utc.timestamp_opt(secs as i64, 0).single())
+			else {
+				continue;
+			};
+			// A batch which conflicts with another write is tried again on the next tick
+			if let Err(e) = self.tier_batch(&ns, &db, &tb, &ti, cutoff.into()).await {
+				warn!("Error moving records of table {tb} in {ns}/{db} to cold storage: {e}");
+			}
+		}
+		Ok(())
+	}
+
+	// tier_batch moves the records in the next batch of the sweep of a table, which are older
+	// than the cutoff, to cold storage, and records where the next batch starts.
+	async fn tier_batch(
+		&self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		ti: &Tiering,
+		cutoff: Datetime,
+	) -> Result<(), Error> {
+		let mut tx = self.transaction(true, false).await?;
+		let cursor = crate::key::table::tc::new(ns, db, tb);
+		let beg = match tx.get(cursor.clone()).await? {
+			Some(mut key) => {
+				key.push(0x00);
+				key
+			}
+			None => crate::key::thing::prefix(ns, db, tb),
+		};
+		let end = crate::key::thing::suffix(ns, db, tb);
+		let batch = tx.scan(beg..end, TIERING_BATCH_SIZE).await?;
+		for (k, v) in batch.iter() {
+			// Records which reference shared values are left as they are, to keep the references
+			if record::is_cold(v) || record::is_shared(v) {
+				continue;
+			}
+			let val = record::decode(v)?;
+			if let Value::Datetime(dt) = val.pick(&ti.field) {
+				if dt < cutoff {
+					let id = crate::key::thing::Thing::decode(k)?.id;
+					record::freeze(&mut tx, ns, db, tb, &id, &val).await?;
+				}
+			}
+		}
+		// Once the end of the table is reached, the next sweep starts from the beginning
+		match batch.last() {
+			Some((k, _)) if batch.len() == TIERING_BATCH_SIZE as usize => {
+				tx.set(cursor, k.clone()).await?
+			}
+			_ => tx.del(cursor).await?,
+		}
+		tx.commit().await
+	}
+
+	// rollup_tables aggregates the changes to the source tables of rollup tables, which were recorded in
+	// their change feeds, into the buckets of the rollup tables. Each rollup table applies one batch of the
+	// change feed per tick, in the same transaction which records how far it has got, so that each change
+	// is applied exactly once, even when a batch fails or many nodes tick at the same time.
+	pub async fn rollup_tables(&self) -> Result<(), Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let mut rollups = Vec::new();
+		let nses = tx.all_ns().await?;
+		for ns in nses.iter() {
+			let ns = ns.name.as_str();
+			let dbs = tx.all_db(ns).await?;
+			for db in dbs.iter() {
+				let db = db.name.as_str();
+				let tbs = tx.all_tb(ns, db).await?;
+				for tb in tbs.iter() {
+					if let Some(ru) = &tb.rollup {
+						rollups.push((ns.to_owned(), db.to_owned(), tb.name.to_raw(), ru.clone()));
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		for (ns, db, tb, ru) in rollups {
+			// A batch which conflicts with another write is tried again on the next tick
+			if let Err(e) = self.rollup_batch(&ns, &db, &tb, &ru).await {
+				warn!(
+					"Error aggregating the changes of table {} into table {tb} in {ns}/{db}: {e}",
+					ru.from
+				);
+			}
+		}
+		Ok(())
+	}
+
+	// rollup_batch applies the next batch of the change feed of a database to a rollup table,
+	// and records the last change feed entry which it read.
+	async fn rollup_batch(&self, ns: &str, db: &str, tb: &str, ru: &Rollup) -> Result<(), Error> {
+		let mut tx = self.transaction(true, false).await?;
+		let cursor = crate::key::table::ru::new(ns, db, tb);
+		let beg = match tx.get(cursor.clone()).await? {
+			Some(mut key) => {
+				key.push(0x00);
+				key
+			}
+			None => crate::key::change::prefix(ns, db),
+		};
+		let end = crate::key::change::suffix(ns, db);
+		let batch = tx.scan(beg..end, ROLLUP_BATCH_SIZE).await?;
+		for (k, v) in batch.iter() {
+			let cf = crate::key::change::Cf::decode(k)?;
+			if cf.tb != ru.from.0 {
+				continue;
+			}
+			let muts: TableMutations = v.clone().into();
+			for m in muts.1 {
+				rollup::apply(&mut tx, ns, db, tb, ru, m).await?;
+			}
+		}
+		if let Some((k, _)) = batch.last() {
+			tx.set(cursor, k.clone()).await?;
+		}
+		tx.commit().await
+	}
+
+	// run_tasks_at runs the tasks defined with DEFINE TASK which are due at the timestamp.
+	// Each run of a task is claimed in its own transaction before it starts, so that when many
+	// nodes share a TiKV or FoundationDB cluster, the conflicting claims of the other nodes fail.
+	pub async fn run_tasks_at(&self, ts: u64) -> Result<(), Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let mut tasks = Vec::new();
+		let nses = tx.all_ns().await?;
+		for ns in nses.iter() {
+			let ns = ns.name.as_str();
+			let dbs = tx.all_db(ns).await?;
+			for db in dbs.iter() {
+				let db = db.name.as_str();
+				for ta in tx.all_db_tasks(ns, db).await?.iter() {
+					tasks.push((ns.to_owned(), db.to_owned(), ta.clone()));
+				}
+			}
+		}
+		tx.cancel().await?;
+		for (ns, db, ta) in tasks {
+			// A task which can't be claimed, or fails to run, doesn't stop the other tasks
+			match self.claim_task_at(&ns, &db, &ta, ts).await {
+				Ok(true) => (),
+				Ok(false) => continue,
+				Err(e) => {
+					warn!("Error running task {} in {ns}/{db}: {e}", ta.name);
+					continue;
+				}
+			}
+			// The task runs with the current permissions of the user who defined it
+			let sess = match &ta.auth {
+				Some(auth) => match self.current_auth(auth).await {
+					Ok(auth) => Session {
+						au: Arc::new(auth),
+						..Session::default()
+					}
+					.with_ns(&ns)
+					.with_db(&db),
+					Err(e) => {
+						warn!("Error running task {} in {ns}/{db}: {e}", ta.name);
+						continue;
+					}
+				},
+				None => Session::for_level(Level::Database(ns.clone(), db.clone()), Role::Editor),
+			};
+			let ast = Query(Statements(ta.then.iter().cloned().map(Statement::Value).collect()));
+			let res = match self.process(ast, &sess, None).await {
+				Ok(res) => res,
+				Err(e) => {
+					warn!("Error running task {} in {ns}/{db}: {e}", ta.name);
+					continue;
+				}
+			};
+			for res in res {
+				if let Err(e) = res.result {
+					warn!("Error running task {} in {ns}/{db}: {e}", ta.name);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	// current_auth resolves the current rights of the user who a background job runs as.
+	async fn current_auth(&self, auth: &Auth) -> Result<Auth, Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let res = auth.current(&mut tx).await;
+		tx.cancel().await?;
+		res
+	}
+
+	// claim_task_at schedules the next run of a task, if the task is due at the timestamp.
+	// Returns false if the task is not due, or if another node claimed this run of the task.
+	async fn claim_task_at(
+		&self,
+		ns: &str,
+		db: &str,
+		ta: &DefineTaskStatement,
+		ts: u64,
+	) -> Result<bool, Error> {
+		let mut tx = self.transaction(true, false).await?;
+		let key = crate::key::database::tr::new(ns, db, &ta.name);
+		let next = match tx.get(key.clone()).await? {
+			Some(v) => Some(
+				v.as_slice().try_into().map(u64::from_be_bytes).map_err(|_| Error::Unreachable)?,
+			),
+			None => None,
+		};
+		match next {
+			Some(next) if next > ts => {
+				tx.cancel().await?;
+				return Ok(false);
+			}
+			// A task with a cron schedule first runs at the next time in its schedule
+			None if ta.cron.is_some() => {
+				tx.set(key, ta.next_run(ts)?.to_be_bytes().to_vec()).await?;
+				tx.commit().await?;
+				return Ok(false);
+			}
+			_ => (),
+		}
+		tx.set(key, ta.next_run(ts)?.to_be_bytes().to_vec()).await?;
+		Ok(tx.commit().await.is_ok())
+	}
+
+	// backfill_fields sets the fields which were added with ALTER TABLE ... BACKFILL on a batch
+	// of existing records for each backfill. Each batch is updated in its own transaction,
+	// so that a backfill does not need to lock the whole table at once. The backfills share a
+	// budget of records on each tick, so that however many there are, they don't hold up the
+	// other writes to the datastore.
+	pub async fn backfill_fields(&self) -> Result<(), Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let mut backfills = Vec::new();
+		let nses = tx.all_ns().await?;
+		for ns in nses.iter() {
+			let ns = ns.name.as_str();
+			let dbs = tx.all_db(ns).await?;
+			for db in dbs.iter() {
+				let db = db.name.as_str();
+				let tbs = tx.all_tb(ns, db).await?;
+				for tb in tbs.iter() {
+					for bf in tx.all_tb_backfills(ns, db, &tb.name).await?.iter() {
+						backfills.push((
+							ns.to_owned(),
+							db.to_owned(),
+							tb.name.to_raw(),
+							bf.clone(),
+						));
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		let mut budget = BACKFILL_BATCH_SIZE;
+		for (ns, db, tb, bf) in backfills {
+			if budget == 0 {
+				break;
+			}
+			let field = bf.field.to_string();
+			// A batch which fails is tried again on the next tick, without stopping the others
+			let ids = match self.backfill_batch(&ns, &db, &tb, &bf, budget).await {
+				Ok(ids) => ids,
+				Err(e) => {
+					warn!("Error backfilling field {field} of table {tb} in {ns}/{db}: {e}");
+					continue;
+				}
+			};
+			let done = ids.len() < budget as usize;
+			budget -= ids.len() as u32;
+			if let Err(e) = self.advance_backfill(&ns, &db, &tb, bf, ids, done).await {
+				warn!("Error backfilling field {field} of table {tb} in {ns}/{db}: {e}");
+			}
+		}
+		Ok(())
+	}
+
+	// backfill_batch sets the field of a backfill on the next batch of records, with the current
+	// permissions of the user who altered the table, and returns the ids of the batch.
+	async fn backfill_batch(
+		&self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		bf: &Backfill,
+		limit: u32,
+	) -> Result<Vec<Id>, Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let beg = match &bf.cursor {
+			Some(id) => {
+				let mut key = crate::key::thing::new(ns, db, tb, id).encode()?;
+				key.push(0x00);
+				key
+			}
+			None => crate::key::thing::prefix(ns, db, tb),
+		};
+		let end = crate::key::thing::suffix(ns, db, tb);
+		let res = tx.scan(beg..end, limit).await;
+		// The backfill runs with the current permissions of the user who altered the table
+		let auth = match (&res, &bf.auth) {
+			(Ok(_), Some(auth)) => Some(auth.current(&mut tx).await),
+			_ => None,
+		};
+		tx.cancel().await?;
+		let mut ids = Vec::new();
+		for (k, _) in res? {
+			ids.push(crate::key::thing::Thing::decode(&k)?.id);
+		}
+		if ids.is_empty() {
+			return Ok(ids);
+		}
+		let sess = match auth.transpose()? {
+			Some(auth) => Session {
+				au: Arc::new(auth),
+				..Session::default()
+			}
+			.with_ns(ns)
+			.with_db(db),
+			None => Session::for_level(Level::Database(ns.to_owned(), db.to_owned()), Role::Editor),
+		};
+		// Records which were deleted in the meantime are not created again, and records
+		// which were given a value for the field in the meantime are left as they are
+		let cond = Expression::Binary {
+			l: Value::from(Expression::Binary {
+				l: Value::from(Param::from("this")),
+				o: Operator::NotEqual,
+				r: Value::None,
+			}),
+			o: Operator::And,
+			r: Value::from(Expression::Binary {
+				l: Value::from(bf.field.clone()),
+				o: Operator::Equal,
+				r: Value::None,
+			}),
+		};
+		let stm = UpdateStatement {
+			what: Values(ids.iter().map(|id| Thing::from((tb, id.clone())).into()).collect()),
+			data: Some(Data::SetExpression(vec![(
+				bf.field.clone(),
+				Operator::Equal,
+				bf.default.clone(),
+			)])),
+			cond: Some(Cond(Value::from(cond))),
+			output: Some(Output::None),
+			..Default::default()
+		};
+		let ast = Query(Statements(vec![Statement::Update(stm)]));
+		for res in self.process(ast, &sess, None).await? {
+			res.result?;
+		}
+		Ok(ids)
+	}
+
+	// advance_backfill records the progress of a backfill after a batch of records has been
+	// updated, or removes the backfill when there are no more records. The progress is only
+	// recorded if no other node has advanced the backfill in the meantime.
+	async fn advance_backfill(
+		&self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		bf: Backfill,
+		ids: Vec<Id>,
+		done: bool,
+	) -> Result<(), Error> {
+		let mut tx = self.transaction(true, false).await?;
+		let fd = bf.field.to_string();
+		let key = crate::key::table::bf::new(ns, db, tb, &fd);
+		let res = if done {
+			tx.delc(key, Some(bf)).await
+		} else {
+			let next = Backfill {
+				processed: bf.processed + ids.len() as u64,
+				cursor: ids.into_iter().last(),
+				..bf.clone()
+			};
+			tx.putc(key, next, Some(bf)).await
+		};
+		match res {
+			Ok(_) => tx.commit().await,
+			Err(Error::TxConditionNotMet) => tx.cancel().await,
+			Err(e) => {
+				tx.cancel().await?;
+				Err(e)
+			}
+		}
+	}
+
+	// rebuild_indexes indexes a batch of existing records for each index which is being rebuilt
+	// with REBUILD INDEX. Each batch is indexed in its own transaction, so that writes to the
+	// table are not blocked for the duration of the rebuild.
+	pub async fn rebuild_indexes(&self) -> Result<(), Error> {
+		let mut tx = self.transaction(false, false).await?;
+		let mut rebuilds = Vec::new();
+		let nses = tx.all_ns().await?;
+		for ns in nses.iter() {
+			let ns = ns.name.as_str();
+			let dbs = tx.all_db(ns).await?;
+			for db in dbs.iter() {
+				let db = db.name.as_str();
+				let tbs = tx.all_tb(ns, db).await?;
+				for tb in tbs.iter() {
+					for rb in tx.all_tb_rebuilds(ns, db, &tb.name).await?.iter() {
+						rebuilds.push((ns.to_owned(), db.to_owned(), tb.name.to_raw(), rb.clone()));
+					}
+				}
+			}
+		}
+		tx.cancel().await?;
+		for (ns, db, tb, rb) in rebuilds {
+			let ix = rb.index.clone();
+			// A batch which fails is tried again on the next tick, without stopping the others
+			if let Err(e) = self.rebuild_index_batch(&ns, &db, &tb, rb).await {
+				warn!("Error rebuilding index {ix} on table {tb} in {ns}/{db}: {e}");
+			}
+		}
+		Ok(())
+	}
+
+	// rebuild_index_batch advances an index rebuild by one batch, and records the progress in
+	// the same transaction, or removes the rebuild once it has completed. The records are
+	// counted first, then indexed, and finally the stale entries of a unique or non-unique
+	// index are pruned. The batch is discarded if another node has advanced the rebuild in the
+	// meantime.
+	async fn rebuild_index_batch(
+		&self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		rb: Rebuild,
+	) -> Result<(), Error> {
+		let sess = Session::owner().with_ns(ns).with_db(db);
+		// Force the records to be indexed, without processing fields, events or tables
+		let opt = Options::default()
+			.with_id(self.id.0)
+			.with_ns(sess.ns())
+			.with_db(sess.db())
+			.with_auth(sess.au.clone())
+			.with_strict(self.strict)
+			.with_auth_enabled(self.auth_enabled)
+			.new_with_force(true)
+			.new_with_fields(false)
+			.new_with_events(false)
+			.new_with_tables(false);
+		let mut ctx = Context::default();
+		ctx.add_capabilities(self.capabilities.clone());
+		ctx.add_http_usage(self.http_usage.clone());
+		ctx.add_write_throttle(self.write_throttle.clone());
+		let txn = self.transaction(true, false).await?.enclose();
+		let res = async {
+			let ix = txn.lock().await.get_and_cache_tb_index(ns, db, tb, &rb.index).await?;
+			// Unique and non-unique indexes are kept in place while they are rebuilt
+			let prunes = matches!(ix.index, sql::index::Index::Uniq | sql::index::Index::Idx);
+			let next = if !rb.counted {
+				// Count the next batch of records
+				let ids = Self::rebuild_next_ids(&txn, ns, db, tb, &rb.cursor).await?;
+				let total = rb.total + ids.len() as u64;
+				if ids.len() < REBUILD_BATCH_SIZE as usize {
+					// The other indexes are emptied, and kept out of query plans until rebuilt
+					if !prunes {
+						let key = crate::key::index::all::new(ns, db, tb, &ix.name);
+						txn.lock().await.delp(key, u32::MAX).await?;
+					}
+					Some(Rebuild {
+						counted: true,
+						cursor: None,
+						total,
+						..rb.clone()
+					})
+				} else {
+					Some(Rebuild {
+						cursor: ids.into_iter().last(),
+						total,
+						..rb.clone()
+					})
+				}
+			} else if let Some(beg) = &rb.pruned {
+				// Prune the next batch of entries which no record produces any more
+				let end = crate::key::index::Index::prefix_end(ns, db, tb, &ix.name);
+				let batch = txn.lock().await.scan(beg.clone()..end, REBUILD_BATCH_SIZE).await?;
+				let full = batch.len() == REBUILD_BATCH_SIZE as usize;
+				for (k, v) in batch.iter() {
+					let key = crate::key::index::Index::decode(k)?;
+					let rid = match key.id {
+						Some(id) => Thing::from((tb, id.into_owned())),
+						None => Thing::from(v.clone()),
+					};
+					let val = record::get(&mut *txn.lock().await, ns, db, tb, &rid.id).await?;
+					let doc = CursorDoc::new(None, Some(&rid), None, &val);
+					if !has_index_entry(&ctx, &opt, &txn, &ix, &doc, &key.fd).await? {
+						txn.lock().await.del(k.clone()).await?;
+					}
+				}
+				match batch.into_iter().last() {
+					Some((mut k, _)) if full => {
+						k.push(0x00);
+						Some(Rebuild {
+							pruned: Some(k),
+							..rb.clone()
+						})
+					}
+					_ => None,
+				}
+			} else {
+				// Index the next batch of records
+				let ids = Self::rebuild_next_ids(&txn, ns, db, tb, &rb.cursor).await?;
+				if !ids.is_empty() {
+					let stm = UpdateStatement {
+						what: Values(
+							ids.iter().map(|id| Thing::from((tb, id.clone())).into()).collect(),
+						),
+						output: Some(Output::None),
+						..Default::default()
+					};
+					stm.compute(&ctx, &opt, &txn, None).await?;
+				}
+				let processed = rb.processed + ids.len() as u64;
+				if ids.len() == REBUILD_BATCH_SIZE as usize {
+					Some(Rebuild {
+						processed,
+						cursor: ids.into_iter().last(),
+						..rb.clone()
+					})
+				} else if prunes {
+					Some(Rebuild {
+						processed,
+						pruned: Some(crate::key::index::Index::prefix_beg(ns, db, tb, &ix.name)),
+						..rb.clone()
+					})
+				} else {
+					None
+				}
+			};
+			let mut run = txn.lock().await;
+			let key = crate::key::table::rb::new(ns, db, tb, &rb.index);
+			match next {
+				Some(next) => run.putc(key, next, Some(rb)).await,
+				None => run.delc(key, Some(rb)).await,
+			}
+		}
+		.await;
+		let mut run = txn.lock().await;
+		match res {
+			Ok(_) => run.commit().await,
+			Err(Error::TxConditionNotMet) => run.cancel().await,
+			Err(e) => {
+				run.cancel().await?;
+				Err(e)
+			}
+		}
+	}
+
+	// rebuild_next_ids reads the ids of the next batch of records of an index rebuild
+	async fn rebuild_next_ids(
+		txn: &crate::dbs::Transaction,
+		ns: &str,
+		db: &str,
+		tb: &str,
+		cursor: &Option<Id>,
+	) -> Result<Vec<Id>, Error> {
+		let beg = match cursor {
+			Some(id) => {
+				let mut key = crate::key::thing::new(ns, db, tb, id).encode()?;
+				key.push(0x00);
+				key
+			}
+			None => crate::key::thing::prefix(ns, db, tb),
+		};
+		let end = crate::key::thing::suffix(ns, db, tb);
+		let mut ids = Vec::new();
+		for (k, _) in txn.lock().await.scan(beg..end, REBUILD_BATCH_SIZE).await? {
+			ids.push(crate::key::thing::Thing::decode(&k)?.id);
+		}
+		Ok(ids)
+	}
+
 	// Creates a heartbeat entry for the member indicating to the cluster
 	// that the node is alive.
 	// This is the preferred way of creating heartbeats inside the database, so try to use this.
@@ -758,11 +1825,26 @@ impl Datastore {
 				let tx = v.transaction(write, lock).await?;
 				super::tx::Inner::SpeeDB(tx)
 			}
+			#[cfg(feature = "kv-sled")]
+			Inner::Sled(v) => {
+				let tx = v.transaction(write, lock).await?;
+				super::tx::Inner::Sled(tx)
+			}
+			#[cfg(feature = "kv-redb")]
+			Inner::Redb(v) => {
+				let tx = v.transaction(write, lock).await?;
+				super::tx::Inner::Redb(tx)
+			}
 			#[cfg(feature = "kv-indxdb")]
 			Inner::IndxDB(v) => {
 				let tx = v.transaction(write, lock).await?;
 				super::tx::Inner::IndxDB(tx)
 			}
+			#[cfg(feature = "kv-opfs")]
+			Inner::Opfs(v) => {
+				let tx = v.transaction(write, lock).await?;
+				super::tx::Inner::Opfs(tx)
+			}
 			#[cfg(feature = "kv-tikv")]
 			Inner::TiKV(v) => {
 				let tx = v.transaction(write, lock).await?;
@@ -773,6 +1855,10 @@ impl Datastore {
 				let tx = v.transaction(write, lock).await?;
 				super::tx::Inner::FoundationDB(tx)
 			}
+			Inner::Custom(v) => {
+				let tx = v.begin(write, lock).await?;
+				super::tx::Inner::Custom(tx)
+			}
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
 		};
@@ -783,9 +1869,96 @@ impl Datastore {
 			cache: super::cache::Cache::default(),
 			cf: cf::Writer::new(),
 			vso: self.versionstamp_oracle.clone(),
+			tmp: None,
+			pin: self.pinned(),
+			analyzers: self.analyzers.clone(),
+			read_ahead: self.read_ahead,
+			telemetry: self.telemetry.clone(),
+			#[cfg(feature = "failpoints")]
+			failpoints: self.failpoints.clone(),
 		})
 	}
 
+	/// The view of a new transaction on the cached tables of the datastore
+	fn pinned(&self) -> Option<Pinned> {
+		match &self.inner {
+			// Other nodes change the data of distributed storage engines, so it is never cached
+			#[cfg(feature = "kv-tikv")]
+			Inner::TiKV(_) => None,
+			#[cfg(feature = "kv-fdb")]
+			Inner::FoundationDB(_) => None,
+			Inner::Custom(_) => None,
+			#[allow(unreachable_patterns)]
+			_ => Some(Pinned::new(self.table_cache.clone())),
+		}
+	}
+
+	/// Create a new read-only transaction at a historical version of the data
+	///
+	/// Only the TiKV and FoundationDB storage engines keep old versions of the
+	/// data around, so other storage engines return an error. The versionstamp
+	/// is the same one reported in the change feeds of a database.
+	///
+	/// ```rust,no_run
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	/// use surrealdb::sql::Versionstamp;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("tikv://127.0.0.1:2379").await?;
+	///     let mut tx = ds.transaction_at(Versionstamp(442316523521474562)).await?;
+	///     tx.cancel().await?;
+	///     Ok(())
+	/// }
+	/// ```
+	pub async fn transaction_at(&self, versionstamp: Versionstamp) -> Result<Transaction, Error> {
+		#![allow(unused_variables)]
+		let inner = match &self.inner {
+			#[cfg(feature = "kv-tikv")]
+			Inner::TiKV(v) => {
+				let tx = v.transaction_at(versionstamp.0).await?;
+				super::tx::Inner::TiKV(tx)
+			}
+			#[cfg(feature = "kv-fdb")]
+			Inner::FoundationDB(v) => {
+				let tx = v.transaction_at(versionstamp.0).await?;
+				super::tx::Inner::FoundationDB(tx)
+			}
+			#[allow(unreachable_patterns)]
+			_ => return Err(Error::TxPointInTimeUnsupported(self.to_string())),
+		};
+
+		#[allow(unreachable_code)]
+		Ok(Transaction {
+			inner,
+			cache: super::cache::Cache::default(),
+			cf: cf::Writer::new(),
+			vso: self.versionstamp_oracle.clone(),
+			tmp: None,
+			// Historical data is never read from the cache
+			pin: None,
+			analyzers: self.analyzers.clone(),
+			read_ahead: self.read_ahead,
+			telemetry: self.telemetry.clone(),
+			#[cfg(feature = "failpoints")]
+			failpoints: self.failpoints.clone(),
+		})
+	}
+
+	/// Get the versionstamp of the data at a point in time, for use with [`Datastore::transaction_at`]
+	pub(crate) async fn versionstamp_at(&self, at: &sql::Datetime) -> Result<Versionstamp, Error> {
+		#![allow(unused_variables)]
+		match &self.inner {
+			#[cfg(feature = "kv-tikv")]
+			Inner::TiKV(v) => Ok(Versionstamp(v.version_at(at.0))),
+			#[cfg(feature = "kv-fdb")]
+			Inner::FoundationDB(v) => Ok(Versionstamp(v.version_at(at.0).await?)),
+			#[allow(unreachable_patterns)]
+			_ => Err(Error::TxPointInTimeUnsupported(self.to_string())),
+		}
+	}
+
 	/// Parse and execute an SQL query
 	///
 	/// ```rust,no_run
@@ -838,6 +2011,51 @@ impl Datastore {
 		ast: Query,
 		sess: &Session,
 		vars: Variables,
+	) -> Result<Vec<Response>, Error> {
+		self.process_at_version(ast, sess, vars, None).await
+	}
+
+	/// Parse and execute a read-only SQL query against a historical version of the data
+	///
+	/// Every statement runs in a transaction created with [`Datastore::transaction_at`],
+	/// so statements which write data fail with a read-only error.
+	///
+	/// ```rust,no_run
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	/// use surrealdb::dbs::Session;
+	/// use surrealdb::sql::Versionstamp;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("tikv://127.0.0.1:2379").await?;
+	///     let ses = Session::owner().with_ns("test").with_db("test");
+	///     let ast = "SELECT * FROM person;";
+	///     let res = ds.execute_at(ast, &ses, None, Versionstamp(442316523521474562)).await?;
+	///     Ok(())
+	/// }
+	/// ```
+	#[instrument(level = "debug", skip_all)]
+	pub async fn execute_at(
+		&self,
+		txt: &str,
+		sess: &Session,
+		vars: Variables,
+		versionstamp: Versionstamp,
+	) -> Result<Vec<Response>, Error> {
+		// Parse the SQL query text
+		let ast = sql::parse(txt)?;
+		// Process the AST
+		self.process_at_version(ast, sess, vars, Some(versionstamp)).await
+	}
+
+	/// Execute a pre-parsed SQL query, optionally at a historical version of the data
+	async fn process_at_version(
+		&self,
+		mut ast: Query,
+		sess: &Session,
+		vars: Variables,
+		at: Option<Versionstamp>,
 	) -> Result<Vec<Response>, Error> {
 		// Check if anonymous actors can execute queries when auth is enabled
 		// TODO(sgirones): Check this as part of the authorisation layer
@@ -859,10 +2077,15 @@ impl Datastore {
 			.with_strict(self.strict)
 			.with_auth_enabled(self.auth_enabled);
 		// Create a new query executor
-		let mut exe = Executor::new(self);
+		let mut exe = Executor::new(self)
+			.with_versionstamp(at)
+			.with_temporary(sess.tt.clone())
+			.with_result_limit(self.max_result_size.filter(|_| !sess.ul));
 		// Create a default context
 		let mut ctx = Context::default();
 		ctx.add_capabilities(self.capabilities.clone());
+		ctx.add_http_usage(self.http_usage.clone());
+		ctx.add_write_throttle(self.write_throttle.clone());
 		// Set the global query timeout
 		if let Some(timeout) = self.query_timeout {
 			ctx.add_timeout(timeout);
@@ -871,12 +2094,40 @@ impl Datastore {
 		if let Some(channel) = &self.notification_channel {
 			ctx.add_notifications(Some(&channel.0));
 		}
+		// Report permission denials, when the owner of the datastore allows it
+		if sess.dn && self.denials {
+			ctx.add_denials(Arc::new(Denials::default()));
+		}
 		// Start an execution context
 		let ctx = sess.context(ctx);
 		// Store the query variables
 		let ctx = vars.attach(ctx)?;
+		// Apply the rewrite rules
+		rewrite::apply(&self.rewrite_rules, sess, &mut ast);
 		// Process all statements
-		exe.execute(ctx, opt, ast).await
+		if !self.telemetry.is_active() {
+			return exe.execute(ctx, opt, ast).await;
+		}
+		let query = uuid::Uuid::now_v7();
+		self.telemetry.emit(TelemetryKind::QueryStarted {
+			query,
+			ns: sess.ns.clone(),
+			db: sess.db.clone(),
+			statements: ast.len(),
+		});
+		let now = Instant::now();
+		let res = exe.execute(ctx, opt, ast).await;
+		let (statements, errors) = match &res {
+			Ok(res) => (res.len(), res.iter().filter(|r| r.result.is_err()).count()),
+			Err(_) => (0, 1),
+		};
+		self.telemetry.emit(TelemetryKind::QueryFinished {
+			query,
+			duration_us: now.elapsed().as_micros() as u64,
+			statements,
+			errors,
+		});
+		res
 	}
 
 	/// Ensure a SQL [`Value`] is fully computed
@@ -927,6 +2178,8 @@ impl Datastore {
 		let mut ctx = Context::default();
 		// Set context capabilities
 		ctx.add_capabilities(self.capabilities.clone());
+		ctx.add_http_usage(self.http_usage.clone());
+		ctx.add_write_throttle(self.write_throttle.clone());
 		// Set the global query timeout
 		if let Some(timeout) = self.query_timeout {
 			ctx.add_timeout(timeout);
@@ -935,6 +2188,10 @@ impl Datastore {
 		if let Some(channel) = &self.notification_channel {
 			ctx.add_notifications(Some(&channel.0));
 		}
+		// Report permission denials, when the owner of the datastore allows it
+		if sess.dn && self.denials {
+			ctx.add_denials(Arc::new(Denials::default()));
+		}
 		// Start an execution context
 		let ctx = sess.context(ctx);
 		// Store the query variables
@@ -996,6 +2253,8 @@ impl Datastore {
 		let mut ctx = Context::default();
 		// Set context capabilities
 		ctx.add_capabilities(self.capabilities.clone());
+		ctx.add_http_usage(self.http_usage.clone());
+		ctx.add_write_throttle(self.write_throttle.clone());
 		// Set the global query timeout
 		if let Some(timeout) = self.query_timeout {
 			ctx.add_timeout(timeout);
@@ -1004,6 +2263,10 @@ impl Datastore {
 		if let Some(channel) = &self.notification_channel {
 			ctx.add_notifications(Some(&channel.0));
 		}
+		// Report permission denials, when the owner of the datastore allows it
+		if sess.dn && self.denials {
+			ctx.add_denials(Arc::new(Denials::default()));
+		}
 		// Start an execution context
 		let ctx = sess.context(ctx);
 		// Store the query variables
@@ -1047,6 +2310,36 @@ impl Datastore {
 		self.notification_channel.as_ref().map(|v| v.1.clone())
 	}
 
+	/// Subscribe to the internal events of this datastore
+	///
+	/// The events, such as queries which were run and transactions which were committed, have
+	/// a stable [JSON schema](crate::dbs::TelemetryEvent::to_json), so they can be shipped to a
+	/// telemetry pipeline as they are. Events are only sent to the subscribers which exist when
+	/// they happen, and are dropped for a subscriber which falls too far behind.
+	///
+	/// ```rust,no_run
+	/// use surrealdb::kvs::Datastore;
+	/// use surrealdb::err::Error;
+	///
+	/// #[tokio::main]
+	/// async fn main() -> Result<(), Error> {
+	///     let ds = Datastore::new("memory").await?;
+	///     let events = ds.telemetry();
+	///     while let Ok(event) = events.recv().await {
+	///         println!("{}", event.to_json());
+	///     }
+	///     Ok(())
+	/// }
+	/// ```
+	pub fn telemetry(&self) -> Receiver<TelemetryEvent> {
+		self.telemetry.subscribe()
+	}
+
+	/// The subscribers to the internal events of this datastore
+	pub(crate) fn telemetry_bus(&self) -> &Telemetry {
+		&self.telemetry
+	}
+
 	/// Performs a full database export as SQL
 	#[instrument(level = "debug", skip(self, sess, chn))]
 	pub async fn export(
@@ -1072,6 +2365,74 @@ impl Datastore {
 		})
 	}
 
+	/// Stores a binary blob for a record, replacing any blob which it already has
+	///
+	/// The blob is read in chunks, which are stored under separate keys so that it is never
+	/// held in memory as a whole, and are written in bounded transactions. The blob only
+	/// replaces the previous one once all of its chunks have been written. The blob is kept
+	/// apart from the fields of the record, and is removed along with the record. Returns
+	/// the size of the blob in bytes.
+	#[instrument(level = "debug", skip(self, sess, reader))]
+	pub async fn put_blob<R>(
+		&self,
+		sess: &Session,
+		rid: &Thing,
+		reader: &mut R,
+	) -> Result<u64, Error>
+	where
+		R: AsyncRead + Unpin + ?Sized,
+	{
+		let (ns, db) = self.check_blob_allowed(sess, Action::Edit)?;
+		// Write the chunks of the blob
+		blob::put(self, &ns, &db, rid, reader).await
+	}
+
+	/// Reads the binary blob of a record, as a stream of chunks
+	///
+	/// Like [`Datastore::export`], this returns a job which sends the chunks of the blob
+	/// to the channel, in order, from a consistent snapshot.
+	#[instrument(level = "debug", skip(self, sess, chn))]
+	pub async fn get_blob(
+		&self,
+		sess: &Session,
+		rid: &Thing,
+		chn: Sender<Vec<u8>>,
+	) -> Result<impl Future<Output = Result<(), Error>>, Error> {
+		let (ns, db) = self.check_blob_allowed(sess, Action::View)?;
+		// Create a new readonly transaction
+		let mut txn = self.transaction(false, false).await?;
+		// Check that there is a blob to read
+		if !blob::exists(&mut txn, &ns, &db, rid).await? {
+			txn.cancel().await?;
+			return Err(Error::BlobNotFound {
+				value: rid.to_string(),
+			});
+		}
+		let rid = rid.clone();
+		// Return an async read job
+		Ok(async move {
+			let res = blob::get(&mut txn, &ns, &db, &rid, chn).await;
+			txn.cancel().await?;
+			res
+		})
+	}
+
+	/// Checks that the session may access the blobs of its database, returning the database
+	fn check_blob_allowed(
+		&self,
+		sess: &Session,
+		action: Action,
+	) -> Result<(String, String), Error> {
+		let ns = sess.ns.clone().ok_or(Error::NsEmpty)?;
+		let db = sess.db.clone().ok_or(Error::DbEmpty)?;
+		// Skip auth for Anonymous users if auth is disabled
+		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
+		if !skip_auth {
+			sess.au.is_allowed(action, &ResourceKind::Any.on_db(&ns, &db))?;
+		}
+		Ok((ns, db))
+	}
+
 	/// Performs a database import from SQL
 	#[instrument(level = "debug", skip(self, sess, sql))]
 	pub async fn import(&self, sql: &str, sess: &Session) -> Result<Vec<Response>, Error> {
@@ -1086,4 +2447,102 @@ impl Datastore {
 		// Execute the SQL import
 		self.execute(sql, sess, None).await
 	}
+
+	/// Performs a full binary backup of the datastore
+	///
+	/// The key-value pairs are written directly, which makes this much faster than
+	/// an SQL export for large datasets. The returned [`Checkpoint`] can be passed
+	/// to [`Datastore::backup_since`] to take incremental backups afterwards.
+	#[instrument(level = "debug", skip(self, sess, writer))]
+	pub async fn backup<W>(&self, sess: &Session, writer: &mut W) -> Result<Checkpoint, Error>
+	where
+		W: AsyncWrite + Unpin + ?Sized,
+	{
+		self.check_backup_allowed(sess)?;
+		// Create a new readonly transaction
+		let mut txn = self.transaction(false, false).await?;
+		// Write the backup from a consistent snapshot
+		let res = backup::backup(&mut txn, writer, None).await;
+		txn.cancel().await?;
+		res
+	}
+
+	/// Performs an incremental binary backup of the changes since a previous backup
+	///
+	/// Incremental backups are read from the change feeds of each database, for as long
+	/// as the change feed retains them. Tables which have no change feed, and aren't in a
+	/// database with one, are written in full instead.
+	#[instrument(level = "debug", skip(self, sess, writer))]
+	pub async fn backup_since<W>(
+		&self,
+		sess: &Session,
+		writer: &mut W,
+		since: &Checkpoint,
+	) -> Result<Checkpoint, Error>
+	where
+		W: AsyncWrite + Unpin + ?Sized,
+	{
+		self.check_backup_allowed(sess)?;
+		// Create a new readonly transaction
+		let mut txn = self.transaction(false, false).await?;
+		// Write the backup from a consistent snapshot
+		let res = backup::backup(&mut txn, writer, Some(since)).await;
+		txn.cancel().await?;
+		res
+	}
+
+	/// Restores a full or incremental binary backup
+	///
+	/// A full backup can only be restored into an empty datastore. Incremental backups
+	/// must be restored in the order they were taken, after the full backup they are
+	/// based on.
+	#[instrument(level = "debug", skip(self, sess, reader))]
+	pub async fn restore<R>(&self, sess: &Session, reader: &mut R) -> Result<Checkpoint, Error>
+	where
+		R: AsyncRead + Unpin + ?Sized,
+	{
+		// Skip auth for Anonymous users if auth is disabled
+		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
+		if !skip_auth {
+			sess.au.is_allowed(Action::Edit, &ResourceKind::Any.on_root())?;
+		}
+		backup::restore(self, sess, reader).await
+	}
+
+	/// Captures the whole contents of an in-memory datastore
+	///
+	/// The snapshot can be restored with [`Datastore::restore_snapshot`], so that test
+	/// suites can seed a datastore once, and reset it to the seeded state between tests.
+	#[cfg(feature = "kv-mem")]
+	pub async fn snapshot(&self) -> Result<MemSnapshot, Error> {
+		match &self.inner {
+			Inner::Mem(v) => v.snapshot().await,
+			#[allow(unreachable_patterns)]
+			_ => Err(Error::Ds(format!("The `{self}` storage engine does not support snapshots"))),
+		}
+	}
+
+	/// Replaces the whole contents of an in-memory datastore with a snapshot
+	#[cfg(feature = "kv-mem")]
+	pub async fn restore_snapshot(&self, snapshot: MemSnapshot) -> Result<(), Error> {
+		match &self.inner {
+			Inner::Mem(v) => {
+				v.restore_snapshot(snapshot).await?;
+				// None of the cached records are current any more
+				self.table_cache.clear();
+				Ok(())
+			}
+			#[allow(unreachable_patterns)]
+			_ => Err(Error::Ds(format!("The `{self}` storage engine does not support snapshots"))),
+		}
+	}
+
+	fn check_backup_allowed(&self, sess: &Session) -> Result<(), Error> {
+		// Skip auth for Anonymous users if auth is disabled
+		let skip_auth = !self.is_auth_enabled() && sess.au.is_anon();
+		if !skip_auth {
+			sess.au.is_allowed(Action::View, &ResourceKind::Any.on_root())?;
+		}
+		Ok(())
+	}
 }
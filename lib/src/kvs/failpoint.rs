@@ -0,0 +1,235 @@
+//! Failure injection for testing the durability of writes
+//!
+//! With the `failpoints` feature enabled, failures can be injected into the write paths of a
+//! datastore, so that applications which embed the crate can test how their data recovers from
+//! crashes and IO errors. A [`Failpoint`] is enabled with an [`Action`] on the [`Failpoints`] of
+//! a datastore, and fires every time the write path reaches it, until it is disabled again.
+//!
+//! ```rust,no_run
+//! use surrealdb::dbs::Session;
+//! use surrealdb::err::Error;
+//! use surrealdb::kvs::failpoint::{self, Action, Failpoint};
+//! use surrealdb::kvs::Datastore;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Error> {
+//!     let ds = Datastore::new("memory").await?;
+//!     let ses = Session::owner().with_ns("test").with_db("test");
+//!     ds.execute("DEFINE INDEX email ON user FIELDS email UNIQUE", &ses, None).await?;
+//!     // Fail the next writes while the index is being updated
+//!     ds.failpoints().enable(Failpoint::Index, Action::Error);
+//!     ds.execute("CREATE user SET email = 'tobie@surrealdb.com'", &ses, None).await?;
+//!     ds.failpoints().disable(Failpoint::Index);
+//!     // Check that the indexes still match the records
+//!     failpoint::verify(&ds).await?;
+//!     Ok(())
+//! }
+//! ```
+
+use super::Datastore;
+use crate::ctx::Context;
+use crate::dbs::{Options, Session};
+use crate::err::Error;
+use crate::key;
+use crate::sql::index::Index;
+use crate::sql::statements::UpdateStatement;
+use crate::sql::{Output, Table, Value, Values};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// A point in the write path at which a failure can be injected
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Failpoint {
+	/// Before a transaction is committed to the storage engine
+	Commit,
+	/// Before the entries of an index are updated for a record
+	Index,
+	/// Before the changes of a transaction are written to the change feeds
+	ChangeFeed,
+}
+
+impl fmt::Display for Failpoint {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Commit => f.write_str("commit"),
+			Self::Index => f.write_str("index"),
+			Self::ChangeFeed => f.write_str("changefeed"),
+		}
+	}
+}
+
+/// The failure which is injected when a failpoint is reached
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Action {
+	/// Return an IO error, which fails the transaction
+	Error,
+	/// Panic, as if the process crashed before the transaction finished
+	Panic,
+}
+
+/// The failpoints which are enabled on a datastore
+#[derive(Clone, Default)]
+pub struct Failpoints(Arc<Mutex<HashMap<Failpoint, Action>>>);
+
+impl Failpoints {
+	/// Injects a failure every time the failpoint is reached
+	pub fn enable(&self, fp: Failpoint, action: Action) {
+		self.0.lock().unwrap_or_else(|e| e.into_inner()).insert(fp, action);
+	}
+
+	/// Stops injecting failures at the failpoint
+	pub fn disable(&self, fp: Failpoint) {
+		self.0.lock().unwrap_or_else(|e| e.into_inner()).remove(&fp);
+	}
+
+	/// Stops injecting failures at every failpoint
+	pub fn clear(&self) {
+		self.0.lock().unwrap_or_else(|e| e.into_inner()).clear();
+	}
+
+	/// Injects the failure which is enabled for the failpoint, if any
+	pub(crate) fn trigger(&self, fp: Failpoint) -> Result<(), Error> {
+		let action = self.0.lock().unwrap_or_else(|e| e.into_inner()).get(&fp).copied();
+		match action {
+			Some(Action::Error) => Err(Error::Failpoint(fp.to_string())),
+			Some(Action::Panic) => panic!("Injected a crash at the '{fp}' failpoint"),
+			None => Ok(()),
+		}
+	}
+}
+
+/// Checks that the entries of the unique and non-unique indexes match the records they index
+///
+/// Every index is rebuilt from the records of its table in a transaction which is never
+/// committed, and the rebuilt entries are compared with the stored ones. This makes it
+/// possible to verify that a datastore recovered to a consistent state after a failure.
+pub async fn verify(ds: &Datastore) -> Result<(), Error> {
+	let txn = ds.transaction(true, false).await?.enclose();
+	let res = async {
+		let mut tables = Vec::new();
+		let mut run = txn.lock().await;
+		for ns in run.all_ns().await?.iter() {
+			let ns = ns.name.to_raw();
+			for db in run.all_db(&ns).await?.iter() {
+				let db = db.name.to_raw();
+				for tb in run.all_tb(&ns, &db).await?.iter() {
+					let tb = tb.name.to_raw();
+					let ixs: Vec<_> = run
+						.all_tb_indexes(&ns, &db, &tb)
+						.await?
+						.iter()
+						.filter(|ix| matches!(ix.index, Index::Uniq | Index::Idx))
+						.map(|ix| ix.name.to_raw())
+						.collect();
+					if !ixs.is_empty() {
+						tables.push((ns.clone(), db.clone(), tb, ixs));
+					}
+				}
+			}
+		}
+		drop(run);
+		for (ns, db, tb, ixs) in tables {
+			// Read, and then remove, the stored index entries
+			let mut stored = Vec::with_capacity(ixs.len());
+			let mut run = txn.lock().await;
+			for ix in ixs.iter() {
+				let beg = key::index::Index::prefix_beg(&ns, &db, &tb, ix);
+				let end = key::index::Index::prefix_end(&ns, &db, &tb, ix);
+				stored.push(run.getr(beg.clone()..end.clone(), u32::MAX).await?);
+				run.delr(beg..end, u32::MAX).await?;
+			}
+			drop(run);
+			// Index all the records of the table again
+			let sess = Session::owner().with_ns(&ns).with_db(&db);
+			let opt = Options::default()
+				.with_ns(sess.ns())
+				.with_db(sess.db())
+				.with_auth(sess.au.clone())
+				.new_with_force(true)
+				.new_with_fields(false)
+				.new_with_events(false)
+				.new_with_tables(false);
+			let stm = UpdateStatement {
+				what: Values(vec![Value::Table(Table(tb.clone()))]),
+				output: Some(Output::None),
+				..Default::default()
+			};
+			stm.compute(&Context::default(), &opt, &txn, None).await?;
+			// Compare the rebuilt index entries with the stored ones
+			let mut run = txn.lock().await;
+			for (ix, stored) in ixs.iter().zip(stored) {
+				let beg = key::index::Index::prefix_beg(&ns, &db, &tb, ix);
+				let end = key::index::Index::prefix_end(&ns, &db, &tb, ix);
+				if run.getr(beg..end, u32::MAX).await? != stored {
+					return Err(Error::IndexInconsistent {
+						table: tb.clone(),
+						index: ix.clone(),
+					});
+				}
+			}
+		}
+		Ok(())
+	}
+	.await;
+	// Discard the rebuilt indexes
+	txn.lock().await.cancel().await?;
+	res
+}
+
+#[cfg(all(test, feature = "kv-mem"))]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn failed_writes_are_not_committed() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let sql = "
+			DEFINE TABLE user CHANGEFEED 1h;
+			DEFINE INDEX email ON user FIELDS email UNIQUE;
+			CREATE user:one SET email = 'one@surrealdb.com';
+		";
+		ds.execute(sql, &ses, None).await.unwrap();
+		for fp in [Failpoint::Commit, Failpoint::Index, Failpoint::ChangeFeed] {
+			ds.failpoints().enable(fp, Action::Error);
+			let mut res = ds
+				.execute("CREATE user:two SET email = 'two@surrealdb.com'", &ses, None)
+				.await
+				.unwrap();
+			assert!(res.remove(0).result.is_err(), "{fp}");
+			ds.failpoints().disable(fp);
+			let mut res = ds.execute("SELECT VALUE id FROM user:two", &ses, None).await.unwrap();
+			assert_eq!(res.remove(0).result.unwrap(), Value::from(Vec::<Value>::new()), "{fp}");
+			verify(&ds).await.unwrap();
+		}
+		// The failpoints are disabled again
+		let mut res = ds
+			.execute("CREATE user:two SET email = 'two@surrealdb.com'", &ses, None)
+			.await
+			.unwrap();
+		assert!(res.remove(0).result.is_ok());
+		verify(&ds).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn detects_inconsistent_indexes() {
+		let ds = Datastore::new("memory").await.unwrap();
+		let ses = Session::owner().with_ns("test").with_db("test");
+		let sql = "
+			DEFINE INDEX email ON user FIELDS email;
+			CREATE user:one SET email = 'one@surrealdb.com';
+		";
+		ds.execute(sql, &ses, None).await.unwrap();
+		verify(&ds).await.unwrap();
+		// Remove the index entries behind the back of the index
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		let beg = key::index::Index::prefix_beg("test", "test", "user", "email");
+		let end = key::index::Index::prefix_end("test", "test", "user", "email");
+		tx.delr(beg..end, u32::MAX).await.unwrap();
+		tx.commit().await.unwrap();
+		assert!(matches!(verify(&ds).await, Err(Error::IndexInconsistent { .. })));
+	}
+}
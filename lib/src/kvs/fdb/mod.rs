@@ -5,6 +5,7 @@ use crate::kvs::Check;
 use crate::kvs::Key;
 use crate::kvs::Val;
 use crate::vs::{u64_to_versionstamp, Versionstamp};
+use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
 use std::ops::Range;
 use std::sync::Arc;
@@ -111,6 +112,36 @@ impl Datastore {
 			Err(e) => Err(Error::Tx(e.to_string())),
 		}
 	}
+	/// Start a new read-only transaction at a historical version
+	///
+	/// FoundationDB only keeps around 5 seconds of history, so reading
+	/// at an older version fails with a `transaction_too_old` error.
+	pub(crate) async fn transaction_at(&self, version: u64) -> Result<Transaction, Error> {
+		// Create a new transaction
+		let inner = self.db.create_trx().map_err(|e| Error::Tx(e.to_string()))?;
+		// Read the data as it was at the specified version
+		inner.set_read_version(version as i64);
+		// Return the read-only transaction
+		Ok(Transaction {
+			done: false,
+			check: Check::None,
+			write: false,
+			lock: false,
+			inner: Arc::new(Mutex::new(Some(inner))),
+		})
+	}
+	/// Get the version of the data at a point in time
+	///
+	/// Versions are not related to the wall clock, but advance by around one
+	/// million every second, so the version is estimated from the current one.
+	pub(crate) async fn version_at(&self, at: DateTime<Utc>) -> Result<u64, Error> {
+		// Get the current version
+		let inner = self.db.create_trx().map_err(|e| Error::Tx(e.to_string()))?;
+		let now = inner.get_read_version().await.map_err(|e| Error::Tx(e.to_string()))?;
+		// Go back one version for every microsecond
+		let ago = (Utc::now() - at).num_microseconds().unwrap_or(i64::MAX).max(0);
+		Ok(now.saturating_sub(ago).max(0) as u64)
+	}
 }
 
 impl Transaction {
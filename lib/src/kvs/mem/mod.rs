@@ -6,6 +6,15 @@ use crate::kvs::Key;
 use crate::kvs::Val;
 use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
 use std::ops::Range;
+use std::sync::Arc;
+
+/// A copy of the whole contents of an in-memory datastore
+///
+/// Cloning a snapshot is cheap, so a single snapshot can be restored many times.
+#[derive(Clone, Debug, Default)]
+pub struct MemSnapshot {
+	data: Arc<Vec<(Key, Val)>>,
+}
 
 pub struct Datastore {
 	db: echodb::Db<Key, Val>,
@@ -77,6 +86,27 @@ impl Datastore {
 			Err(e) => Err(Error::Tx(e.to_string())),
 		}
 	}
+	/// Copy the whole contents of the datastore
+	pub(crate) async fn snapshot(&self) -> Result<MemSnapshot, Error> {
+		let mut tx = self.db.begin(false).await?;
+		let data = tx.scan(vec![]..vec![u8::MAX], u32::MAX)?;
+		tx.cancel()?;
+		Ok(MemSnapshot {
+			data: Arc::new(data),
+		})
+	}
+	/// Replace the whole contents of the datastore with a snapshot
+	pub(crate) async fn restore_snapshot(&self, snapshot: MemSnapshot) -> Result<(), Error> {
+		let mut tx = self.db.begin(true).await?;
+		for (k, _) in tx.scan(vec![]..vec![u8::MAX], u32::MAX)? {
+			tx.del(k)?;
+		}
+		for (k, v) in snapshot.data.iter() {
+			tx.set(k.clone(), v.clone())?;
+		}
+		tx.commit()?;
+		Ok(())
+	}
 }
 
 impl Transaction {
@@ -8,24 +8,56 @@
 //! These operations can be processed by the following storage engines:
 //! - `fdb`: [FoundationDB](https://github.com/apple/foundationdb/) a distributed database designed to handle large volumes of structured data across clusters of commodity servers
 //! - `indxdb`: WASM based database to store data in the browser
+//! - `opfs`: WASM based database persisted to the browser's Origin Private File System
 //! - `rocksdb`: [RocksDB](https://github.com/facebook/rocksdb) an embeddable persistent key-value store for fast storage
+//! - `redb`: [redb](https://github.com/cberner/redb) a pure-Rust embedded database with memory-mapped reads
+//! - `sled`: [sled](https://github.com/spacejam/sled) a pure-Rust embedded database, for targets where RocksDB can not be built
 //! - `speedb`: [SpeedyDB](https://github.com/speedb-io/speedb) fork of rocksDB making it faster (Redis is using speedb but this is not acid transactions)
 //! - `tikv`: [TiKV](https://github.com/tikv/tikv) a distributed, and transactional key-value database
 //! - `mem`: in-memory database
+//!
+//! Other storage engines can be plugged in by implementing the [`Backend`] trait, and
+//! registering it for a URL scheme with [`register`].
+mod backend;
+mod backup;
+mod batch;
+pub(crate) mod blob;
 mod cache;
 mod ds;
+#[cfg(feature = "failpoints")]
+#[cfg_attr(docsrs, doc(cfg(feature = "failpoints")))]
+pub mod failpoint;
 mod fdb;
 mod indxdb;
 mod kv;
 mod mem;
+mod opfs;
+mod pinned;
+pub(crate) mod record;
+mod redb;
+mod replica;
 mod rocksdb;
+mod rollup;
+#[cfg(feature = "cron-schedules")]
+mod schedule;
+mod sled;
 mod speedb;
+mod temporary;
 mod tikv;
 mod tx;
 
 #[cfg(test)]
 mod tests;
 
+pub use self::backend::{register, Backend, BackendFuture, BackendTransaction};
+pub use self::backup::Checkpoint;
+pub(crate) use self::batch::BatchSize;
 pub use self::ds::*;
 pub use self::kv::*;
+#[cfg(feature = "kv-mem")]
+pub use self::mem::MemSnapshot;
+#[cfg(feature = "cron-schedules")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cron-schedules")))]
+pub use self::schedule::{BackupSchedule, BlobStore};
+pub use self::temporary::Temporary;
 pub use self::tx::*;
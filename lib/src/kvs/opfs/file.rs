@@ -0,0 +1,285 @@
+use crate::err::Error;
+use crate::kvs::Key;
+use crate::kvs::Val;
+use js_sys::Promise;
+use std::collections::BTreeMap;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::FileSystemDirectoryHandle;
+use web_sys::FileSystemFileHandle;
+use web_sys::FileSystemGetDirectoryOptions;
+use web_sys::FileSystemGetFileOptions;
+use web_sys::FileSystemReadWriteOptions;
+use web_sys::FileSystemSyncAccessHandle;
+use web_sys::WorkerGlobalScope;
+
+/// Marks a key which was set by a transaction
+const OP_SET: u8 = 1;
+/// Marks a key which was deleted by a transaction
+const OP_DEL: u8 = 2;
+
+/// The suffix of the file a compacted log is staged in
+const COMPACT_SUFFIX: &str = ".compact";
+
+/// The log is only compacted once it is this many times bigger than the live data
+const COMPACT_RATIO: f64 = 2.0;
+
+/// Logs smaller than this are never compacted
+const COMPACT_MIN_SIZE: f64 = 4.0 * 1024.0 * 1024.0;
+
+/// A single write made by a transaction
+pub(super) enum Op {
+	Set(Key, Val),
+	Del(Key),
+}
+
+/// An append-only log of committed transactions stored in the Origin Private File System
+///
+/// Every commit is written as a single frame, prefixed with its length. A frame which
+/// was only partially written, because the tab was closed in the middle of a commit,
+/// is detected and discarded when the log is opened.
+pub(super) struct Log {
+	/// The file the log is written to
+	file: FileSystemSyncAccessHandle,
+	/// The file a compacted log is staged in
+	compact: FileSystemSyncAccessHandle,
+	/// The current size of the log
+	size: f64,
+}
+
+// SAFETY: the OPFS handles can only be used from the worker which created them, and
+// the WASM build of SurrealDB is single threaded, so the log never leaves that worker.
+unsafe impl Send for Log {}
+unsafe impl Sync for Log {}
+
+impl Log {
+	/// Opens the log at the given path, returning it alongside the data it contains
+	pub(super) async fn open(path: &str) -> Result<(Log, Vec<(Key, Val)>), Error> {
+		let (dir, name) = directory(path).await?;
+		let file = sync_handle(&dir, name).await?;
+		let compact = sync_handle(&dir, &format!("{name}{COMPACT_SUFFIX}")).await?;
+		let mut log = Log {
+			size: file.get_size().map_err(js_error)?,
+			file,
+			compact,
+		};
+		// Finish a compaction which was interrupted after it was staged
+		log.recover()?;
+		// Replay the committed transactions
+		let (data, size) = replay(&read(&log.file)?);
+		if size < log.size {
+			warn!("Discarding a partially written transaction at the end of the OPFS log");
+			log.file.truncate_with_f64(size).map_err(js_error)?;
+			log.file.flush().map_err(js_error)?;
+			log.size = size;
+		}
+		let data: Vec<(Key, Val)> = data.into_iter().collect();
+		log.compact(&data)?;
+		Ok((log, data))
+	}
+
+	/// Appends the writes made by a transaction to the log
+	pub(super) fn append(&mut self, ops: &[Op]) -> Result<(), Error> {
+		if ops.is_empty() {
+			return Ok(());
+		}
+		let mut frame = encode(ops);
+		write_at(&self.file, &mut frame, self.size)?;
+		self.file.flush().map_err(js_error)?;
+		self.size += frame.len() as f64;
+		Ok(())
+	}
+
+	/// Rewrites the log with only the live data, if enough of it is obsolete
+	fn compact(&mut self, data: &[(Key, Val)]) -> Result<(), Error> {
+		if self.size < COMPACT_MIN_SIZE {
+			return Ok(());
+		}
+		let live: usize = data.iter().map(|(k, v)| k.len() + v.len() + 9).sum();
+		if self.size < live as f64 * COMPACT_RATIO {
+			return Ok(());
+		}
+		debug!("Compacting the OPFS log from {} to {live} bytes", self.size);
+		let ops: Vec<Op> = data.iter().map(|(k, v)| Op::Set(k.clone(), v.clone())).collect();
+		let mut frame = encode(&ops);
+		// Stage the compacted log first, so the current one stays intact if we are interrupted
+		self.compact.truncate_with_f64(0.0).map_err(js_error)?;
+		write_at(&self.compact, &mut frame, 0.0)?;
+		self.compact.flush().map_err(js_error)?;
+		self.recover()
+	}
+
+	/// Copies a fully staged compacted log over the current one
+	fn recover(&mut self) -> Result<(), Error> {
+		let mut staged = read(&self.compact)?;
+		if staged.is_empty() {
+			return Ok(());
+		}
+		// Only a complete frame can be trusted
+		let (_, size) = replay(&staged);
+		if size as usize == staged.len() {
+			write_at(&self.file, &mut staged, 0.0)?;
+			self.file.truncate_with_f64(size).map_err(js_error)?;
+			self.file.flush().map_err(js_error)?;
+			self.size = size;
+		}
+		self.compact.truncate_with_f64(0.0).map_err(js_error)?;
+		self.compact.flush().map_err(js_error)?;
+		Ok(())
+	}
+}
+
+impl Drop for Log {
+	fn drop(&mut self) {
+		// Release the exclusive locks so the files can be opened again
+		self.file.close();
+		self.compact.close();
+	}
+}
+
+/// Encodes the writes made by a transaction as a single frame
+fn encode(ops: &[Op]) -> Vec<u8> {
+	let mut frame = vec![0; 4];
+	for op in ops {
+		match op {
+			Op::Set(key, val) => {
+				frame.push(OP_SET);
+				put_bytes(&mut frame, key);
+				put_bytes(&mut frame, val);
+			}
+			Op::Del(key) => {
+				frame.push(OP_DEL);
+				put_bytes(&mut frame, key);
+			}
+		}
+	}
+	let len = (frame.len() - 4) as u32;
+	frame[..4].copy_from_slice(&len.to_be_bytes());
+	frame
+}
+
+/// Replays the frames in a log, returning the resulting data and the size of the valid frames
+fn replay(bytes: &[u8]) -> (BTreeMap<Key, Val>, f64) {
+	let mut data = BTreeMap::new();
+	let mut offset = 0;
+	while let Some(len) = take_u32(bytes, offset) {
+		let start = offset + 4;
+		let Some(frame) = bytes.get(start..start + len as usize) else {
+			break;
+		};
+		let Some(ops) = decode(frame) else {
+			break;
+		};
+		for op in ops {
+			match op {
+				Op::Set(key, val) => {
+					data.insert(key, val);
+				}
+				Op::Del(key) => {
+					data.remove(&key);
+				}
+			}
+		}
+		offset = start + len as usize;
+	}
+	(data, offset as f64)
+}
+
+/// Decodes the writes in a single frame
+fn decode(mut frame: &[u8]) -> Option<Vec<Op>> {
+	let mut ops = Vec::new();
+	while let Some((&op, rest)) = frame.split_first() {
+		let (key, rest) = take_bytes(rest)?;
+		frame = match op {
+			OP_SET => {
+				let (val, rest) = take_bytes(rest)?;
+				ops.push(Op::Set(key, val));
+				rest
+			}
+			OP_DEL => {
+				ops.push(Op::Del(key));
+				rest
+			}
+			_ => return None,
+		};
+	}
+	Some(ops)
+}
+
+fn put_bytes(frame: &mut Vec<u8>, bytes: &[u8]) {
+	frame.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+	frame.extend_from_slice(bytes);
+}
+
+fn take_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+	let bytes = bytes.get(offset..offset + 4)?;
+	Some(u32::from_be_bytes(bytes.try_into().ok()?))
+}
+
+fn take_bytes(bytes: &[u8]) -> Option<(Vec<u8>, &[u8])> {
+	let len = take_u32(bytes, 0)? as usize;
+	let value = bytes.get(4..4 + len)?;
+	Some((value.to_vec(), &bytes[4 + len..]))
+}
+
+/// Reads the whole contents of a file
+fn read(file: &FileSystemSyncAccessHandle) -> Result<Vec<u8>, Error> {
+	let size = file.get_size().map_err(js_error)?;
+	let mut bytes = vec![0; size as usize];
+	let mut options = FileSystemReadWriteOptions::new();
+	options.at(0.0);
+	file.read_with_u8_array_and_options(&mut bytes, &options).map_err(js_error)?;
+	Ok(bytes)
+}
+
+/// Writes the bytes at the given offset of a file
+fn write_at(file: &FileSystemSyncAccessHandle, bytes: &mut [u8], at: f64) -> Result<(), Error> {
+	let mut options = FileSystemReadWriteOptions::new();
+	options.at(at);
+	let written = file.write_with_u8_array_and_options(bytes, &options).map_err(js_error)?;
+	if written as usize != bytes.len() {
+		return Err(Error::Ds("Failed to write the transaction to the OPFS log".to_owned()));
+	}
+	Ok(())
+}
+
+/// Opens the directory containing the log, creating it if necessary
+async fn directory(path: &str) -> Result<(FileSystemDirectoryHandle, &str), Error> {
+	let scope = js_sys::global().dyn_into::<WorkerGlobalScope>().map_err(|_| {
+		Error::Ds("The `opfs` storage engine can only be used from a web worker".to_owned())
+	})?;
+	let storage = scope.navigator().storage();
+	let mut dir: FileSystemDirectoryHandle = resolve(storage.get_directory()).await?;
+	let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+	let Some(name) = segments.pop() else {
+		return Err(Error::Ds("The `opfs` storage engine requires a database name".to_owned()));
+	};
+	let mut options = FileSystemGetDirectoryOptions::new();
+	options.create(true);
+	for segment in segments {
+		dir = resolve(dir.get_directory_handle_with_options(segment, &options)).await?;
+	}
+	Ok((dir, name))
+}
+
+/// Opens an exclusive synchronous handle to a file, creating it if necessary
+async fn sync_handle(
+	dir: &FileSystemDirectoryHandle,
+	name: &str,
+) -> Result<FileSystemSyncAccessHandle, Error> {
+	let mut options = FileSystemGetFileOptions::new();
+	options.create(true);
+	let file: FileSystemFileHandle =
+		resolve(dir.get_file_handle_with_options(name, &options)).await?;
+	resolve(file.create_sync_access_handle()).await
+}
+
+async fn resolve<T: JsCast>(promise: Promise) -> Result<T, Error> {
+	let value = JsFuture::from(promise).await.map_err(js_error)?;
+	value.dyn_into().map_err(js_error)
+}
+
+fn js_error(error: JsValue) -> Error {
+	Error::Ds(format!("OPFS error: {error:?}"))
+}
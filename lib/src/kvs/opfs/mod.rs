@@ -0,0 +1,377 @@
+#![cfg(feature = "kv-opfs")]
+
+mod file;
+
+use crate::err::Error;
+use crate::kvs::Check;
+use crate::kvs::Key;
+use crate::kvs::Val;
+use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
+use file::Log;
+use file::Op;
+use std::ops::Range;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+pub struct Datastore {
+	db: echodb::Db<Key, Val>,
+	/// The log which committed transactions are persisted to
+	log: Arc<Mutex<Log>>,
+}
+
+pub struct Transaction {
+	/// Is the transaction complete?
+	done: bool,
+	/// Is the transaction writeable?
+	write: bool,
+	/// Should we check unhandled transactions?
+	check: Check,
+	/// The underlying datastore transaction
+	inner: echodb::Tx<Key, Val>,
+	/// The writes made by this transaction
+	ops: Vec<Op>,
+	/// The log which the writes are persisted to on commit
+	log: Arc<Mutex<Log>>,
+}
+
+impl Drop for Transaction {
+	fn drop(&mut self) {
+		if !self.done && self.write {
+			// Check if already panicking
+			if std::thread::panicking() {
+				return;
+			}
+			// Handle the behaviour
+			match self.check {
+				Check::None => {
+					trace!("A transaction was dropped without being committed or cancelled");
+				}
+				Check::Warn => {
+					warn!("A transaction was dropped without being committed or cancelled");
+				}
+				Check::Panic => {
+					#[cfg(debug_assertions)]
+					{
+						let backtrace = std::backtrace::Backtrace::force_capture();
+						if let std::backtrace::BacktraceStatus::Captured = backtrace.status() {
+							println!("{}", backtrace);
+						}
+					}
+					panic!("A transaction was dropped without being committed or cancelled");
+				}
+			}
+		}
+	}
+}
+
+impl Datastore {
+	/// Open a new database
+	pub(crate) async fn new(path: &str) -> Result<Datastore, Error> {
+		// Load the persisted data into memory
+		let (log, data) = Log::open(path).await?;
+		let db = echodb::db::new();
+		let mut tx = db.begin(true).await?;
+		for (key, val) in data {
+			tx.set(key, val)?;
+		}
+		tx.commit()?;
+		Ok(Datastore {
+			db,
+			log: Arc::new(Mutex::new(log)),
+		})
+	}
+	/// Start a new transaction
+	pub(crate) async fn transaction(&self, write: bool, _: bool) -> Result<Transaction, Error> {
+		// Specify the check level
+		#[cfg(not(debug_assertions))]
+		let check = Check::Warn;
+		#[cfg(debug_assertions)]
+		let check = Check::Panic;
+		// Create a new transaction
+		match self.db.begin(write).await {
+			Ok(inner) => Ok(Transaction {
+				done: false,
+				check,
+				write,
+				inner,
+				ops: Vec::new(),
+				log: self.log.clone(),
+			}),
+			Err(e) => Err(Error::Tx(e.to_string())),
+		}
+	}
+}
+
+impl Transaction {
+	/// Behaviour if unclosed
+	pub(crate) fn check_level(&mut self, check: Check) {
+		self.check = check;
+	}
+	/// Check if closed
+	pub(crate) fn closed(&self) -> bool {
+		self.done
+	}
+	/// Cancel a transaction
+	pub(crate) async fn cancel(&mut self) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Mark this transaction as done
+		self.done = true;
+		// Cancel this transaction
+		self.inner.cancel()?;
+		// Continue
+		Ok(())
+	}
+	/// Commit a transaction
+	pub(crate) async fn commit(&mut self) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Mark this transaction as done
+		self.done = true;
+		// Persist the writes before they become visible
+		let persisted = self.log.lock().unwrap_or_else(|e| e.into_inner()).append(&self.ops);
+		if let Err(e) = persisted {
+			self.inner.cancel()?;
+			return Err(e);
+		}
+		// Commit this transaction
+		self.inner.commit()?;
+		// Continue
+		Ok(())
+	}
+	/// Check if a key exists
+	pub(crate) async fn exi<K>(&mut self, key: K) -> Result<bool, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check the key
+		let res = self.inner.exi(key.into())?;
+		// Return result
+		Ok(res)
+	}
+	/// Fetch a key from the database
+	pub(crate) async fn get<K>(&mut self, key: K) -> Result<Option<Val>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Get the key
+		let res = self.inner.get(key.into())?;
+		// Return result
+		Ok(res)
+	}
+	/// Obtain a new change timestamp for a key
+	/// which is replaced with the current timestamp when the transaction is committed.
+	/// NOTE: This should be called when composing the change feed entries for this transaction,
+	/// which should be done immediately before the transaction commit.
+	/// That is to keep other transactions commit delay(pessimistic) or conflict(optimistic) as less as possible.
+	#[allow(unused)]
+	pub(crate) async fn get_timestamp<K>(&mut self, key: K) -> Result<Versionstamp, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Write the timestamp to the "last-write-timestamp" key
+		// to ensure that no other transactions can commit with older timestamps.
+		let k: Key = key.into();
+		let prev = self.inner.get(k.clone())?;
+		let ver = match prev {
+			Some(prev) => {
+				let slice = prev.as_slice();
+				let res: Result<[u8; 10], Error> = match slice.try_into() {
+					Ok(ba) => Ok(ba),
+					Err(e) => Err(Error::Ds(e.to_string())),
+				};
+				let array = res?;
+				let prev = try_to_u64_be(array)?;
+				prev + 1
+			}
+			None => 1,
+		};
+
+		let verbytes = u64_to_versionstamp(ver);
+
+		self.inner.set(k.clone(), verbytes.to_vec())?;
+		self.ops.push(Op::Set(k, verbytes.to_vec()));
+		// Return the uint64 representation of the timestamp as the result
+		Ok(verbytes)
+	}
+	/// Obtain a new key that is suffixed with the change timestamp
+	pub(crate) async fn get_versionstamped_key<K>(
+		&mut self,
+		ts_key: K,
+		prefix: K,
+		suffix: K,
+	) -> Result<Vec<u8>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+
+		let ts_key: Key = ts_key.into();
+		let prefix: Key = prefix.into();
+		let suffix: Key = suffix.into();
+
+		let ts = self.get_timestamp(ts_key.clone()).await?;
+		let mut k: Vec<u8> = prefix.clone();
+		k.append(&mut ts.to_vec());
+		k.append(&mut suffix.clone());
+
+		trace!("get_versionstamped_key; {ts_key:?} {prefix:?} {ts:?} {suffix:?} {k:?}",);
+
+		Ok(k)
+	}
+
+	/// Insert or update a key in the database
+	pub(crate) async fn set<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Set the key
+		let (key, val) = (key.into(), val.into());
+		self.inner.set(key.clone(), val.clone())?;
+		self.ops.push(Op::Set(key, val));
+		// Return result
+		Ok(())
+	}
+	/// Insert a key if it doesn't exist in the database
+	pub(crate) async fn put<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Set the key
+		let (key, val) = (key.into(), val.into());
+		self.inner.put(key.clone(), val.clone())?;
+		self.ops.push(Op::Set(key, val));
+		// Return result
+		Ok(())
+	}
+	/// Insert a key if it doesn't exist in the database
+	pub(crate) async fn putc<K, V>(&mut self, key: K, val: V, chk: Option<V>) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Set the key
+		let (key, val) = (key.into(), val.into());
+		self.inner.putc(key.clone(), val.clone(), chk.map(Into::into))?;
+		self.ops.push(Op::Set(key, val));
+		// Return result
+		Ok(())
+	}
+	/// Delete a key
+	pub(crate) async fn del<K>(&mut self, key: K) -> Result<(), Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Remove the key
+		let key = key.into();
+		self.inner.del(key.clone())?;
+		self.ops.push(Op::Del(key));
+		// Return result
+		Ok(())
+	}
+	/// Delete a key
+	pub(crate) async fn delc<K, V>(&mut self, key: K, chk: Option<V>) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Remove the key
+		let key = key.into();
+		self.inner.delc(key.clone(), chk.map(Into::into))?;
+		self.ops.push(Op::Del(key));
+		// Return result
+		Ok(())
+	}
+	/// Retrieve a range of keys from the databases
+	pub(crate) async fn scan<K>(
+		&mut self,
+		rng: Range<K>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Scan the keys
+		let res = self.inner.scan(rng, limit)?;
+		// Return result
+		Ok(res)
+	}
+}
@@ -0,0 +1,184 @@
+//! Keeps the records of tables which are defined with CACHE FULL in memory
+//!
+//! The records of a cached table are loaded from the datastore the first time they are
+//! read, and are then read from memory, until a transaction which changes the table or
+//! its definition commits. Only storage engines which run on a single node cache tables,
+//! as a node of a distributed storage engine can not see the changes made through others.
+use super::temporary;
+use super::Key;
+use super::Val;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// The records of a table, by their keys
+pub(super) type Records = Arc<BTreeMap<Key, Val>>;
+
+/// The cached tables of a datastore
+#[derive(Clone, Default)]
+pub(super) struct TableCache(Arc<Mutex<State>>);
+
+#[derive(Default)]
+struct State {
+	/// The number of times a cached table has been changed
+	epoch: u64,
+	/// The tables which have been read, by their definition key
+	tables: HashMap<Key, Entry>,
+}
+
+#[derive(Default)]
+struct Entry {
+	/// Whether the table is defined with CACHE FULL
+	cached: Option<bool>,
+	/// The records of the table, once they have been loaded
+	records: Option<Records>,
+}
+
+impl TableCache {
+	fn state(&self) -> std::sync::MutexGuard<'_, State> {
+		self.0.lock().unwrap_or_else(|e| e.into_inner())
+	}
+
+	/// Drops all the cached tables, when the data of the datastore is replaced
+	#[cfg(feature = "kv-mem")]
+	pub(super) fn clear(&self) {
+		let mut state = self.state();
+		state.epoch += 1;
+		state.tables.clear();
+	}
+}
+
+/// Splits the key of a record into the key of its table definition and the range of its records
+pub(super) fn table(key: &[u8]) -> Option<(Key, Range<Key>)> {
+	match temporary::split(key)? {
+		(ns, db, tb, b'*') => Some((
+			crate::key::database::tb::new(ns, db, tb).into(),
+			crate::key::thing::prefix(ns, db, tb)..crate::key::thing::suffix(ns, db, tb),
+		)),
+		_ => None,
+	}
+}
+
+/// The view of a transaction on the cached tables of a datastore
+///
+/// The cache is only filled from transactions which started after the last change to a
+/// cached table, so that records are never cached from an outdated snapshot.
+pub(super) struct Pinned {
+	cache: TableCache,
+	epoch: u64,
+	/// The tables which this transaction has changed, which it reads from the datastore
+	changed: HashSet<Key>,
+}
+
+impl Pinned {
+	pub(super) fn new(cache: TableCache) -> Self {
+		let epoch = cache.state().epoch;
+		Self {
+			cache,
+			epoch,
+			changed: HashSet::new(),
+		}
+	}
+
+	/// Checks whether the records of a table are read from the cache, if this is known
+	pub(super) fn cached(&self, tb: &Key) -> Option<bool> {
+		if self.changed.contains(tb) {
+			return Some(false);
+		}
+		self.cache.state().tables.get(tb).and_then(|e| e.cached)
+	}
+
+	/// Remembers whether a table is defined with CACHE FULL
+	pub(super) fn define(&self, tb: Key, cached: bool) {
+		let mut state = self.cache.state();
+		if state.epoch == self.epoch {
+			state.tables.entry(tb).or_default().cached = Some(cached);
+		}
+	}
+
+	pub(super) fn records(&self, tb: &Key) -> Option<Records> {
+		self.cache.state().tables.get(tb).and_then(|e| e.records.clone())
+	}
+
+	/// Keeps the records of a table which have been loaded from the datastore
+	pub(super) fn fill(&self, tb: Key, records: Records) {
+		let mut state = self.cache.state();
+		if state.epoch == self.epoch {
+			state.tables.entry(tb).or_default().records = Some(records);
+		}
+	}
+
+	/// Tracks a key which this transaction has written
+	pub(super) fn changed(&mut self, key: &Key) {
+		if temporary::is_definition(key) {
+			self.changed.insert(key.clone());
+		} else if let Some((tb, _)) = table(key) {
+			// Changes to tables which are known not to be cached need no tracking
+			if self.cached(&tb) != Some(false) {
+				self.changed.insert(tb);
+			}
+		}
+	}
+
+	/// Drops the tables which a committed transaction has changed from the cache
+	pub(super) fn commit(&mut self) {
+		if self.changed.is_empty() {
+			return;
+		}
+		let mut state = self.cache.state();
+		state.epoch += 1;
+		for tb in self.changed.drain() {
+			state.tables.remove(&tb);
+		}
+	}
+
+	pub(super) fn cancel(&mut self) {
+		self.changed.clear();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn table_of_key() {
+		let rid = crate::key::thing::new("test", "test", "country", &"uk".into());
+		let key: Key = rid.into();
+		let tb: Key = crate::key::database::tb::new("test", "test", "country").into();
+		let (def, rng) = table(&key).unwrap();
+		assert_eq!(def, tb);
+		assert!(rng.contains(&key));
+		let ix: Key = crate::key::index::all::new("test", "test", "country", "code").into();
+		assert!(table(&ix).is_none());
+	}
+
+	#[test]
+	fn invalidated_on_commit() {
+		let cache = TableCache::default();
+		let tb: Key = crate::key::database::tb::new("test", "test", "country").into();
+		let key: Key = crate::key::thing::new("test", "test", "country", &"uk".into()).into();
+		let records = Arc::new(BTreeMap::from([(key.clone(), b"uk".to_vec())]));
+		// A transaction fills the cache
+		let reader = Pinned::new(cache.clone());
+		reader.define(tb.clone(), true);
+		reader.fill(tb.clone(), records.clone());
+		assert_eq!(reader.records(&tb), Some(records.clone()));
+		// A transaction which changes the table reads it from the datastore
+		let mut writer = Pinned::new(cache.clone());
+		writer.changed(&key);
+		assert_eq!(writer.cached(&tb), Some(false));
+		assert_eq!(reader.cached(&tb), Some(true));
+		// Once it commits the table is dropped from the cache
+		writer.commit();
+		assert_eq!(reader.cached(&tb), None);
+		assert_eq!(reader.records(&tb), None);
+		// And transactions which started before can not fill it again
+		reader.fill(tb.clone(), records);
+		assert_eq!(reader.records(&tb), None);
+		assert_eq!(Pinned::new(cache).records(&tb), None);
+	}
+}
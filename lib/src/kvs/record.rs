@@ -0,0 +1,531 @@
+//! The versioned envelope which record values are stored in.
+//!
+//! Every record is stored as a marker byte, followed by the version of the encoding, followed
+//! by a byte of flags, followed by the encoded record. When the encoding of records changes,
+//! [`VERSION`] is incremented and an upgrade hook is added to [`UPGRADES`], which converts
+//! records from the previous encoding. Records are upgraded when they are read, and are stored
+//! with the latest encoding the next time they are written, so existing datastores never need
+//! to be exported and imported again. Records stored with version 1 have no byte of flags.
+//!
+//! Records which were moved to cold storage by a table tiering policy are compressed, which is
+//! marked with the [`COMPRESSED`] flag, and stored under a cold storage key of their own. Their
+//! record key only keeps a stub with the id of the record, which is marked with the [`COLD`]
+//! flag. Cold records are read through from cold storage when they are loaded, so they can be
+//! queried like any other record, and are stored in place again when they are written.
+//!
+//! The records of a table which is defined with `DEDUPLICATE` store each field value which is
+//! larger than the specified size once, keyed by the hash of its content, and reference it from
+//! the records, which is marked with the [`SHARED`] flag. The number of records which
+//! reference each shared value is counted, and the value is removed once it is no longer
+//! referenced.
+//!
+//! Records in cold storage and records with shared values must be read with [`get`] or [`load`].
+//! What a record references outside of its key is kept with it once it has been read, so it can
+//! be released when the record is written again, without reading the record a second time.
+use super::Transaction;
+use super::Val;
+use crate::err::Error;
+use crate::sql::{Id, Object, Part, Value};
+use revision::Revisioned;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io;
+
+/// Marks a record which is stored in the versioned envelope
+///
+/// Records stored before the envelope was introduced start with the revision of the
+/// [`Value`] type, which is never zero, so they can not be mistaken for an envelope.
+const MARKER: u8 = 0x00;
+
+/// The version of the encoding which records are currently stored with
+pub(crate) const VERSION: u8 = 2;
+
+/// Marks the stub of a record which was moved to cold storage
+const COLD: u8 = 0x01;
+
+/// Marks a record which references shared values
+const SHARED: u8 = 0x02;
+
+/// Marks a record which is compressed
+const COMPRESSED: u8 = 0x04;
+
+/// What a stored record references outside of its key
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Refs {
+	/// The hashes of the shared values which the record references
+	hashes: Vec<String>,
+	/// Whether the record is in cold storage
+	cold: bool,
+}
+
+/// Converts an encoded record from one version of the encoding to the next
+type Upgrade = fn(Vec<u8>) -> Result<Vec<u8>, Error>;
+
+/// The upgrade hooks, where the hook at index `n` upgrades records from version `n` to `n + 1`
+///
+/// Records stored before the envelope use the same encoding as version 1, and version 2 only
+/// added the byte of flags to the envelope, so neither of them changes the encoded record.
+const UPGRADES: [Upgrade; VERSION as usize] = [Ok, Ok];
+
+/// Encodes a record value for storing in the datastore
+pub(crate) fn encode(val: &Value) -> Result<Val, Error> {
+	let mut out = vec![MARKER, VERSION, 0];
+	val.serialize_revisioned(&mut out)?;
+	Ok(out)
+}
+
+/// Encodes a record value for storing in cold storage, where it is compressed
+fn encode_compressed(val: &Value) -> Result<Val, Error> {
+	let mut out = vec![MARKER, VERSION, COMPRESSED];
+	{
+		let mut wtr = snap::write::FrameEncoder::new(&mut out);
+		val.serialize_revisioned(&mut wtr)?;
+		io::Write::flush(&mut wtr)?;
+	}
+	Ok(out)
+}
+
+/// Encodes the stub which is stored in place of a record which was moved to cold storage
+fn encode_stub(id: &Id) -> Result<Val, Error> {
+	let mut out = vec![MARKER, VERSION, COLD];
+	id.serialize_revisioned(&mut out)?;
+	Ok(out)
+}
+
+/// Checks whether a stored record is in cold storage
+pub(crate) fn is_cold(val: &[u8]) -> bool {
+	envelope(val).1 & COLD != 0
+}
+
+/// Checks whether a stored record references shared values
+pub(crate) fn is_shared(val: &[u8]) -> bool {
+	envelope(val).1 & SHARED != 0
+}
+
+/// Decodes a record value which was stored in the datastore, upgrading it if necessary
+///
+/// A record in cold storage, or which references shared values, can not be decoded from what
+/// is stored under its record key alone, and is loaded with [`load`] instead.
+pub(crate) fn decode(val: &[u8]) -> Result<Value, Error> {
+	match envelope(val) {
+		(_, flags, _) if flags & COLD != 0 => Err(Error::ColdRecordNotLoaded),
+		(_, flags, _) if flags & SHARED != 0 => Err(Error::SharedValuesNotLoaded),
+		(version, flags, val) if flags & COMPRESSED != 0 => {
+			let mut out = Vec::new();
+			io::copy(&mut snap::read::FrameDecoder::new(val), &mut out)?;
+			decode_version(version, &out)
+		}
+		(version, _, val) => decode_version(version, val),
+	}
+}
+
+/// Splits a stored record into the version of its encoding, its flags, and the encoded record
+fn envelope(val: &[u8]) -> (u8, u8, &[u8]) {
+	match val {
+		// The record was stored before the byte of flags was introduced
+		[MARKER, 1, val @ ..] => (1, 0, val),
+		[MARKER, version, flags, val @ ..] => (*version, *flags, val),
+		// The record was stored before the envelope was introduced
+		val => (0, 0, val),
+	}
+}
+
+/// Reads a record from the datastore, returning NONE if the record does not exist
+pub(crate) async fn get(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	id: &Id,
+) -> Result<Value, Error> {
+	Ok(get_with_refs(tx, ns, db, tb, id).await?.0)
+}
+
+/// Reads a record from the datastore, along with what it references outside of its key
+pub(crate) async fn get_with_refs(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	id: &Id,
+) -> Result<(Value, Refs), Error> {
+	let key = crate::key::thing::new(ns, db, tb, id);
+	match tx.get(key).await? {
+		Some(v) => load_with_refs(tx, ns, db, tb, &v).await,
+		None => Ok((Value::None, Refs::default())),
+	}
+}
+
+/// Decodes a record which was read from the datastore, reading it through from cold storage, and
+/// along with any shared values it references
+pub(crate) async fn load(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	val: &[u8],
+) -> Result<Value, Error> {
+	Ok(load_with_refs(tx, ns, db, tb, val).await?.0)
+}
+
+/// Decodes a record which was read from the datastore, along with what it references outside
+/// of its key
+pub(crate) async fn load_with_refs(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	val: &[u8],
+) -> Result<(Value, Refs), Error> {
+	if let Some(id) = stub(val)? {
+		let key = crate::key::table::cd::new(ns, db, tb, &id);
+		return match tx.get(key).await? {
+			Some(v) => Ok((
+				decode(&v)?,
+				Refs {
+					cold: true,
+					..Refs::default()
+				},
+			)),
+			None => Err(Error::ColdRecordNotFound {
+				id: id.to_raw(),
+			}),
+		};
+	}
+	let Some((refs, mut val)) = split(val)? else {
+		return Ok((decode(val)?, Refs::default()));
+	};
+	let mut hashes = Vec::with_capacity(refs.len());
+	for (field, hash) in refs {
+		let key = crate::key::table::cs::new(ns, db, tb, &hash);
+		match tx.get(key).await? {
+			Some(v) => val.put(&[Part::from(field)], decode(&v)?),
+			None => {
+				return Err(Error::SharedValueNotFound {
+					hash,
+				})
+			}
+		}
+		hashes.push(hash);
+	}
+	Ok((
+		val,
+		Refs {
+			hashes,
+			cold: false,
+		},
+	))
+}
+
+/// Stores a record in the datastore
+///
+/// When `dedup` is specified, each field value which is larger than `dedup` bytes is stored
+/// once, and shared with the other records of the table which contain the same value. What the
+/// previous version of the record references outside of its key, which is passed in `prior`
+/// when the record has already been read, is released. When the record as it was read is
+/// passed in `cas`, the record is only written if it is still stored that way, and
+/// [`Error::TxConditionNotMet`] is returned otherwise.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn store(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	id: &Id,
+	val: &Value,
+	dedup: Option<u64>,
+	prior: Option<&Refs>,
+	cas: Option<&Value>,
+) -> Result<(), Error> {
+	let key = crate::key::thing::new(ns, db, tb, id);
+	let chk = match cas {
+		Some(cas) => Some(checked(tx, ns, db, tb, &key, cas).await?),
+		None => None,
+	};
+	let mut counts = released(tx, ns, db, tb, &key, prior).await?;
+	let mut values = HashMap::new();
+	let enc = match (dedup, val) {
+		(Some(size), Value::Object(obj)) => {
+			let mut refs = Vec::new();
+			let mut rest = Object::default();
+			for (field, v) in obj.iter() {
+				let enc = encode(v)?;
+				if enc.len() as u64 > size {
+					let hash = format!("{:x}", Sha256::digest(&enc));
+					*counts.entry(hash.clone()).or_default() += 1;
+					refs.push((field.clone(), hash.clone()));
+					values.insert(hash, enc);
+				} else {
+					rest.insert(field.clone(), v.clone());
+				}
+			}
+			match refs.is_empty() {
+				true => encode(val)?,
+				false => encode_shared(&rest.into(), refs)?,
+			}
+		}
+		_ => encode(val)?,
+	};
+	match chk {
+		Some(chk) => tx.putc(key, enc, chk).await?,
+		None => tx.set(key, enc).await?,
+	}
+	count(tx, ns, db, tb, counts, values).await
+}
+
+/// Deletes a record from the datastore, releasing what it references outside of its key
+///
+/// When the record as it was read is passed in `cas`, the record is only deleted if it is
+/// still stored that way, and [`Error::TxConditionNotMet`] is returned otherwise.
+pub(crate) async fn delete(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	id: &Id,
+	prior: Option<&Refs>,
+	cas: Option<&Value>,
+) -> Result<(), Error> {
+	let key = crate::key::thing::new(ns, db, tb, id);
+	let chk = match cas {
+		Some(cas) => checked(tx, ns, db, tb, &key, cas).await?,
+		None => None,
+	};
+	let counts = released(tx, ns, db, tb, &key, prior).await?;
+	match chk {
+		Some(chk) => tx.delc(key, Some(chk)).await?,
+		None => tx.del(key).await?,
+	}
+	count(tx, ns, db, tb, counts, HashMap::new()).await
+}
+
+/// Moves a record to cold storage, leaving a stub with the id of the record under its key
+pub(crate) async fn freeze(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	id: &Id,
+	val: &Value,
+) -> Result<(), Error> {
+	tx.set(crate::key::table::cd::new(ns, db, tb, id), encode_compressed(val)?).await?;
+	tx.set(crate::key::thing::new(ns, db, tb, id), encode_stub(id)?).await
+}
+
+/// Checks that a record is still stored as it was read, returning the stored record
+///
+/// The returned record is what a conditional write of the record has to replace, or `None`
+/// when the record didn't exist, and must still not exist when it is written.
+async fn checked(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	key: &crate::key::thing::Thing<'_>,
+	cas: &Value,
+) -> Result<Option<Val>, Error> {
+	match tx.get(key.clone()).await? {
+		Some(v) if !cas.is_none() && load(tx, ns, db, tb, &v).await? == *cas => Ok(Some(v)),
+		None if cas.is_none() => Ok(None),
+		_ => Err(Error::TxConditionNotMet),
+	}
+}
+
+/// Encodes a record value, along with the fields which reference shared values by their hash
+fn encode_shared(val: &Value, refs: Vec<(String, String)>) -> Result<Val, Error> {
+	let refs = Object(refs.into_iter().map(|(field, hash)| (field, hash.into())).collect());
+	let mut head = Vec::new();
+	Value::from(refs).serialize_revisioned(&mut head)?;
+	let mut out = vec![MARKER, VERSION, SHARED];
+	out.extend_from_slice(&(head.len() as u32).to_be_bytes());
+	out.extend(head);
+	val.serialize_revisioned(&mut out)?;
+	Ok(out)
+}
+
+/// Reads the id of a record from the stub which is stored in its place in cold storage
+fn stub(val: &[u8]) -> Result<Option<Id>, Error> {
+	match envelope(val) {
+		(_, flags, mut val) if flags & COLD != 0 => Ok(Some(Id::deserialize_revisioned(&mut val)?)),
+		_ => Ok(None),
+	}
+}
+
+/// Splits a stored record into the fields which reference shared values, and the rest of the record
+fn split(val: &[u8]) -> Result<Option<(Vec<(String, String)>, Value)>, Error> {
+	match envelope(val) {
+		(version, flags, [a, b, c, d, val @ ..]) if flags & SHARED != 0 => {
+			let len = (u32::from_be_bytes([*a, *b, *c, *d]) as usize).min(val.len());
+			let (head, rest) = val.split_at(len);
+			let Value::Object(head) = decode_version(version, head)? else {
+				return Err(Error::Unreachable);
+			};
+			let refs = head
+				.0
+				.into_iter()
+				.filter_map(|(field, hash)| match hash {
+					Value::Strand(hash) => Some((field, hash.0)),
+					_ => None,
+				})
+				.collect();
+			Ok(Some((refs, decode_version(version, rest)?)))
+		}
+		(_, flags, _) if flags & SHARED != 0 => Err(Error::Unreachable),
+		_ => Ok(None),
+	}
+}
+
+/// Releases what a stored record references outside of its key when the record is overwritten,
+/// removing it from cold storage, and counting the references to shared values which are released
+///
+/// The stored record is only read if what it references isn't known.
+async fn released(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	key: &crate::key::thing::Thing<'_>,
+	prior: Option<&Refs>,
+) -> Result<HashMap<String, i64>, Error> {
+	let refs = match prior {
+		Some(refs) => refs.clone(),
+		None => match tx.get(key.clone()).await? {
+			Some(old) => Refs {
+				hashes: match split(&old)? {
+					Some((refs, _)) => refs.into_iter().map(|(_, hash)| hash).collect(),
+					None => Vec::new(),
+				},
+				cold: is_cold(&old),
+			},
+			None => Refs::default(),
+		},
+	};
+	if refs.cold {
+		tx.del(crate::key::table::cd::new(ns, db, tb, &key.id)).await?;
+	}
+	let mut counts = HashMap::new();
+	for hash in refs.hashes {
+		*counts.entry(hash).or_default() -= 1;
+	}
+	Ok(counts)
+}
+
+/// Updates the number of references to shared values, storing new values and removing unused ones
+async fn count(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	counts: HashMap<String, i64>,
+	mut values: HashMap<String, Val>,
+) -> Result<(), Error> {
+	for (hash, n) in counts {
+		if n == 0 {
+			continue;
+		}
+		let key = crate::key::table::cr::new(ns, db, tb, &hash);
+		let refs = match tx.get(key.clone()).await? {
+			Some(v) => u64::from_be_bytes(v.try_into().map_err(|_| Error::Unreachable)?),
+			None => 0,
+		};
+		let total = refs as i64 + n;
+		if total <= 0 {
+			tx.del(key).await?;
+			tx.del(crate::key::table::cs::new(ns, db, tb, &hash)).await?;
+			continue;
+		}
+		if refs == 0 {
+			if let Some(v) = values.remove(&hash) {
+				tx.set(crate::key::table::cs::new(ns, db, tb, &hash), v).await?;
+			}
+		}
+		tx.set(key, (total as u64).to_be_bytes().to_vec()).await?;
+	}
+	Ok(())
+}
+
+/// Decodes a record which was encoded with the specified version of the encoding
+fn decode_version(version: u8, mut val: &[u8]) -> Result<Value, Error> {
+	match version {
+		VERSION => Ok(Value::deserialize_revisioned(&mut val)?),
+		v if v > VERSION => Err(Error::RecordVersion {
+			version,
+		}),
+		_ => {
+			let mut val = val.to_vec();
+			for upgrade in &UPGRADES[version as usize..] {
+				val = upgrade(val)?;
+			}
+			Ok(Value::deserialize_revisioned(&mut val.as_slice())?)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn record_roundtrip() {
+		let val = Value::parse("{ name: 'Tobie', tags: ['founder'], age: 33 }");
+		let enc = encode(&val).unwrap();
+		assert_eq!(&enc[..3], &[MARKER, VERSION, 0]);
+		assert_eq!(decode(&enc).unwrap(), val);
+	}
+
+	#[test]
+	fn record_version_without_flags() {
+		let val = Value::parse("{ name: 'Tobie', tags: ['founder'], age: 33 }");
+		let mut enc = vec![MARKER, 1];
+		val.serialize_revisioned(&mut enc).unwrap();
+		assert!(!is_cold(&enc));
+		assert!(!is_shared(&enc));
+		assert_eq!(decode(&enc).unwrap(), val);
+	}
+
+	#[test]
+	fn record_unversioned() {
+		let val = Value::parse("{ name: 'Tobie', tags: ['founder'], age: 33 }");
+		let enc: Vec<u8> = val.clone().try_into().unwrap();
+		assert_ne!(enc[0], MARKER);
+		assert_eq!(decode(&enc).unwrap(), val);
+	}
+
+	#[test]
+	fn record_newer_version() {
+		let enc = vec![MARKER, VERSION + 1, 0, 1, 0];
+		assert!(matches!(decode(&enc), Err(Error::RecordVersion { .. })));
+	}
+
+	#[test]
+	fn record_compressed_roundtrip() {
+		let val = Value::parse("{ name: 'Tobie', tags: ['founder'], age: 33 }");
+		let enc = encode_compressed(&val).unwrap();
+		assert!(!is_cold(&enc));
+		assert_eq!(decode(&enc).unwrap(), val);
+	}
+
+	#[test]
+	fn record_cold_stub() {
+		let id = Id::from("tobie");
+		let enc = encode_stub(&id).unwrap();
+		assert!(is_cold(&enc));
+		assert!(!is_cold(&encode(&Value::None).unwrap()));
+		assert!(matches!(decode(&enc), Err(Error::ColdRecordNotLoaded)));
+		assert_eq!(stub(&enc).unwrap(), Some(id));
+		assert_eq!(stub(&encode(&Value::None).unwrap()).unwrap(), None);
+	}
+
+	#[test]
+	fn record_shared_roundtrip() {
+		let val = Value::parse("{ name: 'Tobie' }");
+		let refs = vec![("bio".to_owned(), "0123abcd".to_owned())];
+		let enc = encode_shared(&val, refs.clone()).unwrap();
+		assert!(is_shared(&enc));
+		assert!(!is_shared(&encode(&val).unwrap()));
+		assert!(matches!(decode(&enc), Err(Error::SharedValuesNotLoaded)));
+		assert_eq!(split(&enc).unwrap(), Some((refs, val.clone())));
+		assert_eq!(split(&encode(&val).unwrap()).unwrap(), None);
+	}
+}
@@ -0,0 +1,453 @@
+#![cfg(feature = "kv-redb")]
+
+use crate::err::Error;
+use crate::kvs::Check;
+use crate::kvs::Key;
+use crate::kvs::Val;
+use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
+use redb::{ReadableTable, TableDefinition};
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// The table which all keys are stored in
+const TABLE: TableDefinition<&[u8], &[u8]> = TableDefinition::new("surrealdb");
+
+#[derive(Clone)]
+pub struct Datastore {
+	db: Pin<Arc<redb::Database>>,
+}
+
+/// The underlying redb transaction
+///
+/// redb allows only one write transaction at a time, so read-only
+/// transactions use a read transaction, which never blocks.
+enum Inner {
+	Read(redb::ReadTransaction<'static>),
+	Write(redb::WriteTransaction<'static>),
+}
+
+pub struct Transaction {
+	// Is the transaction complete?
+	done: bool,
+	// Is the transaction writeable?
+	write: bool,
+	/// Should we check unhandled transactions?
+	check: Check,
+	/// The underlying datastore transaction
+	inner: Option<Inner>,
+	// The above, supposedly 'static transaction
+	// actually points here, so we need to ensure
+	// the memory is kept alive. This pointer must
+	// be declared last, so that it is dropped last
+	_db: Pin<Arc<redb::Database>>,
+}
+
+impl Drop for Transaction {
+	fn drop(&mut self) {
+		if !self.done && self.write {
+			// Check if already panicking
+			if std::thread::panicking() {
+				return;
+			}
+			// Handle the behaviour
+			match self.check {
+				Check::None => {
+					trace!("A transaction was dropped without being committed or cancelled");
+				}
+				Check::Warn => {
+					warn!("A transaction was dropped without being committed or cancelled");
+				}
+				Check::Panic => {
+					#[cfg(debug_assertions)]
+					{
+						let backtrace = std::backtrace::Backtrace::force_capture();
+						if let std::backtrace::BacktraceStatus::Captured = backtrace.status() {
+							println!("{}", backtrace);
+						}
+					}
+					panic!("A transaction was dropped without being committed or cancelled");
+				}
+			}
+		}
+	}
+}
+
+impl Datastore {
+	/// Open a new database
+	pub(crate) async fn new(path: &str) -> Result<Datastore, Error> {
+		let db = redb::Database::create(path)?;
+		// Ensure the table exists, so that it can be opened in read transactions
+		let tx = db.begin_write()?;
+		tx.open_table(TABLE)?;
+		tx.commit()?;
+		// Continue
+		Ok(Datastore {
+			db: Arc::pin(db),
+		})
+	}
+	/// Start a new transaction
+	pub(crate) async fn transaction(&self, write: bool, _: bool) -> Result<Transaction, Error> {
+		// The database reference must always outlive
+		// the transaction. If it doesn't then this
+		// is undefined behaviour. These unsafe blocks
+		// ensure that the transaction reference is
+		// static, but will cause a crash if the
+		// datastore is dropped prematurely.
+		let inner = match write {
+			true => Inner::Write(unsafe {
+				std::mem::transmute::<redb::WriteTransaction<'_>, redb::WriteTransaction<'static>>(
+					self.db.begin_write()?,
+				)
+			}),
+			false => Inner::Read(unsafe {
+				std::mem::transmute::<redb::ReadTransaction<'_>, redb::ReadTransaction<'static>>(
+					self.db.begin_read()?,
+				)
+			}),
+		};
+		// Specify the check level
+		#[cfg(not(debug_assertions))]
+		let check = Check::Warn;
+		#[cfg(debug_assertions)]
+		let check = Check::Panic;
+		// Create a new transaction
+		Ok(Transaction {
+			done: false,
+			check,
+			write,
+			inner: Some(inner),
+			_db: self.db.clone(),
+		})
+	}
+}
+
+/// Fetch a key from a table
+fn get<T>(table: &T, key: &[u8]) -> Result<Option<Val>, Error>
+where
+	T: ReadableTable<&'static [u8], &'static [u8]>,
+{
+	Ok(table.get(key)?.map(|v| v.value().to_vec()))
+}
+
+/// Retrieve a range of keys from a table
+fn scan<T>(table: &T, rng: Range<&[u8]>, limit: u32) -> Result<Vec<(Key, Val)>, Error>
+where
+	T: ReadableTable<&'static [u8], &'static [u8]>,
+{
+	let mut res = vec![];
+	for item in table.range(rng)?.take(limit as usize) {
+		let (k, v) = item?;
+		res.push((k.value().to_vec(), v.value().to_vec()));
+	}
+	Ok(res)
+}
+
+impl Transaction {
+	/// Fetch a key as seen by this transaction
+	fn read(&self, key: &[u8]) -> Result<Option<Val>, Error> {
+		match self.inner.as_ref().unwrap() {
+			Inner::Read(tx) => get(&tx.open_table(TABLE)?, key),
+			Inner::Write(tx) => get(&tx.open_table(TABLE)?, key),
+		}
+	}
+	/// Get the write transaction
+	fn writer(&self) -> &redb::WriteTransaction<'static> {
+		match self.inner.as_ref().unwrap() {
+			Inner::Write(tx) => tx,
+			Inner::Read(_) => unreachable!(),
+		}
+	}
+	/// Insert or update a key
+	fn insert(&self, key: &[u8], val: &[u8]) -> Result<(), Error> {
+		self.writer().open_table(TABLE)?.insert(key, val)?;
+		Ok(())
+	}
+	/// Remove a key
+	fn remove(&self, key: &[u8]) -> Result<(), Error> {
+		self.writer().open_table(TABLE)?.remove(key)?;
+		Ok(())
+	}
+	/// Behaviour if unclosed
+	pub(crate) fn check_level(&mut self, check: Check) {
+		self.check = check;
+	}
+	/// Check if closed
+	pub(crate) fn closed(&self) -> bool {
+		self.done
+	}
+	/// Cancel a transaction
+	pub(crate) async fn cancel(&mut self) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Mark this transaction as done
+		self.done = true;
+		// Cancel this transaction
+		match self.inner.take() {
+			Some(Inner::Write(tx)) => tx.abort()?,
+			Some(Inner::Read(_)) => (),
+			None => unreachable!(),
+		};
+		// Continue
+		Ok(())
+	}
+	/// Commit a transaction
+	pub(crate) async fn commit(&mut self) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Mark this transaction as done
+		self.done = true;
+		// Commit this transaction
+		match self.inner.take() {
+			Some(Inner::Write(tx)) => tx.commit()?,
+			_ => unreachable!(),
+		};
+		// Continue
+		Ok(())
+	}
+	/// Check if a key exists
+	pub(crate) async fn exi<K>(&mut self, key: K) -> Result<bool, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check the key
+		let res = self.read(&key.into())?.is_some();
+		// Return result
+		Ok(res)
+	}
+	/// Fetch a key from the database
+	pub(crate) async fn get<K>(&mut self, key: K) -> Result<Option<Val>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Get the key
+		let res = self.read(&key.into())?;
+		// Return result
+		Ok(res)
+	}
+	/// Obtain a new change timestamp for a key
+	/// which is replaced with the current timestamp when the transaction is committed.
+	/// NOTE: This should be called when composing the change feed entries for this transaction,
+	/// which should be done immediately before the transaction commit.
+	/// That is to keep other transactions commit delay(pessimistic) or conflict(optimistic) as less as possible.
+	#[allow(unused)]
+	pub(crate) async fn get_timestamp<K>(&mut self, key: K) -> Result<Versionstamp, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Write the timestamp to the "last-write-timestamp" key
+		// to ensure that no other transactions can commit with older timestamps.
+		let k: Key = key.into();
+		let prev = self.read(&k)?;
+		let ver = match prev {
+			Some(prev) => {
+				let slice = prev.as_slice();
+				let res: Result<[u8; 10], Error> = match slice.try_into() {
+					Ok(ba) => Ok(ba),
+					Err(e) => Err(Error::Ds(e.to_string())),
+				};
+				let array = res?;
+				let prev = try_to_u64_be(array)?;
+				prev + 1
+			}
+			None => 1,
+		};
+
+		let verbytes = u64_to_versionstamp(ver);
+
+		self.insert(&k, &verbytes)?;
+		// Return the uint64 representation of the timestamp as the result
+		Ok(verbytes)
+	}
+	/// Obtain a new key that is suffixed with the change timestamp
+	pub(crate) async fn get_versionstamped_key<K>(
+		&mut self,
+		ts_key: K,
+		prefix: K,
+		suffix: K,
+	) -> Result<Vec<u8>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		let ts = self.get_timestamp(ts_key).await?;
+		let mut k: Vec<u8> = prefix.into();
+		k.append(&mut ts.to_vec());
+		k.append(&mut suffix.into());
+		Ok(k)
+	}
+	/// Insert or update a key in the database
+	pub(crate) async fn set<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Set the key
+		self.insert(&key.into(), &val.into())?;
+		// Return result
+		Ok(())
+	}
+	/// Insert a key if it doesn't exist in the database
+	pub(crate) async fn put<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let val = val.into();
+		// Set the key if empty
+		match self.read(&key)? {
+			None => self.insert(&key, &val)?,
+			_ => return Err(Error::TxKeyAlreadyExists),
+		};
+		// Return result
+		Ok(())
+	}
+	/// Insert a key if it doesn't exist in the database
+	pub(crate) async fn putc<K, V>(&mut self, key: K, val: V, chk: Option<V>) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let val = val.into();
+		let chk = chk.map(Into::into);
+		// Set the key if valid
+		match (self.read(&key)?, chk) {
+			(Some(v), Some(w)) if v == w => self.insert(&key, &val)?,
+			(None, None) => self.insert(&key, &val)?,
+			_ => return Err(Error::TxConditionNotMet),
+		};
+		// Return result
+		Ok(())
+	}
+	/// Delete a key
+	pub(crate) async fn del<K>(&mut self, key: K) -> Result<(), Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Remove the key
+		self.remove(&key.into())?;
+		// Return result
+		Ok(())
+	}
+	/// Delete a key
+	pub(crate) async fn delc<K, V>(&mut self, key: K, chk: Option<V>) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let chk = chk.map(Into::into);
+		// Delete the key if valid
+		match (self.read(&key)?, chk) {
+			(Some(v), Some(w)) if v == w => self.remove(&key)?,
+			(None, None) => self.remove(&key)?,
+			_ => return Err(Error::TxConditionNotMet),
+		};
+		// Return result
+		Ok(())
+	}
+	/// Retrieve a range of keys from the databases
+	pub(crate) async fn scan<K>(
+		&mut self,
+		rng: Range<K>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Set the key range
+		let rng = rng.start.as_slice()..rng.end.as_slice();
+		// Retrieve the keys
+		let res = match self.inner.as_ref().unwrap() {
+			Inner::Read(tx) => scan(&tx.open_table(TABLE)?, rng, limit)?,
+			Inner::Write(tx) => scan(&tx.open_table(TABLE)?, rng, limit)?,
+		};
+		// Return result
+		Ok(res)
+	}
+}
@@ -0,0 +1,128 @@
+//! Warm-standby replication of local storage engines
+//!
+//! A [`Replica`] ships consistent snapshots of a RocksDB or SpeeDB datastore to a standby
+//! directory at a fixed interval. The snapshots are checkpoints of the storage engine, which
+//! hard-link the immutable SST files and copy the manifest and WAL segments, so each snapshot
+//! only writes the data which changed since the previous one. The standby directory can be
+//! opened as a datastore of the same engine to fail over.
+
+use super::Datastore;
+use crate::err::Error;
+use futures::lock::Mutex;
+use std::ffi::OsString;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Ships snapshots of the datastore to a standby directory
+pub(super) struct Replica {
+	/// The standby directory
+	path: PathBuf,
+	/// The number of seconds between snapshots
+	interval: u64,
+	/// When the previous snapshot was taken
+	last: Mutex<Option<u64>>,
+}
+
+impl Replica {
+	pub(super) fn new(path: PathBuf, interval: Duration) -> Self {
+		Self {
+			path,
+			interval: interval.as_secs().max(1),
+			last: Mutex::new(None),
+		}
+	}
+
+	/// Ships a snapshot, if one is due at the timestamp
+	pub(super) async fn run_at(&self, ds: &Datastore, ts: u64) {
+		let mut last = self.last.lock().await;
+		if matches!(*last, Some(last) if ts < last + self.interval) {
+			return;
+		}
+		*last = Some(ts);
+		if let Err(e) = self.ship(ds) {
+			warn!("Replication to {} failed: {e}", self.path.display());
+		}
+	}
+
+	/// Replaces the standby directory with a new snapshot
+	///
+	/// The snapshot is written next to the standby directory and then moved into place,
+	/// so the standby directory never contains a partly written snapshot.
+	fn ship(&self, ds: &Datastore) -> Result<(), Error> {
+		let tmp = sibling(&self.path, "tmp");
+		let old = sibling(&self.path, "old");
+		if let Some(parent) = self.path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		remove(&tmp)?;
+		ds.checkpoint(&tmp)?;
+		remove(&old)?;
+		if self.path.exists() {
+			fs::rename(&self.path, &old)?;
+		}
+		fs::rename(&tmp, &self.path)?;
+		remove(&old)
+	}
+}
+
+/// The path of a directory next to the standby directory, with the given extension
+fn sibling(path: &Path, ext: &str) -> PathBuf {
+	let mut name = OsString::from(path.as_os_str());
+	name.push(".");
+	name.push(ext);
+	PathBuf::from(name)
+}
+
+/// Removes a directory, if it exists
+fn remove(path: &Path) -> Result<(), Error> {
+	match fs::remove_dir_all(path) {
+		Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+		_ => Ok(()),
+	}
+}
+
+#[cfg(all(test, feature = "kv-rocksdb"))]
+mod tests {
+	use super::*;
+	use crate::dbs::Session;
+	use crate::sql::test::Parse;
+	use crate::sql::Value;
+	use temp_dir::TempDir;
+
+	#[tokio::test]
+	async fn ships_snapshots_to_standby() {
+		let dir = TempDir::new().unwrap();
+		let standby = dir.path().join("standby");
+		let path = format!("rocksdb:{}", dir.path().join("primary").display());
+		let ds = Datastore::new(&path)
+			.await
+			.unwrap()
+			.replicate_to(&standby, Duration::from_secs(10))
+			.unwrap();
+		let sess = Session::owner().with_ns("test").with_db("test");
+		ds.execute("CREATE person:one", &sess, None).await.unwrap();
+		ds.replicate_at(100).await.unwrap();
+		// Snapshots are not shipped again until the interval has passed
+		ds.execute("CREATE person:two", &sess, None).await.unwrap();
+		ds.replicate_at(105).await.unwrap();
+		let replica = Datastore::new(&format!("rocksdb:{}", standby.display())).await.unwrap();
+		let mut res = replica.execute("SELECT VALUE id FROM person", &sess, None).await.unwrap();
+		assert_eq!(res.remove(0).result.unwrap(), Value::parse("[person:one]"));
+		drop(replica);
+		ds.replicate_at(110).await.unwrap();
+		let replica = Datastore::new(&format!("rocksdb:{}", standby.display())).await.unwrap();
+		let mut res = replica.execute("SELECT VALUE id FROM person", &sess, None).await.unwrap();
+		assert_eq!(res.remove(0).result.unwrap(), Value::parse("[person:one, person:two]"));
+	}
+
+	#[cfg(feature = "kv-mem")]
+	#[tokio::test]
+	async fn rejects_unsupported_engines() {
+		let res =
+			Datastore::new("memory").await.unwrap().replicate_to("standby", Duration::from_secs(1));
+		assert!(matches!(res, Err(Error::ReplicationUnsupported(_))));
+	}
+}
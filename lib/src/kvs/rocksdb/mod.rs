@@ -8,6 +8,7 @@ use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
 use futures::lock::Mutex;
 use rocksdb::{OptimisticTransactionDB, OptimisticTransactionOptions, ReadOptions, WriteOptions};
 use std::ops::Range;
+use std::path::Path;
 use std::pin::Pin;
 use std::sync::Arc;
 
@@ -71,6 +72,14 @@ impl Datastore {
 			db: Arc::pin(OptimisticTransactionDB::open_default(path)?),
 		})
 	}
+	/// Write a consistent snapshot of the database to a new directory
+	///
+	/// The SST files are hard-linked into the directory, when it is on the same
+	/// filesystem, and the manifest and WAL segments are copied.
+	pub(crate) fn checkpoint(&self, path: &Path) -> Result<(), Error> {
+		rocksdb::checkpoint::Checkpoint::new(&*self.db)?.create_checkpoint(path)?;
+		Ok(())
+	}
 	/// Start a new transaction
 	pub(crate) async fn transaction(&self, write: bool, _: bool) -> Result<Transaction, Error> {
 		// Activate the snapshot options
@@ -0,0 +1,132 @@
+//! Maintains the aggregates of rollup tables, from the change feeds of their source tables
+//!
+//! Each bucket of a rollup table is stored as a record, whose id is the start of the bucket.
+//! The change feed only records the current version of a changed record, so what each source
+//! record contributes to its bucket is stored alongside the rollup table, and is removed from
+//! the bucket again when the source record is updated or deleted.
+use super::record;
+use super::tx::Transaction;
+use crate::cf::TableMutation;
+use crate::err::Error;
+use crate::sql::Array;
+use crate::sql::Id;
+use crate::sql::Number;
+use crate::sql::Part;
+use crate::sql::Rollup;
+use crate::sql::Thing;
+use crate::sql::Value;
+use chrono::TimeZone;
+use chrono::Utc;
+
+const BUCKET: &str = "bucket";
+const COUNT: &str = "count";
+const SUM: &str = "sum";
+
+/// Applies a change to a record of the source table to the buckets of the rollup table
+pub(crate) async fn apply(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	ru: &Rollup,
+	mutation: TableMutation,
+) -> Result<(), Error> {
+	let (id, val) = match mutation {
+		TableMutation::Set(id, val) => (id.id, Some(val)),
+		TableMutation::Del(id) => (id.id, None),
+		TableMutation::Def(_) => return Ok(()),
+	};
+	let key = crate::key::table::rs::new(ns, db, tb, &id);
+	// Remove what the previous version of the record contributed
+	if let Some(old) = tx.get(key.clone()).await? {
+		let old = record::load(tx, ns, db, tb, &old).await?;
+		update(tx, ns, db, tb, ru, &old, false).await?;
+	}
+	// Add what the current version of the record contributes
+	match val.and_then(|val| contribution(ru, &val)) {
+		Some(new) => {
+			update(tx, ns, db, tb, ru, &new, true).await?;
+			tx.set(key, record::encode(&new)?).await
+		}
+		None => tx.del(key).await,
+	}
+}
+
+/// Works out the bucket of a record, and the values which it adds to the sums of the bucket
+fn contribution(ru: &Rollup, val: &Value) -> Option<Value> {
+	let Value::Datetime(at) = val.pick(&ru.field) else {
+		return None;
+	};
+	let every = ru.every.as_secs().max(1) as i64;
+	let secs = at.0.timestamp();
+	let bucket = Utc.timestamp_opt(secs - secs.rem_euclid(every), 0).single()?;
+	let mut out = Value::base();
+	out.put(&[Part::from(BUCKET)], bucket.into());
+	for field in ru.sum.iter() {
+		if let Value::Number(v) = val.pick(field) {
+			out.put(&sum(field), v.into());
+		}
+	}
+	Some(out)
+}
+
+/// Adds a contribution to, or removes it from, its bucket
+async fn update(
+	tx: &mut Transaction,
+	ns: &str,
+	db: &str,
+	tb: &str,
+	ru: &Rollup,
+	contribution: &Value,
+	add: bool,
+) -> Result<(), Error> {
+	let bucket = contribution.pick(&[Part::from(BUCKET)]);
+	let id = Id::Array(Array(vec![bucket.clone()]));
+	let key = crate::key::thing::new(ns, db, tb, &id);
+	let mut rec = match tx.get(key.clone()).await? {
+		Some(v) => record::load(tx, ns, db, tb, &v).await?,
+		None => {
+			let mut rec = Value::base();
+			rec.put(&[Part::from("id")], Thing::from((tb, id)).into());
+			rec.put(&[Part::from(BUCKET)], bucket);
+			rec
+		}
+	};
+	let count = match rec.pick(&[Part::from(COUNT)]) {
+		Value::Number(v) => v,
+		_ => Number::Int(0),
+	};
+	let count = if add {
+		count + Number::Int(1)
+	} else {
+		count - Number::Int(1)
+	};
+	// A bucket which no longer has any records is removed
+	if count <= Number::Int(0) {
+		return tx.del(key).await;
+	}
+	rec.put(&[Part::from(COUNT)], count.into());
+	for field in ru.sum.iter() {
+		let path = sum(field);
+		if let Value::Number(v) = contribution.pick(&path) {
+			let total = match rec.pick(&path) {
+				Value::Number(v) => v,
+				_ => Number::Int(0),
+			};
+			let total = if add {
+				total + v
+			} else {
+				total - v
+			};
+			rec.put(&path, total.into());
+		}
+	}
+	tx.set(key, record::encode(&rec)?).await
+}
+
+/// The path of the sum of a field in a bucket
+fn sum(field: &[Part]) -> Vec<Part> {
+	let mut path = vec![Part::from(SUM)];
+	path.extend_from_slice(field);
+	path
+}
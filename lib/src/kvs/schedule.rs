@@ -0,0 +1,306 @@
+//! Scheduled backups of the datastore
+//!
+//! A [`BackupSchedule`] takes binary backups of the datastore at the times given by a
+//! cron expression, and stores them in a [`BlobStore`]. The schedule is checked whenever
+//! the datastore ticks, so backups are taken within one tick interval of their due time.
+
+use super::backend::BackendFuture;
+use super::Checkpoint;
+use super::Datastore;
+use crate::dbs::Session;
+use crate::err::Error;
+use chrono::DateTime;
+use chrono::TimeZone;
+use chrono::Utc;
+use futures::lock::Mutex;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// The prefix of the names of scheduled backups
+const PREFIX: &str = "backup-";
+/// The suffix of the names of full backups
+const FULL: &str = "-full";
+/// The suffix of the names of incremental backups
+const INCREMENTAL: &str = "-incremental";
+
+/// Storage for backups, such as a directory or an object storage bucket
+pub trait BlobStore: Send + Sync + 'static {
+	/// Stores a blob, replacing any blob with the same name
+	fn put(&self, name: String, data: Vec<u8>) -> BackendFuture<'_, ()>;
+
+	/// Lists the names of all stored blobs
+	fn list(&self) -> BackendFuture<'_, Vec<String>>;
+
+	/// Deletes a stored blob
+	fn delete(&self, name: String) -> BackendFuture<'_, ()>;
+}
+
+/// Stores the backups as files in a directory, which is created if it doesn't exist
+#[cfg(not(target_arch = "wasm32"))]
+impl BlobStore for std::path::PathBuf {
+	fn put(&self, name: String, data: Vec<u8>) -> BackendFuture<'_, ()> {
+		Box::pin(async move {
+			tokio::fs::create_dir_all(self).await?;
+			// Write to a temporary file first, so that a partly written backup is never listed
+			let tmp = self.join(format!(".{name}.tmp"));
+			tokio::fs::write(&tmp, data).await?;
+			tokio::fs::rename(&tmp, self.join(name)).await?;
+			Ok(())
+		})
+	}
+
+	fn list(&self) -> BackendFuture<'_, Vec<String>> {
+		Box::pin(async move {
+			let mut dir = match tokio::fs::read_dir(self).await {
+				Ok(dir) => dir,
+				Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+				Err(e) => return Err(e.into()),
+			};
+			let mut names = Vec::new();
+			while let Some(entry) = dir.next_entry().await? {
+				if let Some(name) = entry.file_name().to_str() {
+					if !name.starts_with('.') {
+						names.push(name.to_owned());
+					}
+				}
+			}
+			Ok(names)
+		})
+	}
+
+	fn delete(&self, name: String) -> BackendFuture<'_, ()> {
+		Box::pin(async move { Ok(tokio::fs::remove_file(self.join(name)).await?) })
+	}
+}
+
+/// A callback which is run when a scheduled backup fails
+type FailureCallback = Arc<dyn Fn(&Error) + Send + Sync>;
+
+#[derive(Default)]
+struct State {
+	/// When the next backup is due
+	next: Option<DateTime<Utc>>,
+	/// The checkpoint of the previous backup, which an incremental backup continues from
+	checkpoint: Option<Checkpoint>,
+	/// The number of incremental backups taken since the previous full backup
+	incremental: u32,
+}
+
+/// Takes backups of the datastore at the times given by a cron expression
+///
+/// By default every backup is a full backup, and the seven most recent backups are kept.
+pub struct BackupSchedule {
+	schedule: cron::Schedule,
+	store: Arc<dyn BlobStore>,
+	incremental: u32,
+	retain: usize,
+	on_failure: Option<FailureCallback>,
+	state: Mutex<State>,
+}
+
+impl fmt::Debug for BackupSchedule {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("BackupSchedule")
+			.field("schedule", &self.schedule.to_string())
+			.field("incremental", &self.incremental)
+			.field("retain", &self.retain)
+			.finish_non_exhaustive()
+	}
+}
+
+impl BackupSchedule {
+	/// Creates a schedule from a cron expression, which is evaluated in UTC
+	///
+	/// The expression starts with a field for the seconds, so `0 0 3 * * *`
+	/// takes a backup every day at 3am.
+	pub fn new(cron: &str, store: impl BlobStore) -> Result<Self, Error> {
+		let schedule =
+			cron::Schedule::from_str(cron).map_err(|e| Error::InvalidBackupSchedule {
+				value: cron.to_owned(),
+				message: e.to_string(),
+			})?;
+		Ok(Self {
+			schedule,
+			store: Arc::new(store),
+			incremental: 0,
+			retain: 7,
+			on_failure: None,
+			state: Default::default(),
+		})
+	}
+
+	/// Takes incremental backups in between the full backups
+	///
+	/// Every full backup is followed by `count` incremental backups, which only contain
+	/// the changes recorded by change feeds since the previous backup. Tables without a
+	/// change feed are written in full to every incremental backup.
+	pub fn incremental(mut self, count: u32) -> Self {
+		self.incremental = count;
+		self
+	}
+
+	/// Sets how many full backups are kept, along with the incremental backups which follow them
+	pub fn retain(mut self, count: usize) -> Self {
+		self.retain = count.max(1);
+		self
+	}
+
+	/// Sets a callback which is run when a scheduled backup fails
+	pub fn on_failure(mut self, callback: impl Fn(&Error) + Send + Sync + 'static) -> Self {
+		self.on_failure = Some(Arc::new(callback));
+		self
+	}
+
+	/// Takes a backup, if one is due at the timestamp
+	pub(super) async fn run_at(&self, ds: &Datastore, ts: u64) {
+		let Some(now) = Utc.timestamp_opt(ts as i64, 0).single() else {
+			return;
+		};
+		let mut state = self.state.lock().await;
+		match state.next {
+			// The first backup is due at the first time in the schedule after the datastore started
+			None => {
+				state.next = self.schedule.after(&now).next();
+				return;
+			}
+			Some(next) if now < next => return,
+			Some(_) => state.next = self.schedule.after(&now).next(),
+		}
+		if let Err(e) = self.backup(ds, &mut state, ts).await {
+			warn!("Scheduled backup failed: {e}");
+			if let Some(callback) = &self.on_failure {
+				callback(&e);
+			}
+		}
+	}
+
+	async fn backup(&self, ds: &Datastore, state: &mut State, ts: u64) -> Result<(), Error> {
+		let sess = Session::owner();
+		let mut data = Vec::new();
+		let (suffix, checkpoint) = match &state.checkpoint {
+			Some(since) if state.incremental < self.incremental => {
+				(INCREMENTAL, ds.backup_since(&sess, &mut data, since).await?)
+			}
+			_ => (FULL, ds.backup(&sess, &mut data).await?),
+		};
+		self.store.put(format!("{PREFIX}{ts:020}{suffix}"), data).await?;
+		state.checkpoint = Some(checkpoint);
+		state.incremental = match suffix {
+			FULL => 0,
+			_ => state.incremental + 1,
+		};
+		self.prune().await
+	}
+
+	/// Deletes the backups which were taken before the oldest full backup that is kept
+	async fn prune(&self) -> Result<(), Error> {
+		let mut names: Vec<String> =
+			self.store.list().await?.into_iter().filter(|name| name.starts_with(PREFIX)).collect();
+		names.sort_unstable();
+		let full: Vec<usize> = names
+			.iter()
+			.enumerate()
+			.filter(|(_, name)| name.ends_with(FULL))
+			.map(|(i, _)| i)
+			.collect();
+		if full.len() <= self.retain {
+			return Ok(());
+		}
+		let oldest = full[full.len() - self.retain];
+		for name in names.drain(..oldest) {
+			self.store.delete(name).await?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(all(test, feature = "kv-mem"))]
+mod tests {
+	use super::*;
+	use std::collections::BTreeMap;
+	use std::sync::atomic::AtomicUsize;
+	use std::sync::atomic::Ordering;
+
+	#[derive(Clone, Default)]
+	struct Memory(Arc<std::sync::Mutex<BTreeMap<String, Vec<u8>>>>);
+
+	impl Memory {
+		fn names(&self) -> Vec<String> {
+			self.0.lock().unwrap().keys().cloned().collect()
+		}
+	}
+
+	impl BlobStore for Memory {
+		fn put(&self, name: String, data: Vec<u8>) -> BackendFuture<'_, ()> {
+			self.0.lock().unwrap().insert(name, data);
+			Box::pin(async { Ok(()) })
+		}
+
+		fn list(&self) -> BackendFuture<'_, Vec<String>> {
+			let names = self.names();
+			Box::pin(async { Ok(names) })
+		}
+
+		fn delete(&self, name: String) -> BackendFuture<'_, ()> {
+			self.0.lock().unwrap().remove(&name);
+			Box::pin(async { Ok(()) })
+		}
+	}
+
+	#[tokio::test]
+	async fn takes_and_prunes_backups() {
+		let store = Memory::default();
+		let schedule =
+			BackupSchedule::new("0 * * * * *", store.clone()).unwrap().incremental(1).retain(2);
+		let ds = Datastore::new("memory").await.unwrap().with_backup_schedule(schedule);
+		// The first tick only schedules the first backup
+		ds.tick_at(30).await.unwrap();
+		assert!(store.names().is_empty());
+		for ts in [60, 90, 120, 180, 240, 300] {
+			ds.tick_at(ts).await.unwrap();
+		}
+		assert_eq!(
+			store.names(),
+			vec![
+				"backup-00000000000000000180-full",
+				"backup-00000000000000000240-incremental",
+				"backup-00000000000000000300-full",
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn reports_failures() {
+		struct Failing;
+
+		impl BlobStore for Failing {
+			fn put(&self, _: String, _: Vec<u8>) -> BackendFuture<'_, ()> {
+				Box::pin(async { Err(Error::Unreachable) })
+			}
+
+			fn list(&self) -> BackendFuture<'_, Vec<String>> {
+				Box::pin(async { Ok(Vec::new()) })
+			}
+
+			fn delete(&self, _: String) -> BackendFuture<'_, ()> {
+				Box::pin(async { Ok(()) })
+			}
+		}
+
+		let failures = Arc::new(AtomicUsize::new(0));
+		let counter = failures.clone();
+		let schedule = BackupSchedule::new("0 * * * * *", Failing).unwrap().on_failure(move |_| {
+			counter.fetch_add(1, Ordering::SeqCst);
+		});
+		let ds = Datastore::new("memory").await.unwrap().with_backup_schedule(schedule);
+		ds.tick_at(30).await.unwrap();
+		ds.tick_at(60).await.unwrap();
+		assert_eq!(failures.load(Ordering::SeqCst), 1);
+	}
+
+	#[test]
+	fn invalid_cron_expression() {
+		assert!(BackupSchedule::new("every day", Memory::default()).is_err());
+	}
+}
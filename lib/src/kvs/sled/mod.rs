@@ -0,0 +1,430 @@
+#![cfg(feature = "kv-sled")]
+
+use crate::err::Error;
+use crate::kvs::Check;
+use crate::kvs::Key;
+use crate::kvs::Val;
+use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
+use sled::transaction::ConflictableTransactionError;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::ops::Range;
+
+#[derive(Clone)]
+pub struct Datastore {
+	db: sled::Db,
+}
+
+/// An optimistic transaction on top of sled
+///
+/// Sled has no long-lived transactions, so writes are buffered in the transaction, and
+/// the committed value of every key which is read or written is remembered. When the
+/// transaction is committed, these values are checked and the writes are applied within
+/// a single sled transaction, which fails if any of the keys were changed in the meantime.
+/// Keys which are only returned from a range scan are not checked, so a transaction does
+/// not conflict with another transaction which inserts keys into a range it has scanned.
+pub struct Transaction {
+	// Is the transaction complete?
+	done: bool,
+	// Is the transaction writeable?
+	write: bool,
+	/// Should we check unhandled transactions?
+	check: Check,
+	/// The underlying datastore
+	db: sled::Db,
+	/// The committed values of the keys read or written in this transaction
+	reads: HashMap<Key, Option<Val>>,
+	/// The keys set (or deleted, if `None`) in this transaction
+	writes: BTreeMap<Key, Option<Val>>,
+}
+
+impl Drop for Transaction {
+	fn drop(&mut self) {
+		if !self.done && self.write {
+			// Check if already panicking
+			if std::thread::panicking() {
+				return;
+			}
+			// Handle the behaviour
+			match self.check {
+				Check::None => {
+					trace!("A transaction was dropped without being committed or cancelled");
+				}
+				Check::Warn => {
+					warn!("A transaction was dropped without being committed or cancelled");
+				}
+				Check::Panic => {
+					#[cfg(debug_assertions)]
+					{
+						let backtrace = std::backtrace::Backtrace::force_capture();
+						if let std::backtrace::BacktraceStatus::Captured = backtrace.status() {
+							println!("{}", backtrace);
+						}
+					}
+					panic!("A transaction was dropped without being committed or cancelled");
+				}
+			}
+		}
+	}
+}
+
+impl Datastore {
+	/// Open a new database
+	pub(crate) async fn new(path: &str) -> Result<Datastore, Error> {
+		Ok(Datastore {
+			db: sled::open(path)?,
+		})
+	}
+	/// Start a new transaction
+	pub(crate) async fn transaction(&self, write: bool, _: bool) -> Result<Transaction, Error> {
+		// Specify the check level
+		#[cfg(not(debug_assertions))]
+		let check = Check::Warn;
+		#[cfg(debug_assertions)]
+		let check = Check::Panic;
+		// Create a new transaction
+		Ok(Transaction {
+			done: false,
+			check,
+			write,
+			db: self.db.clone(),
+			reads: HashMap::new(),
+			writes: BTreeMap::new(),
+		})
+	}
+}
+
+impl Transaction {
+	/// Fetch the committed value of a key, remembering it for the commit checks
+	fn committed(&mut self, key: &Key) -> Result<Option<Val>, Error> {
+		if let Some(val) = self.reads.get(key) {
+			return Ok(val.clone());
+		}
+		let val = self.db.get(key)?.map(|v| v.to_vec());
+		self.reads.insert(key.clone(), val.clone());
+		Ok(val)
+	}
+	/// Fetch the value of a key as seen by this transaction
+	fn read(&mut self, key: &Key) -> Result<Option<Val>, Error> {
+		match self.writes.get(key) {
+			Some(val) => Ok(val.clone()),
+			None => self.committed(key),
+		}
+	}
+	/// Buffer a write, remembering the committed value of the key
+	fn buffer(&mut self, key: Key, val: Option<Val>) -> Result<(), Error> {
+		self.committed(&key)?;
+		self.writes.insert(key, val);
+		Ok(())
+	}
+	/// Behaviour if unclosed
+	pub(crate) fn check_level(&mut self, check: Check) {
+		self.check = check;
+	}
+	/// Check if closed
+	pub(crate) fn closed(&self) -> bool {
+		self.done
+	}
+	/// Cancel a transaction
+	pub(crate) async fn cancel(&mut self) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Mark this transaction as done
+		self.done = true;
+		// Discard the buffered writes
+		self.writes.clear();
+		// Continue
+		Ok(())
+	}
+	/// Commit a transaction
+	pub(crate) async fn commit(&mut self) -> Result<(), Error> {
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Mark this transaction as done
+		self.done = true;
+		// Nothing to do if nothing was written
+		if self.writes.is_empty() {
+			return Ok(());
+		}
+		// Check the keys and apply the writes atomically
+		self.db.transaction(|tx| {
+			for (key, val) in &self.reads {
+				if tx.get(key)?.as_deref() != val.as_deref() {
+					return Err(ConflictableTransactionError::Abort(()));
+				}
+			}
+			for (key, val) in &self.writes {
+				match val {
+					Some(val) => tx.insert(key.as_slice(), val.as_slice())?,
+					None => tx.remove(key.as_slice())?,
+				};
+			}
+			Ok(())
+		})?;
+		// Continue
+		Ok(())
+	}
+	/// Check if a key exists
+	pub(crate) async fn exi<K>(&mut self, key: K) -> Result<bool, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check the key
+		let res = self.read(&key.into())?.is_some();
+		// Return result
+		Ok(res)
+	}
+	/// Fetch a key from the database
+	pub(crate) async fn get<K>(&mut self, key: K) -> Result<Option<Val>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Get the key
+		let res = self.read(&key.into())?;
+		// Return result
+		Ok(res)
+	}
+	/// Obtain a new change timestamp for a key
+	/// which is replaced with the current timestamp when the transaction is committed.
+	/// NOTE: This should be called when composing the change feed entries for this transaction,
+	/// which should be done immediately before the transaction commit.
+	/// That is to keep other transactions commit delay(pessimistic) or conflict(optimistic) as less as possible.
+	#[allow(unused)]
+	pub(crate) async fn get_timestamp<K>(&mut self, key: K) -> Result<Versionstamp, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Write the timestamp to the "last-write-timestamp" key
+		// to ensure that no other transactions can commit with older timestamps.
+		let k: Key = key.into();
+		let prev = self.read(&k)?;
+		let ver = match prev {
+			Some(prev) => {
+				let slice = prev.as_slice();
+				let res: Result<[u8; 10], Error> = match slice.try_into() {
+					Ok(ba) => Ok(ba),
+					Err(e) => Err(Error::Ds(e.to_string())),
+				};
+				let array = res?;
+				let prev = try_to_u64_be(array)?;
+				prev + 1
+			}
+			None => 1,
+		};
+
+		let verbytes = u64_to_versionstamp(ver);
+
+		self.buffer(k, Some(verbytes.to_vec()))?;
+		// Return the uint64 representation of the timestamp as the result
+		Ok(verbytes)
+	}
+	/// Obtain a new key that is suffixed with the change timestamp
+	pub(crate) async fn get_versionstamped_key<K>(
+		&mut self,
+		ts_key: K,
+		prefix: K,
+		suffix: K,
+	) -> Result<Vec<u8>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		let ts = self.get_timestamp(ts_key).await?;
+		let mut k: Vec<u8> = prefix.into();
+		k.append(&mut ts.to_vec());
+		k.append(&mut suffix.into());
+		Ok(k)
+	}
+	/// Insert or update a key in the database
+	pub(crate) async fn set<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Set the key
+		self.buffer(key.into(), Some(val.into()))?;
+		// Return result
+		Ok(())
+	}
+	/// Insert a key if it doesn't exist in the database
+	pub(crate) async fn put<K, V>(&mut self, key: K, val: V) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let val = val.into();
+		// Set the key if empty
+		match self.read(&key)? {
+			None => self.buffer(key, Some(val))?,
+			_ => return Err(Error::TxKeyAlreadyExists),
+		};
+		// Return result
+		Ok(())
+	}
+	/// Insert a key if it doesn't exist in the database
+	pub(crate) async fn putc<K, V>(&mut self, key: K, val: V, chk: Option<V>) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let val = val.into();
+		let chk = chk.map(Into::into);
+		// Set the key if valid
+		match (self.read(&key)?, chk) {
+			(Some(v), Some(w)) if v == w => self.buffer(key, Some(val))?,
+			(None, None) => self.buffer(key, Some(val))?,
+			_ => return Err(Error::TxConditionNotMet),
+		};
+		// Return result
+		Ok(())
+	}
+	/// Delete a key
+	pub(crate) async fn del<K>(&mut self, key: K) -> Result<(), Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Remove the key
+		self.buffer(key.into(), None)?;
+		// Return result
+		Ok(())
+	}
+	/// Delete a key
+	pub(crate) async fn delc<K, V>(&mut self, key: K, chk: Option<V>) -> Result<(), Error>
+	where
+		K: Into<Key>,
+		V: Into<Val>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Check to see if transaction is writable
+		if !self.write {
+			return Err(Error::TxReadonly);
+		}
+		// Get the arguments
+		let key = key.into();
+		let chk = chk.map(Into::into);
+		// Delete the key if valid
+		match (self.read(&key)?, chk) {
+			(Some(v), Some(w)) if v == w => self.buffer(key, None)?,
+			(None, None) => self.buffer(key, None)?,
+			_ => return Err(Error::TxConditionNotMet),
+		};
+		// Return result
+		Ok(())
+	}
+	/// Retrieve a range of keys from the databases
+	pub(crate) async fn scan<K>(
+		&mut self,
+		rng: Range<K>,
+		limit: u32,
+	) -> Result<Vec<(Key, Val)>, Error>
+	where
+		K: Into<Key>,
+	{
+		// Check to see if transaction is closed
+		if self.done {
+			return Err(Error::TxFinished);
+		}
+		// Convert the range to bytes
+		let rng: Range<Key> = Range {
+			start: rng.start.into(),
+			end: rng.end.into(),
+		};
+		// Create result set
+		let mut res = vec![];
+		// Merge the committed keys with the buffered writes
+		let mut db = self.db.range(rng.clone());
+		let mut tx = self.writes.range(rng).peekable();
+		let mut cur = db.next().transpose()?;
+		while res.len() < limit as usize {
+			// Take the lowest key from either side
+			let (k, v) = match (&cur, tx.peek()) {
+				(None, None) => break,
+				(Some((k, _)), Some((w, _))) if k[..] == w[..] => {
+					cur = db.next().transpose()?;
+					tx.next().map(|(k, v)| (k.clone(), v.clone())).unwrap()
+				}
+				(Some((k, _)), Some((w, _))) if k[..] > w[..] => {
+					tx.next().map(|(k, v)| (k.clone(), v.clone())).unwrap()
+				}
+				(Some(_), _) => {
+					let (k, v) = cur.take().unwrap();
+					cur = db.next().transpose()?;
+					(k.to_vec(), Some(v.to_vec()))
+				}
+				(None, Some(_)) => tx.next().map(|(k, v)| (k.clone(), v.clone())).unwrap(),
+			};
+			// Skip keys deleted in this transaction
+			if let Some(v) = v {
+				res.push((k, v));
+			}
+		}
+		// Return result
+		Ok(res)
+	}
+}
@@ -0,0 +1,243 @@
+//! Keeps the data of temporary tables in memory, instead of in the datastore
+//!
+//! The records, indexes and graph edges of a table which is defined as TEMPORARY are
+//! stored in a keyspace which belongs to a session, so they are only visible to queries
+//! run with that session, and are dropped along with the session. The definition of the
+//! table itself is stored in the datastore as usual.
+use super::Key;
+use super::Val;
+use crate::err::Error;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// The in-memory keyspace of the temporary tables of a session
+///
+/// Clones of a session share the same keyspace.
+#[derive(Clone, Default)]
+pub struct Temporary(Arc<Mutex<BTreeMap<Key, Val>>>);
+
+impl fmt::Debug for Temporary {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Temporary").finish_non_exhaustive()
+	}
+}
+
+// The keyspace holds data, not settings, so it does not take part in comparing sessions
+impl PartialEq for Temporary {
+	fn eq(&self, _: &Self) -> bool {
+		true
+	}
+}
+
+impl Eq for Temporary {}
+
+impl Temporary {
+	fn data(&self) -> std::sync::MutexGuard<'_, BTreeMap<Key, Val>> {
+		self.0.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+/// Splits the key of an entry of a table into its namespace, database, table, and type of entry
+pub(super) fn split(key: &[u8]) -> Option<(&str, &str, &str, u8)> {
+	// The key starts with /*{ns}\0*{db}\0*{tb}\0 followed by the type of the entry
+	let mut parts = key.strip_prefix(b"/*")?.splitn(4, |b| *b == 0);
+	let ns = std::str::from_utf8(parts.next()?).ok()?;
+	let db = std::str::from_utf8(parts.next()?.strip_prefix(b"*")?).ok()?;
+	let tb = std::str::from_utf8(parts.next()?.strip_prefix(b"*")?).ok()?;
+	Some((ns, db, tb, *parts.next()?.first()?))
+}
+
+/// Splits the key of a record, index entry or graph edge into the key of its table definition
+pub(super) fn table(key: &[u8]) -> Option<Key> {
+	match split(key)? {
+		(ns, db, tb, b'*' | b'+' | b'~') => Some(crate::key::database::tb::new(ns, db, tb).into()),
+		_ => None,
+	}
+}
+
+/// Splits a range which only covers the records, index entries or graph edges of a single
+/// table into the key of its table definition
+pub(super) fn range(rng: &Range<Key>) -> Option<Key> {
+	match (split(&rng.start)?, split(&rng.end)?) {
+		(beg, end) if beg == end => table(&rng.start),
+		_ => None,
+	}
+}
+
+/// Checks whether a key is the definition of a table
+pub(super) fn is_definition(key: &[u8]) -> bool {
+	// The key is /*{ns}\0*{db}\0!tb{tb}\0
+	let mut parts = match key.strip_prefix(b"/*") {
+		Some(v) => v.splitn(3, |b| *b == 0),
+		None => return false,
+	};
+	let _ = parts.next();
+	matches!(parts.next(), Some(v) if v.starts_with(b"*"))
+		&& matches!(parts.next(), Some(v) if v.starts_with(b"!tb"))
+}
+
+/// The changes which a transaction makes to the temporary tables of a session
+///
+/// The changes are kept apart until the transaction commits, so that a transaction
+/// which is cancelled leaves the keyspace of the session as it was.
+pub(super) struct Overlay {
+	space: Temporary,
+	writes: BTreeMap<Key, Option<Val>>,
+	/// Whether each table which has been accessed is temporary, by its definition key
+	pub(super) tables: HashMap<Key, bool>,
+}
+
+impl Overlay {
+	pub(super) fn new(space: Temporary) -> Self {
+		Self {
+			space,
+			writes: BTreeMap::new(),
+			tables: HashMap::new(),
+		}
+	}
+
+	pub(super) fn get(&self, key: &Key) -> Option<Val> {
+		match self.writes.get(key) {
+			Some(v) => v.clone(),
+			None => self.space.data().get(key).cloned(),
+		}
+	}
+
+	pub(super) fn set(&mut self, key: Key, val: Val) {
+		self.writes.insert(key, Some(val));
+	}
+
+	pub(super) fn put(&mut self, key: Key, val: Val) -> Result<(), Error> {
+		match self.get(&key) {
+			None => self.set(key, val),
+			Some(_) => return Err(Error::TxKeyAlreadyExists),
+		}
+		Ok(())
+	}
+
+	pub(super) fn putc(&mut self, key: Key, val: Val, chk: Option<Val>) -> Result<(), Error> {
+		match (self.get(&key), chk) {
+			(Some(v), Some(w)) if v == w => self.set(key, val),
+			(None, None) => self.set(key, val),
+			_ => return Err(Error::TxConditionNotMet),
+		}
+		Ok(())
+	}
+
+	/// Deletes a key, returning whether it was held in the keyspace
+	pub(super) fn del(&mut self, key: Key) -> bool {
+		let held = self.get(&key).is_some();
+		if held {
+			self.writes.insert(key, None);
+		}
+		held
+	}
+
+	pub(super) fn delc(&mut self, key: Key, chk: Option<Val>) -> Result<(), Error> {
+		match (self.get(&key), chk) {
+			(Some(v), Some(w)) if v == w => {
+				self.del(key);
+			}
+			(None, None) => {
+				self.del(key);
+			}
+			_ => return Err(Error::TxConditionNotMet),
+		}
+		Ok(())
+	}
+
+	/// Fetches the entries in a range, merged with the entries fetched from the datastore
+	pub(super) fn scan(
+		&self,
+		rng: &Range<Key>,
+		limit: u32,
+		mut res: Vec<(Key, Val)>,
+	) -> Vec<(Key, Val)> {
+		if rng.start >= rng.end {
+			return res;
+		}
+		let mut out: BTreeMap<Key, Val> =
+			self.space.data().range(rng.clone()).map(|(k, v)| (k.clone(), v.clone())).collect();
+		for (k, v) in self.writes.range(rng.clone()) {
+			match v {
+				Some(v) => out.insert(k.clone(), v.clone()),
+				None => out.remove(k),
+			};
+		}
+		if out.is_empty() {
+			return res;
+		}
+		res.extend(out);
+		res.sort_by(|(a, _), (b, _)| a.cmp(b));
+		res.truncate(limit as usize);
+		res
+	}
+
+	/// Applies the changes of a committed transaction to the keyspace of the session
+	pub(super) fn commit(&mut self) {
+		let mut data = self.space.data();
+		for (k, v) in std::mem::take(&mut self.writes) {
+			match v {
+				Some(v) => data.insert(k, v),
+				None => data.remove(&k),
+			};
+		}
+	}
+
+	/// Discards the changes of a cancelled transaction
+	pub(super) fn cancel(&mut self) {
+		self.writes.clear();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn table_of_key() {
+		let rid = crate::key::thing::new("test", "test", "person", &"tobie".into());
+		let key: Key = rid.into();
+		let tb: Key = crate::key::database::tb::new("test", "test", "person").into();
+		assert_eq!(table(&key), Some(tb.clone()));
+		assert!(is_definition(&tb));
+		assert_eq!(table(&tb), None);
+		let fd: Key = crate::key::table::fd::new("test", "test", "person", "name").into();
+		assert_eq!(table(&fd), None);
+		assert!(!is_definition(&fd));
+	}
+
+	#[test]
+	fn table_of_range() {
+		let tb: Key = crate::key::database::tb::new("test", "test", "person").into();
+		let beg = crate::key::thing::prefix("test", "test", "person");
+		let end = crate::key::thing::suffix("test", "test", "person");
+		assert_eq!(range(&(beg.clone()..end)), Some(tb));
+		// A range over the whole table also covers its definitions
+		let mut end: Key = crate::key::table::all::new("test", "test", "person").into();
+		end.push(0xff);
+		assert_eq!(range(&(beg..end)), None);
+	}
+
+	#[test]
+	fn overlay_commit_and_cancel() {
+		let space = Temporary::default();
+		let mut tx = Overlay::new(space.clone());
+		tx.set(b"a".to_vec(), b"1".to_vec());
+		tx.cancel();
+		assert_eq!(Overlay::new(space.clone()).get(&b"a".to_vec()), None);
+		tx.set(b"a".to_vec(), b"1".to_vec());
+		tx.set(b"c".to_vec(), b"3".to_vec());
+		tx.commit();
+		let mut tx = Overlay::new(space);
+		tx.del(b"c".to_vec());
+		let res =
+			tx.scan(&(b"a".to_vec()..b"z".to_vec()), 10, vec![(b"b".to_vec(), b"2".to_vec())]);
+		assert_eq!(res, vec![(b"a".to_vec(), b"1".to_vec()), (b"b".to_vec(), b"2".to_vec())]);
+		assert!(tx.put(b"a".to_vec(), b"2".to_vec()).is_err());
+	}
+}
@@ -0,0 +1,30 @@
+#[tokio::test]
+#[serial]
+async fn mem_snapshot() {
+	// Create a new datastore
+	let node_id = Uuid::parse_str("4f2bc1ba-0c9d-4b0b-8f8a-2d3c6c1e4a7d").unwrap();
+	let (ds, _) = new_ds(node_id).await;
+	// Seed the datastore
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("test", "seeded").await.unwrap();
+	tx.commit().await.unwrap();
+	// Capture the seeded state
+	let snapshot = ds.snapshot().await.unwrap();
+	// Change the datastore
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("test", "changed").await.unwrap();
+	tx.set("other", "added").await.unwrap();
+	tx.commit().await.unwrap();
+	// Restore the seeded state, twice
+	for _ in 0..2 {
+		ds.restore_snapshot(snapshot.clone()).await.unwrap();
+		let mut tx = ds.transaction(false, false).await.unwrap();
+		assert_eq!(tx.get("test").await.unwrap().unwrap(), b"seeded");
+		assert!(tx.get("other").await.unwrap().is_none());
+		tx.cancel().await.unwrap();
+		// Change the datastore again
+		let mut tx = ds.transaction(true, false).await.unwrap();
+		tx.del("test").await.unwrap();
+		tx.commit().await.unwrap();
+	}
+}
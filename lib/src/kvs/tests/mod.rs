@@ -7,6 +7,10 @@ pub(crate) enum Kvs {
 	#[allow(dead_code)]
 	Speedb,
 	#[allow(dead_code)]
+	Sled,
+	#[allow(dead_code)]
+	Redb,
+	#[allow(dead_code)]
 	Tikv,
 	#[allow(dead_code)]
 	Fdb,
@@ -43,6 +47,7 @@ mod mem {
 	include!("ndlq.rs");
 	include!("tblq.rs");
 	include!("tbnt.rs");
+	include!("mem_snapshot.rs");
 }
 
 #[cfg(feature = "kv-rocksdb")]
@@ -131,6 +136,90 @@ mod speedb {
 	include!("tbnt.rs");
 }
 
+#[cfg(feature = "kv-sled")]
+mod sled {
+
+	use crate::kvs::tests::Kvs;
+	use crate::kvs::Datastore;
+	use crate::kvs::Transaction;
+	use serial_test::serial;
+	use temp_dir::TempDir;
+
+	async fn new_ds(node_id: Uuid) -> (Datastore, Kvs) {
+		let path = TempDir::new().unwrap().path().to_string_lossy().to_string();
+		(
+			Datastore::new(format!("sled:{path}").as_str())
+				.await
+				.unwrap()
+				.with_node_id(sql::Uuid::from(node_id)),
+			Kvs::Sled,
+		)
+	}
+
+	async fn new_tx(write: bool, lock: bool) -> Transaction {
+		// Shared node id for one-off transactions
+		// We should delete this, node IDs should be known.
+		let new_tx_uuid = Uuid::parse_str("c261defb-c42f-44f7-b143-29cc0bffa7b3").unwrap();
+		new_ds(new_tx_uuid).await.0.transaction(write, lock).await.unwrap()
+	}
+
+	include!("cluster_init.rs");
+	include!("helper.rs");
+	include!("lq.rs");
+	include!("nq.rs");
+	include!("raw.rs");
+	include!("snapshot.rs");
+	include!("tb.rs");
+	include!("multireader.rs");
+	include!("multiwriter_different_keys.rs");
+	include!("multiwriter_same_keys_conflict.rs");
+	include!("timestamp_to_versionstamp.rs");
+	include!("ndlq.rs");
+	include!("tblq.rs");
+	include!("tbnt.rs");
+}
+
+#[cfg(feature = "kv-redb")]
+mod redb {
+
+	use crate::kvs::tests::Kvs;
+	use crate::kvs::Datastore;
+	use crate::kvs::Transaction;
+	use serial_test::serial;
+	use temp_dir::TempDir;
+
+	async fn new_ds(node_id: Uuid) -> (Datastore, Kvs) {
+		let path = TempDir::new().unwrap().path().to_string_lossy().to_string();
+		(
+			Datastore::new(format!("redb:{path}").as_str())
+				.await
+				.unwrap()
+				.with_node_id(sql::Uuid::from(node_id)),
+			Kvs::Redb,
+		)
+	}
+
+	async fn new_tx(write: bool, lock: bool) -> Transaction {
+		// Shared node id for one-off transactions
+		// We should delete this, node IDs should be known.
+		let new_tx_uuid = Uuid::parse_str("6366a0b6-6f73-464f-a508-2d5ffbe53f77").unwrap();
+		new_ds(new_tx_uuid).await.0.transaction(write, lock).await.unwrap()
+	}
+
+	include!("cluster_init.rs");
+	include!("helper.rs");
+	include!("lq.rs");
+	include!("nq.rs");
+	include!("raw.rs");
+	include!("snapshot.rs");
+	include!("tb.rs");
+	include!("multireader.rs");
+	include!("timestamp_to_versionstamp.rs");
+	include!("ndlq.rs");
+	include!("tblq.rs");
+	include!("tbnt.rs");
+}
+
 #[cfg(feature = "kv-tikv")]
 mod tikv {
 
@@ -164,6 +253,7 @@ mod tikv {
 	include!("lq.rs");
 	include!("nq.rs");
 	include!("raw.rs");
+	include!("point_in_time.rs");
 	include!("snapshot.rs");
 	include!("tb.rs");
 	include!("multireader.rs");
@@ -208,6 +298,7 @@ mod fdb {
 	include!("lq.rs");
 	include!("nq.rs");
 	include!("raw.rs");
+	include!("point_in_time.rs");
 	include!("snapshot.rs");
 	include!("tb.rs");
 	include!("multireader.rs");
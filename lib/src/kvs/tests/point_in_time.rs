@@ -0,0 +1,62 @@
+#[tokio::test]
+#[serial]
+async fn point_in_time() {
+	// Create a new datastore
+	let node_id = Uuid::parse_str("8ec3c7a4-1f0a-4b6e-9d07-5e2a2a1c6b3d").unwrap();
+	let (ds, _) = new_ds(node_id).await;
+	// Insert an initial key
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("test", "some text").await.unwrap();
+	tx.commit().await.unwrap();
+	// Get the current version of the data
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let vs = tx.get_timestamp("test", false).await.unwrap();
+	let vs = crate::sql::Versionstamp::from_bytes(vs);
+	tx.cancel().await.unwrap();
+	// Update the test key content
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	tx.set("test", "other text").await.unwrap();
+	tx.commit().await.unwrap();
+	// Check the key at the historical version
+	let mut tx = ds.transaction_at(vs).await.unwrap();
+	let val = tx.get("test").await.unwrap().unwrap();
+	assert_eq!(val, b"some text");
+	// Check that the historical version can not be written to
+	assert!(matches!(tx.set("test", "extra text").await, Err(crate::err::Error::TxReadonly)));
+	tx.cancel().await.unwrap();
+	// Check the key at the latest version
+	let mut tx = ds.transaction(false, false).await.unwrap();
+	let val = tx.get("test").await.unwrap().unwrap();
+	assert_eq!(val, b"other text");
+	tx.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn select_version() {
+	use crate::dbs::Session;
+	use crate::sql::{Datetime, Value};
+	use std::time::Duration;
+	// Create a new datastore
+	let node_id = Uuid::parse_str("0b2f5d0e-6b8c-4f4e-a2a5-7d1c9e0f3b84").unwrap();
+	let (ds, _) = new_ds(node_id).await;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	// Create the initial record
+	let sql = "CREATE person:test SET name = 'Tobie'";
+	ds.execute(sql, &ses, None).await.unwrap().remove(0).result.unwrap();
+	// Remember the time after the record was created
+	tokio::time::sleep(Duration::from_millis(500)).await;
+	let at = Datetime::default();
+	tokio::time::sleep(Duration::from_millis(500)).await;
+	// Update the record
+	let sql = "UPDATE person:test SET name = 'Jaime'";
+	ds.execute(sql, &ses, None).await.unwrap().remove(0).result.unwrap();
+	// Read the record at both versions
+	let sql = format!(
+		"SELECT VALUE name FROM person:test VERSION {at}; SELECT VALUE name FROM person:test"
+	);
+	let res = &mut ds.execute(&sql, &ses, None).await.unwrap();
+	assert_eq!(res.len(), 2);
+	assert_eq!(res.remove(0).result.unwrap(), Value::parse("['Tobie']"));
+	assert_eq!(res.remove(0).result.unwrap(), Value::parse("['Jaime']"));
+}
@@ -230,3 +230,64 @@ async fn scan() {
 	assert_eq!(val[1].1, b"2");
 	tx.cancel().await.unwrap();
 }
+
+#[tokio::test]
+#[serial]
+async fn scan_prefix() {
+	use futures::TryStreamExt;
+	// Create a new datastore
+	let node_id = Uuid::parse_str("5c3f1a8e-7d2b-4e69-a1f0-3b8c9d6e2f47").unwrap();
+	let (ds, _) = new_ds(node_id).await;
+	// Create a writeable transaction
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	for i in 0..120 {
+		assert!(tx.put(format!("test{i:03}"), i.to_string()).await.is_ok());
+	}
+	assert!(tx.put("other", "0").await.is_ok());
+	tx.commit().await.unwrap();
+	// Stream all keys across several batches
+	let tx = ds.transaction(false, false).await.unwrap().enclose();
+	let val: Vec<_> = Transaction::scan_prefix(&tx, "test", u32::MAX).try_collect().await.unwrap();
+	assert_eq!(val.len(), 120);
+	assert_eq!(val[0].0, b"test000");
+	assert_eq!(val[0].1, b"0");
+	assert_eq!(val[119].0, b"test119");
+	assert_eq!(val[119].1, b"119");
+	// Stream a limited number of keys
+	let val: Vec<_> = Transaction::scan_prefix(&tx, "test", 60).try_collect().await.unwrap();
+	assert_eq!(val.len(), 60);
+	assert_eq!(val[59].0, b"test059");
+	tx.lock().await.cancel().await.unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn scan_read_ahead() {
+	use crate::kvs::Scanner;
+	// Create a new datastore
+	let node_id = Uuid::parse_str("9e4b7c21-3f8a-4d56-b0e2-7a1c5d9f3e68").unwrap();
+	let (ds, _) = new_ds(node_id).await;
+	let ds = ds.with_read_ahead(2);
+	// Create a writeable transaction
+	let mut tx = ds.transaction(true, false).await.unwrap();
+	for i in 0..120 {
+		assert!(tx.put(format!("test{i:03}"), i.to_string()).await.is_ok());
+	}
+	assert!(tx.put("other", "0").await.is_ok());
+	tx.commit().await.unwrap();
+	// Scan all keys across several batches, reading ahead of each batch
+	let tx = ds.transaction(false, false).await.unwrap().enclose();
+	let mut scan = Scanner::new(&tx, b"test".to_vec(), b"test\xff".to_vec()).await;
+	let mut val = Vec::new();
+	while let Some(res) = scan.next_batch().await.unwrap() {
+		scan.read_ahead().await.unwrap();
+		scan.read_ahead().await.unwrap();
+		val.extend(res);
+	}
+	assert_eq!(val.len(), 120);
+	for (i, (k, v)) in val.iter().enumerate() {
+		assert_eq!(k, format!("test{i:03}").as_bytes());
+		assert_eq!(v, i.to_string().as_bytes());
+	}
+	tx.lock().await.cancel().await.unwrap();
+}
@@ -24,6 +24,13 @@ async fn table_definitions_can_be_scanned() {
 		permissions: Default::default(),
 		changefeed: None,
 		comment: None,
+		ttl: None,
+		retention: None,
+		tiering: None,
+		rollup: None,
+		temporary: false,
+		cache: false,
+		dedup: None,
 	};
 	tx.set(&key, &value).await.unwrap();
 
@@ -61,6 +68,13 @@ async fn table_definitions_can_be_deleted() {
 		permissions: Default::default(),
 		changefeed: None,
 		comment: None,
+		ttl: None,
+		retention: None,
+		tiering: None,
+		rollup: None,
+		temporary: false,
+		cache: false,
+		dedup: None,
 	};
 	tx.set(&key, &value).await.unwrap();
 
@@ -5,6 +5,7 @@ use crate::kvs::Check;
 use crate::kvs::Key;
 use crate::kvs::Val;
 use crate::vs::{try_to_u64_be, u64_to_versionstamp, Versionstamp};
+use chrono::{DateTime, Utc};
 use std::ops::Range;
 use tikv::CheckLevel;
 use tikv::TimestampExt;
@@ -22,7 +23,50 @@ pub struct Transaction {
 	/// Should we check unhandled transactions?
 	check: Check,
 	/// The underlying datastore transaction
-	inner: tikv::Transaction,
+	inner: Inner,
+}
+
+/// The underlying TiKV transaction or snapshot
+enum Inner {
+	/// A transaction reading the latest version of the data
+	Transaction(tikv::Transaction),
+	/// A read-only snapshot of a historical version of the data
+	Snapshot(tikv::Snapshot),
+}
+
+impl Inner {
+	/// Get the writeable transaction, if this is not a snapshot
+	fn tx(&mut self) -> Result<&mut tikv::Transaction, Error> {
+		match self {
+			Inner::Transaction(v) => Ok(v),
+			Inner::Snapshot(_) => Err(Error::TxReadonly),
+		}
+	}
+	/// Check if a key exists
+	async fn key_exists(&mut self, key: Key) -> Result<bool, Error> {
+		Ok(match self {
+			Inner::Transaction(v) => v.key_exists(key).await?,
+			Inner::Snapshot(v) => v.key_exists(key).await?,
+		})
+	}
+	/// Fetch a key from the database
+	async fn get(&mut self, key: Key) -> Result<Option<Val>, Error> {
+		Ok(match self {
+			Inner::Transaction(v) => v.get(key).await?,
+			Inner::Snapshot(v) => v.get(key).await?,
+		})
+	}
+	/// Retrieve a range of keys from the database
+	async fn scan(&mut self, rng: Range<Key>, limit: u32) -> Result<Vec<(Key, Val)>, Error> {
+		Ok(match self {
+			Inner::Transaction(v) => {
+				v.scan(rng, limit).await?.map(|kv| (Key::from(kv.0), kv.1)).collect()
+			}
+			Inner::Snapshot(v) => {
+				v.scan(rng, limit).await?.map(|kv| (Key::from(kv.0), kv.1)).collect()
+			}
+		})
+	}
 }
 
 impl Drop for Transaction {
@@ -95,11 +139,30 @@ impl Datastore {
 				done: false,
 				check,
 				write,
-				inner,
+				inner: Inner::Transaction(inner),
 			}),
 			Err(e) => Err(Error::Tx(e.to_string())),
 		}
 	}
+	/// Start a new read-only transaction at a historical version
+	pub(crate) async fn transaction_at(&self, version: u64) -> Result<Transaction, Error> {
+		// Snapshots never hold locks, so there is nothing to clean up when dropped
+		let opt = TransactionOptions::new_optimistic().read_only().drop_check(CheckLevel::None);
+		// Create a new snapshot at the specified timestamp
+		let inner = self.db.snapshot(tikv::Timestamp::from_version(version), opt);
+		// Return the read-only transaction
+		Ok(Transaction {
+			done: false,
+			check: Check::None,
+			write: false,
+			inner: Inner::Snapshot(inner),
+		})
+	}
+	/// Get the version of the data at a point in time
+	pub(crate) fn version_at(&self, at: DateTime<Utc>) -> u64 {
+		// Timestamps contain the physical time in milliseconds, followed by 18 logical bits
+		(at.timestamp_millis().max(0) as u64) << 18
+	}
 }
 
 impl Transaction {
@@ -121,7 +184,7 @@ impl Transaction {
 		self.done = true;
 		// Cancel this transaction
 		if self.write {
-			self.inner.rollback().await?;
+			self.inner.tx()?.rollback().await?;
 		}
 		// Continue
 		Ok(())
@@ -139,8 +202,9 @@ impl Transaction {
 		// Mark this transaction as done
 		self.done = true;
 		// Commit this transaction
-		if let Err(err) = self.inner.commit().await {
-			if let Err(inner_err) = self.inner.rollback().await {
+		let inner = self.inner.tx()?;
+		if let Err(err) = inner.commit().await {
+			if let Err(inner_err) = inner.rollback().await {
 				error!("Transaction commit failed {} and rollback failed: {}", err, inner_err);
 			}
 			return Err(err.into());
@@ -167,7 +231,7 @@ impl Transaction {
 			return Err(Error::TxFinished);
 		}
 		// Get the current timestamp
-		let res = self.inner.get_current_timestamp().await?;
+		let res = self.inner.tx()?.get_current_timestamp().await?;
 		let ver = res.version();
 		let verbytes = u64_to_versionstamp(ver);
 		// Write the timestamp to the "last-write-timestamp" key
@@ -188,7 +252,7 @@ impl Transaction {
 				}
 			}
 
-			self.inner.put(k, verbytes.to_vec()).await?;
+			self.inner.tx()?.put(k, verbytes.to_vec()).await?;
 		}
 		// Return the uint64 representation of the timestamp as the result
 		Ok(u64_to_versionstamp(ver))
@@ -261,7 +325,7 @@ impl Transaction {
 			return Err(Error::TxReadonly);
 		}
 		// Set the key
-		self.inner.put(key.into(), val.into()).await?;
+		self.inner.tx()?.put(key.into(), val.into()).await?;
 		// Return result
 		Ok(())
 	}
@@ -285,7 +349,7 @@ impl Transaction {
 		let val = val.into();
 		// Set the key if empty
 		match self.inner.key_exists(key.clone()).await? {
-			false => self.inner.put(key, val).await?,
+			false => self.inner.tx()?.put(key, val).await?,
 			_ => return Err(Error::TxKeyAlreadyExists),
 		};
 		// Return result
@@ -313,8 +377,8 @@ impl Transaction {
 		let chk = chk.map(Into::into);
 		// Delete the key
 		match (self.inner.get(key.clone()).await?, chk) {
-			(Some(v), Some(w)) if v == w => self.inner.put(key, val).await?,
-			(None, None) => self.inner.put(key, val).await?,
+			(Some(v), Some(w)) if v == w => self.inner.tx()?.put(key, val).await?,
+			(None, None) => self.inner.tx()?.put(key, val).await?,
 			_ => return Err(Error::TxConditionNotMet),
 		};
 		// Return result
@@ -334,7 +398,7 @@ impl Transaction {
 			return Err(Error::TxReadonly);
 		}
 		// Delete the key
-		self.inner.delete(key.into()).await?;
+		self.inner.tx()?.delete(key.into()).await?;
 		// Return result
 		Ok(())
 	}
@@ -358,8 +422,8 @@ impl Transaction {
 		let chk = chk.map(Into::into);
 		// Delete the key
 		match (self.inner.get(key.clone()).await?, chk) {
-			(Some(v), Some(w)) if v == w => self.inner.delete(key).await?,
-			(None, None) => self.inner.delete(key).await?,
+			(Some(v), Some(w)) if v == w => self.inner.tx()?.delete(key).await?,
+			(None, None) => self.inner.tx()?.delete(key).await?,
 			_ => return Err(Error::TxConditionNotMet),
 		};
 		// Return result
@@ -385,7 +449,6 @@ impl Transaction {
 		};
 		// Scan the keys
 		let res = self.inner.scan(rng, limit).await?;
-		let res = res.map(|kv| (Key::from(kv.0), kv.1)).collect();
 		// Return result
 		Ok(res)
 	}
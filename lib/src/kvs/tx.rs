@@ -3,13 +3,30 @@ use super::kv::Convert;
 use super::Key;
 use super::Val;
 use crate::cf;
+use crate::cnf::PROCESSOR_BATCH_SIZE;
 use crate::dbs::node::ClusterMembership;
 use crate::dbs::node::Timestamp;
+use crate::dbs::Telemetry;
+use crate::dbs::TelemetryKind;
 use crate::err::Error;
 use crate::idg::u32::U32;
+use crate::idx::ft::analyzer::custom::Analyzer;
+use crate::idx::ft::analyzer::custom::Registry;
 use crate::key::debug;
 use crate::kvs::cache::Cache;
 use crate::kvs::cache::Entry;
+#[cfg(feature = "failpoints")]
+use crate::kvs::failpoint::Failpoint;
+#[cfg(feature = "failpoints")]
+use crate::kvs::failpoint::Failpoints;
+use crate::kvs::pinned;
+use crate::kvs::pinned::Pinned;
+use crate::kvs::pinned::Records;
+use crate::kvs::record;
+use crate::kvs::temporary;
+use crate::kvs::temporary::Overlay;
+use crate::kvs::temporary::Temporary;
+use crate::kvs::BatchSize;
 use crate::kvs::Check;
 use crate::kvs::LqValue;
 use crate::sql;
@@ -23,7 +40,11 @@ use crate::vs::Oracle;
 use crate::vs::Versionstamp;
 use channel::Sender;
 use futures::lock::Mutex;
+use futures::stream;
+use futures::Stream;
 use sql::permission::Permissions;
+use sql::statements::alter::Backfill;
+use sql::statements::rebuild::Rebuild;
 use sql::statements::DefineAnalyzerStatement;
 use sql::statements::DefineDatabaseStatement;
 use sql::statements::DefineEventStatement;
@@ -34,10 +55,13 @@ use sql::statements::DefineNamespaceStatement;
 use sql::statements::DefineParamStatement;
 use sql::statements::DefineScopeStatement;
 use sql::statements::DefineTableStatement;
+use sql::statements::DefineTaskStatement;
 use sql::statements::DefineTokenStatement;
 use sql::statements::DefineUserStatement;
 use sql::statements::LiveStatement;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
 use std::fmt;
 use std::fmt::Debug;
 use std::ops::Range;
@@ -55,6 +79,92 @@ pub struct Transaction {
 	pub(super) cache: Cache,
 	pub(super) cf: cf::Writer,
 	pub(super) vso: Arc<Mutex<Oracle>>,
+	pub(super) tmp: Option<Overlay>,
+	pub(super) pin: Option<Pinned>,
+	pub(super) analyzers: Registry,
+	pub(super) read_ahead: usize,
+	pub(super) telemetry: Telemetry,
+	#[cfg(feature = "failpoints")]
+	pub(super) failpoints: Failpoints,
+}
+
+/// A batched scan over a range of keys, which reads ahead of the batch being processed
+///
+/// The scan keeps up to the read-ahead window of the datastore in batches which are requested
+/// before they are needed. A batch is read while the transaction is otherwise idle, such as
+/// while the fields of the current batch are computed, so that some of the latency of remote
+/// storage engines is hidden behind the processing of the current batch. Batches are only read
+/// ahead for statements which don't write records, as they would otherwise miss the writes made
+/// while the earlier records are processed.
+pub(crate) struct Scanner {
+	txn: Arc<Mutex<Transaction>>,
+	beg: Key,
+	end: Key,
+	window: usize,
+	size: BatchSize,
+	batches: VecDeque<Vec<(Key, Val)>>,
+	done: bool,
+	num: u32,
+}
+
+impl Scanner {
+	/// Start a scan over a range of keys
+	pub(crate) async fn new(txn: &Arc<Mutex<Transaction>>, beg: Key, end: Key) -> Self {
+		let window = txn.lock().await.read_ahead;
+		Self {
+			txn: txn.clone(),
+			beg,
+			end,
+			window,
+			size: BatchSize::new(PROCESSOR_BATCH_SIZE),
+			batches: VecDeque::new(),
+			done: false,
+			num: u32::MAX,
+		}
+	}
+
+	/// Stop the scan once the specified number of entries has been fetched
+	pub(crate) fn with_limit(mut self, limit: u32) -> Self {
+		self.num = limit;
+		self
+	}
+
+	/// Fetch the next batch from the storage engine
+	async fn fetch(&mut self) -> Result<Option<Vec<(Key, Val)>>, Error> {
+		if self.done || self.num == 0 {
+			return Ok(None);
+		}
+		let min = self.beg.clone();
+		let max = self.end.clone();
+		let num = std::cmp::min(self.size.get(), self.num);
+		let res = self.txn.lock().await.scan(min..max, num).await?;
+		self.size.observe(&res);
+		self.num -= res.len() as u32;
+		// Ready the next batch
+		match res.last() {
+			Some((k, _)) => self.beg = k.clone().add(0x00),
+			None => self.done = true,
+		}
+		Ok(Some(res).filter(|res| !res.is_empty()))
+	}
+
+	/// Get the next batch of key-value entries, or `None` once the range is exhausted
+	pub(crate) async fn next_batch(&mut self) -> Result<Option<Vec<(Key, Val)>>, Error> {
+		match self.batches.pop_front() {
+			Some(res) => Ok(Some(res)),
+			None => self.fetch().await,
+		}
+	}
+
+	/// Request one more batch, if the read-ahead window isn't full
+	pub(crate) async fn read_ahead(&mut self) -> Result<(), Error> {
+		if self.batches.len() < self.window {
+			if let Some(res) = self.fetch().await? {
+				self.batches.push_back(res);
+			}
+		}
+		Ok(())
+	}
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -65,12 +175,19 @@ pub(super) enum Inner {
 	RocksDB(super::rocksdb::Transaction),
 	#[cfg(feature = "kv-speedb")]
 	SpeeDB(super::speedb::Transaction),
+	#[cfg(feature = "kv-sled")]
+	Sled(super::sled::Transaction),
+	#[cfg(feature = "kv-redb")]
+	Redb(super::redb::Transaction),
 	#[cfg(feature = "kv-indxdb")]
 	IndxDB(super::indxdb::Transaction),
+	#[cfg(feature = "kv-opfs")]
+	Opfs(super::opfs::Transaction),
 	#[cfg(feature = "kv-tikv")]
 	TiKV(super::tikv::Transaction),
 	#[cfg(feature = "kv-fdb")]
 	FoundationDB(super::fdb::Transaction),
+	Custom(Box<dyn super::BackendTransaction>),
 }
 
 impl fmt::Display for Transaction {
@@ -83,12 +200,19 @@ impl fmt::Display for Transaction {
 			Inner::RocksDB(_) => write!(f, "rocksdb"),
 			#[cfg(feature = "kv-speedb")]
 			Inner::SpeeDB(_) => write!(f, "speedb"),
+			#[cfg(feature = "kv-sled")]
+			Inner::Sled(_) => write!(f, "sled"),
+			#[cfg(feature = "kv-redb")]
+			Inner::Redb(_) => write!(f, "redb"),
 			#[cfg(feature = "kv-indxdb")]
 			Inner::IndxDB(_) => write!(f, "indxdb"),
+			#[cfg(feature = "kv-opfs")]
+			Inner::Opfs(_) => write!(f, "opfs"),
 			#[cfg(feature = "kv-tikv")]
 			Inner::TiKV(_) => write!(f, "tikv"),
 			#[cfg(feature = "kv-fdb")]
 			Inner::FoundationDB(_) => write!(f, "fdb"),
+			Inner::Custom(_) => write!(f, "custom"),
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
 		}
@@ -119,6 +243,117 @@ impl Transaction {
 		Arc::new(Mutex::new(self))
 	}
 
+	/// Keep the data of temporary tables in the keyspace of a session
+	pub(crate) fn with_temporary(mut self, space: Temporary) -> Self {
+		self.tmp = Some(Overlay::new(space));
+		self
+	}
+
+	/// Check if the storage engine keeps the records of tables defined with CACHE FULL in memory
+	pub(crate) fn caches_tables(&self) -> bool {
+		self.pin.is_some()
+	}
+
+	/// Injects the failure which is enabled for a failpoint of the write path, if any
+	#[cfg(feature = "failpoints")]
+	pub(crate) fn failpoint(&self, fp: Failpoint) -> Result<(), Error> {
+		self.failpoints.trigger(fp)
+	}
+
+	// --------------------------------------------------
+	// Temporary methods
+	// --------------------------------------------------
+
+	/// Check if a key belongs to a temporary table, whose data is kept in the session.
+	async fn temporary(&mut self, key: &Key) -> Result<Option<&mut Overlay>, Error> {
+		if self.tmp.is_none() {
+			return Ok(None);
+		}
+		let Some(tb) = temporary::table(key) else {
+			return Ok(None);
+		};
+		let cached = self.tmp.as_ref().and_then(|tmp| tmp.tables.get(&tb).copied());
+		let is = match cached {
+			Some(v) => v,
+			None => {
+				let is = match self.fetch(tb.clone()).await? {
+					Some(v) => DefineTableStatement::from(v).temporary,
+					None => false,
+				};
+				if let Some(tmp) = self.tmp.as_mut() {
+					tmp.tables.insert(tb, is);
+				}
+				is
+			}
+		};
+		Ok(self.tmp.as_mut().filter(|_| is))
+	}
+
+	/// Forget which tables are temporary when a table is defined or removed, and keep
+	/// track of the cached tables which are changed.
+	fn changed(&mut self, key: &Key) {
+		if let Some(tmp) = self.tmp.as_mut() {
+			if temporary::is_definition(key) {
+				tmp.tables.clear();
+			}
+		}
+		if let Some(pin) = self.pin.as_mut() {
+			pin.changed(key);
+		}
+	}
+
+	// --------------------------------------------------
+	// Cached table methods
+	// --------------------------------------------------
+
+	/// Check if a key is a record of a table which is defined with CACHE FULL, returning
+	/// the key of the table definition and the range of its records.
+	async fn pinned(&mut self, key: &Key) -> Result<Option<(Key, Range<Key>)>, Error> {
+		let Some(pin) = self.pin.as_ref() else {
+			return Ok(None);
+		};
+		let Some((tb, rng)) = pinned::table(key) else {
+			return Ok(None);
+		};
+		let is = match pin.cached(&tb) {
+			Some(v) => v,
+			None => {
+				let is = match self.fetch(tb.clone()).await? {
+					Some(v) => DefineTableStatement::from(v).cache,
+					None => false,
+				};
+				if let Some(pin) = self.pin.as_ref() {
+					pin.define(tb.clone(), is);
+				}
+				is
+			}
+		};
+		Ok(is.then_some((tb, rng)))
+	}
+
+	/// Fetch all the records of a cached table, loading them from the datastore if needed.
+	async fn records(&mut self, tb: Key, rng: Range<Key>) -> Result<Records, Error> {
+		if let Some(v) = self.pin.as_ref().and_then(|pin| pin.records(&tb)) {
+			return Ok(v);
+		}
+		let mut out = BTreeMap::new();
+		let mut beg = rng.start;
+		loop {
+			let res = self.fetch_range(beg.clone()..rng.end.clone(), 1000).await?;
+			let Some((last, _)) = res.last() else {
+				break;
+			};
+			beg = last.clone();
+			beg.push(0x00);
+			out.extend(res);
+		}
+		let out = Arc::new(out);
+		if let Some(pin) = self.pin.as_ref() {
+			pin.fill(tb, out.clone());
+		}
+		Ok(out)
+	}
+
 	// --------------------------------------------------
 	// Integral methods
 	// --------------------------------------------------
@@ -148,11 +383,30 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.closed(),
+			#[cfg(feature = "kv-sled")]
+			Transaction {
+				inner: Inner::Sled(v),
+				..
+			} => v.closed(),
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.closed(),
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
 				..
 			} => v.closed(),
+			#[cfg(feature = "kv-opfs")]
+			Transaction {
+				inner: Inner::Opfs(v),
+				..
+			} => v.closed(),
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.closed(),
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(v),
@@ -174,6 +428,12 @@ impl Transaction {
 	pub async fn cancel(&mut self) -> Result<(), Error> {
 		#[cfg(debug_assertions)]
 		trace!("Cancel");
+		if let Some(tmp) = self.tmp.as_mut() {
+			tmp.cancel();
+		}
+		if let Some(pin) = self.pin.as_mut() {
+			pin.cancel();
+		}
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -190,11 +450,30 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.cancel().await,
+			#[cfg(feature = "kv-sled")]
+			Transaction {
+				inner: Inner::Sled(v),
+				..
+			} => v.cancel().await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.cancel().await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
 				..
 			} => v.cancel().await,
+			#[cfg(feature = "kv-opfs")]
+			Transaction {
+				inner: Inner::Opfs(v),
+				..
+			} => v.cancel().await,
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.cancel().await,
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(v),
@@ -216,7 +495,12 @@ impl Transaction {
 	pub async fn commit(&mut self) -> Result<(), Error> {
 		#[cfg(debug_assertions)]
 		trace!("Commit");
-		match self {
+		#[cfg(feature = "failpoints")]
+		if let Err(e) = self.failpoint(Failpoint::Commit) {
+			self.cancel().await?;
+			return Err(e);
+		}
+		let res = match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
 				inner: Inner::Mem(v),
@@ -232,11 +516,30 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.commit().await,
+			#[cfg(feature = "kv-sled")]
+			Transaction {
+				inner: Inner::Sled(v),
+				..
+			} => v.commit().await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.commit().await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
 				..
 			} => v.commit().await,
+			#[cfg(feature = "kv-opfs")]
+			Transaction {
+				inner: Inner::Opfs(v),
+				..
+			} => v.commit().await,
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.commit().await,
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(v),
@@ -249,7 +552,23 @@ impl Transaction {
 			} => v.commit().await,
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
+		};
+		// Keep the changes to temporary tables once the transaction has committed
+		if res.is_ok() {
+			if let Some(tmp) = self.tmp.as_mut() {
+				tmp.commit();
+			}
+			if let Some(pin) = self.pin.as_mut() {
+				pin.commit();
+			}
+			self.telemetry.emit(TelemetryKind::TransactionCommitted);
 		}
+		res
+	}
+
+	/// The subscribers to the internal events of the datastore
+	pub(crate) fn telemetry(&self) -> &Telemetry {
+		&self.telemetry
 	}
 
 	/// Delete a key from the datastore.
@@ -260,6 +579,19 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Del {:?}", crate::key::debug::sprint_key(&key.clone().into()));
+		let key: Key = key.into();
+		if let Some(tmp) = self.temporary(&key).await? {
+			tmp.del(key);
+			return Ok(());
+		}
+		// The data of a temporary table which has been removed is removed along with it, and
+		// was never written to the datastore
+		if let Some(tmp) = self.tmp.as_mut() {
+			if temporary::table(&key).is_some() && tmp.del(key.clone()) {
+				return Ok(());
+			}
+		}
+		self.changed(&key);
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -276,11 +608,30 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.del(key).await,
+			#[cfg(feature = "kv-sled")]
+			Transaction {
+				inner: Inner::Sled(v),
+				..
+			} => v.del(key).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.del(key).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
 				..
 			} => v.del(key).await,
+			#[cfg(feature = "kv-opfs")]
+			Transaction {
+				inner: Inner::Opfs(v),
+				..
+			} => v.del(key).await,
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.del(key.into()).await,
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(v),
@@ -304,6 +655,13 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Exi {:?}", key);
+		let key: Key = key.into();
+		if let Some(tmp) = self.temporary(&key).await? {
+			return Ok(tmp.get(&key).is_some());
+		}
+		if let Some((tb, rng)) = self.pinned(&key).await? {
+			return Ok(self.records(tb, rng).await?.contains_key(&key));
+		}
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -320,11 +678,30 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.exi(key).await,
+			#[cfg(feature = "kv-sled")]
+			Transaction {
+				inner: Inner::Sled(v),
+				..
+			} => v.exi(key).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.exi(key).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
 				..
 			} => v.exi(key).await,
+			#[cfg(feature = "kv-opfs")]
+			Transaction {
+				inner: Inner::Opfs(v),
+				..
+			} => v.exi(key).await,
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.exi(key.into()).await,
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(v),
@@ -341,13 +718,25 @@ impl Transaction {
 	}
 
 	/// Fetch a key from the datastore.
-	#[allow(unused_variables)]
 	pub async fn get<K>(&mut self, key: K) -> Result<Option<Val>, Error>
 	where
 		K: Into<Key> + Debug,
 	{
 		#[cfg(debug_assertions)]
 		trace!("Get {:?}", key);
+		let key: Key = key.into();
+		if let Some(tmp) = self.temporary(&key).await? {
+			return Ok(tmp.get(&key));
+		}
+		if let Some((tb, rng)) = self.pinned(&key).await? {
+			return Ok(self.records(tb, rng).await?.get(&key).cloned());
+		}
+		self.fetch(key).await
+	}
+
+	/// Fetch a key from the underlying datastore, without looking at temporary or cached tables.
+	#[allow(unused_variables)]
+	async fn fetch(&mut self, key: Key) -> Result<Option<Val>, Error> {
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -364,11 +753,30 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.get(key).await,
+			#[cfg(feature = "kv-sled")]
+			Transaction {
+				inner: Inner::Sled(v),
+				..
+			} => v.get(key).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.get(key).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
 				..
 			} => v.get(key).await,
+			#[cfg(feature = "kv-opfs")]
+			Transaction {
+				inner: Inner::Opfs(v),
+				..
+			} => v.get(key).await,
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.get(key.into()).await,
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(v),
@@ -393,6 +801,12 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Set {:?} => {:?}", key, val);
+		let key: Key = key.into();
+		if let Some(tmp) = self.temporary(&key).await? {
+			tmp.set(key, val.into());
+			return Ok(());
+		}
+		self.changed(&key);
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -409,11 +823,30 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.set(key, val).await,
+			#[cfg(feature = "kv-sled")]
+			Transaction {
+				inner: Inner::Sled(v),
+				..
+			} => v.set(key, val).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.set(key, val).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
 				..
 			} => v.set(key, val).await,
+			#[cfg(feature = "kv-opfs")]
+			Transaction {
+				inner: Inner::Opfs(v),
+				..
+			} => v.set(key, val).await,
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.set(key.into(), val.into()).await,
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(v),
@@ -457,6 +890,15 @@ impl Transaction {
 				inner: Inner::IndxDB(v),
 				..
 			} => v.get_timestamp(key).await,
+			#[cfg(feature = "kv-opfs")]
+			Transaction {
+				inner: Inner::Opfs(v),
+				..
+			} => v.get_timestamp(key).await,
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.get_timestamp(key.into()).await,
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(v),
@@ -472,6 +914,16 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.get_timestamp(key).await,
+			#[cfg(feature = "kv-sled")]
+			Transaction {
+				inner: Inner::Sled(v),
+				..
+			} => v.get_timestamp(key).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.get_timestamp(key).await,
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
 		}
@@ -540,6 +992,24 @@ impl Transaction {
 				let k = v.get_versionstamped_key(ts_key, prefix, suffix).await?;
 				v.set(k, val).await
 			}
+			#[cfg(feature = "kv-opfs")]
+			Transaction {
+				inner: Inner::Opfs(v),
+				..
+			} => {
+				let k = v.get_versionstamped_key(ts_key, prefix, suffix).await?;
+				v.set(k, val).await
+			}
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => {
+				let ts = v.get_timestamp(ts_key.into()).await?;
+				let mut k: Vec<u8> = prefix.into();
+				k.extend_from_slice(&ts);
+				k.append(&mut suffix.into());
+				v.set(k, val.into()).await
+			}
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(v),
@@ -561,6 +1031,22 @@ impl Transaction {
 				let k = v.get_versionstamped_key(ts_key, prefix, suffix).await?;
 				v.set(k, val).await
 			}
+			#[cfg(feature = "kv-sled")]
+			Transaction {
+				inner: Inner::Sled(v),
+				..
+			} => {
+				let k = v.get_versionstamped_key(ts_key, prefix, suffix).await?;
+				v.set(k, val).await
+			}
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => {
+				let k = v.get_versionstamped_key(ts_key, prefix, suffix).await?;
+				v.set(k, val).await
+			}
 			#[allow(unreachable_patterns)]
 			_ => unreachable!(),
 		}
@@ -575,6 +1061,11 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Put {:?} => {:?}", key, val);
+		let key: Key = key.into();
+		if let Some(tmp) = self.temporary(&key).await? {
+			return tmp.put(key, val.into());
+		}
+		self.changed(&key);
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -591,11 +1082,30 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.put(key, val).await,
+			#[cfg(feature = "kv-sled")]
+			Transaction {
+				inner: Inner::Sled(v),
+				..
+			} => v.put(key, val).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.put(key, val).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
 				..
 			} => v.put(key, val).await,
+			#[cfg(feature = "kv-opfs")]
+			Transaction {
+				inner: Inner::Opfs(v),
+				..
+			} => v.put(key, val).await,
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.put(key.into(), val.into()).await,
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(v),
@@ -625,6 +1135,33 @@ impl Transaction {
 			debug::sprint_key(&rng.start.clone().into()),
 			debug::sprint_key(&rng.end.clone().into())
 		);
+		let rng: Range<Key> = rng.start.into()..rng.end.into();
+		// The entries of temporary tables are only kept in the session
+		if temporary::range(&rng).is_some() {
+			if let Some(tmp) = self.temporary(&rng.start).await? {
+				return Ok(tmp.scan(&rng, limit, vec![]));
+			}
+		}
+		// The records of cached tables are read from memory
+		let res = match self.pinned(&rng.start).await? {
+			Some((tb, all)) if rng.start < rng.end && rng.end <= all.end => {
+				let records = self.records(tb, all).await?;
+				let res = records.range(rng.clone()).take(limit as usize);
+				Ok(res.map(|(k, v)| (k.clone(), v.clone())).collect())
+			}
+			_ => self.fetch_range(rng.clone(), limit).await,
+		};
+		// Merge in the entries of temporary tables in the range
+		match self.tmp.as_ref() {
+			Some(tmp) => Ok(tmp.scan(&rng, limit, res?)),
+			None => res,
+		}
+	}
+
+	/// Retrieve a range of keys from the underlying datastore, without looking at temporary or
+	/// cached tables.
+	#[allow(unused_variables)]
+	async fn fetch_range(&mut self, rng: Range<Key>, limit: u32) -> Result<Vec<(Key, Val)>, Error> {
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -641,11 +1178,30 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.scan(rng, limit).await,
+			#[cfg(feature = "kv-sled")]
+			Transaction {
+				inner: Inner::Sled(v),
+				..
+			} => v.scan(rng, limit).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.scan(rng, limit).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
 				..
 			} => v.scan(rng, limit).await,
+			#[cfg(feature = "kv-opfs")]
+			Transaction {
+				inner: Inner::Opfs(v),
+				..
+			} => v.scan(rng, limit).await,
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.scan(rng, limit).await,
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(v),
@@ -670,6 +1226,11 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Putc {:?} if {:?} => {:?}", key, chk, val);
+		let key: Key = key.into();
+		if let Some(tmp) = self.temporary(&key).await? {
+			return tmp.putc(key, val.into(), chk.map(Into::into));
+		}
+		self.changed(&key);
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -686,11 +1247,30 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.putc(key, val, chk).await,
+			#[cfg(feature = "kv-sled")]
+			Transaction {
+				inner: Inner::Sled(v),
+				..
+			} => v.putc(key, val, chk).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.putc(key, val, chk).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
 				..
 			} => v.putc(key, val, chk).await,
+			#[cfg(feature = "kv-opfs")]
+			Transaction {
+				inner: Inner::Opfs(v),
+				..
+			} => v.putc(key, val, chk).await,
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.putc(key.into(), val.into(), chk.map(Into::into)).await,
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(v),
@@ -715,6 +1295,11 @@ impl Transaction {
 	{
 		#[cfg(debug_assertions)]
 		trace!("Delc {:?} if {:?}", key, chk);
+		let key: Key = key.into();
+		if let Some(tmp) = self.temporary(&key).await? {
+			return tmp.delc(key, chk.map(Into::into));
+		}
+		self.changed(&key);
 		match self {
 			#[cfg(feature = "kv-mem")]
 			Transaction {
@@ -731,11 +1316,30 @@ impl Transaction {
 				inner: Inner::SpeeDB(v),
 				..
 			} => v.delc(key, chk).await,
+			#[cfg(feature = "kv-sled")]
+			Transaction {
+				inner: Inner::Sled(v),
+				..
+			} => v.delc(key, chk).await,
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(v),
+				..
+			} => v.delc(key, chk).await,
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(v),
 				..
 			} => v.delc(key, chk).await,
+			#[cfg(feature = "kv-opfs")]
+			Transaction {
+				inner: Inner::Opfs(v),
+				..
+			} => v.delc(key, chk).await,
+			Transaction {
+				inner: Inner::Custom(v),
+				..
+			} => v.delc(key.into(), chk.map(Into::into)).await,
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(v),
@@ -910,6 +1514,49 @@ impl Transaction {
 		}
 		Ok(out)
 	}
+	/// Stream a specific prefix of keys from the datastore.
+	///
+	/// Unlike [`Transaction::getp`], the key-value pairs are not collected into memory. They
+	/// are fetched from the underlying datastore in batches by a [`Scanner`], as table scans
+	/// are, and the transaction is only locked while each batch is fetched, so it can be used
+	/// while the stream is consumed.
+	pub fn scan_prefix<K>(
+		txn: &Arc<Mutex<Transaction>>,
+		prefix: K,
+		limit: u32,
+	) -> impl Stream<Item = Result<(Key, Val), Error>>
+	where
+		K: Into<Key> + Debug,
+	{
+		#[cfg(debug_assertions)]
+		trace!("Scan prefix {:?} (limit: {limit})", prefix);
+		let beg: Key = prefix.into();
+		let end: Key = beg.clone().add(0xff);
+		// The entries are only fetched when the stream is polled, so nothing is read ahead
+		let scan = Scanner {
+			txn: txn.clone(),
+			beg,
+			end,
+			window: 0,
+			size: BatchSize::new(PROCESSOR_BATCH_SIZE),
+			batches: VecDeque::new(),
+			done: false,
+			num: limit,
+		};
+		stream::try_unfold((scan, Vec::new().into_iter()), |(mut scan, mut res)| async move {
+			loop {
+				// Return the next entry from the current batch
+				if let Some(kv) = res.next() {
+					return Ok(Some((kv, (scan, res))));
+				}
+				// Fetch the next batch, until the prefix is exhausted
+				match scan.next_batch().await? {
+					Some(batch) => res = batch.into_iter(),
+					None => return Ok(None),
+				}
+			}
+		})
+	}
 	/// Delete a prefix of keys from the datastore.
 	///
 	/// This function fetches key-value pairs from the underlying datastore in batches of 1000.
@@ -1458,6 +2105,29 @@ impl Transaction {
 		})
 	}
 
+	/// Retrieve all task definitions for a specific database.
+	pub async fn all_db_tasks(
+		&mut self,
+		ns: &str,
+		db: &str,
+	) -> Result<Arc<[DefineTaskStatement]>, Error> {
+		let key = crate::key::database::ta::prefix(ns, db);
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Tas(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let beg = crate::key::database::ta::prefix(ns, db);
+			let end = crate::key::database::ta::suffix(ns, db);
+			let val = self.getr(beg..end, u32::MAX).await?;
+			let val = val.convert().into();
+			self.cache.set(key, Entry::Tas(Arc::clone(&val)));
+			val
+		})
+	}
+
 	/// Retrieve all scope definitions for a specific database.
 	pub async fn all_sc(
 		&mut self,
@@ -1576,6 +2246,54 @@ impl Transaction {
 		})
 	}
 
+	/// Retrieve all field backfills for a specific table.
+	pub async fn all_tb_backfills(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+	) -> Result<Arc<[Backfill]>, Error> {
+		let key = crate::key::table::bf::prefix(ns, db, tb);
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Bfs(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let beg = crate::key::table::bf::prefix(ns, db, tb);
+			let end = crate::key::table::bf::suffix(ns, db, tb);
+			let val = self.getr(beg..end, u32::MAX).await?;
+			let val = val.convert().into();
+			self.cache.set(key, Entry::Bfs(Arc::clone(&val)));
+			val
+		})
+	}
+
+	/// Retrieve all index rebuilds for a specific table.
+	pub async fn all_tb_rebuilds(
+		&mut self,
+		ns: &str,
+		db: &str,
+		tb: &str,
+	) -> Result<Arc<[Rebuild]>, Error> {
+		let key = crate::key::table::rb::prefix(ns, db, tb);
+		Ok(if let Some(e) = self.cache.get(&key) {
+			if let Entry::Rbs(v) = e {
+				v
+			} else {
+				unreachable!();
+			}
+		} else {
+			let beg = crate::key::table::rb::prefix(ns, db, tb);
+			let end = crate::key::table::rb::suffix(ns, db, tb);
+			let val = self.getr(beg..end, u32::MAX).await?;
+			let val = val.convert().into();
+			self.cache.set(key, Entry::Rbs(Arc::clone(&val)));
+			val
+		})
+	}
+
 	/// Retrieve all index definitions for a specific table.
 	pub async fn all_tb_indexes(
 		&mut self,
@@ -1755,6 +2473,11 @@ impl Transaction {
 		Ok(val.into())
 	}
 
+	/// Retrieve an analyzer which is registered with the datastore.
+	pub(crate) fn get_custom_analyzer(&self, az: &str) -> Option<Arc<dyn Analyzer>> {
+		self.analyzers.get(az)
+	}
+
 	/// Retrieve a specific analyzer definition.
 	pub async fn get_db_analyzer(
 		&mut self,
@@ -2298,6 +3021,20 @@ impl Transaction {
 				chn.send(bytes!("")).await?;
 			}
 		}
+		// Output TASKS
+		{
+			let tas = self.all_db_tasks(ns, db).await?;
+			if !tas.is_empty() {
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("-- TASKS")).await?;
+				chn.send(bytes!("-- ------------------------------")).await?;
+				chn.send(bytes!("")).await?;
+				for ta in tas.iter() {
+					chn.send(bytes!(format!("{ta};"))).await?;
+				}
+				chn.send(bytes!("")).await?;
+			}
+		}
 		// Output SCOPES
 		{
 			let scs = self.all_sc(ns, db).await?;
@@ -2378,20 +3115,22 @@ impl Transaction {
 					let beg = crate::key::thing::prefix(ns, db, &tb.name);
 					let end = crate::key::thing::suffix(ns, db, &tb.name);
 					let mut nxt: Option<Vec<u8>> = None;
+					let mut size = BatchSize::new(1000);
 					loop {
 						let res = match nxt {
 							None => {
 								let min = beg.clone();
 								let max = end.clone();
-								self.scan(min..max, 1000).await?
+								self.scan(min..max, size.get()).await?
 							}
 							Some(ref mut beg) => {
 								beg.push(0x00);
 								let min = beg.clone();
 								let max = end.clone();
-								self.scan(min..max, 1000).await?
+								self.scan(min..max, size.get()).await?
 							}
 						};
+						size.observe(&res);
 						if !res.is_empty() {
 							// Get total results
 							let n = res.len();
@@ -2407,7 +3146,7 @@ impl Transaction {
 								}
 								// Parse the key and the value
 								let k: crate::key::thing::Thing = (&k).into();
-								let v: Value = (&v).into();
+								let v = record::load(self, ns, db, &tb.name, &v).await?;
 								let t = Thing::from((k.tb, k.id));
 								// Check if this is a graph edge
 								match (v.pick(&*EDGE), v.pick(&*IN), v.pick(&*OUT)) {
@@ -2624,6 +3363,13 @@ impl Transaction {
 	// non-FDB backends.
 	pub(crate) async fn complete_changes(&mut self, _lock: bool) -> Result<(), Error> {
 		let changes = self.cf.get();
+		#[cfg(feature = "failpoints")]
+		if !changes.is_empty() {
+			if let Err(e) = self.failpoint(Failpoint::ChangeFeed) {
+				self.cancel().await?;
+				return Err(e);
+			}
+		}
 		for (tskey, prefix, suffix, v) in changes {
 			self.set_versionstamped_key(tskey, prefix, suffix, v).await?
 		}
@@ -2710,11 +3456,30 @@ impl Transaction {
 				inner: Inner::SpeeDB(ref mut v),
 				..
 			} => v.check_level(check),
+			#[cfg(feature = "kv-sled")]
+			Transaction {
+				inner: Inner::Sled(ref mut v),
+				..
+			} => v.check_level(check),
+			#[cfg(feature = "kv-redb")]
+			Transaction {
+				inner: Inner::Redb(ref mut v),
+				..
+			} => v.check_level(check),
 			#[cfg(feature = "kv-indxdb")]
 			Transaction {
 				inner: Inner::IndxDB(ref mut v),
 				..
 			} => v.check_level(check),
+			#[cfg(feature = "kv-opfs")]
+			Transaction {
+				inner: Inner::Opfs(ref mut v),
+				..
+			} => v.check_level(check),
+			Transaction {
+				inner: Inner::Custom(_),
+				..
+			} => (),
 			#[cfg(feature = "kv-tikv")]
 			Transaction {
 				inner: Inner::TiKV(ref mut v),
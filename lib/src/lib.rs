@@ -94,12 +94,26 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! # Building without OpenSSL
+//!
+//! The `rustls-only` feature guarantees that neither the remote engines nor the outbound
+//! `http::` functions link against OpenSSL, which is useful for static musl binaries and
+//! scratch containers. Enabling it together with the `native-tls` feature fails to compile.
+//!
+//! ```toml
+//! [dependencies]
+//! surrealdb = { version = "1", default-features = false, features = ["protocol-ws", "protocol-http", "http", "rustls-only"] }
+//! ```
 
 #![doc(html_favicon_url = "https://surrealdb.s3.amazonaws.com/favicon.png")]
 #![doc(html_logo_url = "https://surrealdb.s3.amazonaws.com/icon.png")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![cfg_attr(test, deny(warnings))]
 
+#[cfg(all(feature = "rustls-only", feature = "native-tls"))]
+compile_error!("The `rustls-only` feature can not be used together with the `native-tls` feature");
+
 #[macro_use]
 extern crate tracing;
 
@@ -116,6 +130,18 @@ mod vs;
 
 pub mod sql;
 
+#[cfg(feature = "values")]
+#[cfg_attr(docsrs, doc(cfg(feature = "values")))]
+pub mod values;
+
+#[cfg(all(feature = "capi", not(target_arch = "wasm32")))]
+#[cfg_attr(docsrs, doc(cfg(feature = "capi")))]
+pub mod capi;
+
+#[cfg(feature = "bindings")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bindings")))]
+pub mod bindings;
+
 #[doc(hidden)]
 pub mod cnf;
 #[doc(hidden)]
@@ -135,13 +161,26 @@ pub mod key;
 #[doc(hidden)]
 pub mod kvs;
 
+#[doc(inline)]
+pub use api::builder;
 #[doc(inline)]
 pub use api::engine;
 #[doc(inline)]
+pub use api::expr;
+#[doc(inline)]
 pub use api::method;
 #[doc(inline)]
+pub use api::migrations;
+#[cfg(feature = "macros")]
+#[doc(inline)]
+pub use api::model;
+#[doc(inline)]
 pub use api::opt;
 #[doc(inline)]
+pub use api::seed;
+#[doc(inline)]
+pub use api::unit_of_work;
+#[doc(inline)]
 pub use api::Connect;
 #[doc(inline)]
 pub use api::Connection;
@@ -163,6 +202,7 @@ pub mod channel {
 /// Different error types for embedded and remote databases
 pub mod error {
 	pub use crate::api::err::Error as Api;
+	pub use crate::api::err::ErrorBody;
 	pub use crate::err::Error as Db;
 }
 
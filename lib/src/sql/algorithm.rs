@@ -21,6 +21,7 @@ pub enum Algorithm {
 	Rs256,
 	Rs384,
 	Rs512,
+	Jwks,
 }
 
 impl Default for Algorithm {
@@ -45,6 +46,7 @@ impl fmt::Display for Algorithm {
 			Self::Rs256 => "RS256",
 			Self::Rs384 => "RS384",
 			Self::Rs512 => "RS512",
+			Self::Jwks => "JWKS",
 		})
 	}
 }
@@ -64,5 +66,6 @@ pub fn algorithm(i: &str) -> IResult<&str, Algorithm> {
 		value(Algorithm::Rs256, tag("RS256")),
 		value(Algorithm::Rs384, tag("RS384")),
 		value(Algorithm::Rs512, tag("RS512")),
+		value(Algorithm::Jwks, tag("JWKS")),
 	))(i)
 }
@@ -231,13 +231,16 @@ pub(crate) fn builtin_name(i: &str) -> IResult<&str, BuiltinName<&str>, ParseErr
 		},
 		geo => {
 			area => { fn },
+			bbox => { fn },
 			bearing => { fn },
 			centroid => { fn },
+			contains => { fn },
 			distance => { fn },
 			hash => {
 				decode => { fn },
 				encode => { fn },
 			},
+			intersects => { fn },
 		},
 		http => {
 			head => { fn },
@@ -251,12 +254,15 @@ pub(crate) fn builtin_name(i: &str) -> IResult<&str, BuiltinName<&str>, ParseErr
 			abs => { fn },
 			bottom => { fn },
 			ceil => { fn },
+			count_state => { fn },
 			fixed => { fn },
 			floor => { fn },
+			hll_state => { fn },
 			interquartile => { fn },
 			max => { fn },
 			mean => { fn },
 			median => { fn },
+			merge_state => { fn },
 			midhinge => { fn },
 			min => { fn },
 			mode => { fn },
@@ -267,8 +273,11 @@ pub(crate) fn builtin_name(i: &str) -> IResult<&str, BuiltinName<&str>, ParseErr
 			round => { fn },
 			spread => { fn },
 			sqrt => { fn },
+			state_value => { fn },
 			stddev => { fn },
 			sum => { fn },
+			sum_state => { fn },
+			tdigest_state => { fn },
 			top => { fn },
 			trimean => { fn },
 			variance => { fn },
@@ -294,6 +303,7 @@ pub(crate) fn builtin_name(i: &str) -> IResult<&str, BuiltinName<&str>, ParseErr
 			TAU => { const = constant::Constant::MathTau },
 		},
 		meta => {
+			checksum => { fn },
 			id => { fn },
 			table => { fn },
 			tb => { fn },
@@ -328,9 +338,12 @@ pub(crate) fn builtin_name(i: &str) -> IResult<&str, BuiltinName<&str>, ParseErr
 			},
 		},
 		search => {
+			duplicates => { fn },
 			score => { fn },
 			highlight => { fn },
+			minhash => { fn },
 			offsets => { fn },
+			simhash => { fn },
 		},
 		session => {
 			db => { fn },
@@ -384,6 +397,9 @@ pub(crate) fn builtin_name(i: &str) -> IResult<&str, BuiltinName<&str>, ParseErr
 				uuid => { fn },
 			}
 		},
+		sys => {
+			http_usage => { fn },
+		},
 		time => {
 			ceil => { fn },
 			day => { fn },
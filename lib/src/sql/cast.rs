@@ -3,6 +3,7 @@ use crate::dbs::{Options, Transaction};
 use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::sql::comment::mightbespace;
+use crate::sql::diagnostics;
 use crate::sql::error::IResult;
 use crate::sql::idiom::Idiom;
 use crate::sql::kind::{kind, Kind};
@@ -58,10 +59,11 @@ impl fmt::Display for Cast {
 	}
 }
 
-pub fn cast(i: &str) -> IResult<&str, Cast> {
-	let (i, k) = delimited(char('<'), cut(kind), char('>'))(i)?;
+pub fn cast(start: &str) -> IResult<&str, Cast> {
+	let (i, k) = delimited(char('<'), cut(kind), char('>'))(start)?;
 	let (i, _) = mightbespace(i)?;
 	let (i, v) = cut(single)(i)?;
+	diagnostics::cast(start, i, &k, &v);
 	Ok((i, Cast(k, v)))
 }
 
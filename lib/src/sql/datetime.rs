@@ -48,6 +48,59 @@ impl From<Datetime> for DateTime<Utc> {
 	}
 }
 
+impl From<DateTime<FixedOffset>> for Datetime {
+	fn from(v: DateTime<FixedOffset>) -> Self {
+		Self(v.with_timezone(&Utc))
+	}
+}
+
+impl From<Datetime> for DateTime<FixedOffset> {
+	fn from(x: Datetime) -> Self {
+		x.0.fixed_offset()
+	}
+}
+
+/// Datetimes without a timezone are taken to be in UTC
+impl From<NaiveDateTime> for Datetime {
+	fn from(v: NaiveDateTime) -> Self {
+		Self(Utc.from_utc_datetime(&v))
+	}
+}
+
+impl From<Datetime> for NaiveDateTime {
+	fn from(x: Datetime) -> Self {
+		x.0.naive_utc()
+	}
+}
+
+#[cfg(feature = "time")]
+impl From<::time::OffsetDateTime> for Datetime {
+	fn from(v: ::time::OffsetDateTime) -> Self {
+		// The range of the time crate is narrower than the range of chrono
+		let v = NaiveDateTime::from_timestamp_opt(v.unix_timestamp(), v.nanosecond())
+			.expect("datetimes of the time crate are within the range of chrono");
+		Self(Utc.from_utc_datetime(&v))
+	}
+}
+
+/// Datetimes without a timezone are taken to be in UTC
+#[cfg(feature = "time")]
+impl From<::time::PrimitiveDateTime> for Datetime {
+	fn from(v: ::time::PrimitiveDateTime) -> Self {
+		Self::from(v.assume_utc())
+	}
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<Datetime> for ::time::OffsetDateTime {
+	type Error = crate::err::Error;
+	fn try_from(x: Datetime) -> Result<Self, Self::Error> {
+		::time::OffsetDateTime::from_unix_timestamp(x.0.timestamp())
+			.and_then(|v| v.replace_nanosecond(x.0.timestamp_subsec_nanos()))
+			.map_err(|_| crate::err::Error::TryFrom(x.to_string(), "time::OffsetDateTime"))
+	}
+}
+
 impl FromStr for Datetime {
 	type Err = ();
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -361,4 +414,32 @@ mod tests {
 		let sql = "2022-11-31T12:00:00.000Z";
 		datetime_raw(sql).unwrap_err();
 	}
+
+	#[test]
+	fn date_time_chrono() {
+		let out = Datetime::try_from("2012-04-23T18:25:43.000051100Z").unwrap();
+		let offset = DateTime::parse_from_rfc3339("2012-04-23T20:25:43.000051100+02:00").unwrap();
+		assert_eq!(Datetime::from(offset), out);
+		assert_eq!(DateTime::<FixedOffset>::from(out.clone()), offset);
+		let naive = NaiveDateTime::from(out.clone());
+		assert_eq!(naive.to_string(), "2012-04-23 18:25:43.000051100");
+		assert_eq!(Datetime::from(naive), out);
+	}
+
+	#[test]
+	#[cfg(feature = "time")]
+	fn date_time_time() {
+		let out = Datetime::try_from("2012-04-23T18:25:43.000051100Z").unwrap();
+		let offset = ::time::OffsetDateTime::from_unix_timestamp(1335205543)
+			.unwrap()
+			.replace_nanosecond(51100)
+			.unwrap()
+			.to_offset(::time::UtcOffset::from_hms(2, 0, 0).unwrap());
+		assert_eq!(Datetime::from(offset), out);
+		assert_eq!(::time::OffsetDateTime::try_from(out.clone()).unwrap(), offset);
+		let primitive = ::time::PrimitiveDateTime::new(offset.date(), offset.time());
+		assert_eq!(Datetime::from(primitive), Datetime(out.0 + chrono::Duration::hours(2)));
+		let far = Datetime::from(Utc.with_ymd_and_hms(20000, 1, 1, 0, 0, 0).unwrap());
+		::time::OffsetDateTime::try_from(far).unwrap_err();
+	}
 }
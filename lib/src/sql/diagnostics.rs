@@ -0,0 +1,304 @@
+//! Reports the problems in a SurrealQL query, without stopping at the first one
+//!
+//! Syntax errors are found by parsing the query one statement at a time, carrying on
+//! after the next `;` when a statement fails to parse. Semantic problems, such as calls
+//! to builtin functions with the wrong number of arguments, or literals which can not be
+//! cast to the requested kind, are reported by the parser as it goes, so that all of the
+//! problems are found in a single pass.
+use crate::err::Error;
+use crate::fnc;
+use crate::sql::comment::mightbespace;
+use crate::sql::error::ParseError;
+use crate::sql::kind::Kind;
+use crate::sql::parser::depth;
+use crate::sql::statement::statement;
+use crate::sql::value::Value;
+use nom::Err;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::Range;
+
+thread_local! {
+	/// The problems found so far, while diagnostics are being collected
+	static FOUND: RefCell<Option<Found>> = RefCell::default();
+}
+
+struct Found {
+	/// The address of the start of the input
+	base: usize,
+	diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+	/// The query will fail to parse or run
+	Error,
+	/// The query is valid, but might not behave as expected
+	Warning,
+}
+
+impl fmt::Display for Severity {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Error => f.write_str("error"),
+			Self::Warning => f.write_str("warning"),
+		}
+	}
+}
+
+/// A problem in a query, and where it was found
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Diagnostic {
+	/// The byte range of the problem in the input
+	pub range: Range<usize>,
+	pub severity: Severity,
+	pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} at {}..{}: {}", self.severity, self.range.start, self.range.end, self.message)
+	}
+}
+
+/// Finds all of the syntax and semantic problems in a SurrealQL query
+///
+/// The diagnostics are ordered by their position in the input. An input which parses
+/// successfully, and passes all checks, returns no diagnostics.
+pub fn diagnostics(input: &str) -> Vec<Diagnostic> {
+	// Collect the problems found by the parser
+	FOUND.with(|found| {
+		*found.borrow_mut() = Some(Found {
+			base: input.as_ptr() as usize,
+			diagnostics: Vec::new(),
+		})
+	});
+	let mut out = Vec::new();
+	let mut i = input;
+	loop {
+		// Skip any whitespace, comments, and empty statements
+		i = skip(i);
+		if i.is_empty() {
+			break;
+		}
+		// Reset the parse depth limiter
+		depth::reset();
+		let tried = match statement(i) {
+			Ok((rest, _)) => {
+				let (rest, _) = mightbespace(rest).unwrap_or((rest, ()));
+				if rest.is_empty() || rest.starts_with(';') {
+					i = rest;
+					continue;
+				}
+				out.push(Diagnostic {
+					range: token(input, rest),
+					severity: Severity::Error,
+					message: String::from("Expected the end of the statement"),
+				});
+				rest
+			}
+			Err(Err::Error(e) | Err::Failure(e)) => {
+				let tried = e.tried();
+				out.push(Diagnostic {
+					range: token(input, tried),
+					severity: Severity::Error,
+					message: message(&e, input),
+				});
+				tried
+			}
+			Err(Err::Incomplete(_)) => break,
+		};
+		// Carry on after the end of the failed statement
+		i = match tried.find(';') {
+			Some(pos) => &tried[pos..],
+			None => "",
+		};
+	}
+	// Add the problems found while parsing
+	if let Some(found) = FOUND.with(|found| found.borrow_mut().take()) {
+		for v in found.diagnostics {
+			// The parser may backtrack over the same input more than once
+			if !out.contains(&v) {
+				out.push(v);
+			}
+		}
+	}
+	out.sort_by_key(|v| (v.range.start, v.range.end));
+	out
+}
+
+/// Checks a call to a builtin function, which spans from `start` up to `rest`
+pub(crate) fn function(start: &str, rest: &str, name: &str, args: &[Value]) {
+	if !collecting() {
+		return;
+	}
+	if let Err(Error::FunctionDisabled {
+		feature,
+		..
+	}) = fnc::enabled(name)
+	{
+		report(
+			start,
+			rest,
+			Severity::Warning,
+			format!("The function '{name}' is not available without the '{feature}' feature"),
+		);
+	}
+	if let Some(arity) = fnc::arity(name) {
+		if !arity.accepts(args.len()) {
+			report(
+				start,
+				rest,
+				Severity::Error,
+				format!("The function '{name}' expects {arity}, but was given {}", args.len()),
+			);
+		}
+	}
+}
+
+/// Checks a cast of a literal value, which spans from `start` up to `rest`
+pub(crate) fn cast(start: &str, rest: &str, kind: &Kind, value: &Value) {
+	if !collecting() || !literal(value) {
+		return;
+	}
+	if let Err(e) = value.clone().convert_to(kind) {
+		report(start, rest, Severity::Error, e.to_string());
+	}
+}
+
+/// Checks whether a value is known when the query is parsed
+fn literal(v: &Value) -> bool {
+	match v {
+		Value::Array(v) => v.iter().all(literal),
+		Value::Object(v) => v.values().all(literal),
+		Value::Constant(_) => false,
+		v => v.is_static(),
+	}
+}
+
+fn collecting() -> bool {
+	FOUND.with(|found| found.borrow().is_some())
+}
+
+fn report(start: &str, rest: &str, severity: Severity, message: String) {
+	FOUND.with(|found| {
+		if let Some(found) = found.borrow_mut().as_mut() {
+			let start = start.as_ptr() as usize - found.base;
+			let end = rest.as_ptr() as usize - found.base;
+			found.diagnostics.push(Diagnostic {
+				range: start..end,
+				severity,
+				message,
+			});
+		}
+	});
+}
+
+fn skip(mut i: &str) -> &str {
+	loop {
+		let (rest, _) = mightbespace(i).unwrap_or((i, ()));
+		match rest.strip_prefix(';') {
+			Some(rest) => i = rest,
+			None => return rest,
+		}
+	}
+}
+
+/// Returns the byte range of the token at the start of `tried`
+fn token(input: &str, tried: &str) -> Range<usize> {
+	let start = tried.as_ptr() as usize - input.as_ptr() as usize;
+	let len = match tried.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':')) {
+		// The token is a single symbol
+		Some(0) => tried.chars().next().map_or(0, char::len_utf8),
+		Some(len) => len,
+		None => tried.len(),
+	};
+	start..start + len
+}
+
+fn message(e: &ParseError<&str>, input: &str) -> String {
+	match e {
+		ParseError::Expected {
+			expected,
+			..
+		} => format!("Expected {expected}"),
+		ParseError::Explained {
+			explained,
+			..
+		}
+		| ParseError::ExplainedExpected {
+			explained,
+			..
+		} => explained.to_string(),
+		ParseError::InvalidPath {
+			tried,
+			parent,
+		} => {
+			let name = &tried[..token(tried, tried).end];
+			format!("Unknown function or constant '{parent}::{name}'")
+		}
+		e => e.render_on(input).text,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn found(input: &str) -> Vec<(&str, Severity)> {
+		diagnostics(input).into_iter().map(|v| (&input[v.range], v.severity)).collect()
+	}
+
+	#[test]
+	fn valid_query() {
+		assert!(diagnostics("SELECT * FROM person; RETURN string::len('test');").is_empty());
+	}
+
+	#[test]
+	fn continues_after_syntax_errors() {
+		let sql = "SELECT * FROM; CREATE person SET age = 1; SELECT * FROM person WHERE;";
+		let res = diagnostics(sql);
+		assert_eq!(res.len(), 2);
+		assert!(res.iter().all(|v| v.severity == Severity::Error));
+		assert!(res[0].range.start < 15);
+		assert!(res[1].range.start > 43);
+	}
+
+	#[test]
+	fn unknown_function() {
+		let sql = "RETURN string::nope('test');";
+		let res = diagnostics(sql);
+		assert_eq!(res.len(), 1);
+		assert_eq!(&sql[res[0].range.clone()], "nope");
+		assert_eq!(res[0].message, "Unknown function or constant 'string::nope'");
+	}
+
+	#[test]
+	fn wrong_arity() {
+		let sql = "RETURN string::len('a', 'b'); RETURN math::max([1, 2]);";
+		let res = diagnostics(sql);
+		assert_eq!(res.len(), 1);
+		assert_eq!(&sql[res[0].range.clone()], "string::len('a', 'b')");
+		assert_eq!(
+			res[0].message,
+			"The function 'string::len' expects 1 argument, but was given 2"
+		);
+	}
+
+	#[test]
+	fn invalid_cast() {
+		let sql = "RETURN <int> 'one'; RETURN <int> '1'; RETURN <int> $value;";
+		assert_eq!(found(sql), vec![("<int> 'one'", Severity::Error)]);
+	}
+
+	#[test]
+	fn nested_problems() {
+		let sql = "SELECT string::len() AS a, <bool> 'maybe' AS b FROM person;";
+		assert_eq!(
+			found(sql),
+			vec![("string::len()", Severity::Error), ("<bool> 'maybe'", Severity::Error)]
+		);
+	}
+}
@@ -1,3 +1,4 @@
+use crate::err::Error;
 use crate::sql::common::take_u64;
 use crate::sql::datetime::Datetime;
 use crate::sql::ending::duration as ending;
@@ -7,7 +8,7 @@ use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::multi::many1;
 use revision::revisioned;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::fmt;
 use std::iter::Sum;
 use std::ops;
@@ -27,7 +28,7 @@ static NANOSECONDS_PER_MICROSECOND: u32 = 1000;
 
 pub(crate) const TOKEN: &str = "$surrealdb::private::sql::Duration";
 
-#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Hash)]
 #[serde(rename = "$surrealdb::private::sql::Duration")]
 #[revisioned(revision = 1)]
 pub struct Duration(pub time::Duration);
@@ -44,6 +45,74 @@ impl From<Duration> for time::Duration {
 	}
 }
 
+impl TryFrom<chrono::Duration> for Duration {
+	type Error = Error;
+	fn try_from(v: chrono::Duration) -> Result<Self, Self::Error> {
+		// Negative durations can't be represented
+		match v.to_std() {
+			Ok(v) => Ok(Self(v)),
+			Err(_) => Err(Error::TryFrom(v.to_string(), "Duration")),
+		}
+	}
+}
+
+impl TryFrom<Duration> for chrono::Duration {
+	type Error = Error;
+	fn try_from(v: Duration) -> Result<Self, Self::Error> {
+		chrono::Duration::from_std(v.0)
+			.map_err(|_| Error::TryFrom(v.to_string(), "chrono::Duration"))
+	}
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<::time::Duration> for Duration {
+	type Error = Error;
+	fn try_from(v: ::time::Duration) -> Result<Self, Self::Error> {
+		// Negative durations can't be represented
+		match time::Duration::try_from(v) {
+			Ok(v) => Ok(Self(v)),
+			Err(_) => Err(Error::TryFrom(v.to_string(), "Duration")),
+		}
+	}
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<Duration> for ::time::Duration {
+	type Error = Error;
+	fn try_from(v: Duration) -> Result<Self, Self::Error> {
+		::time::Duration::try_from(v.0).map_err(|_| Error::TryFrom(v.to_string(), "time::Duration"))
+	}
+}
+
+/// The duration as it is stored, as seconds and nanoseconds
+#[derive(Deserialize)]
+#[serde(rename = "$surrealdb::private::sql::Duration")]
+struct RawDuration(time::Duration);
+
+impl<'de> Deserialize<'de> for Duration {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		// Durations nested in JSON responses are rendered as strings, such as `1h30m`
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum Repr {
+			Raw(time::Duration),
+			Str(String),
+		}
+
+		match deserializer.is_human_readable() {
+			true => match Repr::deserialize(deserializer)? {
+				Repr::Raw(v) => Ok(Self(v)),
+				Repr::Str(s) => Duration::try_from(s.as_str())
+					.map_err(|_| serde::de::Error::custom(format!("invalid duration `{s}`"))),
+			},
+			false => RawDuration::deserialize(deserializer).map(|raw| Self(raw.0)),
+		}
+	}
+}
+
 impl FromStr for Duration {
 	type Err = ();
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -438,4 +507,35 @@ mod tests {
 		let res = duration(sql);
 		res.unwrap_err();
 	}
+
+	#[test]
+	fn duration_chrono() {
+		let out = super::Duration::from(Duration::new(5400, 1));
+		let chrono = chrono::Duration::seconds(5400) + chrono::Duration::nanoseconds(1);
+		assert_eq!(chrono::Duration::try_from(out.clone()).unwrap(), chrono);
+		assert_eq!(super::Duration::try_from(chrono).unwrap(), out);
+		super::Duration::try_from(-chrono).unwrap_err();
+	}
+
+	#[test]
+	#[cfg(feature = "time")]
+	fn duration_time() {
+		let out = super::Duration::from(Duration::new(5400, 1));
+		let time = ::time::Duration::new(5400, 1);
+		assert_eq!(::time::Duration::try_from(out.clone()).unwrap(), time);
+		assert_eq!(super::Duration::try_from(time).unwrap(), out);
+		super::Duration::try_from(-time).unwrap_err();
+	}
+
+	#[test]
+	fn duration_deserialize() {
+		let out = super::Duration::from(Duration::new(5400, 500000000));
+		let res: super::Duration = serde_json::from_str(r#""1h30m500ms""#).unwrap();
+		assert_eq!(res, out);
+		let res: super::Duration = serde_json::from_value(serde_json::json!(out)).unwrap();
+		assert_eq!(res, out);
+		let bytes = crate::sql::serde::serialize(&out).unwrap();
+		let res: super::Duration = crate::sql::serde::deserialize(&bytes).unwrap();
+		assert_eq!(res, out);
+	}
 }
@@ -0,0 +1,475 @@
+//! Fingerprints of queries, which are equal for queries which differ only in their literal values
+//!
+//! A fingerprint is a stable hash of the shape of a parsed query. Strings, numbers, durations,
+//! datetimes, uuids, bytes, booleans, geometries, and the ids of record ids are replaced with a
+//! placeholder before hashing, while tables, fields, params, functions, operators and clauses are
+//! all kept. The fingerprint only depends on the parsed query, so differences in whitespace,
+//! comments, or the case of keywords make no difference either.
+//!
+//! Fingerprints are the same across processes, platforms and connections, so they can be used
+//! to group the metrics of queries, as the key of a cache of query plans, or to aggregate slow
+//! queries, on clients and servers alike.
+use crate::sql::Query;
+use serde::de::value::Error;
+use serde::ser::{self, Serialize};
+
+/// The types whose values are all literals
+const LITERAL_TYPES: &[&str] = &[
+	"$surrealdb::private::sql::Strand",
+	"$surrealdb::private::sql::Number",
+	"$surrealdb::private::sql::Duration",
+	"$surrealdb::private::sql::Datetime",
+	"$surrealdb::private::sql::Uuid",
+	"$surrealdb::private::sql::Geometry",
+];
+
+/// The variants of values which are literals, but aren't of a literal type
+const LITERAL_VALUES: &[&str] = &["Bool", "Bytes"];
+
+/// Computes the fingerprint of a query
+///
+/// ```
+/// use surrealdb::sql;
+///
+/// let a = sql::parse("SELECT * FROM person WHERE age > 18 LIMIT 10").unwrap();
+/// let b = sql::parse("select * from person where age > 65 limit 5").unwrap();
+/// let c = sql::parse("SELECT * FROM person WHERE name = 'Tobie'").unwrap();
+/// assert_eq!(sql::fingerprint(&a), sql::fingerprint(&b));
+/// assert_ne!(sql::fingerprint(&a), sql::fingerprint(&c));
+/// ```
+pub fn fingerprint(query: &Query) -> u64 {
+	let mut hasher = Fingerprinter::default();
+	// Hashing never fails
+	let _ = query.serialize(&mut hasher);
+	hasher.finish()
+}
+
+/// Hashes the shape of a value as it is serialized
+struct Fingerprinter {
+	hash: u64,
+}
+
+impl Default for Fingerprinter {
+	fn default() -> Self {
+		Self {
+			hash: 0xcbf29ce484222325,
+		}
+	}
+}
+
+impl Fingerprinter {
+	fn write(&mut self, bytes: &[u8]) {
+		// FNV-1a, which is stable across versions and platforms
+		for b in bytes {
+			self.hash ^= *b as u64;
+			self.hash = self.hash.wrapping_mul(0x100000001b3);
+		}
+	}
+
+	fn tag(&mut self, tag: u8) {
+		self.write(&[tag]);
+	}
+
+	fn str(&mut self, v: &str) {
+		self.write(&(v.len() as u64).to_be_bytes());
+		self.write(v.as_bytes());
+	}
+
+	fn literal(&mut self) {
+		self.tag(b'?');
+	}
+
+	fn finish(&self) -> u64 {
+		// The SplitMix64 finalizer, to spread the bits
+		let h = self.hash;
+		let h = (h ^ (h >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+		let h = (h ^ (h >> 27)).wrapping_mul(0x94d049bb133111eb);
+		h ^ (h >> 31)
+	}
+}
+
+impl<'a> ser::Serializer for &'a mut Fingerprinter {
+	type Ok = ();
+	type Error = Error;
+	type SerializeSeq = Self;
+	type SerializeTuple = Self;
+	type SerializeTupleStruct = Self;
+	type SerializeTupleVariant = Self;
+	type SerializeMap = Self;
+	type SerializeStruct = Self;
+	type SerializeStructVariant = Self;
+
+	fn serialize_bool(self, v: bool) -> Result<(), Error> {
+		self.tag(b'b');
+		self.write(&[v as u8]);
+		Ok(())
+	}
+
+	fn serialize_i8(self, v: i8) -> Result<(), Error> {
+		self.serialize_i64(v as i64)
+	}
+
+	fn serialize_i16(self, v: i16) -> Result<(), Error> {
+		self.serialize_i64(v as i64)
+	}
+
+	fn serialize_i32(self, v: i32) -> Result<(), Error> {
+		self.serialize_i64(v as i64)
+	}
+
+	fn serialize_i64(self, v: i64) -> Result<(), Error> {
+		self.tag(b'i');
+		self.write(&v.to_be_bytes());
+		Ok(())
+	}
+
+	fn serialize_i128(self, v: i128) -> Result<(), Error> {
+		self.tag(b'i');
+		self.write(&v.to_be_bytes());
+		Ok(())
+	}
+
+	fn serialize_u8(self, v: u8) -> Result<(), Error> {
+		self.serialize_u64(v as u64)
+	}
+
+	fn serialize_u16(self, v: u16) -> Result<(), Error> {
+		self.serialize_u64(v as u64)
+	}
+
+	fn serialize_u32(self, v: u32) -> Result<(), Error> {
+		self.serialize_u64(v as u64)
+	}
+
+	fn serialize_u64(self, v: u64) -> Result<(), Error> {
+		self.tag(b'u');
+		self.write(&v.to_be_bytes());
+		Ok(())
+	}
+
+	fn serialize_u128(self, v: u128) -> Result<(), Error> {
+		self.tag(b'u');
+		self.write(&v.to_be_bytes());
+		Ok(())
+	}
+
+	fn serialize_f32(self, v: f32) -> Result<(), Error> {
+		self.serialize_f64(v as f64)
+	}
+
+	fn serialize_f64(self, v: f64) -> Result<(), Error> {
+		self.tag(b'f');
+		self.write(&v.to_bits().to_be_bytes());
+		Ok(())
+	}
+
+	fn serialize_char(self, v: char) -> Result<(), Error> {
+		self.serialize_str(v.encode_utf8(&mut [0; 4]))
+	}
+
+	fn serialize_str(self, v: &str) -> Result<(), Error> {
+		self.tag(b's');
+		self.str(v);
+		Ok(())
+	}
+
+	fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+		self.tag(b'x');
+		self.write(&(v.len() as u64).to_be_bytes());
+		self.write(v);
+		Ok(())
+	}
+
+	fn serialize_none(self) -> Result<(), Error> {
+		self.tag(b'n');
+		Ok(())
+	}
+
+	fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Error> {
+		self.tag(b'o');
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<(), Error> {
+		self.tag(b'e');
+		Ok(())
+	}
+
+	fn serialize_unit_struct(self, name: &'static str) -> Result<(), Error> {
+		self.tag(b'U');
+		self.str(name);
+		Ok(())
+	}
+
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		_index: u32,
+		variant: &'static str,
+	) -> Result<(), Error> {
+		self.tag(b'V');
+		self.str(name);
+		self.str(variant);
+		Ok(())
+	}
+
+	fn serialize_newtype_struct<T: ?Sized + Serialize>(
+		self,
+		name: &'static str,
+		value: &T,
+	) -> Result<(), Error> {
+		if LITERAL_TYPES.contains(&name) {
+			self.literal();
+			return Ok(());
+		}
+		self.tag(b'N');
+		self.str(name);
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: ?Sized + Serialize>(
+		self,
+		name: &'static str,
+		_index: u32,
+		variant: &'static str,
+		value: &T,
+	) -> Result<(), Error> {
+		let literal = match name {
+			"$surrealdb::private::sql::Value" => LITERAL_VALUES.contains(&variant),
+			// Record ids which are generated, such as with ulid(), aren't literals
+			"Id" => variant != "Generate",
+			name => LITERAL_TYPES.contains(&name),
+		};
+		if literal {
+			self.literal();
+			return Ok(());
+		}
+		self.tag(b'W');
+		self.str(name);
+		self.str(variant);
+		value.serialize(self)
+	}
+
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self, Error> {
+		self.tag(b'[');
+		self.write(&(len.unwrap_or_default() as u64).to_be_bytes());
+		Ok(self)
+	}
+
+	fn serialize_tuple(self, len: usize) -> Result<Self, Error> {
+		self.serialize_seq(Some(len))
+	}
+
+	fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self, Error> {
+		self.tag(b'T');
+		self.str(name);
+		self.serialize_seq(Some(len))
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		name: &'static str,
+		_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self, Error> {
+		self.tag(b'T');
+		self.str(name);
+		self.str(variant);
+		self.serialize_seq(Some(len))
+	}
+
+	fn serialize_map(self, len: Option<usize>) -> Result<Self, Error> {
+		self.tag(b'{');
+		self.write(&(len.unwrap_or_default() as u64).to_be_bytes());
+		Ok(self)
+	}
+
+	fn serialize_struct(self, name: &'static str, _len: usize) -> Result<Self, Error> {
+		self.tag(b'S');
+		self.str(name);
+		Ok(self)
+	}
+
+	fn serialize_struct_variant(
+		self,
+		name: &'static str,
+		_index: u32,
+		variant: &'static str,
+		_len: usize,
+	) -> Result<Self, Error> {
+		self.tag(b'S');
+		self.str(name);
+		self.str(variant);
+		Ok(self)
+	}
+}
+
+impl<'a> ser::SerializeSeq for &'a mut Fingerprinter {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		self.tag(b']');
+		Ok(())
+	}
+}
+
+impl<'a> ser::SerializeTuple for &'a mut Fingerprinter {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		self.tag(b']');
+		Ok(())
+	}
+}
+
+impl<'a> ser::SerializeTupleStruct for &'a mut Fingerprinter {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		self.tag(b']');
+		Ok(())
+	}
+}
+
+impl<'a> ser::SerializeTupleVariant for &'a mut Fingerprinter {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		self.tag(b']');
+		Ok(())
+	}
+}
+
+impl<'a> ser::SerializeMap for &'a mut Fingerprinter {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+		key.serialize(&mut **self)
+	}
+
+	fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		self.tag(b'}');
+		Ok(())
+	}
+}
+
+impl<'a> ser::SerializeStruct for &'a mut Fingerprinter {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: ?Sized + Serialize>(
+		&mut self,
+		key: &'static str,
+		value: &T,
+	) -> Result<(), Error> {
+		self.str(key);
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		self.tag(b'}');
+		Ok(())
+	}
+}
+
+impl<'a> ser::SerializeStructVariant for &'a mut Fingerprinter {
+	type Ok = ();
+	type Error = Error;
+
+	fn serialize_field<T: ?Sized + Serialize>(
+		&mut self,
+		key: &'static str,
+		value: &T,
+	) -> Result<(), Error> {
+		self.str(key);
+		value.serialize(&mut **self)
+	}
+
+	fn end(self) -> Result<(), Error> {
+		self.tag(b'}');
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::parse;
+
+	fn check(a: &str, b: &str) -> bool {
+		fingerprint(&parse(a).unwrap()) == fingerprint(&parse(b).unwrap())
+	}
+
+	#[test]
+	fn literals_are_ignored() {
+		assert!(check(
+			"SELECT * FROM person WHERE name = 'Tobie' AND age > 18 LIMIT 10",
+			"SELECT * FROM person WHERE name = 'Jaime' AND age > 21.5 LIMIT 100",
+		));
+		assert!(check(
+			"UPDATE person:tobie SET active = true",
+			"UPDATE person:100 SET active = false"
+		));
+		assert!(check(
+			"CREATE event SET at = d'2024-01-01T00:00:00Z', took = 1s",
+			"CREATE event SET at = d'2023-06-01T12:00:00Z', took = 3h",
+		));
+	}
+
+	#[test]
+	fn formatting_is_ignored() {
+		assert!(check(
+			"SELECT * FROM person WHERE age > 18",
+			"select *\n\tfrom person -- the people\n\twhere age > 65;",
+		));
+	}
+
+	#[test]
+	fn shape_is_kept() {
+		assert!(!check("SELECT * FROM person", "SELECT * FROM user"));
+		assert!(!check("SELECT name FROM person", "SELECT age FROM person"));
+		assert!(!check(
+			"SELECT * FROM person WHERE age > 18",
+			"SELECT * FROM person WHERE age < 18"
+		));
+		assert!(!check(
+			"SELECT * FROM person WHERE age > $min",
+			"SELECT * FROM person WHERE age > 18"
+		));
+		assert!(!check("SELECT * FROM person:ulid()", "SELECT * FROM person:uuid()"));
+		assert!(!check("RETURN 1; RETURN 2", "RETURN 1"));
+	}
+
+	#[test]
+	fn deterministic() {
+		let q = parse("SELECT * FROM person WHERE age > 18").unwrap();
+		assert_eq!(fingerprint(&q), fingerprint(&q.clone()));
+		assert_ne!(fingerprint(&q), 0);
+	}
+}
@@ -26,6 +26,7 @@ use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::fmt;
+use std::sync::Arc;
 
 use super::error::expected;
 use super::util::delimited_list0;
@@ -109,6 +110,8 @@ impl Function {
 			Self::Normal(f, _) if f == "math::mean" => true,
 			Self::Normal(f, _) if f == "math::min" => true,
 			Self::Normal(f, _) if f == "math::sum" => true,
+			Self::Normal(f, _) if f == "math::sum_state" => true,
+			Self::Normal(f, _) if f == "math::tdigest_state" => true,
 			Self::Normal(f, _) if f == "time::max" => true,
 			Self::Normal(f, _) if f == "time::min" => true,
 			_ => false,
@@ -123,10 +126,13 @@ impl Function {
 			Self::Normal(f, _) if f == "array::last" => true,
 			Self::Normal(f, _) if f == "count" => true,
 			Self::Normal(f, _) if f == "math::bottom" => true,
+			Self::Normal(f, _) if f == "math::count_state" => true,
+			Self::Normal(f, _) if f == "math::hll_state" => true,
 			Self::Normal(f, _) if f == "math::interquartile" => true,
 			Self::Normal(f, _) if f == "math::max" => true,
 			Self::Normal(f, _) if f == "math::mean" => true,
 			Self::Normal(f, _) if f == "math::median" => true,
+			Self::Normal(f, _) if f == "math::merge_state" => true,
 			Self::Normal(f, _) if f == "math::midhinge" => true,
 			Self::Normal(f, _) if f == "math::min" => true,
 			Self::Normal(f, _) if f == "math::mode" => true,
@@ -136,6 +142,8 @@ impl Function {
 			Self::Normal(f, _) if f == "math::spread" => true,
 			Self::Normal(f, _) if f == "math::stddev" => true,
 			Self::Normal(f, _) if f == "math::sum" => true,
+			Self::Normal(f, _) if f == "math::sum_state" => true,
+			Self::Normal(f, _) if f == "math::tdigest_state" => true,
 			Self::Normal(f, _) if f == "math::top" => true,
 			Self::Normal(f, _) if f == "math::trimean" => true,
 			Self::Normal(f, _) if f == "math::variance" => true,
@@ -219,8 +227,17 @@ impl Function {
 				for (val, (name, kind)) in a.into_iter().zip(&val.args) {
 					ctx.add_value(name.to_raw(), val.coerce_to(kind)?);
 				}
+				// Functions with definer rights check the current permissions of their definer,
+				// rather than those of the invoker
+				let opt = match (val.definer, &val.auth) {
+					(true, Some(auth)) => {
+						let auth = auth.current(&mut *txn.lock().await).await?;
+						opt.new_with_perms(true).with_auth(Arc::new(auth))
+					}
+					_ => opt.clone(),
+				};
 				// Run the custom function
-				val.block.compute(&ctx, opt, txn, doc).await
+				val.block.compute(&ctx, &opt, txn, doc).await
 			}
 			#[allow(unused_variables)]
 			Self::Script(s, x) => {
@@ -1,5 +1,6 @@
 #![allow(clippy::derived_hash_with_manual_eq)]
 
+use crate::err::Error;
 use crate::sql::array::Array;
 use crate::sql::comment::mightbespace;
 use crate::sql::common::{
@@ -10,7 +11,7 @@ use crate::sql::fmt::Fmt;
 use crate::sql::value::Value;
 use geo::algorithm::contains::Contains;
 use geo::algorithm::intersects::Intersects;
-use geo::{Coord, LineString, Point, Polygon};
+use geo::{Coord, Line, LineString, Point, Polygon, Rect, Triangle};
 use geo::{MultiLineString, MultiPoint, MultiPolygon};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
@@ -20,7 +21,7 @@ use nom::number::complete::double;
 use nom::sequence::preceded;
 use nom::sequence::{delimited, terminated};
 use revision::revisioned;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::cmp::Ordering;
 use std::iter::{once, FromIterator};
 use std::{fmt, hash};
@@ -29,10 +30,12 @@ use super::util::{delimited_list0, delimited_list1};
 
 pub(crate) const TOKEN: &str = "$surrealdb::private::sql::Geometry";
 
+pub(crate) const GEOJSON_TOKEN: &str = "$surrealdb::private::sql::GeoJson";
+
 const SINGLE: char = '\'';
 const DOUBLE: char = '\"';
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 #[serde(rename = "$surrealdb::private::sql::Geometry")]
 #[revisioned(revision = 1)]
 pub enum Geometry {
@@ -46,6 +49,55 @@ pub enum Geometry {
 	// Add new variants here
 }
 
+#[derive(Deserialize)]
+#[serde(rename = "$surrealdb::private::sql::Geometry")]
+enum RawGeometry {
+	Point(Point<f64>),
+	Line(LineString<f64>),
+	Polygon(Polygon<f64>),
+	MultiPoint(MultiPoint<f64>),
+	MultiLine(MultiLineString<f64>),
+	MultiPolygon(MultiPolygon<f64>),
+	Collection(Vec<Geometry>),
+}
+
+impl From<RawGeometry> for Geometry {
+	fn from(v: RawGeometry) -> Self {
+		match v {
+			RawGeometry::Point(v) => Self::Point(v),
+			RawGeometry::Line(v) => Self::Line(v),
+			RawGeometry::Polygon(v) => Self::Polygon(v),
+			RawGeometry::MultiPoint(v) => Self::MultiPoint(v),
+			RawGeometry::MultiLine(v) => Self::MultiLine(v),
+			RawGeometry::MultiPolygon(v) => Self::MultiPolygon(v),
+			RawGeometry::Collection(v) => Self::Collection(v),
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for Geometry {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		// Geometries nested in JSON responses are rendered as GeoJSON
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum Repr {
+			GeoJson(GeoJson),
+			Raw(RawGeometry),
+		}
+
+		match deserializer.is_human_readable() {
+			true => match Repr::deserialize(deserializer)? {
+				Repr::GeoJson(v) => Ok(v.into()),
+				Repr::Raw(v) => Ok(v.into()),
+			},
+			false => RawGeometry::deserialize(deserializer).map(Into::into),
+		}
+	}
+}
+
 impl Geometry {
 	/// Check if this is a Point
 	pub fn is_point(&self) -> bool {
@@ -312,6 +364,68 @@ impl From<Geometry> for geo::Geometry<f64> {
 	}
 }
 
+impl From<Line<f64>> for Geometry {
+	fn from(v: Line<f64>) -> Self {
+		Self::Line(LineString(vec![v.start, v.end]))
+	}
+}
+
+impl From<Rect<f64>> for Geometry {
+	fn from(v: Rect<f64>) -> Self {
+		Self::Polygon(v.to_polygon())
+	}
+}
+
+impl From<Triangle<f64>> for Geometry {
+	fn from(v: Triangle<f64>) -> Self {
+		Self::Polygon(v.to_polygon())
+	}
+}
+
+impl From<geo::Geometry<f64>> for Geometry {
+	fn from(v: geo::Geometry<f64>) -> Self {
+		match v {
+			geo::Geometry::Point(v) => v.into(),
+			geo::Geometry::Line(v) => v.into(),
+			geo::Geometry::LineString(v) => v.into(),
+			geo::Geometry::Polygon(v) => v.into(),
+			geo::Geometry::MultiPoint(v) => v.into(),
+			geo::Geometry::MultiLineString(v) => v.into(),
+			geo::Geometry::MultiPolygon(v) => v.into(),
+			geo::Geometry::GeometryCollection(v) => {
+				Self::Collection(v.0.into_iter().map(Geometry::from).collect())
+			}
+			geo::Geometry::Rect(v) => v.into(),
+			geo::Geometry::Triangle(v) => v.into(),
+		}
+	}
+}
+
+macro_rules! try_into_geo {
+	($($variant:ident => $ty:ident),*) => {
+		$(
+			impl TryFrom<Geometry> for $ty<f64> {
+				type Error = Error;
+				fn try_from(v: Geometry) -> Result<Self, Self::Error> {
+					match v {
+						Geometry::$variant(v) => Ok(v),
+						v => Err(Error::TryFrom(v.to_string(), stringify!($ty))),
+					}
+				}
+			}
+		)*
+	};
+}
+
+try_into_geo!(
+	Point => Point,
+	Line => LineString,
+	Polygon => Polygon,
+	MultiPoint => MultiPoint,
+	MultiLine => MultiLineString,
+	MultiPolygon => MultiPolygon
+);
+
 impl FromIterator<Geometry> for geo::Geometry<f64> {
 	fn from_iter<I: IntoIterator<Item = Geometry>>(iter: I) -> Self {
 		let mut c: Vec<geo::Geometry<f64>> = vec![];
@@ -322,6 +436,110 @@ impl FromIterator<Geometry> for geo::Geometry<f64> {
 	}
 }
 
+/// A geometry as it is represented in GeoJSON
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub(crate) enum GeoJson {
+	Point {
+		coordinates: (f64, f64),
+	},
+	LineString {
+		coordinates: Vec<(f64, f64)>,
+	},
+	Polygon {
+		coordinates: Vec<Vec<(f64, f64)>>,
+	},
+	MultiPoint {
+		coordinates: Vec<(f64, f64)>,
+	},
+	MultiLineString {
+		coordinates: Vec<Vec<(f64, f64)>>,
+	},
+	MultiPolygon {
+		coordinates: Vec<Vec<Vec<(f64, f64)>>>,
+	},
+	GeometryCollection {
+		geometries: Vec<GeoJson>,
+	},
+}
+
+impl GeoJson {
+	fn coordinates(v: &LineString<f64>) -> Vec<(f64, f64)> {
+		v.coords().map(|c| c.x_y()).collect()
+	}
+
+	fn rings(v: &Polygon<f64>) -> Vec<Vec<(f64, f64)>> {
+		once(v.exterior()).chain(v.interiors()).map(Self::coordinates).collect()
+	}
+
+	fn polygon(rings: Vec<Vec<(f64, f64)>>) -> Polygon<f64> {
+		let mut rings = rings.into_iter().map(LineString::from);
+		// The first ring is the exterior, and any others are holes in it
+		let exterior = rings.next().unwrap_or_else(|| LineString(vec![]));
+		Polygon::new(exterior, rings.collect())
+	}
+}
+
+impl From<&Geometry> for GeoJson {
+	fn from(v: &Geometry) -> Self {
+		match v {
+			Geometry::Point(v) => Self::Point {
+				coordinates: v.x_y(),
+			},
+			Geometry::Line(v) => Self::LineString {
+				coordinates: Self::coordinates(v),
+			},
+			Geometry::Polygon(v) => Self::Polygon {
+				coordinates: Self::rings(v),
+			},
+			Geometry::MultiPoint(v) => Self::MultiPoint {
+				coordinates: v.iter().map(|p| p.x_y()).collect(),
+			},
+			Geometry::MultiLine(v) => Self::MultiLineString {
+				coordinates: v.iter().map(Self::coordinates).collect(),
+			},
+			Geometry::MultiPolygon(v) => Self::MultiPolygon {
+				coordinates: v.iter().map(Self::rings).collect(),
+			},
+			Geometry::Collection(v) => Self::GeometryCollection {
+				geometries: v.iter().map(Self::from).collect(),
+			},
+		}
+	}
+}
+
+impl From<GeoJson> for Geometry {
+	fn from(v: GeoJson) -> Self {
+		match v {
+			GeoJson::Point {
+				coordinates,
+			} => Self::Point(coordinates.into()),
+			GeoJson::LineString {
+				coordinates,
+			} => Self::Line(coordinates.into()),
+			GeoJson::Polygon {
+				coordinates,
+			} => Self::Polygon(GeoJson::polygon(coordinates)),
+			GeoJson::MultiPoint {
+				coordinates,
+			} => Self::MultiPoint(coordinates.into()),
+			GeoJson::MultiLineString {
+				coordinates,
+			} => Self::MultiLine(MultiLineString(
+				coordinates.into_iter().map(LineString::from).collect(),
+			)),
+			GeoJson::MultiPolygon {
+				coordinates,
+			} => Self::MultiPolygon(MultiPolygon(
+				coordinates.into_iter().map(GeoJson::polygon).collect(),
+			)),
+			GeoJson::GeometryCollection {
+				geometries,
+			} => Self::Collection(geometries.into_iter().map(Geometry::from).collect()),
+		}
+	}
+}
+
 impl Geometry {
 	// -----------------------------------
 	// Value operations
@@ -1000,4 +1218,39 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("{ type: 'Polygon', coordinates: [[[-0.38314819, 51.37692386], [0.1785278, 51.37692386], [0.1785278, 51.6146057], [-0.38314819, 51.6146057], [-0.38314819, 51.37692386]], [[[-0.38314819, 51.37692386], [0.1785278, 51.37692386], [0.1785278, 51.6146057], [-0.38314819, 51.6146057], [-0.38314819, 51.37692386]]]] }", format!("{}", out));
 	}
+
+	#[test]
+	fn geo_conversions() {
+		let point = Point::new(-0.118092, 51.509865);
+		let geometry = Geometry::from(geo::Geometry::from(point));
+		assert_eq!(geometry, Geometry::Point(point));
+		assert_eq!(Point::try_from(geometry.clone()).unwrap(), point);
+		assert!(Polygon::<f64>::try_from(geometry).is_err());
+		let rect = Rect::new((0.0, 0.0), (1.0, 1.0));
+		let geometry = Geometry::from(geo::Geometry::Rect(rect));
+		assert_eq!(Polygon::try_from(geometry).unwrap(), rect.to_polygon());
+	}
+
+	#[test]
+	fn geojson_deserialize() {
+		let sql = r#"{
+			type: 'Polygon',
+			coordinates: [
+				[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]],
+				[[0.2, 0.2], [0.4, 0.2], [0.4, 0.4], [0.2, 0.2]]
+			]
+		}"#;
+		let out = geometry(sql).unwrap().1;
+		let json = serde_json::to_value(GeoJson::from(&out)).unwrap();
+		assert_eq!(json["type"], "Polygon");
+		assert_eq!(json["coordinates"][1][0], serde_json::json!([0.2, 0.2]));
+		let res: Geometry = serde_json::from_value(json).unwrap();
+		assert_eq!(res, out);
+		// The derived representation is still accepted
+		let res: Geometry = serde_json::from_value(serde_json::to_value(&out).unwrap()).unwrap();
+		assert_eq!(res, out);
+		let res: Geometry =
+			crate::sql::serde::deserialize(&crate::sql::serde::serialize(&out).unwrap()).unwrap();
+		assert_eq!(res, out);
+	}
 }
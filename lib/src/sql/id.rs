@@ -82,6 +82,12 @@ impl From<Uuid> for Id {
 	}
 }
 
+impl From<uuid::Uuid> for Id {
+	fn from(v: uuid::Uuid) -> Self {
+		Self::from(Uuid(v))
+	}
+}
+
 impl From<Strand> for Id {
 	fn from(v: Strand) -> Self {
 		Self::String(v.as_string())
@@ -134,6 +140,28 @@ impl From<Thing> for Id {
 	}
 }
 
+impl TryFrom<Id> for Uuid {
+	type Error = Error;
+	fn try_from(v: Id) -> Result<Self, Self::Error> {
+		match uuid::Uuid::try_from(v) {
+			Ok(v) => Ok(Self(v)),
+			Err(Error::TryFrom(v, _)) => Err(Error::TryFrom(v, "Uuid")),
+			Err(e) => Err(e),
+		}
+	}
+}
+
+impl TryFrom<Id> for uuid::Uuid {
+	type Error = Error;
+	fn try_from(v: Id) -> Result<Self, Self::Error> {
+		match &v {
+			Id::String(s) => uuid::Uuid::try_parse(s).ok(),
+			_ => None,
+		}
+		.ok_or_else(|| Error::TryFrom(v.to_string(), "uuid::Uuid"))
+	}
+}
+
 impl Id {
 	/// Generate a new random ID
 	pub fn rand() -> Self {
@@ -153,6 +181,13 @@ impl Id {
 	pub fn uuid() -> Self {
 		Self::String(Uuid::new_v4().to_raw())
 	}
+	/// Generate a new random V7 UUID
+	///
+	/// Unlike [`Id::uuid`], the generated IDs are sortable by the time
+	/// at which they were generated, to the nearest millisecond.
+	pub fn uuid_v7() -> Self {
+		Self::String(Uuid::new_v7().to_raw())
+	}
 	/// Convert the Id to a raw String
 	pub fn to_raw(&self) -> String {
 		match self {
@@ -272,4 +307,15 @@ mod tests {
 		assert_eq!(Id::from("100test"), out);
 		assert_eq!("100test", format!("{}", out));
 	}
+	#[test]
+	fn id_uuid() {
+		let uuid = uuid::Uuid::parse_str("018b2b7e-1d5e-7b0a-9b3c-0b9e4e1a2c3d").unwrap();
+		let out = Id::from(uuid);
+		assert_eq!(Id::from(Uuid::from(uuid)), out);
+		assert_eq!("⟨018b2b7e-1d5e-7b0a-9b3c-0b9e4e1a2c3d⟩", format!("{}", out));
+		assert_eq!(uuid::Uuid::try_from(out.clone()).unwrap(), uuid);
+		assert_eq!(Uuid::try_from(out).unwrap(), Uuid::from(uuid));
+		assert!(uuid::Uuid::try_from(Id::from("test")).is_err());
+		assert!(uuid::Uuid::try_from(Id::from(1)).is_err());
+	}
 }
@@ -1,4 +1,5 @@
 use crate::idx::ft::analyzer::Analyzers;
+use crate::idx::spatial::MAX_LEVEL;
 use crate::sql::comment::{mightbespace, shouldbespace};
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
@@ -8,6 +9,7 @@ use nom::branch::alt;
 use nom::bytes::complete::{tag, tag_no_case};
 use nom::character::complete::u16 as uint16;
 use nom::character::complete::u32 as uint32;
+use nom::character::complete::u8 as uint8;
 use nom::combinator::{cut, map, opt};
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
@@ -15,7 +17,7 @@ use std::fmt;
 use std::fmt::{Display, Formatter};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 4)]
 pub enum Index {
 	/// (Basic) non unique
 	#[default]
@@ -26,6 +28,15 @@ pub enum Index {
 	Search(SearchParams),
 	/// M-Tree index for distance based metrics
 	MTree(MTreeParams),
+	/// SimHash index for finding near-duplicate text
+	#[revision(start = 2)]
+	SimHash(SimHashParams),
+	/// HNSW index for approximate nearest neighbour search
+	#[revision(start = 3)]
+	Hnsw(HnswParams),
+	/// Spatial index for geometries which are inside or intersect a geometry
+	#[revision(start = 4)]
+	Spatial(SpatialParams),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
@@ -49,6 +60,32 @@ pub struct MTreeParams {
 	pub doc_ids_order: u32,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct SimHashParams {
+	/// The number of bits in which near-duplicate fingerprints may differ
+	pub distance: u8,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct HnswParams {
+	pub dimension: u16,
+	pub distance: Distance,
+	/// The number of neighbours each node is linked to on the upper layers
+	pub m: u16,
+	/// The number of candidates explored when a node is inserted
+	pub ef_construction: u16,
+	pub doc_ids_order: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct SpatialParams {
+	/// The finest level of the cells which geometries are stored under
+	pub level: u8,
+}
+
 #[derive(Clone, Default, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
 #[revisioned(revision = 1)]
 pub enum Distance {
@@ -102,12 +139,21 @@ impl Display for Index {
 					p.dimension, p.distance, p.capacity, p.doc_ids_order
 				)
 			}
+			Self::SimHash(p) => write!(f, "SIMHASH DISTANCE {}", p.distance),
+			Self::Hnsw(p) => {
+				write!(
+					f,
+					"HNSW DIMENSION {} DIST {} M {} EF_CONSTRUCTION {} DOC_IDS_ORDER {}",
+					p.dimension, p.distance, p.m, p.ef_construction, p.doc_ids_order
+				)
+			}
+			Self::Spatial(p) => write!(f, "SPATIAL LEVEL {}", p.level),
 		}
 	}
 }
 
 pub fn index(i: &str) -> IResult<&str, Index> {
-	alt((unique, search, mtree))(i)
+	alt((unique, search, mtree, simhash, hnsw, spatial))(i)
 }
 
 pub fn unique(i: &str) -> IResult<&str, Index> {
@@ -235,3 +281,73 @@ pub fn mtree(i: &str) -> IResult<&str, Index> {
 		))
 	})(i)
 }
+
+pub fn simhash(i: &str) -> IResult<&str, Index> {
+	let (i, _) = tag_no_case("SIMHASH")(i)?;
+	let (i, distance) = opt(|i| {
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("DISTANCE")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		cut(uint8)(i)
+	})(i)?;
+	Ok((
+		i,
+		Index::SimHash(SimHashParams {
+			distance: distance.unwrap_or(3),
+		}),
+	))
+}
+
+pub fn m(i: &str) -> IResult<&str, u16> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("M")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, m) = cut(uint16)(i)?;
+	Ok((i, m))
+}
+
+pub fn ef_construction(i: &str) -> IResult<&str, u16> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("EF_CONSTRUCTION")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, ef) = cut(uint16)(i)?;
+	Ok((i, ef))
+}
+
+pub fn hnsw(i: &str) -> IResult<&str, Index> {
+	let (i, _) = tag_no_case("HNSW")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(|i| {
+		let (i, dimension) = dimension(i)?;
+		let (i, distance) = opt(distance)(i)?;
+		let (i, m) = opt(m)(i)?;
+		let (i, ef_construction) = opt(ef_construction)(i)?;
+		let (i, doc_ids_order) = opt(doc_ids_order)(i)?;
+		Ok((
+			i,
+			Index::Hnsw(HnswParams {
+				dimension,
+				distance: distance.unwrap_or(Distance::Euclidean),
+				m: m.unwrap_or(12),
+				ef_construction: ef_construction.unwrap_or(150),
+				doc_ids_order: doc_ids_order.unwrap_or(100),
+			}),
+		))
+	})(i)
+}
+
+pub fn spatial(i: &str) -> IResult<&str, Index> {
+	let (i, _) = tag_no_case("SPATIAL")(i)?;
+	let (i, level) = opt(|i| {
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("LEVEL")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		cut(uint8)(i)
+	})(i)?;
+	Ok((
+		i,
+		Index::Spatial(SpatialParams {
+			level: level.unwrap_or(20).min(MAX_LEVEL),
+		}),
+	))
+}
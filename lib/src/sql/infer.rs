@@ -0,0 +1,288 @@
+//! Infers the kinds of values, to propose a schema for schemaless tables
+use crate::sql::geometry::Geometry;
+use crate::sql::ident::Ident;
+use crate::sql::idiom::Idiom;
+use crate::sql::kind::Kind;
+use crate::sql::number::Number;
+use crate::sql::object::Object;
+use crate::sql::part::Part;
+use crate::sql::statements::DefineFieldStatement;
+use crate::sql::table::Table;
+use crate::sql::value::Value;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+/// Infers the most specific kind which a value matches
+///
+/// The kinds of the items in an array are merged, so that `[1, 2.5]` is inferred
+/// as `array<number>`, and `[1, NONE]` as `array<option<int>>`.
+pub fn infer_kind(v: &Value) -> Kind {
+	match v {
+		Value::None => Kind::Option(Box::new(Kind::Any)),
+		Value::Null => Kind::Null,
+		Value::Bool(_) => Kind::Bool,
+		Value::Number(Number::Int(_)) => Kind::Int,
+		Value::Number(Number::Float(_)) => Kind::Float,
+		Value::Number(Number::Decimal(_)) => Kind::Decimal,
+		Value::Strand(_) => Kind::String,
+		Value::Duration(_) => Kind::Duration,
+		Value::Datetime(_) => Kind::Datetime,
+		Value::Uuid(_) => Kind::Uuid,
+		Value::Array(v) => Kind::Array(Box::new(Inferred::of(v.iter()).kind()), None),
+		Value::Object(_) => Kind::Object,
+		Value::Geometry(v) => Kind::Geometry(vec![geometry(v).to_owned()]),
+		Value::Bytes(_) => Kind::Bytes,
+		Value::Thing(v) => Kind::Record(vec![Table(v.tb.clone())]),
+		_ => Kind::Any,
+	}
+}
+
+/// Proposes field definitions for a table, from a sample of its records
+///
+/// Every field found in the records is defined, including the fields of nested
+/// objects, and of the objects in arrays. A field which is missing from some of
+/// the records is defined as optional.
+pub(crate) fn infer_fields(table: &str, records: &[Value]) -> Vec<DefineFieldStatement> {
+	let mut schema = Schema::default();
+	for record in records {
+		if let Value::Object(v) = record {
+			schema.object(&Idiom::default(), v);
+		}
+	}
+	schema
+		.fields
+		.into_values()
+		.map(|field| {
+			let count = schema.objects.get(&field.parent).copied().unwrap_or_default();
+			let mut kind = field.inferred.kind();
+			if field.count < count && !matches!(kind, Kind::Option(_) | Kind::Any) {
+				kind = Kind::Option(Box::new(kind));
+			}
+			DefineFieldStatement {
+				name: field.name,
+				what: Ident(table.to_owned()),
+				kind: Some(kind),
+				..Default::default()
+			}
+		})
+		.collect()
+}
+
+/// The fields found in a sample of records
+#[derive(Default)]
+struct Schema {
+	/// The fields, keyed by their path
+	fields: BTreeMap<String, Field>,
+	/// The number of objects found at each path
+	objects: HashMap<String, usize>,
+}
+
+struct Field {
+	name: Idiom,
+	/// The path of the object which contains the field
+	parent: String,
+	/// The number of objects which contain the field
+	count: usize,
+	inferred: Inferred,
+}
+
+impl Schema {
+	fn object(&mut self, path: &Idiom, v: &Object) {
+		let parent = path.to_string();
+		*self.objects.entry(parent.clone()).or_default() += 1;
+		for (key, v) in v.iter() {
+			// The record id is defined with the table
+			if path.is_empty() && key == "id" {
+				continue;
+			}
+			let name = path.clone().push(Part::Field(Ident(key.to_owned())));
+			let field = self.fields.entry(name.to_string()).or_insert_with(|| Field {
+				name: name.clone(),
+				parent: parent.clone(),
+				count: 0,
+				inferred: Inferred::default(),
+			});
+			field.count += 1;
+			field.inferred.add(v);
+			match v {
+				Value::Object(v) => self.object(&name, v),
+				Value::Array(v) => {
+					let name = name.push(Part::All);
+					for v in v.iter() {
+						if let Value::Object(v) = v {
+							self.object(&name, v);
+						}
+					}
+				}
+				_ => {}
+			}
+		}
+	}
+}
+
+/// The kinds inferred from a number of values
+#[derive(Default)]
+struct Inferred {
+	kind: Option<Kind>,
+	/// Whether any of the values was NONE
+	optional: bool,
+}
+
+impl Inferred {
+	fn of<'a>(values: impl Iterator<Item = &'a Value>) -> Self {
+		let mut inferred = Self::default();
+		for v in values {
+			inferred.add(v);
+		}
+		inferred
+	}
+
+	fn add(&mut self, v: &Value) {
+		if v.is_none() {
+			self.optional = true;
+			return;
+		}
+		let kind = infer_kind(v);
+		self.kind = Some(match self.kind.take() {
+			Some(k) => merge(k, kind),
+			None => kind,
+		});
+	}
+
+	fn kind(self) -> Kind {
+		match self.kind {
+			None => Kind::Any,
+			Some(k @ (Kind::Any | Kind::Option(_))) => k,
+			Some(k) if self.optional => Kind::Option(Box::new(k)),
+			Some(k) => k,
+		}
+	}
+}
+
+/// Merges two kinds into a kind which matches the values of both
+fn merge(a: Kind, b: Kind) -> Kind {
+	match (a, b) {
+		(a, b) if a == b => a,
+		(Kind::Any, _) | (_, Kind::Any) => Kind::Any,
+		(Kind::Option(a), Kind::Option(b)) => Kind::Option(Box::new(merge(*a, *b))),
+		(Kind::Option(a), b) | (b, Kind::Option(a)) => Kind::Option(Box::new(merge(*a, b))),
+		(a, b) => {
+			let mut kinds = Vec::new();
+			for k in either(a).into_iter().chain(either(b)) {
+				match kinds.iter().position(|v| combine(v, &k).is_some()) {
+					Some(i) => kinds[i] = combine(&kinds[i], &k).unwrap_or(k),
+					None => kinds.push(k),
+				}
+			}
+			match kinds.len() {
+				1 => kinds.remove(0),
+				_ => Kind::Either(kinds),
+			}
+		}
+	}
+}
+
+/// Combines two kinds of the same sort, such as two numeric kinds
+fn combine(a: &Kind, b: &Kind) -> Option<Kind> {
+	match (a, b) {
+		(a, b) if a == b => Some(a.clone()),
+		(
+			Kind::Int | Kind::Float | Kind::Decimal | Kind::Number,
+			Kind::Int | Kind::Float | Kind::Decimal | Kind::Number,
+		) => Some(Kind::Number),
+		(Kind::Record(a), Kind::Record(b)) => Some(Kind::Record(union(a, b))),
+		(Kind::Geometry(a), Kind::Geometry(b)) => Some(Kind::Geometry(union(a, b))),
+		(Kind::Array(a, _), Kind::Array(b, _)) => {
+			Some(Kind::Array(Box::new(merge(*a.clone(), *b.clone())), None))
+		}
+		_ => None,
+	}
+}
+
+/// Joins two lists of alternatives, where an empty list allows anything
+fn union<T: Clone + PartialEq>(a: &[T], b: &[T]) -> Vec<T> {
+	if a.is_empty() || b.is_empty() {
+		return Vec::new();
+	}
+	let mut v = a.to_vec();
+	for x in b {
+		if !v.contains(x) {
+			v.push(x.clone());
+		}
+	}
+	v
+}
+
+fn either(k: Kind) -> Vec<Kind> {
+	match k {
+		Kind::Either(v) => v,
+		k => vec![k],
+	}
+}
+
+fn geometry(v: &Geometry) -> &'static str {
+	match v {
+		Geometry::Point(_) => "point",
+		Geometry::Line(_) => "line",
+		Geometry::Polygon(_) => "polygon",
+		Geometry::MultiPoint(_) => "multipoint",
+		Geometry::MultiLine(_) => "multiline",
+		Geometry::MultiPolygon(_) => "multipolygon",
+		Geometry::Collection(_) => "collection",
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sql::test::Parse;
+
+	fn kind(v: &str) -> String {
+		infer_kind(&Value::parse(v)).to_string()
+	}
+
+	#[test]
+	fn infer_simple() {
+		assert_eq!(kind("true"), "bool");
+		assert_eq!(kind("1"), "int");
+		assert_eq!(kind("1.5"), "float");
+		assert_eq!(kind("'text'"), "string");
+		assert_eq!(kind("d'2023-01-01T00:00:00Z'"), "datetime");
+		assert_eq!(kind("1h"), "duration");
+		assert_eq!(kind("person:tobie"), "record<person>");
+		assert_eq!(kind("{ a: 1 }"), "object");
+		assert_eq!(kind("(1.0, 2.0)"), "geometry<point>");
+	}
+
+	#[test]
+	fn infer_arrays() {
+		assert_eq!(kind("[]"), "array");
+		assert_eq!(kind("[1, 2]"), "array<int>");
+		assert_eq!(kind("[1, 2.5]"), "array<number>");
+		assert_eq!(kind("[1, NONE]"), "array<option<int>>");
+		assert_eq!(kind("[1, 'a', 2.5]"), "array<number | string>");
+		assert_eq!(kind("[person:a, user:b]"), "array<record<person | user>>");
+		assert_eq!(kind("[[1], ['a']]"), "array<array<int | string>>");
+	}
+
+	#[test]
+	fn infer_fields_from_records() {
+		let records = vec![
+			Value::parse("{ id: person:1, name: 'Tobie', age: 33, address: { city: 'London' } }"),
+			Value::parse("{ id: person:2, name: 'Jaime', age: 32.5, tags: [{ name: 'a' }] }"),
+		];
+		let fields: Vec<String> =
+			infer_fields("person", &records).into_iter().map(|v| v.to_string()).collect();
+		assert_eq!(
+			fields,
+			vec![
+				"DEFINE FIELD address ON person TYPE option<object>",
+				"DEFINE FIELD address.city ON person TYPE string",
+				"DEFINE FIELD age ON person TYPE number",
+				"DEFINE FIELD name ON person TYPE string",
+				"DEFINE FIELD tags ON person TYPE option<array<object>>",
+				"DEFINE FIELD tags[*].name ON person TYPE string",
+			]
+		);
+	}
+}
@@ -0,0 +1,168 @@
+//! Conversions between values and JSON, which keep the types which JSON has no place for
+//!
+//! Datetimes, record ids, uuids, durations, decimals, bytes and geometries are written as
+//! objects with a single key which names their type, such as `{ "$datetime": "..." }`, so
+//! that they are the same values when they are read back. Anything else is plain JSON, and
+//! other services can read it without knowing about the tags at all.
+use crate::sql::geometry::GeoJson;
+use crate::sql::{
+	Array, Bytes, Datetime, Duration, Geometry, Number, Object, Strand, Thing, Uuid, Value,
+};
+use base64_lib::engine::general_purpose::STANDARD_NO_PAD;
+use base64_lib::Engine;
+use rust_decimal::Decimal;
+use serde_json::{Map, Value as Json};
+use std::str::FromStr;
+
+const BYTES: &str = "$bytes";
+const DATETIME: &str = "$datetime";
+const DECIMAL: &str = "$decimal";
+const DURATION: &str = "$duration";
+const GEOMETRY: &str = "$geometry";
+const THING: &str = "$thing";
+const UUID: &str = "$uuid";
+
+/// Converts a JSON value into a value, reading the types which were tagged by [`value_to_json`]
+///
+/// Objects which only look like a tag, but whose contents are not valid for it, are kept as
+/// objects.
+///
+/// ```
+/// use surrealdb::sql;
+///
+/// let json = serde_json::json!({ "author": { "$thing": "person:tobie" } });
+/// let value = sql::json_to_value(json);
+/// assert_eq!(value, sql::value("{ author: person:tobie }").unwrap());
+/// ```
+pub fn json_to_value(json: Json) -> Value {
+	match json {
+		Json::Null => Value::Null,
+		Json::Bool(v) => v.into(),
+		Json::Number(v) => match (v.as_i64(), v.as_f64()) {
+			(Some(v), _) => v.into(),
+			(None, Some(v)) => v.into(),
+			// Only integers which are too large for an i64 are left
+			(None, None) => Number::from_str(&v.to_string()).map_or(Value::Null, Value::from),
+		},
+		Json::String(v) => v.into(),
+		Json::Array(v) => Value::Array(Array(v.into_iter().map(json_to_value).collect())),
+		Json::Object(v) => match tagged(&v) {
+			Some(v) => v,
+			None => {
+				Value::Object(Object(v.into_iter().map(|(k, v)| (k, json_to_value(v))).collect()))
+			}
+		},
+	}
+}
+
+/// Reads an object which has been tagged with the type of its value
+fn tagged(object: &Map<String, Json>) -> Option<Value> {
+	let (tag, v) = match object.len() {
+		1 => object.iter().next()?,
+		_ => return None,
+	};
+	if tag == GEOMETRY {
+		return serde_json::from_value::<Geometry>(v.clone()).ok().map(Value::Geometry);
+	}
+	let v = v.as_str()?;
+	match tag.as_str() {
+		BYTES => STANDARD_NO_PAD.decode(v).ok().map(|v| Value::Bytes(Bytes::from(v))),
+		DATETIME => Datetime::try_from(v).ok().map(Value::Datetime),
+		DECIMAL => Decimal::from_str(v).ok().map(|v| Value::Number(Number::Decimal(v))),
+		DURATION => Duration::try_from(v).ok().map(Value::Duration),
+		THING => Thing::try_from(v).ok().map(Value::Thing),
+		UUID => Uuid::try_from(v).ok().map(Value::Uuid),
+		_ => None,
+	}
+}
+
+/// Converts a value into JSON, tagging the types which JSON can't represent
+///
+/// Floats which are not finite are converted to `null`, and values which have not been
+/// computed, such as params or subqueries, are converted to their SurrealQL text.
+///
+/// ```
+/// use surrealdb::sql;
+///
+/// let value = sql::value("{ author: person:tobie, age: 30 }").unwrap();
+/// let json = sql::value_to_json(&value);
+/// assert_eq!(json, serde_json::json!({ "author": { "$thing": "person:tobie" }, "age": 30 }));
+/// assert_eq!(sql::json_to_value(json), value);
+/// ```
+pub fn value_to_json(value: &Value) -> Json {
+	match value {
+		Value::None | Value::Null => Json::Null,
+		Value::Bool(v) => Json::Bool(*v),
+		Value::Number(Number::Int(v)) => Json::from(*v),
+		Value::Number(Number::Float(v)) => Json::from(*v),
+		Value::Number(Number::Decimal(v)) => tag(DECIMAL, v.to_string()),
+		Value::Strand(Strand(v)) => Json::String(v.clone()),
+		Value::Duration(v) => tag(DURATION, v.to_raw()),
+		Value::Datetime(v) => tag(DATETIME, v.to_raw()),
+		Value::Uuid(v) => tag(UUID, v.to_raw()),
+		Value::Array(v) => Json::Array(v.iter().map(value_to_json).collect()),
+		Value::Object(v) => {
+			Json::Object(v.iter().map(|(k, v)| (k.clone(), value_to_json(v))).collect())
+		}
+		Value::Geometry(v) => {
+			let v = serde_json::to_value(GeoJson::from(v)).unwrap_or_default();
+			Json::Object(Map::from_iter([(GEOMETRY.to_owned(), v)]))
+		}
+		Value::Bytes(v) => tag(BYTES, STANDARD_NO_PAD.encode(v.as_slice())),
+		Value::Thing(v) => tag(THING, v.to_raw()),
+		v => Json::String(v.to_string()),
+	}
+}
+
+fn tag(tag: &str, v: String) -> Json {
+	Json::Object(Map::from_iter([(tag.to_owned(), Json::String(v))]))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn tagged_types_round_trip() {
+		let value = Value::from(map! {
+			"at".to_owned() => Value::Datetime(Datetime::try_from("2023-01-01T10:00:00Z").unwrap()),
+			"by".to_owned() => Value::Thing(Thing::from(("person", "tobie"))),
+			"for".to_owned() => Value::Duration(Duration::try_from("1h30m").unwrap()),
+			"id".to_owned() => Value::Uuid(Uuid::try_from("e72bee20-f49b-11ec-b939-0242ac120002").unwrap()),
+			"price".to_owned() => Value::Number(Number::Decimal(Decimal::new(999, 2))),
+			"tags".to_owned() => crate::sql::value("['a', 1, 1.5, NULL]").unwrap(),
+			"where".to_owned() => Value::Geometry(Geometry::Point((-0.1, 51.5).into())),
+		});
+		let json = value_to_json(&value);
+		assert_eq!(
+			json,
+			json!({
+				"at": { "$datetime": "2023-01-01T10:00:00Z" },
+				"by": { "$thing": "person:tobie" },
+				"for": { "$duration": "1h30m" },
+				"id": { "$uuid": "e72bee20-f49b-11ec-b939-0242ac120002" },
+				"price": { "$decimal": "9.99" },
+				"tags": ["a", 1, 1.5, null],
+				"where": { "$geometry": { "type": "Point", "coordinates": [-0.1, 51.5] } },
+			})
+		);
+		assert_eq!(json_to_value(json), value);
+	}
+
+	#[test]
+	fn bytes_round_trip() {
+		let value = Value::Bytes(Bytes::from(vec![1, 2, 3]));
+		let json = value_to_json(&value);
+		assert_eq!(json, json!({ "$bytes": "AQID" }));
+		assert_eq!(json_to_value(json), value);
+	}
+
+	#[test]
+	fn invalid_tags_are_kept_as_objects() {
+		let value = json_to_value(json!({ "$datetime": "yesterday" }));
+		assert_eq!(value, Value::from(map! { "$datetime".to_owned() => Value::from("yesterday") }));
+		let value = json_to_value(json!({ "$datetime": "2023-01-01T10:00:00Z", "$thing": 1 }));
+		assert!(matches!(value, Value::Object(v) if v.len() == 2));
+	}
+}
@@ -14,6 +14,7 @@ pub(crate) mod cond;
 pub(crate) mod constant;
 pub(crate) mod data;
 pub(crate) mod datetime;
+pub(crate) mod diagnostics;
 pub(crate) mod dir;
 pub(crate) mod duration;
 pub(crate) mod edges;
@@ -25,6 +26,7 @@ pub(crate) mod expression;
 pub(crate) mod fetch;
 pub(crate) mod field;
 pub(crate) mod filter;
+pub(crate) mod fingerprint;
 pub(crate) mod fmt;
 pub(crate) mod function;
 pub(crate) mod future;
@@ -35,6 +37,8 @@ pub(crate) mod id;
 pub(crate) mod ident;
 pub(crate) mod idiom;
 pub(crate) mod index;
+pub(crate) mod infer;
+pub(crate) mod json;
 pub(crate) mod kind;
 pub(crate) mod language;
 pub(crate) mod limit;
@@ -55,6 +59,8 @@ pub(crate) mod permission;
 pub(crate) mod query;
 pub(crate) mod range;
 pub(crate) mod regex;
+pub(crate) mod retention;
+pub(crate) mod rollup;
 pub(crate) mod scoring;
 pub(crate) mod script;
 pub(crate) mod special;
@@ -65,12 +71,14 @@ pub(crate) mod strand;
 pub(crate) mod subquery;
 pub(crate) mod table;
 pub(crate) mod thing;
+pub(crate) mod tiering;
 pub(crate) mod timeout;
 pub(crate) mod tokenizer;
 pub(crate) mod util;
 pub(crate) mod uuid;
 pub(crate) mod value;
 pub(crate) mod version;
+pub(crate) mod versionstamp;
 pub(crate) mod view;
 pub(crate) mod with;
 
@@ -92,6 +100,9 @@ pub use self::cond::Cond;
 pub use self::constant::Constant;
 pub use self::data::Data;
 pub use self::datetime::Datetime;
+pub use self::diagnostics::diagnostics;
+pub use self::diagnostics::Diagnostic;
+pub use self::diagnostics::Severity;
 pub use self::dir::Dir;
 pub use self::duration::Duration;
 pub use self::edges::Edges;
@@ -102,6 +113,8 @@ pub use self::fetch::Fetch;
 pub use self::fetch::Fetchs;
 pub use self::field::Field;
 pub use self::field::Fields;
+pub use self::filter::Filter;
+pub use self::fingerprint::fingerprint;
 pub use self::function::Function;
 pub use self::future::Future;
 pub use self::geometry::Geometry;
@@ -113,7 +126,11 @@ pub use self::ident::Ident;
 pub use self::idiom::Idiom;
 pub use self::idiom::Idioms;
 pub use self::index::Index;
+pub use self::infer::infer_kind;
+pub use self::json::json_to_value;
+pub use self::json::value_to_json;
 pub use self::kind::Kind;
+pub use self::language::Language;
 pub use self::limit::Limit;
 pub use self::mock::Mock;
 pub use self::number::Number;
@@ -130,6 +147,8 @@ pub use self::permission::Permissions;
 pub use self::query::Query;
 pub use self::range::Range;
 pub use self::regex::Regex;
+pub use self::retention::Retention;
+pub use self::rollup::Rollup;
 pub use self::scoring::Scoring;
 pub use self::script::Script;
 pub use self::split::Split;
@@ -142,11 +161,14 @@ pub use self::subquery::Subquery;
 pub use self::table::Table;
 pub use self::table::Tables;
 pub use self::thing::Thing;
+pub use self::tiering::Tiering;
 pub use self::timeout::Timeout;
+pub use self::tokenizer::Tokenizer;
 pub use self::uuid::Uuid;
 pub use self::value::Value;
 pub use self::value::Values;
 pub use self::version::Version;
+pub use self::versionstamp::Versionstamp;
 pub use self::view::View;
 pub use self::with::With;
 
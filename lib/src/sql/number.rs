@@ -9,6 +9,7 @@ use nom::character::complete::i64;
 use nom::combinator::{opt, value};
 use nom::number::complete::recognize_float;
 use nom::Err;
+use num_bigint::BigInt;
 use revision::revisioned;
 use rust_decimal::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -50,7 +51,29 @@ macro_rules! from_prim_ints {
 	};
 }
 
-from_prim_ints!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+from_prim_ints!(i8, i16, i32, i64, isize, u8, u16, u32);
+
+macro_rules! from_wide_ints {
+	($($int: ty => $from_int: ident),*) => {
+		$(
+			impl From<$int> for Number {
+				/// Integers which don't fit in an `i64` are kept as decimals, and only those
+				/// which are too large for a decimal are kept as floats
+				fn from(i: $int) -> Self {
+					match i64::try_from(i) {
+						Ok(v) => Self::Int(v),
+						Err(_) => match Decimal::$from_int(i) {
+							Some(v) => Self::Decimal(v),
+							None => Self::Float(i as f64),
+						},
+					}
+				}
+			}
+		)*
+	};
+}
+
+from_wide_ints!(i128 => from_i128, u64 => from_u64, u128 => from_u128, usize => from_usize);
 
 impl From<f32> for Number {
 	fn from(f: f32) -> Self {
@@ -159,6 +182,38 @@ impl TryFrom<Number> for Decimal {
 	}
 }
 
+impl TryFrom<BigInt> for Number {
+	type Error = Error;
+	fn try_from(value: BigInt) -> Result<Self, Self::Error> {
+		if let Some(v) = value.to_i64() {
+			return Ok(Self::Int(v));
+		}
+		match value.to_i128().and_then(Decimal::from_i128) {
+			Some(v) => Ok(Self::Decimal(v)),
+			None => Err(Error::TryFrom(value.to_string(), "Number")),
+		}
+	}
+}
+
+impl TryFrom<Number> for BigInt {
+	type Error = Error;
+	fn try_from(value: Number) -> Result<Self, Self::Error> {
+		match value {
+			Number::Int(v) => Ok(BigInt::from(v)),
+			// Only whole numbers are converted, rather than truncating their fractional part
+			Number::Float(v) if v.fract() == 0.0 => match BigInt::from_f64(v) {
+				Some(v) => Ok(v),
+				None => Err(Error::TryFrom(value.to_string(), "BigInt")),
+			},
+			Number::Decimal(v) if v.fract().is_zero() => match v.to_i128() {
+				Some(v) => Ok(BigInt::from(v)),
+				None => Err(Error::TryFrom(value.to_string(), "BigInt")),
+			},
+			_ => Err(Error::TryFrom(value.to_string(), "BigInt")),
+		}
+	}
+}
+
 impl Display for Number {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
 		match self {
@@ -1022,4 +1077,31 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn number_from_wide_int() {
+		assert!(matches!(Number::from(i64::MAX as i128), Number::Int(i64::MAX)));
+		let large = i64::MAX as i128 + 1;
+		let number = Number::from(large);
+		assert!(matches!(number, Number::Decimal(..)));
+		assert_eq!(i128::try_from(number).unwrap(), large);
+		assert_eq!(Number::from(u64::MAX), Number::Decimal(Decimal::from(u64::MAX)));
+		assert!(matches!(Number::from(u128::MAX), Number::Float(..)));
+	}
+
+	#[test]
+	fn number_bigint() {
+		let large = BigInt::from(i64::MAX) * 1000;
+		let number = Number::try_from(large.clone()).unwrap();
+		assert!(matches!(number, Number::Decimal(..)));
+		assert_eq!(BigInt::try_from(number).unwrap(), large);
+		assert!(Number::try_from(BigInt::from(u128::MAX)).is_err());
+		assert_eq!(BigInt::try_from(Number::Float(1e20)).unwrap(), BigInt::from(10u128.pow(20)));
+		assert_eq!(
+			BigInt::try_from(Number::Decimal(Decimal::new(200, 2))).unwrap(),
+			BigInt::from(2)
+		);
+		assert!(BigInt::try_from(Number::Decimal(Decimal::new(150, 2))).is_err());
+		assert!(BigInt::try_from(Number::Float(f64::NAN)).is_err());
+	}
 }
@@ -96,7 +96,7 @@ pub(crate) mod depth {
 
 	/// Call when starting the parser to reset the recursion depth.
 	#[inline(never)]
-	pub(super) fn reset() {
+	pub(crate) fn reset() {
 		DEPTH.with(|cell| {
 			debug_assert_eq!(cell.get(), 0, "previous parsing stopped abruptly");
 			cell.set(0)
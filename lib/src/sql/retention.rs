@@ -0,0 +1,94 @@
+use crate::sql::comment::shouldbespace;
+use crate::sql::duration::{duration, Duration};
+use crate::sql::error::IResult;
+use crate::sql::idiom::{local, Idiom};
+use crate::sql::table::{table, Table};
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, opt};
+use nom::sequence::tuple;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// How long the records of a table are kept, before they are deleted or archived
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct Retention {
+	/// How long a record is kept for, after the datetime in its field
+	pub window: Duration,
+	/// The datetime field which the age of a record is measured from
+	pub field: Idiom,
+	/// The table which the records are moved to, instead of being deleted
+	pub archive: Option<Table>,
+}
+
+impl Display for Retention {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "RETENTION {} ON FIELD {}", self.window, self.field)?;
+		if let Some(ref v) = self.archive {
+			write!(f, " ARCHIVE TO {v}")?;
+		}
+		Ok(())
+	}
+}
+
+pub fn retention(i: &str) -> IResult<&str, Retention> {
+	let (i, _) = tag_no_case("RETENTION")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(|i| {
+		let (i, window) = duration(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("ON")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("FIELD")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, field) = local(i)?;
+		let (i, archive) = opt(tuple((
+			shouldbespace,
+			tag_no_case("ARCHIVE"),
+			shouldbespace,
+			tag_no_case("TO"),
+			shouldbespace,
+			cut(table),
+		)))(i)?;
+		Ok((
+			i,
+			Retention {
+				window,
+				field,
+				archive: archive.map(|(_, _, _, _, _, v)| v),
+			},
+		))
+	})(i)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn retention_delete() {
+		let sql = "RETENTION 90d ON FIELD created_at";
+		let res = retention(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.window, Duration::from_days(90));
+		assert_eq!(out.archive, None);
+	}
+
+	#[test]
+	fn retention_archive() {
+		let sql = "RETENTION 1w ON FIELD meta.created ARCHIVE TO event_archive";
+		let res = retention(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.archive, Some(Table::from("event_archive")));
+	}
+
+	#[test]
+	fn retention_missing_field() {
+		let sql = "RETENTION 90d";
+		let res = retention(sql);
+		assert!(res.is_err());
+	}
+}
@@ -0,0 +1,98 @@
+use crate::sql::comment::shouldbespace;
+use crate::sql::duration::{duration, Duration};
+use crate::sql::error::IResult;
+use crate::sql::idiom::{local, locals, Idiom, Idioms};
+use crate::sql::table::{table, Table};
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::{cut, opt};
+use nom::sequence::tuple;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// How the records of a table are aggregated, from the change feed of another table
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct Rollup {
+	/// The table whose change feed is aggregated
+	pub from: Table,
+	/// The length of the time buckets which the records are aggregated into
+	pub every: Duration,
+	/// The datetime field which decides the bucket of a record
+	pub field: Idiom,
+	/// The numeric fields which are summed in each bucket
+	pub sum: Idioms,
+}
+
+impl Display for Rollup {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "ROLLUP FROM {} EVERY {} ON FIELD {}", self.from, self.every, self.field)?;
+		if !self.sum.is_empty() {
+			write!(f, " SUM {}", self.sum)?;
+		}
+		Ok(())
+	}
+}
+
+pub fn rollup(i: &str) -> IResult<&str, Rollup> {
+	let (i, _) = tag_no_case("ROLLUP")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(|i| {
+		let (i, _) = tag_no_case("FROM")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, from) = table(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("EVERY")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, every) = duration(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("ON")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("FIELD")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, field) = local(i)?;
+		let (i, sum) =
+			opt(tuple((shouldbespace, tag_no_case("SUM"), shouldbespace, cut(locals))))(i)?;
+		Ok((
+			i,
+			Rollup {
+				from,
+				every,
+				field,
+				sum: sum.map(|(_, _, _, v)| v).unwrap_or_default(),
+			},
+		))
+	})(i)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rollup_count() {
+		let sql = "ROLLUP FROM metric EVERY 1h ON FIELD at";
+		let res = rollup(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.from, Table::from("metric"));
+		assert_eq!(out.every, Duration::from_hours(1));
+		assert!(out.sum.is_empty());
+	}
+
+	#[test]
+	fn rollup_sum() {
+		let sql = "ROLLUP FROM metric EVERY 1d ON FIELD meta.at SUM value, cpu.load";
+		let res = rollup(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.sum.len(), 2);
+	}
+
+	#[test]
+	fn rollup_missing_field() {
+		let sql = "ROLLUP FROM metric EVERY 1h";
+		let res = rollup(sql);
+		assert!(res.is_err());
+	}
+}
@@ -7,6 +7,7 @@ use crate::sql::common::colons;
 use crate::sql::error::IResult;
 use crate::sql::fmt::Fmt;
 use crate::sql::fmt::Pretty;
+use crate::sql::statements::alter::{alter, AlterStatement};
 use crate::sql::statements::analyze::{analyze, AnalyzeStatement};
 use crate::sql::statements::begin::{begin, BeginStatement};
 use crate::sql::statements::cancel::{cancel, CancelStatement};
@@ -25,6 +26,7 @@ use crate::sql::statements::output::{output, OutputStatement};
 use crate::sql::statements::r#break::{r#break, BreakStatement};
 use crate::sql::statements::r#continue::{r#continue, ContinueStatement};
 use crate::sql::statements::r#use::{r#use, UseStatement};
+use crate::sql::statements::rebuild::{rebuild, RebuildStatement};
 use crate::sql::statements::relate::{relate, RelateStatement};
 use crate::sql::statements::remove::{remove, RemoveStatement};
 use crate::sql::statements::select::{select, SelectStatement};
@@ -81,7 +83,7 @@ pub fn statements(i: &str) -> IResult<&str, Statements> {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 3)]
 pub enum Statement {
 	Value(Value),
 	Analyze(AnalyzeStatement),
@@ -110,6 +112,10 @@ pub enum Statement {
 	Update(UpdateStatement),
 	Throw(ThrowStatement),
 	Use(UseStatement),
+	#[revision(start = 2)]
+	Alter(AlterStatement),
+	#[revision(start = 3)]
+	Rebuild(RebuildStatement),
 }
 
 impl Statement {
@@ -129,6 +135,7 @@ impl Statement {
 	pub(crate) fn writeable(&self) -> bool {
 		match self {
 			Self::Value(v) => v.writeable(),
+			Self::Alter(_) => true,
 			Self::Analyze(_) => false,
 			Self::Break(_) => false,
 			Self::Continue(_) => false,
@@ -143,6 +150,7 @@ impl Statement {
 			Self::Live(_) => true,
 			Self::Output(v) => v.writeable(),
 			Self::Option(_) => false,
+			Self::Rebuild(_) => true,
 			Self::Relate(v) => v.writeable(),
 			Self::Remove(_) => true,
 			Self::Select(v) => v.writeable(),
@@ -164,6 +172,7 @@ impl Statement {
 		doc: Option<&CursorDoc<'_>>,
 	) -> Result<Value, Error> {
 		match self {
+			Self::Alter(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Analyze(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Break(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Continue(v) => v.compute(ctx, opt, txn, doc).await,
@@ -177,6 +186,7 @@ impl Statement {
 			Self::Kill(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Live(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Output(v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Rebuild(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Relate(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Remove(v) => v.compute(ctx, opt, txn, doc).await,
 			Self::Select(v) => v.compute(ctx, opt, txn, doc).await,
@@ -200,6 +210,7 @@ impl Display for Statement {
 	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
 		match self {
 			Self::Value(v) => write!(Pretty::from(f), "{v}"),
+			Self::Alter(v) => write!(Pretty::from(f), "{v}"),
 			Self::Analyze(v) => write!(Pretty::from(f), "{v}"),
 			Self::Begin(v) => write!(Pretty::from(f), "{v}"),
 			Self::Break(v) => write!(Pretty::from(f), "{v}"),
@@ -217,6 +228,7 @@ impl Display for Statement {
 			Self::Live(v) => write!(Pretty::from(f), "{v}"),
 			Self::Option(v) => write!(Pretty::from(f), "{v}"),
 			Self::Output(v) => write!(Pretty::from(f), "{v}"),
+			Self::Rebuild(v) => write!(Pretty::from(f), "{v}"),
 			Self::Relate(v) => write!(Pretty::from(f), "{v}"),
 			Self::Remove(v) => write!(Pretty::from(f), "{v}"),
 			Self::Select(v) => write!(Pretty::from(f), "{v}"),
@@ -235,6 +247,7 @@ pub fn statement(i: &str) -> IResult<&str, Statement> {
 		mightbespace,
 		alt((
 			alt((
+				map(alter, Statement::Alter),
 				map(analyze, Statement::Analyze),
 				map(begin, Statement::Begin),
 				map(r#break, Statement::Break),
@@ -254,6 +267,7 @@ pub fn statement(i: &str) -> IResult<&str, Statement> {
 				map(live, Statement::Live),
 				map(option, Statement::Option),
 				map(output, Statement::Output),
+				map(rebuild, Statement::Rebuild),
 				map(relate, Statement::Relate),
 				map(remove, Statement::Remove),
 				map(select, Statement::Select),
@@ -0,0 +1,184 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::Action;
+use crate::iam::Auth;
+use crate::iam::ResourceKind;
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::id::Id;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::idiom;
+use crate::sql::idiom::Idiom;
+use crate::sql::kind::{kind, Kind};
+use crate::sql::statements::DefineFieldStatement;
+use crate::sql::value::{value, Value};
+use derive::Store;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::cut;
+use nom::combinator::opt;
+use nom::sequence::tuple;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct AlterStatement {
+	pub what: Ident,
+	pub field: Idiom,
+	pub kind: Option<Kind>,
+	pub default: Value,
+	pub backfill: bool,
+}
+
+/// The progress of a field backfill, which is scheduled with `ALTER TABLE ... BACKFILL`
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
+#[revisioned(revision = 2)]
+pub struct Backfill {
+	/// The field which is being backfilled
+	pub field: Idiom,
+	/// The value which is set on records without the field
+	pub default: Value,
+	/// The id of the last record which has been processed
+	pub cursor: Option<Id>,
+	/// The number of records which have been processed
+	pub processed: u64,
+	/// The authentication of the user who altered the table, which the backfill runs with
+	#[revision(start = 2)]
+	pub auth: Option<Auth>,
+}
+
+impl AlterStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Field, &Base::Db)?;
+		// Only new fields can be added
+		let fd = self.field.to_string();
+		{
+			let mut run = txn.lock().await;
+			let key = crate::key::table::fd::new(opt.ns(), opt.db(), &self.what, &fd);
+			if run.exi(key).await? {
+				return Err(Error::FdAlreadyExists {
+					value: fd,
+				});
+			}
+		}
+		// Define the field
+		DefineFieldStatement {
+			name: self.field.clone(),
+			what: self.what.clone(),
+			kind: self.kind.clone(),
+			default: Some(self.default.clone()),
+			..Default::default()
+		}
+		.compute(ctx, opt, txn, doc)
+		.await?;
+		// Schedule the backfill of existing records
+		if self.backfill {
+			let mut run = txn.lock().await;
+			let key = crate::key::table::bf::new(opt.ns(), opt.db(), &self.what, &fd);
+			run.set(
+				key,
+				Backfill {
+					field: self.field.clone(),
+					default: self.default.clone(),
+					auth: Some(opt.auth.as_ref().clone()),
+					..Default::default()
+				},
+			)
+			.await?;
+			// Clear the cache
+			let key = crate::key::table::bf::prefix(opt.ns(), opt.db(), &self.what);
+			run.clr(key).await?;
+		}
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for AlterStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "ALTER TABLE {} ADD FIELD {}", self.what, self.field)?;
+		if let Some(ref v) = self.kind {
+			write!(f, " TYPE {v}")?
+		}
+		write!(f, " DEFAULT {}", self.default)?;
+		if self.backfill {
+			write!(f, " BACKFILL")?
+		}
+		Ok(())
+	}
+}
+
+pub fn alter(i: &str) -> IResult<&str, AlterStatement> {
+	let (i, _) = tag_no_case("ALTER")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("TABLE")(i)?;
+	cut(|i| {
+		let (i, _) = shouldbespace(i)?;
+		let (i, what) = ident(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = expect_tag_no_case("ADD")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = expect_tag_no_case("FIELD")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, field) = idiom::local(i)?;
+		let (i, kind) = opt(|i| {
+			let (i, _) = shouldbespace(i)?;
+			let (i, _) = tag_no_case("TYPE")(i)?;
+			let (i, _) = shouldbespace(i)?;
+			cut(kind)(i)
+		})(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = expect_tag_no_case("DEFAULT")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, default) = value(i)?;
+		let (i, backfill) = opt(tuple((shouldbespace, tag_no_case("BACKFILL"))))(i)?;
+		Ok((
+			i,
+			AlterStatement {
+				what,
+				field,
+				kind,
+				default,
+				backfill: backfill.is_some(),
+			},
+		))
+	})(i)
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn alter_table_add_field() {
+		let sql = "ALTER TABLE person ADD FIELD active TYPE bool DEFAULT true BACKFILL";
+		let res = alter(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert!(out.backfill);
+	}
+
+	#[test]
+	fn alter_table_add_field_without_backfill() {
+		let sql = "ALTER TABLE person ADD FIELD score DEFAULT 0";
+		let res = alter(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert!(!out.backfill);
+	}
+}
@@ -4,6 +4,7 @@ use crate::dbs::Transaction;
 use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::{Action, ResourceKind};
+use crate::idx::ft::analyzer::Analyzer;
 use crate::idx::ft::FtIndex;
 use crate::idx::trees::mtree::MTreeIndex;
 use crate::idx::trees::store::TreeStoreType;
@@ -52,7 +53,8 @@ impl AnalyzeStatement {
 				// Index operation dispatching
 				let value: Value = match &ix.index {
 					Index::Search(p) => {
-						let az = run.get_db_analyzer(opt.ns(), opt.db(), p.az.as_str()).await?;
+						let az =
+							Analyzer::load(&mut run, opt.ns(), opt.db(), p.az.as_str()).await?;
 						let ft =
 							FtIndex::new(&mut run, az, ikb, p, TreeStoreType::Traversal).await?;
 						ft.statistics(&mut run).await?.into()
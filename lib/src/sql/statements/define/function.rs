@@ -4,6 +4,7 @@ use crate::dbs::Transaction;
 use crate::doc::CursorDoc;
 use crate::err::Error;
 use crate::iam::Action;
+use crate::iam::Auth;
 use crate::iam::ResourceKind;
 use crate::sql::base::Base;
 use crate::sql::block::{block, Block};
@@ -29,19 +30,29 @@ use nom::bytes::complete::tag;
 use nom::bytes::complete::tag_no_case;
 use nom::character::complete::char;
 use nom::combinator::cut;
+use nom::combinator::map;
 use nom::multi::many0;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Write};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 3)]
 pub struct DefineFunctionStatement {
 	pub name: Ident,
 	pub args: Vec<(Ident, Kind)>,
 	pub block: Block,
 	pub comment: Option<Strand>,
 	pub permissions: Permission,
+	/// Whether the function runs with the rights of its definer, rather than of its invoker
+	#[revision(start = 2)]
+	pub definer: bool,
+	/// The user who defined the function, whose current rights a definer function runs with
+	///
+	/// This is only set once the function is stored, so it isn't part of the statement.
+	#[revision(start = 3)]
+	#[serde(skip)]
+	pub auth: Option<Auth>,
 }
 
 impl DefineFunctionStatement {
@@ -63,7 +74,12 @@ impl DefineFunctionStatement {
 		let key = crate::key::database::fc::new(opt.ns(), opt.db(), &self.name);
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
-		run.set(key, self).await?;
+		// A function with definer rights runs with the rights of the user who defined it
+		let fc = DefineFunctionStatement {
+			auth: self.definer.then(|| opt.auth.as_ref().clone()),
+			..self.clone()
+		};
+		run.set(key, fc).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -83,6 +99,9 @@ impl fmt::Display for DefineFunctionStatement {
 		if let Some(ref v) = self.comment {
 			write!(f, " COMMENT {v}")?
 		}
+		if self.definer {
+			f.write_str(" SECURITY DEFINER")?;
+		}
 		if !self.permissions.is_full() {
 			let _indent = if is_pretty() {
 				Some(pretty_indent())
@@ -119,7 +138,7 @@ pub fn function(i: &str) -> IResult<&str, DefineFunctionStatement> {
 	let (i, _) = mightbespace(i)?;
 	let (i, block) = block(i)?;
 	let (i, opts) = many0(function_opts)(i)?;
-	let (i, _) = expected("PERMISSIONS or COMMENT", ending::query)(i)?;
+	let (i, _) = expected("PERMISSIONS, SECURITY or COMMENT", ending::query)(i)?;
 	// Create the base statement
 	let mut res = DefineFunctionStatement {
 		name,
@@ -136,6 +155,9 @@ pub fn function(i: &str) -> IResult<&str, DefineFunctionStatement> {
 			DefineFunctionOption::Permissions(v) => {
 				res.permissions = v;
 			}
+			DefineFunctionOption::Definer(v) => {
+				res.definer = v;
+			}
 		}
 	}
 	// Return the statement
@@ -145,10 +167,11 @@ pub fn function(i: &str) -> IResult<&str, DefineFunctionStatement> {
 enum DefineFunctionOption {
 	Comment(Strand),
 	Permissions(Permission),
+	Definer(bool),
 }
 
 fn function_opts(i: &str) -> IResult<&str, DefineFunctionOption> {
-	alt((function_comment, function_permissions))(i)
+	alt((function_comment, function_permissions, function_security))(i)
 }
 
 fn function_comment(i: &str) -> IResult<&str, DefineFunctionOption> {
@@ -166,3 +189,14 @@ fn function_permissions(i: &str) -> IResult<&str, DefineFunctionOption> {
 	let (i, v) = cut(permission)(i)?;
 	Ok((i, DefineFunctionOption::Permissions(v)))
 }
+
+fn function_security(i: &str) -> IResult<&str, DefineFunctionOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("SECURITY")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(alt((
+		map(tag_no_case("DEFINER"), |_| true),
+		map(tag_no_case("INVOKER"), |_| false),
+	)))(i)?;
+	Ok((i, DefineFunctionOption::Definer(v)))
+}
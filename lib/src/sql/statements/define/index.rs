@@ -14,7 +14,7 @@ use crate::sql::ident::{ident, Ident};
 use crate::sql::idiom;
 use crate::sql::idiom::Idioms;
 use crate::sql::index;
-use crate::sql::index::Index;
+use crate::sql::index::{Index, SearchParams};
 use crate::sql::statements::UpdateStatement;
 use crate::sql::strand::{strand, Strand};
 use crate::sql::value::{Value, Values};
@@ -59,13 +59,31 @@ impl DefineIndexStatement {
 		run.add_ns(opt.ns(), opt.strict).await?;
 		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
 		run.add_tb(opt.ns(), opt.db(), &self.what, opt.strict).await?;
+		// Check if the existing index data can be kept
+		let keep = match run.get(key.clone()).await? {
+			Some(v) => self.only_changes_scoring(&v.into()),
+			None => false,
+		};
 		run.set(key, self).await?;
+		if keep {
+			// Clear the cache
+			let key = crate::key::table::ix::prefix(opt.ns(), opt.db(), &self.what);
+			run.clr(key).await?;
+			// Ok all good
+			return Ok(Value::None);
+		}
 		// Remove the index data
 		let key = crate::key::index::all::new(opt.ns(), opt.db(), &self.what, &self.name);
 		run.delp(key, u32::MAX).await?;
 		// Clear the cache
 		let key = crate::key::table::ix::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
+		// The index is rebuilt here, so any rebuild in progress is no longer needed
+		let key = crate::key::table::rb::new(opt.ns(), opt.db(), &self.what, &self.name);
+		run.del(key).await?;
+		// Clear the cache
+		let key = crate::key::table::rb::prefix(opt.ns(), opt.db(), &self.what);
+		run.clr(key).await?;
 		// Release the transaction
 		drop(run);
 		// Force queries to run
@@ -85,6 +103,24 @@ impl DefineIndexStatement {
 		// Ok all good
 		Ok(Value::None)
 	}
+
+	/// Checks whether this definition only changes the scoring of an existing
+	/// full-text index, such as its BM25 parameters. The scoring is applied
+	/// when the index is searched, so the index does not need to be rebuilt.
+	fn only_changes_scoring(&self, old: &DefineIndexStatement) -> bool {
+		match (&self.index, &old.index) {
+			(Index::Search(new), Index::Search(old_p)) => {
+				self.what == old.what
+					&& self.cols == old.cols
+					&& new.sc != old_p.sc
+					&& SearchParams {
+						sc: old_p.sc.clone(),
+						..new.clone()
+					} == *old_p
+			}
+			_ => false,
+		}
+	}
 }
 
 impl Display for DefineIndexStatement {
@@ -178,7 +214,9 @@ fn index_comment(i: &str) -> IResult<&str, DefineIndexOption> {
 mod tests {
 
 	use super::*;
-	use crate::sql::index::{Distance, MTreeParams, SearchParams};
+	use crate::sql::index::{
+		Distance, HnswParams, MTreeParams, SearchParams, SimHashParams, SpatialParams,
+	};
 	use crate::sql::Ident;
 	use crate::sql::Idiom;
 	use crate::sql::Idioms;
@@ -300,4 +338,73 @@ mod tests {
 			"DEFINE INDEX my_index ON my_table FIELDS my_col MTREE DIMENSION 4 DIST EUCLIDEAN CAPACITY 40 DOC_IDS_ORDER 100"
 		);
 	}
+
+	#[test]
+	fn check_create_simhash_index() {
+		let sql = "INDEX my_index ON TABLE my_table COLUMNS my_col SIMHASH";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx,
+			DefineIndexStatement {
+				name: Ident("my_index".to_string()),
+				what: Ident("my_table".to_string()),
+				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
+				index: Index::SimHash(SimHashParams {
+					distance: 3,
+				}),
+				comment: None,
+			}
+		);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS my_col SIMHASH DISTANCE 3"
+		);
+	}
+
+	#[test]
+	fn check_create_hnsw_index() {
+		let sql = "INDEX my_index ON TABLE my_table COLUMNS my_col HNSW DIMENSION 4 DIST MANHATTAN M 16 EF_CONSTRUCTION 200";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx,
+			DefineIndexStatement {
+				name: Ident("my_index".to_string()),
+				what: Ident("my_table".to_string()),
+				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
+				index: Index::Hnsw(HnswParams {
+					dimension: 4,
+					distance: Distance::Manhattan,
+					m: 16,
+					ef_construction: 200,
+					doc_ids_order: 100,
+				}),
+				comment: None,
+			}
+		);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS my_col HNSW DIMENSION 4 DIST MANHATTAN M 16 EF_CONSTRUCTION 200 DOC_IDS_ORDER 100"
+		);
+	}
+	#[test]
+	fn check_create_spatial_index() {
+		let sql = "INDEX my_index ON TABLE my_table COLUMNS my_col SPATIAL LEVEL 16";
+		let (_, idx) = index(sql).unwrap();
+		assert_eq!(
+			idx,
+			DefineIndexStatement {
+				name: Ident("my_index".to_string()),
+				what: Ident("my_table".to_string()),
+				cols: Idioms(vec![Idiom(vec![Part::Field(Ident("my_col".to_string()))])]),
+				index: Index::Spatial(SpatialParams {
+					level: 16,
+				}),
+				comment: None,
+			}
+		);
+		assert_eq!(
+			idx.to_string(),
+			"DEFINE INDEX my_index ON my_table FIELDS my_col SPATIAL LEVEL 16"
+		);
+	}
 }
@@ -9,6 +9,7 @@ mod namespace;
 mod param;
 mod scope;
 mod table;
+mod task;
 mod token;
 mod user;
 
@@ -24,6 +25,7 @@ use nom::bytes::complete::tag_no_case;
 pub use param::{param, DefineParamStatement};
 pub use scope::{scope, DefineScopeStatement};
 pub use table::{table, DefineTableStatement};
+pub use task::{task, DefineTaskStatement};
 pub use token::{token, DefineTokenStatement};
 pub use user::{user, DefineUserStatement};
 
@@ -43,7 +45,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub enum DefineStatement {
 	Namespace(DefineNamespaceStatement),
 	Database(DefineDatabaseStatement),
@@ -58,6 +60,8 @@ pub enum DefineStatement {
 	Index(DefineIndexStatement),
 	User(DefineUserStatement),
 	MlModel(DefineModelStatement),
+	#[revision(start = 2)]
+	Task(DefineTaskStatement),
 }
 
 impl DefineStatement {
@@ -87,6 +91,7 @@ impl DefineStatement {
 			Self::Analyzer(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::User(ref v) => v.compute(ctx, opt, txn, doc).await,
 			Self::MlModel(ref v) => v.compute(ctx, opt, txn, doc).await,
+			Self::Task(ref v) => v.compute(ctx, opt, txn, doc).await,
 		}
 	}
 }
@@ -107,6 +112,7 @@ impl Display for DefineStatement {
 			Self::Index(v) => Display::fmt(v, f),
 			Self::Analyzer(v) => Display::fmt(v, f),
 			Self::MlModel(v) => Display::fmt(v, f),
+			Self::Task(v) => Display::fmt(v, f),
 		}
 	}
 }
@@ -127,6 +133,7 @@ pub fn define(i: &str) -> IResult<&str, DefineStatement> {
 		map(field, DefineStatement::Field),
 		map(index, DefineStatement::Index),
 		map(analyzer, DefineStatement::Analyzer),
+		map(task, DefineStatement::Task),
 	))(i)
 }
 
@@ -8,6 +8,7 @@ use crate::iam::ResourceKind;
 use crate::sql::base::Base;
 use crate::sql::changefeed::{changefeed, ChangeFeed};
 use crate::sql::comment::shouldbespace;
+use crate::sql::duration::{duration, Duration};
 use crate::sql::ending;
 use crate::sql::error::expected;
 use crate::sql::error::IResult;
@@ -15,21 +16,30 @@ use crate::sql::fmt::is_pretty;
 use crate::sql::fmt::pretty_indent;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::permission::{permissions, Permissions};
+use crate::sql::retention::{retention, Retention};
+use crate::sql::rollup::{rollup, Rollup};
 use crate::sql::statements::UpdateStatement;
 use crate::sql::strand::{strand, Strand};
+use crate::sql::tiering::{tiering, Tiering};
 use crate::sql::value::{Value, Values};
 use crate::sql::view::{view, View};
 use derive::Store;
 use nom::branch::alt;
 use nom::bytes::complete::tag_no_case;
+use nom::character::complete::u64;
 use nom::combinator::cut;
+use nom::combinator::opt;
 use nom::multi::many0;
+use nom::sequence::preceded;
 use revision::revisioned;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Write};
 
+/// The size in bytes above which field values are shared, when no size is specified
+const DEDUPLICATE_SIZE: u64 = 1024;
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 8)]
 pub struct DefineTableStatement {
 	pub id: Option<u32>,
 	pub name: Ident,
@@ -39,6 +49,22 @@ pub struct DefineTableStatement {
 	pub permissions: Permissions,
 	pub changefeed: Option<ChangeFeed>,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub ttl: Option<Duration>,
+	#[revision(start = 3)]
+	pub retention: Option<Retention>,
+	#[revision(start = 4)]
+	pub tiering: Option<Tiering>,
+	#[revision(start = 5)]
+	pub rollup: Option<Rollup>,
+	#[revision(start = 6)]
+	pub temporary: bool,
+	/// Whether all the records of the table are kept in memory, on single-node storage engines
+	#[revision(start = 7)]
+	pub cache: bool,
+	/// The size in bytes above which field values are stored once, and shared by the records
+	#[revision(start = 8)]
+	pub dedup: Option<u64>,
 }
 
 impl DefineTableStatement {
@@ -53,6 +79,10 @@ impl DefineTableStatement {
 		opt.is_allowed(Action::Edit, ResourceKind::Table, &Base::Db)?;
 		// Claim transaction
 		let mut run = txn.lock().await;
+		// Distributed storage engines can not cache tables
+		if self.cache && !run.caches_tables() {
+			return Err(Error::TableCacheUnsupported(run.to_string()));
+		}
 		// Clear the cache
 		run.clear_cache();
 		// Process the statement
@@ -115,6 +145,15 @@ impl Display for DefineTableStatement {
 		if self.drop {
 			f.write_str(" DROP")?;
 		}
+		if self.temporary {
+			f.write_str(" TEMPORARY")?;
+		}
+		if self.cache {
+			f.write_str(" CACHE FULL")?;
+		}
+		if let Some(v) = self.dedup {
+			write!(f, " DEDUPLICATE {v}")?;
+		}
 		f.write_str(if self.full {
 			" SCHEMAFULL"
 		} else {
@@ -129,6 +168,18 @@ impl Display for DefineTableStatement {
 		if let Some(ref v) = self.changefeed {
 			write!(f, " {v}")?;
 		}
+		if let Some(ref v) = self.ttl {
+			write!(f, " TTL {v}")?;
+		}
+		if let Some(ref v) = self.retention {
+			write!(f, " {v}")?;
+		}
+		if let Some(ref v) = self.tiering {
+			write!(f, " {v}")?;
+		}
+		if let Some(ref v) = self.rollup {
+			write!(f, " {v}")?;
+		}
 		if !self.permissions.is_full() {
 			let _indent = if is_pretty() {
 				Some(pretty_indent())
@@ -148,7 +199,7 @@ pub fn table(i: &str) -> IResult<&str, DefineTableStatement> {
 	let (i, name) = cut(ident)(i)?;
 	let (i, opts) = many0(table_opts)(i)?;
 	let (i, _) = expected(
-		"DROP, SCHEMALESS, SCHEMAFUL(L), VIEW, CHANGEFEED, PERMISSIONS, or COMMENT",
+		"DROP, TEMPORARY, CACHE, DEDUPLICATE, SCHEMALESS, SCHEMAFUL(L), VIEW, CHANGEFEED, TTL, RETENTION, TIERING, ROLLUP, PERMISSIONS, or COMMENT",
 		ending::query,
 	)(i)?;
 	// Create the base statement
@@ -162,6 +213,15 @@ pub fn table(i: &str) -> IResult<&str, DefineTableStatement> {
 			DefineTableOption::Drop => {
 				res.drop = true;
 			}
+			DefineTableOption::Temporary => {
+				res.temporary = true;
+			}
+			DefineTableOption::Cache => {
+				res.cache = true;
+			}
+			DefineTableOption::Dedup(v) => {
+				res.dedup = Some(v);
+			}
 			DefineTableOption::Schemafull => {
 				res.full = true;
 			}
@@ -177,6 +237,18 @@ pub fn table(i: &str) -> IResult<&str, DefineTableStatement> {
 			DefineTableOption::ChangeFeed(v) => {
 				res.changefeed = Some(v);
 			}
+			DefineTableOption::Ttl(v) => {
+				res.ttl = Some(v);
+			}
+			DefineTableOption::Retention(v) => {
+				res.retention = Some(v);
+			}
+			DefineTableOption::Tiering(v) => {
+				res.tiering = Some(v);
+			}
+			DefineTableOption::Rollup(v) => {
+				res.rollup = Some(v);
+			}
 			DefineTableOption::Permissions(v) => {
 				res.permissions = v;
 			}
@@ -188,23 +260,37 @@ pub fn table(i: &str) -> IResult<&str, DefineTableStatement> {
 
 enum DefineTableOption {
 	Drop,
+	Temporary,
+	Cache,
+	Dedup(u64),
 	View(View),
 	Schemaless,
 	Schemafull,
 	Comment(Strand),
 	Permissions(Permissions),
 	ChangeFeed(ChangeFeed),
+	Ttl(Duration),
+	Retention(Retention),
+	Tiering(Tiering),
+	Rollup(Rollup),
 }
 
 fn table_opts(i: &str) -> IResult<&str, DefineTableOption> {
 	alt((
 		table_drop,
+		table_temporary,
+		table_cache,
+		table_dedup,
 		table_view,
 		table_comment,
 		table_schemaless,
 		table_schemafull,
 		table_permissions,
 		table_changefeed,
+		table_ttl,
+		table_retention,
+		table_tiering,
+		table_rollup,
 	))(i)
 }
 
@@ -214,12 +300,59 @@ fn table_drop(i: &str) -> IResult<&str, DefineTableOption> {
 	Ok((i, DefineTableOption::Drop))
 }
 
+fn table_temporary(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("TEMPORARY")(i)?;
+	Ok((i, DefineTableOption::Temporary))
+}
+
+fn table_cache(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("CACHE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = cut(tag_no_case("FULL"))(i)?;
+	Ok((i, DefineTableOption::Cache))
+}
+
+fn table_dedup(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("DEDUPLICATE")(i)?;
+	let (i, v) = opt(preceded(shouldbespace, u64))(i)?;
+	Ok((i, DefineTableOption::Dedup(v.unwrap_or(DEDUPLICATE_SIZE))))
+}
+
 fn table_changefeed(i: &str) -> IResult<&str, DefineTableOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, v) = changefeed(i)?;
 	Ok((i, DefineTableOption::ChangeFeed(v)))
 }
 
+fn table_ttl(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("TTL")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(duration)(i)?;
+	Ok((i, DefineTableOption::Ttl(v)))
+}
+
+fn table_retention(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = retention(i)?;
+	Ok((i, DefineTableOption::Retention(v)))
+}
+
+fn table_tiering(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = tiering(i)?;
+	Ok((i, DefineTableOption::Tiering(v)))
+}
+
+fn table_rollup(i: &str) -> IResult<&str, DefineTableOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = rollup(i)?;
+	Ok((i, DefineTableOption::Rollup(v)))
+}
+
 fn table_view(i: &str) -> IResult<&str, DefineTableOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, v) = view(i)?;
@@ -268,4 +401,72 @@ mod tests {
 		let deserialized = DefineTableStatement::try_from(&serialized).unwrap();
 		assert_eq!(out, deserialized);
 	}
+
+	#[test]
+	fn define_table_with_ttl() {
+		let sql = "TABLE session SCHEMALESS TTL 1d";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+		assert_eq!(out.ttl, Some(Duration::from_days(1)));
+	}
+
+	#[test]
+	fn define_table_with_retention() {
+		let sql =
+			"TABLE event SCHEMALESS RETENTION 90d ON FIELD created_at ARCHIVE TO event_archive";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+		assert_eq!(out.retention.unwrap().window, Duration::from_days(90));
+	}
+
+	#[test]
+	fn define_table_with_tiering() {
+		let sql = "TABLE event SCHEMALESS TIERING AFTER 30d ON FIELD updated_at";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+		assert_eq!(out.tiering.unwrap().after, Duration::from_days(30));
+	}
+
+	#[test]
+	fn define_table_with_rollup() {
+		let sql =
+			"TABLE metric_hourly SCHEMALESS ROLLUP FROM metric EVERY 1h ON FIELD at SUM value";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+		assert_eq!(out.rollup.unwrap().from.0, "metric");
+	}
+
+	#[test]
+	fn define_table_temporary() {
+		let sql = "TABLE staging TEMPORARY SCHEMALESS";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+		assert!(out.temporary);
+	}
+
+	#[test]
+	fn define_table_dedup() {
+		let sql = "TABLE template DEDUPLICATE 4096 SCHEMALESS";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+		assert_eq!(out.dedup, Some(4096));
+		let out = table("TABLE template DEDUPLICATE").unwrap().1;
+		assert_eq!(out.dedup, Some(DEDUPLICATE_SIZE));
+	}
+
+	#[test]
+	fn define_table_cache() {
+		let sql = "TABLE country CACHE FULL SCHEMALESS";
+		let res = table(sql);
+		let out = res.unwrap().1;
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+		assert!(out.cache);
+		assert!(table("TABLE country CACHE SCHEMALESS").is_err());
+	}
 }
@@ -0,0 +1,253 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::Action;
+use crate::iam::Auth;
+use crate::iam::ResourceKind;
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::duration::{duration, Duration};
+use crate::sql::ending;
+use crate::sql::error::expected;
+use crate::sql::error::IResult;
+use crate::sql::error::ParseError;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::strand::{strand, Strand};
+use crate::sql::value::{values, Value, Values};
+#[cfg(feature = "cron-schedules")]
+use chrono::{TimeZone, Utc};
+use derive::Store;
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::cut;
+use nom::multi::many0;
+use nom::Err;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+#[cfg(feature = "cron-schedules")]
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 2)]
+pub struct DefineTaskStatement {
+	pub name: Ident,
+	pub every: Duration,
+	pub then: Values,
+	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub cron: Option<Strand>,
+	/// The user who defined the task, whose current rights the task runs with
+	#[revision(start = 2)]
+	pub auth: Option<Auth>,
+}
+
+impl DefineTaskStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Task, &Base::Db)?;
+		// Check the schedule of the task
+		if let Some(cron) = &self.cron {
+			self.schedule(cron)?;
+		}
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Clear the cache
+		run.clear_cache();
+		// Process the statement
+		let key = crate::key::database::ta::new(opt.ns(), opt.db(), &self.name);
+		run.add_ns(opt.ns(), opt.strict).await?;
+		run.add_db(opt.ns(), opt.db(), opt.strict).await?;
+		// The task runs with the current permissions of the user who defined it
+		let task = DefineTaskStatement {
+			auth: Some(opt.auth.as_ref().clone()),
+			..self.clone()
+		};
+		run.set(key, task).await?;
+		// Restart the schedule of the task
+		let key = crate::key::database::tr::new(opt.ns(), opt.db(), &self.name);
+		run.del(key).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+
+	/// Returns the time of the next run of the task after the timestamp
+	pub(crate) fn next_run(&self, ts: u64) -> Result<u64, Error> {
+		match &self.cron {
+			#[cfg(feature = "cron-schedules")]
+			Some(cron) => {
+				let schedule = self.schedule(cron)?;
+				let next = Utc
+					.timestamp_opt(ts as i64, 0)
+					.single()
+					.and_then(|now| schedule.after(&now).next())
+					.ok_or_else(|| self.invalid(cron, "it has no runs after this time"))?;
+				Ok(next.timestamp() as u64)
+			}
+			#[cfg(not(feature = "cron-schedules"))]
+			Some(cron) => match self.schedule(cron)? {},
+			None => Ok(ts.saturating_add(self.every.secs())),
+		}
+	}
+
+	/// Parses a cron expression, which starts with a field for the seconds and is evaluated in UTC
+	#[cfg(feature = "cron-schedules")]
+	fn schedule(&self, cron: &Strand) -> Result<cron::Schedule, Error> {
+		cron::Schedule::from_str(cron).map_err(|e| self.invalid(cron, &e.to_string()))
+	}
+
+	/// Cron schedules can't be run without the `cron-schedules` feature
+	#[cfg(not(feature = "cron-schedules"))]
+	fn schedule(&self, cron: &Strand) -> Result<std::convert::Infallible, Error> {
+		Err(self.invalid(cron, "cron schedules are not enabled"))
+	}
+
+	/// The error for a schedule of this task which can't be run
+	fn invalid(&self, cron: &Strand, message: &str) -> Error {
+		Error::InvalidTaskSchedule {
+			name: self.name.to_raw(),
+			value: cron.as_str().to_owned(),
+			message: message.to_owned(),
+		}
+	}
+}
+
+impl Display for DefineTaskStatement {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "DEFINE TASK {}", self.name)?;
+		match self.cron {
+			Some(ref v) => write!(f, " CRON {v}")?,
+			None => write!(f, " EVERY {}", self.every)?,
+		}
+		write!(f, " THEN {}", self.then)?;
+		if let Some(ref v) = self.comment {
+			write!(f, " COMMENT {v}")?
+		}
+		Ok(())
+	}
+}
+
+pub fn task(i: &str) -> IResult<&str, DefineTaskStatement> {
+	let (i, _) = tag_no_case("TASK")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = cut(ident)(i)?;
+	let (i, opts) = many0(task_opts)(i)?;
+	let (i, _) = expected("EVERY, CRON, THEN, or COMMENT", ending::query)(i)?;
+	// Create the base statement
+	let mut res = DefineTaskStatement {
+		name,
+		..Default::default()
+	};
+	// Assign any defined options
+	for opt in opts {
+		match opt {
+			DefineTaskOption::Every(v) => {
+				res.every = v;
+				res.cron = None;
+			}
+			DefineTaskOption::Cron(v) => {
+				res.every = Duration::default();
+				res.cron = Some(v);
+			}
+			DefineTaskOption::Then(v) => {
+				res.then = v;
+			}
+			DefineTaskOption::Comment(v) => {
+				res.comment = Some(v);
+			}
+		}
+	}
+	// Check necessary options
+	if res.then.is_empty() {
+		return Err(Err::Failure(ParseError::Expected {
+			tried: i,
+			expected: "a THEN clause",
+		}));
+	}
+	// Return the statement
+	Ok((i, res))
+}
+
+enum DefineTaskOption {
+	Every(Duration),
+	Cron(Strand),
+	Then(Values),
+	Comment(Strand),
+}
+
+fn task_opts(i: &str) -> IResult<&str, DefineTaskOption> {
+	alt((task_every, task_cron, task_then, task_comment))(i)
+}
+
+fn task_every(i: &str) -> IResult<&str, DefineTaskOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("EVERY")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(duration)(i)?;
+	Ok((i, DefineTaskOption::Every(v)))
+}
+
+fn task_cron(i: &str) -> IResult<&str, DefineTaskOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("CRON")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(strand)(i)?;
+	Ok((i, DefineTaskOption::Cron(v)))
+}
+
+fn task_then(i: &str) -> IResult<&str, DefineTaskOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("THEN")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(values)(i)?;
+	Ok((i, DefineTaskOption::Then(v)))
+}
+
+fn task_comment(i: &str) -> IResult<&str, DefineTaskOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("COMMENT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(strand)(i)?;
+	Ok((i, DefineTaskOption::Comment(v)))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn define_task() {
+		let sql = "TASK cleanup EVERY 1h THEN (DELETE session WHERE expired = true)";
+		let res = task(sql);
+		let out = res.unwrap().1;
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+		assert_eq!(out.every, Duration::from_hours(1));
+	}
+
+	#[test]
+	#[cfg(feature = "cron-schedules")]
+	fn define_task_cron() {
+		let sql = "TASK report CRON '0 0 3 * * *' THEN (CREATE report)";
+		let out = task(sql).unwrap().1;
+		assert_eq!(format!("DEFINE {sql}"), format!("{}", out));
+		// 2023-11-14T22:13:20Z is followed by 2023-11-15T03:00:00Z
+		assert_eq!(out.next_run(1_700_000_000).unwrap(), 1_700_017_200);
+	}
+
+	#[test]
+	fn define_task_invalid() {
+		let out = task("TASK report CRON 'every day' THEN (CREATE report)").unwrap().1;
+		assert!(matches!(out.next_run(1_700_000_000), Err(Error::InvalidTaskSchedule { .. })));
+		assert!(task("TASK report EVERY 1h").is_err());
+	}
+}
@@ -26,13 +26,17 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub struct DefineTokenStatement {
 	pub name: Ident,
 	pub base: Base,
 	pub kind: Algorithm,
 	pub code: String,
 	pub comment: Option<Strand>,
+	#[revision(start = 2)]
+	pub issuer: Option<Strand>,
+	#[revision(start = 2)]
+	pub audience: Option<Strand>,
 }
 
 impl DefineTokenStatement {
@@ -102,6 +106,12 @@ impl Display for DefineTokenStatement {
 			self.kind,
 			quote_str(&self.code)
 		)?;
+		if let Some(ref v) = self.issuer {
+			write!(f, " ISSUER {v}")?
+		}
+		if let Some(ref v) = self.audience {
+			write!(f, " AUDIENCE {v}")?
+		}
 		if let Some(ref v) = self.comment {
 			write!(f, " COMMENT {v}")?
 		}
@@ -119,7 +129,7 @@ pub fn token(i: &str) -> IResult<&str, DefineTokenStatement> {
 		let (i, _) = shouldbespace(i)?;
 		let (i, base) = base_or_scope(i)?;
 		let (i, opts) = many0(token_opts)(i)?;
-		let (i, _) = expected("TYPE, VALUE, or COMMENT", ending::query)(i)?;
+		let (i, _) = expected("TYPE, VALUE, ISSUER, AUDIENCE, or COMMENT", ending::query)(i)?;
 		Ok((i, (name, base, opts)))
 	})(i)?;
 	// Create the base statement
@@ -137,6 +147,12 @@ pub fn token(i: &str) -> IResult<&str, DefineTokenStatement> {
 			DefineTokenOption::Value(v) => {
 				res.code = v;
 			}
+			DefineTokenOption::Issuer(v) => {
+				res.issuer = Some(v);
+			}
+			DefineTokenOption::Audience(v) => {
+				res.audience = Some(v);
+			}
 			DefineTokenOption::Comment(v) => {
 				res.comment = Some(v);
 			}
@@ -153,11 +169,13 @@ pub fn token(i: &str) -> IResult<&str, DefineTokenStatement> {
 enum DefineTokenOption {
 	Type(Algorithm),
 	Value(String),
+	Issuer(Strand),
+	Audience(Strand),
 	Comment(Strand),
 }
 
 fn token_opts(i: &str) -> IResult<&str, DefineTokenOption> {
-	alt((token_type, token_value, token_comment))(i)
+	alt((token_type, token_value, token_issuer, token_audience, token_comment))(i)
 }
 
 fn token_type(i: &str) -> IResult<&str, DefineTokenOption> {
@@ -176,6 +194,22 @@ fn token_value(i: &str) -> IResult<&str, DefineTokenOption> {
 	Ok((i, DefineTokenOption::Value(v)))
 }
 
+fn token_issuer(i: &str) -> IResult<&str, DefineTokenOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("ISSUER")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(strand)(i)?;
+	Ok((i, DefineTokenOption::Issuer(v)))
+}
+
+fn token_audience(i: &str) -> IResult<&str, DefineTokenOption> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("AUDIENCE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = cut(strand)(i)?;
+	Ok((i, DefineTokenOption::Audience(v)))
+}
+
 fn token_comment(i: &str) -> IResult<&str, DefineTokenOption> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, _) = tag_no_case("COMMENT")(i)?;
@@ -12,6 +12,7 @@ use crate::sql::error::ExplainResultExt;
 use crate::sql::error::IResult;
 use crate::sql::ident::{ident, Ident};
 use crate::sql::object::Object;
+use crate::sql::thing::Thing;
 use crate::sql::value::Value;
 use crate::sql::Base;
 use derive::Store;
@@ -138,6 +139,12 @@ impl InfoStatement {
 					tmp.insert(v.name.to_string(), v.to_string().into());
 				}
 				res.insert("tables".to_owned(), tmp.into());
+				// Process the tasks
+				let mut tmp = Object::default();
+				for v in run.all_db_tasks(opt.ns(), opt.db()).await?.iter() {
+					tmp.insert(v.name.to_string(), v.to_string().into());
+				}
+				res.insert("tasks".to_owned(), tmp.into());
 				// Process the analyzers
 				let mut tmp = Object::default();
 				for v in run.all_db_analyzers(opt.ns(), opt.db()).await?.iter() {
@@ -170,6 +177,20 @@ impl InfoStatement {
 				let mut run = txn.lock().await;
 				// Create the result set
 				let mut res = Object::default();
+				// Process the backfills
+				let mut tmp = Object::default();
+				for v in run.all_tb_backfills(opt.ns(), opt.db(), tb).await?.iter() {
+					let mut bf = Object::default();
+					// The cursor only tracks the indexed records once they have been counted
+					let cursor = match &v.cursor {
+						Some(id) if v.counted => Thing::from((tb.as_str(), id.clone())).into(),
+						None => Value::None,
+					};
+					bf.insert("cursor".to_owned(), cursor);
+					bf.insert("processed".to_owned(), v.processed.into());
+					tmp.insert(v.field.to_string(), bf.into());
+				}
+				res.insert("backfills".to_owned(), tmp.into());
 				// Process the events
 				let mut tmp = Object::default();
 				for v in run.all_tb_events(opt.ns(), opt.db(), tb).await?.iter() {
@@ -200,6 +221,21 @@ impl InfoStatement {
 					tmp.insert(v.id.to_raw(), v.to_string().into());
 				}
 				res.insert("lives".to_owned(), tmp.into());
+				// Process the index rebuilds
+				let mut tmp = Object::default();
+				for v in run.all_tb_rebuilds(opt.ns(), opt.db(), tb).await?.iter() {
+					let mut rb = Object::default();
+					// The cursor only tracks the indexed records once they have been counted
+					let cursor = match &v.cursor {
+						Some(id) if v.counted => Thing::from((tb.as_str(), id.clone())).into(),
+						None => Value::None,
+					};
+					rb.insert("cursor".to_owned(), cursor);
+					rb.insert("processed".to_owned(), v.processed.into());
+					rb.insert("total".to_owned(), v.total.into());
+					tmp.insert(v.index.to_raw(), rb.into());
+				}
+				res.insert("rebuilds".to_owned(), tmp.into());
 				// Ok all good
 				Value::from(res).ok()
 			}
@@ -1,3 +1,4 @@
+pub(crate) mod alter;
 pub(crate) mod analyze;
 pub(crate) mod begin;
 pub(crate) mod r#break;
@@ -15,6 +16,7 @@ pub(crate) mod kill;
 pub(crate) mod live;
 pub(crate) mod option;
 pub(crate) mod output;
+pub(crate) mod rebuild;
 pub(crate) mod relate;
 pub(crate) mod remove;
 pub(crate) mod select;
@@ -25,6 +27,7 @@ pub(crate) mod throw;
 pub(crate) mod update;
 pub(crate) mod r#use;
 
+pub use self::alter::AlterStatement;
 pub use self::begin::BeginStatement;
 pub use self::cancel::CancelStatement;
 pub use self::commit::CommitStatement;
@@ -41,6 +44,7 @@ pub use self::output::OutputStatement;
 pub use self::r#break::BreakStatement;
 pub use self::r#continue::ContinueStatement;
 pub use self::r#use::UseStatement;
+pub use self::rebuild::RebuildStatement;
 pub use self::relate::RelateStatement;
 pub use self::select::SelectStatement;
 pub use self::set::SetStatement;
@@ -58,6 +62,7 @@ pub use self::define::DefineParamStatement;
 pub use self::define::DefineScopeStatement;
 pub use self::define::DefineStatement;
 pub use self::define::DefineTableStatement;
+pub use self::define::DefineTaskStatement;
 pub use self::define::DefineTokenStatement;
 pub use self::define::DefineUserStatement;
 
@@ -71,5 +76,6 @@ pub use self::remove::RemoveParamStatement;
 pub use self::remove::RemoveScopeStatement;
 pub use self::remove::RemoveStatement;
 pub use self::remove::RemoveTableStatement;
+pub use self::remove::RemoveTaskStatement;
 pub use self::remove::RemoveTokenStatement;
 pub use self::remove::RemoveUserStatement;
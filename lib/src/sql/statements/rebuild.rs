@@ -0,0 +1,167 @@
+use crate::ctx::Context;
+use crate::dbs::Options;
+use crate::dbs::Transaction;
+use crate::doc::CursorDoc;
+use crate::err::Error;
+use crate::iam::Action;
+use crate::iam::ResourceKind;
+use crate::sql::base::Base;
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::expect_tag_no_case;
+use crate::sql::error::IResult;
+use crate::sql::id::Id;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::value::Value;
+use derive::Store;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::cut;
+use nom::combinator::map;
+use nom::combinator::opt;
+use nom::sequence::tuple;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub enum RebuildStatement {
+	Index(RebuildIndexStatement),
+}
+
+impl RebuildStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+		_doc: Option<&CursorDoc<'_>>,
+	) -> Result<Value, Error> {
+		match self {
+			Self::Index(v) => v.compute(ctx, opt, txn).await,
+		}
+	}
+}
+
+impl Display for RebuildStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Self::Index(v) => Display::fmt(v, f),
+		}
+	}
+}
+
+pub fn rebuild(i: &str) -> IResult<&str, RebuildStatement> {
+	let (i, _) = tag_no_case("REBUILD")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	map(index, RebuildStatement::Index)(i)
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[revisioned(revision = 1)]
+pub struct RebuildIndexStatement {
+	pub name: Ident,
+	pub what: Ident,
+}
+
+/// The progress of an index rebuild, which is scheduled with `REBUILD INDEX`
+///
+/// The records in the table are counted first, then indexed again. The entries of a unique
+/// or non-unique index are left in place while it is rebuilt, so that its constraint is still
+/// enforced, and the entries which no record produces any more are pruned at the end.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Store)]
+#[revisioned(revision = 1)]
+pub struct Rebuild {
+	/// The index which is being rebuilt
+	pub index: Ident,
+	/// Whether all the records in the table have been counted
+	pub counted: bool,
+	/// The id of the last record which has been counted, or processed once counted
+	pub cursor: Option<Id>,
+	/// The number of records which have been processed
+	pub processed: u64,
+	/// The number of records in the table, once they have been counted
+	pub total: u64,
+	/// The key from which the index entries are still to be pruned, once all the records
+	/// have been processed
+	pub pruned: Option<Vec<u8>>,
+}
+
+impl RebuildIndexStatement {
+	/// Process this type returning a computed simple Value
+	pub(crate) async fn compute(
+		&self,
+		_ctx: &Context<'_>,
+		opt: &Options,
+		txn: &Transaction,
+	) -> Result<Value, Error> {
+		// Allowed to run?
+		opt.is_allowed(Action::Edit, ResourceKind::Index, &Base::Db)?;
+		// Claim transaction
+		let mut run = txn.lock().await;
+		// Check that the index exists
+		run.get_and_cache_tb_index(opt.ns(), opt.db(), &self.what, &self.name).await?;
+		// Schedule the rebuild, restarting any which is in progress. The index data is
+		// left in place, and the records are counted and indexed in the background.
+		let key = crate::key::table::rb::new(opt.ns(), opt.db(), &self.what, &self.name);
+		run.set(
+			key,
+			Rebuild {
+				index: self.name.clone(),
+				..Default::default()
+			},
+		)
+		.await?;
+		// Clear the cache
+		let key = crate::key::table::rb::prefix(opt.ns(), opt.db(), &self.what);
+		run.clr(key).await?;
+		// Ok all good
+		Ok(Value::None)
+	}
+}
+
+impl Display for RebuildIndexStatement {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "REBUILD INDEX {} ON {}", self.name, self.what)
+	}
+}
+
+fn index(i: &str) -> IResult<&str, RebuildIndexStatement> {
+	let (i, _) = tag_no_case("INDEX")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = cut(ident)(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = expect_tag_no_case("ON")(i)?;
+	let (i, _) = opt(tuple((shouldbespace, tag_no_case("TABLE"))))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, what) = cut(ident)(i)?;
+	Ok((
+		i,
+		RebuildIndexStatement {
+			name,
+			what,
+		},
+	))
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn rebuild_index() {
+		let sql = "REBUILD INDEX uniq_email ON user";
+		let res = rebuild(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+	}
+
+	#[test]
+	fn rebuild_index_on_table() {
+		let sql = "REBUILD INDEX uniq_email ON TABLE user";
+		let res = rebuild(sql);
+		let out = res.unwrap().1;
+		assert_eq!("REBUILD INDEX uniq_email ON user", format!("{}", out));
+	}
+}
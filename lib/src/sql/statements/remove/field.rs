@@ -48,6 +48,12 @@ impl RemoveFieldStatement {
 		// Clear the cache
 		let key = crate::key::table::fd::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
+		// Delete any backfill of the field
+		let key = crate::key::table::bf::new(opt.ns(), opt.db(), &self.what, &fd);
+		run.del(key).await?;
+		// Clear the cache
+		let key = crate::key::table::bf::prefix(opt.ns(), opt.db(), &self.what);
+		run.clr(key).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
@@ -48,6 +48,12 @@ impl RemoveIndexStatement {
 		// Clear the cache
 		let key = crate::key::table::ix::prefix(opt.ns(), opt.db(), &self.what);
 		run.clr(key).await?;
+		// Delete any rebuild of the index
+		let key = crate::key::table::rb::new(opt.ns(), opt.db(), &self.what, &self.name);
+		run.del(key).await?;
+		// Clear the cache
+		let key = crate::key::table::rb::prefix(opt.ns(), opt.db(), &self.what);
+		run.clr(key).await?;
 		// Ok all good
 		Ok(Value::None)
 	}
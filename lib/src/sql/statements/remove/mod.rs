@@ -8,6 +8,7 @@ mod namespace;
 mod param;
 mod scope;
 mod table;
+mod task;
 mod token;
 mod user;
 
@@ -22,6 +23,7 @@ use nom::bytes::complete::tag_no_case;
 pub use param::{param, RemoveParamStatement};
 pub use scope::{scope, RemoveScopeStatement};
 pub use table::{table, RemoveTableStatement};
+pub use task::{task, RemoveTaskStatement};
 pub use token::{token, RemoveTokenStatement};
 pub use user::{user, RemoveUserStatement};
 
@@ -41,7 +43,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Store, Hash)]
-#[revisioned(revision = 1)]
+#[revisioned(revision = 2)]
 pub enum RemoveStatement {
 	Namespace(RemoveNamespaceStatement),
 	Database(RemoveDatabaseStatement),
@@ -55,6 +57,8 @@ pub enum RemoveStatement {
 	Field(RemoveFieldStatement),
 	Index(RemoveIndexStatement),
 	User(RemoveUserStatement),
+	#[revision(start = 2)]
+	Task(RemoveTaskStatement),
 }
 
 impl RemoveStatement {
@@ -83,6 +87,7 @@ impl RemoveStatement {
 			Self::Index(ref v) => v.compute(ctx, opt, txn).await,
 			Self::Analyzer(ref v) => v.compute(ctx, opt, txn).await,
 			Self::User(ref v) => v.compute(ctx, opt, txn).await,
+			Self::Task(ref v) => v.compute(ctx, opt, txn).await,
 		}
 	}
 }
@@ -102,6 +107,7 @@ impl Display for RemoveStatement {
 			Self::Index(v) => Display::fmt(v, f),
 			Self::Analyzer(v) => Display::fmt(v, f),
 			Self::User(v) => Display::fmt(v, f),
+			Self::Task(v) => Display::fmt(v, f),
 		}
 	}
 }
@@ -122,6 +128,7 @@ pub fn remove(i: &str) -> IResult<&str, RemoveStatement> {
 		map(index, RemoveStatement::Index),
 		map(analyzer, RemoveStatement::Analyzer),
 		map(user, RemoveStatement::User),
+		map(task, RemoveStatement::Task),
 	))(i)
 }
 
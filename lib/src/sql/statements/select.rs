@@ -91,8 +91,10 @@ impl SelectStatement {
 		opt.valid_for_db()?;
 		// Create a new iterator
 		let mut i = Iterator::new();
+		// Stop once the result is cut short, but don't cut the results of any subqueries
+		i.cap(opt.limit);
 		// Ensure futures are stored
-		let opt = &opt.new_with_futures(false).with_projections(true);
+		let opt = &opt.new_with_futures(false).with_projections(true).with_limit(None);
 		// Get a query planner
 		let mut planner = QueryPlanner::new(opt, &self.with, &self.cond);
 		// Loop over the select targets
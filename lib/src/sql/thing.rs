@@ -15,7 +15,7 @@ use nom::character::complete::char;
 use nom::combinator::value;
 use nom::sequence::delimited;
 use revision::revisioned;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::fmt;
 use std::str::FromStr;
 
@@ -23,7 +23,7 @@ use super::error::expected;
 
 pub(crate) const TOKEN: &str = "$surrealdb::private::sql::Thing";
 
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize, Store, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Store, Hash)]
 #[serde(rename = "$surrealdb::private::sql::Thing")]
 #[revisioned(revision = 1)]
 pub struct Thing {
@@ -31,6 +31,44 @@ pub struct Thing {
 	pub id: Id,
 }
 
+/// The record ID as it is stored, with its table and ID as separate fields
+#[derive(Deserialize)]
+#[serde(rename = "$surrealdb::private::sql::Thing")]
+struct RawThing {
+	tb: String,
+	id: Id,
+}
+
+impl<'de> Deserialize<'de> for Thing {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		// Record links nested in JSON responses are rendered as `table:id` strings
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum Repr {
+			Raw(RawThing),
+			Str(String),
+		}
+
+		let raw = match deserializer.is_human_readable() {
+			true => match Repr::deserialize(deserializer)? {
+				Repr::Raw(raw) => raw,
+				Repr::Str(s) => {
+					return Thing::try_from(s.as_str())
+						.map_err(|_| serde::de::Error::custom(format!("invalid record ID `{s}`")));
+				}
+			},
+			false => RawThing::deserialize(deserializer)?,
+		};
+		Ok(Self {
+			tb: raw.tb,
+			id: raw.id,
+		})
+	}
+}
+
 impl From<(&str, Id)> for Thing {
 	fn from((tb, id): (&str, Id)) -> Self {
 		Self {
@@ -93,6 +131,27 @@ impl TryFrom<&str> for Thing {
 }
 
 impl Thing {
+	/// Generate a new record ID in the table, with a random ID
+	pub fn random(tb: impl Into<String>) -> Self {
+		Self {
+			tb: tb.into(),
+			id: Id::rand(),
+		}
+	}
+	/// Generate a new record ID in the table, with a random ULID
+	pub fn ulid(tb: impl Into<String>) -> Self {
+		Self {
+			tb: tb.into(),
+			id: Id::ulid(),
+		}
+	}
+	/// Generate a new record ID in the table, with a random V7 UUID
+	pub fn uuid_v7(tb: impl Into<String>) -> Self {
+		Self {
+			tb: tb.into(),
+			id: Id::uuid_v7(),
+		}
+	}
 	/// Convert the Thing to a raw String
 	pub fn to_raw(&self) -> String {
 		self.to_string()
@@ -252,4 +311,36 @@ mod tests {
 			}
 		);
 	}
+
+	#[test]
+	fn thing_generated() {
+		let out = Thing::random("test");
+		assert_eq!(out.tb, "test");
+		assert!(matches!(out.id, Id::String(ref v) if v.len() == 20));
+		let one = Thing::ulid("test");
+		std::thread::sleep(std::time::Duration::from_millis(2));
+		let two = Thing::ulid("test");
+		assert!(one < two);
+		let one = Thing::uuid_v7("test");
+		std::thread::sleep(std::time::Duration::from_millis(2));
+		let two = Thing::uuid_v7("test");
+		assert!(one < two);
+		assert_eq!(thing(&one.to_string()).unwrap().1, one);
+	}
+
+	#[test]
+	fn thing_deserialize() {
+		let out = Thing::from(("test", "id"));
+		let res: Thing = serde_json::from_value(serde_json::json!(out)).unwrap();
+		assert_eq!(res, out);
+		let res: Thing = serde_json::from_str(r#""test:id""#).unwrap();
+		assert_eq!(res, out);
+		let res: Option<Vec<Thing>> = serde_json::from_str(r#"["test:id", "test:⟨id⟩"]"#).unwrap();
+		assert_eq!(res, Some(vec![out.clone(), out.clone()]));
+		let res = serde_json::from_str::<Thing>(r#""test""#);
+		assert!(res.is_err());
+		let bytes = crate::sql::serde::serialize(&out).unwrap();
+		let res: Thing = crate::sql::serde::deserialize(&bytes).unwrap();
+		assert_eq!(res, out);
+	}
 }
@@ -0,0 +1,69 @@
+use crate::sql::comment::shouldbespace;
+use crate::sql::duration::{duration, Duration};
+use crate::sql::error::IResult;
+use crate::sql::idiom::{local, Idiom};
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::cut;
+use revision::revisioned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// When the records of a table are moved to cold storage
+#[derive(Clone, Debug, Default, Eq, PartialEq, PartialOrd, Serialize, Deserialize, Hash)]
+#[revisioned(revision = 1)]
+pub struct Tiering {
+	/// How long a record stays in hot storage, after the datetime in its field
+	pub after: Duration,
+	/// The datetime field which the age of a record is measured from
+	pub field: Idiom,
+}
+
+impl Display for Tiering {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		write!(f, "TIERING AFTER {} ON FIELD {}", self.after, self.field)
+	}
+}
+
+pub fn tiering(i: &str) -> IResult<&str, Tiering> {
+	let (i, _) = tag_no_case("TIERING")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	cut(|i| {
+		let (i, _) = tag_no_case("AFTER")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, after) = duration(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("ON")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, _) = tag_no_case("FIELD")(i)?;
+		let (i, _) = shouldbespace(i)?;
+		let (i, field) = local(i)?;
+		Ok((
+			i,
+			Tiering {
+				after,
+				field,
+			},
+		))
+	})(i)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn tiering_after() {
+		let sql = "TIERING AFTER 30d ON FIELD updated_at";
+		let res = tiering(sql);
+		let out = res.unwrap().1;
+		assert_eq!(sql, format!("{}", out));
+		assert_eq!(out.after, Duration::from_days(30));
+	}
+
+	#[test]
+	fn tiering_missing_field() {
+		let sql = "TIERING AFTER 30d";
+		let res = tiering(sql);
+		assert!(res.is_err());
+	}
+}
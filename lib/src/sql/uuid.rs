@@ -93,6 +93,18 @@ impl Uuid {
 	pub fn new_v7() -> Self {
 		Self(uuid::Uuid::now_v7())
 	}
+	/// Generate a new V7 UUID
+	///
+	/// A ULID has the same layout as a V7 UUID, with a 48 bit millisecond
+	/// timestamp followed by random bits, so only the version and variant
+	/// bits need to be set.
+	#[cfg(not(uuid_unstable))]
+	pub fn new_v7() -> Self {
+		let mut bytes = ulid::Ulid::new().0.to_be_bytes();
+		bytes[6] = (bytes[6] & 0x0f) | 0x70;
+		bytes[8] = (bytes[8] & 0x3f) | 0x80;
+		Self(uuid::Uuid::from_bytes(bytes))
+	}
 	/// Convert the Uuid to a raw String
 	pub fn to_raw(&self) -> String {
 		self.0.to_string()
@@ -147,6 +159,16 @@ mod tests {
 
 	use super::*;
 
+	#[test]
+	fn uuid_new_v7() {
+		let one = Uuid::new_v7();
+		std::thread::sleep(std::time::Duration::from_millis(2));
+		let two = Uuid::new_v7();
+		assert_eq!(one.get_version_num(), 7);
+		assert_eq!(one.get_variant(), uuid::Variant::RFC4122);
+		assert!(one < two);
+	}
+
 	#[test]
 	fn uuid_v1() {
 		let sql = "e72bee20-f49b-11ec-b939-0242ac120002";
@@ -219,6 +219,15 @@ mod tests {
 		assert_eq!(res, val);
 	}
 
+	#[tokio::test]
+	async fn patch_add_escaped() {
+		let mut val = Value::parse("{ test: 123 }");
+		let ops = Value::parse("[{ op: 'add', path: '/a~1b', value: true }, { op: 'add', path: '/m~0n', value: false }]");
+		let res = Value::parse("{ test: 123, 'a/b': true, 'm~n': false }");
+		val.patch(ops).unwrap();
+		assert_eq!(res, val);
+	}
+
 	#[tokio::test]
 	async fn patch_change_invalid() {
 		// See https://github.com/surrealdb/surrealdb/issues/2001
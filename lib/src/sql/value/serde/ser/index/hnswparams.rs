@@ -0,0 +1,108 @@
+use crate::err::Error;
+use crate::sql::index::{Distance, HnswParams};
+use crate::sql::value::serde::ser;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::Serialize;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = HnswParams;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<HnswParams, Error>;
+	type SerializeTuple = Impossible<HnswParams, Error>;
+	type SerializeTupleStruct = Impossible<HnswParams, Error>;
+	type SerializeTupleVariant = Impossible<HnswParams, Error>;
+	type SerializeMap = Impossible<HnswParams, Error>;
+	type SerializeStruct = SerializeHnsw;
+	type SerializeStructVariant = Impossible<HnswParams, Error>;
+
+	const EXPECTED: &'static str = "a struct `HnswParams`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeHnsw::default())
+	}
+
+	#[inline]
+	fn serialize_newtype_struct<T>(
+		self,
+		_name: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		value.serialize(self.wrap())
+	}
+}
+
+#[derive(Default)]
+pub(super) struct SerializeHnsw {
+	dimension: u16,
+	distance: Distance,
+	m: u16,
+	ef_construction: u16,
+	doc_ids_order: u32,
+}
+impl serde::ser::SerializeStruct for SerializeHnsw {
+	type Ok = HnswParams;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"dimension" => {
+				self.dimension = value.serialize(ser::primitive::u16::Serializer.wrap())?;
+			}
+			"distance" => {
+				self.distance = value.serialize(ser::distance::Serializer.wrap())?;
+			}
+			"m" => {
+				self.m = value.serialize(ser::primitive::u16::Serializer.wrap())?;
+			}
+			"ef_construction" => {
+				self.ef_construction = value.serialize(ser::primitive::u16::Serializer.wrap())?;
+			}
+			"doc_ids_order" => {
+				self.doc_ids_order = value.serialize(ser::primitive::u32::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `HnswParams {{ {key} }}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(HnswParams {
+			dimension: self.dimension,
+			distance: self.distance,
+			m: self.m,
+			ef_construction: self.ef_construction,
+			doc_ids_order: self.doc_ids_order,
+		})
+	}
+}
+
+#[test]
+fn hnsw_params() {
+	let params = HnswParams {
+		dimension: 1,
+		distance: Default::default(),
+		m: 2,
+		ef_construction: 3,
+		doc_ids_order: 4,
+	};
+	let serialized = params.serialize(Serializer.wrap()).unwrap();
+	assert_eq!(params, serialized);
+}
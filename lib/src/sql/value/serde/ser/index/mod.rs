@@ -1,5 +1,8 @@
+mod hnswparams;
 mod mtreeparams;
 mod searchparams;
+mod simhashparams;
+mod spatialparams;
 
 use crate::err::Error;
 use crate::sql::index::Index;
@@ -52,6 +55,9 @@ impl ser::Serializer for Serializer {
 		match variant {
 			"Search" => Ok(Index::Search(value.serialize(searchparams::Serializer.wrap())?)),
 			"MTree" => Ok(Index::MTree(value.serialize(mtreeparams::Serializer.wrap())?)),
+			"SimHash" => Ok(Index::SimHash(value.serialize(simhashparams::Serializer.wrap())?)),
+			"Hnsw" => Ok(Index::Hnsw(value.serialize(hnswparams::Serializer.wrap())?)),
+			"Spatial" => Ok(Index::Spatial(value.serialize(spatialparams::Serializer.wrap())?)),
 			variant => {
 				Err(Error::custom(format!("unexpected newtype variant `{name}::{variant}`")))
 			}
@@ -0,0 +1,84 @@
+use crate::err::Error;
+use crate::sql::index::SimHashParams;
+use crate::sql::value::serde::ser;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::Serialize;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = SimHashParams;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<SimHashParams, Error>;
+	type SerializeTuple = Impossible<SimHashParams, Error>;
+	type SerializeTupleStruct = Impossible<SimHashParams, Error>;
+	type SerializeTupleVariant = Impossible<SimHashParams, Error>;
+	type SerializeMap = Impossible<SimHashParams, Error>;
+	type SerializeStruct = SerializeSimHash;
+	type SerializeStructVariant = Impossible<SimHashParams, Error>;
+
+	const EXPECTED: &'static str = "a struct `SimHashParams`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeSimHash::default())
+	}
+
+	#[inline]
+	fn serialize_newtype_struct<T>(
+		self,
+		_name: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		value.serialize(self.wrap())
+	}
+}
+
+#[derive(Default)]
+pub(super) struct SerializeSimHash {
+	distance: u8,
+}
+impl serde::ser::SerializeStruct for SerializeSimHash {
+	type Ok = SimHashParams;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"distance" => {
+				self.distance = value.serialize(ser::primitive::u8::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `SimHashParams {{ {key} }}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(SimHashParams {
+			distance: self.distance,
+		})
+	}
+}
+
+#[test]
+fn simhash_params() {
+	let params = SimHashParams {
+		distance: 3,
+	};
+	let serialized = params.serialize(Serializer.wrap()).unwrap();
+	assert_eq!(params, serialized);
+}
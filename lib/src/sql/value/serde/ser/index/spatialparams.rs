@@ -0,0 +1,84 @@
+use crate::err::Error;
+use crate::sql::index::SpatialParams;
+use crate::sql::value::serde::ser;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::Serialize;
+
+pub(super) struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = SpatialParams;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<SpatialParams, Error>;
+	type SerializeTuple = Impossible<SpatialParams, Error>;
+	type SerializeTupleStruct = Impossible<SpatialParams, Error>;
+	type SerializeTupleVariant = Impossible<SpatialParams, Error>;
+	type SerializeMap = Impossible<SpatialParams, Error>;
+	type SerializeStruct = SerializeSpatial;
+	type SerializeStructVariant = Impossible<SpatialParams, Error>;
+
+	const EXPECTED: &'static str = "a struct `SpatialParams`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeSpatial::default())
+	}
+
+	#[inline]
+	fn serialize_newtype_struct<T>(
+		self,
+		_name: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		value.serialize(self.wrap())
+	}
+}
+
+#[derive(Default)]
+pub(super) struct SerializeSpatial {
+	level: u8,
+}
+impl serde::ser::SerializeStruct for SerializeSpatial {
+	type Ok = SpatialParams;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"level" => {
+				self.level = value.serialize(ser::primitive::u8::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `SpatialParams {{ {key} }}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(SpatialParams {
+			level: self.level,
+		})
+	}
+}
+
+#[test]
+fn spatial_params() {
+	let params = SpatialParams {
+		level: 20,
+	};
+	let serialized = params.serialize(Serializer.wrap()).unwrap();
+	assert_eq!(params, serialized);
+}
@@ -40,6 +40,8 @@ mod permission;
 mod permissions;
 mod primitive;
 mod range;
+mod retention;
+mod rollup;
 mod scoring;
 mod split;
 mod start;
@@ -49,6 +51,7 @@ mod string;
 mod subquery;
 mod table;
 mod thing;
+mod tiering;
 mod timeout;
 mod tokenizer;
 mod uuid;
@@ -0,0 +1,91 @@
+pub(super) mod opt;
+
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Duration;
+use crate::sql::Idiom;
+use crate::sql::Retention;
+use crate::sql::Table;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Retention;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Retention, Error>;
+	type SerializeTuple = Impossible<Retention, Error>;
+	type SerializeTupleStruct = Impossible<Retention, Error>;
+	type SerializeTupleVariant = Impossible<Retention, Error>;
+	type SerializeMap = Impossible<Retention, Error>;
+	type SerializeStruct = SerializeRetention;
+	type SerializeStructVariant = Impossible<Retention, Error>;
+
+	const EXPECTED: &'static str = "a struct `Retention`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeRetention::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeRetention {
+	window: Duration,
+	field: Idiom,
+	archive: Option<Table>,
+}
+
+impl serde::ser::SerializeStruct for SerializeRetention {
+	type Ok = Retention;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"window" => {
+				self.window = value.serialize(ser::duration::Serializer.wrap())?.into();
+			}
+			"field" => {
+				self.field = Idiom(value.serialize(ser::part::vec::Serializer.wrap())?);
+			}
+			"archive" => {
+				self.archive = value.serialize(ser::table::opt::Serializer.wrap())?;
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `Retention::{key}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(Retention {
+			window: self.window,
+			field: self.field,
+			archive: self.archive,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = Retention::default();
+		let value: Retention = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
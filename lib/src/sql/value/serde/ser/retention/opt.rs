@@ -0,0 +1,55 @@
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Retention;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Option<Retention>;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Option<Retention>, Error>;
+	type SerializeTuple = Impossible<Option<Retention>, Error>;
+	type SerializeTupleStruct = Impossible<Option<Retention>, Error>;
+	type SerializeTupleVariant = Impossible<Option<Retention>, Error>;
+	type SerializeMap = Impossible<Option<Retention>, Error>;
+	type SerializeStruct = Impossible<Option<Retention>, Error>;
+	type SerializeStructVariant = Impossible<Option<Retention>, Error>;
+
+	const EXPECTED: &'static str = "an `Option<Retention>`";
+
+	#[inline]
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(None)
+	}
+
+	#[inline]
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Ok(Some(value.serialize(super::Serializer.wrap())?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+
+	#[test]
+	fn none() {
+		let option: Option<Retention> = None;
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+
+	#[test]
+	fn some() {
+		let option = Some(Retention::default());
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+}
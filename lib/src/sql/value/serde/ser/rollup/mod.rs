@@ -0,0 +1,97 @@
+pub(super) mod opt;
+
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Duration;
+use crate::sql::Idiom;
+use crate::sql::Idioms;
+use crate::sql::Rollup;
+use crate::sql::Table;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Rollup;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Rollup, Error>;
+	type SerializeTuple = Impossible<Rollup, Error>;
+	type SerializeTupleStruct = Impossible<Rollup, Error>;
+	type SerializeTupleVariant = Impossible<Rollup, Error>;
+	type SerializeMap = Impossible<Rollup, Error>;
+	type SerializeStruct = SerializeRollup;
+	type SerializeStructVariant = Impossible<Rollup, Error>;
+
+	const EXPECTED: &'static str = "a struct `Rollup`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeRollup::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeRollup {
+	from: Table,
+	every: Duration,
+	field: Idiom,
+	sum: Idioms,
+}
+
+impl serde::ser::SerializeStruct for SerializeRollup {
+	type Ok = Rollup;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"from" => {
+				self.from = Table(value.serialize(ser::string::Serializer.wrap())?);
+			}
+			"every" => {
+				self.every = value.serialize(ser::duration::Serializer.wrap())?.into();
+			}
+			"field" => {
+				self.field = Idiom(value.serialize(ser::part::vec::Serializer.wrap())?);
+			}
+			"sum" => {
+				self.sum = Idioms(value.serialize(ser::idiom::vec::Serializer.wrap())?);
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `Rollup::{key}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(Rollup {
+			from: self.from,
+			every: self.every,
+			field: self.field,
+			sum: self.sum,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = Rollup::default();
+		let value: Rollup = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
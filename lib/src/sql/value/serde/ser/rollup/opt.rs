@@ -0,0 +1,55 @@
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Rollup;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Option<Rollup>;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Option<Rollup>, Error>;
+	type SerializeTuple = Impossible<Option<Rollup>, Error>;
+	type SerializeTupleStruct = Impossible<Option<Rollup>, Error>;
+	type SerializeTupleVariant = Impossible<Option<Rollup>, Error>;
+	type SerializeMap = Impossible<Option<Rollup>, Error>;
+	type SerializeStruct = Impossible<Option<Rollup>, Error>;
+	type SerializeStructVariant = Impossible<Option<Rollup>, Error>;
+
+	const EXPECTED: &'static str = "an `Option<Rollup>`";
+
+	#[inline]
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(None)
+	}
+
+	#[inline]
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Ok(Some(value.serialize(super::Serializer.wrap())?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+
+	#[test]
+	fn none() {
+		let option: Option<Rollup> = None;
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+
+	#[test]
+	fn some() {
+		let option = Some(Rollup::default());
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+}
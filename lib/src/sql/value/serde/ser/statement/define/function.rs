@@ -44,6 +44,7 @@ pub struct SerializeDefineFunctionStatement {
 	block: Block,
 	comment: Option<Strand>,
 	permissions: Permission,
+	definer: bool,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineFunctionStatement {
@@ -70,6 +71,9 @@ impl serde::ser::SerializeStruct for SerializeDefineFunctionStatement {
 			"permissions" => {
 				self.permissions = value.serialize(ser::permission::Serializer.wrap())?;
 			}
+			"definer" => {
+				self.definer = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineFunctionStatement::{key}`"
@@ -86,6 +90,8 @@ impl serde::ser::SerializeStruct for SerializeDefineFunctionStatement {
 			block: self.block,
 			comment: self.comment,
 			permissions: self.permissions,
+			definer: self.definer,
+			auth: None,
 		})
 	}
 }
@@ -2,9 +2,13 @@ use crate::err::Error;
 use crate::sql::changefeed::ChangeFeed;
 use crate::sql::statements::DefineTableStatement;
 use crate::sql::value::serde::ser;
+use crate::sql::Duration;
 use crate::sql::Ident;
 use crate::sql::Permissions;
+use crate::sql::Retention;
+use crate::sql::Rollup;
 use crate::sql::Strand;
+use crate::sql::Tiering;
 use crate::sql::View;
 use ser::Serializer as _;
 use serde::ser::Error as _;
@@ -47,6 +51,13 @@ pub struct SerializeDefineTableStatement {
 	permissions: Permissions,
 	changefeed: Option<ChangeFeed>,
 	comment: Option<Strand>,
+	ttl: Option<Duration>,
+	retention: Option<Retention>,
+	tiering: Option<Tiering>,
+	rollup: Option<Rollup>,
+	temporary: bool,
+	cache: bool,
+	dedup: Option<u64>,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineTableStatement {
@@ -82,6 +93,27 @@ impl serde::ser::SerializeStruct for SerializeDefineTableStatement {
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"ttl" => {
+				self.ttl = value.serialize(ser::duration::opt::Serializer.wrap())?.map(Into::into);
+			}
+			"retention" => {
+				self.retention = value.serialize(ser::retention::opt::Serializer.wrap())?;
+			}
+			"tiering" => {
+				self.tiering = value.serialize(ser::tiering::opt::Serializer.wrap())?;
+			}
+			"rollup" => {
+				self.rollup = value.serialize(ser::rollup::opt::Serializer.wrap())?;
+			}
+			"temporary" => {
+				self.temporary = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"cache" => {
+				self.cache = value.serialize(ser::primitive::bool::Serializer.wrap())?;
+			}
+			"dedup" => {
+				self.dedup = value.serialize(ser::primitive::u64::opt::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineTableStatement::{key}`"
@@ -101,6 +133,13 @@ impl serde::ser::SerializeStruct for SerializeDefineTableStatement {
 			permissions: self.permissions,
 			changefeed: self.changefeed,
 			comment: self.comment,
+			ttl: self.ttl,
+			retention: self.retention,
+			tiering: self.tiering,
+			rollup: self.rollup,
+			temporary: self.temporary,
+			cache: self.cache,
+			dedup: self.dedup,
 		})
 	}
 }
@@ -43,6 +43,8 @@ pub struct SerializeDefineTokenStatement {
 	kind: Algorithm,
 	code: String,
 	comment: Option<Strand>,
+	issuer: Option<Strand>,
+	audience: Option<Strand>,
 }
 
 impl serde::ser::SerializeStruct for SerializeDefineTokenStatement {
@@ -69,6 +71,12 @@ impl serde::ser::SerializeStruct for SerializeDefineTokenStatement {
 			"comment" => {
 				self.comment = value.serialize(ser::strand::opt::Serializer.wrap())?;
 			}
+			"issuer" => {
+				self.issuer = value.serialize(ser::strand::opt::Serializer.wrap())?;
+			}
+			"audience" => {
+				self.audience = value.serialize(ser::strand::opt::Serializer.wrap())?;
+			}
 			key => {
 				return Err(Error::custom(format!(
 					"unexpected field `DefineTokenStatement::{key}`"
@@ -85,6 +93,8 @@ impl serde::ser::SerializeStruct for SerializeDefineTokenStatement {
 			kind: self.kind,
 			code: self.code,
 			comment: self.comment,
+			issuer: self.issuer,
+			audience: self.audience,
 		})
 	}
 }
@@ -0,0 +1,85 @@
+pub(super) mod opt;
+
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Duration;
+use crate::sql::Idiom;
+use crate::sql::Tiering;
+use ser::Serializer as _;
+use serde::ser::Error as _;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Tiering;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Tiering, Error>;
+	type SerializeTuple = Impossible<Tiering, Error>;
+	type SerializeTupleStruct = Impossible<Tiering, Error>;
+	type SerializeTupleVariant = Impossible<Tiering, Error>;
+	type SerializeMap = Impossible<Tiering, Error>;
+	type SerializeStruct = SerializeTiering;
+	type SerializeStructVariant = Impossible<Tiering, Error>;
+
+	const EXPECTED: &'static str = "a struct `Tiering`";
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeStruct, Error> {
+		Ok(SerializeTiering::default())
+	}
+}
+
+#[derive(Default)]
+pub struct SerializeTiering {
+	after: Duration,
+	field: Idiom,
+}
+
+impl serde::ser::SerializeStruct for SerializeTiering {
+	type Ok = Tiering;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match key {
+			"after" => {
+				self.after = value.serialize(ser::duration::Serializer.wrap())?.into();
+			}
+			"field" => {
+				self.field = Idiom(value.serialize(ser::part::vec::Serializer.wrap())?);
+			}
+			key => {
+				return Err(Error::custom(format!("unexpected field `Tiering::{key}`")));
+			}
+		}
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Error> {
+		Ok(Tiering {
+			after: self.after,
+			field: self.field,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default() {
+		let stmt = Tiering::default();
+		let value: Tiering = stmt.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(value, stmt);
+	}
+}
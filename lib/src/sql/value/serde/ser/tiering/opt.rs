@@ -0,0 +1,55 @@
+use crate::err::Error;
+use crate::sql::value::serde::ser;
+use crate::sql::Tiering;
+use serde::ser::Impossible;
+use serde::ser::Serialize;
+
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+	type Ok = Option<Tiering>;
+	type Error = Error;
+
+	type SerializeSeq = Impossible<Option<Tiering>, Error>;
+	type SerializeTuple = Impossible<Option<Tiering>, Error>;
+	type SerializeTupleStruct = Impossible<Option<Tiering>, Error>;
+	type SerializeTupleVariant = Impossible<Option<Tiering>, Error>;
+	type SerializeMap = Impossible<Option<Tiering>, Error>;
+	type SerializeStruct = Impossible<Option<Tiering>, Error>;
+	type SerializeStructVariant = Impossible<Option<Tiering>, Error>;
+
+	const EXPECTED: &'static str = "an `Option<Tiering>`";
+
+	#[inline]
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(None)
+	}
+
+	#[inline]
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Ok(Some(value.serialize(super::Serializer.wrap())?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::Serializer as _;
+
+	#[test]
+	fn none() {
+		let option: Option<Tiering> = None;
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+
+	#[test]
+	fn some() {
+		let option = Some(Tiering::default());
+		let serialized = option.serialize(Serializer.wrap()).unwrap();
+		assert_eq!(option, serialized);
+	}
+}
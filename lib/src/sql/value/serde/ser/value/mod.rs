@@ -235,6 +235,10 @@ impl ser::Serializer for Serializer {
 			sql::datetime::TOKEN => {
 				Ok(Value::Datetime(Datetime(value.serialize(ser::datetime::Serializer.wrap())?)))
 			}
+			sql::geometry::GEOJSON_TOKEN => {
+				let json = serde_json::to_value(value).map_err(Error::custom)?;
+				serde_json::from_value(json).map(Value::Geometry).map_err(Error::custom)
+			}
 			_ => value.serialize(self.wrap()),
 		}
 	}
@@ -15,6 +15,7 @@ use crate::sql::comment::mightbespace;
 use crate::sql::common::commas;
 use crate::sql::constant::Constant;
 use crate::sql::datetime::{datetime, Datetime};
+use crate::sql::diagnostics;
 use crate::sql::duration::{duration, Duration};
 use crate::sql::edges::{edges, Edges};
 use crate::sql::ending::keyword;
@@ -53,10 +54,13 @@ use nom::combinator::{self, cut, into, opt};
 use nom::multi::separated_list0;
 use nom::multi::separated_list1;
 use nom::sequence::terminated;
+use num_bigint::BigInt;
 use revision::revisioned;
+use revision::Revisioned;
 use rust_decimal::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as Json;
+use sha2::{Digest, Sha256};
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
@@ -430,6 +434,13 @@ impl From<Decimal> for Value {
 	}
 }
 
+impl TryFrom<BigInt> for Value {
+	type Error = Error;
+	fn try_from(v: BigInt) -> Result<Self, Self::Error> {
+		Ok(Value::Number(Number::try_from(v)?))
+	}
+}
+
 impl From<String> for Value {
 	fn from(v: String) -> Self {
 		Self::Strand(Strand::from(v))
@@ -690,6 +701,16 @@ impl TryFrom<Value> for Decimal {
 	}
 }
 
+impl TryFrom<Value> for BigInt {
+	type Error = Error;
+	fn try_from(value: Value) -> Result<Self, Self::Error> {
+		match value {
+			Value::Number(x) => x.try_into(),
+			_ => Err(Error::TryFrom(value.to_string(), "BigInt")),
+		}
+	}
+}
+
 impl TryFrom<Value> for String {
 	type Error = Error;
 	fn try_from(value: Value) -> Result<Self, Self::Error> {
@@ -1116,6 +1137,17 @@ impl Value {
 		self.into()
 	}
 
+	/// Computes the SHA-256 checksum of this Value, as a hex string
+	///
+	/// The checksum is computed over the serialized form of the value, which doesn't depend
+	/// on how the value is formatted, and matches `meta::checksum($value)` in SurrealQL, so
+	/// it can be used to check whether a record has changed since it was last read.
+	pub fn checksum(&self) -> String {
+		let mut val = Vec::new();
+		self.serialize_revisioned(&mut val).expect("values serialize into memory");
+		format!("{:x}", Sha256::digest(val))
+	}
+
 	// -----------------------------------
 	// Simple conversion of values
 	// -----------------------------------
@@ -2302,11 +2334,16 @@ impl Value {
 
 	/// Converts this Value into a JSONPatch path
 	pub(crate) fn jsonpath(&self) -> Idiom {
-		self.to_raw_string()
-			.as_str()
-			.trim_start_matches('/')
-			.split(&['.', '/'][..])
-			.map(Part::from)
+		let path = self.to_raw_string();
+		// A JSON Pointer only separates its segments with slashes, so that they can contain
+		// dots, while a path without a leading slash can also be separated with dots
+		let segments: Vec<&str> = match path.strip_prefix('/') {
+			Some(path) => path.split('/').collect(),
+			None => path.split(&['.', '/'][..]).collect(),
+		};
+		segments
+			.into_iter()
+			.map(|v| Part::from(v.replace("~1", "/").replace("~0", "~").as_str()))
 			.collect::<Vec<Part>>()
 			.into()
 	}
@@ -2829,12 +2866,14 @@ pub fn select_start(i: &str) -> IResult<&str, Value> {
 
 /// A path like production: Constants, predefined functions, user defined functions and ml models.
 pub fn path_like(i: &str) -> IResult<&str, Value> {
-	alt((into(defined_function), into(model), |i| {
-		let (i, v) = builtin_name(i)?;
+	alt((into(defined_function), into(model), |start| {
+		let (i, v) = builtin_name(start)?;
 		match v {
 			builtin::BuiltinName::Constant(x) => Ok((i, x.into())),
 			builtin::BuiltinName::Function(name) => {
-				builtin_function(name, i).map(|(i, v)| (i, v.into()))
+				let (i, v) = builtin_function(name, i)?;
+				diagnostics::function(start, i, name, v.args());
+				Ok((i, v.into()))
 			}
 		}
 	}))(i)
@@ -2944,6 +2983,15 @@ mod tests {
 		assert!(!Value::from(1).is_none());
 	}
 
+	#[test]
+	fn check_jsonpath() {
+		let path: Idiom = vec![Part::from("a"), Part::from("b")].into();
+		assert_eq!(Value::from("a.b").jsonpath(), path);
+		assert_eq!(Value::from("/a/b").jsonpath(), path);
+		let path: Idiom = vec![Part::from("a.b"), Part::from("c/d")].into();
+		assert_eq!(Value::from("/a.b/c~1d").jsonpath(), path);
+	}
+
 	#[test]
 	fn check_null() {
 		assert!(Value::Null.is_null());
@@ -0,0 +1,94 @@
+use crate::vs::u64_to_versionstamp;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// The position of a committed transaction in the change feed of a database
+///
+/// Versionstamps increase monotonically within a database, so they can be compared
+/// to order changes and used as a cursor when reading change feeds.
+#[derive(
+	Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd, Serialize, Deserialize, Hash,
+)]
+#[serde(transparent)]
+pub struct Versionstamp(pub u64);
+
+impl Versionstamp {
+	/// Returns the versionstamp directly after this one
+	///
+	/// Use this to continue reading a change feed after the last change which was read.
+	pub fn next(self) -> Self {
+		Self(self.0.saturating_add(1))
+	}
+	/// Converts the versionstamp from its 10-byte big-endian storage representation
+	///
+	/// The last two bytes order the changes within a single transaction, so they are ignored.
+	pub fn from_bytes(bytes: [u8; 10]) -> Self {
+		let mut buf = [0; 8];
+		buf.copy_from_slice(&bytes[..8]);
+		Self(u64::from_be_bytes(buf))
+	}
+	/// Converts the versionstamp to its 10-byte big-endian storage representation
+	pub fn to_bytes(self) -> [u8; 10] {
+		u64_to_versionstamp(self.0)
+	}
+}
+
+impl From<u64> for Versionstamp {
+	fn from(v: u64) -> Self {
+		Self(v)
+	}
+}
+
+impl From<Versionstamp> for u64 {
+	fn from(v: Versionstamp) -> Self {
+		v.0
+	}
+}
+
+impl From<[u8; 10]> for Versionstamp {
+	fn from(v: [u8; 10]) -> Self {
+		Self::from_bytes(v)
+	}
+}
+
+impl From<Versionstamp> for [u8; 10] {
+	fn from(v: Versionstamp) -> Self {
+		v.to_bytes()
+	}
+}
+
+impl Display for Versionstamp {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		Display::fmt(&self.0, f)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn versionstamp_bytes() {
+		let vs = Versionstamp(0x0102_0304_0506_0708);
+		assert_eq!(vs.to_bytes(), [1, 2, 3, 4, 5, 6, 7, 8, 0, 0]);
+		assert_eq!(Versionstamp::from_bytes(vs.to_bytes()), vs);
+		assert_eq!(Versionstamp::from([1, 2, 3, 4, 5, 6, 7, 8, 0, 9]), vs);
+	}
+
+	#[test]
+	fn versionstamp_ordering() {
+		let a = Versionstamp::from_bytes(u64_to_versionstamp(255));
+		let b = Versionstamp::from_bytes(u64_to_versionstamp(256));
+		assert!(a < b);
+		assert_eq!(a.next(), b);
+		assert_eq!(Versionstamp(u64::MAX).next(), Versionstamp(u64::MAX));
+	}
+
+	#[test]
+	fn versionstamp_serde() {
+		let vs = Versionstamp(42);
+		assert_eq!(serde_json::to_string(&vs).unwrap(), "42");
+		assert_eq!(serde_json::from_str::<Versionstamp>("42").unwrap(), vs);
+	}
+}
@@ -0,0 +1,246 @@
+//! The SurrealDB value types which are covered by semantic versioning.
+//!
+//! The [`sql`](crate::sql) module also contains the query syntax tree, which changes
+//! between minor releases as SurrealQL evolves. The types in this module only change in
+//! a backwards compatible way within a major release, so libraries can use them in
+//! their own public APIs and depend on `surrealdb` with a caret requirement such as
+//! `surrealdb = "1"`.
+//!
+//! A [`Value`] only holds data. It converts into a [`sql::Value`](crate::sql::Value), and
+//! back from one as long as that value has been computed.
+//!
+//! ```
+//! use surrealdb::values::{Thing, Value};
+//!
+//! let value = Value::from(Thing::new("person", "tobie"));
+//! assert_eq!(value.to_string(), "person:tobie");
+//!
+//! let sql = surrealdb::sql::Value::from(value.clone());
+//! assert_eq!(Value::try_from(sql).unwrap(), value);
+//! ```
+
+use crate::err::Error;
+use crate::sql;
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[doc(inline)]
+pub use crate::sql::Datetime;
+#[doc(inline)]
+pub use crate::sql::Duration;
+#[doc(inline)]
+pub use crate::sql::Geometry;
+#[doc(inline)]
+pub use crate::sql::Number;
+
+/// A value which is stored in, or returned from, the database
+#[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum Value {
+	#[default]
+	None,
+	Null,
+	Bool(bool),
+	Number(Number),
+	String(String),
+	Duration(Duration),
+	Datetime(Datetime),
+	Uuid(uuid::Uuid),
+	Array(Vec<Value>),
+	Object(BTreeMap<String, Value>),
+	Geometry(Geometry),
+	Bytes(Vec<u8>),
+	Thing(Thing),
+}
+
+/// The ID of a record, in the table which it belongs to
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub struct Thing {
+	tb: String,
+	id: Id,
+}
+
+/// The ID of a record within its table
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum Id {
+	Number(i64),
+	String(String),
+	Array(Vec<Value>),
+	Object(BTreeMap<String, Value>),
+}
+
+impl Thing {
+	/// Creates the ID of a record in a table
+	pub fn new(tb: impl Into<String>, id: impl Into<Id>) -> Self {
+		Self {
+			tb: tb.into(),
+			id: id.into(),
+		}
+	}
+
+	/// The table which the record belongs to
+	pub fn tb(&self) -> &str {
+		&self.tb
+	}
+
+	/// The ID of the record within its table
+	pub fn id(&self) -> &Id {
+		&self.id
+	}
+}
+
+impl From<Value> for sql::Value {
+	fn from(v: Value) -> Self {
+		match v {
+			Value::None => Self::None,
+			Value::Null => Self::Null,
+			Value::Bool(v) => Self::Bool(v),
+			Value::Number(v) => Self::Number(v),
+			Value::String(v) => Self::Strand(v.into()),
+			Value::Duration(v) => Self::Duration(v),
+			Value::Datetime(v) => Self::Datetime(v),
+			Value::Uuid(v) => Self::Uuid(sql::Uuid(v)),
+			Value::Array(v) => Self::Array(sql::Array(v.into_iter().map(Into::into).collect())),
+			Value::Object(v) => {
+				Self::Object(sql::Object(v.into_iter().map(|(k, v)| (k, v.into())).collect()))
+			}
+			Value::Geometry(v) => Self::Geometry(v),
+			Value::Bytes(v) => Self::Bytes(v.into()),
+			Value::Thing(v) => Self::Thing(v.into()),
+		}
+	}
+}
+
+impl TryFrom<sql::Value> for Value {
+	type Error = Error;
+	/// Fails for values which have not been computed, such as params or subqueries
+	fn try_from(v: sql::Value) -> Result<Self, Self::Error> {
+		Ok(match v {
+			sql::Value::None => Self::None,
+			sql::Value::Null => Self::Null,
+			sql::Value::Bool(v) => Self::Bool(v),
+			sql::Value::Number(v) => Self::Number(v),
+			sql::Value::Strand(v) => Self::String(v.0),
+			sql::Value::Duration(v) => Self::Duration(v),
+			sql::Value::Datetime(v) => Self::Datetime(v),
+			sql::Value::Uuid(v) => Self::Uuid(v.0),
+			sql::Value::Array(v) => Self::Array(array(v)?),
+			sql::Value::Object(v) => Self::Object(object(v)?),
+			sql::Value::Geometry(v) => Self::Geometry(v),
+			sql::Value::Bytes(v) => Self::Bytes(v.0),
+			sql::Value::Thing(v) => Self::Thing(v.try_into()?),
+			v => return Err(Error::TryFrom(v.to_string(), "values::Value")),
+		})
+	}
+}
+
+impl From<Thing> for sql::Thing {
+	fn from(v: Thing) -> Self {
+		Self {
+			tb: v.tb,
+			id: v.id.into(),
+		}
+	}
+}
+
+impl TryFrom<sql::Thing> for Thing {
+	type Error = Error;
+	fn try_from(v: sql::Thing) -> Result<Self, Self::Error> {
+		Ok(Self {
+			tb: v.tb,
+			id: v.id.try_into()?,
+		})
+	}
+}
+
+impl From<Id> for sql::Id {
+	fn from(v: Id) -> Self {
+		match v {
+			Id::Number(v) => Self::Number(v),
+			Id::String(v) => Self::String(v),
+			Id::Array(v) => Self::Array(sql::Array(v.into_iter().map(Into::into).collect())),
+			Id::Object(v) => {
+				Self::Object(sql::Object(v.into_iter().map(|(k, v)| (k, v.into())).collect()))
+			}
+		}
+	}
+}
+
+impl TryFrom<sql::Id> for Id {
+	type Error = Error;
+	/// Fails for IDs which are still to be generated
+	fn try_from(v: sql::Id) -> Result<Self, Self::Error> {
+		Ok(match v {
+			sql::Id::Number(v) => Self::Number(v),
+			sql::Id::String(v) => Self::String(v),
+			sql::Id::Array(v) => Self::Array(array(v)?),
+			sql::Id::Object(v) => Self::Object(object(v)?),
+			v => return Err(Error::TryFrom(v.to_string(), "values::Id")),
+		})
+	}
+}
+
+fn array(v: sql::Array) -> Result<Vec<Value>, Error> {
+	v.0.into_iter().map(Value::try_from).collect()
+}
+
+fn object(v: sql::Object) -> Result<BTreeMap<String, Value>, Error> {
+	v.0.into_iter().map(|(k, v)| Ok((k, Value::try_from(v)?))).collect()
+}
+
+macro_rules! impl_from {
+	($($t:ty => $variant:ident,)+) => {
+		$(impl From<$t> for Value {
+			fn from(v: $t) -> Self {
+				Self::$variant(v.into())
+			}
+		})+
+	};
+}
+
+impl_from! {
+	bool => Bool,
+	i64 => Number,
+	f64 => Number,
+	Number => Number,
+	String => String,
+	&str => String,
+	Duration => Duration,
+	Datetime => Datetime,
+	uuid::Uuid => Uuid,
+	Vec<Value> => Array,
+	BTreeMap<String, Value> => Object,
+	Geometry => Geometry,
+	Thing => Thing,
+}
+
+impl From<i64> for Id {
+	fn from(v: i64) -> Self {
+		Self::Number(v)
+	}
+}
+
+impl From<String> for Id {
+	fn from(v: String) -> Self {
+		Self::String(v)
+	}
+}
+
+impl From<&str> for Id {
+	fn from(v: &str) -> Self {
+		Self::String(v.to_owned())
+	}
+}
+
+impl fmt::Display for Value {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		sql::Value::from(self.clone()).fmt(f)
+	}
+}
+
+impl fmt::Display for Thing {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		sql::Thing::from(self.clone()).fmt(f)
+	}
+}
@@ -10,17 +10,24 @@ mod api_integration {
 	use std::sync::Arc;
 	use std::sync::Mutex;
 	use std::time::Duration;
+	use surrealdb::builder::select_from;
 	use surrealdb::dbs::capabilities::Capabilities;
 	use surrealdb::error::Api as ApiError;
 	use surrealdb::error::Db as DbError;
+	use surrealdb::expr::field;
+	#[cfg(feature = "macros")]
+	use surrealdb::model::SurrealModel;
 	use surrealdb::opt::auth::Database;
 	use surrealdb::opt::auth::Jwt;
 	use surrealdb::opt::auth::Namespace;
 	use surrealdb::opt::auth::Root;
 	use surrealdb::opt::auth::Scope;
 	use surrealdb::opt::Config;
+	use surrealdb::opt::Diff;
+	use surrealdb::opt::Link;
 	use surrealdb::opt::PatchOp;
 	use surrealdb::opt::Resource;
+	use surrealdb::path;
 	use surrealdb::sql::statements::BeginStatement;
 	use surrealdb::sql::statements::CommitStatement;
 	use surrealdb::sql::thing;
@@ -63,6 +70,38 @@ mod api_integration {
 		name: String,
 	}
 
+	#[cfg(feature = "macros")]
+	#[derive(Debug, Clone, Serialize, Deserialize, SurrealModel)]
+	struct UserAccount {
+		id: Option<Thing>,
+		name: String,
+	}
+
+	#[cfg(feature = "macros")]
+	#[derive(Debug, Serialize, Deserialize, SurrealModel)]
+	#[surreal(table = "person")]
+	struct Person {
+		name: String,
+	}
+
+	#[cfg(feature = "macros")]
+	#[derive(Debug, Serialize, Deserialize, SurrealModel)]
+	struct Author {
+		id: Option<Thing>,
+		name: String,
+	}
+
+	#[cfg(feature = "macros")]
+	#[derive(Debug, Serialize, Deserialize, SurrealModel)]
+	struct Book {
+		id: Option<Thing>,
+		title: String,
+		#[surreal(link)]
+		author: Author,
+		#[surreal(link)]
+		reviewers: Vec<Author>,
+	}
+
 	#[derive(Debug, Serialize, Deserialize)]
 	struct AuthParams<'a> {
 		email: &'a str,
@@ -119,6 +158,7 @@ mod api_integration {
 
 		include!("api/mod.rs");
 		include!("api/backup.rs");
+		include!("api/blob.rs");
 	}
 
 	#[cfg(feature = "kv-mem")]
@@ -210,8 +250,61 @@ mod api_integration {
 			db.use_ns("test").use_db("test").await.unwrap();
 		}
 
+		#[tokio::test]
+		async fn select_cache() {
+			use surrealdb::opt::CacheConfig;
+
+			init_logger();
+			let config = Config::new().with_cache(CacheConfig::new().capacity(10));
+			let db = Surreal::new::<Mem>(config).await.unwrap();
+			db.use_ns("test").use_db("test").await.unwrap();
+			let _: Option<RecordId> = db.create(("person", "tobie")).await.unwrap();
+			let people: Vec<RecordId> = db.select("person").await.unwrap();
+			assert_eq!(people.len(), 1);
+			let people: Vec<RecordId> = db.select("person").await.unwrap();
+			assert_eq!(people.len(), 1);
+			// Writes through the client invalidate the cached table
+			let _: Option<RecordId> = db.create(("person", "jaime")).await.unwrap();
+			let people: Vec<RecordId> = db.select("person").await.unwrap();
+			assert_eq!(people.len(), 2);
+			db.query("CREATE person:john").await.unwrap().check().unwrap();
+			let people: Vec<RecordId> = db.select("person").await.unwrap();
+			assert_eq!(people.len(), 3);
+			db.query("DELETE person").await.unwrap().check().unwrap();
+			let person: Option<RecordId> = db.select(("person", "tobie")).await.unwrap();
+			assert!(person.is_none());
+		}
+
+		#[tokio::test]
+		async fn live_queries_are_shared() {
+			use futures::StreamExt;
+			use surrealdb::dbs::Action;
+
+			init_logger();
+			let db = Surreal::new::<Mem>(Config::new().notifications()).await.unwrap();
+			db.use_ns("test").use_db("test").await.unwrap();
+			let mut first = db.live("person").await.unwrap();
+			let mut second = db.live("person").await.unwrap();
+			let other = db.live("person").filter(field("age").gte(18)).await.unwrap();
+			assert_eq!(first.id(), second.id());
+			assert_ne!(first.id(), other.id());
+			let id = first.id();
+			let _: Option<RecordId> = db.create(("person", "tobie")).await.unwrap();
+			for subscription in [&mut first, &mut second] {
+				let next = tokio::time::timeout(Duration::from_secs(5), subscription.next());
+				let notification = next.await.unwrap().unwrap().unwrap();
+				assert_eq!(notification.action, Action::Create);
+				assert_eq!(notification.id, id);
+			}
+			// Live queries are not shared between databases
+			db.use_db("other").await.unwrap();
+			let elsewhere = db.live("person").await.unwrap();
+			assert_ne!(elsewhere.id(), id);
+		}
+
 		include!("api/mod.rs");
 		include!("api/backup.rs");
+		include!("api/blob.rs");
 	}
 
 	#[cfg(feature = "kv-rocksdb")]
@@ -239,6 +332,7 @@ mod api_integration {
 
 		include!("api/mod.rs");
 		include!("api/backup.rs");
+		include!("api/blob.rs");
 	}
 
 	#[cfg(feature = "kv-rocksdb")]
@@ -266,6 +360,7 @@ mod api_integration {
 
 		include!("api/mod.rs");
 		include!("api/backup.rs");
+		include!("api/blob.rs");
 	}
 
 	#[cfg(feature = "kv-speedb")]
@@ -293,6 +388,63 @@ mod api_integration {
 
 		include!("api/mod.rs");
 		include!("api/backup.rs");
+		include!("api/blob.rs");
+	}
+
+	#[cfg(feature = "kv-sled")]
+	mod sled {
+		use super::*;
+		use surrealdb::engine::local::Db;
+		use surrealdb::engine::local::Sled;
+
+		async fn new_db() -> Surreal<Db> {
+			let _guard = SETUP_MUTEX.lock().unwrap();
+			init_logger();
+			let path = format!("/tmp/{}.db", Ulid::new());
+			let root = Root {
+				username: ROOT_USER,
+				password: ROOT_PASS,
+			};
+			let config = Config::new()
+				.user(root)
+				.tick_interval(TICK_INTERVAL)
+				.capabilities(Capabilities::all());
+			let db = Surreal::new::<Sled>((path, config)).await.unwrap();
+			db.signin(root).await.unwrap();
+			db
+		}
+
+		include!("api/mod.rs");
+		include!("api/backup.rs");
+		include!("api/blob.rs");
+	}
+
+	#[cfg(feature = "kv-redb")]
+	mod redb {
+		use super::*;
+		use surrealdb::engine::local::Db;
+		use surrealdb::engine::local::RedbFile;
+
+		async fn new_db() -> Surreal<Db> {
+			let _guard = SETUP_MUTEX.lock().unwrap();
+			init_logger();
+			let path = format!("/tmp/{}.db", Ulid::new());
+			let root = Root {
+				username: ROOT_USER,
+				password: ROOT_PASS,
+			};
+			let config = Config::new()
+				.user(root)
+				.tick_interval(TICK_INTERVAL)
+				.capabilities(Capabilities::all());
+			let db = Surreal::new::<RedbFile>((path, config)).await.unwrap();
+			db.signin(root).await.unwrap();
+			db
+		}
+
+		include!("api/mod.rs");
+		include!("api/backup.rs");
+		include!("api/blob.rs");
 	}
 
 	#[cfg(feature = "kv-tikv")]
@@ -319,6 +471,7 @@ mod api_integration {
 
 		include!("api/mod.rs");
 		include!("api/backup.rs");
+		include!("api/blob.rs");
 	}
 
 	#[cfg(feature = "kv-fdb")]
@@ -345,6 +498,7 @@ mod api_integration {
 
 		include!("api/mod.rs");
 		include!("api/backup.rs");
+		include!("api/blob.rs");
 	}
 
 	#[cfg(feature = "protocol-http")]
@@ -367,5 +521,6 @@ mod api_integration {
 
 		include!("api/mod.rs");
 		include!("api/backup.rs");
+		include!("api/blob.rs");
 	}
 }
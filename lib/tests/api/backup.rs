@@ -22,3 +22,25 @@ async fn export_import() {
 	db.import(&file).await.unwrap();
 	remove_file(file).await.unwrap();
 }
+
+#[tokio::test]
+async fn export_import_stream() {
+	let db = new_db().await;
+	let db_name = Ulid::new().to_string();
+	db.use_ns(NS).use_db(&db_name).await.unwrap();
+	for i in 0..10 {
+		let _: Vec<RecordId> = db
+			.create("user")
+			.content(Record {
+				name: &format!("User {i}"),
+			})
+			.await
+			.unwrap();
+	}
+	let mut backup = Vec::new();
+	db.export(&mut backup).await.unwrap();
+	db.query("REMOVE TABLE user").await.unwrap().check().unwrap();
+	db.import(&mut backup.as_slice()).await.unwrap();
+	let users: Vec<RecordId> = db.select("user").await.unwrap();
+	assert_eq!(users.len(), 10);
+}
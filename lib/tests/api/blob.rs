@@ -0,0 +1,35 @@
+// Tests for storing and reading binary blobs
+// Supported by the storage engines and the HTTP protocol
+
+#[tokio::test]
+async fn put_get_blob() {
+	let db = new_db().await;
+	let db_name = Ulid::new().to_string();
+	db.use_ns(NS).use_db(&db_name).await.unwrap();
+	let _: Vec<RecordId> = db
+		.create("user")
+		.content(Record {
+			name: "John Doe",
+		})
+		.await
+		.unwrap();
+	let user: Vec<RecordId> = db.select("user").await.unwrap();
+	let id = user[0].id.clone();
+	// Large enough to be stored in several chunks
+	let data: Vec<u8> = (0..200_000).map(|i| (i % 251) as u8).collect();
+	let size = db.put_blob(&id, data.as_slice()).await.unwrap();
+	assert_eq!(size, data.len() as u64);
+	let mut blob = db.get_blob(&id).await.unwrap();
+	let mut read = Vec::new();
+	tokio::io::copy(&mut blob, &mut read).await.unwrap();
+	assert_eq!(read, data);
+	// The blob is replaced when it is stored again
+	db.put_blob(&id, &b"avatar"[..]).await.unwrap();
+	let mut blob = db.get_blob(&id).await.unwrap();
+	let mut read = Vec::new();
+	tokio::io::copy(&mut blob, &mut read).await.unwrap();
+	assert_eq!(read, b"avatar");
+	// The blob is removed along with its record
+	let _: Option<RecordId> = db.delete(&id).await.unwrap();
+	db.get_blob(&id).await.unwrap_err();
+}
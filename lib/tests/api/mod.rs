@@ -235,6 +235,72 @@ async fn query_binds() {
 	assert_eq!(record.name, "John Doe");
 }
 
+#[tokio::test]
+async fn query_builder() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+		CREATE user:john SET name = 'John Doe', age = 42;
+		CREATE user:jane SET name = 'Jane Doe', age = 17;
+		CREATE user:amos SET name = 'Amos Doe', age = 21;
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	let query =
+		select_from("user").fields(["name"]).filter(field("age").gt(18)).order_by("name").limit(1);
+	let mut response = db.query(query).await.unwrap();
+	let records: Vec<RecordName> = response.take(0).unwrap();
+	let names: Vec<_> = records.into_iter().map(|record| record.name).collect();
+	assert_eq!(names, vec!["Amos Doe"]);
+}
+
+#[tokio::test]
+async fn process_parsed_query() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let query = surrealdb::sql::parse("CREATE user:john SET name = $name").unwrap();
+	let mut response = db.process(query).bind(("name", "John Doe")).await.unwrap();
+	let Some(record): Option<RecordName> = response.take(0).unwrap() else {
+		panic!("query returned no record");
+	};
+	assert_eq!(record.name, "John Doe");
+}
+
+#[tokio::test]
+async fn query_bind_chunked() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+		CREATE user:1 SET name = 'one';
+		CREATE user:2 SET name = 'two';
+		CREATE user:3 SET name = 'three';
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	let ids: Vec<Thing> = ["user:1", "user:2", "user:3"].map(|id| thing(id).unwrap()).into();
+	let mut response = db
+		.query("SELECT VALUE name FROM user WHERE id IN $ids ORDER BY name")
+		.bind_chunked("ids", ids, 2)
+		.await
+		.unwrap();
+	let names: Vec<String> = response.take(0).unwrap();
+	// Each chunk is ordered on its own
+	assert_eq!(names, vec!["one", "two", "three"]);
+}
+
+#[tokio::test]
+async fn query_bind_reader() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let data = r#"[{ "id": "user:1", "name": "one" }, { "id": "user:2", "name": "two" }]"#;
+	let mut response = db
+		.query("INSERT INTO user $data; SELECT VALUE name FROM user WHERE name != $skip")
+		.bind(("skip", "three"))
+		.bind_reader("data", data.as_bytes())
+		.await
+		.unwrap();
+	let names: Vec<String> = response.take(1).unwrap();
+	assert_eq!(names, vec!["one", "two"]);
+}
+
 #[tokio::test]
 async fn query_chaining() {
 	let db = new_db().await;
@@ -320,6 +386,143 @@ async fn create_record_with_id_with_content() {
 	assert_eq!(value.record(), thing("user:jane").ok());
 }
 
+#[tokio::test]
+async fn create_record_with_uuid_id() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let id = uuid::Uuid::new_v4();
+	let record: Option<RecordId> = db.create(("user", id)).await.unwrap();
+	assert_eq!(uuid::Uuid::try_from(record.unwrap().id.id).unwrap(), id);
+	let record: Option<RecordId> = db.select(("user", id)).await.unwrap();
+	assert!(record.is_some());
+}
+
+#[tokio::test]
+async fn create_record_with_validated_content() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+		DEFINE TABLE user SCHEMAFULL;
+		DEFINE FIELD name ON user TYPE string;
+		DEFINE FIELD age ON user TYPE int;
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	let record: Option<RecordId> = db
+		.create(("user", "john"))
+		.content(json!({ "name": "John Doe", "age": 42 }))
+		.validate()
+		.await
+		.unwrap();
+	assert_eq!(record.unwrap().id, thing("user:john").unwrap());
+	let error = db
+		.create::<Option<RecordId>>(("user", "jane"))
+		.content(json!({ "nmae": "Jane Doe", "age": "42" }))
+		.validate()
+		.await
+		.unwrap_err();
+	match error {
+		Error::Api(ApiError::SchemaMismatch {
+			table,
+			problems,
+		}) => {
+			assert_eq!(table, "user");
+			assert_eq!(problems.len(), 2);
+		}
+		error => panic!("{error:?}"),
+	}
+	let jane: Option<RecordId> = db.select(("user", "jane")).await.unwrap();
+	assert!(jane.is_none());
+}
+
+#[tokio::test]
+async fn create_record_if_not_exists() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let record: Option<RecordName> = db
+		.create(("user", "john"))
+		.if_not_exists()
+		.content(Record {
+			name: "John Doe",
+		})
+		.await
+		.unwrap();
+	assert_eq!(record.unwrap().name, "John Doe");
+	let record: Option<RecordName> = db
+		.create(("user", "john"))
+		.if_not_exists()
+		.content(Record {
+			name: "Jane Doe",
+		})
+		.await
+		.unwrap();
+	assert!(record.is_none());
+	let record: Option<RecordName> = db.select(("user", "john")).await.unwrap();
+	assert_eq!(record.unwrap().name, "John Doe");
+}
+
+#[tokio::test]
+async fn create_record_with_id_from_hash() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let created: Vec<RecordId> = db
+		.create("user")
+		.id_from_hash()
+		.content(Record {
+			name: "John Doe",
+		})
+		.await
+		.unwrap();
+	let id = created[0].id.clone();
+	assert_eq!(id.tb, "user");
+	// Retrying the insert resolves to the same record
+	let retried: Vec<RecordId> = db
+		.create("user")
+		.id_from_hash()
+		.if_not_exists()
+		.content(Record {
+			name: "John Doe",
+		})
+		.await
+		.unwrap();
+	assert!(retried.is_empty());
+	db.create::<Vec<RecordId>>("user")
+		.id_from_hash()
+		.content(Record {
+			name: "John Doe",
+		})
+		.await
+		.unwrap_err();
+	// Hashes of selected fields ignore the other fields
+	let created: Vec<RecordId> = db
+		.create("user")
+		.id_from_fields(["name"])
+		.content(json!({ "name": "John Doe", "age": 42 }))
+		.await
+		.unwrap();
+	let other: Vec<RecordId> = db
+		.create("user")
+		.id_from_fields(["name"])
+		.if_not_exists()
+		.content(json!({ "name": "John Doe", "age": 43 }))
+		.await
+		.unwrap();
+	assert_ne!(created[0].id, id);
+	assert!(other.is_empty());
+	// Only records created in a table can have hashed IDs
+	match db
+		.create::<Option<RecordId>>(("user", "jane"))
+		.id_from_hash()
+		.content(Record {
+			name: "Jane Doe",
+		})
+		.await
+		.unwrap_err()
+	{
+		Error::Api(ApiError::HashIdOnRecord(_)) => {}
+		error => panic!("{error:?}"),
+	}
+}
+
 #[tokio::test]
 async fn select_table() {
 	let db = new_db().await;
@@ -346,6 +549,402 @@ async fn select_record_id() {
 	assert_eq!(value.record(), thing("user:john").ok());
 }
 
+#[tokio::test]
+async fn select_many_record_ids() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let _: Option<RecordId> = db.create(("user", "jane")).await.unwrap();
+	let _: Option<RecordId> = db.create(("user", "john")).await.unwrap();
+	let users: Vec<Option<RecordId>> =
+		db.select_many(["user:john", "user:amos", "user:jane"]).await.unwrap();
+	let users: Vec<Option<Thing>> =
+		users.into_iter().map(|user| user.map(|user| user.id)).collect();
+	assert_eq!(users, vec![thing("user:john").ok(), None, thing("user:jane").ok()]);
+	let users: Vec<Option<RecordId>> =
+		db.select_many(["user:jane", "user:john", "user:jane"]).await.unwrap();
+	let users: Vec<Option<Thing>> =
+		users.into_iter().map(|user| user.map(|user| user.id)).collect();
+	assert_eq!(
+		users,
+		vec![thing("user:jane").ok(), thing("user:john").ok(), thing("user:jane").ok()]
+	);
+	let users: Vec<Option<RecordId>> = db.select_many(Vec::<Thing>::new()).await.unwrap();
+	assert!(users.is_empty());
+}
+
+#[tokio::test]
+async fn traverse_graph() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+		CREATE user:john, user:jane, user:amos, company:surrealdb, company:acme;
+		RELATE user:john->knows->user:jane;
+		RELATE user:jane->knows->user:amos;
+		RELATE user:jane->works_at->company:surrealdb;
+		RELATE user:amos->works_at->company:acme;
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	let ids =
+		|records: Vec<RecordId>| records.into_iter().map(|record| record.id).collect::<Vec<_>>();
+	let records: Vec<RecordId> = db.traverse("user:john").out("knows").await.unwrap();
+	assert_eq!(ids(records), vec![thing("user:jane").unwrap()]);
+	let records: Vec<RecordId> =
+		db.traverse("user:john").out("knows").out("works_at").await.unwrap();
+	assert_eq!(ids(records), vec![thing("company:surrealdb").unwrap()]);
+	let records: Vec<RecordId> = db.traverse("user:john").out("knows").depth(1..=2).await.unwrap();
+	assert_eq!(ids(records), vec![thing("user:jane").unwrap(), thing("user:amos").unwrap()]);
+	let records: Vec<RecordId> =
+		db.traverse("company:acme").r#in("works_at").r#in("knows").await.unwrap();
+	assert_eq!(ids(records), vec![thing("user:jane").unwrap()]);
+	let records: Result<Vec<RecordId>, _> = db.traverse("user:john").out("knows").depth(1..).await;
+	records.unwrap_err();
+}
+
+#[tokio::test]
+async fn deserialize_graph_links() {
+	#[derive(Debug, Deserialize)]
+	struct Company {
+		id: Thing,
+	}
+
+	#[derive(Debug, Deserialize)]
+	struct User {
+		id: Thing,
+		#[serde(rename = "->knows->user")]
+		knows: Vec<Thing>,
+		#[serde(rename = "->works_at->company")]
+		works_at: Vec<Link<Company>>,
+		manager: Option<Thing>,
+	}
+
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+		CREATE user:john, company:surrealdb;
+		CREATE user:jane SET manager = user:john;
+		RELATE user:jane->knows->user:john;
+		RELATE user:jane->works_at->company:surrealdb;
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	let sql = "SELECT id, manager, ->knows->user, ->works_at->company FROM user ORDER BY id";
+	let users: Vec<User> = db.query(sql).await.unwrap().take(0).unwrap();
+	assert_eq!(users[0].id, thing("user:jane").unwrap());
+	assert_eq!(users[0].knows, vec![thing("user:john").unwrap()]);
+	assert_eq!(users[0].works_at, vec![Link::Id(thing("company:surrealdb").unwrap())]);
+	assert_eq!(users[0].manager, thing("user:john").ok());
+	assert_eq!(users[1].manager, None);
+	assert!(users[1].works_at.is_empty());
+	let sql = format!("{sql} FETCH ->works_at->company");
+	let users: Vec<User> = db.query(sql).await.unwrap().take(0).unwrap();
+	let company = users[0].works_at[0].record().unwrap();
+	assert_eq!(company.id, thing("company:surrealdb").unwrap());
+}
+
+#[tokio::test]
+async fn load_links() {
+	#[derive(Debug, Deserialize)]
+	struct Post {
+		title: String,
+	}
+
+	#[derive(Debug, Deserialize)]
+	struct Author {
+		posts: Vec<Link<Post>>,
+		editor: Option<Link<Author>>,
+	}
+
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+		CREATE post:one SET title = 'One';
+		CREATE post:two SET title = 'Two';
+		CREATE author:jaime SET posts = [];
+		CREATE author:tobie SET posts = [post:one, post:missing, post:two], editor = author:jaime;
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	let author: Option<Author> = db.select(("author", "tobie")).await.unwrap();
+	let mut author = author.unwrap();
+	Link::load_many(&mut author.posts, &db).await.unwrap();
+	let titles: Vec<_> =
+		author.posts.iter().map(|post| post.record().map(|post| &post.title)).collect();
+	assert_eq!(titles, vec![Some(&"One".to_owned()), None, Some(&"Two".to_owned())]);
+	assert_eq!(author.posts[1].id(), thing("post:missing").ok().as_ref());
+	let editor = author.editor.as_mut().unwrap();
+	assert!(!editor.is_fetched());
+	let loaded = editor.load(&db).await.unwrap().unwrap();
+	assert!(loaded.posts.is_empty());
+	assert!(editor.is_fetched());
+}
+
+#[tokio::test]
+async fn precise_numbers() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Account {
+		#[serde(with = "surrealdb::opt::number::decimal")]
+		balance: rust_decimal::Decimal,
+		#[serde(with = "surrealdb::opt::number::int128")]
+		transactions: i128,
+	}
+
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let account = Account {
+		balance: "1234567890123456789.01".parse().unwrap(),
+		transactions: i64::MAX as i128 + 1,
+	};
+	let _: Option<RecordId> = db.create(("account", "one")).content(&account).await.unwrap();
+	let stored: Option<Account> = db.select(("account", "one")).await.unwrap();
+	assert_eq!(stored, Some(account));
+	let sql = "SELECT VALUE type::is::decimal(balance) FROM account:one";
+	let is_decimal: Option<bool> = db.query(sql).await.unwrap().take(0).unwrap();
+	assert_eq!(is_decimal, Some(true));
+}
+
+#[tokio::test]
+async fn aggregate_table() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	assert_eq!(db.count("sale").await.unwrap(), 0);
+	assert_eq!(db.sum("sale", "total").await.unwrap(), 0.0);
+	assert_eq!(db.avg("sale", "total").await.unwrap(), None);
+	let sql = "
+		CREATE sale:1 SET total = 10, meta.items = 1;
+		CREATE sale:2 SET total = 20, meta.items = 3;
+		CREATE sale:3 SET total = 30.5, meta.items = 2;
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	assert_eq!(db.count("sale").await.unwrap(), 3);
+	assert_eq!(db.sum("sale", "total").await.unwrap(), 60.5);
+	assert_eq!(db.avg("sale", "meta.items").await.unwrap(), Some(2.0));
+}
+
+#[tokio::test]
+async fn vector_search() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+		CREATE pts:1 SET point = [1,2,3,4];
+		CREATE pts:2 SET point = [4,5,6,7];
+		CREATE pts:3 SET point = [8,9,10,11];
+		DEFINE INDEX mt_pts ON pts FIELDS point MTREE DIMENSION 4;
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	let results: Vec<surrealdb::method::SearchResult<RecordId>> =
+		db.search("pts").vector("point", vec![2.0, 3.0, 4.0, 5.0]).knn(2).await.unwrap();
+	let found: Vec<_> = results.iter().map(|r| (r.record.id.id.to_string(), r.distance)).collect();
+	assert_eq!(found, vec![("1".to_owned(), 2.0), ("2".to_owned(), 4.0)]);
+	let results: Vec<surrealdb::method::SearchResult<RecordId>> = db
+		.search("pts")
+		.vector("point", vec![2.0, 3.0, 4.0, 5.0])
+		.knn(2)
+		.filter("id != pts:1")
+		.await
+		.unwrap();
+	let found: Vec<_> = results.iter().map(|r| r.record.id.id.to_string()).collect();
+	assert_eq!(found, vec!["2"]);
+}
+
+#[tokio::test]
+async fn text_search() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+		CREATE article:1 SET title = 'Rust async in practice';
+		CREATE article:2 SET title = 'Gardening for beginners';
+		CREATE article:3 SET title = 'Async Rust, async everywhere';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX title ON article FIELDS title SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	let results: Vec<surrealdb::method::TextSearchResult<RecordId>> =
+		db.search("article").matches("title", "async").await.unwrap();
+	let mut found: Vec<_> = results.iter().map(|r| r.record.id.id.to_string()).collect();
+	found.sort();
+	assert_eq!(found, vec!["1", "3"]);
+	assert!(results.iter().all(|r| r.score > 0.0));
+	assert!(results.windows(2).all(|w| w[0].score >= w[1].score));
+	let results: Vec<surrealdb::method::TextSearchResult<RecordId>> = db
+		.search("article")
+		.matches("title", "practice")
+		.highlight("<em>", "</em>")
+		.filter("id != article:3")
+		.await
+		.unwrap();
+	assert_eq!(results.len(), 1);
+	assert_eq!(results[0].highlights, vec!["Rust async in <em>practice</em>".to_owned()]);
+}
+
+#[tokio::test]
+async fn index_stats() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+		CREATE article:1 SET title = 'Rust async in practice';
+		CREATE article:2 SET title = 'Gardening for beginners';
+		DEFINE ANALYZER simple TOKENIZERS blank,class FILTERS lowercase;
+		DEFINE INDEX title ON article FIELDS title SEARCH ANALYZER simple BM25(1.2,0.75);
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	let stats = db.index_stats("article", "title").await.unwrap();
+	assert_eq!(stats.doc_count, 2);
+	assert_eq!(stats.total_docs_lengths, 7);
+	assert_eq!(stats.doc_ids.keys_count, 2);
+	assert_eq!(stats.terms.keys_count, 7);
+	// Changing the BM25 parameters keeps the index
+	let sql = "DEFINE INDEX title ON article FIELDS title SEARCH ANALYZER simple BM25(2.0,0.5)";
+	db.query(sql).await.unwrap().check().unwrap();
+	assert_eq!(db.index_stats("article", "title").await.unwrap(), stats);
+	let results: Vec<surrealdb::method::TextSearchResult<RecordId>> =
+		db.search("article").matches("title", "practice").await.unwrap();
+	assert_eq!(results.len(), 1);
+}
+
+#[tokio::test]
+async fn estimate_query_cost() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+		DEFINE INDEX email ON user FIELDS email UNIQUE;
+		CREATE user:1 SET email = 'one@surrealdb.com', name = 'One';
+		CREATE user:2 SET email = 'two@surrealdb.com', name = 'Two';
+		CREATE user:3 SET email = 'three@surrealdb.com', name = 'Three';
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	let cost = db.estimate("SELECT * FROM user WHERE email = 'two@surrealdb.com'").await.unwrap();
+	assert_eq!(cost.indexes, vec!["email".to_owned()]);
+	assert!(!cost.requires_table_scan());
+	assert_eq!(cost.rows, 1);
+	let cost = db
+		.estimate("UPDATE user SET active = true WHERE name = 'Two'; DELETE user:1")
+		.await
+		.unwrap();
+	assert!(cost.indexes.is_empty());
+	assert_eq!(cost.table_scans, vec!["user".to_owned()]);
+	assert_eq!(cost.rows, 4);
+	// Nothing was run
+	let users: Vec<RecordId> = db.select("user").await.unwrap();
+	assert_eq!(users.len(), 3);
+	let cost = db.estimate("CREATE user:4").await.unwrap();
+	assert_eq!(cost.rows, 0);
+}
+
+#[tokio::test]
+async fn infer_schema() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+		CREATE person:1 SET name = 'Tobie', age = 33, tags = ['a'];
+		CREATE person:2 SET name = 'Jaime', age = 32.5;
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	let fields: Vec<String> =
+		db.infer_schema("person", 10).await.unwrap().iter().map(ToString::to_string).collect();
+	assert_eq!(
+		fields,
+		vec![
+			"DEFINE FIELD age ON person TYPE number",
+			"DEFINE FIELD name ON person TYPE string",
+			"DEFINE FIELD tags ON person TYPE option<array<string>>",
+		]
+	);
+}
+
+#[tokio::test]
+async fn define_typed() {
+	use surrealdb::sql::Filter;
+	use surrealdb::sql::Kind;
+	use surrealdb::sql::Tokenizer;
+
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	db.define_field("person").name("age").kind(Kind::Int).default(0).await.unwrap();
+	db.define_field("person").name("adult").value(field("age").gte(18)).await.unwrap();
+	db.define_index("person")
+		.name("person_name")
+		.columns(["name", "address.country"])
+		.unique()
+		.await
+		.unwrap();
+	db.define_event("person")
+		.name("audit")
+		.then("CREATE audit SET person = $after.id")
+		.await
+		.unwrap();
+	db.define_analyzer("simple")
+		.tokenizers([Tokenizer::Blank, Tokenizer::Class])
+		.filters([Filter::Lowercase])
+		.await
+		.unwrap();
+	db.define_index("article").name("title").columns(["title"]).search("simple").await.unwrap();
+	let mut response = db.query("INFO FOR TABLE person; INFO FOR DB").await.unwrap();
+	let info: Value = response.take(0).unwrap();
+	let val = Value::parse(
+		"{
+			backfills: {},
+			events: { audit: 'DEFINE EVENT audit ON person WHEN true THEN (CREATE audit SET person = $after.id)' },
+			fields: {
+				adult: 'DEFINE FIELD adult ON person VALUE age >= 18',
+				age: 'DEFINE FIELD age ON person TYPE int DEFAULT 0',
+			},
+			indexes: { person_name: 'DEFINE INDEX person_name ON person FIELDS name, address.country UNIQUE' },
+			lives: {},
+			rebuilds: {},
+			tables: {},
+		}",
+	);
+	assert_eq!(info, val);
+	let info: Value = response.take(1).unwrap();
+	assert_eq!(
+		info.pick(&["analyzers".into(), "simple".into()]),
+		"DEFINE ANALYZER simple TOKENIZERS BLANK,CLASS FILTERS LOWERCASE".into()
+	);
+	// The definitions are checked when the records are written
+	db.query("CREATE person:1 SET name = 'Tobie', address.country = 'GB', age = 33")
+		.await
+		.unwrap()
+		.check()
+		.unwrap();
+	db.query("CREATE person:2 SET name = 'Tobie', address.country = 'GB'")
+		.await
+		.unwrap()
+		.check()
+		.unwrap_err();
+	let adult: Option<bool> =
+		db.query("SELECT VALUE adult FROM ONLY person:1").await.unwrap().take(0).unwrap();
+	assert_eq!(adult, Some(true));
+	// An event needs statements to run
+	db.define_event("person").name("empty").await.unwrap_err();
+}
+
+#[tokio::test]
+async fn rebuild_index() {
+	use futures::StreamExt;
+
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+		DEFINE INDEX email ON person FIELDS email UNIQUE;
+		CREATE person:1 SET email = 'tobie@surrealdb.com';
+		CREATE person:2 SET email = 'jaime@surrealdb.com';
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	let progress: Vec<_> = db
+		.rebuild_index("person", "email")
+		.poll_interval(Duration::from_millis(100))
+		.progress()
+		.map(Result::unwrap)
+		.collect()
+		.await;
+	assert!(progress.iter().all(|v| v.total == 2));
+	let last = progress.last().unwrap();
+	assert_eq!((last.processed, last.total), (2, 2));
+	// The rebuilt index still enforces uniqueness
+	let sql = "CREATE person:3 SET email = 'tobie@surrealdb.com'";
+	db.query(sql).await.unwrap().check().unwrap_err();
+	// Rebuilding an index which doesn't exist fails
+	db.rebuild_index("person", "name").await.unwrap_err();
+}
+
 #[tokio::test]
 async fn select_record_ranges() {
 	let db = new_db().await;
@@ -581,6 +1180,111 @@ async fn merge_record_id() {
 	);
 }
 
+#[tokio::test]
+async fn merge_record_id_skip_none() {
+	#[derive(Debug, Serialize)]
+	struct Partial {
+		title: Option<String>,
+		marketing: Option<bool>,
+	}
+
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let record_id = ("person", "jaime");
+	let _: Option<Person> = db
+		.create(record_id)
+		.content(Person {
+			id: None,
+			title: "Founder & COO".into(),
+			name: Name {
+				first: "Jaime".into(),
+				last: "Morgan Hitchcock".into(),
+			},
+			marketing: false,
+		})
+		.await
+		.unwrap();
+	let jaime: Option<Person> = db
+		.update(record_id)
+		.merge(Partial {
+			title: None,
+			marketing: Some(true),
+		})
+		.skip_none()
+		.await
+		.unwrap();
+	let jaime = jaime.unwrap();
+	assert_eq!(jaime.title, "Founder & COO");
+	assert!(jaime.marketing);
+}
+
+#[tokio::test]
+async fn update_record_if_matches() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let record_id = ("user", "john");
+	let current: Value = db
+		.create(record_id)
+		.content(Record {
+			name: "John Doe",
+		})
+		.await
+		.unwrap();
+	let checksum = current.checksum();
+	let record: Option<RecordName> = db
+		.update(record_id)
+		.if_matches(&checksum)
+		.merge(json!({ "name": "John Smith" }))
+		.await
+		.unwrap();
+	assert_eq!(record.unwrap().name, "John Smith");
+	// The record has changed since the checksum was computed
+	let record: Option<RecordName> = db
+		.update(record_id)
+		.if_matches(&checksum)
+		.merge(json!({ "name": "Johnny" }))
+		.await
+		.unwrap();
+	assert!(record.is_none());
+	let record: Option<RecordName> = db.select(record_id).await.unwrap();
+	assert_eq!(record.unwrap().name, "John Smith");
+}
+
+#[tokio::test]
+async fn write_records_with_conditions_concurrently() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let record_id = ("user", "john");
+	// Only one of the writers which create the same record wins
+	let creates = (0..8).map(|i| {
+		let db = &db;
+		async move {
+			db.create::<Option<RecordName>>(record_id)
+				.if_not_exists()
+				.content(json!({ "name": format!("John {i}") }))
+				.await
+		}
+	});
+	let created = futures::future::try_join_all(creates).await.unwrap();
+	assert_eq!(created.iter().flatten().count(), 1);
+	// Only one of the writers which checked the same version of the record wins
+	let current: Value = db.select(record_id).await.unwrap();
+	let checksum = current.checksum();
+	let updates = (0..8).map(|i| {
+		let (db, checksum) = (&db, &checksum);
+		async move {
+			db.update::<Option<RecordName>>(record_id)
+				.if_matches(checksum)
+				.merge(json!({ "name": format!("Johnny {i}") }))
+				.await
+		}
+	});
+	let updated = futures::future::try_join_all(updates).await.unwrap();
+	assert_eq!(updated.iter().flatten().count(), 1);
+	let record: Option<RecordName> = db.select(record_id).await.unwrap();
+	assert_eq!(record.unwrap().name, updated.into_iter().flatten().next().unwrap().name);
+}
+
 #[tokio::test]
 async fn patch_record_id() {
 	#[derive(Debug, Deserialize, Eq, PartialEq)]
@@ -619,6 +1323,75 @@ async fn patch_record_id() {
 	);
 }
 
+#[tokio::test]
+async fn patch_record_id_with_operations() {
+	#[derive(Debug, Deserialize, Eq, PartialEq)]
+	struct Record {
+		id: Thing,
+		tags: Vec<String>,
+		main_tag: String,
+	}
+
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let id = "john";
+	let _: Option<RecordId> = db
+		.create(("user", id))
+		.content(json!({
+			"active": true,
+			"skills": ["rust"]
+		}))
+		.await
+		.unwrap();
+	let _: Option<Record> = db
+		.update(("user", id))
+		.patch([
+			PatchOp::test(path!["active"], true),
+			PatchOp::r#move(path!["skills"], path!["tags"]),
+			PatchOp::copy(path!["tags", 0], path!["main_tag"]),
+			PatchOp::remove(path!["active"]),
+		])
+		.await
+		.unwrap();
+	let value: Option<Record> = db.select(("user", id)).await.unwrap();
+	assert_eq!(
+		value,
+		Some(Record {
+			id: thing(&format!("user:{id}")).unwrap(),
+			tags: vec!["rust".to_owned()],
+			main_tag: "rust".to_owned(),
+		})
+	);
+}
+
+#[tokio::test]
+async fn write_records_with_return_clause() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let record: Option<serde_json::Value> = db
+		.create(("user", "john"))
+		.return_fields(["name"])
+		.content(Record {
+			name: "John Doe",
+		})
+		.await
+		.unwrap();
+	assert_eq!(record, Some(json!({ "name": "John Doe" })));
+	let records: Vec<RecordName> = db
+		.update("user")
+		.return_none()
+		.merge(Record {
+			name: "Jane Doe",
+		})
+		.await
+		.unwrap();
+	assert!(records.is_empty());
+	let diff: Value = db.update(("user", "john")).return_diff().await.unwrap();
+	assert_eq!(diff, Value::Array(Default::default()));
+	let before: Option<RecordName> = db.delete(("user", "john")).return_before().await.unwrap();
+	assert_eq!(before.unwrap().name, "Jane Doe");
+}
+
 #[tokio::test]
 async fn delete_table() {
 	let db = new_db().await;
@@ -696,6 +1469,107 @@ async fn delete_record_range() {
 	);
 }
 
+#[tokio::test]
+async fn dry_run_writes() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	db.query("CREATE user:john SET name = 'John', age = 30").await.unwrap().check().unwrap();
+	let changes: Value = db
+		.update(Resource::from(("user", "john")))
+		.merge(json!({ "age": 31 }))
+		.dry_run()
+		.await
+		.unwrap();
+	let Value::Array(changes) = changes else {
+		panic!("expected the changes to the record, found {changes}");
+	};
+	assert_eq!(changes.len(), 1);
+	let changes: Vec<Value> = db.delete("user").dry_run().await.unwrap();
+	assert_eq!(changes.len(), 1);
+	let changes: Vec<Value> = db.create("user").dry_run().await.unwrap();
+	assert_eq!(changes.len(), 1);
+	let mut response = db.query("DELETE user; CREATE user:jane").dry_run().await.unwrap();
+	let deleted: Vec<Value> = response.take(0).unwrap();
+	assert_eq!(deleted.len(), 1);
+	let created: Vec<Value> = response.take(1).unwrap();
+	assert_eq!(created.len(), 1);
+	// Nothing was written
+	let mut response = db.query("SELECT VALUE age FROM user").await.unwrap();
+	let ages: Vec<i64> = response.take(0).unwrap();
+	assert_eq!(ages, vec![30]);
+}
+
+#[tokio::test]
+async fn apply_and_revert_diffs() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let before: Option<serde_json::Value> = db
+		.create(("user", "john"))
+		.content(json!({ "name": "John", "tags": ["a", "b"] }))
+		.await
+		.unwrap();
+	let mut cached = before.unwrap();
+	let original = cached.clone();
+	let diff: Option<Diff> = db
+		.update(("user", "john"))
+		.merge(json!({ "name": "Johnny", "tags": ["a"], "age": 30 }))
+		.return_diff()
+		.await
+		.unwrap();
+	let diff = diff.unwrap();
+	diff.apply(&mut cached).unwrap();
+	let after: Option<serde_json::Value> = db.select(("user", "john")).await.unwrap();
+	assert_eq!(cached, after.unwrap());
+	diff.revert(&mut cached).unwrap();
+	assert_eq!(cached, original);
+	// Deleting a record replaces it as a whole
+	let diffs: Vec<Diff> = db.delete("user").return_diff().await.unwrap();
+	assert_eq!(diffs.len(), 1);
+	diffs[0].apply(&mut cached).unwrap();
+	assert_eq!(cached, serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn update_with_filter() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+        CREATE user:amos SET name = 'Amos', age = 18;
+        CREATE user:jane SET name = 'Jane', age = 25;
+        CREATE user:john SET name = 'John', age = 32;
+    ";
+	db.query(sql).await.unwrap().check().unwrap();
+	let condition = field("age").gt(20).and(field("name").contains("o"));
+	let users: Vec<RecordId> =
+		db.update("user").filter(condition).merge(json!({ "senior": true })).await.unwrap();
+	assert_eq!(users.len(), 1);
+	assert_eq!(users[0].id, thing("user:john").unwrap());
+	let deleted = db.delete("user").filter(field("senior").eq(true).not()).await.unwrap();
+	assert_eq!(deleted, 2);
+}
+
+#[tokio::test]
+async fn delete_with_filter() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+        CREATE user:amos SET age = 18;
+        CREATE user:jane SET age = 25;
+        CREATE user:john SET age = 32;
+        CREATE user:zoey SET age = 47;
+    ";
+	db.query(sql).await.unwrap().check().unwrap();
+	let deleted = db.delete("user").filter("age < $cutoff").bind(("cutoff", 30)).await.unwrap();
+	assert_eq!(deleted, 2);
+	let deleted = db.delete("user").filter(field("age").gt(40)).await.unwrap();
+	assert_eq!(deleted, 1);
+	let deleted = db.delete("user").filter("age > 100").await.unwrap();
+	assert_eq!(deleted, 0);
+	let users: Vec<RecordId> = db.select("user").await.unwrap();
+	assert_eq!(users.len(), 1);
+	assert_eq!(users[0].id, thing("user:john").unwrap());
+}
+
 #[tokio::test]
 async fn changefeed() {
 	let db = new_db().await;
@@ -869,6 +1743,83 @@ async fn changefeed() {
 	);
 }
 
+#[tokio::test]
+async fn changefeed_api() {
+	use futures::StreamExt;
+	use surrealdb::method::Change;
+
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+		DEFINE TABLE user CHANGEFEED 1h;
+		CREATE user:amos SET name = 'Amos';
+		DELETE user:amos;
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	let sets = db.changes::<RecordBuf>("user").await.unwrap();
+	assert_eq!(sets.len(), 3);
+	assert!(matches!(&sets[0].changes[..], [Change::DefineTable { name }] if name == "user"));
+	assert!(
+		matches!(&sets[1].changes[..], [Change::Update(RecordBuf { name, .. })] if name == "Amos")
+	);
+	assert!(matches!(&sets[2].changes[..], [Change::Delete(id)] if id.to_string() == "user:amos"));
+	// Only newer changes are returned
+	let since = sets[2].versionstamp.next();
+	let sets = db.changes::<RecordBuf>("user").since(since).await.unwrap();
+	assert!(sets.is_empty());
+	// The stream picks up changes as they are committed
+	let mut stream = db
+		.changes::<RecordBuf>("user")
+		.since(since)
+		.poll_interval(Duration::from_millis(10))
+		.stream();
+	db.query("CREATE user:jane SET name = 'Jane'").await.unwrap().check().unwrap();
+	let set = stream.next().await.unwrap().unwrap();
+	assert!(matches!(&set.changes[..], [Change::Update(RecordBuf { name, .. })] if name == "Jane"));
+}
+
+#[tokio::test]
+async fn invalidation_events() {
+	use futures::StreamExt;
+	use surrealdb::dbs::Action;
+
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let sql = "
+		DEFINE TABLE user CHANGEFEED 1h;
+		DEFINE TABLE post CHANGEFEED 1h;
+		CREATE user:amos SET name = 'Amos';
+		CREATE post:one SET title = 'One';
+		DELETE user:amos;
+	";
+	db.query(sql).await.unwrap().check().unwrap();
+	let events = db.invalidation_events(["user", "post"]).await.unwrap();
+	let events: Vec<_> = events
+		.iter()
+		.map(|event| (event.table.as_str(), event.id.to_string(), event.action.clone()))
+		.collect();
+	assert_eq!(
+		events,
+		vec![
+			("user", "user:amos".to_owned(), Action::Update),
+			("post", "post:one".to_owned(), Action::Update),
+			("user", "user:amos".to_owned(), Action::Delete),
+		]
+	);
+	// The stream picks up changes to any of the tables as they are committed
+	let mut stream = db
+		.invalidation_events(["user", "post"])
+		.poll_interval(Duration::from_millis(10))
+		.stream();
+	for _ in 0..3 {
+		stream.next().await.unwrap().unwrap();
+	}
+	db.query("UPDATE post:one SET title = 'Uno'").await.unwrap().check().unwrap();
+	let event = stream.next().await.unwrap().unwrap();
+	assert_eq!(event.id, thing("post:one").unwrap());
+	assert_eq!(event.action, Action::Update);
+}
+
 #[tokio::test]
 async fn version() {
 	let db = new_db().await;
@@ -893,6 +1844,20 @@ async fn set_unset() {
 	assert!(name.is_none());
 }
 
+#[tokio::test]
+async fn set_protected_param() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	for key in ["auth", "scope", "token", "session"] {
+		match db.set(key, "Doe").await {
+			Err(Error::Db(DbError::InvalidParam {
+				name,
+			})) => assert_eq!(name, key),
+			result => panic!("unexpected result: {result:?}"),
+		}
+	}
+}
+
 #[tokio::test]
 async fn return_bool() {
 	let db = new_db().await;
@@ -905,3 +1870,301 @@ async fn return_bool() {
 	let value: Value = response.take(0).unwrap();
 	assert_eq!(value, vec![Value::Bool(false)].into());
 }
+
+#[tokio::test]
+async fn migrate() {
+	use surrealdb::migrations::Migration;
+	use surrealdb::migrations::Migrations;
+
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let migrations = Migrations::new()
+		.add(
+			Migration::sql(1, "create users", "DEFINE TABLE user SCHEMALESS")
+				.down_sql("REMOVE TABLE user"),
+		)
+		.add(
+			Migration::new(2, "create admin", |db| async move {
+				db.query("CREATE user:admin SET name = 'Admin'").await?.check()?;
+				Ok(())
+			})
+			.down(|db| async move {
+				db.query("DELETE user:admin").await?.check()?;
+				Ok(())
+			}),
+		);
+	let applied = "SELECT VALUE version FROM _migrations ORDER BY version";
+	// Only the pending migrations are applied
+	db.migrate(&migrations).to(1).await.unwrap();
+	let versions: Vec<u64> = db.query(applied).await.unwrap().take(0).unwrap();
+	assert_eq!(versions, vec![1]);
+	db.migrate(&migrations).await.unwrap();
+	db.migrate(&migrations).await.unwrap();
+	let versions: Vec<u64> = db.query(applied).await.unwrap().take(0).unwrap();
+	assert_eq!(versions, vec![1, 2]);
+	let user: Option<RecordName> = db.select(("user", "admin")).await.unwrap();
+	assert_eq!(user.unwrap().name, "Admin");
+	// Migrations are reverted newest first
+	db.migrate(&migrations).to(0).await.unwrap();
+	let versions: Vec<u64> = db.query(applied).await.unwrap().take(0).unwrap();
+	assert!(versions.is_empty());
+	let user: Option<RecordId> = db.select(("user", "admin")).await.unwrap();
+	assert!(user.is_none());
+	// A failed SQL migration is rolled back, along with its version
+	let failing =
+		Migrations::new().add(Migration::sql(1, "invalid", "CREATE user:one; THROW 'failed'"));
+	db.migrate(&failing).await.unwrap_err();
+	let versions: Vec<u64> = db.query(applied).await.unwrap().take(0).unwrap();
+	assert!(versions.is_empty());
+	let user: Option<RecordId> = db.select(("user", "one")).await.unwrap();
+	assert!(user.is_none());
+	// A failed Rust migration is left incomplete, and has to be resolved first
+	let failing = Migrations::new().add(Migration::new(1, "failing", |db| async move {
+		db.query("CREATE user:two").await?.check()?;
+		Err(ApiError::Query("failed".to_owned()).into())
+	}));
+	db.migrate(&failing).await.unwrap_err();
+	match db.migrate(&migrations).await.unwrap_err() {
+		Error::Api(ApiError::IncompleteMigration(1)) => {}
+		error => panic!("{:?}", error),
+	}
+	db.query("DELETE _migrations").await.unwrap().check().unwrap();
+	db.migrate(&migrations).await.unwrap();
+	// The database can't be migrated by two processes at the same time
+	db.query("CREATE _migrations_lock:lock").await.unwrap().check().unwrap();
+	match db.migrate(&migrations).to(0).await.unwrap_err() {
+		Error::Api(ApiError::MigrationLocked) => {}
+		error => panic!("{:?}", error),
+	}
+}
+
+#[cfg(feature = "macros")]
+#[tokio::test]
+async fn surreal_model() {
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	assert_eq!(UserAccount::table_name(), "user_account");
+	assert_eq!(Person::table_name(), "person");
+	// Records without an ID are created with a random one
+	let account = UserAccount {
+		id: None,
+		name: "Tobie".to_owned(),
+	};
+	let mut account = account.create(&db).await.unwrap().unwrap();
+	let id = account.id.clone().unwrap();
+	assert_eq!(id.tb, "user_account");
+	// Records are updated in place
+	account.name = "Jaime".to_owned();
+	account.update(&db).await.unwrap();
+	let found = UserAccount::find(&db, id.id.clone()).await.unwrap().unwrap();
+	assert_eq!(found.name, "Jaime");
+	assert_eq!(found.id, Some(id.clone()));
+	// Records with an ID are created with that ID
+	let account = UserAccount {
+		id: Some(thing("user_account:john").unwrap()),
+		name: "John".to_owned(),
+	};
+	account.create(&db).await.unwrap();
+	let accounts = UserAccount::all(&db).await.unwrap();
+	assert_eq!(accounts.len(), 2);
+	// References to models are resources
+	let selected: Option<UserAccount> = db.select(&account).await.unwrap();
+	assert_eq!(selected.unwrap().name, "John");
+	let deleted: Option<UserAccount> = db.delete(&account).await.unwrap();
+	assert!(deleted.is_some());
+	assert_eq!(UserAccount::all(&db).await.unwrap().len(), 1);
+	// Models without an ID can't be updated
+	let person = Person {
+		name: "Tobie".to_owned(),
+	};
+	person.create(&db).await.unwrap();
+	match person.update(&db).await.unwrap_err() {
+		Error::Api(ApiError::MissingRecordId(table)) => assert_eq!(table, "person"),
+		error => panic!("{:?}", error),
+	}
+}
+
+#[tokio::test]
+async fn seed() {
+	use surrealdb::seed::Seed;
+
+	let db = new_db().await;
+	let database = Ulid::new().to_string();
+	let seed = json!({
+		"namespace": NS,
+		"database": database,
+		"schema": "DEFINE TABLE country SCHEMAFULL; DEFINE FIELD name ON country TYPE string;",
+		"tables": {
+			"person": {
+				"depends_on": ["country"],
+				"records": [{ "id": "tobie", "name": "Tobie", "country": { "$thing": "country:uk" } }],
+			},
+			"country": {
+				"records": [{ "id": "uk", "name": "United Kingdom" }],
+			},
+		},
+	});
+	let seed = Seed::parse_json(&seed.to_string()).unwrap();
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	// Seeding the database again leaves it in the same state
+	db.seed(seed.clone()).await.unwrap();
+	db.seed(seed).await.unwrap();
+	// The client is not switched to the seeded database
+	let people: Vec<RecordName> = db.select("person").await.unwrap();
+	assert!(people.is_empty());
+	db.use_db(&database).await.unwrap();
+	let people: Vec<RecordName> = db.select("person").await.unwrap();
+	assert_eq!(people.len(), 1);
+	let countries: Vec<RecordName> = db.select("country").await.unwrap();
+	assert_eq!(countries.len(), 1);
+	let mut response = db.query("SELECT VALUE country.name FROM person:tobie").await.unwrap();
+	let names: Vec<String> = response.take(0).unwrap();
+	assert_eq!(names, vec!["United Kingdom".to_owned()]);
+	// Records which don't match the schema are not written
+	let seed = json!({
+		"tables": {
+			"country": { "records": [{ "id": "fr", "name": 1 }, { "id": "de", "name": "Germany" }] },
+		},
+	});
+	let seed = Seed::parse_json(&seed.to_string()).unwrap();
+	db.seed(seed).await.unwrap_err();
+	let countries: Vec<RecordName> = db.select("country").await.unwrap();
+	assert_eq!(countries.len(), 1);
+}
+
+#[tokio::test]
+async fn unit_of_work() {
+	use surrealdb::unit_of_work::Ref;
+	use surrealdb::unit_of_work::UnitOfWork;
+
+	#[derive(Debug, Serialize)]
+	struct Post<'a> {
+		title: &'a str,
+		author: Ref,
+	}
+
+	#[derive(Debug, Deserialize)]
+	struct PostAuthor {
+		author: Thing,
+	}
+
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	db.create::<Option<RecordId>>(("user", "john")).await.unwrap();
+	let mut work = UnitOfWork::new();
+	// The post is registered before its author, which is created first
+	let author = work.reserve();
+	let post = work.create(
+		"post",
+		Post {
+			title: "Hello",
+			author,
+		},
+	);
+	work.create_reserved(
+		author,
+		"user",
+		Record {
+			name: "Tobie",
+		},
+	);
+	work.relate(author, "wrote", post, ());
+	work.merge(post, json!({ "published": true }));
+	work.delete(("user", "john"));
+	let committed = db.submit(work).await.unwrap();
+	assert_eq!(committed[author].tb, "user");
+	assert_eq!(committed[post].tb, "post");
+	let found: Option<PostAuthor> = db.select(&committed[post]).await.unwrap();
+	assert_eq!(found.unwrap().author, committed[author]);
+	let sql = "SELECT VALUE ->wrote->post FROM $author";
+	let mut response = db.query(sql).bind(("author", &committed[author])).await.unwrap();
+	let posts: Vec<Vec<Thing>> = response.take(0).unwrap();
+	assert_eq!(posts, vec![vec![committed[post].clone()]]);
+	let john: Option<RecordId> = db.select(("user", "john")).await.unwrap();
+	assert!(john.is_none());
+	// Failed writes roll back the whole unit of work
+	db.query("DEFINE INDEX name ON user FIELDS name UNIQUE").await.unwrap().check().unwrap();
+	let mut work = UnitOfWork::new();
+	work.create(
+		"user",
+		Record {
+			name: "Jaime",
+		},
+	);
+	work.create(
+		"user",
+		Record {
+			name: "Jaime",
+		},
+	);
+	db.submit(work).await.unwrap_err();
+	let users: Vec<RecordId> = db.select("user").await.unwrap();
+	assert_eq!(users.len(), 1);
+	// References which are never created are rejected
+	let mut work = UnitOfWork::new();
+	let missing = work.reserve();
+	work.relate(missing, "wrote", committed[post].clone(), ());
+	match db.submit(work).await.unwrap_err() {
+		Error::Api(ApiError::UndefinedRef(_)) => {}
+		error => panic!("{:?}", error),
+	}
+}
+
+#[cfg(feature = "macros")]
+#[tokio::test]
+async fn surreal_model_links() {
+	#[derive(Debug, Deserialize)]
+	struct StoredBook {
+		author: Thing,
+		reviewers: Vec<Thing>,
+	}
+
+	let db = new_db().await;
+	db.use_ns(NS).use_db(Ulid::new().to_string()).await.unwrap();
+	let reviewer = Author {
+		id: None,
+		name: "Jaime".to_owned(),
+	};
+	let reviewer = reviewer.create(&db).await.unwrap().unwrap();
+	let book = Book {
+		id: None,
+		title: "The Hobbit".to_owned(),
+		author: Author {
+			id: None,
+			name: "Tobie".to_owned(),
+		},
+		reviewers: vec![
+			reviewer,
+			Author {
+				id: None,
+				name: "John".to_owned(),
+			},
+		],
+	};
+	// Linked records without an ID are created along with the record
+	let mut book = book.create(&db).await.unwrap().unwrap();
+	assert_eq!(book.author.name, "Tobie");
+	let author = book.author.id.clone().unwrap();
+	assert_eq!(author.tb, "author");
+	assert_eq!(book.reviewers.len(), 2);
+	assert_eq!(Author::all(&db).await.unwrap().len(), 3);
+	// Only the IDs of the linked records are stored
+	let id = book.id.clone().unwrap();
+	let stored: Option<StoredBook> = db.select(&id).await.unwrap();
+	let stored = stored.unwrap();
+	assert_eq!(stored.author, author);
+	assert_eq!(stored.reviewers.len(), 2);
+	// Linked records are fetched when the record is read
+	book.title = "The Lord of the Rings".to_owned();
+	book.reviewers.push(Author {
+		id: None,
+		name: "Jane".to_owned(),
+	});
+	book.update(&db).await.unwrap();
+	let book = Book::find(&db, id.id).await.unwrap().unwrap();
+	assert_eq!(book.title, "The Lord of the Rings");
+	assert_eq!(book.author.name, "Tobie");
+	let reviewers: Vec<_> = book.reviewers.iter().map(|r| r.name.as_str()).collect();
+	assert_eq!(reviewers, vec!["Jaime", "John", "Jane"]);
+	assert_eq!(Author::all(&db).await.unwrap().len(), 4);
+}
@@ -2,7 +2,9 @@ mod parse;
 use parse::Parse;
 mod helpers;
 use helpers::new_ds;
+use std::time::Duration;
 use surrealdb::dbs::Session;
+use surrealdb::dbs::WriteLimit;
 use surrealdb::err::Error;
 use surrealdb::iam::Role;
 use surrealdb::sql::Part;
@@ -671,3 +673,32 @@ async fn check_permissions_auth_disabled() {
 		assert!(res.unwrap() != Value::parse("[]"), "{}", "anonymous user should be able to create a new record if the table exists and grants full permissions");
 	}
 }
+
+#[tokio::test]
+async fn create_with_write_limit() -> Result<(), Error> {
+	let limit = WriteLimit::new(Duration::from_secs(60)).with_writes(2);
+	let dbs = new_ds().await?.with_write_limit("log", limit);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "
+		CREATE log:1;
+		CREATE log:2;
+		CREATE log:3;
+		CREATE person:1;
+		SELECT VALUE id FROM log;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	res.remove(0).result?;
+	res.remove(0).result?;
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::TableWriteLimitExceeded { .. })));
+	// Other tables are not limited
+	res.remove(0).result?;
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[log:1, log:2]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
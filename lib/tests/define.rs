@@ -5,10 +5,11 @@ mod helpers;
 use helpers::*;
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
-use surrealdb::iam::Role;
+use surrealdb::iam::{Actor, Auth, Level, Role};
 use surrealdb::sql::Idiom;
 use surrealdb::sql::{Part, Value};
 
@@ -85,6 +86,7 @@ async fn define_statement_function() -> Result<(), Error> {
 	let val = Value::parse(
 		"{
 			analyzers: {},
+			tasks: {},
 			tokens: {},
 			functions: { test: 'DEFINE FUNCTION fn::test($first: string, $last: string) { RETURN $first + $last; }' },
 			params: {},
@@ -100,6 +102,130 @@ async fn define_statement_function() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_function_security() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE audit PERMISSIONS NONE;
+		DEFINE FUNCTION fn::log($msg: string) {
+			CREATE audit SET msg = $msg, user = $auth.id;
+			RETURN true;
+		} SECURITY DEFINER PERMISSIONS WHERE $auth.id != NONE;
+		DEFINE FUNCTION fn::direct($msg: string) {
+			CREATE audit SET msg = $msg, user = $auth.id;
+			RETURN true;
+		} SECURITY INVOKER;
+	";
+	let dbs = new_ds().await?.with_auth_enabled(true);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	//
+	let sql = "
+		RETURN fn::log('definer');
+		RETURN fn::direct('invoker');
+	";
+	let ses = Session::for_scope("test", "test", "test", Value::parse("user:test"));
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result?;
+		assert_eq!(tmp, Value::Bool(true));
+	}
+	//
+	let sql = "
+		SELECT msg, user FROM audit;
+		INFO FOR DB;
+	";
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	// Only the function with definer rights could write to the table
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ msg: 'definer', user: user:test }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let log = tmp.pick(&[Part::from("functions"), Part::from("log")]).to_string();
+	assert!(log.ends_with("} SECURITY DEFINER PERMISSIONS WHERE $auth.id != NONE'"), "{log}");
+	let direct = tmp.pick(&[Part::from("functions"), Part::from("direct")]).to_string();
+	assert!(!direct.contains("SECURITY"), "{direct}");
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_function_security_definer_rights() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE secret PERMISSIONS NONE;
+		CREATE secret:1 SET value = 'hidden';
+		DEFINE USER writer ON DATABASE PASSWORD 'secret' ROLES EDITOR;
+	";
+	let dbs = new_ds().await?.with_auth_enabled(true);
+	let owner = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &owner, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	for _ in 0..3 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The functions are defined by the database user
+	let sql = "
+		DEFINE FUNCTION fn::peek() { RETURN (SELECT VALUE value FROM secret); } SECURITY DEFINER;
+		DEFINE FUNCTION fn::poke() { CREATE secret:2 SET value = 'leaked'; } SECURITY DEFINER;
+	";
+	let level = Level::Database("test".to_owned(), "test".to_owned());
+	let writer = Session {
+		au: Arc::new(Auth::new(Actor::new("writer".to_owned(), vec![Role::Editor], level))),
+		..Session::default()
+	}
+	.with_ns("test")
+	.with_db("test");
+	let res = &mut dbs.execute(sql, &writer, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	for _ in 0..2 {
+		let tmp = res.remove(0).result;
+		assert!(tmp.is_ok());
+	}
+	// The invoker can't access the table, but the definer can
+	let sql = "
+		SELECT VALUE value FROM secret;
+		RETURN fn::peek();
+	";
+	let scope = Session::for_scope("test", "test", "test", Value::parse("user:test"));
+	let res = &mut dbs.execute(sql, &scope, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[]"));
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("['hidden']"));
+	// The functions follow the current rights of the definer, who can no longer write
+	let sql = "DEFINE USER writer ON DATABASE PASSWORD 'secret' ROLES VIEWER;";
+	dbs.execute(sql, &owner, None).await?.remove(0).result?;
+	let res = &mut dbs.execute("RETURN fn::poke();", &scope, None).await?;
+	assert_eq!(res.len(), 1);
+	res.remove(0).result?;
+	let res = &mut dbs.execute("SELECT VALUE value FROM secret;", &owner, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("['hidden']"));
+	// Once the definer is removed, the functions don't run at all
+	dbs.execute("REMOVE USER writer ON DATABASE;", &owner, None).await?.remove(0).result?;
+	let res = &mut dbs.execute("RETURN fn::peek();", &scope, None).await?;
+	assert_eq!(res.len(), 1);
+	assert!(res.remove(0).result.is_err());
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_table_drop() -> Result<(), Error> {
 	let sql = "
@@ -118,6 +244,7 @@ async fn define_statement_table_drop() -> Result<(), Error> {
 	let val = Value::parse(
 		"{
 			analyzers: {},
+			tasks: {},
 			tokens: {},
 			functions: {},
 			params: {},
@@ -149,6 +276,7 @@ async fn define_statement_table_schemaless() -> Result<(), Error> {
 	let val = Value::parse(
 		"{
 			analyzers: {},
+			tasks: {},
 			tokens: {},
 			functions: {},
 			params: {},
@@ -184,6 +312,7 @@ async fn define_statement_table_schemafull() -> Result<(), Error> {
 	let val = Value::parse(
 		"{
 			analyzers: {},
+			tasks: {},
 			tokens: {},
 			functions: {},
 			params: {},
@@ -215,6 +344,7 @@ async fn define_statement_table_schemaful() -> Result<(), Error> {
 	let val = Value::parse(
 		"{
 			analyzers: {},
+			tasks: {},
 			tokens: {},
 			functions: {},
 			params: {},
@@ -254,6 +384,7 @@ async fn define_statement_table_foreigntable() -> Result<(), Error> {
 	let val = Value::parse(
 		"{
 			analyzers: {},
+			tasks: {},
 			tokens: {},
 			functions: {},
 			params: {},
@@ -270,11 +401,13 @@ async fn define_statement_table_foreigntable() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: {},
 			tables: { view: 'DEFINE TABLE view SCHEMALESS AS SELECT count() FROM test GROUP ALL' },
 			indexes: {},
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -286,6 +419,7 @@ async fn define_statement_table_foreigntable() -> Result<(), Error> {
 	let val = Value::parse(
 		"{
 			analyzers: {},
+			tasks: {},
 			tokens: {},
 			functions: {},
 			params: {},
@@ -301,11 +435,13 @@ async fn define_statement_table_foreigntable() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: {},
 			tables: {},
 			indexes: {},
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -313,6 +449,107 @@ async fn define_statement_table_foreigntable() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_task() -> Result<(), Error> {
+	let sql = "
+		DEFINE TASK counter EVERY 1h THEN (UPDATE counter:one SET runs += 1);
+		INFO FOR DB;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			analyzers: {},
+			tasks: { counter: 'DEFINE TASK counter EVERY 1h THEN (UPDATE counter:one SET runs += 1)' },
+			tokens: {},
+			functions: {},
+			params: {},
+			scopes: {},
+			tables: {},
+			users: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	// The task runs once when it is due, and then after each interval
+	let ts = 1_700_000_000;
+	dbs.tick_at(ts).await?;
+	dbs.tick_at(ts + 60).await?;
+	let sql = "SELECT VALUE runs FROM counter:one";
+	let tmp = dbs.execute(sql, &ses, None).await?.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[1]"));
+	dbs.tick_at(ts + 3600).await?;
+	let tmp = dbs.execute(sql, &ses, None).await?.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[2]"));
+	// Removed tasks no longer run
+	dbs.execute("REMOVE TASK counter", &ses, None).await?.remove(0).result?;
+	dbs.tick_at(ts + 7200).await?;
+	let tmp = dbs.execute(sql, &ses, None).await?.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[2]"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "cron-schedules")]
+async fn define_statement_task_cron() -> Result<(), Error> {
+	let sql = "
+		DEFINE TASK counter CRON '0 0 * * * *' THEN (UPDATE counter:one SET runs += 1);
+		INFO FOR DB;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			analyzers: {},
+			tasks: { counter: \"DEFINE TASK counter CRON '0 0 * * * *' THEN (UPDATE counter:one SET runs += 1)\" },
+			tokens: {},
+			functions: {},
+			params: {},
+			scopes: {},
+			tables: {},
+			users: {},
+		}",
+	);
+	assert_eq!(tmp, val);
+	// The task first runs at the next time in its schedule, at the start of the hour
+	let ts = 1_700_000_000;
+	dbs.tick_at(ts).await?;
+	let sql = "SELECT VALUE runs FROM counter:one";
+	let tmp = dbs.execute(sql, &ses, None).await?.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[]"));
+	dbs.tick_at(1_700_002_800).await?;
+	dbs.tick_at(1_700_002_860).await?;
+	let tmp = dbs.execute(sql, &ses, None).await?.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[1]"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn define_statement_task_without_then() -> Result<(), Error> {
+	let sql = "DEFINE TASK counter EVERY 1h";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = dbs.execute(sql, &ses, None).await;
+	assert!(matches!(res, Err(Error::InvalidQuery(_))));
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_event() -> Result<(), Error> {
 	let sql = "
@@ -342,11 +579,13 @@ async fn define_statement_event() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: { test: 'DEFINE EVENT test ON user WHEN true THEN (CREATE activity SET user = $this, value = $after.email, action = $event)' },
 			fields: {},
 			tables: {},
 			indexes: {},
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -400,11 +639,13 @@ async fn define_statement_event_when_event() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		r#"{
+			backfills: {},
 			events: { test: "DEFINE EVENT test ON user WHEN $event = 'CREATE' THEN (CREATE activity SET user = $this, value = $after.email, action = $event)" },
 			fields: {},
 			tables: {},
 			indexes: {},
 			lives: {},
+			rebuilds: {},
 		}"#,
 	);
 	assert_eq!(tmp, val);
@@ -458,11 +699,13 @@ async fn define_statement_event_when_logic() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: { test: 'DEFINE EVENT test ON user WHEN $before.email != $after.email THEN (CREATE activity SET user = $this, value = $after.email, action = $event)' },
 			fields: {},
 			tables: {},
 			indexes: {},
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -508,11 +751,13 @@ async fn define_statement_field() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: { test: 'DEFINE FIELD test ON user' },
 			tables: {},
 			indexes: {},
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -541,11 +786,13 @@ async fn define_statement_field_type() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: { test: 'DEFINE FIELD test ON user TYPE string' },
 			tables: {},
 			indexes: {},
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -574,11 +821,13 @@ async fn define_statement_field_value() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		r#"{
+			backfills: {},
 			events: {},
 			fields: { test: "DEFINE FIELD test ON user VALUE $value OR 'GBR'" },
 			tables: {},
 			indexes: {},
 			lives: {},
+			rebuilds: {},
 		}"#,
 	);
 	assert_eq!(tmp, val);
@@ -607,11 +856,13 @@ async fn define_statement_field_assert() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: { test: 'DEFINE FIELD test ON user ASSERT $value != NONE AND $value = /[A-Z]{3}/' },
 			tables: {},
 			indexes: {},
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -640,11 +891,13 @@ async fn define_statement_field_type_value_assert() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		r#"{
+			backfills: {},
 			events: {},
 			fields: { test: "DEFINE FIELD test ON user TYPE string VALUE $value OR 'GBR' ASSERT $value != NONE AND $value = /[A-Z]{3}/" },
 			tables: {},
 			indexes: {},
 			lives: {},
+			rebuilds: {},
 		}"#,
 	);
 	assert_eq!(tmp, val);
@@ -683,11 +936,13 @@ async fn define_statement_index_single_simple() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: {},
 			tables: {},
 			indexes: { test: 'DEFINE INDEX test ON user FIELDS age' },
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -726,11 +981,13 @@ async fn define_statement_index_single() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: {},
 			tables: {},
 			indexes: { test: 'DEFINE INDEX test ON user FIELDS email' },
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -771,11 +1028,13 @@ async fn define_statement_index_multiple() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: {},
 			tables: {},
 			indexes: { test: 'DEFINE INDEX test ON user FIELDS account, email' },
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -824,11 +1083,13 @@ async fn define_statement_index_single_unique() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: {},
 			tables: {},
 			indexes: { test: 'DEFINE INDEX test ON user FIELDS email UNIQUE' },
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -883,11 +1144,13 @@ async fn define_statement_index_multiple_unique() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: {},
 			tables: {},
 			indexes: { test: 'DEFINE INDEX test ON user FIELDS account, email UNIQUE' },
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -970,11 +1233,13 @@ async fn define_statement_index_single_unique_existing() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: {},
 			tables: {},
 			indexes: {},
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -1018,11 +1283,13 @@ async fn define_statement_index_multiple_unique_existing() -> Result<(), Error>
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: {},
 			tables: {},
 			indexes: {},
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -1053,11 +1320,13 @@ async fn define_statement_index_single_unique_embedded_multiple() -> Result<(),
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: {},
 			tables: {},
 			indexes: { test: 'DEFINE INDEX test ON user FIELDS tags UNIQUE' },
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -1104,11 +1373,13 @@ async fn define_statement_index_multiple_unique_embedded_multiple() -> Result<()
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: {},
 			tables: {},
 			indexes: { test: 'DEFINE INDEX test ON user FIELDS account, tags UNIQUE' },
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -1208,6 +1479,7 @@ async fn define_statement_search_index() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: {},
 			tables: {},
@@ -1215,6 +1487,7 @@ async fn define_statement_search_index() -> Result<(), Error> {
 			SEARCH ANALYZER simple BM25(1.2,0.75) \
 			DOC_IDS_ORDER 100 DOC_LENGTHS_ORDER 100 POSTINGS_ORDER 100 TERMS_ORDER 100 HIGHLIGHTS' },
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -1244,6 +1517,31 @@ async fn define_statement_search_index() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn define_statement_token_jwks() -> Result<(), Error> {
+	let sql = "
+		DEFINE TOKEN auth0 ON SCOPE account TYPE JWKS VALUE 'https://example.com/.well-known/jwks.json' ISSUER 'https://example.com/' AUDIENCE 'surrealdb';
+		INFO FOR SCOPE account;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok(), "{:?}", tmp);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			tokens: { auth0: \"DEFINE TOKEN auth0 ON SCOPE account TYPE JWKS VALUE 'https://example.com/.well-known/jwks.json' ISSUER 'https://example.com/' AUDIENCE 'surrealdb'\" },
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn define_statement_user_root() -> Result<(), Error> {
 	let sql = "
@@ -1491,8 +1789,8 @@ async fn permissions_checks_define_function() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: { greet: \"DEFINE FUNCTION fn::greet() { RETURN 'Hello'; }\" }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: { greet: \"DEFINE FUNCTION fn::greet() { RETURN 'Hello'; }\" }, params: {  }, scopes: {  }, tables: {  }, tasks: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tasks: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1533,8 +1831,8 @@ async fn permissions_checks_define_analyzer() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: { analyzer: 'DEFINE ANALYZER analyzer TOKENIZERS BLANK' }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: { analyzer: 'DEFINE ANALYZER analyzer TOKENIZERS BLANK' }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tasks: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tasks: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1617,8 +1915,8 @@ async fn permissions_checks_define_token_db() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: { token: \"DEFINE TOKEN token ON DATABASE TYPE HS512 VALUE 'secret'\" }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tasks: {  }, tokens: { token: \"DEFINE TOKEN token ON DATABASE TYPE HS512 VALUE 'secret'\" }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tasks: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1743,8 +2041,8 @@ async fn permissions_checks_define_user_db() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: { user: \"DEFINE USER user ON DATABASE PASSHASH 'secret' ROLES VIEWER\" } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tasks: {  }, tokens: {  }, users: { user: \"DEFINE USER user ON DATABASE PASSHASH 'secret' ROLES VIEWER\" } }"],
+		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tasks: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1785,8 +2083,8 @@ async fn permissions_checks_define_scope() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: { account: 'DEFINE SCOPE account SESSION 1h' }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: { account: 'DEFINE SCOPE account SESSION 1h' }, tables: {  }, tasks: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tasks: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1827,8 +2125,8 @@ async fn permissions_checks_define_param() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: { param: \"DEFINE PARAM $param VALUE 'foo'\" }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, params: { param: \"DEFINE PARAM $param VALUE 'foo'\" }, scopes: {  }, tables: {  }, tasks: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tasks: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1866,8 +2164,8 @@ async fn permissions_checks_define_table() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: { TB: 'DEFINE TABLE TB SCHEMALESS' }, tokens: {  }, users: {  } }"],
-		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tokens: {  }, users: {  } }"]
+        vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: { TB: 'DEFINE TABLE TB SCHEMALESS' }, tasks: {  }, tokens: {  }, users: {  } }"],
+		vec!["{ analyzers: {  }, functions: {  }, params: {  }, scopes: {  }, tables: {  }, tasks: {  }, tokens: {  }, users: {  } }"]
     ];
 
 	let test_cases = [
@@ -1908,8 +2206,8 @@ async fn permissions_checks_define_event() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ events: { event: \"DEFINE EVENT event ON TB WHEN true THEN (RETURN 'foo')\" }, fields: {  }, indexes: {  }, lives: {  }, tables: {  } }"],
-		vec!["{ events: {  }, fields: {  }, indexes: {  }, lives: {  }, tables: {  } }"]
+        vec!["{ backfills: {  }, events: { event: \"DEFINE EVENT event ON TB WHEN true THEN (RETURN 'foo')\" }, fields: {  }, indexes: {  }, lives: {  }, rebuilds: {  }, tables: {  } }"],
+		vec!["{ backfills: {  }, events: {  }, fields: {  }, indexes: {  }, lives: {  }, rebuilds: {  }, tables: {  } }"]
     ];
 
 	let test_cases = [
@@ -1950,8 +2248,8 @@ async fn permissions_checks_define_field() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ events: {  }, fields: { field: 'DEFINE FIELD field ON TB' }, indexes: {  }, lives: {  }, tables: {  } }"],
-		vec!["{ events: {  }, fields: {  }, indexes: {  }, lives: {  }, tables: {  } }"]
+        vec!["{ backfills: {  }, events: {  }, fields: { field: 'DEFINE FIELD field ON TB' }, indexes: {  }, lives: {  }, rebuilds: {  }, tables: {  } }"],
+		vec!["{ backfills: {  }, events: {  }, fields: {  }, indexes: {  }, lives: {  }, rebuilds: {  }, tables: {  } }"]
     ];
 
 	let test_cases = [
@@ -1992,8 +2290,8 @@ async fn permissions_checks_define_index() {
 
 	// Define the expected results for the check statement when the test statement succeeded and when it failed
 	let check_results = [
-        vec!["{ events: {  }, fields: {  }, indexes: { index: 'DEFINE INDEX index ON TB FIELDS field' }, lives: {  }, tables: {  } }"],
-		vec!["{ events: {  }, fields: {  }, indexes: {  }, lives: {  }, tables: {  } }"]
+        vec!["{ backfills: {  }, events: {  }, fields: {  }, indexes: { index: 'DEFINE INDEX index ON TB FIELDS field' }, lives: {  }, rebuilds: {  }, tables: {  } }"],
+		vec!["{ backfills: {  }, events: {  }, fields: {  }, indexes: {  }, lives: {  }, rebuilds: {  }, tables: {  } }"]
     ];
 
 	let test_cases = [
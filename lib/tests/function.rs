@@ -1880,6 +1880,17 @@ async fn function_encoding_base64_encode() -> Result<(), Error> {
 // geo
 // --------------------------------------------------
 
+#[cfg(not(feature = "geo-functions"))]
+#[tokio::test]
+async fn function_geo_disabled() -> Result<(), Error> {
+	let sql = "RETURN geo::hash::decode('gcpvhchdswz9');";
+	check_test_is_error(sql, &[
+		"The function 'geo::hash::decode' is not enabled in this build of SurrealDB. Enable the `geo-functions` feature to use it"
+	]).await?;
+	Ok(())
+}
+
+#[cfg(feature = "geo-functions")]
 #[tokio::test]
 async fn function_parse_geo_area() -> Result<(), Error> {
 	let sql = r#"
@@ -1904,6 +1915,47 @@ async fn function_parse_geo_area() -> Result<(), Error> {
 	Ok(())
 }
 
+#[cfg(feature = "geo-functions")]
+#[tokio::test]
+async fn function_parse_geo_bbox() -> Result<(), Error> {
+	let sql = r#"
+		RETURN geo::bbox({
+			type: 'LineString',
+			coordinates: [[0, 0], [1, 0.5], [2, 1]]
+		});
+		RETURN geo::bbox((1, 2));
+		RETURN geo::bbox('test');
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			type: 'Polygon',
+			coordinates: [[[0, 0], [0, 1], [2, 1], [2, 0], [0, 0]]]
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"{
+			type: 'Polygon',
+			coordinates: [[[1, 2], [1, 2], [1, 2], [1, 2], [1, 2]]]
+		}",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	Ok(())
+}
+
+#[cfg(feature = "geo-functions")]
 #[tokio::test]
 async fn function_parse_geo_bearing() -> Result<(), Error> {
 	let sql = r#"
@@ -1930,6 +1982,7 @@ async fn function_parse_geo_bearing() -> Result<(), Error> {
 	Ok(())
 }
 
+#[cfg(feature = "geo-functions")]
 #[tokio::test]
 async fn function_parse_geo_centroid() -> Result<(), Error> {
 	let sql = r#"
@@ -1962,6 +2015,38 @@ async fn function_parse_geo_centroid() -> Result<(), Error> {
 	Ok(())
 }
 
+#[cfg(feature = "geo-functions")]
+#[tokio::test]
+async fn function_parse_geo_contains() -> Result<(), Error> {
+	let sql = r#"
+		LET $area = {
+			type: 'Polygon',
+			coordinates: [[[0, 0], [2, 0], [2, 2], [0, 2], [0, 0]]]
+		};
+		RETURN geo::contains($area, (1, 1));
+		RETURN geo::contains($area, (3, 1));
+		RETURN geo::contains((1, 1), $area);
+		RETURN geo::contains($area, 'test');
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let _ = res.remove(0).result?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::Bool(true));
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::Bool(false));
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::Bool(false));
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::None);
+	//
+	Ok(())
+}
+
+#[cfg(feature = "geo-functions")]
 #[tokio::test]
 async fn function_parse_geo_distance() -> Result<(), Error> {
 	let sql = r#"
@@ -1988,6 +2073,7 @@ async fn function_parse_geo_distance() -> Result<(), Error> {
 	Ok(())
 }
 
+#[cfg(feature = "geo-functions")]
 #[tokio::test]
 async fn function_parse_geo_hash_encode() -> Result<(), Error> {
 	let sql = r#"
@@ -2008,6 +2094,7 @@ async fn function_parse_geo_hash_encode() -> Result<(), Error> {
 	Ok(())
 }
 
+#[cfg(feature = "geo-functions")]
 #[tokio::test]
 async fn function_parse_geo_hash_decode() -> Result<(), Error> {
 	let sql = r#"
@@ -2033,6 +2120,37 @@ async fn function_parse_geo_hash_decode() -> Result<(), Error> {
 	Ok(())
 }
 
+#[cfg(feature = "geo-functions")]
+#[tokio::test]
+async fn function_parse_geo_intersects() -> Result<(), Error> {
+	let sql = r#"
+		LET $area = {
+			type: 'Polygon',
+			coordinates: [[[0, 0], [2, 0], [2, 2], [0, 2], [0, 0]]]
+		};
+		RETURN geo::intersects($area, {
+			type: 'LineString',
+			coordinates: [[1, 1], [3, 3]]
+		});
+		RETURN geo::intersects((1, 1), $area);
+		RETURN geo::intersects($area, (3, 1));
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let _ = res.remove(0).result?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::Bool(true));
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::Bool(true));
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::Bool(false));
+	//
+	Ok(())
+}
+
 // --------------------------------------------------
 // math
 // --------------------------------------------------
@@ -2278,6 +2396,62 @@ async fn function_math_median() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn function_math_merge_state() -> Result<(), Error> {
+	let sql = r#"
+		CREATE metric:1 SET shard = 'a', value = 10;
+		CREATE metric:2 SET shard = 'a', value = 20;
+		CREATE metric:3 SET shard = 'b', value = 20;
+		CREATE metric:4 SET shard = 'b', value = 40;
+		LET $states = SELECT math::count_state(value) AS count, math::sum_state(value) AS sum, math::hll_state(value) AS distinct FROM metric GROUP BY shard;
+		RETURN math::state_value(math::merge_state($states.count));
+		RETURN math::state_value(math::merge_state($states.sum));
+		RETURN math::state_value(math::merge_state($states.distinct));
+		RETURN math::state_value(math::merge_state([math::tdigest_state([1, 2]), math::tdigest_state([3, 4, 5])]), 50);
+		RETURN math::merge_state([]);
+		RETURN math::merge_state([math::count_state([1]), math::sum_state([1])]);
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 11);
+	//
+	for _ in 0..5 {
+		res.remove(0).result?;
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(4);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(90);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(3);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(3.0);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::None;
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(
+		matches!(
+			&tmp,
+			Err(e) if e.to_string() == "Incorrect arguments for function math::merge_state(). The aggregate states must all be of the same kind."
+		),
+		"{tmp:?}"
+	);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn function_math_midhinge() -> Result<(), Error> {
 	let sql = r#"
@@ -2669,6 +2843,23 @@ async fn function_math_variance() -> Result<(), Error> {
 // meta
 // --------------------------------------------------
 
+#[tokio::test]
+async fn function_parse_meta_checksum() -> Result<(), Error> {
+	let sql = r#"
+		RETURN meta::checksum({ name: "Tobie", age: 33 });
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(Value::parse("{ age: 33, name: 'Tobie' }").checksum());
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 #[tokio::test]
 async fn function_parse_meta_id() -> Result<(), Error> {
 	let sql = r#"
@@ -3152,6 +3343,104 @@ async fn function_rand_uuid_v7() -> Result<(), Error> {
 	Ok(())
 }
 
+// --------------------------------------------------
+// search
+// --------------------------------------------------
+
+#[tokio::test]
+async fn function_search_duplicates() -> Result<(), Error> {
+	let sql = r#"
+		DEFINE INDEX dedup ON post FIELDS content SIMHASH DISTANCE 3;
+		CREATE post:1 SET content = "The quick brown fox jumps over the lazy dog, and then runs far into the dark forest";
+		CREATE post:2 SET content = "The quick brown fox jumps over the lazy dog and then runs far into the dark forest!";
+		CREATE post:3 SET content = "Databases store records, and indexes make finding those records much faster";
+		RETURN search::duplicates("post", "dedup", "the quick brown fox jumps over the lazy dog, and then runs far into the dark forest").id;
+		DELETE post:2;
+		RETURN search::duplicates("post", "dedup", "the quick brown fox jumps over the lazy dog, and then runs far into the dark forest").id;
+		RETURN search::duplicates("post", "dedup", "Something else entirely");
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..4 {
+		res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[post:1, post:2]");
+	assert_eq!(tmp, val);
+	//
+	res.remove(0).result?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[post:1]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_search_minhash() -> Result<(), Error> {
+	let sql = r#"
+		RETURN array::len(search::minhash("one two three four five"));
+		RETURN array::len(search::minhash("one two three four five", 16));
+		RETURN search::minhash("one two three four five") = search::minhash("One, two, three, four, five!");
+		RETURN search::minhash("one two three four five", 0);
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(64);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(16);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(true);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::InvalidArguments { .. })));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn function_search_simhash() -> Result<(), Error> {
+	let sql = r#"
+		RETURN type::is::int(search::simhash("hello world"));
+		RETURN search::simhash("hello world") = search::simhash("Hello, World!");
+		RETURN search::simhash("hello world") = search::simhash("goodbye world");
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(true);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(true);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::from(false);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 // --------------------------------------------------
 // string
 // --------------------------------------------------
@@ -4018,6 +4307,27 @@ async fn function_string_words() -> Result<(), Error> {
 	Ok(())
 }
 
+// --------------------------------------------------
+// sys
+// --------------------------------------------------
+
+#[tokio::test]
+async fn function_sys_http_usage() -> Result<(), Error> {
+	let sql = r#"
+		RETURN sys::http_usage();
+	"#;
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 1);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ bytes: 0, calls: 0 }");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
 // --------------------------------------------------
 // time
 // --------------------------------------------------
@@ -5960,6 +6270,59 @@ pub async fn function_http_error() -> Result<(), Error> {
 	Ok(())
 }
 
+#[cfg(feature = "http")]
+#[tokio::test]
+pub async fn function_http_quota() -> Result<(), Error> {
+	use std::time::Duration;
+	use surrealdb::dbs::HttpQuota;
+	use wiremock::{
+		matchers::{method, path},
+		Mock, ResponseTemplate,
+	};
+
+	let server = wiremock::MockServer::start().await;
+	Mock::given(method("GET"))
+		.and(path("/some/path"))
+		.respond_with(ResponseTemplate::new(200).set_body_string("some text result"))
+		.expect(2)
+		.mount(&server)
+		.await;
+
+	let quota = HttpQuota::new(Duration::from_secs(60)).with_calls(2);
+	let db = new_ds().await?.with_http_quota(quota);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = format!(
+		r#"
+		RETURN http::get("{0}/some/path");
+		RETURN http::get("{0}/some/path");
+		RETURN http::get("{0}/some/path");
+		RETURN sys::http_usage();
+		"#,
+		server.uri()
+	);
+	let res = &mut db.execute(&sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	res.remove(0).result?;
+	res.remove(0).result?;
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::HttpQuotaExceeded { .. })));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ bytes: 32, calls: 2 }");
+	assert_eq!(tmp, val);
+	// Other databases have their own quota
+	let ses = Session::owner().with_ns("test").with_db("other");
+	let res = &mut db.execute("RETURN sys::http_usage()", &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ bytes: 0, calls: 0 }");
+	assert_eq!(tmp, val);
+
+	server.verify().await;
+
+	Ok(())
+}
+
 #[cfg(all(feature = "http", feature = "scripting"))]
 #[tokio::test]
 pub async fn function_http_get_from_script() -> Result<(), Error> {
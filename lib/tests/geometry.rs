@@ -470,3 +470,120 @@ async fn geometry_inner_access() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn geometry_inside_using_spatial_index() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX city_centre ON city FIELDS centre SPATIAL;
+		UPDATE city:london SET centre = (-0.118092, 51.509865);
+		UPDATE city:paris SET centre = (2.352222, 48.856613);
+		UPDATE city:tokyo SET centre = (139.691706, 35.689487);
+		SELECT id FROM city WHERE centre INSIDE {
+			type: 'Polygon',
+			coordinates: [[[-1, 51], [1, 51], [1, 52], [-1, 52], [-1, 51]]]
+		} EXPLAIN;
+		SELECT id FROM city WHERE centre INSIDE {
+			type: 'Polygon',
+			coordinates: [[[-1, 51], [1, 51], [1, 52], [-1, 52], [-1, 51]]]
+		};
+		SELECT id FROM city WHERE centre INTERSECTS {
+			type: 'Polygon',
+			coordinates: [[[-10, 40], [10, 40], [10, 60], [-10, 60], [-10, 40]]]
+		} ORDER BY id;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				detail: {
+					plan: {
+						index: 'city_centre',
+						operator: 'INSIDE',
+						value: {
+							type: 'Polygon',
+							coordinates: [[[-1, 51], [1, 51], [1, 52], [-1, 52], [-1, 51]]]
+						}
+					},
+					table: 'city',
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: city:london }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: city:london }, { id: city:paris }]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[cfg(feature = "geo-functions")]
+#[tokio::test]
+async fn geometry_functions_using_spatial_index() -> Result<(), Error> {
+	let sql = "
+		DEFINE INDEX city_centre ON city FIELDS centre SPATIAL;
+		UPDATE city:london SET centre = (-0.118092, 51.509865);
+		UPDATE city:paris SET centre = (2.352222, 48.856613);
+		UPDATE city:tokyo SET centre = (139.691706, 35.689487);
+		LET $viewport = {
+			type: 'MultiPoint',
+			coordinates: [[-1, 48], [3, 52]]
+		};
+		SELECT id FROM city WHERE geo::intersects(centre, geo::bbox($viewport)) EXPLAIN;
+		SELECT id FROM city WHERE geo::intersects(centre, geo::bbox($viewport)) ORDER BY id;
+		SELECT id FROM city WHERE geo::contains(geo::bbox($viewport), centre) EXPLAIN;
+		SELECT id FROM city WHERE geo::contains(geo::bbox($viewport), centre) ORDER BY id;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	for _ in 0..5 {
+		let _ = res.remove(0).result?;
+	}
+	//
+	for operator in ["INTERSECTS", "CONTAINS"] {
+		let tmp = res.remove(0).result?;
+		let val = Value::parse(&format!(
+			"[
+				{{
+					detail: {{
+						plan: {{
+							index: 'city_centre',
+							operator: '{operator}',
+							value: {{
+								type: 'Polygon',
+								coordinates: [[[-1, 48], [-1, 52], [3, 52], [3, 48], [-1, 48]]]
+							}}
+						}},
+						table: 'city',
+					}},
+					operation: 'Iterate Index'
+				}}
+			]"
+		));
+		assert_eq!(tmp, val);
+		//
+		let tmp = res.remove(0).result?;
+		let val = Value::parse("[{ id: city:london }, { id: city:paris }]");
+		assert_eq!(tmp, val);
+	}
+	//
+	Ok(())
+}
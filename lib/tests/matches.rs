@@ -4,6 +4,7 @@ mod helpers;
 use helpers::new_ds;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
+use surrealdb::sql::Part;
 use surrealdb::sql::Value;
 
 #[tokio::test]
@@ -310,6 +311,40 @@ async fn select_where_matches_using_index_and_score() -> Result<(), Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn select_where_matches_after_changing_bm25_parameters() -> Result<(), Error> {
+	let sql = r"
+		CREATE blog:1 SET title = 'the quick brown fox jumped over the lazy dog';
+		CREATE blog:2 SET title = 'the other animals sat there watching';
+		DEFINE ANALYZER simple TOKENIZERS blank,class;
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER simple BM25(1.2,0.75);
+		SELECT id,search::score(1) AS score FROM blog WHERE title @1@ 'animals';
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER simple BM25(2.0,0.1);
+		SELECT id,search::score(1) AS score FROM blog WHERE title @1@ 'animals';
+		ANALYZE INDEX blog_title ON blog;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 8);
+	//
+	for _ in 0..4 {
+		let _ = res.remove(0).result?;
+	}
+	let before = res.remove(0).result?;
+	let _ = res.remove(0).result?;
+	let after = res.remove(0).result?;
+	// The index is kept, but the new parameters are used for scoring
+	let id = [Part::from("id")];
+	let score = [Part::from("score")];
+	assert_eq!(before.pick(&id), Value::parse("[blog:2]"));
+	assert_eq!(after.pick(&id), Value::parse("[blog:2]"));
+	assert_ne!(after.pick(&score), before.pick(&score));
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp.pick(&[Part::from("doc_count")]), Value::from(2));
+	Ok(())
+}
+
 #[tokio::test]
 async fn select_where_matches_without_using_index_and_score() -> Result<(), Error> {
 	let sql = r"
@@ -353,3 +388,48 @@ async fn select_where_matches_without_using_index_and_score() -> Result<(), Erro
 	assert_eq!(tmp, val);
 	Ok(())
 }
+
+#[tokio::test]
+async fn select_where_matches_using_custom_analyzer() -> Result<(), Error> {
+	use surrealdb::idx::{AnalyzedTerm, Analyzer};
+	// Splits a text into overlapping pairs of characters
+	struct Bigrams;
+	impl Analyzer for Bigrams {
+		fn analyze(&self, text: &str) -> Vec<AnalyzedTerm> {
+			let chars: Vec<(usize, char)> = text.char_indices().collect();
+			chars
+				.windows(2)
+				.map(|w| {
+					let end = w[1].0 + w[1].1.len_utf8();
+					AnalyzedTerm::new(&text[w[0].0..end], w[0].0..end)
+				})
+				.collect()
+		}
+	}
+	let sql = r"
+		CREATE blog:1 SET title = '東京都に住む';
+		CREATE blog:2 SET title = '大阪に住む';
+		DEFINE INDEX blog_title ON blog FIELDS title SEARCH ANALYZER bigrams BM25 HIGHLIGHTS;
+		SELECT id, search::highlight('<em>', '</em>', 1) AS title FROM blog WHERE title @1@ '京都';
+	";
+	let dbs = new_ds().await?;
+	dbs.register_analyzer("bigrams", Bigrams);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let _ = res.remove(0).result?;
+	let _ = res.remove(0).result?;
+	let _ = res.remove(0).result?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: blog:1,
+				title: '東<em>京都</em>に住む'
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	Ok(())
+}
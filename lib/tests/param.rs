@@ -27,6 +27,7 @@ async fn define_global_param() -> Result<(), Error> {
 	let val = Value::parse(
 		"{
 			analyzers: {},
+			tasks: {},
 			tokens: {},
 			functions: {},
 			params: { test: 'DEFINE PARAM $test VALUE 12345' },
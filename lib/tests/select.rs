@@ -2,6 +2,7 @@ mod parse;
 use parse::Parse;
 mod helpers;
 use helpers::new_ds;
+use surrealdb::dbs::ResultTruncated;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
 use surrealdb::iam::Role;
@@ -1008,3 +1009,195 @@ async fn check_permissions_auth_disabled() {
 		);
 	}
 }
+
+#[tokio::test]
+async fn select_with_checksum() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie';
+		SELECT * FROM person;
+		OPTION CHECKSUM;
+		SELECT * FROM person;
+		SELECT * FROM person ORDER BY name DESC;
+		SELECT * FROM [person:tobie, person:jaime];
+		SELECT * FROM [person:jaime, person:tobie];
+		CREATE person:jaime SET name = 'Jaime';
+		SELECT * FROM [person:tobie, person:jaime];
+		SELECT * FROM [person:jaime, person:tobie];
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 9);
+	//
+	let tmp = res.remove(0);
+	assert!(tmp.checksum.is_none());
+	//
+	let tmp = res.remove(0);
+	assert!(tmp.checksum.is_none());
+	//
+	let tmp = res.remove(0);
+	assert!(tmp.checksum.is_some());
+	//
+	let tmp = res.remove(0);
+	assert!(tmp.checksum.is_some());
+	//
+	let one = res.remove(0).checksum;
+	let two = res.remove(0).checksum;
+	assert_eq!(one, two);
+	//
+	res.remove(0);
+	let one = res.remove(0).checksum;
+	let two = res.remove(0).checksum;
+	assert!(one.is_some());
+	assert_eq!(one, two);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_with_version_unsupported() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie';
+		SELECT * FROM person VERSION '2020-01-01T00:00:00Z';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::TxPointInTimeUnsupported(_))));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_with_denials() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS PERMISSIONS FOR select WHERE public = true;
+		DEFINE FIELD email ON person PERMISSIONS FOR select NONE;
+		CREATE person:tobie SET public = true, email = 'tobie@surrealdb.com';
+		CREATE person:jaime SET public = false, email = 'jaime@surrealdb.com';
+		CREATE person:john SET public = false, email = 'john@surrealdb.com';
+	";
+	let dbs = new_ds().await?.with_denials(true);
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let sql = "
+		SELECT * FROM person;
+		SELECT * FROM person:tobie;
+	";
+	let ses = Session::for_scope("test", "test", "test", Value::parse("user:test"));
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert!(res.iter().all(|v| v.denials.is_none()));
+	//
+	let ses = ses.with_denials(true);
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 2);
+	//
+	let tmp = res.remove(0);
+	let val = Value::parse("[{ id: person:tobie, public: true }]");
+	assert_eq!(tmp.result?, val);
+	let val = Value::parse(
+		"[
+			{ action: 'select', permission: 'WHERE public = true', records: 2, table: 'person' },
+			{ action: 'select', field: 'email', permission: 'NONE', records: 1, table: 'person' },
+		]",
+	);
+	assert_eq!(tmp.denials, Some(val));
+	//
+	let tmp = res.remove(0);
+	let val = Value::parse(
+		"[{ action: 'select', field: 'email', permission: 'NONE', records: 1, table: 'person' }]",
+	);
+	assert_eq!(tmp.denials, Some(val));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_with_denials_not_allowed() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE person SCHEMALESS PERMISSIONS FOR select WHERE public = true;
+		CREATE person:tobie SET public = true;
+		CREATE person:jaime SET public = false;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	// The datastore doesn't allow sessions to report denials
+	let sql = "SELECT * FROM person";
+	let ses = Session::for_scope("test", "test", "test", Value::parse("user:test"));
+	let ses = ses.with_denials(true);
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0);
+	let val = Value::parse("[{ id: person:tobie, public: true }]");
+	assert_eq!(tmp.result?, val);
+	assert_eq!(tmp.denials, None);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn select_with_max_result_size() -> Result<(), Error> {
+	let sql = "
+		CREATE |person:1..5| RETURN NONE;
+		SELECT * FROM person;
+		SELECT * FROM person LIMIT 2;
+		SELECT VALUE id FROM person;
+	";
+	let dbs = new_ds().await?.with_max_result_size(Some(3));
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	let tmp = res.remove(0);
+	assert_eq!(tmp.truncated, None);
+	assert!(tmp.result.is_ok());
+	//
+	let tmp = res.remove(0);
+	let Some(ResultTruncated {
+		returned,
+		limit,
+		..
+	}) = tmp.truncated
+	else {
+		panic!("the result was not truncated");
+	};
+	assert_eq!((returned, limit), (3, 3));
+	let val = Value::parse("[{ id: person:1 }, { id: person:2 }, { id: person:3 }]");
+	assert_eq!(tmp.result?, val);
+	//
+	let tmp = res.remove(0);
+	assert_eq!(tmp.truncated, None);
+	let val = Value::parse("[{ id: person:1 }, { id: person:2 }]");
+	assert_eq!(tmp.result?, val);
+	//
+	let tmp = res.remove(0);
+	assert!(tmp.truncated.is_some());
+	let val = Value::parse("[person:1, person:2, person:3]");
+	assert_eq!(tmp.result?, val);
+	// The limit is lifted for trusted sessions
+	let ses = ses.with_unlimited_results(true);
+	let res = &mut dbs.execute("SELECT VALUE id FROM person", &ses, None).await?;
+	let tmp = res.remove(0);
+	assert_eq!(tmp.truncated, None);
+	let val = Value::parse("[person:1, person:2, person:3, person:4, person:5]");
+	assert_eq!(tmp.result?, val);
+	// The limit does not cut the results of subqueries
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let sql = "SELECT VALUE (SELECT VALUE id FROM person) FROM person:1";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0);
+	assert_eq!(tmp.truncated, None);
+	let val = Value::parse("[[person:1, person:2, person:3, person:4, person:5]]");
+	assert_eq!(tmp.result?, val);
+	//
+	Ok(())
+}
@@ -253,6 +253,7 @@ async fn loose_mode_all_ok() -> Result<(), Error> {
 	let val = Value::parse(
 		"{
 			analyzers: {},
+			tasks: {},
 			tokens: {},
 			functions: {},
 			params: {},
@@ -266,11 +267,13 @@ async fn loose_mode_all_ok() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: { extra: 'DEFINE FIELD extra ON test VALUE true' },
 			tables: {},
 			indexes: {},
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -4,6 +4,7 @@ mod helpers;
 use helpers::new_ds;
 use surrealdb::dbs::Session;
 use surrealdb::err::Error;
+use surrealdb::kvs::Datastore;
 use surrealdb::sql::Value;
 
 #[tokio::test]
@@ -41,11 +42,13 @@ async fn define_foreign_table() -> Result<(), Error> {
 	let tmp = res.remove(0).result?;
 	let val = Value::parse(
 		"{
+			backfills: {},
 			events: {},
 			fields: {},
 			tables: { person_by_age: 'DEFINE TABLE person_by_age SCHEMALESS AS SELECT count(), age, math::sum(age) AS total, math::mean(score) AS average FROM person GROUP BY age' },
 			indexes: {},
 			lives: {},
+			rebuilds: {},
 		}",
 	);
 	assert_eq!(tmp, val);
@@ -106,3 +109,496 @@ async fn define_foreign_table() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn table_with_ttl() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE session SCHEMALESS TTL 1h;
+		CREATE session:one SET user = 'tobie';
+		CREATE session:two SET user = 'jaime';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	for res in res.drain(..) {
+		res.result?;
+	}
+	let now = chrono::Utc::now().timestamp() as u64;
+	// Records which have not expired are kept
+	dbs.tick_at(now + 60).await?;
+	let sql = "SELECT VALUE id FROM session";
+	let tmp = dbs.execute(sql, &ses, None).await?.remove(0).result?;
+	let val = Value::parse("[session:one, session:two]");
+	assert_eq!(tmp, val);
+	// Deleted records are removed from the expiry index
+	dbs.execute("DELETE session:one", &ses, None).await?.remove(0).result?;
+	// Expired records are deleted
+	dbs.tick_at(now + 7200).await?;
+	let tmp = dbs.execute(sql, &ses, None).await?.remove(0).result?;
+	let val = Value::parse("[]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_with_retention() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE event SCHEMALESS RETENTION 30d ON FIELD created_at ARCHIVE TO event_archive;
+		DEFINE INDEX event_created ON event FIELDS created_at;
+		DEFINE TABLE log SCHEMALESS RETENTION 1d ON FIELD created_at;
+		CREATE event:old SET created_at = time::now() - 60d;
+		CREATE event:new SET created_at = time::now();
+		CREATE log:old SET created_at = time::now() - 2d;
+		CREATE log:new SET created_at = time::now();
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	for res in res.drain(..) {
+		res.result?;
+	}
+	let now = chrono::Utc::now().timestamp() as u64;
+	dbs.tick_at(now).await?;
+	// Old records are archived
+	let sql = "SELECT VALUE id FROM event; SELECT VALUE id FROM event_archive";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[event:new]");
+	assert_eq!(tmp, val);
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[event_archive:old]");
+	assert_eq!(tmp, val);
+	// Old records are deleted
+	let sql = "SELECT VALUE id FROM log";
+	let tmp = dbs.execute(sql, &ses, None).await?.remove(0).result?;
+	let val = Value::parse("[log:new]");
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_with_tiering() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE event SCHEMALESS TIERING AFTER 30d ON FIELD created_at;
+		CREATE event:old SET created_at = time::now() - 60d, name = 'old';
+		CREATE event:new SET created_at = time::now(), name = 'new';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 3);
+	for res in res.drain(..) {
+		res.result?;
+	}
+	let now = chrono::Utc::now().timestamp() as u64;
+	dbs.tick_at(now).await?;
+	// Only the old record is moved to cold storage
+	let mut tx = dbs.transaction(false, false).await?;
+	let old = surrealdb::key::table::cd::new("test", "test", "event", &"old".into());
+	let new = surrealdb::key::table::cd::new("test", "test", "event", &"new".into());
+	assert!(tx.exi(old.clone()).await?);
+	assert!(!tx.exi(new).await?);
+	tx.cancel().await?;
+	// Cold records can still be queried
+	let sql = "SELECT VALUE name FROM event ORDER BY name; SELECT VALUE name FROM event:old";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("['new', 'old']");
+	assert_eq!(tmp, val);
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("['old']");
+	assert_eq!(tmp, val);
+	// Cold records can still be updated
+	let sql = "UPDATE event:old SET name = 'older'; SELECT VALUE name FROM event:old";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	res.remove(0).result?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("['older']");
+	assert_eq!(tmp, val);
+	// Updated records are removed from cold storage
+	let mut tx = dbs.transaction(false, false).await?;
+	assert!(!tx.exi(old).await?);
+	tx.cancel().await?;
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_with_rollup() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE metric SCHEMALESS CHANGEFEED 1h;
+		DEFINE TABLE metric_hourly SCHEMALESS ROLLUP FROM metric EVERY 1h ON FIELD at SUM value;
+		CREATE metric:1 SET at = d'2024-01-01T10:15:00Z', value = 2;
+		CREATE metric:2 SET at = d'2024-01-01T10:45:00Z', value = 3;
+		CREATE metric:3 SET at = d'2024-01-01T11:05:00Z', value = 4;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	for res in res.drain(..) {
+		res.result?;
+	}
+	let now = chrono::Utc::now().timestamp() as u64;
+	dbs.tick_at(now).await?;
+	let sql = "SELECT bucket, count, sum FROM metric_hourly ORDER BY bucket";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ bucket: d'2024-01-01T10:00:00Z', count: 2, sum: { value: 5 } },
+			{ bucket: d'2024-01-01T11:00:00Z', count: 1, sum: { value: 4 } }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Updates and deletions move the records between buckets
+	let sql = "
+		UPDATE metric:3 SET at = d'2024-01-01T10:30:00Z', value = 1;
+		DELETE metric:1;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	for res in res.drain(..) {
+		res.result?;
+	}
+	// Each change is only applied once
+	dbs.tick_at(now).await?;
+	dbs.tick_at(now).await?;
+	let sql = "SELECT bucket, count, sum FROM metric_hourly ORDER BY bucket";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ bucket: d'2024-01-01T10:00:00Z', count: 2, sum: { value: 4 } }
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_temporary() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE staging TEMPORARY SCHEMALESS;
+		CREATE staging:one SET value = 1;
+		CREATE staging:two SET value = 2;
+		BEGIN;
+		CREATE staging:three SET value = 3;
+		CANCEL;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	for res in res.drain(..) {
+		res.result?;
+	}
+	// The records are kept for the rest of the session
+	let sql = "SELECT * FROM staging";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: staging:one, value: 1 },
+			{ id: staging:two, value: 2 }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The records are not visible to other sessions
+	let other = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &other, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[]"));
+	// The records are removed along with the table
+	let sql = "
+		REMOVE TABLE staging;
+		DEFINE TABLE staging TEMPORARY SCHEMALESS;
+		SELECT * FROM staging;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(2).result?;
+	assert_eq!(tmp, Value::parse("[]"));
+	// Deleting every record only changes the keyspace of the session
+	let sql = "CREATE staging:four SET value = 4";
+	let res = &mut dbs.execute(sql, &other, None).await?;
+	res.remove(0).result?;
+	let sql = "
+		CREATE staging:five SET value = 5;
+		DELETE staging;
+		SELECT * FROM staging;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(2).result?;
+	assert_eq!(tmp, Value::parse("[]"));
+	let sql = "SELECT * FROM staging";
+	let res = &mut dbs.execute(sql, &other, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("[{ id: staging:four, value: 4 }]"));
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn table_cache_full() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE country CACHE FULL SCHEMALESS;
+		CREATE country:uk SET name = 'United Kingdom';
+		CREATE country:fr SET name = 'France';
+		CREATE person:tobie SET country = country:uk;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	for res in res.drain(..) {
+		res.result?;
+	}
+	// The records are read through the cache
+	let sql = "
+		SELECT VALUE country.name FROM person:tobie;
+		SELECT * FROM country;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	assert_eq!(tmp, Value::parse("['United Kingdom']"));
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: country:fr, name: 'France' },
+			{ id: country:uk, name: 'United Kingdom' }
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Changes to the records are seen once they are committed
+	let sql = "
+		UPDATE country:uk SET name = 'UK';
+		DELETE country:fr;
+		SELECT VALUE country.name FROM person:tobie;
+		SELECT VALUE name FROM country;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(2).result?;
+	assert_eq!(tmp, Value::parse("['UK']"));
+	let tmp = res.remove(2).result?;
+	assert_eq!(tmp, Value::parse("['UK']"));
+	// The records are removed along with the table
+	let sql = "
+		REMOVE TABLE country;
+		SELECT * FROM country;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(1).result?;
+	assert_eq!(tmp, Value::parse("[]"));
+	//
+	Ok(())
+}
+
+/// Counts the values which are shared by the records of the page table
+async fn shared_values(dbs: &Datastore) -> Result<usize, Error> {
+	let mut tx = dbs.transaction(false, false).await?;
+	let beg = b"/*test\0*test\0*page\0!cs".to_vec();
+	let end = b"/*test\0*test\0*page\0!cs\xff".to_vec();
+	let res = tx.scan(beg..end, 100).await?;
+	tx.cancel().await?;
+	Ok(res.len())
+}
+
+#[tokio::test]
+async fn table_deduplicate() -> Result<(), Error> {
+	let sql = "
+		DEFINE TABLE page DEDUPLICATE 64 SCHEMALESS;
+		LET $body = string::repeat('lorem ipsum ', 20);
+		CREATE page:one SET title = 'One', body = $body;
+		CREATE page:two SET title = 'Two', body = $body;
+		CREATE page:three SET title = 'Three', body = 'short';
+		SELECT title, string::len(body) AS len FROM page ORDER BY title;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 6);
+	//
+	for _ in 0..5 {
+		res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ title: 'One', len: 240 },
+			{ title: 'Three', len: 5 },
+			{ title: 'Two', len: 240 },
+		]",
+	);
+	assert_eq!(tmp, val);
+	// The large value is stored once
+	assert_eq!(shared_values(&dbs).await?, 1);
+	// The value is kept until it is no longer referenced
+	let sql = "
+		UPDATE page:one SET body = 'changed';
+		SELECT VALUE string::len(body) FROM page:two;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(1).result?;
+	assert_eq!(tmp, Value::parse("[240]"));
+	assert_eq!(shared_values(&dbs).await?, 1);
+	dbs.execute("DELETE page:two", &ses, None).await?;
+	assert_eq!(shared_values(&dbs).await?, 0);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn alter_table_add_field_with_backfill() -> Result<(), Error> {
+	let sql = "
+		CREATE person:one SET name = 'Tobie';
+		CREATE person:two SET name = 'Jaime', active = false;
+		ALTER TABLE person ADD FIELD active TYPE bool DEFAULT true BACKFILL;
+		ALTER TABLE person ADD FIELD active DEFAULT false;
+		INFO FOR TABLE person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	for _ in 0..3 {
+		res.remove(0).result?;
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::FdAlreadyExists { .. })));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ active: { cursor: NONE, processed: 0 } }");
+	assert_eq!(tmp.pick(&["backfills".into()]), val);
+	// Existing records are backfilled on the next tick
+	let now = chrono::Utc::now().timestamp() as u64;
+	dbs.tick_at(now).await?;
+	let sql = "SELECT id, active FROM person; INFO FOR TABLE person;";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{ id: person:one, active: true },
+			{ id: person:two, active: false },
+		]",
+	);
+	assert_eq!(tmp, val);
+	// Finished backfills are removed
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{}");
+	assert_eq!(tmp.pick(&["backfills".into()]), val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rebuild_index_in_background() -> Result<(), Error> {
+	let sql = "
+		CREATE person:one SET name = 'Tobie';
+		CREATE person:two SET name = 'Jaime';
+		DEFINE INDEX name ON person FIELDS name;
+		REBUILD INDEX name ON person;
+		REBUILD INDEX email ON person;
+		INFO FOR TABLE person;
+		SELECT id FROM person WHERE name = 'Tobie' EXPLAIN;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 7);
+	//
+	for _ in 0..4 {
+		res.remove(0).result?;
+	}
+	//
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::IxNotFound { .. })));
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{ name: { cursor: NONE, processed: 0, total: 0 } }");
+	assert_eq!(tmp.pick(&["rebuilds".into()]), val);
+	// The index isn't used until it has been rebuilt
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ detail: { table: 'person' }, operation: 'Iterate Table' }]");
+	assert_eq!(tmp, val);
+	// Records written during the rebuild are indexed
+	dbs.execute("CREATE person:three SET name = 'Tobie'", &ses, None).await?;
+	// Existing records are counted, indexed and pruned on the next ticks
+	let now = chrono::Utc::now().timestamp() as u64;
+	for _ in 0..3 {
+		dbs.tick_at(now).await?;
+	}
+	let sql = "
+		INFO FOR TABLE person;
+		SELECT id FROM person WHERE name = 'Tobie';
+		SELECT id FROM person WHERE name = 'Tobie' EXPLAIN;
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	// Finished rebuilds are removed
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{}");
+	assert_eq!(tmp.pick(&["rebuilds".into()]), val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("[{ id: person:one }, { id: person:three }]");
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				detail: {
+					plan: { index: 'name', operator: '=', value: 'Tobie' },
+					table: 'person'
+				},
+				operation: 'Iterate Index'
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
+
+#[tokio::test]
+async fn rebuild_unique_index_keeps_constraint() -> Result<(), Error> {
+	let sql = "
+		CREATE person:one SET email = 'tobie@surrealdb.com';
+		DEFINE INDEX email ON person FIELDS email UNIQUE;
+		REBUILD INDEX email ON person;
+		CREATE person:two SET email = 'tobie@surrealdb.com';
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 4);
+	//
+	for _ in 0..3 {
+		res.remove(0).result?;
+	}
+	// The entries of the index are still enforced while it is rebuilt
+	let tmp = res.remove(0).result;
+	assert!(matches!(tmp, Err(Error::IndexExists { .. })));
+	// The rebuild completes, and the index follows the records which were updated meanwhile
+	dbs.execute("UPDATE person:one SET email = 'tobie@example.com'", &ses, None).await?;
+	let now = chrono::Utc::now().timestamp() as u64;
+	for _ in 0..3 {
+		dbs.tick_at(now).await?;
+	}
+	let sql = "
+		INFO FOR TABLE person;
+		CREATE person:two SET email = 'tobie@surrealdb.com';
+	";
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	let tmp = res.remove(0).result?;
+	let val = Value::parse("{}");
+	assert_eq!(tmp.pick(&["rebuilds".into()]), val);
+	res.remove(0).result?;
+	//
+	Ok(())
+}
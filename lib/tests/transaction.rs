@@ -185,3 +185,75 @@ async fn transaction_with_throw_and_return() -> Result<(), Error> {
 	//
 	Ok(())
 }
+
+#[tokio::test]
+async fn transaction_with_dry_run() -> Result<(), Error> {
+	let sql = "
+		CREATE person:tobie SET name = 'Tobie', age = 30;
+		OPTION DRY_RUN;
+		UPDATE person:tobie SET age = 31 RETURN DIFF;
+		BEGIN;
+		CREATE person:jaime;
+		DELETE person:tobie RETURN BEFORE;
+		COMMIT;
+		OPTION DRY_RUN = FALSE;
+		SELECT * FROM person;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 5);
+	//
+	let tmp = res.remove(0).result;
+	assert!(tmp.is_ok());
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			[
+				{
+					op: 'replace',
+					path: '/age',
+					value: 31,
+				}
+			]
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: person:jaime,
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				age: 30,
+				id: person:tobie,
+				name: 'Tobie',
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				age: 30,
+				id: person:tobie,
+				name: 'Tobie',
+			}
+		]",
+	);
+	assert_eq!(tmp, val);
+	//
+	Ok(())
+}
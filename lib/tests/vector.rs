@@ -58,3 +58,59 @@ async fn select_where_mtree_knn() -> Result<(), Error> {
 	assert_eq!(format!("{:#}", tmp), format!("{:#}", val));
 	Ok(())
 }
+
+#[tokio::test]
+async fn select_where_hnsw_knn() -> Result<(), Error> {
+	let sql = r"
+		DEFINE INDEX hn_pts ON pts FIELDS point HNSW DIMENSION 4 M 4 EF_CONSTRUCTION 20;
+		CREATE pts:1 SET point = [1,2,3,4];
+		CREATE pts:2 SET point = [40,50,60,70];
+		CREATE pts:3 SET point = [8,9,10,11];
+		CREATE pts:4 SET point = [2,3,4,5];
+		UPDATE pts:2 SET point = [4,5,6,7];
+		DELETE pts:4;
+		LET $pt = [2,3,4,5];
+		SELECT id, vector::distance::euclidean(point, $pt) AS dist FROM pts WHERE point <2> $pt;
+		SELECT id FROM pts WHERE point <2> $pt EXPLAIN;
+	";
+	let dbs = new_ds().await?;
+	let ses = Session::owner().with_ns("test").with_db("test");
+	let res = &mut dbs.execute(sql, &ses, None).await?;
+	assert_eq!(res.len(), 10);
+	//
+	for _ in 0..8 {
+		let _ = res.remove(0).result?;
+	}
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+			{
+				id: pts:1,
+				dist: 2f
+			},
+			{
+				id: pts:2,
+				dist: 4f
+			}
+		]",
+	);
+	assert_eq!(format!("{:#}", tmp), format!("{:#}", val));
+	let tmp = res.remove(0).result?;
+	let val = Value::parse(
+		"[
+					{
+						detail: {
+							plan: {
+								index: 'hn_pts',
+								operator: '<2>',
+								value: [2,3,4,5]
+							},
+							table: 'pts',
+						},
+						operation: 'Iterate Index'
+					}
+			]",
+	);
+	assert_eq!(format!("{:#}", tmp), format!("{:#}", val));
+	Ok(())
+}
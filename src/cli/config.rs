@@ -20,4 +20,5 @@ pub struct Config {
 	pub crt: Option<PathBuf>,
 	pub key: Option<PathBuf>,
 	pub tick_interval: Duration,
+	pub param_max_size: usize,
 }
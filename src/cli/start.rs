@@ -95,6 +95,12 @@ pub struct StartCommandArguments {
 	#[arg(env = "SURREAL_BIND", short = 'b', long = "bind")]
 	#[arg(default_value = "0.0.0.0:8000")]
 	listen_addresses: Vec<SocketAddr>,
+	#[arg(
+		help = "The maximum size in bytes of a query parameter which is sent as the body of an HTTP request"
+	)]
+	#[arg(env = "SURREAL_PARAM_MAX_SIZE", long = "param-max-size")]
+	#[arg(default_value_t = 16 * 1024 * 1024)]
+	param_max_size: usize,
 
 	//
 	// Database options
@@ -140,6 +146,7 @@ pub async fn init(
 		web,
 		log,
 		tick_interval,
+		param_max_size,
 		no_banner,
 		..
 	}: StartCommandArguments,
@@ -163,6 +170,7 @@ pub async fn init(
 		user,
 		pass,
 		tick_interval,
+		param_max_size,
 		crt: web.as_ref().and_then(|x| x.web_crt.clone()),
 		key: web.as_ref().and_then(|x| x.web_key.clone()),
 	});
@@ -17,6 +17,8 @@ pub(crate) fn path_valid(v: &str) -> Result<String, String> {
 		v if v.starts_with("file:") => Ok(v.to_string()),
 		v if v.starts_with("rocksdb:") => Ok(v.to_string()),
 		v if v.starts_with("speedb:") => Ok(v.to_string()),
+		v if v.starts_with("sled:") => Ok(v.to_string()),
+		v if v.starts_with("redb:") => Ok(v.to_string()),
 		v if v.starts_with("tikv:") => Ok(v.to_string()),
 		v if v.starts_with("fdb:") => Ok(v.to_string()),
 		_ => Err(String::from("Provide a valid database path parameter")),
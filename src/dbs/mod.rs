@@ -4,6 +4,7 @@ use clap::Args;
 use std::sync::OnceLock;
 use std::time::Duration;
 use surrealdb::dbs::capabilities::{Capabilities, FuncTarget, NetTarget, Targets};
+use surrealdb::dbs::HttpQuota;
 use surrealdb::kvs::Datastore;
 use surrealdb::opt::auth::Root;
 
@@ -23,10 +24,24 @@ pub struct StartCommandDbsOptions {
 	#[arg(env = "SURREAL_TRANSACTION_TIMEOUT", long)]
 	#[arg(value_parser = super::cli::validator::duration)]
 	transaction_timeout: Option<Duration>,
+	#[arg(help = "The maximum number of values which the result of a statement can hold")]
+	#[arg(env = "SURREAL_MAX_RESULT_SIZE", long)]
+	max_result_size: Option<usize>,
 	#[arg(help = "Whether to enable authentication", help_heading = "Authentication")]
 	#[arg(env = "SURREAL_AUTH", long = "auth")]
 	#[arg(default_value_t = false)]
 	auth_enabled: bool,
+	#[arg(help = "The maximum outbound HTTP calls of each database in a quota window")]
+	#[arg(env = "SURREAL_HTTP_QUOTA_CALLS", long)]
+	http_quota_calls: Option<u64>,
+	#[arg(help = "The maximum outbound HTTP bytes of each database in a quota window")]
+	#[arg(env = "SURREAL_HTTP_QUOTA_BYTES", long)]
+	http_quota_bytes: Option<u64>,
+	#[arg(help = "The duration over which the outbound HTTP usage of each database is counted")]
+	#[arg(env = "SURREAL_HTTP_QUOTA_WINDOW", long)]
+	#[arg(value_parser = super::cli::validator::duration)]
+	#[arg(default_value = "1m")]
+	http_quota_window: Duration,
 	#[command(flatten)]
 	#[command(next_help_heading = "Capabilities")]
 	caps: DbsCapabilities,
@@ -203,7 +218,11 @@ pub async fn init(
 		strict_mode,
 		query_timeout,
 		transaction_timeout,
+		max_result_size,
 		auth_enabled,
+		http_quota_calls,
+		http_quota_bytes,
+		http_quota_window,
 		caps,
 	}: StartCommandDbsOptions,
 ) -> Result<(), Error> {
@@ -219,6 +238,10 @@ pub async fn init(
 	if let Some(v) = transaction_timeout {
 		debug!("Maximum transaction processing timeout is {v:?}");
 	}
+	// Log specified result size limit
+	if let Some(v) = max_result_size {
+		debug!("Maximum result size is {v} values");
+	}
 	// Log whether authentication is enabled
 	if auth_enabled {
 		info!("✅🔒 Authentication is enabled 🔒✅");
@@ -230,14 +253,27 @@ pub async fn init(
 	debug!("Server capabilities: {caps}");
 
 	// Parse and setup the desired kv datastore
-	let dbs = Datastore::new(&opt.path)
+	let mut dbs = Datastore::new(&opt.path)
 		.await?
 		.with_notifications()
 		.with_strict_mode(strict_mode)
 		.with_query_timeout(query_timeout)
 		.with_transaction_timeout(transaction_timeout)
+		.with_max_result_size(max_result_size)
 		.with_auth_enabled(auth_enabled)
 		.with_capabilities(caps);
+	// Limit the outbound HTTP calls of each database
+	if http_quota_calls.is_some() || http_quota_bytes.is_some() {
+		let mut quota = HttpQuota::new(http_quota_window);
+		if let Some(v) = http_quota_calls {
+			quota = quota.with_calls(v);
+		}
+		if let Some(v) = http_quota_bytes {
+			quota = quota.with_bytes(v);
+		}
+		debug!("Outbound HTTP quota is {quota:?}");
+		dbs = dbs.with_http_quota(quota);
+	}
 
 	dbs.bootstrap().await?;
 
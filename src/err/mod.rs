@@ -31,6 +31,9 @@ pub enum Error {
 	#[error("The specified media type is unsupported")]
 	InvalidType,
 
+	#[error("The request body is larger than the limit of {0} bytes")]
+	PayloadTooLarge(usize),
+
 	#[error("There was a problem connecting with the storage engine")]
 	InvalidStorage,
 
@@ -135,6 +138,15 @@ impl IntoResponse for Error {
 					information: None,
 				}),
 			),
+			Error::PayloadTooLarge(_) => (
+				StatusCode::PAYLOAD_TOO_LARGE,
+				Json(Message {
+					code: StatusCode::PAYLOAD_TOO_LARGE.as_u16(),
+					details: Some("Payload too large".to_string()),
+					description: Some("The request body is larger than the server allows. Refer to the documentation for the limits which can be configured.".to_string()),
+					information: Some(self.to_string()),
+				}),
+			),
 			Error::InvalidStorage => (
 				StatusCode::INTERNAL_SERVER_ERROR,
 				Json(Message {
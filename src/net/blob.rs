@@ -0,0 +1,93 @@
+use crate::dbs::DB;
+use crate::err::Error;
+use crate::net::output;
+use axum::body::BodyStream;
+use axum::extract::DefaultBodyLimit;
+use axum::extract::Path;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use axum::TypedHeader;
+use axum::{response::Response, Extension};
+use bytes::Bytes;
+use futures::TryStreamExt;
+use http::StatusCode;
+use http_body::Body as HttpBody;
+use hyper::body::Body;
+use std::io;
+use surrealdb::dbs::Session;
+use tower_http::limit::RequestBodyLimitLayer;
+
+use super::headers::Accept;
+
+const MAX: usize = 1024 * 1024 * 1024 * 4; // 4 GiB
+
+pub(super) fn router<S, B>() -> Router<S, B>
+where
+	B: HttpBody + Send + 'static,
+	B::Data: Into<Bytes> + Send,
+	B::Error: std::error::Error + Send + Sync + 'static,
+	S: Clone + Send + Sync + 'static,
+{
+	Router::new()
+		.route("/blob/:thing", get(get_handler).put(put_handler))
+		.route_layer(DefaultBodyLimit::disable())
+		.layer(RequestBodyLimitLayer::new(MAX))
+}
+
+async fn get_handler(
+	Extension(session): Extension<Session>,
+	Path(thing): Path<String>,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+	// Get the datastore reference
+	let db = DB.get().unwrap();
+	// Parse the Record ID of the blob
+	let rid = surrealdb::sql::thing(&thing).map_err(|_| Error::Request)?;
+	// Create a chunked response
+	let (mut chn, bdy) = Body::channel();
+	// Create a new bounded channel
+	let (snd, rcv) = surrealdb::channel::new(1);
+
+	let blob_job = db.get_blob(&session, &rid, snd).await.map_err(Error::from)?;
+	// Spawn a new blob read job
+	tokio::spawn(blob_job);
+	// Process all read chunks
+	tokio::spawn(async move {
+		while let Ok(v) = rcv.recv().await {
+			let _ = chn.send_data(Bytes::from(v)).await;
+		}
+	});
+	// Return the chunked body
+	Ok(Response::builder().status(StatusCode::OK).body(bdy).unwrap())
+}
+
+async fn put_handler(
+	Extension(session): Extension<Session>,
+	maybe_output: Option<TypedHeader<Accept>>,
+	Path(thing): Path<String>,
+	body: BodyStream,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+	// Get the datastore reference
+	let db = DB.get().unwrap();
+	// Parse the Record ID of the blob
+	let rid = surrealdb::sql::thing(&thing).map_err(|_| Error::Request)?;
+	// Read the request body as it arrives
+	let mut reader = body.map_err(|e| io::Error::new(io::ErrorKind::Other, e)).into_async_read();
+	// Store the blob in the database
+	match db.put_blob(&session, &rid, &mut reader).await {
+		Ok(size) => match maybe_output.as_deref() {
+			// Simple serialization
+			Some(Accept::ApplicationJson) => Ok(output::json(&size)),
+			Some(Accept::ApplicationCbor) => Ok(output::cbor(&size)),
+			Some(Accept::ApplicationPack) => Ok(output::pack(&size)),
+			// Internal serialization
+			Some(Accept::Surrealdb) => Ok(output::full(&size)),
+			// Return nothing
+			Some(Accept::ApplicationOctetStream) => Ok(output::none()),
+			// An incorrect content-type was requested
+			_ => Err(Error::InvalidType),
+		},
+		// There was an error when storing the blob
+		Err(err) => Err(Error::from(err)),
+	}
+}
@@ -1,4 +1,5 @@
 mod auth;
+mod blob;
 pub mod client_ip;
 mod export;
 mod headers;
@@ -121,6 +122,7 @@ pub async fn init(ct: CancellationToken) -> Result<(), Error> {
 		.merge(health::router())
 		.merge(export::router())
 		.merge(import::router())
+		.merge(blob::router())
 		.merge(rpc::router())
 		.merge(version::router())
 		.merge(sync::router())
@@ -1,20 +1,26 @@
+use crate::cli::CF;
 use crate::dbs::DB;
 use crate::err::Error;
 use crate::net::input::bytes_to_utf8;
 use crate::net::output;
 use crate::net::params::Params;
+use axum::body::BodyStream;
 use axum::extract::ws::Message;
 use axum::extract::ws::WebSocket;
 use axum::extract::DefaultBodyLimit;
+use axum::extract::Path;
 use axum::extract::Query;
 use axum::extract::WebSocketUpgrade;
 use axum::response::IntoResponse;
 use axum::routing::options;
+use axum::routing::post;
 use axum::Extension;
 use axum::Router;
 use axum::TypedHeader;
 use bytes::Bytes;
 use futures::{SinkExt, StreamExt};
+use http::HeaderMap;
+use http::HeaderValue;
 use http_body::Body as HttpBody;
 use surrealdb::dbs::Session;
 use tower_http::limit::RequestBodyLimitLayer;
@@ -23,17 +29,28 @@ use super::headers::Accept;
 
 const MAX: usize = 1024 * 1024; // 1 MiB
 
+/// The header which holds the query, when the body holds the value of a parameter
+const QUERY: &str = "surreal-query";
+
 pub(super) fn router<S, B>() -> Router<S, B>
 where
 	B: HttpBody + Send + 'static,
-	B::Data: Send,
+	B::Data: Into<Bytes> + Send,
 	B::Error: std::error::Error + Send + Sync + 'static,
 	S: Clone + Send + Sync + 'static,
 {
+	// The maximum size of a parameter which is sent as the body of a request
+	let param_max = CF.get().unwrap().param_max_size;
 	Router::new()
 		.route("/sql", options(|| async {}).get(ws_handler).post(post_handler))
 		.route_layer(DefaultBodyLimit::disable())
 		.layer(RequestBodyLimitLayer::new(MAX))
+		.merge(
+			Router::new()
+				.route("/sql/:param", post(stream_handler))
+				.route_layer(DefaultBodyLimit::disable())
+				.layer(RequestBodyLimitLayer::new(param_max)),
+		)
 }
 
 async fn post_handler(
@@ -63,6 +80,54 @@ async fn post_handler(
 	}
 }
 
+async fn stream_handler(
+	Extension(session): Extension<Session>,
+	output: Option<TypedHeader<Accept>>,
+	params: Query<Params>,
+	Path(param): Path<String>,
+	headers: HeaderMap,
+	mut body: BodyStream,
+) -> Result<impl IntoResponse, impl IntoResponse> {
+	// Get a database reference
+	let db = DB.get().unwrap();
+	// Extract the query, which is sent in a header as the body holds the parameter
+	let sql = match headers.get(QUERY).map(HeaderValue::to_str) {
+		Some(Ok(sql)) => urlencoding::decode(&sql.replace('+', " "))?.into_owned(),
+		_ => return Err(Error::Request),
+	};
+	// Read the parameter as it arrives, stopping as soon as it is larger than the limit
+	let max = CF.get().unwrap().param_max_size;
+	let mut value = Vec::new();
+	while let Some(chunk) = body.next().await {
+		let chunk = chunk.map_err(|_| Error::Request)?;
+		if value.len() + chunk.len() > max {
+			return Err(Error::PayloadTooLarge(max));
+		}
+		value.extend_from_slice(&chunk);
+	}
+	let value = Bytes::from(value);
+	// Parse the parameter as a SurrealQL value
+	let value = surrealdb::sql::value(bytes_to_utf8(&value)?).map_err(|_| Error::Request)?;
+	// Bind the parameter alongside the others
+	let mut vars = params.0.parse();
+	vars.insert(param, value);
+	// Execute the received sql query
+	match db.execute(&sql, &session, vars.into()).await {
+		Ok(res) => match output.as_deref() {
+			// Simple serialization
+			Some(Accept::ApplicationJson) => Ok(output::json(&output::simplify(res))),
+			Some(Accept::ApplicationCbor) => Ok(output::cbor(&output::simplify(res))),
+			Some(Accept::ApplicationPack) => Ok(output::pack(&output::simplify(res))),
+			// Internal serialization
+			Some(Accept::Surrealdb) => Ok(output::full(&res)),
+			// An incorrect content-type was requested
+			_ => Err(Error::InvalidType),
+		},
+		// There was an error when executing the query
+		Err(err) => Err(Error::from(err)),
+	}
+}
+
 async fn ws_handler(
 	ws: WebSocketUpgrade,
 	Extension(sess): Extension<Session>,
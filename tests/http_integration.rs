@@ -9,7 +9,7 @@ mod http_integration {
 	use serde_json::json;
 	use test_log::test;
 
-	use super::common::{self, PASS, USER};
+	use super::common::{self, StartServerArguments, PASS, USER};
 
 	#[test(tokio::test)]
 	async fn basic_auth() -> Result<(), Box<dyn std::error::Error>> {
@@ -1301,4 +1301,44 @@ mod http_integration {
 
 		Ok(())
 	}
+
+	#[test(tokio::test)]
+	async fn sql_param_endpoint() -> Result<(), Box<dyn std::error::Error>> {
+		let (addr, _server) = common::start_server(StartServerArguments {
+			args: "--allow-all --param-max-size 16".to_owned(),
+			..Default::default()
+		})
+		.await
+		.unwrap();
+		let url = &format!("http://{addr}/sql/name");
+
+		// Prepare HTTP client
+		let mut headers = reqwest::header::HeaderMap::new();
+		headers.insert("NS", "N".parse()?);
+		headers.insert("DB", "D".parse()?);
+		headers.insert(header::ACCEPT, "application/json".parse()?);
+		headers.insert("surreal-query", "RETURN $name".parse()?);
+		let client = reqwest::Client::builder()
+			.connect_timeout(Duration::from_millis(10))
+			.default_headers(headers)
+			.build()?;
+
+		// A parameter within the limit is bound to the query
+		{
+			let res = client.post(url).basic_auth(USER, Some(PASS)).body("'tobie'").send().await?;
+			assert_eq!(res.status(), 200);
+
+			let body: serde_json::Value = serde_json::from_str(&res.text().await?).unwrap();
+			assert_eq!(body[0]["result"], "tobie", "body: {}", body);
+		}
+
+		// A parameter which is larger than the limit is rejected
+		{
+			let value = format!("'{}'", "a".repeat(32));
+			let res = client.post(url).basic_auth(USER, Some(PASS)).body(value).send().await?;
+			assert_eq!(res.status(), 413);
+		}
+
+		Ok(())
+	}
 }